@@ -0,0 +1,160 @@
+// Per-guild "manager" roles for admin commands.
+//
+// Admin commands used to require the ADMINISTRATOR permission, which is
+// all-or-nothing. Servers often have a "bot manager" role that shouldn't be a
+// full admin, so each guild can list roles whose members may run admin
+// commands too (`/config managers add @role`).
+//
+// The role list is read on every admin command, so the service caches it per
+// guild and only goes to the store on first use or on changes.
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::error::Error;
+
+/// Persistence for the manager role list.
+#[async_trait]
+pub trait ManagerRoleStore: Send + Sync {
+    async fn get_manager_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>>;
+
+    /// Add or remove a manager role.
+    async fn set_manager_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+        is_manager: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: ManagerRoleStore + ?Sized> ManagerRoleStore for Box<S> {
+    async fn get_manager_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        (**self).get_manager_roles(guild_id).await
+    }
+
+    async fn set_manager_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+        is_manager: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self)
+            .set_manager_role(guild_id, role_id, is_manager)
+            .await
+    }
+}
+
+pub struct ManagerRoleService<S: ManagerRoleStore> {
+    store: S,
+    /// guild_id -> manager role ids
+    cache: DashMap<u64, HashSet<u64>>,
+}
+
+impl<S: ManagerRoleStore> ManagerRoleService<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            cache: DashMap::new(),
+        }
+    }
+
+    /// The guild's manager roles.
+    pub async fn manager_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<HashSet<u64>, Box<dyn Error + Send + Sync>> {
+        if let Some(roles) = self.cache.get(&guild_id) {
+            return Ok(roles.clone());
+        }
+
+        let roles: HashSet<u64> = self
+            .store
+            .get_manager_roles(guild_id)
+            .await?
+            .into_iter()
+            .collect();
+        self.cache.insert(guild_id, roles.clone());
+        Ok(roles)
+    }
+
+    /// Whether a member with these roles counts as a manager in the guild.
+    /// Store errors deny access rather than fail open.
+    pub async fn is_manager(&self, guild_id: u64, role_ids: &[u64]) -> bool {
+        match self.manager_roles(guild_id).await {
+            Ok(roles) => role_ids.iter().any(|role_id| roles.contains(role_id)),
+            Err(e) => {
+                tracing::warn!(guild_id, error = %e, "Failed to load manager roles");
+                false
+            }
+        }
+    }
+
+    /// Add or remove a manager role. Returns false if nothing changed.
+    pub async fn set_manager_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+        is_manager: bool,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let roles = self.manager_roles(guild_id).await?;
+        if roles.contains(&role_id) == is_manager {
+            return Ok(false);
+        }
+
+        self.store
+            .set_manager_role(guild_id, role_id, is_manager)
+            .await?;
+        let mut cached = self.cache.entry(guild_id).or_default();
+        if is_manager {
+            cached.insert(role_id);
+        } else {
+            cached.remove(&role_id);
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::access::InMemoryManagerRoleStore;
+
+    #[tokio::test]
+    async fn test_only_manager_roles_pass() {
+        let service = ManagerRoleService::new(InMemoryManagerRoleStore::new());
+        let (guild_id, bot_manager, member_role) = (1, 100, 200);
+
+        assert!(service
+            .set_manager_role(guild_id, bot_manager, true)
+            .await
+            .unwrap());
+        // Adding twice is a no-op
+        assert!(!service
+            .set_manager_role(guild_id, bot_manager, true)
+            .await
+            .unwrap());
+
+        assert!(
+            service
+                .is_manager(guild_id, &[member_role, bot_manager])
+                .await
+        );
+        assert!(!service.is_manager(guild_id, &[member_role]).await);
+        assert!(!service.is_manager(guild_id, &[]).await);
+        // Roles are per guild
+        assert!(!service.is_manager(2, &[bot_manager]).await);
+
+        service
+            .set_manager_role(guild_id, bot_manager, false)
+            .await
+            .unwrap();
+        assert!(!service.is_manager(guild_id, &[bot_manager]).await);
+    }
+}
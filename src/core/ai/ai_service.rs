@@ -1,9 +1,16 @@
+use super::ai_settings::AiSettings;
+use super::circuit_breaker::CircuitBreaker;
 use super::models::{
     AiConfig, AiMessage, AiProviderResponse, AiResponse, AiResponseWithMeta, AiTool, Citation,
     FunctionCall,
 };
+use super::request_limit::RequestLimit;
+use super::response_cache::ResponseCache;
+use crate::core::util::links::MAX_CONTEXT_URLS;
 use async_trait::async_trait;
 use std::error::Error;
+use std::sync::RwLock;
+use std::time::Duration;
 
 // =============================================================================
 // AI PROVIDER TRAIT
@@ -20,6 +27,20 @@ pub trait AiProvider: Send + Sync {
         messages: &[AiMessage],
         config: &AiConfig,
     ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>>;
+
+    /// Whether `model` accepts image parts (`AiMessage::images`).
+    /// Providers that return false only ever see the text.
+    fn supports_images(&self, _model: &str) -> bool {
+        false
+    }
+
+    /// Whether `error` from `chat_complete` means the provider is down or
+    /// unusable (timeouts, connection errors, 5xx, 429, rejected credentials)
+    /// rather than that this request was refused. Only these count towards
+    /// the circuit breaker.
+    fn is_outage(&self, _error: &(dyn Error + Send + Sync + 'static)) -> bool {
+        false
+    }
 }
 
 // Blanket implementation for Box<dyn AiProvider>
@@ -35,6 +56,14 @@ impl AiProvider for Box<dyn AiProvider> {
         // Delegate to the inner provider
         (**self).chat_complete(messages, config).await
     }
+
+    fn supports_images(&self, model: &str) -> bool {
+        (**self).supports_images(model)
+    }
+
+    fn is_outage(&self, error: &(dyn Error + Send + Sync + 'static)) -> bool {
+        (**self).is_outage(error)
+    }
 }
 
 // =============================================================================
@@ -74,24 +103,39 @@ pub trait FunctionCallHandler: Send + Sync {
 // AI SERVICE
 // =============================================================================
 
+/// `AiService` with the provider picked at runtime (OpenRouter or Gemini), as
+/// the bot uses it.
+pub type DynAiService = AiService<Box<dyn AiProvider>>;
+
 pub struct AiService<P: AiProvider> {
     provider: P,
-    system_prompt: String,
+    /// System prompt and reasoning options, swapped in place by `/reload`.
+    /// They override the matching fields of `config` on every request.
+    settings: RwLock<AiSettings>,
     config: AiConfig,
     /// Optional function call handler for executing tool calls
     function_handler: Option<Box<dyn FunctionCallHandler>>,
     /// Maximum number of tool call iterations to prevent infinite loops
     max_tool_iterations: usize,
+    /// Replies to identical tool-less requests, when caching is enabled
+    response_cache: Option<ResponseCache>,
+    /// Fails requests fast while the provider keeps erroring
+    circuit_breaker: Option<CircuitBreaker>,
+    /// Bot-wide cap on provider calls in flight, `None` when unlimited
+    request_limit: Option<RequestLimit>,
 }
 
 impl<P: AiProvider> AiService<P> {
     pub fn new(provider: P, system_prompt: String, config: AiConfig) -> Self {
         Self {
             provider,
-            system_prompt,
+            settings: RwLock::new(Self::initial_settings(system_prompt, &config)),
             config,
             function_handler: None,
             max_tool_iterations: 3,
+            response_cache: None,
+            circuit_breaker: None,
+            request_limit: None,
         }
     }
 
@@ -107,19 +151,88 @@ impl<P: AiProvider> AiService<P> {
     ) -> Self {
         Self {
             provider,
-            system_prompt,
+            settings: RwLock::new(Self::initial_settings(system_prompt, &config)),
             config,
             function_handler: Some(handler),
             max_tool_iterations: 3,
+            response_cache: None,
+            circuit_breaker: None,
+            request_limit: None,
+        }
+    }
+
+    /// Reuse the provider's reply to an identical request for `ttl`. `None`
+    /// leaves caching off. Requests with tools or images are never cached.
+    pub fn with_response_cache(mut self, ttl: Option<Duration>) -> Self {
+        self.response_cache = ttl.map(ResponseCache::new);
+        self
+    }
+
+    /// Stop calling the provider for `cooldown` after `failures` errors in a
+    /// row; requests fail with `CircuitOpen` meanwhile. `0` turns it off.
+    pub fn with_circuit_breaker(mut self, failures: u32, cooldown: Duration) -> Self {
+        self.circuit_breaker = (failures > 0).then(|| CircuitBreaker::new(failures, cooldown));
+        self
+    }
+
+    /// Allow at most `limit` requests at once across the bot; others fail
+    /// with `AiBusy`. A request keeps its slot through any tool calls. `0`
+    /// means no limit.
+    pub fn with_max_concurrent_requests(mut self, limit: usize) -> Self {
+        self.request_limit = (limit > 0).then(|| RequestLimit::new(limit));
+        self
+    }
+
+    fn initial_settings(system_prompt: String, config: &AiConfig) -> AiSettings {
+        AiSettings {
+            system_prompt,
+            reasoning_enabled: config.reasoning_enabled,
+            reasoning_effort: config.reasoning_effort.clone(),
         }
     }
 
+    /// The reloadable settings currently in use.
+    pub fn settings(&self) -> AiSettings {
+        self.settings
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Replaces the reloadable settings. Requests already in flight finish
+    /// with the old ones. Returns what changed (see
+    /// `AiSettings::describe_changes`).
+    pub fn apply_settings(&self, new: AiSettings) -> Vec<String> {
+        let mut settings = self
+            .settings
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let changes = settings.describe_changes(&new);
+        *settings = new;
+        changes
+    }
+
+    /// The system prompt and request config with the current settings applied.
+    fn current(&self) -> (String, AiConfig) {
+        let settings = self.settings();
+        let mut config = self.config.clone();
+        config.reasoning_enabled = settings.reasoning_enabled;
+        config.reasoning_effort = settings.reasoning_effort;
+        (settings.system_prompt, config)
+    }
+
     /// Sets the function call handler after construction.
     #[allow(dead_code)]
     pub fn set_function_handler(&mut self, handler: Box<dyn FunctionCallHandler>) {
         self.function_handler = Some(handler);
     }
 
+    /// Whether the configured model can look at image attachments.
+    /// Callers can skip downloading images when this is false.
+    pub fn supports_images(&self) -> bool {
+        self.provider.supports_images(&self.config.model)
+    }
+
     /// Returns the current tools configuration.
     #[allow(dead_code)]
     pub fn tools(&self) -> Option<&Vec<AiTool>> {
@@ -156,17 +269,104 @@ impl<P: AiProvider> AiService<P> {
     pub async fn chat_with_metadata(
         &self,
         context_messages: &[AiMessage],
+    ) -> Result<AiResponseWithMeta, Box<dyn Error + Send + Sync>> {
+        let (system_prompt, config) = self.current();
+        self.chat_with_config(&system_prompt, context_messages, &config)
+            .await
+    }
+
+    /// Like `chat_with_metadata`, but lets the model read `urls` via the URL
+    /// Context tool.
+    ///
+    /// Gemini rejects requests that mix built-in tools with function
+    /// declarations, so function declarations are left out of this one request.
+    /// Google Docs links are skipped (URL Context can't read them) and handled by
+    /// the normal function-calling path instead. With no usable URLs this is the
+    /// same as `chat_with_metadata`.
+    pub async fn chat_with_urls(
+        &self,
+        context_messages: &[AiMessage],
+        urls: &[String],
+    ) -> Result<AiResponseWithMeta, Box<dyn Error + Send + Sync>> {
+        let (system_prompt, config) = self.current();
+        let config = Self::url_context_config(&config, urls).unwrap_or(config);
+        self.chat_with_config(&system_prompt, context_messages, &config)
+            .await
+    }
+
+    /// Runs a one-off task (summarize, translate, ...) with its own system
+    /// prompt instead of the bot's chat persona.
+    ///
+    /// Search and function calling are turned off so the model sticks to the
+    /// task. If `urls` is non-empty, URL Context is the only tool enabled.
+    pub async fn complete_task(
+        &self,
+        system_prompt: &str,
+        messages: &[AiMessage],
+        urls: &[String],
+    ) -> Result<AiResponseWithMeta, Box<dyn Error + Send + Sync>> {
+        let (_, mut config) = self.current();
+        config.tools = if urls.is_empty() {
+            None
+        } else {
+            Some(vec![AiTool::UrlContext {
+                urls: urls.iter().take(MAX_CONTEXT_URLS).cloned().collect(),
+            }])
+        };
+
+        self.chat_with_config(system_prompt, messages, &config)
+            .await
+    }
+
+    /// Build a request config with URL Context enabled, or `None` if there is
+    /// nothing for the tool to read.
+    fn url_context_config(config: &AiConfig, urls: &[String]) -> Option<AiConfig> {
+        let urls: Vec<String> = urls
+            .iter()
+            .filter(|url| !url.contains("docs.google.com"))
+            .take(MAX_CONTEXT_URLS)
+            .cloned()
+            .collect();
+        if urls.is_empty() {
+            return None;
+        }
+
+        let mut tools: Vec<AiTool> = config
+            .tools
+            .iter()
+            .flatten()
+            .filter(|tool| !matches!(tool, AiTool::FunctionDeclaration(_)))
+            .cloned()
+            .collect();
+        tools.push(AiTool::UrlContext { urls });
+
+        tracing::debug!("Enabling URL context for this request (function declarations omitted)");
+
+        let mut config = config.clone();
+        config.tools = Some(tools);
+        Some(config)
+    }
+
+    async fn chat_with_config(
+        &self,
+        system_prompt: &str,
+        context_messages: &[AiMessage],
+        config: &AiConfig,
     ) -> Result<AiResponseWithMeta, Box<dyn Error + Send + Sync>> {
         // Build messages for API: System Prompt + Context
         let mut messages = Vec::new();
-        messages.push(AiMessage {
-            role: "system".to_string(),
-            content: self.system_prompt.clone(),
-        });
+        messages.push(AiMessage::system(system_prompt.to_string()));
         messages.extend(context_messages.iter().cloned());
 
+        // One slot for the whole request, tool rounds included, so a request
+        // that got started can't be turned away halfway through
+        let _slot = match &self.request_limit {
+            Some(limit) => Some(limit.try_acquire()?),
+            None => None,
+        };
+
         // Call provider - now returns AiProviderResponse with thinking and content
-        let mut provider_response = self.provider.chat_complete(&messages, &self.config).await?;
+        let mut provider_response = self.complete_cached(&messages, config).await?;
 
         // Handle function calls with multi-step support (up to max_tool_iterations rounds)
         let mut iteration = 0;
@@ -208,23 +408,20 @@ impl<P: AiProvider> AiService<P> {
                     .iter()
                     .map(|fc| format!("Called {}({})", fc.name, fc.args))
                     .collect();
-                messages.push(AiMessage {
-                    role: "assistant".to_string(),
-                    content: format!(
-                        "I need to call some functions: {}",
-                        function_call_summary.join(", ")
-                    ),
-                });
-                messages.push(AiMessage {
-                    role: "user".to_string(),
-                    content: Self::format_function_results(&function_results),
-                });
+                messages.push(AiMessage::assistant(format!(
+                    "I need to call some functions: {}",
+                    function_call_summary.join(", ")
+                )));
+                messages.push(AiMessage::user(Self::format_function_results(
+                    &function_results,
+                )));
 
                 provider_response = self
                     .continue_with_function_results(
                         &messages,
                         &function_calls_owned,
                         &function_results,
+                        config,
                         iteration < self.max_tool_iterations - 1, // allow more tools unless last iteration
                     )
                     .await?;
@@ -254,9 +451,57 @@ impl<P: AiProvider> AiService<P> {
             reasoning,
             citations,
             confidence,
+            url_context: provider_response.url_context_metadata,
         })
     }
 
+    /// Ask the provider, answering from the response cache when an identical
+    /// request was made recently.
+    async fn complete_cached(
+        &self,
+        messages: &[AiMessage],
+        config: &AiConfig,
+    ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+        let cached = self
+            .response_cache
+            .as_ref()
+            .and_then(|cache| Some((cache, ResponseCache::key(messages, config)?)));
+        if let Some((cache, key)) = cached {
+            if let Some(response) = cache.get(key) {
+                tracing::debug!("Answering from the AI response cache");
+                return Ok(response);
+            }
+        }
+
+        let response = self.call_provider(messages, config).await?;
+        if let Some((cache, key)) = cached {
+            cache.insert(key, &response);
+        }
+        Ok(response)
+    }
+
+    /// Call the provider through the circuit breaker, if there is one. The
+    /// caller holds the request limit slot.
+    async fn call_provider(
+        &self,
+        messages: &[AiMessage],
+        config: &AiConfig,
+    ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+        let Some(breaker) = &self.circuit_breaker else {
+            return self.provider.chat_complete(messages, config).await;
+        };
+
+        breaker.try_acquire()?;
+        let result = self.provider.chat_complete(messages, config).await;
+        match &result {
+            Err(e) if self.provider.is_outage(e.as_ref()) => breaker.record_failure(),
+            // A refused request (bad input, safety block, empty reply) still
+            // shows the provider is up
+            _ => breaker.record_success(),
+        }
+        result
+    }
+
     /// Extracts citations from the provider response's grounding metadata.
     fn extract_citations(provider_response: &AiProviderResponse) -> Vec<Citation> {
         let Some(ref grounding) = provider_response.grounding_metadata else {
@@ -331,6 +576,7 @@ impl<P: AiProvider> AiService<P> {
         original_messages: &[AiMessage],
         function_calls: &[FunctionCall],
         results: &[(String, serde_json::Value, bool)],
+        config: &AiConfig,
         allow_more_tools: bool,
     ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
         // Build messages including the function call and results
@@ -346,31 +592,25 @@ impl<P: AiProvider> AiService<P> {
             .map(|fc| format!("Called {}({})", fc.name, fc.args))
             .collect();
 
-        messages.push(AiMessage {
-            role: "assistant".to_string(),
-            content: format!(
-                "I need to call some functions: {}",
-                function_call_summary.join(", ")
-            ),
-        });
+        messages.push(AiMessage::assistant(format!(
+            "I need to call some functions: {}",
+            function_call_summary.join(", ")
+        )));
 
         // Add function results as user message with structured format
-        messages.push(AiMessage {
-            role: "user".to_string(),
-            content: Self::format_function_results(results),
-        });
+        messages.push(AiMessage::user(Self::format_function_results(results)));
 
         // Make another API call with the function results
         // Only disable tools on the last iteration to allow multi-step tool use
         let config = if allow_more_tools {
-            self.config.clone()
+            config.clone()
         } else {
-            let mut config_without_tools = self.config.clone();
+            let mut config_without_tools = config.clone();
             config_without_tools.tools = None;
             config_without_tools
         };
 
-        self.provider.chat_complete(&messages, &config).await
+        self.call_provider(&messages, &config).await
     }
 
     fn parse_response(&self, content: &str) -> (String, Option<String>) {
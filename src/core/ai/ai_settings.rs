@@ -0,0 +1,159 @@
+// The AI settings that can change while the bot is running (`/reload`).
+//
+// Most of the AI setup (provider, model, API keys, tools) is fixed at startup.
+// The system prompt and reasoning options only shape each request, so
+// `AiService` keeps them behind a lock and swaps them in place. Loading them
+// (`Config::ai_settings`) is the infra layer's job; this
+// module only describes what changed.
+
+/// Prompt used when none is configured.
+pub const DEFAULT_SYSTEM_PROMPT: &str = "You are a helpful AI assistant.";
+
+/// The reloadable part of the AI configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AiSettings {
+    pub system_prompt: String,
+    /// `None` leaves reasoning up to the provider.
+    pub reasoning_enabled: Option<bool>,
+    pub reasoning_effort: Option<String>,
+}
+
+impl AiSettings {
+    /// Human-readable list of differences between `self` and `new`, e.g.
+    /// "Reasoning: off → on". Empty when nothing changed.
+    pub fn describe_changes(&self, new: &AiSettings) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        if self.system_prompt != new.system_prompt {
+            changes.push(format!(
+                "System prompt: {} → {} characters",
+                self.system_prompt.chars().count(),
+                new.system_prompt.chars().count()
+            ));
+        }
+
+        if self.reasoning_enabled != new.reasoning_enabled {
+            let label = |enabled: Option<bool>| match enabled {
+                Some(true) => "on",
+                Some(false) => "off",
+                None => "provider default",
+            };
+            changes.push(format!(
+                "Reasoning: {} → {}",
+                label(self.reasoning_enabled),
+                label(new.reasoning_enabled)
+            ));
+        }
+
+        if self.reasoning_effort != new.reasoning_effort {
+            changes.push(format!(
+                "Reasoning effort: {} → {}",
+                self.reasoning_effort.as_deref().unwrap_or("default"),
+                new.reasoning_effort.as_deref().unwrap_or("default")
+            ));
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::models::{AiConfig, AiMessage, AiProviderResponse, Role};
+    use crate::core::ai::{AiProvider, AiService};
+    use async_trait::async_trait;
+    use std::error::Error;
+    use std::sync::{Arc, Mutex};
+
+    type Requests = Arc<Mutex<Vec<(String, Option<bool>)>>>;
+
+    /// Records the system prompt and reasoning flag of every request.
+    struct RecordingProvider {
+        requests: Requests,
+    }
+
+    #[async_trait]
+    impl AiProvider for RecordingProvider {
+        async fn chat_complete(
+            &self,
+            messages: &[AiMessage],
+            config: &AiConfig,
+        ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+            let system = messages
+                .iter()
+                .find(|message| message.role == Role::System)
+                .map(|message| message.content.clone())
+                .unwrap_or_default();
+            self.requests
+                .lock()
+                .unwrap()
+                .push((system, config.reasoning_enabled));
+            Ok(AiProviderResponse {
+                content: "ok".to_string(),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn settings(prompt: &str, reasoning_enabled: Option<bool>) -> AiSettings {
+        AiSettings {
+            system_prompt: prompt.to_string(),
+            reasoning_enabled,
+            reasoning_effort: None,
+        }
+    }
+
+    #[test]
+    fn test_describe_changes() {
+        let old = settings("Be nice.", None);
+        assert!(old.describe_changes(&old.clone()).is_empty());
+
+        let new = AiSettings {
+            reasoning_effort: Some("high".to_string()),
+            ..settings("Be very nice.", Some(true))
+        };
+        assert_eq!(
+            old.describe_changes(&new),
+            vec![
+                "System prompt: 8 → 13 characters".to_string(),
+                "Reasoning: provider default → on".to_string(),
+                "Reasoning effort: default → high".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reloaded_settings_apply_to_next_request() {
+        let config = AiConfig {
+            model: "test".to_string(),
+            temperature: 0.7,
+            max_tokens: None,
+            top_p: None,
+            repetition_penalty: None,
+            reasoning_enabled: Some(false),
+            reasoning_effort: None,
+            tools: None,
+            tool_config: None,
+        };
+        let requests = Requests::default();
+        let provider = RecordingProvider {
+            requests: Arc::clone(&requests),
+        };
+        let service = AiService::new(provider, "Old".to_string(), config);
+        let message = [AiMessage::user("hi".to_string())];
+
+        service.chat(&message).await.unwrap();
+        let changes = service.apply_settings(settings("New", Some(true)));
+        assert_eq!(changes.len(), 2);
+        service.chat(&message).await.unwrap();
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                ("Old".to_string(), Some(false)),
+                ("New".to_string(), Some(true)),
+            ]
+        );
+    }
+}
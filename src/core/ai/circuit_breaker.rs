@@ -0,0 +1,275 @@
+//! Circuit breaker for the AI provider.
+//!
+//! When the provider is down or the API key has been revoked, every mention
+//! still waits for a request to fail. After enough consecutive failures the
+//! circuit opens and requests fail straight away with `CircuitOpen` for a
+//! cool-down period. Then a single probe request is let through (half-open):
+//! if it succeeds the circuit closes again, if it fails the cool-down restarts.
+//!
+//! Only errors the provider reports as an outage count as failures (see
+//! `AiProvider::is_outage`). A request refused for its content - a 400, a
+//! safety block, an empty reply - says nothing about the provider's health.
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests go through; counts failures in a row.
+    Closed { failures: u32 },
+    /// Requests fail fast until `until`.
+    Open { until: Instant },
+    /// One probe request is in flight; everything else still fails fast.
+    HalfOpen { since: Instant },
+}
+
+/// Returned instead of calling the provider while the circuit is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitOpen {
+    /// Roughly how long until the next request is let through.
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AI provider is temporarily unavailable (retry in {}s)",
+            self.retry_after.as_secs().max(1)
+        )
+    }
+}
+
+impl Error for CircuitOpen {}
+
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<CircuitState>,
+}
+
+impl CircuitBreaker {
+    /// Opens after `failure_threshold` failures in a row (at least one) and
+    /// stays open for `cooldown`.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Mutex::new(CircuitState::Closed { failures: 0 }),
+        }
+    }
+
+    /// The current state, for diagnostics.
+    #[allow(dead_code)]
+    pub fn state(&self) -> CircuitState {
+        *self.lock()
+    }
+
+    /// Whether a request may call the provider now. Once the cool-down has
+    /// passed, the first caller becomes the half-open probe.
+    pub fn try_acquire(&self) -> Result<(), CircuitOpen> {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> Result<(), CircuitOpen> {
+        let mut state = self.lock();
+        match *state {
+            CircuitState::Closed { .. } => Ok(()),
+            CircuitState::Open { until } if now < until => Err(CircuitOpen {
+                retry_after: until - now,
+            }),
+            // A probe that never reported back (e.g. the request was dropped)
+            // shouldn't keep the circuit half-open forever.
+            CircuitState::HalfOpen { since }
+                if now.saturating_duration_since(since) < self.cooldown =>
+            {
+                Err(CircuitOpen {
+                    retry_after: self.cooldown - now.saturating_duration_since(since),
+                })
+            }
+            _ => {
+                *state = CircuitState::HalfOpen { since: now };
+                Ok(())
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        if *state != (CircuitState::Closed { failures: 0 }) {
+            tracing::info!("AI provider recovered, closing circuit");
+        }
+        *state = CircuitState::Closed { failures: 0 };
+    }
+
+    pub fn record_failure(&self) {
+        self.record_failure_at(Instant::now());
+    }
+
+    fn record_failure_at(&self, now: Instant) {
+        let mut state = self.lock();
+        let failures = match *state {
+            CircuitState::Closed { failures } => failures + 1,
+            // The probe failed, or a request that started before the circuit
+            // opened finished late: either way, (re)start the cool-down.
+            CircuitState::HalfOpen { .. } | CircuitState::Open { .. } => self.failure_threshold,
+        };
+        *state = if failures >= self.failure_threshold {
+            tracing::warn!(
+                failures,
+                cooldown_secs = self.cooldown.as_secs(),
+                "AI provider keeps failing, opening circuit"
+            );
+            CircuitState::Open {
+                until: now + self.cooldown,
+            }
+        } else {
+            CircuitState::Closed { failures }
+        };
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CircuitState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::models::{AiConfig, AiMessage, AiProviderResponse};
+    use crate::core::ai::{AiProvider, AiService};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A provider that is down, or with `outage: false`, up but refusing
+    /// every request.
+    struct FailingProvider {
+        calls: Arc<AtomicUsize>,
+        outage: bool,
+    }
+
+    #[async_trait]
+    impl AiProvider for FailingProvider {
+        async fn chat_complete(
+            &self,
+            _messages: &[AiMessage],
+            _config: &AiConfig,
+        ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err("503 Service Unavailable".into())
+        }
+
+        fn is_outage(&self, _error: &(dyn Error + Send + Sync + 'static)) -> bool {
+            self.outage
+        }
+    }
+
+    fn service(outage: bool) -> (AiService<FailingProvider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = FailingProvider {
+            calls: Arc::clone(&calls),
+            outage,
+        };
+        let config = AiConfig {
+            model: "test".to_string(),
+            temperature: 0.7,
+            max_tokens: None,
+            top_p: None,
+            repetition_penalty: None,
+            reasoning_enabled: None,
+            reasoning_effort: None,
+            tools: None,
+            tool_config: None,
+        };
+        let service = AiService::new(provider, "Be helpful.".to_string(), config)
+            .with_circuit_breaker(2, Duration::from_secs(60));
+        (service, calls)
+    }
+
+    #[test]
+    fn test_state_transitions() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(30));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        // Failures below the threshold keep it closed; a success resets them
+        breaker.record_failure_at(at(0));
+        breaker.record_failure_at(at(0));
+        breaker.record_success();
+        breaker.record_failure_at(at(0));
+        breaker.record_failure_at(at(0));
+        assert_eq!(breaker.state(), CircuitState::Closed { failures: 2 });
+        assert!(breaker.try_acquire_at(at(0)).is_ok());
+
+        // The third failure in a row opens it
+        breaker.record_failure_at(at(1));
+        assert_eq!(breaker.state(), CircuitState::Open { until: at(31) });
+        assert_eq!(
+            breaker.try_acquire_at(at(11)),
+            Err(CircuitOpen {
+                retry_after: Duration::from_secs(20)
+            })
+        );
+
+        // After the cool-down exactly one probe goes through
+        assert!(breaker.try_acquire_at(at(31)).is_ok());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen { since: at(31) });
+        assert!(breaker.try_acquire_at(at(32)).is_err());
+
+        // A failed probe reopens it for another cool-down
+        breaker.record_failure_at(at(33));
+        assert_eq!(breaker.state(), CircuitState::Open { until: at(63) });
+
+        // A successful probe closes it
+        assert!(breaker.try_acquire_at(at(63)).is_ok());
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed { failures: 0 });
+        assert!(breaker.try_acquire_at(at(63)).is_ok());
+    }
+
+    #[test]
+    fn test_abandoned_probe_does_not_stick() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(10));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        breaker.record_failure_at(at(0));
+        assert!(breaker.try_acquire_at(at(10)).is_ok());
+        // The probe never reports back
+        assert!(breaker.try_acquire_at(at(15)).is_err());
+        assert!(breaker.try_acquire_at(at(20)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_service_fails_fast_once_open() {
+        let (service, calls) = service(true);
+        let question = [AiMessage::user("Hello?")];
+
+        for _ in 0..2 {
+            let error = service.chat(&question).await.unwrap_err();
+            assert!(error.downcast_ref::<CircuitOpen>().is_none());
+        }
+        for _ in 0..3 {
+            let error = service.chat(&question).await.unwrap_err();
+            assert!(error.downcast_ref::<CircuitOpen>().is_some());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_refused_requests_do_not_open_the_circuit() {
+        let (service, calls) = service(false);
+        let question = [AiMessage::user("Hello?")];
+
+        for _ in 0..5 {
+            let error = service.chat(&question).await.unwrap_err();
+            assert!(error.downcast_ref::<CircuitOpen>().is_none());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+}
@@ -9,7 +9,7 @@
 // 3. Fills the token budget with the highest-scoring messages
 // 4. Returns messages in chronological order
 
-use super::models::AiMessage;
+use super::models::{AiImage, AiMessage, Role};
 
 // =============================================================================
 // CONTEXT MESSAGE
@@ -18,8 +18,8 @@ use super::models::AiMessage;
 /// A message with metadata for relevance scoring.
 #[derive(Debug, Clone)]
 pub struct ContextMessage {
-    /// Role: "user", "assistant", or "system"
-    pub role: String,
+    /// Who wrote the message
+    pub role: Role,
     /// The message content
     pub content: String,
     /// Unix timestamp (for ordering)
@@ -28,31 +28,41 @@ pub struct ContextMessage {
     pub author_name: String,
     /// Relevance score (0.0 - 1.0, computed during selection)
     pub relevance_score: f32,
+    /// Images attached to the message, for multimodal models
+    pub images: Vec<AiImage>,
 }
 
 impl ContextMessage {
     /// Creates a new context message with default relevance score of 0.0
-    pub fn new(role: String, content: String, timestamp: u64, author_name: String) -> Self {
+    pub fn new(role: Role, content: String, timestamp: u64, author_name: String) -> Self {
         Self {
             role,
             content,
             timestamp,
             author_name,
             relevance_score: 0.0,
+            images: Vec::new(),
         }
     }
 
+    /// Attaches images to the message
+    pub fn with_images(mut self, images: Vec<AiImage>) -> Self {
+        self.images = images;
+        self
+    }
+
     /// Converts to an AiMessage for the API
     pub fn to_ai_message(&self) -> AiMessage {
-        let content = if self.role == "user" && !self.author_name.is_empty() {
+        let content = if self.role == Role::User && !self.author_name.is_empty() {
             format!("{}: {}", self.author_name, self.content)
         } else {
             self.content.clone()
         };
 
         AiMessage {
-            role: self.role.clone(),
+            role: self.role,
             content,
+            images: self.images.clone(),
         }
     }
 }
@@ -279,6 +289,101 @@ pub fn select_context(messages: Vec<ContextMessage>, max_tokens: usize) -> Vec<A
     selector.select(messages)
 }
 
+// =============================================================================
+// HISTORY FILTERING
+// =============================================================================
+
+/// A message fetched from the channel as conversation history.
+#[derive(Debug, Clone)]
+pub struct HistoryMessage {
+    pub author_id: u64,
+    pub author_name: String,
+    pub author_is_bot: bool,
+    pub content: String,
+    /// Unix timestamp
+    pub timestamp: u64,
+    /// Images to show a multimodal model, only set on the message that
+    /// mentioned the bot.
+    pub images: Vec<AiImage>,
+}
+
+/// Which history messages are worth showing the model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryFilter {
+    /// Leave out messages from bots other than this one.
+    pub skip_other_bots: bool,
+    /// Leave out text commands starting with this prefix (e.g. `!level`).
+    pub command_prefix: Option<String>,
+    /// Leave out messages shorter than this many characters (0 keeps all).
+    pub min_chars: usize,
+}
+
+impl Default for HistoryFilter {
+    fn default() -> Self {
+        Self {
+            skip_other_bots: true,
+            command_prefix: None,
+            min_chars: 0,
+        }
+    }
+}
+
+impl HistoryFilter {
+    fn keeps(&self, message: &HistoryMessage, bot_id: u64) -> bool {
+        if message.author_id == bot_id {
+            return true;
+        }
+        if self.skip_other_bots && message.author_is_bot {
+            return false;
+        }
+        if let Some(prefix) = self.command_prefix.as_deref().filter(|p| !p.is_empty()) {
+            if message.content.trim_start().starts_with(prefix) {
+                return false;
+            }
+        }
+        message.content.trim().chars().count() >= self.min_chars
+    }
+}
+
+/// Turn fetched history (oldest first) into the conversation sent to the
+/// model: filter it, mark this bot's messages as the assistant's, and select
+/// what fits in `max_tokens`. The newest message is the one being answered,
+/// so it's always kept.
+pub fn history_to_context(
+    messages: &[HistoryMessage],
+    bot_id: u64,
+    filter: &HistoryFilter,
+    max_tokens: usize,
+) -> Vec<AiMessage> {
+    let newest = messages.len().saturating_sub(1);
+    let context = messages
+        .iter()
+        .enumerate()
+        .filter(|(index, message)| {
+            *index == newest || !message.images.is_empty() || filter.keeps(message, bot_id)
+        })
+        .map(|(_, message)| {
+            let context = if message.author_id == bot_id {
+                ContextMessage::new(
+                    Role::Assistant,
+                    message.content.clone(),
+                    message.timestamp,
+                    String::new(),
+                )
+            } else {
+                ContextMessage::new(
+                    Role::User,
+                    message.content.clone(),
+                    message.timestamp,
+                    message.author_name.clone(),
+                )
+            };
+            context.with_images(message.images.clone())
+        })
+        .collect();
+    select_context(context, max_tokens)
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -297,7 +402,7 @@ mod tests {
     #[test]
     fn test_calculate_relevance_no_matches() {
         let msg = ContextMessage::new(
-            "user".to_string(),
+            Role::User,
             "hello there".to_string(),
             1000,
             "User".to_string(),
@@ -309,7 +414,7 @@ mod tests {
     #[test]
     fn test_calculate_relevance_with_matches() {
         let msg = ContextMessage::new(
-            "user".to_string(),
+            Role::User,
             "I need help with the project fiefdom bug".to_string(),
             1000,
             "User".to_string(),
@@ -329,7 +434,7 @@ mod tests {
         let messages: Vec<ContextMessage> = (0..10)
             .map(|i| {
                 ContextMessage::new(
-                    "user".to_string(),
+                    Role::User,
                     format!("Message {}", i),
                     i as u64,
                     "User".to_string(),
@@ -353,7 +458,7 @@ mod tests {
         let messages: Vec<ContextMessage> = (0..20)
             .map(|i| {
                 ContextMessage::new(
-                    "user".to_string(),
+                    Role::User,
                     "x".repeat(100), // ~25 tokens each
                     i as u64,
                     "User".to_string(),
@@ -370,12 +475,7 @@ mod tests {
 
     #[test]
     fn test_to_ai_message_includes_author() {
-        let msg = ContextMessage::new(
-            "user".to_string(),
-            "Hello".to_string(),
-            1000,
-            "Alice".to_string(),
-        );
+        let msg = ContextMessage::new(Role::User, "Hello".to_string(), 1000, "Alice".to_string());
         let ai_msg = msg.to_ai_message();
         assert_eq!(ai_msg.content, "Alice: Hello");
     }
@@ -383,7 +483,7 @@ mod tests {
     #[test]
     fn test_to_ai_message_assistant_no_author() {
         let msg = ContextMessage::new(
-            "assistant".to_string(),
+            Role::Assistant,
             "Hello back".to_string(),
             1000,
             "".to_string(),
@@ -391,4 +491,85 @@ mod tests {
         let ai_msg = msg.to_ai_message();
         assert_eq!(ai_msg.content, "Hello back");
     }
+
+    fn history(author_id: u64, is_bot: bool, content: &str) -> HistoryMessage {
+        HistoryMessage {
+            author_id,
+            author_name: format!("user{}", author_id),
+            author_is_bot: is_bot,
+            content: content.to_string(),
+            timestamp: 0,
+            images: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_history_filters_bots_commands_and_short_messages() {
+        let bot_id = 1;
+        let messages = vec![
+            history(2, false, "How do I level up faster?"),
+            history(1, true, "Chat in channels with XP boosts."),
+            history(3, true, "Another bot's announcement"),
+            history(2, false, "!level"),
+            history(4, false, "ok"),
+            history(2, false, "thx"),
+        ];
+        let contents = |filter: &HistoryFilter| -> Vec<String> {
+            history_to_context(&messages, bot_id, filter, 8000)
+                .into_iter()
+                .map(|message| message.content)
+                .collect()
+        };
+
+        let filter = HistoryFilter {
+            skip_other_bots: true,
+            command_prefix: Some("!".to_string()),
+            min_chars: 5,
+        };
+        // The newest message is kept even though it's short
+        assert_eq!(
+            contents(&filter),
+            vec![
+                "user2: How do I level up faster?",
+                "Chat in channels with XP boosts.",
+                "user2: thx",
+            ]
+        );
+        let roles: Vec<Role> = history_to_context(&messages, bot_id, &filter, 8000)
+            .into_iter()
+            .map(|message| message.role)
+            .collect();
+        assert_eq!(roles, vec![Role::User, Role::Assistant, Role::User]);
+
+        // With every filter off, everything is included
+        let keep_all = HistoryFilter {
+            skip_other_bots: false,
+            command_prefix: None,
+            min_chars: 0,
+        };
+        assert_eq!(contents(&keep_all).len(), messages.len());
+    }
+
+    #[test]
+    fn test_images_stay_on_the_trigger_message() {
+        let image = AiImage {
+            mime_type: "image/png".to_string(),
+            source: crate::core::ai::ImageSource::Inline("aGk=".to_string()),
+        };
+        // Someone else spoke after the mention; the mention itself is short
+        let mut trigger = history(2, false, "?");
+        trigger.images = vec![image.clone()];
+        let messages = vec![trigger, history(3, false, "Unrelated follow-up message")];
+        let filter = HistoryFilter {
+            skip_other_bots: true,
+            command_prefix: None,
+            min_chars: 5,
+        };
+
+        let context = history_to_context(&messages, 1, &filter, 8000);
+        assert_eq!(context.len(), 2);
+        assert_eq!(context[0].content, "user2: ?");
+        assert_eq!(context[0].images, vec![image]);
+        assert!(context[1].images.is_empty());
+    }
 }
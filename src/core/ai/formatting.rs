@@ -3,11 +3,14 @@
 //! This module provides helpers to format AI response metadata (like citations)
 //! into Discord-friendly markdown format.
 
-use super::models::Citation;
+use super::models::{Citation, UrlContextMetadata, UrlRetrievalStatus};
 
 /// Maximum number of citations to display to avoid message spam.
 const MAX_CITATIONS: usize = 5;
 
+/// Maximum number of URLs listed in each URL Context section.
+const MAX_URLS: usize = 5;
+
 /// Formats citations for Discord display.
 ///
 /// Returns a formatted string with citation links, or `None` if there are no citations.
@@ -47,9 +50,66 @@ pub fn format_citations_for_discord(citations: &[Citation]) -> Option<String> {
     Some(result)
 }
 
+/// Formats the URLs the model tried to read with URL Context.
+///
+/// Returns `None` if no URLs were fetched. URLs are wrapped in `<>` so Discord
+/// doesn't add a preview for each one. Each section lists at most 5 URLs.
+///
+/// # Example Output
+/// ```text
+/// Pages read:
+/// - <https://example.com/post>
+/// Couldn't read:
+/// - <https://news.example.org/story> (paywalled)
+/// ```
+pub fn format_url_context_for_discord(metadata: &UrlContextMetadata) -> Option<String> {
+    let read: Vec<String> = metadata
+        .urls_read()
+        .map(|url| format!("- <{}>", url))
+        .collect();
+    let failed: Vec<String> = metadata
+        .urls
+        .iter()
+        .filter_map(|entry| {
+            let reason = match entry.status {
+                UrlRetrievalStatus::Success => return None,
+                UrlRetrievalStatus::Error => "failed to load",
+                UrlRetrievalStatus::Paywall => "paywalled",
+                UrlRetrievalStatus::Unsafe => "blocked as unsafe",
+                UrlRetrievalStatus::Unknown => "no status",
+            };
+            Some(format!("- <{}> ({})", entry.url, reason))
+        })
+        .collect();
+
+    let mut sections = Vec::new();
+    if !read.is_empty() {
+        sections.push(format!("Pages read:\n{}", url_list(&read)));
+    }
+    if !failed.is_empty() {
+        sections.push(format!("Couldn't read:\n{}", url_list(&failed)));
+    }
+
+    if sections.is_empty() {
+        None
+    } else {
+        Some(sections.join("\n"))
+    }
+}
+
+/// The first `MAX_URLS` lines, with a note for the rest.
+fn url_list(lines: &[String]) -> String {
+    let mut list = lines[..lines.len().min(MAX_URLS)].join("\n");
+    if lines.len() > MAX_URLS {
+        list.push_str(&format!("\n_...and {} more_", lines.len() - MAX_URLS));
+    }
+    list
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::ai::models::UrlRetrieval;
 
     #[test]
     fn test_empty_citations() {
@@ -92,4 +152,47 @@ mod tests {
         assert!(!result.contains("Article 5"));
         assert!(result.contains("...and 5 more sources"));
     }
+
+    #[test]
+    fn test_url_context_lists_read_and_failed_pages() {
+        let metadata = UrlContextMetadata {
+            urls: vec![
+                UrlRetrieval {
+                    url: "https://example.com/post".to_string(),
+                    status: UrlRetrievalStatus::Success,
+                },
+                UrlRetrieval {
+                    url: "https://news.example.org/story".to_string(),
+                    status: UrlRetrievalStatus::Paywall,
+                },
+            ],
+        };
+
+        assert_eq!(
+            format_url_context_for_discord(&metadata).unwrap(),
+            "Pages read:\n- <https://example.com/post>\n\
+             Couldn't read:\n- <https://news.example.org/story> (paywalled)"
+        );
+        assert_eq!(
+            format_url_context_for_discord(&UrlContextMetadata::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_url_context_limit() {
+        let metadata = UrlContextMetadata {
+            urls: (0..8)
+                .map(|i| UrlRetrieval {
+                    url: format!("https://example{}.com", i),
+                    status: UrlRetrievalStatus::Success,
+                })
+                .collect(),
+        };
+
+        let result = format_url_context_for_discord(&metadata).unwrap();
+        assert!(result.contains("example4.com"));
+        assert!(!result.contains("example5.com"));
+        assert!(result.ends_with("_...and 3 more_"));
+    }
 }
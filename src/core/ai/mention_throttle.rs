@@ -0,0 +1,166 @@
+//! Debounce for AI mentions.
+//!
+//! Every mention triggers a full AI call. If someone pings the bot three times
+//! in a row we'd pay for three calls and post three interleaved answers. Requests
+//! are tracked per (channel, user): while one is in flight, or shortly after it
+//! finished, new mentions from the same user in the same channel are turned away.
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use std::time::{Duration, Instant};
+
+/// Minimum time between the end of one AI call and the start of the next for
+/// the same user in the same channel.
+pub const MENTION_COOLDOWN: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MentionDecision {
+    /// Go ahead and call the AI.
+    Proceed,
+    /// The previous request hasn't finished yet.
+    InFlight,
+    /// The previous request finished, but too recently.
+    CoolingDown,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MentionState {
+    /// `None` while the request is in flight.
+    pub finished_at: Option<Instant>,
+}
+
+/// Decide whether a new mention should reach the AI, given the last request
+/// for the same (channel, user).
+pub fn decide(last: Option<MentionState>, now: Instant, cooldown: Duration) -> MentionDecision {
+    match last.map(|state| state.finished_at) {
+        None => MentionDecision::Proceed,
+        Some(None) => MentionDecision::InFlight,
+        Some(Some(finished_at)) if now.saturating_duration_since(finished_at) < cooldown => {
+            MentionDecision::CoolingDown
+        }
+        Some(Some(_)) => MentionDecision::Proceed,
+    }
+}
+
+pub struct MentionThrottle {
+    /// (channel_id, user_id) -> last request
+    requests: DashMap<(u64, u64), MentionState>,
+    cooldown: Duration,
+}
+
+impl MentionThrottle {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            requests: DashMap::new(),
+            cooldown,
+        }
+    }
+
+    /// Claim the (channel, user) slot. The request counts as in flight until the
+    /// returned guard is dropped.
+    pub fn try_begin(
+        &self,
+        channel_id: u64,
+        user_id: u64,
+    ) -> Result<MentionGuard<'_>, MentionDecision> {
+        let now = Instant::now();
+        let key = (channel_id, user_id);
+        let started = MentionState { finished_at: None };
+
+        // Decide while holding the entry lock so two mentions can't both proceed
+        match self.requests.entry(key) {
+            Entry::Occupied(mut occupied) => {
+                match decide(Some(*occupied.get()), now, self.cooldown) {
+                    MentionDecision::Proceed => {
+                        occupied.insert(started);
+                    }
+                    refused => return Err(refused),
+                }
+            }
+            Entry::Vacant(vacant) => {
+                vacant.insert(started);
+            }
+        }
+
+        Ok(MentionGuard {
+            throttle: self,
+            key,
+        })
+    }
+
+    fn finish(&self, key: (u64, u64)) {
+        if let Some(mut state) = self.requests.get_mut(&key) {
+            state.finished_at = Some(Instant::now());
+        }
+        // Forget entries whose cooldown is over so the map doesn't grow forever
+        self.requests.retain(|_, state| {
+            state
+                .finished_at
+                .is_none_or(|finished_at| finished_at.elapsed() < self.cooldown)
+        });
+    }
+}
+
+impl Default for MentionThrottle {
+    fn default() -> Self {
+        Self::new(MENTION_COOLDOWN)
+    }
+}
+
+/// Marks a mention as in flight; releases the slot when dropped.
+pub struct MentionGuard<'a> {
+    throttle: &'a MentionThrottle,
+    key: (u64, u64),
+}
+
+impl Drop for MentionGuard<'_> {
+    fn drop(&mut self) {
+        self.throttle.finish(self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide() {
+        let now = Instant::now();
+        let cooldown = Duration::from_secs(5);
+        let finished = |ago| MentionState {
+            finished_at: Some(now - Duration::from_secs(ago)),
+        };
+
+        assert_eq!(decide(None, now, cooldown), MentionDecision::Proceed);
+        assert_eq!(
+            decide(Some(MentionState { finished_at: None }), now, cooldown),
+            MentionDecision::InFlight
+        );
+        // Measured from when the answer came back, however long it took
+        assert_eq!(
+            decide(Some(finished(2)), now, cooldown),
+            MentionDecision::CoolingDown
+        );
+        assert_eq!(
+            decide(Some(finished(6)), now, cooldown),
+            MentionDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn test_guard_releases_slot() {
+        let throttle = MentionThrottle::new(Duration::ZERO);
+
+        let guard = throttle.try_begin(1, 2).unwrap();
+        assert_eq!(
+            throttle.try_begin(1, 2).err(),
+            Some(MentionDecision::InFlight)
+        );
+        // Other users and channels are independent
+        assert!(throttle.try_begin(1, 3).is_ok());
+        assert!(throttle.try_begin(9, 2).is_ok());
+
+        drop(guard);
+        assert!(throttle.try_begin(1, 2).is_ok());
+    }
+}
@@ -1,17 +1,36 @@
 pub mod ai_service;
+pub mod ai_settings;
+pub mod circuit_breaker;
 pub mod context;
 pub mod formatting;
 pub mod knowledge;
+pub mod mention_throttle;
 pub mod models;
+pub mod privacy;
+pub mod request_limit;
+pub mod response_cache;
+pub mod response_limits;
 
-pub use ai_service::{AiProvider, AiService, FunctionCallHandler};
+pub use ai_service::{AiProvider, AiService, DynAiService, FunctionCallHandler};
+pub use ai_settings::{AiSettings, DEFAULT_SYSTEM_PROMPT};
+pub use circuit_breaker::CircuitOpen;
 #[allow(unused_imports)]
-pub use context::{select_context, ContextMessage, ContextSelector};
-pub use formatting::format_citations_for_discord;
+pub use context::{
+    history_to_context, select_context, ContextMessage, ContextSelector, HistoryFilter,
+    HistoryMessage,
+};
+pub use formatting::{format_citations_for_discord, format_url_context_for_discord};
 #[allow(unused_imports)]
 pub use knowledge::{KnowledgeChunk, KnowledgeStore};
+pub use mention_throttle::{MentionDecision, MentionThrottle};
 #[allow(unused_imports)]
 pub use models::{
-    AiConfig, AiMessage, AiProviderResponse, AiResponseWithMeta, AiTool, Citation, FunctionCall,
-    FunctionDef,
+    AiConfig, AiImage, AiMessage, AiProviderResponse, AiResponseWithMeta, AiTool, Citation,
+    EmptyResponse, FunctionCall, FunctionDef, ImageSource, Role,
+};
+pub use privacy::{AiOptOutStore, AiPrivacyService};
+pub use request_limit::AiBusy;
+pub use response_limits::{
+    plan_response, LongResponseMode, ResponseLimits, ResponseLimitsService, ResponseLimitsStore,
+    ResponsePlan,
 };
@@ -1,9 +1,24 @@
 use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
 
 // =============================================================================
 // AI MESSAGE TYPES
 // =============================================================================
 
+/// Who authored a message in an AI conversation.
+///
+/// Serializes to the OpenAI-style names (`"system"`, `"user"`, `"assistant"`),
+/// which is what OpenRouter expects. Providers with different names map them
+/// in their client (Gemini uses `"model"` for the assistant).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
 /// Represents a single message in an AI conversation.
 ///
 /// Messages have a role (user, assistant, system) and content.
@@ -11,8 +26,53 @@ use serde::{Deserialize, Serialize};
 /// provider-specific formats (e.g., Gemini uses "model" instead of "assistant").
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiMessage {
-    pub role: String,
+    pub role: Role,
     pub content: String,
+    /// Images attached to this message. Only sent to providers/models that
+    /// accept multimodal input (see `AiProvider::supports_images`); never part
+    /// of the plain JSON form.
+    #[serde(skip)]
+    pub images: Vec<AiImage>,
+}
+
+impl AiMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(Role::System, content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(Role::User, content)
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(Role::Assistant, content)
+    }
+
+    fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            images: Vec::new(),
+        }
+    }
+}
+
+/// An image attached to a message, for multimodal models.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AiImage {
+    /// MIME type, e.g. "image/png".
+    pub mime_type: String,
+    pub source: ImageSource,
+}
+
+/// Where the image bytes come from.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageSource {
+    /// Base64-encoded image data sent inline with the request.
+    Inline(String),
+    /// A URL the provider fetches itself (Gemini: a Files API / GCS URI).
+    Url(String),
 }
 
 // =============================================================================
@@ -270,6 +330,29 @@ pub struct AiProviderResponse {
     pub function_calls: Option<Vec<FunctionCall>>,
 }
 
+/// Returned when a provider answers the request but without any text, e.g.
+/// because the reply was filtered or the model stopped early. Trying again
+/// usually works.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyResponse {
+    /// The provider that answered, e.g. "OpenRouter".
+    pub provider: &'static str,
+    /// Why, if the provider said (e.g. a finish reason like "content_filter").
+    pub reason: Option<String>,
+}
+
+impl fmt::Display for EmptyResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} returned an empty response", self.provider)?;
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for EmptyResponse {}
+
 /// Metadata from Google Search grounding.
 ///
 /// When the model uses the Google Search tool, it includes this metadata
@@ -283,13 +366,18 @@ pub struct GroundingMetadata {
     /// Web sources that were used.
     pub web_sources: Vec<WebSource>,
 
-    /// Grounding chunks (snippets of content from sources).
+    /// Grounding chunks (snippets of content from sources), in the order the
+    /// provider listed them. `GroundingSupport::chunk_indices` index into this.
     pub grounding_chunks: Vec<GroundingChunk>,
+
+    /// Which parts of the answer are backed by which chunks, for inline
+    /// citation markers.
+    pub supports: Vec<GroundingSupport>,
 }
 
 /// A web source used in grounding.
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WebSource {
     /// URI of the source.
     pub uri: String,
@@ -300,26 +388,75 @@ pub struct WebSource {
 
 /// A chunk of grounded content from a source.
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GroundingChunk {
-    /// The content snippet.
-    pub content: String,
+    /// The content snippet, if the provider sent one (web search results
+    /// only come with a link).
+    pub content: Option<String>,
 
     /// Source information for this chunk.
     pub source: Option<WebSource>,
 }
 
+/// A span of the answer and the grounding chunks that support it.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroundingSupport {
+    /// Byte offset where the span starts in the response text.
+    pub start_index: usize,
+
+    /// Byte offset just past the end of the span.
+    pub end_index: usize,
+
+    /// The text of the span, as the provider quoted it.
+    pub text: Option<String>,
+
+    /// Indices into `GroundingMetadata::grounding_chunks`.
+    pub chunk_indices: Vec<usize>,
+
+    /// The provider's confidence in each chunk, in the same order.
+    pub confidence_scores: Vec<f64>,
+}
+
 /// Metadata from URL Context tool.
 ///
-/// When the model reads URLs, this contains information about what was read.
-#[allow(dead_code)]
-#[derive(Debug, Clone, Default)]
+/// When the model reads URLs, this lists each URL it tried to fetch and
+/// whether that worked.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct UrlContextMetadata {
-    /// URLs that were successfully read.
-    pub urls_read: Vec<String>,
+    /// Every URL the model tried to read, in the order it reported them.
+    pub urls: Vec<UrlRetrieval>,
+}
+
+impl UrlContextMetadata {
+    /// URLs whose content the model actually got to read.
+    pub fn urls_read(&self) -> impl Iterator<Item = &str> {
+        self.urls
+            .iter()
+            .filter(|url| url.status == UrlRetrievalStatus::Success)
+            .map(|url| url.url.as_str())
+    }
+}
+
+/// One URL the model tried to read with the URL Context tool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlRetrieval {
+    pub url: String,
+    pub status: UrlRetrievalStatus,
+}
 
-    /// URLs that failed to be read (with error messages).
-    pub urls_failed: Vec<(String, String)>,
+/// Whether the content of a URL could be retrieved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlRetrievalStatus {
+    Success,
+    /// The page couldn't be fetched (not found, timeout, ...).
+    Error,
+    /// The content is behind a paywall.
+    Paywall,
+    /// The content was flagged as unsafe.
+    Unsafe,
+    /// The provider didn't say, or used a status we don't know.
+    Unknown,
 }
 
 /// A function call requested by the model.
@@ -362,6 +499,8 @@ pub struct AiResponseWithMeta {
     pub citations: Vec<Citation>,
     /// Confidence score if available (0.0 - 1.0).
     pub confidence: Option<f32>,
+    /// Which URLs the model read, when the URL Context tool was used.
+    pub url_context: Option<UrlContextMetadata>,
 }
 
 /// A citation from a grounded web source.
@@ -0,0 +1,112 @@
+//! Per-user AI opt-out (`/ai forgetme`).
+//!
+//! When someone mentions the bot, the recent channel history is sent to the AI
+//! provider as context. Users who opt out have their messages left out of that
+//! history, and the bot doesn't answer their mentions either.
+//!
+//! The opted-out set is small and checked for every message in the history, so
+//! the service keeps it in memory and only goes to the store on changes.
+
+use async_trait::async_trait;
+use dashmap::DashSet;
+use std::error::Error;
+
+/// Persistence for the opted-out user list.
+#[async_trait]
+pub trait AiOptOutStore: Send + Sync {
+    /// All users who opted out.
+    async fn load_opted_out(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>>;
+
+    /// Record (or clear) a user's opt-out.
+    async fn set_opted_out(
+        &self,
+        user_id: u64,
+        opted_out: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: AiOptOutStore + ?Sized> AiOptOutStore for Box<S> {
+    async fn load_opted_out(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        (**self).load_opted_out().await
+    }
+
+    async fn set_opted_out(
+        &self,
+        user_id: u64,
+        opted_out: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).set_opted_out(user_id, opted_out).await
+    }
+}
+
+pub struct AiPrivacyService<S: AiOptOutStore> {
+    store: S,
+    opted_out: DashSet<u64>,
+}
+
+impl<S: AiOptOutStore> AiPrivacyService<S> {
+    /// Create the service and load the opted-out users from the store.
+    pub async fn load(store: S) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let opted_out = store.load_opted_out().await?.into_iter().collect();
+        Ok(Self { store, opted_out })
+    }
+
+    pub fn is_opted_out(&self, user_id: u64) -> bool {
+        self.opted_out.contains(&user_id)
+    }
+
+    /// Opt a user out of (or back into) AI features.
+    pub async fn set_opted_out(
+        &self,
+        user_id: u64,
+        opted_out: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.store.set_opted_out(user_id, opted_out).await?;
+        if opted_out {
+            self.opted_out.insert(user_id);
+        } else {
+            self.opted_out.remove(&user_id);
+        }
+        Ok(())
+    }
+
+    /// Drop the items written by opted-out users, keeping the order of the rest.
+    ///
+    /// `author_id` picks the author out of an item, so this works on Discord
+    /// messages without the core layer knowing about them.
+    pub fn retain_allowed<T>(&self, items: Vec<T>, author_id: impl Fn(&T) -> u64) -> Vec<T> {
+        if self.opted_out.is_empty() {
+            return items;
+        }
+        items
+            .into_iter()
+            .filter(|item| !self.is_opted_out(author_id(item)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::ai::InMemoryOptOutStore;
+
+    #[tokio::test]
+    async fn test_context_excludes_opted_out_users() {
+        let service = AiPrivacyService::load(InMemoryOptOutStore::new())
+            .await
+            .unwrap();
+        service.set_opted_out(2, true).await.unwrap();
+
+        let history = vec![(1, "hi"), (2, "my secret"), (3, "hello"), (2, "more")];
+        let kept = service.retain_allowed(history.clone(), |(author, _)| *author);
+        assert_eq!(kept, vec![(1, "hi"), (3, "hello")]);
+
+        // Opting back in restores them
+        service.set_opted_out(2, false).await.unwrap();
+        assert_eq!(
+            service.retain_allowed(history.clone(), |(a, _)| *a),
+            history
+        );
+    }
+}
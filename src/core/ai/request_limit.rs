@@ -0,0 +1,230 @@
+//! Bot-wide cap on AI requests in flight.
+//!
+//! Mentions, `/summarize`, `/tldr` and `/translate` all end up calling the
+//! provider, and a busy server can start many of them at once. `AiService`
+//! takes a slot for every request and keeps it through any tool-call rounds,
+//! giving it back once the final answer is in. Past the cap a new request fails
+//! straight away with `AiBusy` instead of piling up tasks waiting on the
+//! provider.
+
+use std::error::Error;
+use std::fmt;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Returned instead of calling the provider while every slot is taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AiBusy;
+
+impl fmt::Display for AiBusy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Too many AI requests in flight")
+    }
+}
+
+impl Error for AiBusy {}
+
+pub struct RequestLimit {
+    slots: Semaphore,
+}
+
+impl RequestLimit {
+    /// Allow at most `limit` requests at once.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            slots: Semaphore::new(limit),
+        }
+    }
+
+    /// Take a slot for one request; it's released when the permit drops.
+    pub fn try_acquire(&self) -> Result<SemaphorePermit<'_>, AiBusy> {
+        self.slots.try_acquire().map_err(|_| AiBusy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::models::{AiConfig, AiMessage, AiProviderResponse, FunctionCall};
+    use crate::core::ai::{AiProvider, AiService, FunctionCallHandler};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::Notify;
+
+    /// Answers only once `release` is notified.
+    struct GatedProvider {
+        started: Arc<Notify>,
+        release: Arc<Notify>,
+    }
+
+    #[async_trait]
+    impl AiProvider for GatedProvider {
+        async fn chat_complete(
+            &self,
+            _messages: &[AiMessage],
+            _config: &AiConfig,
+        ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+            self.started.notify_one();
+            self.release.notified().await;
+            Ok(AiProviderResponse {
+                content: "done".to_string(),
+                ..Default::default()
+            })
+        }
+    }
+
+    /// Asks for one tool call, then answers.
+    #[derive(Default)]
+    struct ToolProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl AiProvider for ToolProvider {
+        async fn chat_complete(
+            &self,
+            _messages: &[AiMessage],
+            _config: &AiConfig,
+        ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Ok(AiProviderResponse {
+                    function_calls: Some(vec![FunctionCall {
+                        name: "lookup".to_string(),
+                        args: serde_json::json!({}),
+                    }]),
+                    ..Default::default()
+                });
+            }
+            Ok(AiProviderResponse {
+                content: "done".to_string(),
+                ..Default::default()
+            })
+        }
+    }
+
+    /// Runs a tool only once `release` is notified.
+    struct GatedHandler {
+        started: Arc<Notify>,
+        release: Arc<Notify>,
+    }
+
+    #[async_trait]
+    impl FunctionCallHandler for GatedHandler {
+        async fn handle_function_call(
+            &self,
+            _name: &str,
+            _args: &serde_json::Value,
+        ) -> Result<serde_json::Value, String> {
+            self.started.notify_one();
+            self.release.notified().await;
+            Ok(serde_json::json!({ "found": true }))
+        }
+
+        fn supported_functions(&self) -> Vec<String> {
+            vec!["lookup".to_string()]
+        }
+    }
+
+    fn test_config() -> AiConfig {
+        AiConfig {
+            model: "test".to_string(),
+            temperature: 0.7,
+            max_tokens: None,
+            top_p: None,
+            repetition_penalty: None,
+            reasoning_enabled: None,
+            reasoning_effort: None,
+            tools: None,
+            tool_config: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_service_is_busy_past_the_limit() {
+        let (started, release) = (Arc::new(Notify::new()), Arc::new(Notify::new()));
+        let provider = GatedProvider {
+            started: Arc::clone(&started),
+            release: Arc::clone(&release),
+        };
+        let service = Arc::new(
+            AiService::new(provider, "Be helpful.".to_string(), test_config())
+                .with_max_concurrent_requests(1),
+        );
+        let question = [AiMessage::user("Hello?")];
+
+        let first = tokio::spawn({
+            let service = Arc::clone(&service);
+            async move {
+                let question = [AiMessage::user("Hello?")];
+                service
+                    .chat(&question)
+                    .await
+                    .map(|response| response.answer)
+            }
+        });
+        started.notified().await;
+
+        // Any other request, e.g. a `/tldr`, is turned away while it runs
+        let error = service.chat(&question).await.unwrap_err();
+        assert!(error.downcast_ref::<AiBusy>().is_some());
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap(), "done");
+
+        // The slot is free again
+        release.notify_one();
+        assert!(service.chat(&question).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_slot_is_kept_between_tool_rounds() {
+        let (started, release) = (Arc::new(Notify::new()), Arc::new(Notify::new()));
+        let handler = GatedHandler {
+            started: Arc::clone(&started),
+            release: Arc::clone(&release),
+        };
+        let service = Arc::new(
+            AiService::with_function_handler(
+                ToolProvider::default(),
+                "Be helpful.".to_string(),
+                test_config(),
+                Box::new(handler),
+            )
+            .with_max_concurrent_requests(1),
+        );
+
+        let first = tokio::spawn({
+            let service = Arc::clone(&service);
+            async move {
+                let question = [AiMessage::user("Look it up")];
+                service
+                    .chat(&question)
+                    .await
+                    .map(|response| response.answer)
+            }
+        });
+        started.notified().await;
+
+        // Between the tool call and the follow-up request the slot stays
+        // taken, so the first request can't be turned away halfway through
+        let error = service
+            .chat(&[AiMessage::user("Hello?")])
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<AiBusy>().is_some());
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap(), "done");
+    }
+
+    #[test]
+    fn test_permits_are_released() {
+        let limit = RequestLimit::new(2);
+        let first = limit.try_acquire().unwrap();
+        let _second = limit.try_acquire().unwrap();
+        assert_eq!(limit.try_acquire().err(), Some(AiBusy));
+
+        drop(first);
+        assert!(limit.try_acquire().is_ok());
+    }
+}
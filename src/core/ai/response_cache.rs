@@ -0,0 +1,198 @@
+//! Short-lived cache of AI replies to identical requests.
+//!
+//! FAQ-style questions tend to arrive word for word, and each one is a paid
+//! provider call. When enabled, `AiService` remembers the provider's response
+//! for a few minutes, keyed by the model, reasoning settings and the full
+//! message list (system prompt included). Requests with tools or images are
+//! never cached: search results and function calls change from call to call.
+
+use super::models::{AiConfig, AiMessage, AiProviderResponse};
+use dashmap::DashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// Cached replies kept at most; expired ones are dropped first when full.
+const MAX_ENTRIES: usize = 256;
+
+pub struct ResponseCache {
+    ttl: Duration,
+    entries: DashMap<u64, (Instant, AiProviderResponse)>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: DashMap::new(),
+        }
+    }
+
+    /// Cache key for a request, or `None` when it mustn't be cached.
+    pub fn key(messages: &[AiMessage], config: &AiConfig) -> Option<u64> {
+        let uses_tools = config.tools.as_ref().is_some_and(|tools| !tools.is_empty());
+        let has_images = messages.iter().any(|message| !message.images.is_empty());
+        if uses_tools || has_images {
+            return None;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        config.model.hash(&mut hasher);
+        config.reasoning_enabled.hash(&mut hasher);
+        config.reasoning_effort.hash(&mut hasher);
+        for message in messages {
+            message.role.hash(&mut hasher);
+            message.content.hash(&mut hasher);
+        }
+        Some(hasher.finish())
+    }
+
+    pub fn get(&self, key: u64) -> Option<AiProviderResponse> {
+        self.get_at(key, Instant::now())
+    }
+
+    fn get_at(&self, key: u64, now: Instant) -> Option<AiProviderResponse> {
+        let entry = self.entries.get(&key)?;
+        let (stored_at, response) = &*entry;
+        if now.saturating_duration_since(*stored_at) < self.ttl {
+            Some(response.clone())
+        } else {
+            drop(entry);
+            self.entries.remove(&key);
+            None
+        }
+    }
+
+    /// Remember a response. Replies that asked for function calls aren't final
+    /// answers, so they're skipped.
+    pub fn insert(&self, key: u64, response: &AiProviderResponse) {
+        self.insert_at(key, response, Instant::now());
+    }
+
+    fn insert_at(&self, key: u64, response: &AiProviderResponse, now: Instant) {
+        if response.function_calls.is_some() {
+            return;
+        }
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries
+                .retain(|_, (stored_at, _)| now.saturating_duration_since(*stored_at) < self.ttl);
+            // Still full: forget the oldest reply
+            if self.entries.len() >= MAX_ENTRIES {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|entry| entry.value().0)
+                    .map(|entry| *entry.key());
+                if let Some(oldest) = oldest {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.entries.insert(key, (now, response.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ai::models::AiTool;
+    use crate::core::ai::{AiProvider, AiService};
+    use async_trait::async_trait;
+    use std::error::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Counts provider calls and echoes the last message back.
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl AiProvider for CountingProvider {
+        async fn chat_complete(
+            &self,
+            messages: &[AiMessage],
+            _config: &AiConfig,
+        ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(AiProviderResponse {
+                content: format!("echo: {}", messages.last().unwrap().content),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn config(tools: Option<Vec<AiTool>>) -> AiConfig {
+        AiConfig {
+            model: "test".to_string(),
+            temperature: 0.7,
+            max_tokens: None,
+            top_p: None,
+            repetition_penalty: None,
+            reasoning_enabled: None,
+            reasoning_effort: None,
+            tools,
+            tool_config: None,
+        }
+    }
+
+    fn service(tools: Option<Vec<AiTool>>) -> (AiService<CountingProvider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = CountingProvider {
+            calls: Arc::clone(&calls),
+        };
+        let service = AiService::new(provider, "Be helpful.".to_string(), config(tools))
+            .with_response_cache(Some(Duration::from_secs(60)));
+        (service, calls)
+    }
+
+    #[tokio::test]
+    async fn test_identical_requests_hit_the_provider_once() {
+        let (service, calls) = service(None);
+        let question = [AiMessage::user("How do I earn XP?")];
+
+        let first = service.chat(&question).await.unwrap();
+        let second = service.chat(&question).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.answer, second.answer);
+
+        // A different question, or a different system prompt, is a new request
+        service
+            .chat(&[AiMessage::user("How do I earn coins?")])
+            .await
+            .unwrap();
+        service
+            .complete_task("Summarize.", &question, &[])
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_requests_with_tools_are_not_cached() {
+        let (service, calls) = service(Some(vec![AiTool::GoogleSearch]));
+        let question = [AiMessage::user("What's new today?")];
+        service.chat(&question).await.unwrap();
+        service.chat(&question).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        let response = AiProviderResponse {
+            content: "cached".to_string(),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        cache.insert_at(1, &response, start);
+
+        let at = |secs| start + Duration::from_secs(secs);
+        assert!(cache.get_at(1, at(59)).is_some());
+        assert!(cache.get_at(1, at(60)).is_none());
+        assert!(
+            cache.get_at(1, at(0)).is_none(),
+            "expired entries are removed"
+        );
+    }
+}
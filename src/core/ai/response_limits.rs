@@ -0,0 +1,232 @@
+//! Per-guild limits on how long an AI reply can get in the channel.
+//!
+//! Discord caps a message at 2000 characters, so long answers are split into
+//! several messages. Without a limit one prompt can post a dozen of them, so each
+//! guild picks a maximum number of messages and what happens past it:
+//! - `Truncate` - post the first messages and a "…(response truncated)" notice
+//! - `Attach` - post a short preview and upload the full answer as `response.txt`
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::error::Error;
+
+/// Discord's message length limit (in characters).
+pub const DISCORD_MESSAGE_LIMIT: usize = 2000;
+
+/// Allowed range for `max_messages`.
+pub const MAX_RESPONSE_MESSAGES: u8 = 10;
+
+const TRUNCATED_NOTICE: &str = "\n…(response truncated)";
+const ATTACHED_NOTICE: &str = "\n…(full response attached)";
+
+/// What to do with a reply that needs more messages than allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LongResponseMode {
+    Truncate,
+    Attach,
+}
+
+impl LongResponseMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LongResponseMode::Truncate => "truncate",
+            LongResponseMode::Attach => "attach",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "truncate" => Some(LongResponseMode::Truncate),
+            "attach" => Some(LongResponseMode::Attach),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseLimits {
+    /// Maximum number of Discord messages one reply may use (1-10).
+    pub max_messages: u8,
+    pub mode: LongResponseMode,
+}
+
+impl Default for ResponseLimits {
+    fn default() -> Self {
+        Self {
+            max_messages: 3,
+            mode: LongResponseMode::Truncate,
+        }
+    }
+}
+
+/// How to post a reply once the limits are applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponsePlan {
+    /// Send these messages in order.
+    Messages(Vec<String>),
+    /// Send `preview` with `full_text` attached as a file.
+    Attachment { preview: String, full_text: String },
+}
+
+/// Split `answer` into Discord-sized messages, applying the guild's limits.
+pub fn plan_response(answer: &str, limits: ResponseLimits) -> ResponsePlan {
+    let chunks = split_chars(answer, DISCORD_MESSAGE_LIMIT);
+    let max_messages = limits.max_messages.clamp(1, MAX_RESPONSE_MESSAGES) as usize;
+
+    if chunks.len() <= max_messages {
+        return ResponsePlan::Messages(chunks);
+    }
+
+    match limits.mode {
+        LongResponseMode::Truncate => {
+            let mut messages: Vec<String> = chunks.into_iter().take(max_messages).collect();
+            if let Some(last) = messages.last_mut() {
+                *last = with_notice(last, TRUNCATED_NOTICE);
+            }
+            ResponsePlan::Messages(messages)
+        }
+        LongResponseMode::Attach => ResponsePlan::Attachment {
+            preview: with_notice(&chunks[0], ATTACHED_NOTICE),
+            full_text: answer.to_string(),
+        },
+    }
+}
+
+/// Split on character (not byte) boundaries.
+fn split_chars(text: &str, size: usize) -> Vec<String> {
+    text.chars()
+        .collect::<Vec<char>>()
+        .chunks(size)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+/// Append `notice`, cutting the text so the result still fits in one message.
+fn with_notice(text: &str, notice: &str) -> String {
+    let room = DISCORD_MESSAGE_LIMIT - notice.chars().count();
+    let mut result: String = text.chars().take(room).collect();
+    result.push_str(notice);
+    result
+}
+
+#[async_trait]
+pub trait ResponseLimitsStore: Send + Sync {
+    async fn get_limits(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<ResponseLimits>, Box<dyn Error + Send + Sync>>;
+    async fn save_limits(
+        &self,
+        guild_id: u64,
+        limits: ResponseLimits,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: ResponseLimitsStore + ?Sized> ResponseLimitsStore for Box<S> {
+    async fn get_limits(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<ResponseLimits>, Box<dyn Error + Send + Sync>> {
+        (**self).get_limits(guild_id).await
+    }
+
+    async fn save_limits(
+        &self,
+        guild_id: u64,
+        limits: ResponseLimits,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).save_limits(guild_id, limits).await
+    }
+}
+
+/// Looks up a guild's limits, caching them since they're read on every AI reply.
+pub struct ResponseLimitsService<S: ResponseLimitsStore> {
+    store: S,
+    cache: DashMap<u64, ResponseLimits>,
+}
+
+impl<S: ResponseLimitsStore> ResponseLimitsService<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            cache: DashMap::new(),
+        }
+    }
+
+    /// The guild's limits, or the defaults if it never configured any.
+    pub async fn get(&self, guild_id: u64) -> ResponseLimits {
+        if let Some(limits) = self.cache.get(&guild_id) {
+            return *limits;
+        }
+
+        let limits = match self.store.get_limits(guild_id).await {
+            Ok(limits) => limits.unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!(guild_id, error = %e, "Failed to load AI response limits");
+                return ResponseLimits::default();
+            }
+        };
+        self.cache.insert(guild_id, limits);
+        limits
+    }
+
+    pub async fn set(
+        &self,
+        guild_id: u64,
+        limits: ResponseLimits,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let limits = ResponseLimits {
+            max_messages: limits.max_messages.clamp(1, MAX_RESPONSE_MESSAGES),
+            ..limits
+        };
+        self.store.save_limits(guild_id, limits).await?;
+        self.cache.insert(guild_id, limits);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(max_messages: u8, mode: LongResponseMode) -> ResponseLimits {
+        ResponseLimits { max_messages, mode }
+    }
+
+    #[test]
+    fn test_short_answers_are_sent_as_is() {
+        let plan = plan_response("hello", limits(1, LongResponseMode::Truncate));
+        assert_eq!(plan, ResponsePlan::Messages(vec!["hello".to_string()]));
+    }
+
+    #[test]
+    fn test_long_answers_are_truncated() {
+        let answer = "é".repeat(DISCORD_MESSAGE_LIMIT * 4);
+        let ResponsePlan::Messages(messages) =
+            plan_response(&answer, limits(2, LongResponseMode::Truncate))
+        else {
+            panic!("expected messages");
+        };
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages
+            .iter()
+            .all(|m| m.chars().count() <= DISCORD_MESSAGE_LIMIT));
+        assert!(messages[1].ends_with(TRUNCATED_NOTICE));
+    }
+
+    #[test]
+    fn test_long_answers_can_be_attached() {
+        let answer = "a".repeat(DISCORD_MESSAGE_LIMIT * 3);
+        let ResponsePlan::Attachment { preview, full_text } =
+            plan_response(&answer, limits(2, LongResponseMode::Attach))
+        else {
+            panic!("expected attachment");
+        };
+
+        assert_eq!(full_text, answer);
+        assert_eq!(preview.chars().count(), DISCORD_MESSAGE_LIMIT);
+        assert!(preview.ends_with(ATTACHED_NOTICE));
+    }
+}
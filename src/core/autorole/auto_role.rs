@@ -0,0 +1,246 @@
+// Per-guild auto-role: a role handed to every member when they join
+// (`/config autorole set @role`), e.g. "Member" or "Unverified".
+//
+// Whether the role can actually be assigned depends on Discord state (the
+// bot's permissions and role position), so the Discord layer gathers those
+// facts and `decide_auto_role` makes the call. Keeping the decision pure makes
+// the rejoin and hierarchy cases easy to test.
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::error::Error;
+
+/// A guild's auto-role setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoRoleConfig {
+    pub role_id: u64,
+    pub enabled: bool,
+}
+
+/// Persistence for auto-role settings.
+#[async_trait]
+pub trait AutoRoleStore: Send + Sync {
+    async fn get_auto_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<AutoRoleConfig>, Box<dyn Error + Send + Sync>>;
+
+    async fn set_auto_role(
+        &self,
+        guild_id: u64,
+        config: AutoRoleConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: AutoRoleStore + ?Sized> AutoRoleStore for Box<S> {
+    async fn get_auto_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<AutoRoleConfig>, Box<dyn Error + Send + Sync>> {
+        (**self).get_auto_role(guild_id).await
+    }
+
+    async fn set_auto_role(
+        &self,
+        guild_id: u64,
+        config: AutoRoleConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).set_auto_role(guild_id, config).await
+    }
+}
+
+/// What the bot may do with roles in a guild.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BotRoleStanding {
+    /// MANAGE_ROLES or ADMINISTRATOR.
+    pub can_manage_roles: bool,
+    /// Position of the bot's highest role. Discord only lets the bot assign
+    /// roles strictly below it.
+    pub highest_position: u16,
+}
+
+/// Outcome of `decide_auto_role`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoRoleDecision {
+    Assign(u64),
+    /// No auto-role configured, or it is switched off.
+    Disabled,
+    /// The member already has the role, e.g. after rejoining.
+    AlreadyHasRole,
+    /// The configured role no longer exists.
+    RoleMissing,
+    /// The bot lacks the Manage Roles permission.
+    MissingPermission,
+    /// The role is at or above the bot's highest role.
+    RoleTooHigh,
+}
+
+/// Decide whether to give a joining member the auto-role.
+///
+/// `role_position` is the configured role's position, or `None` if the role
+/// was deleted.
+pub fn decide_auto_role(
+    config: Option<AutoRoleConfig>,
+    member_roles: &[u64],
+    role_position: Option<u16>,
+    bot: BotRoleStanding,
+) -> AutoRoleDecision {
+    let Some(config) = config.filter(|config| config.enabled) else {
+        return AutoRoleDecision::Disabled;
+    };
+    if member_roles.contains(&config.role_id) {
+        return AutoRoleDecision::AlreadyHasRole;
+    }
+    let Some(role_position) = role_position else {
+        return AutoRoleDecision::RoleMissing;
+    };
+    if !bot.can_manage_roles {
+        return AutoRoleDecision::MissingPermission;
+    }
+    if role_position >= bot.highest_position {
+        return AutoRoleDecision::RoleTooHigh;
+    }
+    AutoRoleDecision::Assign(config.role_id)
+}
+
+/// Auto-role settings with a per-guild cache, since every join reads them.
+pub struct AutoRoleService<S: AutoRoleStore> {
+    store: S,
+    cache: DashMap<u64, Option<AutoRoleConfig>>,
+}
+
+impl<S: AutoRoleStore> AutoRoleService<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            cache: DashMap::new(),
+        }
+    }
+
+    /// The guild's auto-role setting, if one was ever configured.
+    pub async fn auto_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<AutoRoleConfig>, Box<dyn Error + Send + Sync>> {
+        if let Some(config) = self.cache.get(&guild_id) {
+            return Ok(*config);
+        }
+
+        let config = self.store.get_auto_role(guild_id).await?;
+        self.cache.insert(guild_id, config);
+        Ok(config)
+    }
+
+    /// Set the auto-role and switch it on.
+    pub async fn set_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.save(
+            guild_id,
+            AutoRoleConfig {
+                role_id,
+                enabled: true,
+            },
+        )
+        .await
+    }
+
+    /// Switch the auto-role on or off. Returns the updated setting, or `None`
+    /// if no role has been set yet.
+    pub async fn set_enabled(
+        &self,
+        guild_id: u64,
+        enabled: bool,
+    ) -> Result<Option<AutoRoleConfig>, Box<dyn Error + Send + Sync>> {
+        let Some(mut config) = self.auto_role(guild_id).await? else {
+            return Ok(None);
+        };
+        config.enabled = enabled;
+        self.save(guild_id, config).await?;
+        Ok(Some(config))
+    }
+
+    async fn save(
+        &self,
+        guild_id: u64,
+        config: AutoRoleConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.store.set_auto_role(guild_id, config).await?;
+        self.cache.insert(guild_id, Some(config));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::autorole::InMemoryAutoRoleStore;
+
+    const BOT: BotRoleStanding = BotRoleStanding {
+        can_manage_roles: true,
+        highest_position: 10,
+    };
+
+    #[test]
+    fn test_auto_role_decision() {
+        let config = Some(AutoRoleConfig {
+            role_id: 42,
+            enabled: true,
+        });
+
+        assert_eq!(
+            decide_auto_role(config, &[], Some(3), BOT),
+            AutoRoleDecision::Assign(42)
+        );
+        // Rejoining members keep their role and aren't re-assigned
+        assert_eq!(
+            decide_auto_role(config, &[7, 42], Some(3), BOT),
+            AutoRoleDecision::AlreadyHasRole
+        );
+        assert_eq!(
+            decide_auto_role(None, &[], Some(3), BOT),
+            AutoRoleDecision::Disabled
+        );
+        let disabled = config.map(|c| AutoRoleConfig {
+            enabled: false,
+            ..c
+        });
+        assert_eq!(
+            decide_auto_role(disabled, &[], Some(3), BOT),
+            AutoRoleDecision::Disabled
+        );
+        assert_eq!(
+            decide_auto_role(config, &[], None, BOT),
+            AutoRoleDecision::RoleMissing
+        );
+        let no_permission = BotRoleStanding {
+            can_manage_roles: false,
+            ..BOT
+        };
+        assert_eq!(
+            decide_auto_role(config, &[], Some(3), no_permission),
+            AutoRoleDecision::MissingPermission
+        );
+        // Equal position is also out of reach
+        assert_eq!(
+            decide_auto_role(config, &[], Some(10), BOT),
+            AutoRoleDecision::RoleTooHigh
+        );
+    }
+
+    #[tokio::test]
+    async fn test_toggle_requires_a_role() {
+        let service = AutoRoleService::new(InMemoryAutoRoleStore::new());
+
+        assert_eq!(service.set_enabled(1, true).await.unwrap(), None);
+
+        service.set_role(1, 42).await.unwrap();
+        let config = service.set_enabled(1, false).await.unwrap().unwrap();
+        assert_eq!(config.role_id, 42);
+        assert!(!service.auto_role(1).await.unwrap().unwrap().enabled);
+        assert_eq!(service.auto_role(2).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,455 @@
+// Member birthdays (`/birthday set MM-DD`) and the daily announcement.
+//
+// Birthdays are stored per guild as a month and day only, so nothing hints at
+// anyone's age. Once a guild picks a birthday channel (`/config birthdays set`),
+// a background task in the Discord layer asks `due_celebration` about the
+// guild every minute with the guild's local date. The first time it sees a new
+// date, it gets that day's birthdays to announce, plus the members given the
+// optional birthday role last time, who lose it again. The last celebrated
+// date and the role's holders are stored, so restarts don't announce twice and
+// birthdays changed in between don't leave the role behind.
+//
+// The local date uses the guild's timezone from `/leveling daily_reset`, so
+// birthdays start at the same midnight as the daily rewards.
+//
+// Members born on Feb 29 are celebrated on Mar 1 in non-leap years.
+
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate};
+use std::error::Error;
+use std::fmt;
+
+/// A birthday without a year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Birthday {
+    month: u32,
+    day: u32,
+}
+
+impl Birthday {
+    /// `None` unless the day exists in the month (Feb 29 included).
+    pub fn new(month: u32, day: u32) -> Option<Self> {
+        // 2000 was a leap year, so Feb 29 counts as a real date
+        NaiveDate::from_ymd_opt(2000, month, day).map(|_| Self { month, day })
+    }
+
+    /// Parse `MM-DD`, e.g. `03-14` (leading zeros optional).
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("`{}` isn't a date. Use MM-DD, e.g. 03-14.", text.trim());
+        let (month, day) = text.trim().split_once('-').ok_or_else(invalid)?;
+        let month: u32 = month.trim().parse().map_err(|_| invalid())?;
+        let day: u32 = day.trim().parse().map_err(|_| invalid())?;
+        Self::new(month, day).ok_or_else(invalid)
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Whether the birthday is celebrated on `date`.
+    pub fn falls_on(&self, date: NaiveDate) -> bool {
+        if (self.month, self.day) == (date.month(), date.day()) {
+            return true;
+        }
+        let leap_year = NaiveDate::from_ymd_opt(date.year(), 2, 29).is_some();
+        (self.month, self.day) == (2, 29) && !leap_year && (date.month(), date.day()) == (3, 1)
+    }
+}
+
+/// "March 14"
+impl fmt::Display for Birthday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = NaiveDate::from_ymd_opt(2000, self.month, self.day).unwrap_or_default();
+        write!(f, "{}", date.format("%B %-d"))
+    }
+}
+
+/// The users in `birthdays` celebrated on `date`, in ascending ID order.
+pub fn birthdays_on(date: NaiveDate, birthdays: &[(u64, Birthday)]) -> Vec<u64> {
+    let mut users: Vec<u64> = birthdays
+        .iter()
+        .filter(|(_, birthday)| birthday.falls_on(date))
+        .map(|&(user_id, _)| user_id)
+        .collect();
+    users.sort_unstable();
+    users
+}
+
+/// A guild's birthday announcement setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BirthdayConfig {
+    pub channel_id: u64,
+    /// Role given to members for their birthday, if any.
+    pub role_id: Option<u64>,
+    /// The last local date whose birthdays were announced.
+    pub last_celebrated: Option<NaiveDate>,
+    /// Members given the birthday role on `last_celebrated`.
+    pub role_holders: Vec<u64>,
+}
+
+/// What to do for a guild on a new day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Celebration {
+    pub date: NaiveDate,
+    pub channel_id: u64,
+    pub role_id: Option<u64>,
+    /// Members whose birthday it is.
+    pub user_ids: Vec<u64>,
+    /// Members given the birthday role on the last celebrated day whose
+    /// birthday is over, who lose it.
+    pub ended_user_ids: Vec<u64>,
+}
+
+/// Persistence for birthdays and each guild's announcement setting.
+#[async_trait]
+pub trait BirthdayStore: Send + Sync {
+    async fn get_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<Option<Birthday>, Box<dyn Error + Send + Sync>>;
+
+    async fn set_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        birthday: Birthday,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    async fn remove_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    /// Every birthday stored in a guild, as (user_id, birthday).
+    async fn guild_birthdays(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<(u64, Birthday)>, Box<dyn Error + Send + Sync>>;
+
+    async fn get_birthday_config(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<BirthdayConfig>, Box<dyn Error + Send + Sync>>;
+
+    async fn set_birthday_config(
+        &self,
+        guild_id: u64,
+        config: &BirthdayConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    async fn remove_birthday_config(
+        &self,
+        guild_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    /// Guilds with birthday announcements on.
+    async fn birthday_guilds(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: BirthdayStore + ?Sized> BirthdayStore for Box<S> {
+    async fn get_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<Option<Birthday>, Box<dyn Error + Send + Sync>> {
+        (**self).get_birthday(guild_id, user_id).await
+    }
+
+    async fn set_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        birthday: Birthday,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).set_birthday(guild_id, user_id, birthday).await
+    }
+
+    async fn remove_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).remove_birthday(guild_id, user_id).await
+    }
+
+    async fn guild_birthdays(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<(u64, Birthday)>, Box<dyn Error + Send + Sync>> {
+        (**self).guild_birthdays(guild_id).await
+    }
+
+    async fn get_birthday_config(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<BirthdayConfig>, Box<dyn Error + Send + Sync>> {
+        (**self).get_birthday_config(guild_id).await
+    }
+
+    async fn set_birthday_config(
+        &self,
+        guild_id: u64,
+        config: &BirthdayConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).set_birthday_config(guild_id, config).await
+    }
+
+    async fn remove_birthday_config(
+        &self,
+        guild_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).remove_birthday_config(guild_id).await
+    }
+
+    async fn birthday_guilds(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        (**self).birthday_guilds().await
+    }
+}
+
+pub struct BirthdayService<S: BirthdayStore> {
+    store: S,
+}
+
+impl<S: BirthdayStore> BirthdayService<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    pub async fn birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<Option<Birthday>, Box<dyn Error + Send + Sync>> {
+        self.store.get_birthday(guild_id, user_id).await
+    }
+
+    pub async fn set_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        birthday: Birthday,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.store.set_birthday(guild_id, user_id, birthday).await
+    }
+
+    /// Forget a member's birthday. Returns whether one was stored.
+    pub async fn remove_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let had_birthday = self.birthday(guild_id, user_id).await?.is_some();
+        self.store.remove_birthday(guild_id, user_id).await?;
+        Ok(had_birthday)
+    }
+
+    /// The guild's announcement setting, if announcements are on.
+    pub async fn config(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<BirthdayConfig>, Box<dyn Error + Send + Sync>> {
+        self.store.get_birthday_config(guild_id).await
+    }
+
+    /// Announce birthdays in `channel_id`, optionally giving `role_id` for the
+    /// day. Keeps the last celebrated date, so changing the channel doesn't
+    /// announce today's birthdays again, and who holds the role.
+    pub async fn enable(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        role_id: Option<u64>,
+    ) -> Result<BirthdayConfig, Box<dyn Error + Send + Sync>> {
+        let (last_celebrated, role_holders) = self
+            .config(guild_id)
+            .await?
+            .map(|config| (config.last_celebrated, config.role_holders))
+            .unwrap_or_default();
+        let config = BirthdayConfig {
+            channel_id,
+            role_id,
+            last_celebrated,
+            role_holders,
+        };
+        self.store.set_birthday_config(guild_id, &config).await?;
+        Ok(config)
+    }
+
+    /// Stop announcing birthdays. Returns whether announcements were on.
+    pub async fn disable(&self, guild_id: u64) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let was_enabled = self.config(guild_id).await?.is_some();
+        self.store.remove_birthday_config(guild_id).await?;
+        Ok(was_enabled)
+    }
+
+    /// Guilds with birthday announcements on.
+    pub async fn guilds(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        self.store.birthday_guilds().await
+    }
+
+    /// The celebration for `today` (the guild's local date), or `None` if
+    /// announcements are off or `today` was already celebrated. Call
+    /// `mark_celebrated` once it has been carried out.
+    pub async fn due_celebration(
+        &self,
+        guild_id: u64,
+        today: NaiveDate,
+    ) -> Result<Option<Celebration>, Box<dyn Error + Send + Sync>> {
+        let Some(config) = self.config(guild_id).await? else {
+            return Ok(None);
+        };
+        if config.last_celebrated.is_some_and(|last| last >= today) {
+            return Ok(None);
+        }
+
+        let birthdays = self.store.guild_birthdays(guild_id).await?;
+        let user_ids = birthdays_on(today, &birthdays);
+        let ended_user_ids = config
+            .role_holders
+            .into_iter()
+            .filter(|user_id| !user_ids.contains(user_id))
+            .collect();
+
+        Ok(Some(Celebration {
+            date: today,
+            channel_id: config.channel_id,
+            role_id: config.role_id,
+            user_ids,
+            ended_user_ids,
+        }))
+    }
+
+    /// Record that `celebration` was carried out in the guild, including who
+    /// now holds the birthday role.
+    pub async fn mark_celebrated(
+        &self,
+        guild_id: u64,
+        celebration: &Celebration,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(mut config) = self.config(guild_id).await? else {
+            return Ok(());
+        };
+        config.last_celebrated = Some(celebration.date);
+        config.role_holders = match celebration.role_id {
+            Some(_) => celebration.user_ids.clone(),
+            None => Vec::new(),
+        };
+        self.store.set_birthday_config(guild_id, &config).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::birthdays::InMemoryBirthdayStore;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn birthday(month: u32, day: u32) -> Birthday {
+        Birthday::new(month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_birthday() {
+        assert_eq!(Birthday::parse("03-14"), Ok(birthday(3, 14)));
+        assert_eq!(Birthday::parse(" 3-7 "), Ok(birthday(3, 7)));
+        assert_eq!(Birthday::parse("02-29"), Ok(birthday(2, 29)));
+        assert!(Birthday::parse("02-30").is_err());
+        assert!(Birthday::parse("13-01").is_err());
+        assert!(Birthday::parse("2000-03-14").is_err());
+        assert!(Birthday::parse("March 14").is_err());
+        assert_eq!(birthday(3, 14).to_string(), "March 14");
+    }
+
+    #[test]
+    fn test_whose_birthday_is_today() {
+        let stored = [
+            (30, birthday(3, 1)),
+            (10, birthday(2, 29)),
+            (20, birthday(2, 28)),
+            (40, birthday(12, 25)),
+        ];
+
+        assert_eq!(birthdays_on(date(2026, 12, 25), &stored), vec![40]);
+        assert_eq!(birthdays_on(date(2026, 2, 28), &stored), vec![20]);
+        assert!(birthdays_on(date(2026, 7, 4), &stored).is_empty());
+
+        // Feb 29 birthdays move to Mar 1 in non-leap years...
+        assert_eq!(birthdays_on(date(2026, 3, 1), &stored), vec![10, 30]);
+        assert_eq!(birthdays_on(date(2100, 3, 1), &stored), vec![10, 30]);
+        // ...but not in leap years
+        assert_eq!(birthdays_on(date(2028, 2, 29), &stored), vec![10]);
+        assert_eq!(birthdays_on(date(2028, 3, 1), &stored), vec![30]);
+    }
+
+    #[tokio::test]
+    async fn test_each_day_is_celebrated_once() {
+        let service = BirthdayService::new(InMemoryBirthdayStore::new());
+        service.set_birthday(1, 10, birthday(6, 1)).await.unwrap();
+        service.set_birthday(1, 11, birthday(6, 2)).await.unwrap();
+        service.set_birthday(2, 12, birthday(6, 1)).await.unwrap();
+
+        // Announcements are off until a channel is set
+        assert_eq!(
+            service.due_celebration(1, date(2026, 6, 1)).await.unwrap(),
+            None
+        );
+        service.enable(1, 99, Some(5)).await.unwrap();
+
+        let first = service
+            .due_celebration(1, date(2026, 6, 1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.user_ids, vec![10]);
+        assert!(first.ended_user_ids.is_empty());
+        assert_eq!((first.channel_id, first.role_id), (99, Some(5)));
+        service.mark_celebrated(1, &first).await.unwrap();
+        assert_eq!(
+            service.due_celebration(1, date(2026, 6, 1)).await.unwrap(),
+            None
+        );
+
+        // Moving the channel doesn't repeat today's announcement
+        service.enable(1, 98, Some(5)).await.unwrap();
+        assert_eq!(
+            service.due_celebration(1, date(2026, 6, 1)).await.unwrap(),
+            None
+        );
+
+        // The next day, yesterday's birthday is over
+        let second = service
+            .due_celebration(1, date(2026, 6, 2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.user_ids, vec![11]);
+        assert_eq!(second.ended_user_ids, vec![10]);
+        // The role comes off whoever got it, even if their birthday changed since
+        service.set_birthday(1, 10, birthday(9, 9)).await.unwrap();
+        let second = service
+            .due_celebration(1, date(2026, 6, 2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.ended_user_ids, vec![10]);
+        assert_eq!(service.guilds().await.unwrap(), vec![1]);
+
+        assert!(service.remove_birthday(1, 11).await.unwrap());
+        assert!(!service.remove_birthday(1, 11).await.unwrap());
+        assert!(service.disable(1).await.unwrap());
+        assert_eq!(
+            service.due_celebration(1, date(2026, 6, 3)).await.unwrap(),
+            None
+        );
+    }
+}
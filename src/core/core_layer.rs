@@ -13,14 +13,45 @@ pub mod timezones;
 #[path = "logging/mod.rs"]
 pub mod logging;
 
+#[cfg(feature = "github")]
 #[path = "github/github_service.rs"]
 pub mod github;
 
+#[cfg(feature = "ai")]
 #[path = "ai/mod.rs"]
 pub mod ai;
 
+#[cfg(feature = "economy")]
 #[path = "economy/mod.rs"]
 pub mod economy;
 
+#[cfg(feature = "moderation")]
 #[path = "moderation/mod.rs"]
 pub mod moderation;
+
+#[path = "i18n/localizer.rs"]
+pub mod i18n;
+
+#[path = "util/mod.rs"]
+pub mod util;
+
+#[path = "access/manager_roles.rs"]
+pub mod access;
+
+#[path = "autorole/auto_role.rs"]
+pub mod autorole;
+
+#[path = "reactionroles/reaction_roles.rs"]
+pub mod reactionroles;
+
+#[path = "welcome/mod.rs"]
+pub mod welcome;
+
+#[path = "feedback/feedback_service.rs"]
+pub mod feedback;
+
+#[path = "birthdays/birthday_service.rs"]
+pub mod birthdays;
+
+#[path = "metrics/app_metrics.rs"]
+pub mod metrics;
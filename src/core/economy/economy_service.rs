@@ -4,10 +4,15 @@
 // Following the same pattern as the leveling system, this is platform-agnostic
 // with no Discord-specific code.
 
+use crate::core::util::ErrorSource;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::sync::Mutex;
+use thiserror::Error;
 
 // ============================================================================
 // DOMAIN MODELS
@@ -35,6 +40,41 @@ pub struct Transaction {
     pub timestamp: DateTime<Utc>,
 }
 
+/// What the coins leaderboard ranks wallets by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WealthRanking {
+    /// Coins held right now.
+    Balance,
+    /// Coins earned over time, regardless of what was spent.
+    TotalEarned,
+}
+
+impl WealthRanking {
+    /// The wallet value this ranking sorts by.
+    pub fn value(self, wallet: &Wallet) -> i64 {
+        match self {
+            WealthRanking::Balance => wallet.balance,
+            WealthRanking::TotalEarned => wallet.total_earned,
+        }
+    }
+}
+
+/// Leaderboard ranks for wallets already sorted highest first. Equal values
+/// share a rank and the next rank skips ahead (1, 2, 2, 4).
+pub fn wealth_ranks(wallets: &[Wallet], ranking: WealthRanking) -> Vec<usize> {
+    let mut ranks: Vec<usize> = Vec::with_capacity(wallets.len());
+    for (index, wallet) in wallets.iter().enumerate() {
+        let rank = match index.checked_sub(1) {
+            Some(previous) if ranking.value(&wallets[previous]) == ranking.value(wallet) => {
+                ranks[previous]
+            }
+            _ => index + 1,
+        };
+        ranks.push(rank);
+    }
+    ranks
+}
+
 /// Result of a daily claim attempt.
 #[derive(Debug, Clone)]
 pub struct DailyClaimResult {
@@ -44,47 +84,65 @@ pub struct DailyClaimResult {
     pub next_claim_time: DateTime<Utc>,
 }
 
+/// A side of the coin in `/coinflip`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoinSide {
+    Heads,
+    Tails,
+}
+
+/// Result of a coinflip bet.
+#[derive(Debug, Clone)]
+pub struct CoinflipResult {
+    pub outcome: CoinSide,
+    pub won: bool,
+    /// Coins paid back: twice the bet on a win, otherwise 0.
+    pub payout: i64,
+    pub new_balance: i64,
+}
+
 // ============================================================================
 // ERRORS
 // ============================================================================
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Error)]
 pub enum EconomyError {
     #[allow(dead_code)]
-    InsufficientFunds {
-        required: i64,
-        available: i64,
-    },
+    #[error("Insufficient funds: need {required} coins, but only have {available}")]
+    InsufficientFunds { required: i64, available: i64 },
     #[allow(dead_code)]
-    OnCooldown {
-        available_at: DateTime<Utc>,
-    },
-    StoreError(String),
+    #[error("On cooldown until {available_at}")]
+    OnCooldown { available_at: DateTime<Utc> },
+    /// The underlying error (e.g. from sqlx), when there is one, is the
+    /// `source` for the logs.
+    #[error("Store error: {0}")]
+    StoreError(String, #[source] Option<ErrorSource>),
+    #[error("Invalid economy config: {0}")]
+    InvalidConfig(String),
+    /// The member has none of the item they tried to use.
+    #[error("You don't have that item")]
+    ItemNotOwned,
+    /// The item works on its own and can't be used by hand.
+    #[error("That item can't be used directly")]
+    ItemNotUsable,
+    /// A transfer asked for more of an item than the sender has.
+    #[error("Not enough items: tried to give {requested}, but only have {available}")]
+    NotEnoughItems { requested: u32, available: i64 },
+    /// A gift that doesn't make sense (to yourself, or zero items).
+    #[error("Invalid transfer: {0}")]
+    InvalidTransfer(&'static str),
+    /// A bet below 1 or above the configured maximum.
+    #[error("Bets must be between 1 and {max_bet} coins")]
+    InvalidBet { max_bet: i64 },
 }
 
-impl fmt::Display for EconomyError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            EconomyError::InsufficientFunds {
-                required,
-                available,
-            } => {
-                write!(
-                    f,
-                    "Insufficient funds: need {} coins, but only have {}",
-                    required, available
-                )
-            }
-            EconomyError::OnCooldown { available_at } => {
-                write!(f, "On cooldown until {}", available_at)
-            }
-            EconomyError::StoreError(msg) => write!(f, "Store error: {}", msg),
-        }
+impl EconomyError {
+    /// A store failure caused by `source`, which stays attached for the logs.
+    pub fn store<E: std::error::Error + Send + Sync + 'static>(source: E) -> Self {
+        EconomyError::StoreError(source.to_string(), Some(Box::new(source)))
     }
 }
 
-impl std::error::Error for EconomyError {}
-
 // ============================================================================
 // STORAGE TRAIT
 // ============================================================================
@@ -129,6 +187,75 @@ pub trait CoinStore: Send + Sync {
         guild_id: u64,
         limit: usize,
     ) -> Result<Vec<Transaction>, EconomyError>;
+
+    /// Wallets in a guild with a positive `ranking` value, highest first and
+    /// ties broken by user ID.
+    async fn get_top_wallets(
+        &self,
+        guild_id: u64,
+        ranking: WealthRanking,
+        limit: usize,
+    ) -> Result<Vec<Wallet>, EconomyError>;
+}
+
+#[async_trait]
+impl<S: CoinStore + ?Sized> CoinStore for Box<S> {
+    async fn get_wallet(&self, user_id: u64, guild_id: u64) -> Result<Wallet, EconomyError> {
+        (**self).get_wallet(user_id, guild_id).await
+    }
+
+    async fn update_balance(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        new_balance: i64,
+    ) -> Result<(), EconomyError> {
+        (**self)
+            .update_balance(user_id, guild_id, new_balance)
+            .await
+    }
+
+    async fn update_last_daily(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), EconomyError> {
+        (**self)
+            .update_last_daily(user_id, guild_id, timestamp)
+            .await
+    }
+
+    async fn add_coins(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        amount: i64,
+    ) -> Result<(), EconomyError> {
+        (**self).add_coins(user_id, guild_id, amount).await
+    }
+
+    async fn log_transaction(&self, transaction: Transaction) -> Result<(), EconomyError> {
+        (**self).log_transaction(transaction).await
+    }
+
+    async fn get_transactions(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, EconomyError> {
+        (**self).get_transactions(user_id, guild_id, limit).await
+    }
+
+    async fn get_top_wallets(
+        &self,
+        guild_id: u64,
+        ranking: WealthRanking,
+        limit: usize,
+    ) -> Result<Vec<Wallet>, EconomyError> {
+        (**self).get_top_wallets(guild_id, ranking, limit).await
+    }
 }
 
 // ============================================================================
@@ -152,6 +279,37 @@ pub struct EconomyConfig {
 
     /// Maximum coins to award on random message reward.
     pub message_reward_max: i64,
+
+    /// Whether chatting can earn coins at all.
+    pub message_rewards_enabled: bool,
+
+    /// Post a short message in the channel when a message earns coins.
+    pub announce_message_rewards: bool,
+
+    /// Largest bet allowed on a single coinflip.
+    pub coinflip_max_bet: i64,
+
+    /// Seconds a member has to wait between coinflips.
+    pub coinflip_cooldown_secs: i64,
+}
+
+impl EconomyConfig {
+    /// Check the message reward settings before they reach the RNG.
+    pub fn validate(&self) -> Result<(), EconomyError> {
+        if !(0.0..=1.0).contains(&self.message_reward_chance) {
+            return Err(EconomyError::InvalidConfig(format!(
+                "message reward chance ({}) must be between 0 and 1",
+                self.message_reward_chance
+            )));
+        }
+        if self.message_reward_min < 1 || self.message_reward_max < self.message_reward_min {
+            return Err(EconomyError::InvalidConfig(format!(
+                "message reward range {}..={} must be positive and not empty",
+                self.message_reward_min, self.message_reward_max
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl Default for EconomyConfig {
@@ -162,6 +320,10 @@ impl Default for EconomyConfig {
             message_reward_chance: 0.05, // 5%
             message_reward_min: 1,
             message_reward_max: 5,
+            message_rewards_enabled: true,
+            announce_message_rewards: false,
+            coinflip_max_bet: 500,
+            coinflip_cooldown_secs: 10,
         }
     }
 }
@@ -176,21 +338,33 @@ impl Default for EconomyConfig {
 pub struct EconomyService<S: CoinStore> {
     store: S,
     config: EconomyConfig,
+    /// Seeded once from the OS. `StdRng` is `Send`, unlike `thread_rng`, and
+    /// the lock is never held across an await.
+    rng: Mutex<StdRng>,
+    /// When each (user, guild) last flipped a coin.
+    last_coinflip: DashMap<(u64, u64), DateTime<Utc>>,
 }
 
 impl<S: CoinStore> EconomyService<S> {
     /// Create a new economy service with the given store.
+    #[allow(dead_code)]
     pub fn new(store: S) -> Self {
+        Self::new_with_config(store, EconomyConfig::default())
+    }
+
+    /// Create a new economy service with custom configuration.
+    pub fn new_with_config(store: S, config: EconomyConfig) -> Self {
         Self {
             store,
-            config: EconomyConfig::default(),
+            config,
+            rng: Mutex::new(StdRng::from_entropy()),
+            last_coinflip: DashMap::new(),
         }
     }
 
-    /// Create a new economy service with custom configuration.
-    #[allow(dead_code)]
-    pub fn new_with_config(store: S, config: EconomyConfig) -> Self {
-        Self { store, config }
+    /// The settings this service was created with.
+    pub fn config(&self) -> &EconomyConfig {
+        &self.config
     }
 
     /// Get a user's current balance.
@@ -215,6 +389,7 @@ impl<S: CoinStore> EconomyService<S> {
         if amount <= 0 {
             return Err(EconomyError::StoreError(
                 "Amount must be positive".to_string(),
+                None,
             ));
         }
 
@@ -277,43 +452,118 @@ impl<S: CoinStore> EconomyService<S> {
 
     /// Try to award random coins for a message.
     ///
-    /// Returns Some(amount) if coins were awarded, None otherwise.
+    /// Returns Some(amount) if coins were awarded, None otherwise (always
+    /// None when message rewards are disabled).
     pub async fn try_random_message_reward(
         &self,
         user_id: u64,
         guild_id: u64,
     ) -> Result<Option<i64>, EconomyError> {
-        // Use a Send-safe rng instead of thread_rng
-        use rand::rngs::StdRng;
-        use rand::{Rng, SeedableRng};
-        use std::time::SystemTime;
-
-        // Create a seed from system time and user/guild IDs for variety
-        let seed = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64
-            ^ user_id
-            ^ guild_id;
+        if !self.config.message_rewards_enabled {
+            return Ok(None);
+        }
 
-        let mut rng = StdRng::seed_from_u64(seed);
+        let Some(amount) = self.roll_message_reward() else {
+            return Ok(None);
+        };
 
+        self.award_coins(
+            user_id,
+            guild_id,
+            amount,
+            "Random message reward".to_string(),
+        )
+        .await?;
+
+        Ok(Some(amount))
+    }
+
+    /// Roll for a message reward: `message_reward_chance` to win an amount
+    /// between min and max (inclusive).
+    fn roll_message_reward(&self) -> Option<i64> {
+        let mut rng = self
+            .rng
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         if rng.gen::<f64>() < self.config.message_reward_chance {
-            // Award random amount between min and max
-            let amount =
-                rng.gen_range(self.config.message_reward_min..=self.config.message_reward_max);
+            Some(rng.gen_range(self.config.message_reward_min..=self.config.message_reward_max))
+        } else {
+            None
+        }
+    }
 
-            self.award_coins(
-                user_id,
-                guild_id,
-                amount,
-                "Random message reward".to_string(),
-            )
-            .await?;
+    /// Bet coins on a coin flip: the bet is taken first, and twice the bet is
+    /// paid back if `call` comes up. A crash between the two legs can only
+    /// lose the bet, never pay out twice.
+    pub async fn coinflip(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        bet: i64,
+        call: CoinSide,
+    ) -> Result<CoinflipResult, EconomyError> {
+        if !(1..=self.config.coinflip_max_bet).contains(&bet) {
+            return Err(EconomyError::InvalidBet {
+                max_bet: self.config.coinflip_max_bet,
+            });
+        }
+
+        // Claim the cooldown slot before any await so two flips can't race
+        let now = Utc::now();
+        let previous = {
+            let mut last = self.last_coinflip.entry((user_id, guild_id)).or_insert(now);
+            let previous = *last;
+            let available_at = previous + Duration::seconds(self.config.coinflip_cooldown_secs);
+            if previous != now && now < available_at {
+                return Err(EconomyError::OnCooldown { available_at });
+            }
+            *last = now;
+            previous
+        };
+
+        let mut new_balance = match self
+            .deduct_coins_for_purchase(user_id, guild_id, bet, "Coinflip bet".to_string())
+            .await
+        {
+            Ok(balance) => balance,
+            Err(e) => {
+                // Nothing was bet, so don't hold the cooldown against them
+                if previous == now {
+                    self.last_coinflip.remove(&(user_id, guild_id));
+                } else {
+                    self.last_coinflip.insert((user_id, guild_id), previous);
+                }
+                return Err(e);
+            }
+        };
 
-            Ok(Some(amount))
+        let outcome = self.flip_coin();
+        let won = outcome == call;
+        let payout = if won { bet * 2 } else { 0 };
+        if won {
+            new_balance = self
+                .award_coins(user_id, guild_id, payout, "Coinflip win".to_string())
+                .await?;
+        }
+
+        Ok(CoinflipResult {
+            outcome,
+            won,
+            payout,
+            new_balance,
+        })
+    }
+
+    /// A fair coin.
+    fn flip_coin(&self) -> CoinSide {
+        let mut rng = self
+            .rng
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if rng.gen_bool(0.5) {
+            CoinSide::Heads
         } else {
-            Ok(None)
+            CoinSide::Tails
         }
     }
 
@@ -327,6 +577,16 @@ impl<S: CoinStore> EconomyService<S> {
         self.store.get_transactions(user_id, guild_id, limit).await
     }
 
+    /// The richest wallets in a guild, for the coins leaderboard.
+    pub async fn get_richest(
+        &self,
+        guild_id: u64,
+        ranking: WealthRanking,
+        limit: usize,
+    ) -> Result<Vec<Wallet>, EconomyError> {
+        self.store.get_top_wallets(guild_id, ranking, limit).await
+    }
+
     /// Purchase a shop item with coins.
     ///
     /// Deducts the item price from the user's balance and returns the new balance.
@@ -341,6 +601,7 @@ impl<S: CoinStore> EconomyService<S> {
         if amount <= 0 {
             return Err(EconomyError::StoreError(
                 "Amount must be positive".to_string(),
+                None,
             ));
         }
 
@@ -496,6 +757,23 @@ mod tests {
                 .collect();
             Ok(filtered)
         }
+
+        async fn get_top_wallets(
+            &self,
+            guild_id: u64,
+            ranking: WealthRanking,
+            limit: usize,
+        ) -> Result<Vec<Wallet>, EconomyError> {
+            let wallets = self.wallets.lock().unwrap();
+            let mut top: Vec<Wallet> = wallets
+                .values()
+                .filter(|w| w.guild_id == guild_id && ranking.value(w) > 0)
+                .cloned()
+                .collect();
+            top.sort_by_key(|w| (std::cmp::Reverse(ranking.value(w)), w.user_id));
+            top.truncate(limit);
+            Ok(top)
+        }
     }
 
     #[tokio::test]
@@ -563,4 +841,178 @@ mod tests {
         assert_eq!(transactions[0].amount, 20); // Most recent first
         assert_eq!(transactions[1].amount, 10);
     }
+
+    #[tokio::test]
+    async fn test_richest_ranks_ties_together() {
+        let service = EconomyService::new(InMemoryCoinStore::new());
+        for (user_id, coins) in [(1, 50), (2, 80), (3, 50), (4, 10)] {
+            service
+                .award_coins(user_id, 1, coins, "Test".to_string())
+                .await
+                .unwrap();
+        }
+        service
+            .deduct_coins_for_purchase(2, 1, 75, "Purchase".to_string())
+            .await
+            .unwrap();
+        // Other guilds and empty wallets are left out
+        service
+            .award_coins(5, 2, 500, "Test".to_string())
+            .await
+            .unwrap();
+        service.get_balance(6, 1).await.unwrap();
+
+        let richest = service
+            .get_richest(1, WealthRanking::Balance, 10)
+            .await
+            .unwrap();
+        let users: Vec<u64> = richest.iter().map(|w| w.user_id).collect();
+        assert_eq!(users, vec![1, 3, 4, 2]);
+        assert_eq!(
+            wealth_ranks(&richest, WealthRanking::Balance),
+            vec![1, 1, 3, 4]
+        );
+
+        let earned = service
+            .get_richest(1, WealthRanking::TotalEarned, 2)
+            .await
+            .unwrap();
+        let users: Vec<u64> = earned.iter().map(|w| w.user_id).collect();
+        assert_eq!(users, vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_random_message_reward_respects_config() {
+        let always = EconomyConfig {
+            message_reward_chance: 1.0,
+            message_reward_min: 2,
+            message_reward_max: 4,
+            ..EconomyConfig::default()
+        };
+        assert!(always.validate().is_ok());
+        let service = EconomyService::new_with_config(InMemoryCoinStore::new(), always.clone());
+        let amount = service.try_random_message_reward(1, 1).await.unwrap();
+        assert!(matches!(amount, Some(2..=4)));
+        assert_eq!(service.get_balance(1, 1).await.unwrap(), amount.unwrap());
+
+        let disabled = EconomyConfig {
+            message_rewards_enabled: false,
+            ..always
+        };
+        let service = EconomyService::new_with_config(InMemoryCoinStore::new(), disabled);
+        assert_eq!(service.try_random_message_reward(1, 1).await.unwrap(), None);
+
+        let empty_range = EconomyConfig {
+            message_reward_min: 5,
+            message_reward_max: 1,
+            ..EconomyConfig::default()
+        };
+        assert!(empty_range.validate().is_err());
+    }
+
+    #[test]
+    fn test_message_reward_rate_matches_configured_chance() {
+        let config = EconomyConfig {
+            message_reward_chance: 0.25,
+            message_reward_min: 3,
+            message_reward_max: 7,
+            ..EconomyConfig::default()
+        };
+        let service = EconomyService::new_with_config(InMemoryCoinStore::new(), config);
+
+        const ROLLS: usize = 20_000;
+        let rewards: Vec<i64> = (0..ROLLS)
+            .filter_map(|_| service.roll_message_reward())
+            .collect();
+
+        // The standard deviation of the rate is about 0.003, so this only
+        // fails if the generator is badly biased
+        let rate = rewards.len() as f64 / ROLLS as f64;
+        assert!((rate - 0.25).abs() < 0.02, "reward rate was {}", rate);
+        assert!(rewards.iter().all(|amount| (3..=7).contains(amount)));
+        // Every amount in the range comes up
+        for amount in 3..=7 {
+            assert!(rewards.contains(&amount), "{} never rolled", amount);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coinflip_enforces_max_bet_and_cooldown() {
+        let service = EconomyService::new(InMemoryCoinStore::new());
+        service
+            .award_coins(1, 1, 1_000, "Test".to_string())
+            .await
+            .unwrap();
+
+        let too_big = service.coinflip(1, 1, 501, CoinSide::Heads).await;
+        assert!(matches!(
+            too_big,
+            Err(EconomyError::InvalidBet { max_bet: 500 })
+        ));
+        let broke = service.coinflip(2, 1, 10, CoinSide::Heads).await;
+        assert!(matches!(broke, Err(EconomyError::InsufficientFunds { .. })));
+
+        let result = service.coinflip(1, 1, 100, CoinSide::Heads).await.unwrap();
+        assert_eq!(result.won, result.outcome == CoinSide::Heads);
+        assert_eq!(result.new_balance, if result.won { 1_100 } else { 900 });
+        assert_eq!(service.get_balance(1, 1).await.unwrap(), result.new_balance);
+
+        let again = service.coinflip(1, 1, 100, CoinSide::Heads).await;
+        assert!(matches!(again, Err(EconomyError::OnCooldown { .. })));
+        // A failed bet didn't start a cooldown for the member without coins
+        service
+            .award_coins(2, 1, 10, "Test".to_string())
+            .await
+            .unwrap();
+        assert!(service.coinflip(2, 1, 10, CoinSide::Tails).await.is_ok());
+
+        // Both legs of a win are logged; a loss only logs the bet
+        let reasons: Vec<String> = service
+            .get_recent_transactions(1, 1, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.reason)
+            .collect();
+        let expected = if result.won {
+            vec!["Coinflip win", "Coinflip bet", "Test"]
+        } else {
+            vec!["Coinflip bet", "Test"]
+        };
+        assert_eq!(reasons, expected);
+    }
+
+    #[tokio::test]
+    async fn test_coinflip_has_no_house_edge() {
+        let config = EconomyConfig {
+            coinflip_cooldown_secs: 0,
+            ..EconomyConfig::default()
+        };
+        let service = EconomyService::new_with_config(InMemoryCoinStore::new(), config);
+        const FLIPS: i64 = 20_000;
+        const START: i64 = 100_000;
+        service
+            .award_coins(1, 1, START, "Test".to_string())
+            .await
+            .unwrap();
+
+        let mut wins = 0;
+        for flip in 0..FLIPS {
+            // Alternate the call so a biased coin can't hide behind one side
+            let call = if flip % 2 == 0 {
+                CoinSide::Heads
+            } else {
+                CoinSide::Tails
+            };
+            if service.coinflip(1, 1, 1, call).await.unwrap().won {
+                wins += 1;
+            }
+        }
+
+        // Betting 1 coin, the net result is wins - losses. Its standard
+        // deviation is about 141 coins, so this allows a 3% edge either way
+        let net = service.get_balance(1, 1).await.unwrap() - START;
+        assert_eq!(net, wins - (FLIPS - wins));
+        assert!(net.abs() < 600, "net result after {} flips: {}", FLIPS, net);
+    }
 }
@@ -3,10 +3,12 @@
 // This module handles user item inventories, following the same
 // architecture pattern as the economy and leveling systems.
 
-use super::item_definitions::ItemId;
+use super::item_definitions::{ItemEffect, ItemId};
 use super::EconomyError;
+use crate::core::leveling::{LevelingService, XpStore};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use std::time::Duration;
 
 // ============================================================================
 // DOMAIN MODELS
@@ -24,6 +26,14 @@ pub struct InventoryItem {
     pub acquired_at: DateTime<Utc>,
 }
 
+/// Result of using an item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemUse {
+    pub effect: ItemEffect,
+    /// When the effect wears off, for timed effects.
+    pub active_until: Option<DateTime<Utc>>,
+}
+
 // ============================================================================
 // STORAGE TRAIT
 // ============================================================================
@@ -70,6 +80,118 @@ pub trait InventoryStore: Send + Sync {
         user_id: u64,
         guild_id: u64,
     ) -> Result<Vec<InventoryItem>, EconomyError>;
+
+    /// Move `quantity` of an item from one member to another, all or nothing.
+    /// Fails with `NotEnoughItems` (and moves nothing) if the sender has fewer.
+    async fn transfer_items(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+        quantity: u32,
+    ) -> Result<(), EconomyError>;
+}
+
+#[async_trait]
+impl<S: InventoryStore + ?Sized> InventoryStore for Box<S> {
+    async fn add_item(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: ItemId,
+    ) -> Result<(), EconomyError> {
+        (**self).add_item(user_id, guild_id, item_id).await
+    }
+
+    async fn remove_item(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+    ) -> Result<bool, EconomyError> {
+        (**self).remove_item(user_id, guild_id, item_id).await
+    }
+
+    async fn has_item(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+    ) -> Result<bool, EconomyError> {
+        (**self).has_item(user_id, guild_id, item_id).await
+    }
+
+    async fn get_item_count(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+    ) -> Result<i64, EconomyError> {
+        (**self).get_item_count(user_id, guild_id, item_id).await
+    }
+
+    async fn get_inventory(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Vec<InventoryItem>, EconomyError> {
+        (**self).get_inventory(user_id, guild_id).await
+    }
+
+    async fn transfer_items(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+        quantity: u32,
+    ) -> Result<(), EconomyError> {
+        (**self)
+            .transfer_items(from_user_id, to_user_id, guild_id, item_id, quantity)
+            .await
+    }
+}
+
+/// Applies item effects to the system they change (XP boosts go to leveling).
+#[async_trait]
+pub trait ItemEffectHandler: Send + Sync {
+    /// Apply `effect` for a member. Returns when a timed effect ends.
+    async fn apply_effect(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        effect: ItemEffect,
+    ) -> Result<Option<DateTime<Utc>>, EconomyError>;
+}
+
+#[async_trait]
+impl<S: XpStore> ItemEffectHandler for LevelingService<S> {
+    async fn apply_effect(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        effect: ItemEffect,
+    ) -> Result<Option<DateTime<Utc>>, EconomyError> {
+        match effect {
+            ItemEffect::XpBoost {
+                multiplier,
+                minutes,
+            } => {
+                let boost = self
+                    .activate_xp_boost(
+                        user_id,
+                        guild_id,
+                        multiplier,
+                        Duration::from_secs(minutes * 60),
+                    )
+                    .await
+                    .map_err(EconomyError::store)?;
+                Ok(Some(boost.expires_at))
+            }
+            ItemEffect::StreakFreeze => Err(EconomyError::ItemNotUsable),
+        }
+    }
 }
 
 // ============================================================================
@@ -108,6 +230,37 @@ impl<S: InventoryStore> InventoryService<S> {
         self.store.remove_item(user_id, guild_id, item_id).await
     }
 
+    /// Use one of the member's items: take it from the inventory and apply
+    /// its effect. If the effect can't be applied the item is given back.
+    pub async fn use_item<H: ItemEffectHandler + ?Sized>(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+        handler: &H,
+    ) -> Result<ItemUse, EconomyError> {
+        let effect = item_id.effect();
+        if !effect.is_usable() {
+            return Err(EconomyError::ItemNotUsable);
+        }
+        if !self.store.remove_item(user_id, guild_id, item_id).await? {
+            return Err(EconomyError::ItemNotOwned);
+        }
+
+        match handler.apply_effect(user_id, guild_id, effect).await {
+            Ok(active_until) => Ok(ItemUse {
+                effect,
+                active_until,
+            }),
+            Err(e) => {
+                self.store
+                    .add_item(user_id, guild_id, item_id.clone())
+                    .await?;
+                Err(e)
+            }
+        }
+    }
+
     /// Check if user has an item.
     pub async fn has_item(
         &self,
@@ -137,6 +290,29 @@ impl<S: InventoryStore> InventoryService<S> {
     ) -> Result<Vec<InventoryItem>, EconomyError> {
         self.store.get_inventory(user_id, guild_id).await
     }
+
+    /// Give `quantity` of an item to another member. Either every item moves
+    /// or none do.
+    pub async fn gift_items(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+        quantity: u32,
+    ) -> Result<(), EconomyError> {
+        if from_user_id == to_user_id {
+            return Err(EconomyError::InvalidTransfer(
+                "you can't gift items to yourself",
+            ));
+        }
+        if quantity == 0 {
+            return Err(EconomyError::InvalidTransfer("gift at least one item"));
+        }
+        self.store
+            .transfer_items(from_user_id, to_user_id, guild_id, item_id, quantity)
+            .await
+    }
 }
 
 // ============================================================================
@@ -237,6 +413,30 @@ mod tests {
             }
             Ok(inventory)
         }
+
+        async fn transfer_items(
+            &self,
+            from_user_id: u64,
+            to_user_id: u64,
+            guild_id: u64,
+            item_id: &ItemId,
+            quantity: u32,
+        ) -> Result<(), EconomyError> {
+            let mut items = self.items.lock().unwrap();
+            let from_key = (from_user_id, guild_id, item_id.as_str().to_string());
+            let available = items.get(&from_key).map(|v| v.len()).unwrap_or(0);
+            if available < quantity as usize {
+                return Err(EconomyError::NotEnoughItems {
+                    requested: quantity,
+                    available: available as i64,
+                });
+            }
+            let sender = items.get_mut(&from_key).unwrap();
+            let moved = sender.split_off(available - quantity as usize);
+            let to_key = (to_user_id, guild_id, item_id.as_str().to_string());
+            items.entry(to_key).or_default().extend(moved);
+            Ok(())
+        }
     }
 
     #[tokio::test]
@@ -355,4 +555,100 @@ mod tests {
         assert_eq!(inventory.len(), 2);
         assert_eq!(inventory[0].item_id, ItemId::DailyStreakSaver);
     }
+
+    #[tokio::test]
+    async fn test_use_item_consumes_it_and_applies_the_effect() {
+        let service = InventoryService::new(InMemoryInventoryStore::new());
+        let leveling = LevelingService::new(crate::infra::leveling::InMemoryXpStore::new());
+
+        // Nothing to use yet
+        let result = service
+            .use_item(1, 1, &ItemId::XpBoostPotion, &leveling)
+            .await;
+        assert!(matches!(result, Err(EconomyError::ItemNotOwned)));
+
+        service.add_item(1, 1, ItemId::XpBoostPotion).await.unwrap();
+        service.add_item(1, 1, ItemId::XpBoostPotion).await.unwrap();
+        let used = service
+            .use_item(1, 1, &ItemId::XpBoostPotion, &leveling)
+            .await
+            .unwrap();
+
+        assert_eq!(used.effect, ItemId::XpBoostPotion.effect());
+        assert_eq!(
+            service
+                .get_item_count(1, 1, &ItemId::XpBoostPotion)
+                .await
+                .unwrap(),
+            1
+        );
+        let boost = leveling.active_xp_boost(1, 1).await.unwrap().unwrap();
+        assert_eq!(boost.multiplier, 1.5);
+        assert_eq!(used.active_until, Some(boost.expires_at));
+        // Other guilds are unaffected
+        assert_eq!(leveling.active_xp_boost(1, 2).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_passive_items_cannot_be_used() {
+        let service = InventoryService::new(InMemoryInventoryStore::new());
+        let leveling = LevelingService::new(crate::infra::leveling::InMemoryXpStore::new());
+        service
+            .add_item(1, 1, ItemId::DailyStreakSaver)
+            .await
+            .unwrap();
+
+        let result = service
+            .use_item(1, 1, &ItemId::DailyStreakSaver, &leveling)
+            .await;
+        assert!(matches!(result, Err(EconomyError::ItemNotUsable)));
+        // Still there for /daily
+        assert!(service
+            .has_item(1, 1, &ItemId::DailyStreakSaver)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_gift_items_conserves_quantity() {
+        let service = InventoryService::new(InMemoryInventoryStore::new());
+        let potion = ItemId::XpBoostPotion;
+        for _ in 0..3 {
+            service.add_item(1, 1, potion.clone()).await.unwrap();
+        }
+        service.add_item(2, 1, potion.clone()).await.unwrap();
+        let count = |user_id| {
+            let service = &service;
+            let potion = &potion;
+            async move { service.get_item_count(user_id, 1, potion).await.unwrap() }
+        };
+
+        // The recipient already has one, so the gift stacks on top
+        service.gift_items(1, 2, 1, &potion, 2).await.unwrap();
+        assert_eq!((count(1).await, count(2).await), (1, 3));
+
+        // Asking for more than the sender has moves nothing
+        let result = service.gift_items(1, 3, 1, &potion, 2).await;
+        assert!(matches!(
+            result,
+            Err(EconomyError::NotEnoughItems {
+                requested: 2,
+                available: 1
+            })
+        ));
+        assert_eq!((count(1).await, count(3).await), (1, 0));
+
+        // A recipient without the item gets a new stack
+        service.gift_items(1, 3, 1, &potion, 1).await.unwrap();
+        assert_eq!((count(1).await, count(2).await, count(3).await), (0, 3, 1));
+
+        assert!(matches!(
+            service.gift_items(2, 2, 1, &potion, 1).await,
+            Err(EconomyError::InvalidTransfer(_))
+        ));
+        assert!(matches!(
+            service.gift_items(2, 1, 1, &potion, 0).await,
+            Err(EconomyError::InvalidTransfer(_))
+        ));
+    }
 }
@@ -1,6 +1,7 @@
 // Shop item definitions
 //
-// This module defines all purchasable items in the shop.
+// This module defines all purchasable items in the shop and what they do when
+// used. Effects are applied by `InventoryService::use_item`.
 
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +9,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ItemId {
     DailyStreakSaver,
+    XpBoostPotion,
+    XpElixir,
 }
 
 impl ItemId {
@@ -15,6 +18,8 @@ impl ItemId {
     pub fn as_str(&self) -> &'static str {
         match self {
             ItemId::DailyStreakSaver => "daily_streak_saver",
+            ItemId::XpBoostPotion => "xp_boost_potion",
+            ItemId::XpElixir => "xp_elixir",
         }
     }
 
@@ -22,13 +27,51 @@ impl ItemId {
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "daily_streak_saver" => Some(ItemId::DailyStreakSaver),
+            "xp_boost_potion" => Some(ItemId::XpBoostPotion),
+            "xp_elixir" => Some(ItemId::XpElixir),
             _ => None,
         }
     }
 
     /// Get all available item IDs.
     pub fn all() -> Vec<ItemId> {
-        vec![ItemId::DailyStreakSaver]
+        vec![
+            ItemId::DailyStreakSaver,
+            ItemId::XpBoostPotion,
+            ItemId::XpElixir,
+        ]
+    }
+
+    /// What the item does.
+    pub fn effect(&self) -> ItemEffect {
+        match self {
+            ItemId::DailyStreakSaver => ItemEffect::StreakFreeze,
+            ItemId::XpBoostPotion => ItemEffect::XpBoost {
+                multiplier: 1.5,
+                minutes: 60,
+            },
+            ItemId::XpElixir => ItemEffect::XpBoost {
+                multiplier: 2.0,
+                minutes: 30,
+            },
+        }
+    }
+}
+
+/// The effect of an item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemEffect {
+    /// Saves a missed daily streak. Spent automatically by `/daily`, so it
+    /// can't be used by hand.
+    StreakFreeze,
+    /// Multiplies message XP for a while.
+    XpBoost { multiplier: f64, minutes: u64 },
+}
+
+impl ItemEffect {
+    /// Whether members use the item themselves (`/use`).
+    pub fn is_usable(&self) -> bool {
+        !matches!(self, ItemEffect::StreakFreeze)
     }
 }
 
@@ -53,6 +96,20 @@ impl ShopItem {
                 price: 100,
                 emoji: "🛡️",
             },
+            ItemId::XpBoostPotion => ShopItem {
+                id: id.clone(),
+                name: "XP Boost Potion",
+                description: "1.5x XP from messages for 1 hour. Use it with `/use`",
+                price: 150,
+                emoji: "⚗️",
+            },
+            ItemId::XpElixir => ShopItem {
+                id: id.clone(),
+                name: "XP Elixir",
+                description: "2x XP from messages for 30 minutes. Use it with `/use`",
+                price: 200,
+                emoji: "🧪",
+            },
         }
     }
 
@@ -71,6 +128,10 @@ mod tests {
         let id = ItemId::DailyStreakSaver;
         assert_eq!(id.as_str(), "daily_streak_saver");
         assert_eq!(ItemId::from_str("daily_streak_saver"), Some(id));
+
+        for id in ItemId::all() {
+            assert_eq!(ItemId::from_str(id.as_str()), Some(id));
+        }
     }
 
     #[test]
@@ -84,7 +145,14 @@ mod tests {
     #[test]
     fn test_all_items() {
         let items = ShopItem::all();
-        assert_eq!(items.len(), 1);
+        assert_eq!(items.len(), 3);
         assert_eq!(items[0].id, ItemId::DailyStreakSaver);
+        // The streak saver is passive; the potions are used by hand
+        let usable: Vec<ItemId> = items
+            .into_iter()
+            .filter(|item| item.id.effect().is_usable())
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(usable, vec![ItemId::XpBoostPotion, ItemId::XpElixir]);
     }
 }
@@ -4,6 +4,9 @@ mod economy_service;
 pub mod inventory_service;
 pub mod item_definitions;
 
-pub use economy_service::{CoinStore, EconomyError, EconomyService, Transaction, Wallet};
+pub use economy_service::{
+    wealth_ranks, CoinSide, CoinStore, EconomyConfig, EconomyError, EconomyService, Transaction,
+    Wallet, WealthRanking,
+};
 pub use inventory_service::{InventoryItem, InventoryService, InventoryStore};
-pub use item_definitions::{ItemId, ShopItem};
+pub use item_definitions::{ItemEffect, ItemId, ShopItem};
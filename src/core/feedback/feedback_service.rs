@@ -0,0 +1,160 @@
+// Feedback about the bot from its users (`/feedback`).
+//
+// Entries are kept so the bot owners can read them later with
+// `/admin feedback recent`. The Discord layer also forwards each new entry to
+// `FEEDBACK_CHANNEL_ID` when that's set. A cooldown on the command keeps one
+// user from flooding the store.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::error::Error;
+
+/// Longest feedback message accepted, in characters.
+pub const MAX_FEEDBACK_CHARS: usize = 1000;
+
+/// Most entries `/admin feedback recent` can ask for.
+pub const MAX_RECENT_FEEDBACK: usize = 50;
+
+/// One piece of feedback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feedback {
+    /// `None` when sent from a DM.
+    pub guild_id: Option<u64>,
+    pub user_id: u64,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Check a feedback message before saving it. Returns the trimmed message.
+pub fn validate_feedback(message: &str) -> Result<String, String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("Feedback can't be empty.".to_string());
+    }
+    if message.chars().count() > MAX_FEEDBACK_CHARS {
+        return Err(format!(
+            "Feedback can be at most {} characters.",
+            MAX_FEEDBACK_CHARS
+        ));
+    }
+    Ok(message.to_string())
+}
+
+/// Persistence for feedback entries.
+#[async_trait]
+pub trait FeedbackStore: Send + Sync {
+    async fn add_feedback(&self, feedback: &Feedback) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    /// Up to `limit` entries, newest first.
+    async fn recent_feedback(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<Feedback>, Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: FeedbackStore + ?Sized> FeedbackStore for Box<S> {
+    async fn add_feedback(&self, feedback: &Feedback) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).add_feedback(feedback).await
+    }
+
+    async fn recent_feedback(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<Feedback>, Box<dyn Error + Send + Sync>> {
+        (**self).recent_feedback(limit).await
+    }
+}
+
+pub struct FeedbackService<S: FeedbackStore> {
+    store: S,
+}
+
+impl<S: FeedbackStore> FeedbackService<S> {
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Save feedback from `user_id`. The message must already have passed
+    /// `validate_feedback`.
+    pub async fn submit(
+        &self,
+        guild_id: Option<u64>,
+        user_id: u64,
+        message: String,
+        now: DateTime<Utc>,
+    ) -> Result<Feedback, Box<dyn Error + Send + Sync>> {
+        let feedback = Feedback {
+            guild_id,
+            user_id,
+            message,
+            created_at: now,
+        };
+        self.store.add_feedback(&feedback).await?;
+        Ok(feedback)
+    }
+
+    /// The latest `limit` entries (at most `MAX_RECENT_FEEDBACK`), newest first.
+    pub async fn recent(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<Feedback>, Box<dyn Error + Send + Sync>> {
+        self.store
+            .recent_feedback(limit.clamp(1, MAX_RECENT_FEEDBACK))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::feedback::InMemoryFeedbackStore;
+    use chrono::Duration;
+
+    #[test]
+    fn test_validate_feedback() {
+        assert_eq!(
+            validate_feedback("  love the bot  "),
+            Ok("love the bot".to_string())
+        );
+        assert!(validate_feedback("   ").is_err());
+        assert!(validate_feedback(&"a".repeat(MAX_FEEDBACK_CHARS)).is_ok());
+        assert!(validate_feedback(&"a".repeat(MAX_FEEDBACK_CHARS + 1)).is_err());
+        // Counted in characters, not bytes
+        assert!(validate_feedback(&"é".repeat(MAX_FEEDBACK_CHARS)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_recent_feedback_is_newest_first_and_capped() {
+        let service = FeedbackService::new(InMemoryFeedbackStore::new());
+        let start = Utc::now();
+
+        service
+            .submit(None, 7, "from a DM".to_string(), start)
+            .await
+            .unwrap();
+        for i in 0..60 {
+            service
+                .submit(
+                    Some(1),
+                    i,
+                    format!("idea {}", i),
+                    start + Duration::minutes(i as i64),
+                )
+                .await
+                .unwrap();
+        }
+
+        let recent = service.recent(3).await.unwrap();
+        let messages: Vec<&str> = recent.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages, vec!["idea 59", "idea 58", "idea 57"]);
+        assert_eq!(recent[0].guild_id, Some(1));
+
+        assert_eq!(
+            service.recent(500).await.unwrap().len(),
+            MAX_RECENT_FEEDBACK
+        );
+        assert_eq!(service.recent(0).await.unwrap().len(), 1);
+        assert_eq!(service.recent(61).await.unwrap().len(), MAX_RECENT_FEEDBACK);
+    }
+}
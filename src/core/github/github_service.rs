@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use crate::core::util::ErrorSource;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
@@ -11,8 +12,18 @@ use tokio::sync::RwLock;
 pub enum GithubError {
     #[error("GitHub API error: {0}")]
     Api(String),
+    #[error("GitHub API did not respond within {}s", .0.as_secs())]
+    Timeout(std::time::Duration),
+    /// The underlying error (file or JSON) is the `source` for the logs.
     #[error("Failed to persist GitHub config: {0}")]
-    Store(String),
+    Store(String, #[source] Option<ErrorSource>),
+}
+
+impl GithubError {
+    /// A store failure caused by `source`, which stays attached for the logs.
+    pub fn store<E: std::error::Error + Send + Sync + 'static>(source: E) -> Self {
+        GithubError::Store(source.to_string(), Some(Box::new(source)))
+    }
 }
 
 /// Light-weight commit representation that is independent of any HTTP or Discord types.
@@ -24,6 +35,16 @@ pub struct Commit {
     pub html_url: String,
     pub avatar_url: Option<String>,
     pub committed_at: Option<DateTime<Utc>>,
+    /// Diff stats, only fetched when commit stats are enabled on the service.
+    pub stats: Option<CommitStats>,
+}
+
+/// Size of a commit's diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitStats {
+    pub files_changed: u32,
+    pub additions: u32,
+    pub deletions: u32,
 }
 
 /// Basic issue model used for both bugs and general issue updates.
@@ -78,14 +99,127 @@ pub enum GithubEvent {
         issue: Issue,
         activity: IssueActivity,
     },
+    /// Every new commit a poll found for an entry in digest mode, across all
+    /// of its repositories.
+    CommitDigest {
+        owner: String,
+        commits: Vec<DigestCommit>,
+    },
 }
 
+/// A commit listed in a `CommitDigest`, with the repository and branch it was
+/// found on.
+#[derive(Debug, Clone)]
+pub struct DigestCommit {
+    pub repo: String,
+    pub branch: String,
+    pub commit: Commit,
+}
+
+/// Default for how often tracked organizations are re-listed: once a day.
+pub const DEFAULT_ORG_REFRESH_INTERVAL_SECS: i64 = 24 * 60 * 60;
+
+/// On the first poll of a repository, issues closed or opened within this many
+/// minutes are still announced; anything older is history.
+const FIRST_RUN_WINDOW_MINUTES: i64 = 30;
+
+/// Commits fetched per page while catching up on a branch.
+const COMMITS_PER_PAGE: usize = 100;
+
 /// Wrapper that includes routing information for the Discord adapter.
 #[derive(Debug, Clone)]
 pub struct GithubUpdate {
     pub guild_id: u64,
     pub channel_id: u64,
     pub event: GithubEvent,
+    /// For each repository the update covers, its watermark with every
+    /// earlier update of the poll posted and this one not.
+    resume_from: Vec<ResumePoint>,
+}
+
+impl GithubUpdate {
+    /// The `(owner, repo)` pairs the update covers: one, or several for a
+    /// digest of an organization.
+    pub fn repositories(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.resume_from
+            .iter()
+            .map(|point| (point.owner.as_str(), point.repo.as_str()))
+    }
+}
+
+/// Where a repository's watermark stops if an update doesn't go out.
+#[derive(Debug, Clone)]
+struct ResumePoint {
+    /// Owner of the tracking entry (the org for org entries)
+    owner: String,
+    repo: String,
+    state: RepoTrackingData,
+}
+
+/// Result of a poll: the events to post and the watermarks they would advance to.
+///
+/// Nothing is persisted until the watermarks are passed to
+/// `GithubService::commit_watermarks`, so events that fail to post are
+/// reported again by the next poll.
+#[derive(Debug, Default)]
+pub struct GithubPoll {
+    pub updates: Vec<GithubUpdate>,
+    pub watermarks: PendingWatermarks,
+}
+
+/// Watermark changes proposed by a poll, per repository.
+#[derive(Debug, Default)]
+pub struct PendingWatermarks {
+    repos: Vec<RepoWatermark>,
+    org_repos: Vec<OrgRepoList>,
+}
+
+impl PendingWatermarks {
+    pub fn is_empty(&self) -> bool {
+        self.repos.is_empty() && self.org_repos.is_empty()
+    }
+
+    /// Stop the watermark of the repository an update came from just before
+    /// it, e.g. because posting it failed. The update and everything after it
+    /// from that repository are reported again; the ones before it are not.
+    ///
+    /// Updates are meant to be posted in order, so only the first update held
+    /// back per repository counts. Later ones are already covered by it.
+    pub fn hold_back(&mut self, update: &GithubUpdate) {
+        for point in &update.resume_from {
+            if let Some(mark) = self.repos.iter_mut().find(|mark| {
+                mark.guild_id == update.guild_id
+                    && mark.owner.eq_ignore_ascii_case(&point.owner)
+                    && mark.repo == point.repo
+            }) {
+                if !mark.held_back {
+                    mark.state = point.state.clone();
+                    mark.held_back = true;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RepoWatermark {
+    guild_id: u64,
+    /// Owner of the tracking entry (the org for org entries)
+    owner: String,
+    repo: String,
+    is_org: bool,
+    state: RepoTrackingData,
+    /// Set once `state` was rolled back to a failed update
+    held_back: bool,
+}
+
+/// Repository list fetched for an org entry that had none cached.
+#[derive(Debug)]
+struct OrgRepoList {
+    guild_id: u64,
+    owner: String,
+    repos: Vec<String>,
+    refreshed_at: DateTime<Utc>,
 }
 
 /// Persisted state that keeps track of where we left off per repository.
@@ -116,8 +250,26 @@ pub struct GithubTrackingEntry {
     pub is_org: bool,
     #[serde(default)]
     pub org_repos: Vec<String>,
+    /// When `org_repos` was last listed from GitHub (organizations only).
+    #[serde(default)]
+    pub org_repos_refreshed_at: Option<DateTime<Utc>>,
     #[serde(default)]
     pub repo_data: HashMap<String, RepoTrackingData>,
+    /// Branch allowlist with `*` / `?` globs, e.g. `["main", "release/*"]`.
+    /// Empty tracks every branch.
+    #[serde(default)]
+    pub branches: Vec<String>,
+    /// Notifications are held back until then (`/github mute`). Watermarks
+    /// still advance, so nothing piles up for when the mute ends.
+    #[serde(default)]
+    pub muted_until: Option<DateTime<Utc>>,
+    /// Muted with no end, until `/github unmute`.
+    #[serde(default)]
+    pub muted_indefinitely: bool,
+    /// Post one digest of a repository's new commits per poll instead of one
+    /// notification per commit.
+    #[serde(default)]
+    pub commit_digest: bool,
 }
 
 impl GithubTrackingEntry {
@@ -131,7 +283,12 @@ impl GithubTrackingEntry {
             last_issue_updated_at: None,
             is_org: false,
             org_repos: Vec::new(),
+            org_repos_refreshed_at: None,
             repo_data: HashMap::new(),
+            branches: Vec::new(),
+            muted_until: None,
+            muted_indefinitely: false,
+            commit_digest: false,
         }
     }
 
@@ -145,9 +302,60 @@ impl GithubTrackingEntry {
             last_issue_updated_at: None,
             is_org: true,
             org_repos: repos,
+            org_repos_refreshed_at: Some(Utc::now()),
             repo_data: HashMap::new(),
+            branches: Vec::new(),
+            muted_until: None,
+            muted_indefinitely: false,
+            commit_digest: false,
+        }
+    }
+
+    /// Identifies this entry within a guild: `repo:owner/name` or `org:login`.
+    pub fn key(&self) -> String {
+        match (&self.repo, self.is_org) {
+            (Some(repo), false) => format!("repo:{}/{}", self.owner, repo),
+            _ => format!("org:{}", self.owner),
+        }
+    }
+
+    /// `owner/repo` for a repository, the login for an organization.
+    pub fn display_name(&self) -> String {
+        match (&self.repo, self.is_org) {
+            (Some(repo), false) => format!("{}/{}", self.owner, repo),
+            _ => self.owner.clone(),
         }
     }
+
+    /// Whether notifications for this entry are suppressed at `now`.
+    pub fn is_muted(&self, now: DateTime<Utc>) -> bool {
+        self.muted_indefinitely || self.muted_until.is_some_and(|until| now < until)
+    }
+
+    /// Whether this is the repo `owner/repo`, or the organization `owner`
+    /// when `repo` is `None`.
+    fn is_target(&self, owner: &str, repo: Option<&str>) -> bool {
+        self.owner.eq_ignore_ascii_case(owner)
+            && match repo {
+                Some(repo) => {
+                    !self.is_org
+                        && self
+                            .repo
+                            .as_deref()
+                            .is_some_and(|r| r.eq_ignore_ascii_case(repo))
+                }
+                None => self.is_org,
+            }
+    }
+
+    /// Whether commits on `branch` should be reported for this entry.
+    pub fn tracks_branch(&self, branch: &str) -> bool {
+        self.branches.is_empty()
+            || self
+                .branches
+                .iter()
+                .any(|pattern| glob_match(pattern, branch))
+    }
 }
 
 /// Top-level configuration map keyed by guild id.
@@ -162,13 +370,18 @@ pub struct GithubConfig {
 pub trait GithubClient: Send + Sync {
     async fn list_org_repos(&self, org: &str) -> Result<Vec<String>, GithubError>;
     async fn list_branches(&self, owner: &str, repo: &str) -> Result<Vec<String>, GithubError>;
+    /// Newest commits first. With `until_sha`, further pages are fetched until
+    /// that commit shows up, so a backlog after downtime isn't cut short.
     async fn list_commits(
         &self,
         owner: &str,
         repo: &str,
         branch: &str,
         per_page: usize,
+        until_sha: Option<&str>,
     ) -> Result<Vec<Commit>, GithubError>;
+    /// Issues labelled bug. With `since`, every page updated after it is
+    /// fetched; without it, only the latest page. Same for `list_general_issues`.
     async fn list_bug_issues(
         &self,
         owner: &str,
@@ -181,6 +394,16 @@ pub trait GithubClient: Send + Sync {
         repo: &str,
         since: Option<DateTime<Utc>>,
     ) -> Result<Vec<Issue>, GithubError>;
+    /// Diff stats for one commit. Costs an extra request per commit, so the
+    /// service only calls it when commit stats are enabled.
+    async fn commit_stats(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _sha: &str,
+    ) -> Result<Option<CommitStats>, GithubError> {
+        Ok(None)
+    }
 }
 
 /// Storage layer abstraction for GitHub configuration.
@@ -190,6 +413,17 @@ pub trait GithubConfigStore: Send + Sync {
     async fn save(&self, config: &GithubConfig) -> Result<(), GithubError>;
 }
 
+#[async_trait]
+impl<S: GithubConfigStore + ?Sized> GithubConfigStore for Box<S> {
+    async fn load(&self) -> Result<GithubConfig, GithubError> {
+        (**self).load().await
+    }
+
+    async fn save(&self, config: &GithubConfig) -> Result<(), GithubError> {
+        (**self).save(config).await
+    }
+}
+
 /// Service that orchestrates polling GitHub and emitting events for the Discord layer.
 ///
 /// The polling logic lives here so it can be tested without Discord or HTTP concerns.
@@ -197,6 +431,8 @@ pub struct GithubService<C: GithubClient, S: GithubConfigStore> {
     client: C,
     store: S,
     config: RwLock<GithubConfig>,
+    fetch_commit_stats: bool,
+    org_refresh_interval: Duration,
 }
 
 impl<C, S> GithubService<C, S>
@@ -212,9 +448,23 @@ where
             client,
             store,
             config: RwLock::new(config),
+            fetch_commit_stats: false,
+            org_refresh_interval: Duration::seconds(DEFAULT_ORG_REFRESH_INTERVAL_SECS),
         })
     }
 
+    /// Fetch diff stats for every reported commit (one extra API call each).
+    pub fn with_commit_stats(mut self, enabled: bool) -> Self {
+        self.fetch_commit_stats = enabled;
+        self
+    }
+
+    /// How often tracked organizations are re-listed to pick up new repositories.
+    pub fn with_org_refresh_interval(mut self, interval: Duration) -> Self {
+        self.org_refresh_interval = interval;
+        self
+    }
+
     /// List tracked entries for a guild so the Discord layer can render them.
     pub async fn list_entries(&self, guild_id: u64) -> Vec<GithubTrackingEntry> {
         self.config
@@ -267,6 +517,7 @@ where
         {
             existing.channel_id = channel_id;
             existing.org_repos = repos.clone();
+            existing.org_repos_refreshed_at = Some(Utc::now());
         } else {
             entries.push(GithubTrackingEntry::new_org(org, channel_id, repos.clone()));
         }
@@ -275,24 +526,12 @@ where
         Ok(repos)
     }
 
-    /// Remove a single repository from tracking.
-    pub async fn remove_repository(
-        &self,
-        guild_id: u64,
-        owner: &str,
-        repo: &str,
-    ) -> Result<bool, GithubError> {
+    /// Remove an organization entry.
+    pub async fn remove_organization(&self, guild_id: u64, org: &str) -> Result<bool, GithubError> {
         let mut config = self.config.write().await;
         if let Some(entries) = config.guilds.get_mut(&guild_id) {
             let before = entries.len();
-            entries.retain(|entry| {
-                !(entry
-                    .repo
-                    .as_deref()
-                    .is_some_and(|r| r.eq_ignore_ascii_case(repo))
-                    && entry.owner.eq_ignore_ascii_case(owner)
-                    && !entry.is_org)
-            });
+            entries.retain(|entry| !(entry.is_org && entry.owner.eq_ignore_ascii_case(org)));
             if entries.len() != before {
                 self.store.save(&config).await?;
                 return Ok(true);
@@ -301,90 +540,292 @@ where
         Ok(false)
     }
 
-    /// Remove an organization entry.
-    pub async fn remove_organization(&self, guild_id: u64, org: &str) -> Result<bool, GithubError> {
+    /// Remove the entry with the given `GithubTrackingEntry::key`. Returns the
+    /// removed entry, or `None` if nothing in the guild has that key.
+    pub async fn remove_entry(
+        &self,
+        guild_id: u64,
+        key: &str,
+    ) -> Result<Option<GithubTrackingEntry>, GithubError> {
         let mut config = self.config.write().await;
-        if let Some(entries) = config.guilds.get_mut(&guild_id) {
-            let before = entries.len();
-            entries.retain(|entry| !(entry.is_org && entry.owner.eq_ignore_ascii_case(org)));
-            if entries.len() != before {
-                self.store.save(&config).await?;
-                return Ok(true);
+        let Some(entries) = config.guilds.get_mut(&guild_id) else {
+            return Ok(None);
+        };
+        let Some(index) = entries.iter().position(|entry| entry.key() == key) else {
+            return Ok(None);
+        };
+        let removed = entries.remove(index);
+        self.store.save(&config).await?;
+        Ok(Some(removed))
+    }
+
+    /// Set the branch allowlist of a tracked repo (`repo: Some`) or org (`repo: None`).
+    /// An empty list tracks every branch again. Returns false if nothing matched.
+    pub async fn set_branch_filter(
+        &self,
+        guild_id: u64,
+        owner: &str,
+        repo: Option<&str>,
+        branches: Vec<String>,
+    ) -> Result<bool, GithubError> {
+        let mut config = self.config.write().await;
+        let Some(entry) = config
+            .guilds
+            .get_mut(&guild_id)
+            .and_then(|entries| entries.iter_mut().find(|e| e.is_target(owner, repo)))
+        else {
+            return Ok(false);
+        };
+
+        entry.branches = branches;
+        self.store.save(&config).await?;
+        Ok(true)
+    }
+
+    /// Switch a tracked repo (`repo: Some`) or org (`repo: None`) between one
+    /// notification per commit and one digest per poll. Returns false if
+    /// nothing matched.
+    pub async fn set_commit_digest(
+        &self,
+        guild_id: u64,
+        owner: &str,
+        repo: Option<&str>,
+        enabled: bool,
+    ) -> Result<bool, GithubError> {
+        let mut config = self.config.write().await;
+        let Some(entry) = config
+            .guilds
+            .get_mut(&guild_id)
+            .and_then(|entries| entries.iter_mut().find(|e| e.is_target(owner, repo)))
+        else {
+            return Ok(false);
+        };
+
+        entry.commit_digest = enabled;
+        self.store.save(&config).await?;
+        Ok(true)
+    }
+
+    /// Stop posting notifications for a tracked repo (`repo: Some`) or org
+    /// (`repo: None`) until `until`, or until unmuted when `until` is `None`.
+    /// Polling carries on in the background. Returns false if nothing matched.
+    pub async fn mute(
+        &self,
+        guild_id: u64,
+        owner: &str,
+        repo: Option<&str>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<bool, GithubError> {
+        let mut config = self.config.write().await;
+        let Some(entry) = config
+            .guilds
+            .get_mut(&guild_id)
+            .and_then(|entries| entries.iter_mut().find(|e| e.is_target(owner, repo)))
+        else {
+            return Ok(false);
+        };
+
+        entry.muted_until = until;
+        entry.muted_indefinitely = until.is_none();
+        self.store.save(&config).await?;
+        Ok(true)
+    }
+
+    /// Resume notifications for a muted repo or org. Only what happens from
+    /// now on is posted. Returns false if nothing matched.
+    pub async fn unmute(
+        &self,
+        guild_id: u64,
+        owner: &str,
+        repo: Option<&str>,
+    ) -> Result<bool, GithubError> {
+        let mut config = self.config.write().await;
+        let Some(entry) = config
+            .guilds
+            .get_mut(&guild_id)
+            .and_then(|entries| entries.iter_mut().find(|e| e.is_target(owner, repo)))
+        else {
+            return Ok(false);
+        };
+
+        entry.muted_until = None;
+        entry.muted_indefinitely = false;
+        self.store.save(&config).await?;
+        Ok(true)
+    }
+
+    /// Fetch the latest commit and newest issue of any repository as events, so
+    /// admins can preview notifications before tracking it. Nothing is stored.
+    pub async fn preview_repository(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<GithubEvent>, GithubError> {
+        let mut events = Vec::new();
+
+        let branches = self.client.list_branches(owner, repo).await?;
+        let branch = branches
+            .iter()
+            .find(|b| *b == "main" || *b == "master")
+            .or(branches.first());
+        if let Some(branch) = branch {
+            let commits = self
+                .client
+                .list_commits(owner, repo, branch, 1, None)
+                .await?;
+            if let Some(mut commit) = commits.into_iter().next() {
+                self.attach_commit_stats(owner, repo, &mut commit).await;
+                events.push(GithubEvent::CommitPushed {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    branch: branch.clone(),
+                    commit,
+                });
             }
         }
-        Ok(false)
+
+        let issues = self.client.list_general_issues(owner, repo, None).await?;
+        if let Some(issue) = issues.into_iter().max_by_key(|issue| issue.created_at) {
+            let event = match issue.state {
+                IssueState::Closed if issue.is_bug => GithubEvent::BugClosed {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    issue,
+                },
+                state => GithubEvent::IssueActivity {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    issue,
+                    activity: match state {
+                        IssueState::Open => IssueActivity::Opened,
+                        IssueState::Closed => IssueActivity::Closed,
+                    },
+                },
+            };
+            events.push(event);
+        }
+
+        Ok(events)
     }
 
-    /// Force an immediate poll and return events to be posted.
-    pub async fn poll_updates(&self) -> Result<Vec<GithubUpdate>, GithubError> {
-        // Clone the config so we can perform HTTP calls without holding locks.
-        let snapshot = { self.config.read().await.clone() };
-        let mut updates = Vec::new();
+    /// Force an immediate poll and return the events to be posted.
+    ///
+    /// Watermarks are not advanced here: post the updates, `hold_back` any that
+    /// failed, then pass the watermarks to `commit_watermarks`.
+    pub async fn poll_updates(&self) -> Result<GithubPoll, GithubError> {
+        self.collect_updates(None).await
+    }
+
+    /// Poll one guild's tracked repos and return what would be posted, without
+    /// advancing any watermarks. The next real poll reports the same events.
+    pub async fn preview_updates(&self, guild_id: u64) -> Result<Vec<GithubUpdate>, GithubError> {
+        Ok(self.collect_updates(Some(guild_id)).await?.updates)
+    }
+
+    /// Persist the watermarks proposed by `poll_updates`.
+    pub async fn commit_watermarks(
+        &self,
+        watermarks: PendingWatermarks,
+    ) -> Result<(), GithubError> {
+        if watermarks.is_empty() {
+            return Ok(());
+        }
 
-        // We collect changes to apply them safely after the poll.
-        struct RepoStateUpdate {
-            guild_id: u64,
-            owner: String,
-            repo: Option<String>, // None if org
-            is_org: bool,
-            // For single repo:
-            new_state: Option<RepoTrackingData>,
-            // For org:
-            org_repos: Option<Vec<String>>,
-            repo_states: Option<HashMap<String, RepoTrackingData>>,
+        let mut config = self.config.write().await;
+        for org in watermarks.org_repos {
+            if let Some(entry) = config.guilds.get_mut(&org.guild_id).and_then(|entries| {
+                entries
+                    .iter_mut()
+                    .find(|e| e.is_org && e.owner.eq_ignore_ascii_case(&org.owner))
+            }) {
+                entry.org_repos = org.repos;
+                entry.org_repos_refreshed_at = Some(org.refreshed_at);
+            }
+        }
+        for mark in watermarks.repos {
+            let Some(entries) = config.guilds.get_mut(&mark.guild_id) else {
+                continue;
+            };
+            if mark.is_org {
+                if let Some(entry) = entries
+                    .iter_mut()
+                    .find(|e| e.is_org && e.owner.eq_ignore_ascii_case(&mark.owner))
+                {
+                    let repo_key = format!("{}/{}", mark.owner, mark.repo);
+                    entry.repo_data.insert(repo_key, mark.state);
+                }
+            } else if let Some(entry) = entries.iter_mut().find(|e| {
+                !e.is_org
+                    && e.owner.eq_ignore_ascii_case(&mark.owner)
+                    && e.repo.as_deref() == Some(mark.repo.as_str())
+            }) {
+                entry.last_commit_shas = mark.state.last_commit_shas;
+                entry.last_bug_closed_at = mark.state.last_bug_closed_at;
+                entry.last_issue_updated_at = mark.state.last_issue_updated_at;
+            }
         }
+        self.store.save(&config).await
+    }
 
-        let mut pending_changes = Vec::new();
+    /// Poll tracked entries (optionally just one guild's) and collect the
+    /// updates together with the watermarks they would advance to.
+    async fn collect_updates(&self, guild_filter: Option<u64>) -> Result<GithubPoll, GithubError> {
+        // Clone the config so we can perform HTTP calls without holding locks.
+        let snapshot = { self.config.read().await.clone() };
+        let mut poll = GithubPoll::default();
 
         for (guild_id, entries) in snapshot.guilds.iter() {
+            if guild_filter.is_some_and(|filter| filter != *guild_id) {
+                continue;
+            }
             for entry in entries {
                 let owner = entry.owner.clone();
                 if entry.is_org {
-                    let mut org_repos_update = None;
                     let mut current_repos = entry.org_repos.clone();
 
-                    if current_repos.is_empty() {
-                        let fetched = self.client.list_org_repos(&owner).await?;
-                        current_repos = fetched.clone();
-                        org_repos_update = Some(fetched);
+                    // Re-list the org now and then so repos created after it was
+                    // tracked are picked up. Their first poll is a quiet baseline.
+                    let now = Utc::now();
+                    let refresh_due = entry
+                        .org_repos_refreshed_at
+                        .is_none_or(|at| now - at >= self.org_refresh_interval);
+                    if current_repos.is_empty() || refresh_due {
+                        for repo in self.client.list_org_repos(&owner).await? {
+                            if !current_repos.contains(&repo) {
+                                current_repos.push(repo);
+                            }
+                        }
+                        poll.watermarks.org_repos.push(OrgRepoList {
+                            guild_id: *guild_id,
+                            owner: owner.clone(),
+                            repos: current_repos.clone(),
+                            refreshed_at: now,
+                        });
                     }
 
-                    let mut repo_states_update = HashMap::new();
-                    let mut org_dirty = false;
-
+                    let mut entry_updates = Vec::new();
                     for repo in current_repos {
                         let repo_key = format!("{}/{}", owner, repo);
                         let mut repo_state =
                             entry.repo_data.get(&repo_key).cloned().unwrap_or_default();
 
                         let (repo_updates, repo_dirty) = self
-                            .poll_repository(
-                                *guild_id,
-                                entry.channel_id,
-                                &owner,
-                                &repo,
-                                &mut repo_state,
-                            )
+                            .poll_repository(*guild_id, entry, &repo, &mut repo_state)
                             .await?;
-                        updates.extend(repo_updates);
+                        entry_updates.extend(repo_updates);
 
                         if repo_dirty {
-                            repo_states_update.insert(repo_key, repo_state);
-                            org_dirty = true;
+                            poll.watermarks.repos.push(RepoWatermark {
+                                guild_id: *guild_id,
+                                owner: owner.clone(),
+                                repo,
+                                is_org: true,
+                                state: repo_state,
+                                held_back: false,
+                            });
                         }
                     }
-
-                    if org_repos_update.is_some() || org_dirty {
-                        pending_changes.push(RepoStateUpdate {
-                            guild_id: *guild_id,
-                            owner: owner.clone(),
-                            repo: None,
-                            is_org: true,
-                            new_state: None,
-                            org_repos: org_repos_update,
-                            repo_states: Some(repo_states_update),
-                        });
-                    }
+                    poll.updates.extend(batch_commits(entry, entry_updates));
                 } else if let Some(repo) = entry.repo.clone() {
                     let mut state = RepoTrackingData {
                         last_commit_shas: entry.last_commit_shas.clone(),
@@ -393,75 +834,58 @@ where
                     };
 
                     let (repo_updates, repo_dirty) = self
-                        .poll_repository(*guild_id, entry.channel_id, &owner, &repo, &mut state)
+                        .poll_repository(*guild_id, entry, &repo, &mut state)
                         .await?;
-
-                    updates.extend(repo_updates);
+                    poll.updates.extend(batch_commits(entry, repo_updates));
 
                     if repo_dirty {
-                        pending_changes.push(RepoStateUpdate {
+                        poll.watermarks.repos.push(RepoWatermark {
                             guild_id: *guild_id,
                             owner: owner.clone(),
-                            repo: Some(repo),
+                            repo,
                             is_org: false,
-                            new_state: Some(state),
-                            org_repos: None,
-                            repo_states: None,
+                            state,
+                            held_back: false,
                         });
                     }
                 }
             }
         }
 
-        if !pending_changes.is_empty() {
-            let mut config = self.config.write().await;
-            for change in pending_changes {
-                if let Some(guild_entries) = config.guilds.get_mut(&change.guild_id) {
-                    if let Some(entry) = guild_entries.iter_mut().find(|e| {
-                        e.owner.eq_ignore_ascii_case(&change.owner)
-                            && e.repo == change.repo
-                            && e.is_org == change.is_org
-                    }) {
-                        if change.is_org {
-                            if let Some(repos) = change.org_repos {
-                                entry.org_repos = repos;
-                            }
-                            if let Some(states) = change.repo_states {
-                                for (k, v) in states {
-                                    entry.repo_data.insert(k, v);
-                                }
-                            }
-                        } else if let Some(state) = change.new_state {
-                            entry.last_commit_shas = state.last_commit_shas;
-                            entry.last_bug_closed_at = state.last_bug_closed_at;
-                            entry.last_issue_updated_at = state.last_issue_updated_at;
-                        }
-                    }
-                }
-            }
-            self.store.save(&config).await?;
-        }
-
-        Ok(updates)
+        Ok(poll)
     }
 
     async fn poll_repository(
         &self,
         guild_id: u64,
-        channel_id: u64,
-        owner: &str,
+        entry: &GithubTrackingEntry,
         repo: &str,
         state: &mut RepoTrackingData,
     ) -> Result<(Vec<GithubUpdate>, bool), GithubError> {
         let mut updates = Vec::new();
         let mut dirty = false;
+        let owner = entry.owner.as_str();
+        let channel_id = entry.channel_id;
 
         let is_first_poll = state.last_commit_shas.is_empty();
         let branches = self.client.list_branches(owner, repo).await?;
-        for branch in branches {
-            let commits = self.client.list_commits(owner, repo, &branch, 10).await?;
-            let latest_sha = commits.first().map(|c| c.sha.as_str());
+        // Filtered-out branches are skipped entirely, so they never get a watermark
+        for branch in branches
+            .into_iter()
+            .filter(|branch| entry.tracks_branch(branch))
+        {
             let last_seen_sha = state.last_commit_shas.get(&branch).cloned();
+            let commits = self
+                .client
+                .list_commits(
+                    owner,
+                    repo,
+                    &branch,
+                    COMMITS_PER_PAGE,
+                    last_seen_sha.as_deref(),
+                )
+                .await?;
+            let latest_sha = commits.first().map(|c| c.sha.as_str());
 
             if last_seen_sha.is_none() {
                 if let Some(sha) = latest_sha {
@@ -485,6 +909,9 @@ where
                         }
                     });
 
+                    // Until its first commit is posted, the branch is still unseen
+                    let resume_from = state.clone();
+
                     // Record that we've now seen this branch
                     state
                         .last_commit_shas
@@ -498,20 +925,15 @@ where
 
                     // Report only commits newer than the first known one
                     let new_commits = collect_new_commits(&commits, first_known_sha);
-                    if !new_commits.is_empty() {
-                        for commit in &new_commits {
-                            updates.push(GithubUpdate {
-                                guild_id,
-                                channel_id,
-                                event: GithubEvent::CommitPushed {
-                                    owner: owner.to_string(),
-                                    repo: repo.to_string(),
-                                    branch: branch.clone(),
-                                    commit: commit.clone(),
-                                },
-                            });
-                        }
-                    }
+                    updates.extend(commit_updates(
+                        guild_id,
+                        channel_id,
+                        owner,
+                        repo,
+                        &branch,
+                        new_commits,
+                        resume_from,
+                    ));
                     continue;
                 } else {
                     // Empty branch or error listing commits
@@ -519,21 +941,42 @@ where
                 }
             }
 
+            // The last seen commit is gone (force-push) or further back than the
+            // client pages: start over from the branch head instead of posting
+            // everything that was listed
+            if !commits
+                .iter()
+                .any(|c| Some(&c.sha) == last_seen_sha.as_ref())
+            {
+                if let Some(latest) = commits.first() {
+                    tracing::warn!(
+                        "{}/{} branch {} no longer contains {}, re-baselining at {}",
+                        owner,
+                        repo,
+                        branch,
+                        last_seen_sha.as_deref().unwrap_or_default(),
+                        latest.sha
+                    );
+                    state
+                        .last_commit_shas
+                        .insert(branch.clone(), latest.sha.clone());
+                    dirty = true;
+                }
+                continue;
+            }
+
             let new_commits = collect_new_commits(&commits, last_seen_sha.as_deref());
 
             if !new_commits.is_empty() {
-                for commit in &new_commits {
-                    updates.push(GithubUpdate {
-                        guild_id,
-                        channel_id,
-                        event: GithubEvent::CommitPushed {
-                            owner: owner.to_string(),
-                            repo: repo.to_string(),
-                            branch: branch.clone(),
-                            commit: commit.clone(),
-                        },
-                    });
-                }
+                updates.extend(commit_updates(
+                    guild_id,
+                    channel_id,
+                    owner,
+                    repo,
+                    &branch,
+                    new_commits,
+                    state.clone(),
+                ));
                 if let Some(latest) = commits.first() {
                     state
                         .last_commit_shas
@@ -544,11 +987,13 @@ where
         }
 
         // Closed bugs
+        let now = Utc::now();
         let bug_issues = self
             .client
             .list_bug_issues(owner, repo, state.last_bug_closed_at)
             .await?;
-        let new_bugs = collect_closed_bugs(&bug_issues, state.last_bug_closed_at);
+        let new_bugs = collect_closed_bugs(&bug_issues, state.last_bug_closed_at, now);
+        let mut resume_from = state.clone();
         if let Some(last_closed_at) = new_bugs
             .last()
             .and_then(|issue| issue.closed_at)
@@ -559,7 +1004,14 @@ where
                 dirty = true;
             }
         }
+        let mut posted_up_to = None;
         for issue in new_bugs {
+            // Bugs closed in the same second share a resume point, so a failed
+            // one isn't skipped for having the watermark's timestamp
+            if posted_up_to.is_some() && issue.closed_at > posted_up_to {
+                resume_from.last_bug_closed_at = posted_up_to;
+            }
+            posted_up_to = issue.closed_at;
             updates.push(GithubUpdate {
                 guild_id,
                 channel_id,
@@ -568,6 +1020,11 @@ where
                     repo: repo.to_string(),
                     issue,
                 },
+                resume_from: vec![ResumePoint {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    state: resume_from.clone(),
+                }],
             });
         }
 
@@ -576,7 +1033,8 @@ where
             .client
             .list_general_issues(owner, repo, state.last_issue_updated_at)
             .await?;
-        let new_issue_events = collect_issue_events(&issues, state.last_issue_updated_at);
+        let new_issue_events = collect_issue_events(&issues, state.last_issue_updated_at, now);
+        let mut resume_from = state.clone();
         if let Some(latest) = issues
             .iter()
             .filter_map(|i| i.updated_at)
@@ -589,7 +1047,12 @@ where
             }
         }
 
+        let mut posted_up_to = None;
         for (issue, activity) in new_issue_events {
+            if posted_up_to.is_some() && issue.updated_at > posted_up_to {
+                resume_from.last_issue_updated_at = posted_up_to;
+            }
+            posted_up_to = issue.updated_at;
             updates.push(GithubUpdate {
                 guild_id,
                 channel_id,
@@ -599,40 +1062,220 @@ where
                     issue,
                     activity,
                 },
+                resume_from: vec![ResumePoint {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    state: resume_from.clone(),
+                }],
             });
         }
 
+        // Muted entries are still polled so their watermarks keep up, but
+        // nothing is posted (and no commit stats are fetched)
+        if entry.is_muted(Utc::now()) {
+            return Ok((Vec::new(), dirty));
+        }
+
+        // Digests only list each commit's first line, so they skip commit
+        // stats. `batch_commits` builds them once every repo is polled.
+        if entry.commit_digest {
+            return Ok((updates, dirty));
+        }
+
+        for update in &mut updates {
+            if let GithubEvent::CommitPushed { commit, .. } = &mut update.event {
+                self.attach_commit_stats(owner, repo, commit).await;
+            }
+        }
+
         Ok((updates, dirty))
     }
+
+    /// Fill in `commit.stats` if enabled. Failures (e.g. rate limits) only drop
+    /// the stats, never the notification.
+    async fn attach_commit_stats(&self, owner: &str, repo: &str, commit: &mut Commit) {
+        if !self.fetch_commit_stats || commit.stats.is_some() {
+            return;
+        }
+        match self.client.commit_stats(owner, repo, &commit.sha).await {
+            Ok(stats) => commit.stats = stats,
+            Err(err) => tracing::debug!(
+                owner,
+                repo,
+                sha = %commit.sha,
+                error = %err,
+                "Skipping commit stats"
+            ),
+        }
+    }
 }
 
-/// Collect only commits that happened after `last_seen`.
-fn collect_new_commits(commits: &[Commit], last_seen: Option<&str>) -> Vec<Commit> {
-    let mut new_commits = Vec::new();
+/// One `CommitPushed` update per commit (oldest first) on `branch`. The
+/// first resumes from `resume_from`, each later one from the commit before it.
+fn commit_updates(
+    guild_id: u64,
+    channel_id: u64,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    commits: Vec<Commit>,
+    mut resume_from: RepoTrackingData,
+) -> Vec<GithubUpdate> {
+    let mut updates = Vec::with_capacity(commits.len());
     for commit in commits {
-        if Some(commit.sha.as_str()) == last_seen {
-            break;
-        }
-        new_commits.push(commit.clone());
+        let sha = commit.sha.clone();
+        updates.push(GithubUpdate {
+            guild_id,
+            channel_id,
+            event: GithubEvent::CommitPushed {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                branch: branch.to_string(),
+                commit,
+            },
+            resume_from: vec![ResumePoint {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                state: resume_from.clone(),
+            }],
+        });
+        resume_from.last_commit_shas.insert(branch.to_string(), sha);
     }
-    new_commits.reverse(); // Oldest first for nicer Discord ordering
-    new_commits
+    updates
 }
 
-/// Pick the most recent closed bug time so we can update the watermark.
-fn latest_closed_timestamp(issues: &[Issue]) -> Option<DateTime<Utc>> {
-    issues.iter().filter_map(|i| i.closed_at).max()
+/// An entry's updates for one poll as they should be posted: unchanged, or
+/// with every commit batched into one `CommitDigest` for entries in digest
+/// mode.
+fn batch_commits(entry: &GithubTrackingEntry, updates: Vec<GithubUpdate>) -> Vec<GithubUpdate> {
+    if entry.commit_digest {
+        into_commit_digest(updates)
+    } else {
+        updates
+    }
 }
 
-/// Identify newly closed bug issues compared to the stored baseline.
-fn collect_closed_bugs(
-    issues: &[Issue],
+/// Replace the `CommitPushed` updates (of every repository in `updates`) with
+/// a single `CommitDigest` in place of the first one. Other events are kept as
+/// they are.
+fn into_commit_digest(updates: Vec<GithubUpdate>) -> Vec<GithubUpdate> {
+    let mut result = Vec::with_capacity(updates.len());
+    let mut digest_at = None;
+    for update in updates {
+        let GithubUpdate {
+            guild_id,
+            channel_id,
+            event,
+            resume_from,
+        } = update;
+        let GithubEvent::CommitPushed {
+            owner,
+            repo,
+            branch,
+            commit,
+        } = event
+        else {
+            result.push(GithubUpdate {
+                guild_id,
+                channel_id,
+                event,
+                resume_from,
+            });
+            continue;
+        };
+
+        let digest = *digest_at.get_or_insert_with(|| {
+            result.push(GithubUpdate {
+                guild_id,
+                channel_id,
+                event: GithubEvent::CommitDigest {
+                    owner,
+                    commits: Vec::new(),
+                },
+                resume_from: Vec::new(),
+            });
+            result.len() - 1
+        });
+        let digest = &mut result[digest];
+        // The digest goes out as one message, so each repository resumes where
+        // its first commit would have
+        for point in resume_from {
+            if !digest
+                .repositories()
+                .any(|(_, listed)| listed == point.repo)
+            {
+                digest.resume_from.push(point);
+            }
+        }
+        if let GithubEvent::CommitDigest { commits, .. } = &mut digest.event {
+            commits.push(DigestCommit {
+                repo,
+                branch,
+                commit,
+            });
+        }
+    }
+    result
+}
+
+/// Match `text` against a glob where `*` matches any run of characters
+/// (including `/`) and `?` matches exactly one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Last `*` seen and the text position it is currently matched up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Collect only commits that happened after `last_seen`.
+fn collect_new_commits(commits: &[Commit], last_seen: Option<&str>) -> Vec<Commit> {
+    let mut new_commits = Vec::new();
+    for commit in commits {
+        if Some(commit.sha.as_str()) == last_seen {
+            break;
+        }
+        new_commits.push(commit.clone());
+    }
+    new_commits.reverse(); // Oldest first for nicer Discord ordering
+    new_commits
+}
+
+/// Pick the most recent closed bug time so we can update the watermark.
+fn latest_closed_timestamp(issues: &[Issue]) -> Option<DateTime<Utc>> {
+    issues.iter().filter_map(|i| i.closed_at).max()
+}
+
+/// Identify newly closed bug issues compared to the stored baseline. Without
+/// a baseline, only bugs closed shortly before `now` count.
+fn collect_closed_bugs(
+    issues: &[Issue],
     last_closed: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
     // returns only closed bugs newer than baseline
 ) -> Vec<Issue> {
     let mut newly_closed = Vec::new();
     let baseline = last_closed.unwrap_or(DateTime::<Utc>::MIN_UTC);
-    let first_run_cutoff = Utc::now() - Duration::minutes(30);
+    let first_run_cutoff = now - Duration::minutes(FIRST_RUN_WINDOW_MINUTES);
 
     for issue in issues {
         if issue.state != IssueState::Closed || !issue.is_bug {
@@ -653,12 +1296,14 @@ fn collect_closed_bugs(
 }
 
 /// Determine whether an issue event should be surfaced based on when we last checked.
+/// Without a baseline, only issues opened shortly before `now` count.
 fn collect_issue_events(
     issues: &[Issue],
     baseline: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
 ) -> Vec<(Issue, IssueActivity)> {
     let mut events = Vec::new();
-    let first_run_cutoff = Utc::now() - Duration::minutes(30);
+    let first_run_cutoff = now - Duration::minutes(FIRST_RUN_WINDOW_MINUTES);
 
     for issue in issues {
         if issue.is_bug {
@@ -709,9 +1354,14 @@ mod tests {
     use super::*;
     use std::sync::Mutex;
 
+    /// Scripted responses, the same whatever `since` or `until_sha` is asked
+    /// for; the service has to filter what it already saw.
+    #[derive(Default)]
     struct MockGithubClient {
         branches: Vec<String>,
         commits: HashMap<String, Vec<Commit>>,
+        bug_issues: Vec<Issue>,
+        general_issues: Vec<Issue>,
     }
 
     #[async_trait]
@@ -732,6 +1382,7 @@ mod tests {
             _repo: &str,
             branch: &str,
             _per_page: usize,
+            _until_sha: Option<&str>,
         ) -> Result<Vec<Commit>, GithubError> {
             Ok(self.commits.get(branch).cloned().unwrap_or_default())
         }
@@ -741,7 +1392,7 @@ mod tests {
             _repo: &str,
             _since: Option<DateTime<Utc>>,
         ) -> Result<Vec<Issue>, GithubError> {
-            Ok(vec![])
+            Ok(self.bug_issues.clone())
         }
         async fn list_general_issues(
             &self,
@@ -749,7 +1400,7 @@ mod tests {
             _repo: &str,
             _since: Option<DateTime<Utc>>,
         ) -> Result<Vec<Issue>, GithubError> {
-            Ok(vec![])
+            Ok(self.general_issues.clone())
         }
     }
 
@@ -768,6 +1419,18 @@ mod tests {
         }
     }
 
+    /// A repo with only a `main` branch, listing `shas` newest first.
+    fn commits_on(shas: &[&str]) -> MockGithubClient {
+        MockGithubClient {
+            branches: vec!["main".to_string()],
+            commits: HashMap::from([(
+                "main".to_string(),
+                shas.iter().map(|sha| create_commit(sha)).collect(),
+            )]),
+            ..Default::default()
+        }
+    }
+
     fn create_commit(sha: &str) -> Commit {
         Commit {
             sha: sha.to_string(),
@@ -776,9 +1439,19 @@ mod tests {
             html_url: "url".to_string(),
             avatar_url: None,
             committed_at: Some(Utc::now()),
+            stats: None,
         }
     }
 
+    /// Poll and commit every watermark, as if all updates were posted.
+    async fn poll_and_commit<C: GithubClient>(
+        service: &GithubService<C, MockStore>,
+    ) -> Vec<GithubUpdate> {
+        let poll = service.poll_updates().await.unwrap();
+        service.commit_watermarks(poll.watermarks).await.unwrap();
+        poll.updates
+    }
+
     #[tokio::test]
     async fn test_new_branch_detection() {
         let mut commits = HashMap::new();
@@ -787,6 +1460,7 @@ mod tests {
         let client = MockGithubClient {
             branches: vec!["main".to_string()],
             commits,
+            ..Default::default()
         };
         let store = MockStore {
             config: Mutex::new(GithubConfig::default()),
@@ -800,7 +1474,7 @@ mod tests {
             .unwrap();
 
         // 2. First poll (baseline)
-        let updates = service.poll_updates().await.unwrap();
+        let updates = poll_and_commit(&service).await;
         assert!(updates.is_empty(), "First poll should be quiet baseline");
 
         // 3. Add a new branch with a NEW commit
@@ -811,11 +1485,12 @@ mod tests {
         let client_v2 = MockGithubClient {
             branches: vec!["main".to_string(), "feat".to_string()],
             commits: new_commits,
+            ..Default::default()
         };
         // Re-inject client (simulated by service update or new service with same store)
         let service_v2 = GithubService::new(client_v2, service.store).await.unwrap();
 
-        let updates = service_v2.poll_updates().await.unwrap();
+        let updates = poll_and_commit(&service_v2).await;
         assert_eq!(
             updates.len(),
             1,
@@ -829,6 +1504,437 @@ mod tests {
         }
     }
 
+    /// An organization whose repos each have one `main` commit, `<repo>-<head>`.
+    struct OrgClient {
+        repos: Vec<String>,
+        /// Each repo's history is commits 1 to `head`
+        head: u32,
+    }
+
+    #[async_trait]
+    impl GithubClient for OrgClient {
+        async fn list_org_repos(&self, _org: &str) -> Result<Vec<String>, GithubError> {
+            Ok(self.repos.clone())
+        }
+        async fn list_branches(
+            &self,
+            _owner: &str,
+            _repo: &str,
+        ) -> Result<Vec<String>, GithubError> {
+            Ok(vec!["main".to_string()])
+        }
+        async fn list_commits(
+            &self,
+            _owner: &str,
+            repo: &str,
+            _branch: &str,
+            _per_page: usize,
+            _until_sha: Option<&str>,
+        ) -> Result<Vec<Commit>, GithubError> {
+            Ok((1..=self.head)
+                .rev()
+                .map(|n| create_commit(&format!("{}-{}", repo, n)))
+                .collect())
+        }
+        async fn list_bug_issues(
+            &self,
+            _owner: &str,
+            _repo: &str,
+            _since: Option<DateTime<Utc>>,
+        ) -> Result<Vec<Issue>, GithubError> {
+            Ok(vec![])
+        }
+        async fn list_general_issues(
+            &self,
+            _owner: &str,
+            _repo: &str,
+            _since: Option<DateTime<Utc>>,
+        ) -> Result<Vec<Issue>, GithubError> {
+            Ok(vec![])
+        }
+    }
+
+    fn org_client(repos: &[&str], head: u32) -> OrgClient {
+        OrgClient {
+            repos: repos.iter().map(|repo| repo.to_string()).collect(),
+            head,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_org_refresh_picks_up_new_repos() {
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(org_client(&["a"], 1), store)
+            .await
+            .unwrap();
+        service.track_organization(1, "org", 100).await.unwrap();
+        assert!(poll_and_commit(&service).await.is_empty(), "Baseline");
+
+        // A repo is created, but the org was listed moments ago
+        let service = GithubService::new(org_client(&["a", "b"], 1), service.store)
+            .await
+            .unwrap();
+        assert!(poll_and_commit(&service).await.is_empty());
+        assert_eq!(service.list_entries(1).await[0].org_repos, vec!["a"]);
+
+        // Once the refresh is due the new repo is tracked, with a quiet baseline
+        let service = GithubService::new(org_client(&["a", "b"], 1), service.store)
+            .await
+            .unwrap()
+            .with_org_refresh_interval(Duration::zero());
+        assert!(poll_and_commit(&service).await.is_empty(), "No flood");
+        let entry = &service.list_entries(1).await[0];
+        assert_eq!(entry.org_repos, vec!["a", "b"]);
+        assert!(entry.repo_data.contains_key("org/b"));
+
+        // New commits in both repos are reported
+        let service = GithubService::new(org_client(&["a", "b"], 2), service.store)
+            .await
+            .unwrap();
+        let shas: Vec<String> = poll_and_commit(&service)
+            .await
+            .into_iter()
+            .filter_map(|update| match update.event {
+                GithubEvent::CommitPushed { commit, .. } => Some(commit.sha),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(shas, vec!["a-2", "b-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_org_digest_covers_every_repo() {
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(org_client(&["a", "b"], 1), store)
+            .await
+            .unwrap();
+        service.track_organization(1, "org", 100).await.unwrap();
+        assert!(service
+            .set_commit_digest(1, "org", None, true)
+            .await
+            .unwrap());
+        poll_and_commit(&service).await;
+
+        let service = GithubService::new(org_client(&["a", "b"], 3), service.store)
+            .await
+            .unwrap();
+        let mut poll = service.poll_updates().await.unwrap();
+        assert_eq!(poll.updates.len(), 1, "One digest for the whole org");
+        let GithubEvent::CommitDigest { owner, commits } = &poll.updates[0].event else {
+            panic!("Expected a commit digest");
+        };
+        assert_eq!(owner, "org");
+        let listed: Vec<String> = commits
+            .iter()
+            .map(|c| format!("{}:{}", c.repo, c.commit.sha))
+            .collect();
+        assert_eq!(listed, vec!["a:a-2", "a:a-3", "b:b-2", "b:b-3"]);
+
+        // If the digest doesn't go out, both repos report their commits again
+        let failed = poll.updates[0].clone();
+        poll.watermarks.hold_back(&failed);
+        service.commit_watermarks(poll.watermarks).await.unwrap();
+        let retry = poll_and_commit(&service).await;
+        let GithubEvent::CommitDigest { commits, .. } = &retry[0].event else {
+            panic!("Expected a commit digest");
+        };
+        assert_eq!(commits.len(), 4);
+        assert!(poll_and_commit(&service).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_remove_entry_by_key() {
+        let client = MockGithubClient {
+            branches: vec![],
+            commits: HashMap::new(),
+            ..Default::default()
+        };
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(client, store).await.unwrap();
+        service
+            .track_repository(1, "owner", "repo", 100)
+            .await
+            .unwrap();
+        service
+            .track_repository(1, "owner", "other", 100)
+            .await
+            .unwrap();
+        service.track_organization(1, "owner", 100).await.unwrap();
+
+        let keys: Vec<String> = service
+            .list_entries(1)
+            .await
+            .iter()
+            .map(GithubTrackingEntry::key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["repo:owner/repo", "repo:owner/other", "org:owner"]
+        );
+
+        // The org shares its owner with the repos but only the org goes
+        let removed = service.remove_entry(1, "org:owner").await.unwrap().unwrap();
+        assert_eq!(removed.display_name(), "owner");
+        let removed = service.remove_entry(1, "repo:owner/repo").await.unwrap();
+        assert_eq!(removed.unwrap().display_name(), "owner/repo");
+
+        let remaining = service.list_entries(1).await;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].key(), "repo:owner/other");
+        assert_eq!(service.store.config.lock().unwrap().guilds[&1].len(), 1);
+
+        // Already gone, or another guild
+        assert!(service
+            .remove_entry(1, "repo:owner/repo")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(service
+            .remove_entry(2, "repo:owner/other")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_preview_does_not_advance_watermarks() {
+        let mut commits = HashMap::new();
+        commits.insert("main".to_string(), vec![create_commit("sha1")]);
+
+        let client = MockGithubClient {
+            branches: vec!["main".to_string()],
+            commits,
+            ..Default::default()
+        };
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(client, store).await.unwrap();
+
+        service
+            .track_repository(1, "owner", "repo", 100)
+            .await
+            .unwrap();
+        service
+            .track_repository(2, "other", "repo", 200)
+            .await
+            .unwrap();
+        poll_and_commit(&service).await;
+
+        let mut new_commits = HashMap::new();
+        new_commits.insert(
+            "main".to_string(),
+            vec![create_commit("sha2"), create_commit("sha1")],
+        );
+        let client_v2 = MockGithubClient {
+            branches: vec!["main".to_string()],
+            commits: new_commits,
+            ..Default::default()
+        };
+        let service_v2 = GithubService::new(client_v2, service.store).await.unwrap();
+
+        // Previews only cover the requested guild and can be repeated
+        for _ in 0..2 {
+            let preview = service_v2.preview_updates(1).await.unwrap();
+            assert_eq!(preview.len(), 1);
+            assert_eq!(preview[0].guild_id, 1);
+            assert_eq!(preview[0].channel_id, 100);
+        }
+
+        // The real poll still reports the previewed commit, for both guilds
+        let updates = poll_and_commit(&service_v2).await;
+        assert_eq!(updates.len(), 2);
+        assert!(poll_and_commit(&service_v2).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_dispatch_keeps_watermark() {
+        let mut commits = HashMap::new();
+        commits.insert("main".to_string(), vec![create_commit("sha1")]);
+
+        let client = MockGithubClient {
+            branches: vec!["main".to_string()],
+            commits,
+            ..Default::default()
+        };
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(client, store).await.unwrap();
+
+        service
+            .track_repository(1, "owner", "repo", 100)
+            .await
+            .unwrap();
+        service
+            .track_repository(2, "owner", "repo", 200)
+            .await
+            .unwrap();
+        poll_and_commit(&service).await;
+
+        let mut new_commits = HashMap::new();
+        new_commits.insert(
+            "main".to_string(),
+            vec![create_commit("sha2"), create_commit("sha1")],
+        );
+        let client_v2 = MockGithubClient {
+            branches: vec!["main".to_string()],
+            commits: new_commits,
+            ..Default::default()
+        };
+        let service_v2 = GithubService::new(client_v2, service.store).await.unwrap();
+
+        // Posting guild 1's update fails; guild 2's goes out
+        let mut poll = service_v2.poll_updates().await.unwrap();
+        assert_eq!(poll.updates.len(), 2);
+        let failed = poll
+            .updates
+            .iter()
+            .find(|update| update.guild_id == 1)
+            .unwrap()
+            .clone();
+        poll.watermarks.hold_back(&failed);
+        service_v2.commit_watermarks(poll.watermarks).await.unwrap();
+
+        // Only the failed update is reported again
+        let retry = poll_and_commit(&service_v2).await;
+        assert_eq!(retry.len(), 1);
+        assert_eq!(retry[0].guild_id, 1);
+        if let GithubEvent::CommitPushed { commit, .. } = &retry[0].event {
+            assert_eq!(commit.sha, "sha2");
+        } else {
+            panic!("Unexpected event type");
+        }
+        assert!(poll_and_commit(&service_v2).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_dispatch_resumes_at_the_failed_update() {
+        let service = tracked_service(repo_client(&["sha1"], vec![], vec![])).await;
+        poll_and_commit(&service).await;
+
+        let client_v2 = repo_client(&["sha4", "sha3", "sha2", "sha1"], vec![], vec![]);
+        let service_v2 = GithubService::new(client_v2, service.store).await.unwrap();
+
+        // sha2 went out, sha3 failed and sha4 was held back behind it
+        let mut poll = service_v2.poll_updates().await.unwrap();
+        assert_eq!(poll.updates.len(), 3);
+        for failed in &poll.updates[1..] {
+            poll.watermarks.hold_back(failed);
+        }
+        service_v2.commit_watermarks(poll.watermarks).await.unwrap();
+
+        let retry = poll_and_commit(&service_v2).await;
+        let shas: Vec<&str> = retry
+            .iter()
+            .map(|update| match &update.event {
+                GithubEvent::CommitPushed { commit, .. } => commit.sha.as_str(),
+                other => panic!("Unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(shas, vec!["sha3", "sha4"]);
+    }
+
+    #[tokio::test]
+    async fn test_preview_repository_prefers_main_and_stores_nothing() {
+        let mut commits = HashMap::new();
+        commits.insert("feat".to_string(), vec![create_commit("sha2")]);
+        commits.insert(
+            "main".to_string(),
+            vec![create_commit("sha1"), create_commit("sha0")],
+        );
+
+        let client = MockGithubClient {
+            branches: vec!["feat".to_string(), "main".to_string()],
+            commits,
+            ..Default::default()
+        };
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(client, store).await.unwrap();
+
+        let events = service.preview_repository("owner", "repo").await.unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            GithubEvent::CommitPushed { branch, commit, .. } => {
+                assert_eq!(branch, "main");
+                assert_eq!(commit.sha, "sha1");
+            }
+            other => panic!("Unexpected event {:?}", other),
+        }
+        assert!(service.list_entries(1).await.is_empty());
+    }
+
+    #[test]
+    fn test_branch_filter_globs() {
+        let mut entry = GithubTrackingEntry::new_repo("owner", "repo", 100);
+        let branches = [
+            "main",
+            "release/1.0",
+            "release/2.0-rc",
+            "feature/x",
+            "mainline",
+        ];
+        assert!(branches.iter().all(|b| entry.tracks_branch(b)));
+
+        entry.branches = vec!["main".to_string(), "release/*".to_string()];
+        let tracked: Vec<_> = branches
+            .iter()
+            .filter(|b| entry.tracks_branch(b))
+            .copied()
+            .collect();
+        assert_eq!(tracked, vec!["main", "release/1.0", "release/2.0-rc"]);
+
+        assert!(glob_match("*-rc", "release/2.0-rc"));
+        assert!(glob_match("v?.*", "v1.2"));
+        assert!(!glob_match("v?.*", "v10.2"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[tokio::test]
+    async fn test_filtered_branches_get_no_watermark() {
+        let mut commits = HashMap::new();
+        commits.insert("main".to_string(), vec![create_commit("sha1")]);
+        commits.insert("feature/x".to_string(), vec![create_commit("sha2")]);
+
+        let client = MockGithubClient {
+            branches: vec!["main".to_string(), "feature/x".to_string()],
+            commits,
+            ..Default::default()
+        };
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(client, store).await.unwrap();
+
+        service
+            .track_repository(1, "owner", "repo", 100)
+            .await
+            .unwrap();
+        assert!(service
+            .set_branch_filter(1, "owner", Some("repo"), vec!["main".to_string()])
+            .await
+            .unwrap());
+        assert!(!service
+            .set_branch_filter(1, "owner", Some("other"), vec![])
+            .await
+            .unwrap());
+        poll_and_commit(&service).await;
+
+        let config = service.store.config.lock().unwrap().clone();
+        let entry = &config.guilds[&1][0];
+        assert_eq!(entry.branches, vec!["main".to_string()]);
+        assert_eq!(entry.last_commit_shas.len(), 1);
+        assert!(entry.last_commit_shas.contains_key("main"));
+    }
+
     #[tokio::test]
     async fn test_new_branch_from_main_no_new_commits_is_quiet() {
         let mut commits = HashMap::new();
@@ -837,6 +1943,7 @@ mod tests {
         let client = MockGithubClient {
             branches: vec!["main".to_string()],
             commits,
+            ..Default::default()
         };
         let store = MockStore {
             config: Mutex::new(GithubConfig::default()),
@@ -847,7 +1954,7 @@ mod tests {
             .track_repository(1, "owner", "repo", 100)
             .await
             .unwrap();
-        service.poll_updates().await.unwrap();
+        poll_and_commit(&service).await;
 
         // Add a new branch pointing to the SAME commit
         let mut new_commits = HashMap::new();
@@ -857,10 +1964,11 @@ mod tests {
         let client_v2 = MockGithubClient {
             branches: vec!["main".to_string(), "feat".to_string()],
             commits: new_commits,
+            ..Default::default()
         };
         let service_v2 = GithubService::new(client_v2, service.store).await.unwrap();
 
-        let updates = service_v2.poll_updates().await.unwrap();
+        let updates = poll_and_commit(&service_v2).await;
         assert!(
             updates.is_empty(),
             "Should be quiet if the branch has no new unique commits"
@@ -887,6 +1995,7 @@ mod tests {
         let client = MockGithubClient {
             branches: vec!["main".to_string()],
             commits,
+            ..Default::default()
         };
         let store = MockStore {
             config: Mutex::new(GithubConfig::default()),
@@ -898,7 +2007,7 @@ mod tests {
             .track_repository(1, "owner", "repo", 100)
             .await
             .unwrap();
-        let updates = service.poll_updates().await.unwrap();
+        let updates = poll_and_commit(&service).await;
         assert!(updates.is_empty(), "First poll should be quiet");
 
         // Now add branch B created from main (sha3) with new commits sha5 (newest), sha4
@@ -926,10 +2035,11 @@ mod tests {
         let client_v2 = MockGithubClient {
             branches: vec!["main".to_string(), "feature-b".to_string()],
             commits: new_commits,
+            ..Default::default()
         };
         let service_v2 = GithubService::new(client_v2, service.store).await.unwrap();
 
-        let updates = service_v2.poll_updates().await.unwrap();
+        let updates = poll_and_commit(&service_v2).await;
 
         // Should only report sha4 and sha5 (the 2 new commits), NOT sha1, sha2, sha3
         assert_eq!(
@@ -952,4 +2062,303 @@ mod tests {
             "Should report sha4 and sha5 in order"
         );
     }
+
+    #[tokio::test]
+    async fn test_muted_repo_advances_watermarks_quietly() {
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(commits_on(&["sha1"]), store)
+            .await
+            .unwrap();
+        service
+            .track_repository(1, "owner", "repo", 100)
+            .await
+            .unwrap();
+        poll_and_commit(&service).await;
+        assert!(service.mute(1, "Owner", Some("Repo"), None).await.unwrap());
+        assert!(!service.mute(1, "owner", None, None).await.unwrap());
+
+        // Pushed while muted: nothing is posted, but the watermark moves on
+        let service = GithubService::new(commits_on(&["sha2", "sha1"]), service.store)
+            .await
+            .unwrap();
+        assert!(poll_and_commit(&service).await.is_empty());
+        let config = service.store.config.lock().unwrap().clone();
+        assert_eq!(config.guilds[&1][0].last_commit_shas["main"], "sha2");
+
+        // After unmuting only newer commits are reported
+        assert!(service.unmute(1, "owner", Some("repo")).await.unwrap());
+        assert!(poll_and_commit(&service).await.is_empty());
+        let service = GithubService::new(commits_on(&["sha3", "sha2", "sha1"]), service.store)
+            .await
+            .unwrap();
+        let updates = poll_and_commit(&service).await;
+        assert_eq!(updates.len(), 1);
+
+        // A mute that has run out no longer holds anything back
+        let ended = Utc::now() - Duration::minutes(1);
+        assert!(service
+            .mute(1, "owner", Some("repo"), Some(ended))
+            .await
+            .unwrap());
+        let service = GithubService::new(commits_on(&["sha4", "sha3"]), service.store)
+            .await
+            .unwrap();
+        assert_eq!(poll_and_commit(&service).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_digest_mode_batches_a_poll_into_one_update() {
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(commits_on(&["sha0"]), store)
+            .await
+            .unwrap();
+        service
+            .track_repository(1, "owner", "repo", 100)
+            .await
+            .unwrap();
+        poll_and_commit(&service).await;
+        assert!(service
+            .set_commit_digest(1, "owner", Some("repo"), true)
+            .await
+            .unwrap());
+
+        // Newest first, as GitHub lists them
+        let shas: Vec<String> = (0..=20).rev().map(|i| format!("sha{i}")).collect();
+        let shas: Vec<&str> = shas.iter().map(String::as_str).collect();
+        let service = GithubService::new(commits_on(&shas), service.store)
+            .await
+            .unwrap();
+        let updates = poll_and_commit(&service).await;
+
+        assert_eq!(updates.len(), 1, "20 commits should become one digest");
+        assert_eq!(updates[0].channel_id, 100);
+        let GithubEvent::CommitDigest { commits, .. } = &updates[0].event else {
+            panic!("Expected a commit digest");
+        };
+        let listed: Vec<&str> = commits.iter().map(|c| c.commit.sha.as_str()).collect();
+        let expected: Vec<String> = (1..=20).map(|i| format!("sha{i}")).collect();
+        assert_eq!(listed, expected, "Digest lists commits oldest first");
+        assert!(commits.iter().all(|c| c.branch == "main"));
+        let config = service.store.config.lock().unwrap().clone();
+        assert_eq!(config.guilds[&1][0].last_commit_shas["main"], "sha20");
+    }
+
+    fn create_issue(number: u64, is_bug: bool, updated_at: DateTime<Utc>) -> Issue {
+        Issue {
+            number,
+            title: format!("Issue {number}"),
+            html_url: "url".to_string(),
+            reporter: None,
+            assignee: None,
+            closed_by: None,
+            labels: Vec::new(),
+            state: IssueState::Open,
+            created_at: Some(updated_at - Duration::days(3)),
+            updated_at: Some(updated_at),
+            closed_at: None,
+            is_bug,
+        }
+    }
+
+    fn closed_bug(number: u64, closed_at: DateTime<Utc>) -> Issue {
+        Issue {
+            state: IssueState::Closed,
+            closed_at: Some(closed_at),
+            ..create_issue(number, true, closed_at)
+        }
+    }
+
+    /// A `main` branch with `shas` (newest first) and the given issues.
+    fn repo_client(shas: &[&str], bugs: Vec<Issue>, issues: Vec<Issue>) -> MockGithubClient {
+        MockGithubClient {
+            bug_issues: bugs,
+            general_issues: issues,
+            ..commits_on(shas)
+        }
+    }
+
+    async fn tracked_service(
+        client: MockGithubClient,
+    ) -> GithubService<MockGithubClient, MockStore> {
+        let store = MockStore {
+            config: Mutex::new(GithubConfig::default()),
+        };
+        let service = GithubService::new(client, store).await.unwrap();
+        service
+            .track_repository(1, "owner", "repo", 100)
+            .await
+            .unwrap();
+        service
+    }
+
+    #[test]
+    fn test_first_run_cutoff_for_closed_bugs() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let bugs = vec![
+            closed_bug(1, now - Duration::minutes(40)),
+            closed_bug(2, now - Duration::minutes(20)),
+        ];
+
+        let announced = collect_closed_bugs(&bugs, None, now);
+        let numbers: Vec<u64> = announced.iter().map(|issue| issue.number).collect();
+        assert_eq!(numbers, vec![2]);
+
+        // With a baseline the window no longer applies
+        let baseline = now - Duration::hours(1);
+        assert_eq!(collect_closed_bugs(&bugs, Some(baseline), now).len(), 2);
+    }
+
+    #[test]
+    fn test_first_run_cutoff_for_issue_events() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let opened = |number, minutes_ago| Issue {
+            created_at: Some(now - Duration::minutes(minutes_ago)),
+            ..create_issue(number, false, now - Duration::minutes(minutes_ago))
+        };
+        let issues = vec![opened(1, 40), opened(2, 20)];
+
+        let events = collect_issue_events(&issues, None, now);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0.number, 2);
+        assert_eq!(events[0].1, IssueActivity::Opened);
+    }
+
+    #[tokio::test]
+    async fn test_first_poll_suppresses_history_but_records_watermarks() {
+        let old = Utc::now() - Duration::days(2);
+        let service = tracked_service(repo_client(
+            &["sha2", "sha1"],
+            vec![closed_bug(1, old)],
+            vec![create_issue(2, false, old)],
+        ))
+        .await;
+
+        assert!(poll_and_commit(&service).await.is_empty());
+        let config = service.store.config.lock().unwrap().clone();
+        let entry = &config.guilds[&1][0];
+        assert_eq!(entry.last_commit_shas["main"], "sha2");
+        assert_eq!(entry.last_bug_closed_at, Some(old));
+        assert_eq!(entry.last_issue_updated_at, Some(old));
+
+        // A bug closed just before tracking started is still announced
+        let recent = Utc::now() - Duration::minutes(5);
+        let service = tracked_service(repo_client(
+            &["sha1"],
+            vec![closed_bug(1, old), closed_bug(3, recent)],
+            vec![],
+        ))
+        .await;
+        let updates = poll_and_commit(&service).await;
+        assert_eq!(updates.len(), 1);
+        assert!(
+            matches!(&updates[0].event, GithubEvent::BugClosed { issue, .. } if issue.number == 3)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_commit_after_baseline_is_one_event() {
+        let service = tracked_service(repo_client(&["sha1"], vec![], vec![])).await;
+        assert!(poll_and_commit(&service).await.is_empty());
+
+        let service = GithubService::new(
+            repo_client(&["sha2", "sha1"], vec![], vec![]),
+            service.store,
+        )
+        .await
+        .unwrap();
+        let updates = poll_and_commit(&service).await;
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].channel_id, 100);
+        let GithubEvent::CommitPushed { branch, commit, .. } = &updates[0].event else {
+            panic!("Expected a commit");
+        };
+        assert_eq!((branch.as_str(), commit.sha.as_str()), ("main", "sha2"));
+    }
+
+    #[tokio::test]
+    async fn test_force_push_rebaselines_quietly() {
+        let service = tracked_service(repo_client(&["sha1"], vec![], vec![])).await;
+        assert!(poll_and_commit(&service).await.is_empty());
+
+        // History was rewritten: sha1 is gone from the branch
+        let service = GithubService::new(
+            repo_client(&["sha3", "sha2", "base"], vec![], vec![]),
+            service.store,
+        )
+        .await
+        .unwrap();
+        assert!(poll_and_commit(&service).await.is_empty());
+        let config = service.store.config.lock().unwrap().clone();
+        assert_eq!(config.guilds[&1][0].last_commit_shas["main"], "sha3");
+    }
+
+    #[tokio::test]
+    async fn test_bug_closed_after_baseline() {
+        let old = Utc::now() - Duration::days(2);
+        let service =
+            tracked_service(repo_client(&["sha1"], vec![closed_bug(1, old)], vec![])).await;
+        assert!(poll_and_commit(&service).await.is_empty());
+
+        // The old close is listed again, but only the new one is reported
+        let closed_at = Utc::now() - Duration::minutes(1);
+        let bugs = vec![closed_bug(1, old), closed_bug(2, closed_at)];
+        let service = GithubService::new(repo_client(&["sha1"], bugs, vec![]), service.store)
+            .await
+            .unwrap();
+        let updates = poll_and_commit(&service).await;
+        assert_eq!(updates.len(), 1);
+        let GithubEvent::BugClosed { issue, .. } = &updates[0].event else {
+            panic!("Expected a closed bug");
+        };
+        assert_eq!(issue.number, 2);
+    }
+
+    #[tokio::test]
+    async fn test_watermarks_advance_across_polls() {
+        let old = Utc::now() - Duration::days(2);
+        let service = tracked_service(repo_client(
+            &["sha1"],
+            vec![closed_bug(1, old)],
+            vec![create_issue(5, false, old)],
+        ))
+        .await;
+        assert!(poll_and_commit(&service).await.is_empty());
+
+        // One of everything: a push, a bug close and an issue comment
+        let now = Utc::now();
+        let second_poll = || {
+            repo_client(
+                &["sha2", "sha1"],
+                vec![closed_bug(1, old), closed_bug(2, now)],
+                vec![create_issue(5, false, now)],
+            )
+        };
+        let service = GithubService::new(second_poll(), service.store)
+            .await
+            .unwrap();
+        let updates = poll_and_commit(&service).await;
+        assert_eq!(updates.len(), 3);
+        assert!(matches!(
+            &updates[2].event,
+            GithubEvent::IssueActivity {
+                activity: IssueActivity::Updated,
+                ..
+            }
+        ));
+        let config = service.store.config.lock().unwrap().clone();
+        let entry = &config.guilds[&1][0];
+        assert_eq!(entry.last_commit_shas["main"], "sha2");
+        assert_eq!(entry.last_bug_closed_at, Some(now));
+        assert_eq!(entry.last_issue_updated_at, Some(now));
+
+        // The same responses again are nothing new
+        let service = GithubService::new(second_poll(), service.store)
+            .await
+            .unwrap();
+        assert!(poll_and_commit(&service).await.is_empty());
+    }
 }
@@ -0,0 +1,127 @@
+// Lightweight localization for user-facing strings.
+//
+// **How it works:**
+// Each locale is a flat map of `key -> template`. Templates use `{name}`
+// placeholders that get replaced with the arguments passed to `t()`.
+//
+// ```ignore
+// let text = localizer.t(Some("es-ES"), "daily.xp", &[("xp", "25".to_string())]);
+// ```
+//
+// **Fallback chain:** exact locale ("es-es") → language ("es") → default locale ("en")
+// → the key itself. A missing translation never breaks a command, it just shows English.
+//
+// This file is pure logic with no I/O. Loading the locale files is the infra layer's job.
+
+use crate::core::util::fill_placeholders;
+use std::collections::HashMap;
+
+/// A flat map of translation keys to templates for one locale.
+pub type LocaleStrings = HashMap<String, String>;
+
+/// Holds every loaded locale and resolves keys with fallbacks.
+#[derive(Debug, Clone)]
+pub struct Localizer {
+    default_locale: String,
+    locales: HashMap<String, LocaleStrings>,
+}
+
+impl Localizer {
+    /// Create an empty localizer. `default_locale` is used when nothing else matches.
+    pub fn new(default_locale: &str) -> Self {
+        Self {
+            default_locale: normalize_locale(default_locale),
+            locales: HashMap::new(),
+        }
+    }
+
+    /// Register (or extend) a locale. Later calls override earlier keys.
+    pub fn add_locale(&mut self, locale: &str, strings: LocaleStrings) {
+        self.locales
+            .entry(normalize_locale(locale))
+            .or_default()
+            .extend(strings);
+    }
+
+    /// List loaded locale codes (sorted) - handy for startup logs.
+    pub fn available_locales(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self.locales.keys().cloned().collect();
+        locales.sort();
+        locales
+    }
+
+    /// Translate `key` for `locale`, substituting `{name}` placeholders from `args`.
+    pub fn t(&self, locale: Option<&str>, key: &str, args: &[(&str, String)]) -> String {
+        let template = self
+            .candidate_locales(locale)
+            .iter()
+            .find_map(|code| self.locales.get(code).and_then(|strings| strings.get(key)))
+            .map(String::as_str)
+            .unwrap_or(key);
+
+        fill_placeholders(template, args)
+    }
+
+    /// Build the lookup order for a requested locale.
+    fn candidate_locales(&self, locale: Option<&str>) -> Vec<String> {
+        let mut candidates = Vec::with_capacity(3);
+
+        if let Some(requested) = locale {
+            let requested = normalize_locale(requested);
+            // "es-es" -> also try "es"
+            if let Some((language, _)) = requested.split_once('-') {
+                let language = language.to_string();
+                candidates.push(requested);
+                candidates.push(language);
+            } else {
+                candidates.push(requested);
+            }
+        }
+
+        candidates.push(self.default_locale.clone());
+        candidates
+    }
+}
+
+/// Discord uses codes like "en-US", "es-ES", "pt-BR". We compare case-insensitively
+/// and accept "_" as a separator too.
+fn normalize_locale(locale: &str) -> String {
+    locale.trim().to_lowercase().replace('_', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localizer() -> Localizer {
+        let mut localizer = Localizer::new("en");
+        localizer.add_locale(
+            "en",
+            HashMap::from([
+                ("greeting".to_string(), "Hello {name}!".to_string()),
+                ("only_en".to_string(), "English only".to_string()),
+            ]),
+        );
+        localizer.add_locale(
+            "es",
+            HashMap::from([("greeting".to_string(), "¡Hola {name}!".to_string())]),
+        );
+        localizer
+    }
+
+    #[test]
+    fn test_translates_with_args() {
+        let localizer = localizer();
+        let args = [("name", "Ana".to_string())];
+        assert_eq!(localizer.t(Some("en-US"), "greeting", &args), "Hello Ana!");
+        assert_eq!(localizer.t(Some("es-ES"), "greeting", &args), "¡Hola Ana!");
+    }
+
+    #[test]
+    fn test_falls_back_to_default_then_key() {
+        let localizer = localizer();
+        assert_eq!(localizer.t(Some("es"), "only_en", &[]), "English only");
+        assert_eq!(localizer.t(Some("fr"), "only_en", &[]), "English only");
+        assert_eq!(localizer.t(None, "missing.key", &[]), "missing.key");
+    }
+}
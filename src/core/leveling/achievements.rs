@@ -1,4 +1,16 @@
+// Achievement definitions.
+//
+// Each achievement carries a data-driven `Requirement`, so the service can check
+// and report progress on any achievement without knowing its ID. The built-in
+// set below is the default; operators can add or override achievements with an
+// `achievements.json` file (see `infra::leveling::achievements_file`).
+
+use super::UserProfile;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Highest XP reward a custom achievement may grant.
+pub const MAX_ACHIEVEMENT_REWARD_XP: u64 = 100_000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Achievement {
@@ -8,6 +20,226 @@ pub struct Achievement {
     pub emoji: String,
     pub category: AchievementCategory,
     pub reward_xp: u64,
+    pub requirement: Requirement,
+}
+
+/// What a profile needs to unlock an achievement.
+///
+/// In JSON, thresholds are written as `{ "messages": 100 }` and the unit
+/// variant as `"claimed_daily"`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Requirement {
+    Level(u32),
+    Messages(u64),
+    Commands(u64),
+    DailyStreak(u32),
+    TotalXp(u64),
+    /// Claimed `/daily` at least once.
+    ClaimedDaily,
+    BoostDays(u64),
+    ImagesShared(u64),
+    LongMessages(u64),
+    LinksShared(u64),
+    /// Custom server emojis used in messages.
+    EmojisUsed(u64),
+    StickersUsed(u64),
+    GoalsCompleted(u64),
+    /// Best leaderboard rank at or above this position (lower is better).
+    BestRank(u32),
+    RankImprovement(u32),
+    /// Number of other achievements unlocked.
+    Achievements(u64),
+    /// Every listed requirement at once.
+    All(Vec<Requirement>),
+}
+
+/// How far a profile is towards a requirement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequirementProgress {
+    pub current: u64,
+    pub target: u64,
+    /// Completion between 0.0 and 1.0.
+    pub fraction: f64,
+}
+
+impl RequirementProgress {
+    fn count(current: u64, target: u64) -> Self {
+        Self {
+            current,
+            target,
+            fraction: (current as f64 / target.max(1) as f64).min(1.0),
+        }
+    }
+}
+
+impl Requirement {
+    pub fn progress(&self, profile: &UserProfile) -> RequirementProgress {
+        match self {
+            Requirement::Level(target) => {
+                RequirementProgress::count(profile.level as u64, *target as u64)
+            }
+            Requirement::Messages(target) => {
+                RequirementProgress::count(profile.total_messages, *target)
+            }
+            Requirement::Commands(target) => {
+                RequirementProgress::count(profile.total_commands_used, *target)
+            }
+            Requirement::DailyStreak(target) => {
+                RequirementProgress::count(profile.daily_streak as u64, *target as u64)
+            }
+            Requirement::TotalXp(target) => RequirementProgress::count(profile.total_xp, *target),
+            Requirement::ClaimedDaily => {
+                RequirementProgress::count(profile.last_daily.is_some() as u64, 1)
+            }
+            Requirement::BoostDays(target) => {
+                RequirementProgress::count(profile.boost_days, *target)
+            }
+            Requirement::ImagesShared(target) => {
+                RequirementProgress::count(profile.images_shared, *target)
+            }
+            Requirement::LongMessages(target) => {
+                RequirementProgress::count(profile.long_messages, *target)
+            }
+            Requirement::LinksShared(target) => {
+                RequirementProgress::count(profile.links_shared, *target)
+            }
+            Requirement::EmojisUsed(target) => {
+                RequirementProgress::count(profile.emojis_used, *target)
+            }
+            Requirement::StickersUsed(target) => {
+                RequirementProgress::count(profile.stickers_used, *target)
+            }
+            Requirement::GoalsCompleted(target) => {
+                RequirementProgress::count(profile.goals_completed, *target)
+            }
+            Requirement::BestRank(target) => {
+                // Lower rank is better, so invert the fraction
+                let rank = profile.best_rank.max(1) as f64;
+                RequirementProgress {
+                    current: profile.best_rank as u64,
+                    target: *target as u64,
+                    fraction: (*target as f64 / rank).min(1.0),
+                }
+            }
+            Requirement::RankImprovement(target) => {
+                RequirementProgress::count(profile.rank_improvement as u64, *target as u64)
+            }
+            Requirement::Achievements(target) => {
+                RequirementProgress::count(profile.achievements.len() as u64, *target)
+            }
+            Requirement::All(requirements) => {
+                // The least complete part decides; shown as x/100 percent
+                let fraction = requirements
+                    .iter()
+                    .map(|requirement| requirement.progress(profile).fraction)
+                    .fold(1.0_f64, f64::min);
+                RequirementProgress {
+                    current: (fraction * 100.0) as u64,
+                    target: 100,
+                    fraction,
+                }
+            }
+        }
+    }
+
+    pub fn is_met(&self, profile: &UserProfile) -> bool {
+        self.progress(profile).fraction >= 1.0
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let zero = match self {
+            Requirement::Level(n)
+            | Requirement::DailyStreak(n)
+            | Requirement::BestRank(n)
+            | Requirement::RankImprovement(n) => *n == 0,
+            Requirement::Messages(n)
+            | Requirement::Commands(n)
+            | Requirement::TotalXp(n)
+            | Requirement::BoostDays(n)
+            | Requirement::ImagesShared(n)
+            | Requirement::LongMessages(n)
+            | Requirement::LinksShared(n)
+            | Requirement::EmojisUsed(n)
+            | Requirement::StickersUsed(n)
+            | Requirement::GoalsCompleted(n)
+            | Requirement::Achievements(n) => *n == 0,
+            Requirement::ClaimedDaily => false,
+            Requirement::All(requirements) => {
+                if requirements.is_empty() {
+                    return Err("`all` needs at least one requirement".to_string());
+                }
+                return requirements.iter().try_for_each(Requirement::validate);
+            }
+        };
+
+        if zero {
+            Err(format!("{:?} has a zero threshold", self))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Achievement {
+    /// Check a (custom) achievement definition for mistakes.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("id is empty".to_string());
+        }
+        if !self
+            .id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(format!(
+                "id `{}` may only use lowercase letters, digits and _",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err(format!("`{}` has no name", self.id));
+        }
+        if self.reward_xp > MAX_ACHIEVEMENT_REWARD_XP {
+            return Err(format!(
+                "`{}` rewards {} XP (max {})",
+                self.id, self.reward_xp, MAX_ACHIEVEMENT_REWARD_XP
+            ));
+        }
+        self.requirement
+            .validate()
+            .map_err(|e| format!("`{}`: {}", self.id, e))
+    }
+}
+
+/// Layer custom achievements on top of `base`: a custom entry with a known ID
+/// replaces that achievement, new IDs are appended. Invalid or duplicate
+/// entries are skipped and returned as error messages.
+pub fn merge_achievements(
+    base: Vec<Achievement>,
+    custom: Vec<Achievement>,
+) -> (Vec<Achievement>, Vec<String>) {
+    let mut merged = base;
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+
+    for achievement in custom {
+        if let Err(e) = achievement.validate() {
+            errors.push(e);
+            continue;
+        }
+        if !seen.insert(achievement.id.clone()) {
+            errors.push(format!("`{}` is defined more than once", achievement.id));
+            continue;
+        }
+
+        match merged.iter_mut().find(|a| a.id == achievement.id) {
+            Some(existing) => *existing = achievement,
+            None => merged.push(achievement),
+        }
+    }
+
+    (merged, errors)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -39,7 +271,8 @@ impl AchievementCategory {
     }
 }
 
-pub fn get_all_achievements() -> Vec<Achievement> {
+/// The achievements that ship with the bot.
+pub fn builtin_achievements() -> Vec<Achievement> {
     vec![
         // Level milestones
         Achievement {
@@ -49,6 +282,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🌱".to_string(),
             category: AchievementCategory::Levels,
             reward_xp: 50,
+            requirement: Requirement::Level(5),
         },
         Achievement {
             id: "rising_star".to_string(),
@@ -57,6 +291,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "⭐".to_string(),
             category: AchievementCategory::Levels,
             reward_xp: 100,
+            requirement: Requirement::Level(10),
         },
         Achievement {
             id: "veteran".to_string(),
@@ -65,6 +300,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🏆".to_string(),
             category: AchievementCategory::Levels,
             reward_xp: 250,
+            requirement: Requirement::Level(25),
         },
         Achievement {
             id: "legend".to_string(),
@@ -73,6 +309,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "👑".to_string(),
             category: AchievementCategory::Levels,
             reward_xp: 500,
+            requirement: Requirement::Level(50),
         },
         Achievement {
             id: "centurion".to_string(),
@@ -81,6 +318,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "💯".to_string(),
             category: AchievementCategory::Levels,
             reward_xp: 2000,
+            requirement: Requirement::Level(100),
         },
         Achievement {
             id: "halfway_there".to_string(),
@@ -89,6 +327,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🎯".to_string(),
             category: AchievementCategory::Levels,
             reward_xp: 150,
+            requirement: Requirement::Level(15),
         },
         // Message milestones
         Achievement {
@@ -98,6 +337,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "💬".to_string(),
             category: AchievementCategory::Activity,
             reward_xp: 75,
+            requirement: Requirement::Messages(100),
         },
         Achievement {
             id: "conversationalist".to_string(),
@@ -106,6 +346,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🗣️".to_string(),
             category: AchievementCategory::Activity,
             reward_xp: 150,
+            requirement: Requirement::Messages(500),
         },
         Achievement {
             id: "voice_of_the_server".to_string(),
@@ -114,6 +355,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "📢".to_string(),
             category: AchievementCategory::Activity,
             reward_xp: 300,
+            requirement: Requirement::Messages(1000),
         },
         Achievement {
             id: "veteran_speaker".to_string(),
@@ -122,6 +364,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🎤".to_string(),
             category: AchievementCategory::Activity,
             reward_xp: 750,
+            requirement: Requirement::Messages(5000),
         },
         // Command usage
         Achievement {
@@ -131,6 +374,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🎮".to_string(),
             category: AchievementCategory::Activity,
             reward_xp: 50,
+            requirement: Requirement::Commands(25),
         },
         Achievement {
             id: "command_expert".to_string(),
@@ -139,6 +383,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🕹️".to_string(),
             category: AchievementCategory::Activity,
             reward_xp: 150,
+            requirement: Requirement::Commands(100),
         },
         Achievement {
             id: "command_master".to_string(),
@@ -147,6 +392,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🎯".to_string(),
             category: AchievementCategory::Activity,
             reward_xp: 400,
+            requirement: Requirement::Commands(500),
         },
         // Daily streak achievements
         Achievement {
@@ -156,6 +402,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "✨".to_string(),
             category: AchievementCategory::Dedication,
             reward_xp: 50,
+            requirement: Requirement::DailyStreak(3),
         },
         Achievement {
             id: "week_warrior".to_string(),
@@ -164,6 +411,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "📅".to_string(),
             category: AchievementCategory::Dedication,
             reward_xp: 100,
+            requirement: Requirement::DailyStreak(7),
         },
         Achievement {
             id: "biweekly_dedication".to_string(),
@@ -172,6 +420,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "⚡".to_string(),
             category: AchievementCategory::Dedication,
             reward_xp: 200,
+            requirement: Requirement::DailyStreak(14),
         },
         Achievement {
             id: "month_master".to_string(),
@@ -180,6 +429,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "📆".to_string(),
             category: AchievementCategory::Dedication,
             reward_xp: 500,
+            requirement: Requirement::DailyStreak(30),
         },
         Achievement {
             id: "dedication_deity".to_string(),
@@ -188,6 +438,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🔥".to_string(),
             category: AchievementCategory::Dedication,
             reward_xp: 1000,
+            requirement: Requirement::DailyStreak(100),
         },
         Achievement {
             id: "half_year_hero".to_string(),
@@ -196,6 +447,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🌟".to_string(),
             category: AchievementCategory::Dedication,
             reward_xp: 2500,
+            requirement: Requirement::DailyStreak(180),
         },
         Achievement {
             id: "yearly_champion".to_string(),
@@ -204,6 +456,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "👑".to_string(),
             category: AchievementCategory::Dedication,
             reward_xp: 5000,
+            requirement: Requirement::DailyStreak(365),
         },
         // XP milestones
         Achievement {
@@ -213,6 +466,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "💎".to_string(),
             category: AchievementCategory::Progression,
             reward_xp: 100,
+            requirement: Requirement::TotalXp(1000),
         },
         Achievement {
             id: "xp_hoarder".to_string(),
@@ -221,6 +475,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "💰".to_string(),
             category: AchievementCategory::Progression,
             reward_xp: 250,
+            requirement: Requirement::TotalXp(5000),
         },
         Achievement {
             id: "xp_tycoon".to_string(),
@@ -229,6 +484,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🏰".to_string(),
             category: AchievementCategory::Progression,
             reward_xp: 500,
+            requirement: Requirement::TotalXp(10000),
         },
         Achievement {
             id: "xp_millionaire".to_string(),
@@ -237,6 +493,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "💸".to_string(),
             category: AchievementCategory::Progression,
             reward_xp: 1000,
+            requirement: Requirement::TotalXp(25000),
         },
         // Special achievements
         Achievement {
@@ -246,6 +503,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🌅".to_string(),
             category: AchievementCategory::Special,
             reward_xp: 25,
+            requirement: Requirement::ClaimedDaily,
         },
         Achievement {
             id: "booster_badge".to_string(),
@@ -254,6 +512,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🚀".to_string(),
             category: AchievementCategory::Special,
             reward_xp: 200,
+            requirement: Requirement::BoostDays(1),
         },
         Achievement {
             id: "server_supporter".to_string(),
@@ -262,6 +521,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "💖".to_string(),
             category: AchievementCategory::Special,
             reward_xp: 500,
+            requirement: Requirement::BoostDays(30),
         },
         Achievement {
             id: "well_rounded".to_string(),
@@ -270,6 +530,11 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "⚖️".to_string(),
             category: AchievementCategory::Special,
             reward_xp: 250,
+            requirement: Requirement::All(vec![
+                Requirement::Level(10),
+                Requirement::Messages(500),
+                Requirement::Commands(50),
+            ]),
         },
         // Leaderboard & Competition
         Achievement {
@@ -279,6 +544,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🥇".to_string(),
             category: AchievementCategory::Competition,
             reward_xp: 200,
+            requirement: Requirement::BestRank(3),
         },
         Achievement {
             id: "top_ten".to_string(),
@@ -287,6 +553,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🔟".to_string(),
             category: AchievementCategory::Competition,
             reward_xp: 100,
+            requirement: Requirement::BestRank(10),
         },
         Achievement {
             id: "leaderboard_climber".to_string(),
@@ -295,6 +562,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "📈".to_string(),
             category: AchievementCategory::Competition,
             reward_xp: 150,
+            requirement: Requirement::RankImprovement(10),
         },
         // Content Creator
         Achievement {
@@ -304,6 +572,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "📸".to_string(),
             category: AchievementCategory::Content,
             reward_xp: 100,
+            requirement: Requirement::ImagesShared(50),
         },
         Achievement {
             id: "lengthy_talker".to_string(),
@@ -312,6 +581,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "📝".to_string(),
             category: AchievementCategory::Content,
             reward_xp: 100,
+            requirement: Requirement::LongMessages(50),
         },
         Achievement {
             id: "link_sharer".to_string(),
@@ -320,6 +590,25 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🔗".to_string(),
             category: AchievementCategory::Content,
             reward_xp: 75,
+            requirement: Requirement::LinksShared(50),
+        },
+        Achievement {
+            id: "emoji_enthusiast".to_string(),
+            name: "Emoji Enthusiast".to_string(),
+            description: "Use 100 custom server emojis".to_string(),
+            emoji: "😎".to_string(),
+            category: AchievementCategory::Content,
+            reward_xp: 75,
+            requirement: Requirement::EmojisUsed(100),
+        },
+        Achievement {
+            id: "sticker_collector".to_string(),
+            name: "Sticker Collector".to_string(),
+            description: "Send 25 stickers".to_string(),
+            emoji: "🏷️".to_string(),
+            category: AchievementCategory::Content,
+            reward_xp: 50,
+            requirement: Requirement::StickersUsed(25),
         },
         // Server Participation
         Achievement {
@@ -329,6 +618,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🎯".to_string(),
             category: AchievementCategory::Community,
             reward_xp: 200,
+            requirement: Requirement::GoalsCompleted(10),
         },
         Achievement {
             id: "goal_enthusiast".to_string(),
@@ -337,6 +627,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🏅".to_string(),
             category: AchievementCategory::Community,
             reward_xp: 500,
+            requirement: Requirement::GoalsCompleted(50),
         },
         // Meta
         Achievement {
@@ -346,6 +637,7 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "🏹".to_string(),
             category: AchievementCategory::Meta,
             reward_xp: 300,
+            requirement: Requirement::Achievements(10),
         },
         Achievement {
             id: "completionist".to_string(),
@@ -354,6 +646,65 @@ pub fn get_all_achievements() -> Vec<Achievement> {
             emoji: "💫".to_string(),
             category: AchievementCategory::Meta,
             reward_xp: 1000,
+            requirement: Requirement::Achievements(30),
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(id: &str, reward_xp: u64, requirement: Requirement) -> Achievement {
+        Achievement {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            emoji: "🎉".to_string(),
+            category: AchievementCategory::Special,
+            reward_xp,
+            requirement,
+        }
+    }
+
+    #[test]
+    fn test_builtin_achievements_are_valid() {
+        let (merged, errors) = merge_achievements(Vec::new(), builtin_achievements());
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(merged.len(), builtin_achievements().len());
+    }
+
+    #[test]
+    fn test_merge_overrides_appends_and_rejects() {
+        let (merged, errors) = merge_achievements(
+            builtin_achievements(),
+            vec![
+                custom("first_steps", 75, Requirement::Level(5)),
+                custom("night_owl", 40, Requirement::Messages(42)),
+                custom("night_owl", 40, Requirement::Messages(42)),
+                custom("Bad ID", 10, Requirement::Level(2)),
+                custom("nothing", 10, Requirement::All(vec![])),
+            ],
+        );
+
+        assert_eq!(errors.len(), 3);
+        assert_eq!(merged.len(), builtin_achievements().len() + 1);
+        let first_steps = merged.iter().find(|a| a.id == "first_steps").unwrap();
+        assert_eq!(first_steps.reward_xp, 75);
+        assert_eq!(merged.last().unwrap().id, "night_owl");
+    }
+
+    #[test]
+    fn test_requirements_read_from_json() {
+        let requirement: Requirement =
+            serde_json::from_str(r#"{ "all": [{ "level": 10 }, "claimed_daily"] }"#).unwrap();
+
+        let mut profile = UserProfile::default_with_ids(1, 1);
+        profile.level = 10;
+        assert!(!requirement.is_met(&profile));
+        assert_eq!(requirement.progress(&profile).fraction, 0.0);
+
+        profile.last_daily = Some(chrono::Utc::now());
+        assert!(requirement.is_met(&profile));
+    }
+}
@@ -4,17 +4,21 @@
 // It works with primitive types (u64, String) so it could theoretically be used
 // in a web app, CLI tool, or any other frontend.
 
+use crate::core::util::ErrorSource;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
-use rand::Rng;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use dashmap::{DashMap, DashSet};
+use rand::{Rng, RngCore};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[path = "achievements.rs"]
 pub mod achievements;
-use achievements::{get_all_achievements, Achievement};
+use achievements::{builtin_achievements, Achievement};
 
 // ============================================================================
 // DOMAIN MODELS
@@ -48,6 +52,72 @@ pub struct LevelUpEvent {
     pub old_level: u32,
     pub new_level: u32,
     pub total_xp: u64,
+    /// Every level crossed, in order (`old_level + 1..=new_level` for a single
+    /// jump), so the Discord layer can act on each one rather than just the
+    /// endpoints.
+    pub levels_reached: Vec<u32>,
+}
+
+/// What a daily claim does to the member's streak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreakStatus {
+    /// Claimed yesterday, or within the grace period.
+    Continues,
+    /// One day past the grace period - a streak freeze item keeps it going.
+    NeedsFreeze,
+    /// The streak starts over at 1.
+    Resets,
+}
+
+/// The calendar day `at` falls on in `tz`.
+fn local_date(tz: Tz, at: DateTime<Utc>) -> NaiveDate {
+    at.with_timezone(&tz).date_naive()
+}
+
+/// The first local midnight in `tz` after `at`.
+fn next_local_midnight(tz: Tz, at: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = local_date(tz, at).succ_opt().unwrap_or(NaiveDate::MAX);
+    let midnight = tomorrow.and_hms_opt(0, 0, 0).unwrap_or_default();
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        // Midnight skipped by a DST change - the day starts an hour later
+        .or_else(|| {
+            tz.from_local_datetime(&(midnight + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .map(|local| local.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+/// How level-up announcements refer to the member (`/leveling announcements`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelUpMention {
+    /// Mention the member, who gets a notification.
+    Ping,
+    /// Show the member's display name; nobody is pinged.
+    #[default]
+    Name,
+    /// Mention the member in a silent message (no push notification).
+    Silent,
+}
+
+impl LevelUpMention {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LevelUpMention::Ping => "ping",
+            LevelUpMention::Name => "name",
+            LevelUpMention::Silent => "silent",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ping" => Some(LevelUpMention::Ping),
+            "name" => Some(LevelUpMention::Name),
+            "silent" => Some(LevelUpMention::Silent),
+            _ => None,
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -60,6 +130,170 @@ pub struct PrestigeEvent {
     pub new_prestige_level: u32,
 }
 
+/// Voice activity summary shown by `/voicestats`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VoiceStats {
+    /// Total minutes in voice, including the current session.
+    pub total_minutes: u64,
+    /// XP from voice-minute events still in the profile's XP history.
+    pub voice_xp: u64,
+    /// The channel with the most minutes, as (channel_id, minutes).
+    pub top_channel: Option<(u64, u64)>,
+}
+
+/// A member's mute and deafen state in voice. Also used as a set of flags in
+/// `LevelingConfig::voice_pause_flags`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VoiceFlags {
+    pub self_mute: bool,
+    pub self_deaf: bool,
+    /// Muted by a moderator.
+    pub mute: bool,
+    /// Deafened by a moderator.
+    pub deaf: bool,
+}
+
+impl VoiceFlags {
+    /// Whether any flag set in `self` is also set in `other`.
+    pub fn intersects(self, other: VoiceFlags) -> bool {
+        (self.self_mute && other.self_mute)
+            || (self.self_deaf && other.self_deaf)
+            || (self.mute && other.mute)
+            || (self.deaf && other.deaf)
+    }
+
+    /// Parse a comma-separated list like `self_deaf,deaf,mute`. An empty
+    /// list sets no flags.
+    pub fn parse_list(list: &str) -> Result<Self, LevelingError> {
+        let mut flags = VoiceFlags::default();
+        for name in list
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            match name.to_ascii_lowercase().as_str() {
+                "self_mute" => flags.self_mute = true,
+                "self_deaf" => flags.self_deaf = true,
+                "mute" => flags.mute = true,
+                "deaf" => flags.deaf = true,
+                _ => {
+                    return Err(LevelingError::InvalidConfig(format!(
+                        "unknown voice flag {:?} (expected self_mute, self_deaf, mute or deaf)",
+                        name
+                    )))
+                }
+            }
+        }
+        Ok(flags)
+    }
+}
+
+/// A timed multiplier on message XP, started by using an item such as the
+/// XP Boost Potion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XpBoost {
+    pub multiplier: f64,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl XpBoost {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.expires_at
+    }
+}
+
+/// Add a boost of `multiplier` for `duration` to a member's queue of boosts.
+///
+/// The queue runs one boost after another, highest multiplier first, so each
+/// boost only gets its own time: a higher boost goes ahead and pauses the
+/// lower ones behind it, and a boost with the same multiplier as a queued one
+/// adds its time to that one. Expired boosts are dropped.
+///
+/// Returns the new queue and the boost the time went to.
+pub fn queue_xp_boost(
+    boosts: &[XpBoost],
+    multiplier: f64,
+    duration: chrono::Duration,
+    now: DateTime<Utc>,
+) -> (Vec<XpBoost>, XpBoost) {
+    let mut boosts: Vec<XpBoost> = boosts
+        .iter()
+        .filter(|boost| boost.is_active(now))
+        .copied()
+        .collect();
+
+    let position = boosts
+        .iter()
+        .take_while(|boost| boost.multiplier >= multiplier)
+        .count();
+    let index = match position.checked_sub(1).map(|i| &mut boosts[i]) {
+        Some(previous) if previous.multiplier == multiplier => {
+            previous.expires_at += duration;
+            position - 1
+        }
+        previous => {
+            let start = previous.map_or(now, |previous| previous.expires_at);
+            boosts.insert(
+                position,
+                XpBoost {
+                    multiplier,
+                    expires_at: start + duration,
+                },
+            );
+            position
+        }
+    };
+    for boost in &mut boosts[index + 1..] {
+        boost.expires_at += duration;
+    }
+    let boost = boosts[index];
+    (boosts, boost)
+}
+
+/// One row of the achievements leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AchievementStanding {
+    pub user_id: u64,
+    /// Number of achievements unlocked.
+    pub unlocked: usize,
+    /// Sum of the unlocked achievements' XP rewards (tiebreaker).
+    pub achievement_xp: u64,
+}
+
+/// Rank profiles by unlocked achievements, breaking ties by achievement XP.
+/// Profiles without any achievements are left out.
+pub fn rank_by_achievements(
+    profiles: &[UserProfile],
+    achievements: &[Achievement],
+) -> Vec<AchievementStanding> {
+    let rewards: HashMap<&str, u64> = achievements
+        .iter()
+        .map(|achievement| (achievement.id.as_str(), achievement.reward_xp))
+        .collect();
+
+    let mut standings: Vec<AchievementStanding> = profiles
+        .iter()
+        .filter(|profile| !profile.achievements.is_empty())
+        .map(|profile| AchievementStanding {
+            user_id: profile.user_id,
+            unlocked: profile.achievements.len(),
+            achievement_xp: profile
+                .achievements
+                .iter()
+                .filter_map(|id| rewards.get(id.as_str()))
+                .sum(),
+        })
+        .collect();
+
+    standings.sort_by(|a, b| {
+        b.unlocked
+            .cmp(&a.unlocked)
+            .then(b.achievement_xp.cmp(&a.achievement_xp))
+            .then(a.user_id.cmp(&b.user_id))
+    });
+    standings
+}
+
 /// Information about a prestige tier.
 #[derive(Debug, Clone)]
 pub struct PrestigeTierInfo {
@@ -85,6 +319,11 @@ pub struct UserProfile {
     pub last_message_timestamp: Option<DateTime<Utc>>,
     #[serde(default)]
     pub achievements: Vec<String>,
+    /// Achievements whose XP reward has been paid out. This, not `achievements`,
+    /// decides whether an achievement is new, so rebuilding the display list
+    /// (restore, import, migration) can never pay the same reward twice.
+    #[serde(default)]
+    pub earned_achievements: BTreeSet<String>,
     #[serde(default = "default_rank")]
     pub best_rank: u32,
     #[serde(default = "default_rank")]
@@ -97,6 +336,11 @@ pub struct UserProfile {
     pub long_messages: u64,
     #[serde(default)]
     pub links_shared: u64,
+    /// Custom server emojis used in messages.
+    #[serde(default)]
+    pub emojis_used: u64,
+    #[serde(default)]
+    pub stickers_used: u64,
     #[serde(default)]
     pub goals_completed: u64,
     #[serde(default)]
@@ -136,6 +380,11 @@ pub struct MessageContentStats {
     pub has_image: bool,
     pub is_long: bool,
     pub has_link: bool,
+    /// Custom emojis in the message (`<:name:id>`).
+    #[serde(default)]
+    pub custom_emojis: u64,
+    #[serde(default)]
+    pub stickers: u64,
 }
 
 impl UserProfile {
@@ -153,12 +402,15 @@ impl UserProfile {
             daily_streak: 0,
             last_message_timestamp: None,
             achievements: Vec::new(),
+            earned_achievements: BTreeSet::new(),
             best_rank: 999,
             previous_rank: 999,
             rank_improvement: 0,
             images_shared: 0,
             long_messages: 0,
             links_shared: 0,
+            emojis_used: 0,
+            stickers_used: 0,
             goals_completed: 0,
             boost_days: 0,
             first_boost_date: None,
@@ -166,6 +418,21 @@ impl UserProfile {
             xp_history: VecDeque::new(),
         }
     }
+
+    /// Bring `achievements` and `earned_achievements` back in sync.
+    ///
+    /// IDs that are only in the display list (imported or legacy profiles) are
+    /// treated as already paid; IDs that are only in the earned set are restored
+    /// to the display list. Neither direction grants XP.
+    pub fn reconcile_achievements(&mut self) {
+        self.earned_achievements
+            .extend(self.achievements.iter().cloned());
+        for id in &self.earned_achievements {
+            if !self.achievements.contains(id) {
+                self.achievements.push(id.clone());
+            }
+        }
+    }
 }
 
 /// Tracks where XP came from (for future analytics or different XP rates).
@@ -217,11 +484,29 @@ pub enum LevelingError {
     #[error("User is on cooldown. Time remaining: {0:?}")]
     OnCooldown(Duration),
 
+    /// The message is shown to users; the underlying error (e.g. from sqlx),
+    /// when there is one, is the `source` for the logs.
     #[error("Storage error: {0}")]
-    StorageError(String),
+    StorageError(String, #[source] Option<ErrorSource>),
 
     #[error("Invalid user or guild ID")]
     InvalidId,
+
+    #[error("XP multiplier must be between 0 and {max}, got {0}", max = MAX_CHANNEL_XP_MULTIPLIER)]
+    InvalidMultiplier(f64),
+
+    #[error("Invalid leveling config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Unknown timezone: {0}")]
+    InvalidTimezone(String),
+}
+
+impl LevelingError {
+    /// A storage failure caused by `source`, which stays attached for the logs.
+    pub fn storage<E: std::error::Error + Send + Sync + 'static>(source: E) -> Self {
+        LevelingError::StorageError(source.to_string(), Some(Box::new(source)))
+    }
 }
 
 // ============================================================================
@@ -239,20 +524,33 @@ pub enum LevelingError {
 /// - Follows Dependency Inversion Principle (core depends on abstraction, not concrete implementation)
 #[async_trait]
 pub trait XpStore: Send + Sync {
-    /// Get a user's current XP in a guild.
-    /// Returns 0 if the user has never gained XP in this guild.
-    async fn get_xp(&self, user_id: u64, guild_id: u64) -> Result<u64, LevelingError>;
+    /// Get one page of the XP leaderboard, ordered by prestige, then total XP,
+    /// then user ID so ties keep a stable order. `page` is 1-based. Users in
+    /// `excluded` (e.g. bots) are left out before paging. Sorting and limiting
+    /// should happen in the store so large guilds don't have to load every
+    /// profile to show a handful.
+    async fn get_leaderboard_page(
+        &self,
+        guild_id: u64,
+        page: usize,
+        per_page: usize,
+        excluded: &[u64],
+    ) -> Result<Vec<UserStats>, LevelingError>;
 
-    /// Add XP to a user's total in a guild.
-    /// This should be atomic (no race conditions if called multiple times).
-    async fn add_xp(&self, user_id: u64, guild_id: u64, amount: u64) -> Result<(), LevelingError>;
+    /// Number of users with a profile in a guild, not counting `excluded`.
+    async fn count_profiles(&self, guild_id: u64, excluded: &[u64]) -> Result<u64, LevelingError>;
 
-    /// Get the top users in a guild by XP.
-    async fn get_leaderboard(
+    /// Number of users, not counting `excluded`, ranked strictly above the
+    /// user with `prestige_level` / `total_xp` / `user_id` in the
+    /// leaderboard order (a user's rank is this plus one).
+    async fn count_ranked_above(
         &self,
         guild_id: u64,
-        limit: usize,
-    ) -> Result<Vec<UserStats>, LevelingError>;
+        prestige_level: u32,
+        total_xp: u64,
+        user_id: u64,
+        excluded: &[u64],
+    ) -> Result<u64, LevelingError>;
 
     /// Get the top users in a guild by daily streak.
     async fn get_streak_leaderboard(
@@ -292,6 +590,247 @@ pub trait XpStore: Send + Sync {
     /// Daily goal per-guild: get and set
     async fn get_daily_goal(&self, guild_id: u64) -> Result<Option<DailyGoal>, LevelingError>;
     async fn save_daily_goal(&self, guild_id: u64, goal: DailyGoal) -> Result<(), LevelingError>;
+
+    /// Per-channel XP multipliers for a guild (channel_id -> multiplier).
+    /// Channels without an entry use 1x.
+    async fn get_channel_multipliers(
+        &self,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, f64>, LevelingError>;
+    /// Set a channel's multiplier, or remove it (back to 1x) with `None`.
+    async fn set_channel_multiplier(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        multiplier: Option<f64>,
+    ) -> Result<(), LevelingError>;
+    /// Channels in a guild where messages earn no XP, whatever their multiplier.
+    async fn get_excluded_channels(&self, guild_id: u64) -> Result<HashSet<u64>, LevelingError>;
+    /// Exclude a channel from XP, or include it again.
+    async fn set_channel_excluded(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        excluded: bool,
+    ) -> Result<(), LevelingError>;
+    /// Minutes a user has spent in each voice channel (channel_id -> minutes).
+    async fn get_voice_minutes(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, u64>, LevelingError>;
+    /// Add to a user's voice time in a channel.
+    async fn add_voice_minutes(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: u64,
+        minutes: u64,
+    ) -> Result<(), LevelingError>;
+    /// How level-up announcements mention members (`None` if never set).
+    async fn get_level_up_mention(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<LevelUpMention>, LevelingError>;
+    async fn set_level_up_mention(
+        &self,
+        guild_id: u64,
+        mention: LevelUpMention,
+    ) -> Result<(), LevelingError>;
+    /// IANA timezone name for the guild's daily reset (`None` means UTC).
+    async fn get_daily_timezone(&self, guild_id: u64) -> Result<Option<String>, LevelingError>;
+    async fn set_daily_timezone(&self, guild_id: u64, timezone: &str) -> Result<(), LevelingError>;
+    /// The member's queue of item XP boosts in the order they run, possibly
+    /// including expired ones.
+    async fn get_xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Vec<XpBoost>, LevelingError>;
+    /// Replace the member's queue of item XP boosts.
+    async fn set_xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        boosts: &[XpBoost],
+    ) -> Result<(), LevelingError>;
+}
+
+// A boxed store is a store, so the composition root can pick the backend at
+// runtime (`STORAGE_BACKEND`) and hand services a `Box<dyn XpStore>`. The
+// other store traits forward the same way.
+#[async_trait]
+impl<S: XpStore + ?Sized> XpStore for Box<S> {
+    async fn get_leaderboard_page(
+        &self,
+        guild_id: u64,
+        page: usize,
+        per_page: usize,
+        excluded: &[u64],
+    ) -> Result<Vec<UserStats>, LevelingError> {
+        (**self)
+            .get_leaderboard_page(guild_id, page, per_page, excluded)
+            .await
+    }
+
+    async fn count_profiles(&self, guild_id: u64, excluded: &[u64]) -> Result<u64, LevelingError> {
+        (**self).count_profiles(guild_id, excluded).await
+    }
+
+    async fn count_ranked_above(
+        &self,
+        guild_id: u64,
+        prestige_level: u32,
+        total_xp: u64,
+        user_id: u64,
+        excluded: &[u64],
+    ) -> Result<u64, LevelingError> {
+        (**self)
+            .count_ranked_above(guild_id, prestige_level, total_xp, user_id, excluded)
+            .await
+    }
+
+    async fn get_streak_leaderboard(
+        &self,
+        guild_id: u64,
+        limit: usize,
+    ) -> Result<Vec<UserProfile>, LevelingError> {
+        (**self).get_streak_leaderboard(guild_id, limit).await
+    }
+
+    async fn update_last_xp_time(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        time: Instant,
+    ) -> Result<(), LevelingError> {
+        (**self).update_last_xp_time(user_id, guild_id, time).await
+    }
+
+    async fn get_last_xp_time(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Option<Instant>, LevelingError> {
+        (**self).get_last_xp_time(user_id, guild_id).await
+    }
+
+    async fn get_user_profile(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Option<UserProfile>, LevelingError> {
+        (**self).get_user_profile(user_id, guild_id).await
+    }
+
+    async fn save_user_profile(&self, profile: UserProfile) -> Result<(), LevelingError> {
+        (**self).save_user_profile(profile).await
+    }
+
+    async fn get_all_profiles(&self, guild_id: u64) -> Result<Vec<UserProfile>, LevelingError> {
+        (**self).get_all_profiles(guild_id).await
+    }
+
+    async fn get_daily_goal(&self, guild_id: u64) -> Result<Option<DailyGoal>, LevelingError> {
+        (**self).get_daily_goal(guild_id).await
+    }
+
+    async fn save_daily_goal(&self, guild_id: u64, goal: DailyGoal) -> Result<(), LevelingError> {
+        (**self).save_daily_goal(guild_id, goal).await
+    }
+
+    async fn get_channel_multipliers(
+        &self,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, f64>, LevelingError> {
+        (**self).get_channel_multipliers(guild_id).await
+    }
+
+    async fn set_channel_multiplier(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        multiplier: Option<f64>,
+    ) -> Result<(), LevelingError> {
+        (**self)
+            .set_channel_multiplier(guild_id, channel_id, multiplier)
+            .await
+    }
+
+    async fn get_excluded_channels(&self, guild_id: u64) -> Result<HashSet<u64>, LevelingError> {
+        (**self).get_excluded_channels(guild_id).await
+    }
+
+    async fn set_channel_excluded(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        excluded: bool,
+    ) -> Result<(), LevelingError> {
+        (**self)
+            .set_channel_excluded(guild_id, channel_id, excluded)
+            .await
+    }
+
+    async fn get_voice_minutes(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, u64>, LevelingError> {
+        (**self).get_voice_minutes(user_id, guild_id).await
+    }
+
+    async fn add_voice_minutes(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: u64,
+        minutes: u64,
+    ) -> Result<(), LevelingError> {
+        (**self)
+            .add_voice_minutes(user_id, guild_id, channel_id, minutes)
+            .await
+    }
+
+    async fn get_level_up_mention(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<LevelUpMention>, LevelingError> {
+        (**self).get_level_up_mention(guild_id).await
+    }
+
+    async fn set_level_up_mention(
+        &self,
+        guild_id: u64,
+        mention: LevelUpMention,
+    ) -> Result<(), LevelingError> {
+        (**self).set_level_up_mention(guild_id, mention).await
+    }
+
+    async fn get_daily_timezone(&self, guild_id: u64) -> Result<Option<String>, LevelingError> {
+        (**self).get_daily_timezone(guild_id).await
+    }
+
+    async fn set_daily_timezone(&self, guild_id: u64, timezone: &str) -> Result<(), LevelingError> {
+        (**self).set_daily_timezone(guild_id, timezone).await
+    }
+
+    async fn get_xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Vec<XpBoost>, LevelingError> {
+        (**self).get_xp_boosts(user_id, guild_id).await
+    }
+
+    async fn set_xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        boosts: &[XpBoost],
+    ) -> Result<(), LevelingError> {
+        (**self).set_xp_boosts(user_id, guild_id, boosts).await
+    }
 }
 
 // ============================================================================
@@ -312,8 +851,88 @@ pub struct LevelingService<S: XpStore> {
 
     /// Runtime configuration for XP rolls and cooldowns.
     config: LevelingConfig,
+
+    /// Guilds whose XP changed since their ranks were last recomputed.
+    stale_rank_guilds: DashSet<u64>,
+
+    /// Per-channel XP multipliers, loaded from the store on first use per guild
+    /// (guild_id -> channel_id -> multiplier).
+    channel_multipliers: DashMap<u64, HashMap<u64, f64>>,
+
+    /// Channels excluded from XP, loaded from the store on first use per guild.
+    excluded_channels: DashMap<u64, HashSet<u64>>,
+
+    /// Members currently in voice: (user_id, guild_id) -> session.
+    /// Kept in memory only, so a session open during a restart isn't counted.
+    voice_sessions: DashMap<(u64, u64), VoiceSession>,
+
+    /// Level-up announcements: (user_id, guild_id) -> (last announced, level-up
+    /// held back since then by `level_up_announce_cooldown`).
+    level_up_announcements: DashMap<(u64, u64), (Instant, Option<LevelUpEvent>)>,
+
+    /// Per-guild level-up mention setting, loaded from the store on first use.
+    level_up_mentions: DashMap<u64, LevelUpMention>,
+
+    /// Per-guild timezone for the daily reset, loaded from the store on first use.
+    daily_timezones: DashMap<u64, Tz>,
+
+    /// Item XP boosts, loaded from the store on first use per member
+    /// ((user_id, guild_id) -> queued boosts, possibly expired).
+    xp_boosts: DashMap<(u64, u64), Vec<XpBoost>>,
+
+    /// Source for message XP rolls. `None` (production) uses `thread_rng`;
+    /// tests inject a seeded generator with `with_rng`.
+    rng: Option<Mutex<Box<dyn RngCore + Send>>>,
+}
+
+/// Time a member has spent in their current voice channel.
+#[derive(Debug, Clone, Copy)]
+struct VoiceSession {
+    channel_id: u64,
+    /// Time counted before the last pause.
+    counted: Duration,
+    /// When counting (re)started, or `None` while paused.
+    counting_since: Option<Instant>,
+}
+
+impl VoiceSession {
+    fn start(channel_id: u64, paused: bool, now: Instant) -> Self {
+        Self {
+            channel_id,
+            counted: Duration::ZERO,
+            counting_since: (!paused).then_some(now),
+        }
+    }
+
+    /// Counted time up to `now`.
+    fn elapsed(&self, now: Instant) -> Duration {
+        self.counted
+            + self
+                .counting_since
+                .map(|since| now.saturating_duration_since(since))
+                .unwrap_or_default()
+    }
+
+    fn set_paused(&mut self, paused: bool, now: Instant) {
+        match (paused, self.counting_since) {
+            (true, Some(_)) => {
+                self.counted = self.elapsed(now);
+                self.counting_since = None;
+            }
+            (false, None) => self.counting_since = Some(now),
+            _ => {}
+        }
+    }
 }
 
+/// Highest per-channel XP multiplier admins can set.
+pub const MAX_CHANNEL_XP_MULTIPLIER: f64 = 5.0;
+
+/// Most custom emojis, and most stickers, counted from one message. Both only
+/// count on messages that earn XP, so with this cap the emoji and sticker
+/// achievements can't be farmed by spamming.
+pub const MAX_COUNTED_PER_MESSAGE: u64 = 3;
+
 /// Configuration knobs for the leveling service.
 #[derive(Debug, Clone)]
 pub struct LevelingConfig {
@@ -323,6 +942,68 @@ pub struct LevelingConfig {
     pub xp_per_message_max: u64,
     /// Cooldown enforced between message-based XP grants.
     pub cooldown: Duration,
+    /// Achievements that can be unlocked (built-ins plus any custom ones).
+    pub achievements: Vec<Achievement>,
+    /// Minimum time between level-up announcements for one member. Level-ups
+    /// inside the window are batched into a single announcement.
+    pub level_up_announce_cooldown: Duration,
+    /// Days a daily streak may be missed without resetting (0 = claim every day).
+    pub daily_streak_grace_days: u32,
+    /// XP handed out by `/daily`.
+    pub daily: DailyRewardConfig,
+    /// Voice time stops counting while a member has any of these flags set.
+    pub voice_pause_flags: VoiceFlags,
+}
+
+/// By default voice time pauses for anyone who can't hear the channel or has
+/// been muted by a moderator; self-muted listeners still count.
+pub const DEFAULT_VOICE_PAUSE_FLAGS: VoiceFlags = VoiceFlags {
+    self_mute: false,
+    self_deaf: true,
+    mute: true,
+    deaf: true,
+};
+
+/// `/daily` reward amounts. A claim is worth `base_xp` plus `streak_bonus_step`
+/// per streak day after the first, up to `streak_bonus_cap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyRewardConfig {
+    pub base_xp: u64,
+    pub streak_bonus_step: u64,
+    pub streak_bonus_cap: u64,
+    /// Paid to everyone who claimed once the server-wide daily goal is reached.
+    pub goal_bonus_xp: u64,
+}
+
+impl DailyRewardConfig {
+    /// Reject curves where the cap is below a single step.
+    pub fn validate(&self) -> Result<(), LevelingError> {
+        if self.streak_bonus_cap < self.streak_bonus_step {
+            return Err(LevelingError::InvalidConfig(format!(
+                "daily streak bonus cap ({}) is below the step ({})",
+                self.streak_bonus_cap, self.streak_bonus_step
+            )));
+        }
+        Ok(())
+    }
+
+    /// Streak bonus for a claim that brings the streak to `streak` days.
+    pub fn streak_bonus(&self, streak: u32) -> u64 {
+        u64::from(streak.saturating_sub(1))
+            .saturating_mul(self.streak_bonus_step)
+            .min(self.streak_bonus_cap)
+    }
+}
+
+impl Default for DailyRewardConfig {
+    fn default() -> Self {
+        Self {
+            base_xp: 25,
+            streak_bonus_step: 5,
+            streak_bonus_cap: 25,
+            goal_bonus_xp: 15,
+        }
+    }
 }
 
 impl LevelingConfig {
@@ -335,6 +1016,11 @@ impl LevelingConfig {
             xp_per_message_min,
             xp_per_message_max,
             cooldown,
+            achievements: builtin_achievements(),
+            level_up_announce_cooldown: Duration::from_secs(60),
+            daily_streak_grace_days: 1,
+            daily: DailyRewardConfig::default(),
+            voice_pause_flags: DEFAULT_VOICE_PAUSE_FLAGS,
         }
     }
 }
@@ -346,6 +1032,11 @@ impl Default for LevelingConfig {
             xp_per_message_min: 15,
             xp_per_message_max: 25,
             cooldown: Duration::from_secs(60),
+            achievements: builtin_achievements(),
+            level_up_announce_cooldown: Duration::from_secs(60),
+            daily_streak_grace_days: 1,
+            daily: DailyRewardConfig::default(),
+            voice_pause_flags: DEFAULT_VOICE_PAUSE_FLAGS,
         }
     }
 }
@@ -362,16 +1053,36 @@ impl<S: XpStore> LevelingService<S> {
 
     /// Create a leveling service with a custom configuration.
     pub fn with_config(store: S, config: LevelingConfig) -> Self {
-        Self { store, config }
+        Self {
+            store,
+            config,
+            stale_rank_guilds: DashSet::new(),
+            channel_multipliers: DashMap::new(),
+            excluded_channels: DashMap::new(),
+            voice_sessions: DashMap::new(),
+            level_up_announcements: DashMap::new(),
+            level_up_mentions: DashMap::new(),
+            daily_timezones: DashMap::new(),
+            xp_boosts: DashMap::new(),
+            rng: None,
+        }
+    }
+
+    /// Roll message XP with `rng` instead of `thread_rng`, so tests can
+    /// assert exact values.
+    #[cfg(test)]
+    pub fn with_rng(mut self, rng: impl RngCore + Send + 'static) -> Self {
+        self.rng = Some(Mutex::new(Box::new(rng)));
+        self
+    }
+
+    /// Every achievement that can be unlocked, in display order.
+    pub fn achievements(&self) -> &[Achievement] {
+        &self.config.achievements
     }
 
     /// Maximum number of XP events to keep in history for analytics
     const XP_HISTORY_LIMIT: usize = 120;
-    /// Base daily reward
-    const BASE_DAILY_REWARD: u64 = 25;
-    const STREAK_BONUS_STEP: u64 = 5;
-    const STREAK_BONUS_CAP: u64 = 25;
-    const GOAL_BONUS_XP: u64 = 15;
 
     fn validate_ids(user_id: u64, guild_id: u64) -> Result<(), LevelingError> {
         if user_id == 0 || guild_id == 0 {
@@ -482,19 +1193,30 @@ impl<S: XpStore> LevelingService<S> {
 
     /// Process a message and potentially award XP.
     ///
+    /// The XP is scaled by the channel's multiplier (see `set_channel_multiplier`)
+    /// and by the member's active item boost (see `activate_xp_boost`). Excluded
+    /// channels (see `set_channel_excluded`) earn none.
+    ///
     /// **Returns:**
     /// - `Ok(Some(LevelUpEvent))` if the user leveled up
-    /// - `Ok(None)` if XP was awarded but no level up occurred
+    /// - `Ok(None)` if XP was awarded but no level up occurred, or XP is
+    ///   disabled in this channel
     /// - `Err(LevelingError::OnCooldown)` if the user is on cooldown
     /// - `Err(...)` for storage errors
     pub async fn process_message(
         &self,
         user_id: u64,
         guild_id: u64,
+        channel_id: u64,
         boosted: bool,
         content_stats: Option<MessageContentStats>,
     ) -> Result<Option<LevelUpEvent>, LevelingError> {
         Self::validate_ids(user_id, guild_id)?;
+        let channel_multiplier = if self.is_channel_excluded(guild_id, channel_id).await? {
+            0.0
+        } else {
+            self.channel_multiplier(guild_id, channel_id).await?
+        };
 
         // Load or create profile
         let mut profile = match self.store.get_user_profile(user_id, guild_id).await? {
@@ -502,6 +1224,12 @@ impl<S: XpStore> LevelingService<S> {
             None => self.create_default_profile(user_id, guild_id),
         };
 
+        // Custom emojis and stickers are counted once the message earns XP
+        // (see `MAX_COUNTED_PER_MESSAGE`)
+        let (custom_emojis, stickers) = content_stats
+            .as_ref()
+            .map_or((0, 0), |stats| (stats.custom_emojis, stats.stickers));
+
         // Update content stats
         if let Some(stats) = content_stats {
             profile.total_messages = profile.total_messages.saturating_add(1);
@@ -519,7 +1247,14 @@ impl<S: XpStore> LevelingService<S> {
             profile.total_messages = profile.total_messages.saturating_add(1);
         }
 
-        // Cooldown based on last_message_timestamp if present
+        // XP is disabled in this channel: keep the message stats, skip the XP
+        // (and don't start a cooldown)
+        if channel_multiplier == 0.0 {
+            self.store.save_user_profile(profile).await?;
+            return Ok(None);
+        }
+
+        // Cooldown based on last_message_timestamp if present
         if let Some(last_ts) = profile.last_message_timestamp {
             let now = Utc::now();
             let elapsed = now
@@ -535,14 +1270,27 @@ impl<S: XpStore> LevelingService<S> {
         }
 
         let old_level = profile.level;
+        profile.emojis_used = profile
+            .emojis_used
+            .saturating_add(custom_emojis.min(MAX_COUNTED_PER_MESSAGE));
+        profile.stickers_used = profile
+            .stickers_used
+            .saturating_add(stickers.min(MAX_COUNTED_PER_MESSAGE));
 
         // Get prestige bonuses
         let tier_info = Self::get_prestige_tier_info(profile.prestige_level);
 
+        let item_multiplier = self
+            .active_xp_boost(user_id, guild_id)
+            .await?
+            .map_or(1.0, |boost| boost.multiplier);
+
         // Award XP with prestige multiplier
         let base_gain = self.roll_message_xp();
         let boosted_gain = self.apply_xp_boost(base_gain, boosted);
-        let prestige_multiplied = (boosted_gain as f64 * tier_info.xp_multiplier).round() as u64;
+        let prestige_multiplied =
+            (boosted_gain as f64 * tier_info.xp_multiplier * channel_multiplier * item_multiplier)
+                .round() as u64;
 
         // Check if we should apply daily bonus (Gold tier and above, once per day)
         let now = Utc::now();
@@ -593,24 +1341,409 @@ impl<S: XpStore> LevelingService<S> {
         let _newly_earned = self.check_and_award_achievements_internal(&mut profile);
 
         // Handle level up
-        let leveled_up = self.handle_level_up_internal(&mut profile);
+        let levels_reached = self.handle_level_up_internal(&mut profile);
 
         // Persist changes
         self.store.save_user_profile(profile.clone()).await?;
+        self.mark_ranks_stale(guild_id);
 
-        if leveled_up {
+        if !levels_reached.is_empty() {
             Ok(Some(LevelUpEvent {
                 user_id,
                 guild_id,
                 old_level,
                 new_level: profile.level,
                 total_xp: profile.total_xp,
+                levels_reached,
             }))
         } else {
             Ok(None)
         }
     }
 
+    /// XP multiplier for messages in a channel (1.0 unless an admin changed it).
+    pub async fn channel_multiplier(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+    ) -> Result<f64, LevelingError> {
+        Ok(self
+            .channel_multipliers(guild_id)
+            .await?
+            .get(&channel_id)
+            .copied()
+            .unwrap_or(1.0))
+    }
+
+    /// All channels in a guild with a non-default multiplier.
+    pub async fn channel_multipliers(
+        &self,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, f64>, LevelingError> {
+        if let Some(cached) = self.channel_multipliers.get(&guild_id) {
+            return Ok(cached.clone());
+        }
+
+        let multipliers = self.store.get_channel_multipliers(guild_id).await?;
+        self.channel_multipliers
+            .insert(guild_id, multipliers.clone());
+        Ok(multipliers)
+    }
+
+    /// Set the XP multiplier for a channel. `0` disables XP there, `1` removes
+    /// the override.
+    pub async fn set_channel_multiplier(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        multiplier: f64,
+    ) -> Result<(), LevelingError> {
+        Self::validate_ids(channel_id, guild_id)?;
+        if !(0.0..=MAX_CHANNEL_XP_MULTIPLIER).contains(&multiplier) {
+            return Err(LevelingError::InvalidMultiplier(multiplier));
+        }
+
+        let stored = (multiplier != 1.0).then_some(multiplier);
+        self.store
+            .set_channel_multiplier(guild_id, channel_id, stored)
+            .await?;
+        // Reload on next use
+        self.channel_multipliers.remove(&guild_id);
+        Ok(())
+    }
+
+    /// Whether messages in a channel are excluded from XP.
+    pub async fn is_channel_excluded(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+    ) -> Result<bool, LevelingError> {
+        Ok(self
+            .excluded_channels(guild_id)
+            .await?
+            .contains(&channel_id))
+    }
+
+    /// All channels in a guild excluded from XP.
+    pub async fn excluded_channels(&self, guild_id: u64) -> Result<HashSet<u64>, LevelingError> {
+        if let Some(cached) = self.excluded_channels.get(&guild_id) {
+            return Ok(cached.clone());
+        }
+
+        let channels = self.store.get_excluded_channels(guild_id).await?;
+        self.excluded_channels.insert(guild_id, channels.clone());
+        Ok(channels)
+    }
+
+    /// Exclude a channel from XP, or include it again. The channel's
+    /// multiplier is kept and applies again once it's included.
+    pub async fn set_channel_excluded(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        excluded: bool,
+    ) -> Result<(), LevelingError> {
+        Self::validate_ids(channel_id, guild_id)?;
+        self.store
+            .set_channel_excluded(guild_id, channel_id, excluded)
+            .await?;
+        // Reload on next use
+        self.excluded_channels.remove(&guild_id);
+        Ok(())
+    }
+
+    /// How level-up announcements mention members in a guild (defaults to
+    /// `LevelUpMention::Name`, so nobody is pinged unless an admin opts in).
+    pub async fn level_up_mention(&self, guild_id: u64) -> Result<LevelUpMention, LevelingError> {
+        if let Some(cached) = self.level_up_mentions.get(&guild_id) {
+            return Ok(*cached);
+        }
+
+        let mention = self
+            .store
+            .get_level_up_mention(guild_id)
+            .await?
+            .unwrap_or_default();
+        self.level_up_mentions.insert(guild_id, mention);
+        Ok(mention)
+    }
+
+    pub async fn set_level_up_mention(
+        &self,
+        guild_id: u64,
+        mention: LevelUpMention,
+    ) -> Result<(), LevelingError> {
+        self.store.set_level_up_mention(guild_id, mention).await?;
+        self.level_up_mentions.insert(guild_id, mention);
+        Ok(())
+    }
+
+    /// Timezone whose midnight resets `/daily` in a guild (UTC unless set).
+    pub async fn daily_timezone(&self, guild_id: u64) -> Result<Tz, LevelingError> {
+        if let Some(cached) = self.daily_timezones.get(&guild_id) {
+            return Ok(*cached);
+        }
+
+        let timezone = match self.store.get_daily_timezone(guild_id).await? {
+            Some(name) => name.parse().unwrap_or_else(|_| {
+                tracing::warn!(guild_id, timezone = %name, "Unknown stored daily timezone, using UTC");
+                Tz::UTC
+            }),
+            None => Tz::UTC,
+        };
+        self.daily_timezones.insert(guild_id, timezone);
+        Ok(timezone)
+    }
+
+    /// Set the daily reset timezone from an IANA name like `America/Los_Angeles`.
+    pub async fn set_daily_timezone(
+        &self,
+        guild_id: u64,
+        timezone: &str,
+    ) -> Result<Tz, LevelingError> {
+        Self::validate_guild_id(guild_id)?;
+        let tz: Tz = timezone
+            .trim()
+            .parse()
+            .map_err(|_| LevelingError::InvalidTimezone(timezone.to_string()))?;
+        self.store.set_daily_timezone(guild_id, tz.name()).await?;
+        self.daily_timezones.insert(guild_id, tz);
+        Ok(tz)
+    }
+
+    /// When `/daily` next resets in the guild: the next local midnight.
+    pub async fn next_daily_reset(&self, guild_id: u64) -> Result<DateTime<Utc>, LevelingError> {
+        let tz = self.daily_timezone(guild_id).await?;
+        Ok(next_local_midnight(tz, Utc::now()))
+    }
+
+    /// The member's item XP boosts that haven't run out, the running one first.
+    pub async fn xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Vec<XpBoost>, LevelingError> {
+        let key = (user_id, guild_id);
+        let boosts = match self.xp_boosts.get(&key).map(|entry| entry.clone()) {
+            Some(cached) => cached,
+            None => {
+                let stored = self.store.get_xp_boosts(user_id, guild_id).await?;
+                self.xp_boosts.insert(key, stored.clone());
+                stored
+            }
+        };
+        let now = Utc::now();
+        Ok(boosts
+            .into_iter()
+            .filter(|boost| boost.is_active(now))
+            .collect())
+    }
+
+    /// The member's item XP boost, if one is running.
+    pub async fn active_xp_boost(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Option<XpBoost>, LevelingError> {
+        Ok(self.xp_boosts(user_id, guild_id).await?.first().copied())
+    }
+
+    /// Multiply the member's message XP by `multiplier` for `duration`, and
+    /// return when that boost ends. Boosts used while another is running are
+    /// queued (see `queue_xp_boost`), so each multiplier only applies for the
+    /// time it was bought for.
+    pub async fn activate_xp_boost(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        multiplier: f64,
+        duration: Duration,
+    ) -> Result<XpBoost, LevelingError> {
+        Self::validate_ids(user_id, guild_id)?;
+        let duration = chrono::Duration::from_std(duration)
+            .map_err(|e| LevelingError::InvalidConfig(e.to_string()))?;
+
+        let current = self.xp_boosts(user_id, guild_id).await?;
+        let (boosts, boost) = queue_xp_boost(&current, multiplier, duration, Utc::now());
+        self.store.set_xp_boosts(user_id, guild_id, &boosts).await?;
+        self.xp_boosts.insert((user_id, guild_id), boosts);
+        Ok(boost)
+    }
+
+    /// Track which voice channel a member is in (`None` when they leave voice).
+    /// Time spent in the previous channel is added to their voice minutes when
+    /// they leave or switch channels. Time doesn't count while `flags` has any
+    /// of the configured `voice_pause_flags` set.
+    pub async fn record_voice_state(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: Option<u64>,
+        flags: VoiceFlags,
+    ) -> Result<(), LevelingError> {
+        self.record_voice_state_at(user_id, guild_id, channel_id, flags, Instant::now())
+            .await
+    }
+
+    async fn record_voice_state_at(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: Option<u64>,
+        flags: VoiceFlags,
+        now: Instant,
+    ) -> Result<(), LevelingError> {
+        Self::validate_ids(user_id, guild_id)?;
+        let key = (user_id, guild_id);
+        let paused = flags.intersects(self.config.voice_pause_flags);
+
+        // Mute/deafen updates keep the same channel - the session continues,
+        // only pausing or resuming
+        if let Some(mut session) = self.voice_sessions.get_mut(&key) {
+            if Some(session.channel_id) == channel_id {
+                session.set_paused(paused, now);
+                return Ok(());
+            }
+        }
+
+        let previous = match channel_id {
+            Some(channel_id) => self
+                .voice_sessions
+                .insert(key, VoiceSession::start(channel_id, paused, now)),
+            None => self.voice_sessions.remove(&key).map(|(_, session)| session),
+        };
+
+        if let Some(session) = previous {
+            let minutes = session.elapsed(now).as_secs() / 60;
+            if minutes > 0 {
+                self.store
+                    .add_voice_minutes(user_id, guild_id, session.channel_id, minutes)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Voice time and voice XP for a member.
+    pub async fn get_voice_stats(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<VoiceStats, LevelingError> {
+        self.get_voice_stats_at(user_id, guild_id, Instant::now())
+            .await
+    }
+
+    async fn get_voice_stats_at(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        now: Instant,
+    ) -> Result<VoiceStats, LevelingError> {
+        Self::validate_ids(user_id, guild_id)?;
+        let mut minutes = self.store.get_voice_minutes(user_id, guild_id).await?;
+
+        // Count the session in progress too
+        if let Some(session) = self.voice_sessions.get(&(user_id, guild_id)) {
+            *minutes.entry(session.channel_id).or_default() += session.elapsed(now).as_secs() / 60;
+        }
+
+        let voice_xp = match self.store.get_user_profile(user_id, guild_id).await? {
+            Some(profile) => profile
+                .xp_history
+                .iter()
+                .filter(|event| event.source == "voice_minute")
+                .map(|event| event.amount)
+                .sum(),
+            None => 0,
+        };
+
+        Ok(VoiceStats {
+            total_minutes: minutes.values().sum(),
+            voice_xp,
+            top_channel: minutes
+                .into_iter()
+                .filter(|(_, minutes)| *minutes > 0)
+                .max_by_key(|(channel_id, minutes)| (*minutes, std::cmp::Reverse(*channel_id))),
+        })
+    }
+
+    /// Decide whether a level-up should be announced now.
+    ///
+    /// A member's first level-up is announced right away. Level-ups within
+    /// `level_up_announce_cooldown` of their last announcement are held back and
+    /// merged, then returned by `take_due_level_ups` once the cooldown passes,
+    /// so a burst of levels ends in one "reached level N" message.
+    pub fn queue_level_up_announcement(&self, level_up: LevelUpEvent) -> Option<LevelUpEvent> {
+        self.queue_level_up_announcement_at(level_up, Instant::now())
+    }
+
+    fn queue_level_up_announcement_at(
+        &self,
+        level_up: LevelUpEvent,
+        now: Instant,
+    ) -> Option<LevelUpEvent> {
+        let key = (level_up.user_id, level_up.guild_id);
+        let mut entry = match self.level_up_announcements.entry(key) {
+            dashmap::Entry::Vacant(vacant) => {
+                vacant.insert((now, None));
+                return Some(level_up);
+            }
+            dashmap::Entry::Occupied(occupied) => occupied,
+        };
+        let (last_announced, held) = entry.get_mut();
+
+        let merged = match held.take() {
+            // Keep the level the burst started from and every level since
+            Some(earlier) => {
+                let mut levels_reached = earlier.levels_reached;
+                levels_reached.extend(&level_up.levels_reached);
+                LevelUpEvent {
+                    old_level: earlier.old_level,
+                    levels_reached,
+                    ..level_up
+                }
+            }
+            None => level_up,
+        };
+        if now.saturating_duration_since(*last_announced) >= self.config.level_up_announce_cooldown
+        {
+            *last_announced = now;
+            Some(merged)
+        } else {
+            *held = Some(merged);
+            None
+        }
+    }
+
+    /// Held-back level-ups whose announcement cooldown has passed, one per member.
+    pub fn take_due_level_ups(&self) -> Vec<LevelUpEvent> {
+        self.take_due_level_ups_at(Instant::now())
+    }
+
+    fn take_due_level_ups_at(&self, now: Instant) -> Vec<LevelUpEvent> {
+        let cooldown = self.config.level_up_announce_cooldown;
+        let mut due = Vec::new();
+
+        self.level_up_announcements
+            .retain(|_, (last_announced, held)| {
+                if now.saturating_duration_since(*last_announced) < cooldown {
+                    return true;
+                }
+                match held.take() {
+                    Some(level_up) => {
+                        due.push(level_up);
+                        *last_announced = now;
+                        true
+                    }
+                    // Nothing pending and the cooldown is over - forget the member
+                    None => false,
+                }
+            });
+
+        due
+    }
+
     /// Increment command usage count and check for achievements.
     pub async fn increment_command_count(
         &self,
@@ -628,17 +1761,18 @@ impl<S: XpStore> LevelingService<S> {
         // Check achievements
         let _newly_earned = self.check_and_award_achievements_internal(&mut profile);
         let old_level = profile.level;
-        let leveled_up = self.handle_level_up_internal(&mut profile);
+        let levels_reached = self.handle_level_up_internal(&mut profile);
 
         self.store.save_user_profile(profile.clone()).await?;
 
-        if leveled_up {
+        if !levels_reached.is_empty() {
             Ok(Some(LevelUpEvent {
                 user_id,
                 guild_id,
                 old_level,
                 new_level: profile.level,
                 total_xp: profile.total_xp,
+                levels_reached,
             }))
         } else {
             Ok(None)
@@ -683,105 +1817,37 @@ impl<S: XpStore> LevelingService<S> {
         &self,
         profile: &UserProfile,
     ) -> Option<(Achievement, f64, u64, u64)> {
-        let all_achievements = get_all_achievements();
-        let mut candidates = Vec::new();
-
-        for achievement in all_achievements {
-            if profile.achievements.contains(&achievement.id) {
-                continue;
-            }
-
-            let (current, target, progress_override) = match achievement.id.as_str() {
-                // Level milestones
-                "first_steps" => (profile.level as u64, 5, None),
-                "rising_star" => (profile.level as u64, 10, None),
-                "veteran" => (profile.level as u64, 25, None),
-                "legend" => (profile.level as u64, 50, None),
-                "centurion" => (profile.level as u64, 100, None),
-                "halfway_there" => (profile.level as u64, 15, None),
-
-                // Message milestones
-                "chatterbox" => (profile.total_messages, 100, None),
-                "conversationalist" => (profile.total_messages, 500, None),
-                "voice_of_the_server" => (profile.total_messages, 1000, None),
-                "veteran_speaker" => (profile.total_messages, 5000, None),
-
-                // Command usage
-                "command_novice" => (profile.total_commands_used, 25, None),
-                "command_expert" => (profile.total_commands_used, 100, None),
-                "command_master" => (profile.total_commands_used, 500, None),
-
-                // Daily streak achievements
-                "streak_starter" => (profile.daily_streak as u64, 3, None),
-                "week_warrior" => (profile.daily_streak as u64, 7, None),
-                "biweekly_dedication" => (profile.daily_streak as u64, 14, None),
-                "month_master" => (profile.daily_streak as u64, 30, None),
-                "dedication_deity" => (profile.daily_streak as u64, 100, None),
-                "half_year_hero" => (profile.daily_streak as u64, 180, None),
-                "yearly_champion" => (profile.daily_streak as u64, 365, None),
-
-                // XP milestones
-                "xp_collector" => (profile.total_xp, 1000, None),
-                "xp_hoarder" => (profile.total_xp, 5000, None),
-                "xp_tycoon" => (profile.total_xp, 10000, None),
-                "xp_millionaire" => (profile.total_xp, 25000, None),
-
-                // Special achievements
-                "early_bird" => (profile.last_daily.is_some() as u64, 1, None),
-                "booster_badge" => (profile.boost_days, 1, None),
-                "server_supporter" => (profile.boost_days, 30, None),
-                "well_rounded" => {
-                    // Combine requirements by taking the minimum percentage across all gates.
-                    let level_progress = profile.level as f64 / 10.0;
-                    let message_progress = profile.total_messages as f64 / 500.0;
-                    let command_progress = profile.total_commands_used as f64 / 50.0;
-                    let progress = level_progress
-                        .min(message_progress)
-                        .min(command_progress)
-                        .min(1.0);
-                    // Represent as x/100 so the bar shows percent complete.
-                    ((progress * 100.0) as u64, 100, Some(progress))
-                }
-
-                // Content Creator
-                "photographer" => (profile.images_shared, 50, None),
-                "lengthy_talker" => (profile.long_messages, 50, None),
-                "link_sharer" => (profile.links_shared, 50, None),
-
-                // Server Participation
-                "goal_contributor" => (profile.goals_completed, 10, None),
-                "goal_enthusiast" => (profile.goals_completed, 50, None),
-
-                // Leaderboard & Competition (lower rank is better, so invert the fraction)
-                "podium_finish" => {
-                    let rank = profile.best_rank.max(1) as f64;
-                    let progress = (3.0 / rank).min(1.0);
-                    (profile.best_rank as u64, 3, Some(progress))
-                }
-                "top_ten" => {
-                    let rank = profile.best_rank.max(1) as f64;
-                    let progress = (10.0 / rank).min(1.0);
-                    (profile.best_rank as u64, 10, Some(progress))
-                }
-                "leaderboard_climber" => (profile.rank_improvement as u64, 10, None),
-
-                // Meta
-                "achievement_hunter" => (profile.achievements.len() as u64, 10, None),
-                "completionist" => (profile.achievements.len() as u64, 30, None),
-
-                _ => (0, 0, None),
-            };
+        self.get_nearest_achievements(profile, 1).into_iter().next()
+    }
 
-            if target > 0 {
-                let progress =
-                    progress_override.unwrap_or_else(|| (current as f64 / target as f64).min(1.0));
-                candidates.push((achievement, progress, current, target));
-            }
-        }
+    /// Get up to `n` locked achievements closest to completion, nearest first,
+    /// as (achievement, progress 0.0-1.0, current, target).
+    pub fn get_nearest_achievements(
+        &self,
+        profile: &UserProfile,
+        n: usize,
+    ) -> Vec<(Achievement, f64, u64, u64)> {
+        let mut candidates: Vec<(Achievement, f64, u64, u64)> = self
+            .config
+            .achievements
+            .iter()
+            .filter(|achievement| !profile.earned_achievements.contains(&achievement.id))
+            .filter(|achievement| !profile.achievements.contains(&achievement.id))
+            .map(|achievement| {
+                let progress = achievement.requirement.progress(profile);
+                (
+                    achievement.clone(),
+                    progress.fraction,
+                    progress.current,
+                    progress.target,
+                )
+            })
+            .collect();
 
         // Sort by progress descending
         candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        candidates.into_iter().next()
+        candidates.truncate(n);
+        candidates
     }
 
     /// Create a default user profile (when a user has no existing data)
@@ -798,12 +1864,15 @@ impl<S: XpStore> LevelingService<S> {
             daily_streak: 0,
             last_message_timestamp: None,
             achievements: Vec::new(),
+            earned_achievements: BTreeSet::new(),
             best_rank: 999,
             previous_rank: 999,
             rank_improvement: 0,
             images_shared: 0,
             long_messages: 0,
             links_shared: 0,
+            emojis_used: 0,
+            stickers_used: 0,
             goals_completed: 0,
             boost_days: 0,
             first_boost_date: None,
@@ -847,80 +1916,17 @@ impl<S: XpStore> LevelingService<S> {
     /// Check and award achievements (internal simplified version). Returns list of newly earned IDs.
     fn check_and_award_achievements_internal(&self, profile: &mut UserProfile) -> Vec<String> {
         let mut newly = Vec::new();
-        let all_achievements = get_all_achievements();
+        profile.reconcile_achievements();
 
-        for achievement in all_achievements {
-            if profile.achievements.contains(&achievement.id) {
+        for achievement in &self.config.achievements {
+            if profile.earned_achievements.contains(&achievement.id) {
                 continue;
             }
 
-            let meets = match achievement.id.as_str() {
-                // Level milestones
-                "first_steps" => profile.level >= 5,
-                "rising_star" => profile.level >= 10,
-                "veteran" => profile.level >= 25,
-                "legend" => profile.level >= 50,
-                "centurion" => profile.level >= 100,
-                "halfway_there" => profile.level >= 15,
-
-                // Message milestones
-                "chatterbox" => profile.total_messages >= 100,
-                "conversationalist" => profile.total_messages >= 500,
-                "voice_of_the_server" => profile.total_messages >= 1000,
-                "veteran_speaker" => profile.total_messages >= 5000,
-
-                // Command usage
-                "command_novice" => profile.total_commands_used >= 25,
-                "command_expert" => profile.total_commands_used >= 100,
-                "command_master" => profile.total_commands_used >= 500,
-
-                // Daily streak achievements
-                "streak_starter" => profile.daily_streak >= 3,
-                "week_warrior" => profile.daily_streak >= 7,
-                "biweekly_dedication" => profile.daily_streak >= 14,
-                "month_master" => profile.daily_streak >= 30,
-                "dedication_deity" => profile.daily_streak >= 100,
-                "half_year_hero" => profile.daily_streak >= 180,
-                "yearly_champion" => profile.daily_streak >= 365,
-
-                // XP milestones
-                "xp_collector" => profile.total_xp >= 1000,
-                "xp_hoarder" => profile.total_xp >= 5000,
-                "xp_tycoon" => profile.total_xp >= 10000,
-                "xp_millionaire" => profile.total_xp >= 25000,
-
-                // Special achievements
-                "early_bird" => profile.last_daily.is_some(),
-                "booster_badge" => profile.boost_days > 0,
-                "server_supporter" => profile.boost_days >= 30,
-                "well_rounded" => {
-                    profile.level >= 10
-                        && profile.total_messages >= 500
-                        && profile.total_commands_used >= 50
-                }
-
-                // Leaderboard & Competition
-                "podium_finish" => profile.best_rank <= 3,
-                "top_ten" => profile.best_rank <= 10,
-                "leaderboard_climber" => profile.rank_improvement >= 10,
-
-                // Content Creator
-                "photographer" => profile.images_shared >= 50,
-                "lengthy_talker" => profile.long_messages >= 50,
-                "link_sharer" => profile.links_shared >= 50,
-
-                // Server Participation
-                "goal_contributor" => profile.goals_completed >= 10,
-                "goal_enthusiast" => profile.goals_completed >= 50,
-
-                // Meta
-                "achievement_hunter" => profile.achievements.len() >= 10,
-                "completionist" => profile.achievements.len() >= 30,
-
-                _ => false,
-            };
-
-            if meets {
+            // Only a genuinely new ID gets past the earned set
+            if achievement.requirement.is_met(profile)
+                && profile.earned_achievements.insert(achievement.id.clone())
+            {
                 profile.achievements.push(achievement.id.clone());
                 profile.total_xp = profile.total_xp.saturating_add(achievement.reward_xp);
                 self.record_xp_event(
@@ -936,15 +1942,16 @@ impl<S: XpStore> LevelingService<S> {
         newly
     }
 
-    /// Internal handler for leveling up a user's profile. Returns true if leveled.
-    fn handle_level_up_internal(&self, profile: &mut UserProfile) -> bool {
-        let mut leveled = false;
+    /// Internal handler for leveling up a user's profile. Returns every level
+    /// reached, in order (empty if the user didn't level up).
+    fn handle_level_up_internal(&self, profile: &mut UserProfile) -> Vec<u32> {
+        let mut levels_reached = Vec::new();
         while profile.total_xp >= Self::xp_threshold_for_level(profile.level + 1) {
             profile.level += 1;
-            leveled = true;
+            levels_reached.push(profile.level);
         }
         profile.xp_to_next_level = Self::xp_threshold_for_level(profile.level + 1);
-        leveled
+        levels_reached
     }
 
     /// Prestige a user - reset their level and XP while incrementing prestige level.
@@ -967,15 +1974,20 @@ impl<S: XpStore> LevelingService<S> {
             .store
             .get_user_profile(user_id, guild_id)
             .await?
-            .ok_or_else(|| LevelingError::StorageError("User profile not found".to_string()))?;
+            .ok_or_else(|| {
+                LevelingError::StorageError("User profile not found".to_string(), None)
+            })?;
 
         // Check if user meets prestige requirements
         const PRESTIGE_LEVEL_REQUIREMENT: u32 = 50;
         if profile.level < PRESTIGE_LEVEL_REQUIREMENT {
-            return Err(LevelingError::StorageError(format!(
-                "Must be at least level {} to prestige (current level: {})",
-                PRESTIGE_LEVEL_REQUIREMENT, profile.level
-            )));
+            return Err(LevelingError::StorageError(
+                format!(
+                    "Must be at least level {} to prestige (current level: {})",
+                    PRESTIGE_LEVEL_REQUIREMENT, profile.level
+                ),
+                None,
+            ));
         }
 
         // Perform prestige
@@ -986,6 +1998,7 @@ impl<S: XpStore> LevelingService<S> {
 
         // Save updated profile
         self.store.save_user_profile(profile.clone()).await?;
+        self.mark_ranks_stale(guild_id);
 
         Ok(PrestigeEvent {
             user_id,
@@ -1079,15 +2092,82 @@ impl<S: XpStore> LevelingService<S> {
         }
     }
 
-    /// Get the leaderboard for a guild.
+    /// Get the top `limit` users of a guild, ordered by prestige then total XP.
     pub async fn get_leaderboard(
         &self,
         guild_id: u64,
         limit: usize,
     ) -> Result<Vec<UserStats>, LevelingError> {
+        self.get_leaderboard_page(guild_id, 1, limit, &[]).await
+    }
+
+    /// Get one page (1-based) of the leaderboard, leaving out the users in
+    /// `excluded` (e.g. bots) so the ranks after them don't skip.
+    pub async fn get_leaderboard_page(
+        &self,
+        guild_id: u64,
+        page: usize,
+        per_page: usize,
+        excluded: &[u64],
+    ) -> Result<Vec<UserStats>, LevelingError> {
+        Self::validate_guild_id(guild_id)?;
+        if per_page == 0 {
+            return Ok(Vec::new());
+        }
+
+        self.store
+            .get_leaderboard_page(guild_id, page.max(1), per_page, excluded)
+            .await
+    }
+
+    /// Number of users on a guild's leaderboard, not counting `excluded`.
+    pub async fn count_ranked_users(
+        &self,
+        guild_id: u64,
+        excluded: &[u64],
+    ) -> Result<u64, LevelingError> {
         Self::validate_guild_id(guild_id)?;
+        self.store.count_profiles(guild_id, excluded).await
+    }
+
+    /// A user's 1-based leaderboard rank among everyone not in `excluded`, or
+    /// `None` if they have no profile yet (or are excluded themselves).
+    pub async fn get_user_rank(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        excluded: &[u64],
+    ) -> Result<Option<u64>, LevelingError> {
+        Self::validate_ids(user_id, guild_id)?;
+        if excluded.contains(&user_id) {
+            return Ok(None);
+        }
+        let Some(profile) = self.store.get_user_profile(user_id, guild_id).await? else {
+            return Ok(None);
+        };
+
+        let above = self
+            .store
+            .count_ranked_above(
+                guild_id,
+                profile.prestige_level,
+                profile.total_xp,
+                user_id,
+                excluded,
+            )
+            .await?;
+        Ok(Some(above + 1))
+    }
 
-        self.store.get_leaderboard(guild_id, limit).await
+    /// Every member with at least one achievement, ranked by achievements
+    /// unlocked (see `rank_by_achievements`).
+    pub async fn get_achievement_leaderboard(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<AchievementStanding>, LevelingError> {
+        Self::validate_guild_id(guild_id)?;
+        let profiles = self.store.get_all_profiles(guild_id).await?;
+        Ok(rank_by_achievements(&profiles, &self.config.achievements))
     }
 
     /// Get the top users in a guild by daily streak.
@@ -1102,47 +2182,158 @@ impl<S: XpStore> LevelingService<S> {
     }
 
     /// Recalculate ranks for all profiles in a guild and persist the updated rank fields.
-    /// Returns the profiles sorted by rank (highest XP first).
+    /// Returns the profiles sorted by rank (same order as the leaderboard).
+    ///
+    /// This is O(N) in the number of profiles, so it runs from the scheduled
+    /// rank refresh task rather than on every leaderboard view. Only profiles
+    /// whose rank fields actually changed are written back.
     pub async fn recalculate_and_update_ranks(
         &self,
         guild_id: u64,
     ) -> Result<Vec<UserProfile>, LevelingError> {
         Self::validate_guild_id(guild_id)?;
         let mut profiles = self.store.get_all_profiles(guild_id).await?;
-        // Sort by total_xp desc
-        profiles.sort_by(|a, b| b.total_xp.cmp(&a.total_xp));
+        // Same order as the leaderboard: prestige first, then total_xp desc
+        profiles.sort_by(|a, b| {
+            b.prestige_level
+                .cmp(&a.prestige_level)
+                .then(b.total_xp.cmp(&a.total_xp))
+        });
 
         for (index, profile) in profiles.iter_mut().enumerate() {
             let rank = (index + 1) as u32;
-            let previous_rank = profile.previous_rank;
-            let best_rank = profile.best_rank;
-            // Update best rank if improved
-            if rank < best_rank {
-                profile.best_rank = rank;
+            let before = (
+                profile.best_rank,
+                profile.previous_rank,
+                profile.rank_improvement,
+            );
+            Self::apply_rank(profile, rank);
+
+            if (
+                profile.best_rank,
+                profile.previous_rank,
+                profile.rank_improvement,
+            ) != before
+            {
+                self.store.save_user_profile(profile.clone()).await?;
+            }
+        }
+
+        Ok(profiles)
+    }
+
+    /// Update a profile's rank history fields for its new `rank`.
+    fn apply_rank(profile: &mut UserProfile, rank: u32) {
+        let previous_rank = profile.previous_rank;
+        // Update best rank if improved
+        if rank < profile.best_rank {
+            profile.best_rank = rank;
+        }
+        // Update rank improvement (largest climb between two refreshes)
+        if previous_rank != 999 && previous_rank > rank {
+            let improvement = previous_rank - rank;
+            if improvement > profile.rank_improvement {
+                profile.rank_improvement = improvement;
             }
-            // Update rank improvement
-            if previous_rank != 999 && previous_rank > rank {
-                let improvement = previous_rank - rank;
-                if improvement > profile.rank_improvement {
-                    profile.rank_improvement = improvement;
+        }
+        profile.previous_rank = rank;
+    }
+
+    /// Remember that a guild's ranks need recomputing.
+    fn mark_ranks_stale(&self, guild_id: u64) {
+        self.stale_rank_guilds.insert(guild_id);
+    }
+
+    /// Recompute ranks for every guild whose XP changed since the last refresh.
+    /// Guilds with no activity are skipped entirely. Returns how many guilds
+    /// were refreshed.
+    pub async fn refresh_stale_ranks(&self) -> usize {
+        let guild_ids: Vec<u64> = self.stale_rank_guilds.iter().map(|id| *id).collect();
+        let mut refreshed = 0;
+
+        for guild_id in guild_ids {
+            // Clear first so XP gained during the recompute marks it stale again
+            self.stale_rank_guilds.remove(&guild_id);
+
+            match self.recalculate_and_update_ranks(guild_id).await {
+                Ok(_) => refreshed += 1,
+                Err(e) => {
+                    tracing::warn!(guild_id, error = %e, "Failed to refresh leaderboard ranks");
+                    self.stale_rank_guilds.insert(guild_id);
                 }
             }
-            profile.previous_rank = rank;
+        }
+
+        refreshed
+    }
+
+    /// What claiming the daily reward today would do to a member's streak.
+    /// `None` if they have no streak yet or already claimed today.
+    pub async fn daily_streak_status(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Option<StreakStatus>, LevelingError> {
+        Self::validate_ids(user_id, guild_id)?;
+        let Some(profile) = self.store.get_user_profile(user_id, guild_id).await? else {
+            return Ok(None);
+        };
+        let Some(last_daily) = profile.last_daily else {
+            return Ok(None);
+        };
 
-            // Save profile back
-            self.store.save_user_profile(profile.clone()).await?;
+        let tz = self.daily_timezone(guild_id).await?;
+        let now = Utc::now();
+        let delta_days = (local_date(tz, now) - local_date(tz, last_daily)).num_days();
+        if delta_days < 1 || profile.daily_streak == 0 {
+            return Ok(None);
         }
+        Ok(Some(self.streak_status(delta_days)))
+    }
 
-        Ok(profiles)
+    /// Streak outcome for a claim `delta_days` after the previous one.
+    fn streak_status(&self, delta_days: i64) -> StreakStatus {
+        let allowed = 1 + i64::from(self.config.daily_streak_grace_days);
+        if delta_days <= allowed {
+            StreakStatus::Continues
+        } else if delta_days == allowed + 1 {
+            StreakStatus::NeedsFreeze
+        } else {
+            StreakStatus::Resets
+        }
     }
 
     /// Claim the daily reward for a user. Returns the amount of XP awarded and whether the user leveled up.
+    ///
+    /// `use_streak_freeze` keeps a `StreakStatus::NeedsFreeze` streak going; the
+    /// caller is responsible for consuming the item.
     pub async fn claim_daily(
         &self,
         user_id: u64,
         guild_id: u64,
         boosted: bool,
         member_count: u64,
+        use_streak_freeze: bool,
+    ) -> Result<(u64, Option<LevelUpEvent>), LevelingError> {
+        self.claim_daily_at(
+            user_id,
+            guild_id,
+            boosted,
+            member_count,
+            use_streak_freeze,
+            chrono::Utc::now(),
+        )
+        .await
+    }
+
+    async fn claim_daily_at(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        boosted: bool,
+        member_count: u64,
+        use_streak_freeze: bool,
+        now: DateTime<Utc>,
     ) -> Result<(u64, Option<LevelUpEvent>), LevelingError> {
         Self::validate_ids(user_id, guild_id)?;
 
@@ -1151,9 +2342,10 @@ impl<S: XpStore> LevelingService<S> {
             None => self.create_default_profile(user_id, guild_id),
         };
 
-        let now = chrono::Utc::now();
-        let today = now.date_naive();
-        let last_daily_date = profile.last_daily.map(|d| d.date_naive());
+        // "Today" is the guild's local day, so the reset happens at local midnight
+        let tz = self.daily_timezone(guild_id).await?;
+        let today = local_date(tz, now);
+        let last_daily_date = profile.last_daily.map(|d| local_date(tz, d));
 
         if let Some(last) = last_daily_date {
             if last == today {
@@ -1166,10 +2358,10 @@ impl<S: XpStore> LevelingService<S> {
         let streak = match last_daily_date {
             Some(last) => {
                 let delta_days = (today - last).num_days();
-                if delta_days == 1 {
-                    profile.daily_streak += 1;
-                } else {
-                    profile.daily_streak = 1;
+                match self.streak_status(delta_days) {
+                    StreakStatus::Continues => profile.daily_streak += 1,
+                    StreakStatus::NeedsFreeze if use_streak_freeze => profile.daily_streak += 1,
+                    StreakStatus::NeedsFreeze | StreakStatus::Resets => profile.daily_streak = 1,
                 }
                 profile.daily_streak
             }
@@ -1179,11 +2371,8 @@ impl<S: XpStore> LevelingService<S> {
             }
         };
 
-        let streak_bonus = std::cmp::min(
-            (streak.saturating_sub(1) as u64).saturating_mul(Self::STREAK_BONUS_STEP),
-            Self::STREAK_BONUS_CAP,
-        );
-        let base_daily_xp = Self::BASE_DAILY_REWARD + streak_bonus;
+        let daily = self.config.daily;
+        let base_daily_xp = daily.base_xp + daily.streak_bonus(streak);
         let award_xp = self.apply_xp_boost(base_daily_xp, boosted);
 
         profile.total_xp = profile.total_xp.saturating_add(award_xp);
@@ -1198,14 +2387,15 @@ impl<S: XpStore> LevelingService<S> {
 
         // Persist and check level up
         let old_level = profile.level;
-        let leveled = self.handle_level_up_internal(&mut profile);
+        let levels_reached = self.handle_level_up_internal(&mut profile);
         self.store.save_user_profile(profile.clone()).await?;
+        self.mark_ranks_stale(guild_id);
 
         // Now handle the server-wide daily goal
         let mut daily_goal = match self.store.get_daily_goal(guild_id).await? {
             Some(g) => g,
             None => DailyGoal {
-                date: now.date_naive().to_string(),
+                date: today.to_string(),
                 target: self.calculate_daily_goal_target(member_count),
                 progress: 0,
                 claimers: vec![],
@@ -1215,9 +2405,9 @@ impl<S: XpStore> LevelingService<S> {
         };
 
         // If the stored goal has a different date, reset
-        if daily_goal.date != now.date_naive().to_string() {
+        if daily_goal.date != today.to_string() {
             daily_goal = DailyGoal {
-                date: now.date_naive().to_string(),
+                date: today.to_string(),
                 target: self.calculate_daily_goal_target(member_count),
                 progress: 0,
                 claimers: vec![],
@@ -1251,10 +2441,10 @@ impl<S: XpStore> LevelingService<S> {
                             None => self.create_default_profile(claimer_id, guild_id),
                         };
                     claimer_profile.total_xp =
-                        claimer_profile.total_xp.saturating_add(Self::GOAL_BONUS_XP);
+                        claimer_profile.total_xp.saturating_add(daily.goal_bonus_xp);
                     self.record_xp_event(
                         &mut claimer_profile,
-                        Self::GOAL_BONUS_XP,
+                        daily.goal_bonus_xp,
                         "goal_bonus".to_string(),
                         Some(daily_goal.date.clone()),
                     );
@@ -1269,7 +2459,7 @@ impl<S: XpStore> LevelingService<S> {
                     daily_goal.bonus_awarded_to.push(claimer_id);
                     newly_awarded.push(claimer_id);
                     if claimer_id == user_id {
-                        user_goal_bonus = Self::GOAL_BONUS_XP;
+                        user_goal_bonus = daily.goal_bonus_xp;
                     }
                 }
             }
@@ -1278,7 +2468,7 @@ impl<S: XpStore> LevelingService<S> {
         self.store.save_daily_goal(guild_id, daily_goal).await?;
 
         let total_award = award_xp + user_goal_bonus;
-        if leveled {
+        if !levels_reached.is_empty() {
             Ok((
                 total_award,
                 Some(LevelUpEvent {
@@ -1287,6 +2477,7 @@ impl<S: XpStore> LevelingService<S> {
                     old_level,
                     new_level: profile.level,
                     total_xp: profile.total_xp + user_goal_bonus,
+                    levels_reached,
                 }),
             ))
         } else {
@@ -1356,18 +2547,20 @@ impl<S: XpStore> LevelingService<S> {
         let _new_ach = self.check_and_award_achievements_internal(&mut profile);
 
         // Handle level up (recomputes xp_to_next_level)
-        let leveled = self.handle_level_up_internal(&mut profile);
+        let levels_reached = self.handle_level_up_internal(&mut profile);
 
         // Save profile back to the store
         self.store.save_user_profile(profile.clone()).await?;
+        self.mark_ranks_stale(guild_id);
 
-        if leveled {
+        if !levels_reached.is_empty() {
             Ok(Some(LevelUpEvent {
                 user_id,
                 guild_id,
                 old_level,
                 new_level: profile.level,
                 total_xp: profile.total_xp,
+                levels_reached,
             }))
         } else {
             Ok(None)
@@ -1379,8 +2572,14 @@ impl<S: XpStore> LevelingService<S> {
             return self.config.xp_per_message_min;
         }
 
-        let mut rng = rand::thread_rng();
-        rng.gen_range(self.config.xp_per_message_min..=self.config.xp_per_message_max)
+        let range = self.config.xp_per_message_min..=self.config.xp_per_message_max;
+        match &self.rng {
+            Some(rng) => rng
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .gen_range(range),
+            None => rand::thread_rng().gen_range(range),
+        }
     }
 
     /// Determine the daily goal target for the guild. Default to 15 claims.
@@ -1397,22 +2596,22 @@ impl<S: XpStore> LevelingService<S> {
         member_count: u64,
     ) -> Result<DailyGoal, LevelingError> {
         Self::validate_guild_id(guild_id)?;
-        let now = chrono::Utc::now();
+        let today = local_date(self.daily_timezone(guild_id).await?, Utc::now());
         let mut daily_goal = self
             .store
             .get_daily_goal(guild_id)
             .await?
             .unwrap_or(DailyGoal {
-                date: now.date_naive().to_string(),
+                date: today.to_string(),
                 target: self.calculate_daily_goal_target(member_count),
                 progress: 0,
                 claimers: vec![],
                 completed: false,
                 bonus_awarded_to: vec![],
             });
-        if daily_goal.date != now.date_naive().to_string() {
+        if daily_goal.date != today.to_string() {
             daily_goal = DailyGoal {
-                date: now.date_naive().to_string(),
+                date: today.to_string(),
                 target: self.calculate_daily_goal_target(member_count),
                 progress: 0,
                 claimers: vec![],
@@ -1441,21 +2640,37 @@ mod tests {
 
     #[async_trait]
     impl XpStore for NoopStore {
-        async fn get_xp(&self, _: u64, _: u64) -> Result<u64, LevelingError> {
+        async fn get_leaderboard_page(
+            &self,
+            _: u64,
+            _: usize,
+            _: usize,
+            _: &[u64],
+        ) -> Result<Vec<UserStats>, LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
-        async fn add_xp(&self, _: u64, _: u64, _: u64) -> Result<(), LevelingError> {
+        async fn count_profiles(&self, _: u64, _: &[u64]) -> Result<u64, LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
-        async fn get_leaderboard(&self, _: u64, _: usize) -> Result<Vec<UserStats>, LevelingError> {
+        async fn count_ranked_above(
+            &self,
+            _: u64,
+            _: u32,
+            _: u64,
+            _: u64,
+            _: &[u64],
+        ) -> Result<u64, LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
@@ -1467,12 +2682,14 @@ mod tests {
         ) -> Result<(), LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
         async fn get_last_xp_time(&self, _: u64, _: u64) -> Result<Option<Instant>, LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
@@ -1483,30 +2700,35 @@ mod tests {
         ) -> Result<Option<UserProfile>, LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
         async fn save_user_profile(&self, _: UserProfile) -> Result<(), LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
         async fn get_all_profiles(&self, _: u64) -> Result<Vec<UserProfile>, LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
         async fn get_daily_goal(&self, _: u64) -> Result<Option<DailyGoal>, LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
         async fn save_daily_goal(&self, _: u64, _: DailyGoal) -> Result<(), LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
 
@@ -1517,9 +2739,119 @@ mod tests {
         ) -> Result<Vec<UserProfile>, LevelingError> {
             Err(LevelingError::StorageError(
                 "Noop store should not be used".to_string(),
+                None,
             ))
         }
-    }
+
+        async fn get_channel_multipliers(
+            &self,
+            _: u64,
+        ) -> Result<HashMap<u64, f64>, LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn set_channel_multiplier(
+            &self,
+            _: u64,
+            _: u64,
+            _: Option<f64>,
+        ) -> Result<(), LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn get_excluded_channels(&self, _: u64) -> Result<HashSet<u64>, LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn set_channel_excluded(&self, _: u64, _: u64, _: bool) -> Result<(), LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn get_voice_minutes(
+            &self,
+            _: u64,
+            _: u64,
+        ) -> Result<HashMap<u64, u64>, LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn add_voice_minutes(
+            &self,
+            _: u64,
+            _: u64,
+            _: u64,
+            _: u64,
+        ) -> Result<(), LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn get_level_up_mention(
+            &self,
+            _: u64,
+        ) -> Result<Option<LevelUpMention>, LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn set_level_up_mention(
+            &self,
+            _: u64,
+            _: LevelUpMention,
+        ) -> Result<(), LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn get_daily_timezone(&self, _: u64) -> Result<Option<String>, LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn set_daily_timezone(&self, _: u64, _: &str) -> Result<(), LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn get_xp_boosts(&self, _: u64, _: u64) -> Result<Vec<XpBoost>, LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+
+        async fn set_xp_boosts(&self, _: u64, _: u64, _: &[XpBoost]) -> Result<(), LevelingError> {
+            Err(LevelingError::StorageError(
+                "Noop store should not be used".to_string(),
+                None,
+            ))
+        }
+    }
 
     fn make_service() -> LevelingService<NoopStore> {
         LevelingService::new(NoopStore)
@@ -1554,9 +2886,16 @@ mod tests {
 
     #[test]
     fn leveling_error_messages_are_descriptive() {
-        let storage_error = LevelingError::StorageError("db down".into());
+        let storage_error = LevelingError::StorageError("db down".into(), None);
         assert!(storage_error.to_string().contains("db down"));
 
+        // The underlying error stays reachable for logging
+        let io = std::io::Error::other("disk full");
+        let storage_error = LevelingError::storage(io);
+        assert_eq!(storage_error.to_string(), "Storage error: disk full");
+        let source = std::error::Error::source(&storage_error).unwrap();
+        assert!(source.downcast_ref::<std::io::Error>().is_some());
+
         let invalid_id = LevelingError::InvalidId;
         assert_eq!(invalid_id.to_string(), "Invalid user or guild ID");
     }
@@ -1572,22 +2911,163 @@ mod tests {
 
         // First claim
         let (xp, levelup) = service
-            .claim_daily(user_id, guild_id, false, 1)
+            .claim_daily(user_id, guild_id, false, 1, false)
             .await
             .unwrap();
-        assert!(
-            xp >= LevelingService::<crate::infra::leveling::InMemoryXpStore>::BASE_DAILY_REWARD
-        );
+        assert!(xp >= service.config.daily.base_xp);
         assert!(levelup.is_none());
 
         // Second claim same day should return 0
         let (xp2, _) = service
-            .claim_daily(user_id, guild_id, false, 1)
+            .claim_daily(user_id, guild_id, false, 1, false)
             .await
             .unwrap();
         assert_eq!(xp2, 0);
     }
 
+    #[tokio::test]
+    async fn test_daily_resets_at_guild_local_midnight() {
+        let service = LevelingService::new(crate::infra::leveling::InMemoryXpStore::new());
+        let (la_guild, utc_guild) = (1u64, 2u64);
+        service
+            .set_daily_timezone(la_guild, "America/Los_Angeles")
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.set_daily_timezone(la_guild, "Mars/Olympus").await,
+            Err(LevelingError::InvalidTimezone(_))
+        ));
+
+        // 23:00 and 01:00 Los Angeles time (UTC-8) - the same UTC day
+        let late = Utc.with_ymd_and_hms(2026, 1, 16, 7, 0, 0).unwrap();
+        let early = Utc.with_ymd_and_hms(2026, 1, 16, 9, 0, 0).unwrap();
+
+        for guild_id in [la_guild, utc_guild] {
+            service
+                .claim_daily_at(1, guild_id, false, 100, false, late)
+                .await
+                .unwrap();
+        }
+        let (la_xp, _) = service
+            .claim_daily_at(1, la_guild, false, 100, false, early)
+            .await
+            .unwrap();
+        let (utc_xp, _) = service
+            .claim_daily_at(1, utc_guild, false, 100, false, early)
+            .await
+            .unwrap();
+
+        assert!(la_xp > 0, "01:00 local is a new day in Los Angeles");
+        assert_eq!(utc_xp, 0, "both claims fall on the same UTC day");
+        let profile = service.get_user_profile(1, la_guild).await.unwrap();
+        assert_eq!(profile.daily_streak, 2);
+
+        let la: Tz = "America/Los_Angeles".parse().unwrap();
+        assert_eq!(
+            next_local_midnight(la, late),
+            Utc.with_ymd_and_hms(2026, 1, 16, 8, 0, 0).unwrap()
+        );
+        // Chile skips midnight when DST starts: the day begins at 01:00 UTC-3
+        let santiago: Tz = "America/Santiago".parse().unwrap();
+        let before_change = Utc.with_ymd_and_hms(2024, 9, 7, 12, 0, 0).unwrap();
+        assert_eq!(
+            next_local_midnight(santiago, before_change),
+            Utc.with_ymd_and_hms(2024, 9, 8, 4, 0, 0).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_daily_rewards_follow_config() {
+        let daily = DailyRewardConfig {
+            base_xp: 100,
+            streak_bonus_step: 10,
+            streak_bonus_cap: 20,
+            goal_bonus_xp: 0,
+        };
+        assert!(daily.validate().is_ok());
+        assert_eq!(
+            (1..=4).map(|d| daily.streak_bonus(d)).collect::<Vec<_>>(),
+            vec![0, 10, 20, 20]
+        );
+        assert!(DailyRewardConfig {
+            streak_bonus_cap: 5,
+            ..daily
+        }
+        .validate()
+        .is_err());
+
+        let service = LevelingService::with_config(
+            crate::infra::leveling::InMemoryXpStore::new(),
+            LevelingConfig {
+                daily,
+                ..LevelingConfig::default()
+            },
+        );
+        let start = Utc::now();
+        let (first, _) = service
+            .claim_daily_at(1, 1, false, 100, false, start)
+            .await
+            .unwrap();
+        let (second, _) = service
+            .claim_daily_at(1, 1, false, 100, false, start + chrono::Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!((first, second), (100, 110));
+    }
+
+    #[tokio::test]
+    async fn test_daily_streak_grace_period() {
+        let service = &LevelingService::new(crate::infra::leveling::InMemoryXpStore::new());
+        let start = Utc::now();
+        let day = |n: i64| start + chrono::Duration::days(n);
+        let streak = |user_id| async move {
+            service
+                .get_user_profile(user_id, 1)
+                .await
+                .unwrap()
+                .daily_streak
+        };
+        // Claim on day 0 and then after `delta` days
+        let claim_after = |user_id, delta, use_freeze| async move {
+            service
+                .claim_daily_at(user_id, 1, false, 1, false, day(0))
+                .await
+                .unwrap();
+            service
+                .claim_daily_at(user_id, 1, false, 1, use_freeze, day(delta))
+                .await
+                .unwrap();
+        };
+
+        // Next day continues
+        claim_after(1, 1, false).await;
+        assert_eq!(streak(1).await, 2);
+        // One missed day is within the default grace
+        claim_after(2, 2, false).await;
+        assert_eq!(streak(2).await, 2);
+        // Two missed days reset the streak...
+        claim_after(3, 3, false).await;
+        assert_eq!(streak(3).await, 1);
+        // ...unless a streak freeze is used
+        claim_after(4, 3, true).await;
+        assert_eq!(streak(4).await, 2);
+        // A freeze only covers one extra day
+        claim_after(5, 4, true).await;
+        assert_eq!(streak(5).await, 1);
+
+        // Grace 0 is the strict behavior
+        let strict = LevelingService::with_config(
+            crate::infra::leveling::InMemoryXpStore::new(),
+            LevelingConfig {
+                daily_streak_grace_days: 0,
+                ..LevelingConfig::default()
+            },
+        );
+        assert_eq!(strict.streak_status(1), StreakStatus::Continues);
+        assert_eq!(strict.streak_status(2), StreakStatus::NeedsFreeze);
+        assert_eq!(strict.streak_status(3), StreakStatus::Resets);
+    }
+
     #[tokio::test]
     async fn test_process_message_cooldown() {
         let store = crate::infra::leveling::InMemoryXpStore::new();
@@ -1597,13 +3077,13 @@ mod tests {
 
         // First message should award XP
         let res = service
-            .process_message(user_id, guild_id, false, None)
+            .process_message(user_id, guild_id, 1, false, None)
             .await;
         assert!(res.is_ok());
 
         // Second message within cooldown should return OnCooldown
         let res2 = service
-            .process_message(user_id, guild_id, false, None)
+            .process_message(user_id, guild_id, 1, false, None)
             .await;
         assert!(matches!(res2, Err(LevelingError::OnCooldown(_))));
     }
@@ -1623,14 +3103,14 @@ mod tests {
 
         // Non-boosted message
         let res = service
-            .process_message(user_id, guild_id, false, None)
+            .process_message(user_id, guild_id, 1, false, None)
             .await
             .unwrap();
         assert!(res.is_none()); // 10 XP shouldn't reach a new level
 
         // Boosted message should give 15 XP instead of 10
         let res2 = service
-            .process_message(user_id, guild_id, true, None)
+            .process_message(user_id, guild_id, 1, true, None)
             .await
             .unwrap();
         assert!(res2.is_none());
@@ -1638,6 +3118,407 @@ mod tests {
         assert!(profile.total_xp >= 25);
     }
 
+    #[tokio::test]
+    async fn test_seeded_rolls_award_exact_xp() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let config = LevelingConfig {
+            xp_per_message_min: 15,
+            xp_per_message_max: 25,
+            cooldown: Duration::from_secs(0),
+            ..Default::default()
+        };
+        let service =
+            LevelingService::with_config(crate::infra::leveling::InMemoryXpStore::new(), config)
+                .with_rng(StdRng::seed_from_u64(42));
+        // The same seed replays the rolls the service makes
+        let mut expected_rolls = StdRng::seed_from_u64(42);
+        let (user_id, guild_id) = (21u64, 22u64);
+
+        for message in 0..20 {
+            let boosted = message % 2 == 1;
+            let roll: u64 = expected_rolls.gen_range(15..=25);
+            // Boosts multiply by 1.5 and round half away from zero
+            let expected = if boosted {
+                (roll * 3).div_ceil(2)
+            } else {
+                roll
+            };
+
+            service
+                .process_message(user_id, guild_id, 1, boosted, None)
+                .await
+                .unwrap();
+            // Achievement rewards are recorded separately, after the message
+            let profile = service.get_user_profile(user_id, guild_id).await.unwrap();
+            let awarded = profile
+                .xp_history
+                .iter()
+                .rev()
+                .find(|event| event.source == "message")
+                .map(|event| event.amount);
+            assert_eq!(awarded, Some(expected), "message {message}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_boost_rounds_half_xp_up() {
+        let config = LevelingConfig {
+            xp_per_message_min: 15,
+            xp_per_message_max: 15,
+            cooldown: Duration::from_secs(0),
+            ..Default::default()
+        };
+        let service =
+            LevelingService::with_config(crate::infra::leveling::InMemoryXpStore::new(), config);
+
+        service.process_message(1, 2, 1, true, None).await.unwrap();
+        // 15 * 1.5 = 22.5
+        let profile = service.get_user_profile(1, 2).await.unwrap();
+        assert_eq!(profile.total_xp, 23);
+    }
+
+    #[tokio::test]
+    async fn test_channel_multiplier_zero_disables_xp() {
+        let store = crate::infra::leveling::InMemoryXpStore::new();
+        let service = LevelingService::new(store);
+        let (user_id, guild_id, memes) = (7u64, 8u64, 500u64);
+
+        service
+            .set_channel_multiplier(guild_id, memes, 0.0)
+            .await
+            .unwrap();
+
+        let res = service
+            .process_message(user_id, guild_id, memes, false, None)
+            .await
+            .unwrap();
+        assert!(res.is_none());
+
+        let profile = service.get_user_profile(user_id, guild_id).await.unwrap();
+        assert_eq!(profile.total_xp, 0);
+        assert_eq!(profile.total_messages, 1);
+        // No cooldown started, so XP still works in other channels right away
+        assert!(service
+            .process_message(user_id, guild_id, 501, false, None)
+            .await
+            .is_ok());
+        let profile = service.get_user_profile(user_id, guild_id).await.unwrap();
+        assert!(profile.total_xp > 0);
+    }
+
+    #[tokio::test]
+    async fn test_channel_multiplier_doubles_xp() {
+        let store = crate::infra::leveling::InMemoryXpStore::new();
+        let config = LevelingConfig {
+            xp_per_message_min: 10,
+            xp_per_message_max: 10,
+            cooldown: Duration::from_secs(0),
+            ..LevelingConfig::default()
+        };
+        let service = LevelingService::with_config(store, config);
+        let (user_id, guild_id, serious) = (7u64, 8u64, 600u64);
+
+        service
+            .set_channel_multiplier(guild_id, serious, 2.0)
+            .await
+            .unwrap();
+        assert_eq!(
+            service.channel_multiplier(guild_id, serious).await.unwrap(),
+            2.0
+        );
+
+        service
+            .process_message(user_id, guild_id, serious, false, None)
+            .await
+            .unwrap();
+        let profile = service.get_user_profile(user_id, guild_id).await.unwrap();
+        assert_eq!(profile.total_xp, 20);
+
+        // Out-of-range values are rejected
+        assert!(matches!(
+            service
+                .set_channel_multiplier(guild_id, serious, 10.0)
+                .await,
+            Err(LevelingError::InvalidMultiplier(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_excluding_a_channel_keeps_its_multiplier() {
+        let store = crate::infra::leveling::InMemoryXpStore::new();
+        let config = LevelingConfig {
+            xp_per_message_min: 10,
+            xp_per_message_max: 10,
+            cooldown: Duration::from_secs(0),
+            ..LevelingConfig::default()
+        };
+        let service = LevelingService::with_config(store, config);
+        let (user_id, guild_id, serious) = (7u64, 8u64, 600u64);
+
+        service
+            .set_channel_multiplier(guild_id, serious, 2.0)
+            .await
+            .unwrap();
+        service
+            .set_channel_excluded(guild_id, serious, true)
+            .await
+            .unwrap();
+        service
+            .process_message(user_id, guild_id, serious, false, None)
+            .await
+            .unwrap();
+        let profile = service.get_user_profile(user_id, guild_id).await.unwrap();
+        assert_eq!(profile.total_xp, 0);
+
+        // Including it again brings back the 2x, not 1x
+        service
+            .set_channel_excluded(guild_id, serious, false)
+            .await
+            .unwrap();
+        service
+            .process_message(user_id, guild_id, serious, false, None)
+            .await
+            .unwrap();
+        let profile = service.get_user_profile(user_id, guild_id).await.unwrap();
+        assert_eq!(profile.total_xp, 20);
+    }
+
+    #[tokio::test]
+    async fn test_voice_sessions_accumulate_minutes() {
+        let store = crate::infra::leveling::InMemoryXpStore::new();
+        let service = LevelingService::new(store);
+        let (user_id, guild_id, lounge, gaming) = (1u64, 2u64, 10u64, 20u64);
+        let start = Instant::now();
+        let at = |minutes: u64| start + Duration::from_secs(minutes * 60);
+        let unmuted = VoiceFlags::default();
+        let self_muted = VoiceFlags {
+            self_mute: true,
+            ..unmuted
+        };
+
+        assert_eq!(
+            service.get_voice_stats(user_id, guild_id).await.unwrap(),
+            VoiceStats::default()
+        );
+
+        service
+            .record_voice_state_at(user_id, guild_id, Some(lounge), unmuted, at(0))
+            .await
+            .unwrap();
+        // A self-mute in the same channel doesn't split or pause the session
+        service
+            .record_voice_state_at(user_id, guild_id, Some(lounge), self_muted, at(5))
+            .await
+            .unwrap();
+        service
+            .record_voice_state_at(user_id, guild_id, Some(gaming), unmuted, at(10))
+            .await
+            .unwrap();
+        service
+            .record_voice_state_at(user_id, guild_id, None, unmuted, at(35))
+            .await
+            .unwrap();
+        service
+            .award_xp(user_id, guild_id, 30, XpSource::VoiceMinute)
+            .await
+            .unwrap();
+
+        let stats = service
+            .get_voice_stats_at(user_id, guild_id, at(60))
+            .await
+            .unwrap();
+        assert_eq!(stats.total_minutes, 35);
+        assert_eq!(stats.top_channel, Some((gaming, 25)));
+        assert_eq!(stats.voice_xp, 30);
+    }
+
+    #[tokio::test]
+    async fn test_voice_time_pauses_while_muted_or_deafened() {
+        let store = crate::infra::leveling::InMemoryXpStore::new();
+        let service = LevelingService::new(store);
+        let (user_id, guild_id, lounge, gaming) = (1u64, 2u64, 10u64, 20u64);
+        let start = Instant::now();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+        let unmuted = VoiceFlags::default();
+        let deafened = VoiceFlags {
+            self_deaf: true,
+            self_mute: true,
+            ..unmuted
+        };
+        let server_muted = VoiceFlags {
+            mute: true,
+            ..unmuted
+        };
+
+        let transitions = [
+            // 0-10m counts
+            (0, Some(lounge), unmuted),
+            // 10-25m deafened
+            (600, Some(lounge), deafened),
+            // 25-30m counts again, adding to the first 10 minutes
+            (1500, Some(lounge), unmuted),
+            // Switching while server-muted: 30-40m in gaming doesn't count
+            (1800, Some(gaming), server_muted),
+            // Unmuted 40-52m, then leaves
+            (2400, Some(gaming), unmuted),
+            (3120, None, unmuted),
+            // Joining already deafened never counts
+            (3600, Some(lounge), deafened),
+        ];
+        for (seconds, channel_id, flags) in transitions {
+            service
+                .record_voice_state_at(user_id, guild_id, channel_id, flags, at(seconds))
+                .await
+                .unwrap();
+        }
+
+        let stats = service
+            .get_voice_stats_at(user_id, guild_id, at(7200))
+            .await
+            .unwrap();
+        assert_eq!(stats.total_minutes, 15 + 12);
+        assert_eq!(stats.top_channel, Some((lounge, 15)));
+
+        // Which flags pause is configurable
+        assert_eq!(
+            VoiceFlags::parse_list(" self_mute, DEAF ").unwrap(),
+            VoiceFlags {
+                self_mute: true,
+                deaf: true,
+                ..VoiceFlags::default()
+            }
+        );
+        assert!(!deafened.intersects(VoiceFlags::parse_list("").unwrap()));
+        assert!(VoiceFlags::parse_list("muted").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_multi_level_jump_reports_every_level() {
+        let store = crate::infra::leveling::InMemoryXpStore::new();
+        let service = LevelingService::new(store);
+
+        let level_up = service
+            .award_xp(1, 2, service.xp_for_level(7), XpSource::VoiceMinute)
+            .await
+            .unwrap()
+            .expect("should level up");
+        assert_eq!((level_up.old_level, level_up.new_level), (1, 7));
+        assert_eq!(level_up.levels_reached, vec![2, 3, 4, 5, 6, 7]);
+
+        // A small grant after that crosses exactly one level
+        let to_next = service.xp_for_level(8) - level_up.total_xp;
+        let level_up = service
+            .award_xp(1, 2, to_next, XpSource::VoiceMinute)
+            .await
+            .unwrap()
+            .expect("should level up");
+        assert_eq!(level_up.levels_reached, vec![8]);
+    }
+
+    #[tokio::test]
+    async fn test_level_up_announcements_are_coalesced() {
+        let store = crate::infra::leveling::InMemoryXpStore::new();
+        let service = LevelingService::new(store);
+        let (user_id, guild_id) = (1u64, 2u64);
+
+        // One big grant jumping three levels is a single event
+        let level_up = service
+            .award_xp(
+                user_id,
+                guild_id,
+                service.xp_for_level(4),
+                XpSource::VoiceMinute,
+            )
+            .await
+            .unwrap()
+            .expect("should level up");
+        assert_eq!((level_up.old_level, level_up.new_level), (1, 4));
+
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let event = |old_level, new_level| LevelUpEvent {
+            user_id,
+            guild_id,
+            old_level,
+            new_level,
+            total_xp: service.xp_for_level(new_level),
+            levels_reached: (old_level + 1..=new_level).collect(),
+        };
+
+        assert!(service
+            .queue_level_up_announcement_at(level_up, at(0))
+            .is_some());
+        // Further level-ups inside the cooldown are held back and merged
+        assert!(service
+            .queue_level_up_announcement_at(event(4, 5), at(10))
+            .is_none());
+        assert!(service
+            .queue_level_up_announcement_at(event(5, 6), at(20))
+            .is_none());
+        assert!(service.take_due_level_ups_at(at(30)).is_empty());
+
+        let due = service.take_due_level_ups_at(at(60));
+        assert_eq!(due.len(), 1);
+        assert_eq!((due[0].old_level, due[0].new_level), (4, 6));
+        assert_eq!(due[0].levels_reached, vec![5, 6]);
+        assert!(service.take_due_level_ups_at(at(200)).is_empty());
+    }
+
+    #[test]
+    fn test_rank_by_achievements_uses_xp_tiebreaker() {
+        let all = builtin_achievements();
+        let mut sorted = all.clone();
+        sorted.sort_by_key(|a| a.reward_xp);
+        let (cheap, pricey) = (&sorted[0], &sorted[sorted.len() - 1]);
+
+        let profile = |user_id: u64, achievements: Vec<&Achievement>| {
+            let mut profile = UserProfile::default_with_ids(user_id, 1);
+            profile.achievements = achievements.iter().map(|a| a.id.clone()).collect();
+            profile
+        };
+        let profiles = vec![
+            profile(1, vec![cheap]),
+            profile(2, vec![cheap, pricey]),
+            profile(3, vec![pricey]),
+            profile(4, vec![]),
+        ];
+
+        let standings = rank_by_achievements(&profiles, &all);
+        let order: Vec<u64> = standings.iter().map(|s| s.user_id).collect();
+        assert_eq!(order, vec![2, 3, 1]);
+        assert_eq!(standings[0].unlocked, 2);
+        assert_eq!(
+            standings[0].achievement_xp,
+            cheap.reward_xp + pricey.reward_xp
+        );
+    }
+
+    #[test]
+    fn test_nearest_achievements_sorted_by_progress() {
+        let service = make_service();
+        let mut profile = UserProfile::default_with_ids(1, 1);
+        profile.level = 9;
+        profile.total_messages = 80;
+        profile.achievements = vec!["first_steps".to_string()];
+
+        let nearest = service.get_nearest_achievements(&profile, 5);
+        assert_eq!(nearest.len(), 5);
+        assert!(nearest.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(nearest.iter().all(|(ach, ..)| ach.id != "first_steps"));
+
+        // Level 9/10 is the closest goal
+        assert_eq!(nearest[0].0.id, "rising_star");
+        assert_eq!((nearest[0].2, nearest[0].3), (9, 10));
+        assert_eq!(
+            service
+                .get_next_achievement(&profile)
+                .map(|(ach, ..)| ach.id),
+            Some("rising_star".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn test_award_xp_records_event_and_achievements() {
         let store = crate::infra::leveling::InMemoryXpStore::new();
@@ -1658,6 +3539,49 @@ mod tests {
         assert!(profile.achievements.iter().any(|id| id == "xp_collector"));
     }
 
+    #[test]
+    fn test_imported_achievements_are_not_rewarded_again() {
+        let service = make_service();
+        let reward = |id: &str| {
+            builtin_achievements()
+                .into_iter()
+                .find(|a| a.id == id)
+                .unwrap()
+                .reward_xp
+        };
+
+        // An imported profile that already lists (and was paid for) two achievements
+        let mut profile = UserProfile::default_with_ids(1, 1);
+        profile.level = 10;
+        profile.total_xp = 500;
+        profile.achievements = vec!["first_steps".to_string(), "rising_star".to_string()];
+
+        assert!(service
+            .check_and_award_achievements_internal(&mut profile)
+            .is_empty());
+        assert_eq!(profile.total_xp, 500);
+        assert!(profile.earned_achievements.contains("rising_star"));
+
+        // A partially rebuilt list doesn't re-award the missing entry
+        profile.achievements = vec!["first_steps".to_string()];
+        assert!(service
+            .check_and_award_achievements_internal(&mut profile)
+            .is_empty());
+        assert_eq!(profile.total_xp, 500);
+        assert!(profile.achievements.iter().any(|id| id == "rising_star"));
+
+        // Genuinely new achievements are still granted exactly once
+        profile.total_messages = 100;
+        assert_eq!(
+            service.check_and_award_achievements_internal(&mut profile),
+            vec!["chatterbox".to_string()]
+        );
+        assert!(service
+            .check_and_award_achievements_internal(&mut profile)
+            .is_empty());
+        assert_eq!(profile.total_xp, 500 + reward("chatterbox"));
+    }
+
     #[tokio::test]
     async fn test_increment_command_count_achievements() {
         let store = crate::infra::leveling::InMemoryXpStore::new();
@@ -1686,16 +3610,59 @@ mod tests {
             has_image: true,
             is_long: true,
             has_link: true,
+            custom_emojis: 3,
+            stickers: 1,
         };
         let _ = service
-            .process_message(user_id, guild_id, false, Some(stats))
+            .process_message(user_id, guild_id, 1, false, Some(stats.clone()))
             .await
             .unwrap();
+        // During the cooldown the message still counts, its emojis and
+        // stickers don't
+        let result = service
+            .process_message(user_id, guild_id, 1, false, Some(stats))
+            .await;
+        assert!(matches!(result, Err(LevelingError::OnCooldown(_))));
 
         let profile = service.get_user_profile(user_id, guild_id).await.unwrap();
-        assert_eq!(profile.images_shared, 1);
-        assert_eq!(profile.long_messages, 1);
-        assert_eq!(profile.links_shared, 1);
+        assert_eq!(profile.images_shared, 2);
+        assert_eq!(profile.long_messages, 2);
+        assert_eq!(profile.links_shared, 2);
+        assert_eq!(profile.emojis_used, 3);
+        assert_eq!(profile.stickers_used, 1);
+    }
+
+    #[tokio::test]
+    async fn test_emoji_and_sticker_spam_is_not_counted() {
+        let service = LevelingService::new(crate::infra::leveling::InMemoryXpStore::new());
+        let spam = MessageContentStats {
+            custom_emojis: 20,
+            stickers: 5,
+            ..MessageContentStats::default()
+        };
+        // Messages in channels without XP don't count
+        service.set_channel_excluded(1, 20, true).await.unwrap();
+        service
+            .process_message(1, 1, 20, false, Some(spam.clone()))
+            .await
+            .unwrap();
+
+        let _ = service
+            .process_message(1, 1, 10, false, Some(spam.clone()))
+            .await
+            .unwrap();
+        // Messages on cooldown don't count at all
+        for _ in 0..5 {
+            let result = service
+                .process_message(1, 1, 10, false, Some(spam.clone()))
+                .await;
+            assert!(matches!(result, Err(LevelingError::OnCooldown(_))));
+        }
+
+        let profile = service.get_user_profile(1, 1).await.unwrap();
+        assert_eq!(profile.total_messages, 7);
+        assert_eq!(profile.emojis_used, MAX_COUNTED_PER_MESSAGE);
+        assert_eq!(profile.stickers_used, MAX_COUNTED_PER_MESSAGE);
     }
 
     #[tokio::test]
@@ -1708,20 +3675,144 @@ mod tests {
 
         // member_count=1 -> target = 1, should cause immediate completion and award goal bonus
         let (xp, _levelup) = service
-            .claim_daily(user_id, guild_id, false, 1)
+            .claim_daily(user_id, guild_id, false, 1, false)
             .await
             .unwrap();
         // xp must include base daily reward and the goal bonus
-        assert!(
-            xp >= LevelingService::<crate::infra::leveling::InMemoryXpStore>::BASE_DAILY_REWARD
-                + LevelingService::<crate::infra::leveling::InMemoryXpStore>::GOAL_BONUS_XP
-        );
+        let daily = service.config.daily;
+        assert!(xp >= daily.base_xp + daily.goal_bonus_xp);
         // Verify profile reflects bonus
         let profile = service.get_user_profile(user_id, guild_id).await.unwrap();
-        assert!(
-            profile.total_xp
-                >= LevelingService::<crate::infra::leveling::InMemoryXpStore>::BASE_DAILY_REWARD
-                    + LevelingService::<crate::infra::leveling::InMemoryXpStore>::GOAL_BONUS_XP
+        assert!(profile.total_xp >= daily.base_xp + daily.goal_bonus_xp);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_stale_ranks_only_touches_active_guilds() {
+        let store = crate::infra::leveling::InMemoryXpStore::new();
+        let service = LevelingService::new(store);
+
+        // Nothing happened yet, nothing to refresh
+        assert_eq!(service.refresh_stale_ranks().await, 0);
+
+        service
+            .award_xp(1, 5, 100, XpSource::VoiceMinute)
+            .await
+            .unwrap();
+        service
+            .award_xp(2, 5, 300, XpSource::VoiceMinute)
+            .await
+            .unwrap();
+
+        assert_eq!(service.refresh_stale_ranks().await, 1);
+        let top = service.get_user_profile(2, 5).await.unwrap();
+        assert_eq!(top.best_rank, 1);
+        let second = service.get_user_profile(1, 5).await.unwrap();
+        assert_eq!(second.previous_rank, 2);
+
+        // No new XP -> guild is skipped
+        assert_eq!(service.refresh_stale_ranks().await, 0);
+
+        // User 1 overtakes user 2 and climbs a rank
+        service
+            .award_xp(1, 5, 500, XpSource::VoiceMinute)
+            .await
+            .unwrap();
+        assert_eq!(service.refresh_stale_ranks().await, 1);
+        let climber = service.get_user_profile(1, 5).await.unwrap();
+        assert_eq!(climber.best_rank, 1);
+        assert_eq!(climber.rank_improvement, 1);
+    }
+
+    #[test]
+    fn test_queued_boosts_keep_their_own_time() {
+        let now = Utc::now();
+        let minutes = chrono::Duration::minutes;
+        let boost = |multiplier, ends_in| XpBoost {
+            multiplier,
+            expires_at: now + minutes(ends_in),
+        };
+
+        // A lower boost runs after the current one
+        let (queue, queued) = queue_xp_boost(&[boost(2.0, 60)], 1.5, minutes(30), now);
+        assert_eq!(queue, vec![boost(2.0, 60), boost(1.5, 90)]);
+        assert_eq!(queued, boost(1.5, 90));
+
+        // A higher one goes first and pauses the rest
+        let (queue, queued) = queue_xp_boost(&queue, 3.0, minutes(10), now);
+        assert_eq!(queue, vec![boost(3.0, 10), boost(2.0, 70), boost(1.5, 100)]);
+        assert_eq!(queued, boost(3.0, 10));
+
+        // The same multiplier adds its time to the queued one
+        let (queue, queued) = queue_xp_boost(&queue, 2.0, minutes(60), now);
+        assert_eq!(
+            queue,
+            vec![boost(3.0, 10), boost(2.0, 130), boost(1.5, 160)]
         );
+        assert_eq!(queued, boost(2.0, 130));
+
+        // Expired boosts are dropped
+        let (queue, _) = queue_xp_boost(&[boost(2.0, -5)], 1.5, minutes(30), now);
+        assert_eq!(queue, vec![boost(1.5, 30)]);
+    }
+
+    #[tokio::test]
+    async fn test_xp_boost_multiplies_message_xp_until_it_expires() {
+        let config = LevelingConfig {
+            xp_per_message_min: 20,
+            xp_per_message_max: 20,
+            cooldown: Duration::ZERO,
+            achievements: Vec::new(),
+            ..LevelingConfig::default()
+        };
+        let service =
+            LevelingService::with_config(crate::infra::leveling::InMemoryXpStore::new(), config);
+        async fn total_xp(
+            service: &LevelingService<crate::infra::leveling::InMemoryXpStore>,
+        ) -> u64 {
+            service.get_user_profile(1, 1).await.unwrap().total_xp
+        }
+
+        service
+            .process_message(1, 1, 10, false, None)
+            .await
+            .unwrap();
+        assert_eq!(total_xp(&service).await, 20);
+
+        let boost = service
+            .activate_xp_boost(1, 1, 2.0, Duration::from_secs(3600))
+            .await
+            .unwrap();
+        service
+            .process_message(1, 1, 10, false, None)
+            .await
+            .unwrap();
+        assert_eq!(total_xp(&service).await, 60);
+
+        // A lower boost waits for the running one to end
+        let queued = service
+            .activate_xp_boost(1, 1, 1.5, Duration::from_secs(1800))
+            .await
+            .unwrap();
+        assert_eq!(
+            queued.expires_at - boost.expires_at,
+            chrono::Duration::minutes(30)
+        );
+        assert_eq!(service.active_xp_boost(1, 1).await.unwrap(), Some(boost));
+        assert_eq!(service.xp_boosts(1, 1).await.unwrap(), vec![boost, queued]);
+
+        // Expired boosts stop applying
+        service.xp_boosts.insert(
+            (1, 1),
+            vec![XpBoost {
+                multiplier: 2.0,
+                expires_at: Utc::now() - chrono::Duration::seconds(1),
+            }],
+        );
+        assert_eq!(service.active_xp_boost(1, 1).await.unwrap(), None);
+        service
+            .process_message(1, 1, 10, false, None)
+            .await
+            .unwrap();
+        assert_eq!(total_xp(&service).await, 80);
     }
 }
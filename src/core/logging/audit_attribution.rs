@@ -0,0 +1,144 @@
+// Guessing who performed a moderation action from the guild audit log.
+//
+// Discord's delete/kick/ban events don't say who did it. The audit log does,
+// but it is fetched separately and may lag behind (or run ahead of) the
+// gateway event, so matching is a heuristic: an entry for the same target,
+// close enough in time, in the same channel when that matters. Results are
+// shown as "likely" in the logs.
+//
+// The Discord layer converts audit log entries to `AuditEntry` and calls
+// `match_audit_entry`; everything here is pure and testable.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// How far back a kick or ban entry may be and still explain an event.
+pub const MEMBER_ACTION_LOOKBACK_SECS: i64 = 30;
+
+/// How far back a message delete entry may be. Discord folds repeated
+/// deletes of one author's messages in a channel into a single entry and only
+/// bumps its count, so the entry can be a few minutes older than the delete.
+pub const MESSAGE_DELETE_LOOKBACK_SECS: i64 = 5 * 60;
+
+/// Entries stamped this far after the event still match (clock skew).
+const CLOCK_SKEW_SECS: i64 = 5;
+
+/// The parts of an audit log entry the matcher needs.
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub moderator_id: u64,
+    pub target_id: Option<u64>,
+    /// Channel the action happened in, for message deletes.
+    pub channel_id: Option<u64>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The moderator an action was most likely performed by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditAttribution {
+    pub moderator_id: u64,
+    pub reason: Option<String>,
+}
+
+impl AuditAttribution {
+    /// Moderator mention marked as a guess, e.g. "<@1> (likely)".
+    pub fn moderator_label(&self) -> String {
+        format!("<@{}> (likely)", self.moderator_id)
+    }
+}
+
+/// What to look for in the audit log.
+#[derive(Debug, Clone, Copy)]
+pub struct AuditQuery {
+    pub target_id: u64,
+    /// Only match entries in this channel (message deletes).
+    pub channel_id: Option<u64>,
+    /// When the event was seen.
+    pub event_at: DateTime<Utc>,
+    /// Oldest entry age, relative to `event_at`, that still matches.
+    pub lookback: Duration,
+}
+
+/// Pick the entry that most likely caused the event: same target (and
+/// channel, if given), created within the lookback window. The newest match
+/// wins since it is the closest to the event.
+pub fn match_audit_entry(entries: &[AuditEntry], query: &AuditQuery) -> Option<AuditAttribution> {
+    let earliest = query.event_at - query.lookback;
+    let latest = query.event_at + Duration::seconds(CLOCK_SKEW_SECS);
+
+    entries
+        .iter()
+        .filter(|entry| entry.target_id == Some(query.target_id))
+        .filter(|entry| query.channel_id.is_none() || entry.channel_id == query.channel_id)
+        .filter(|entry| entry.created_at >= earliest && entry.created_at <= latest)
+        .max_by_key(|entry| entry.created_at)
+        .map(|entry| AuditAttribution {
+            moderator_id: entry.moderator_id,
+            reason: entry.reason.clone(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        moderator_id: u64,
+        target_id: u64,
+        channel_id: Option<u64>,
+        age_secs: i64,
+        now: DateTime<Utc>,
+    ) -> AuditEntry {
+        AuditEntry {
+            moderator_id,
+            target_id: Some(target_id),
+            channel_id,
+            reason: Some(format!("by {}", moderator_id)),
+            created_at: now - Duration::seconds(age_secs),
+        }
+    }
+
+    #[test]
+    fn test_match_audit_entry_by_target_and_recency() {
+        let now = Utc::now();
+        let query = AuditQuery {
+            target_id: 7,
+            channel_id: None,
+            event_at: now,
+            lookback: Duration::seconds(MEMBER_ACTION_LOOKBACK_SECS),
+        };
+
+        let entries = vec![
+            entry(1, 8, None, 2, now),   // someone else
+            entry(2, 7, None, 10, now),  // matches
+            entry(3, 7, None, 5, now),   // matches and is newer
+            entry(4, 7, None, 600, now), // an old ban from a previous visit
+        ];
+        let attribution = match_audit_entry(&entries, &query).unwrap();
+        assert_eq!(attribution.moderator_id, 3);
+        assert_eq!(attribution.reason.as_deref(), Some("by 3"));
+        assert_eq!(attribution.moderator_label(), "<@3> (likely)");
+
+        // Only stale entries: no guess
+        assert_eq!(match_audit_entry(&entries[3..], &query), None);
+        assert_eq!(match_audit_entry(&[], &query), None);
+    }
+
+    #[test]
+    fn test_message_delete_match_requires_channel() {
+        let now = Utc::now();
+        let query = AuditQuery {
+            target_id: 7,
+            channel_id: Some(100),
+            event_at: now,
+            lookback: Duration::seconds(MESSAGE_DELETE_LOOKBACK_SECS),
+        };
+
+        let entries = vec![
+            entry(1, 7, Some(200), 1, now),
+            // A grouped entry from a couple of minutes ago still counts
+            entry(2, 7, Some(100), 120, now),
+        ];
+        assert_eq!(match_audit_entry(&entries, &query).unwrap().moderator_id, 2);
+    }
+}
@@ -1,3 +1,4 @@
+use super::audit_attribution::AuditAttribution;
 use chrono::{DateTime, Utc};
 
 #[allow(dead_code)]
@@ -6,6 +7,51 @@ pub struct LogConfig {
     pub guild_id: u64,
     pub enabled: bool,
     pub channel_id: Option<u64>,
+    /// Log `MemberBanned` events.
+    pub log_bans: bool,
+    /// Log `MemberUnbanned` events.
+    pub log_unbans: bool,
+    /// Look up who deleted/kicked/banned in the audit log. Costs an extra API
+    /// call per event, so guilds opt in.
+    pub audit_attribution: bool,
+}
+
+impl LogConfig {
+    /// A guild with logging switched off, every event type on and audit log
+    /// attribution off.
+    pub fn new(guild_id: u64) -> Self {
+        Self {
+            guild_id,
+            enabled: false,
+            channel_id: None,
+            log_bans: true,
+            log_unbans: true,
+            audit_attribution: false,
+        }
+    }
+
+    /// Whether the guild wants this event logged, assuming logging is on.
+    pub fn logs(&self, event: &LogEvent) -> bool {
+        match event {
+            LogEvent::MemberBanned { .. } => self.log_bans,
+            LogEvent::MemberUnbanned { .. } => self.log_unbans,
+            _ => true,
+        }
+    }
+
+    pub fn is_event_enabled(&self, event: OptionalLogEvent) -> bool {
+        match event {
+            OptionalLogEvent::Bans => self.log_bans,
+            OptionalLogEvent::Unbans => self.log_unbans,
+        }
+    }
+}
+
+/// Event types that can be switched off individually (`/logging events`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionalLogEvent {
+    Bans,
+    Unbans,
 }
 
 #[allow(dead_code)]
@@ -41,6 +87,8 @@ pub enum LogEvent {
         user_mention: String,
         avatar_url: Option<String>,
         joined_at: Option<DateTime<Utc>>,
+        /// Set when the audit log suggests the member was kicked.
+        kicked_by: Option<AuditAttribution>,
     },
     MessageDeleted {
         guild_id: u64,
@@ -50,6 +98,9 @@ pub enum LogEvent {
         content: String,
         attachments: Vec<String>,
         avatar_url: Option<String>,
+        /// Set when the audit log suggests a moderator deleted it; authors
+        /// deleting their own messages leave no entry.
+        deleted_by: Option<AuditAttribution>,
     },
     MessageEdited {
         guild_id: u64,
@@ -60,6 +111,39 @@ pub enum LogEvent {
         after_content: String,
         avatar_url: Option<String>,
     },
+    MessagesBulkDeleted {
+        guild_id: u64,
+        channel_id: u64,
+        count: usize,
+        /// Author names with how many of their messages were deleted, when known.
+        authors: Vec<(String, usize)>,
+        /// Moderator mention if the deletion came from `/purge`.
+        moderator: Option<String>,
+    },
+    MemberBanned {
+        guild_id: u64,
+        user_id: u64,
+        user_name: String,
+        avatar_url: Option<String>,
+        /// Moderator and reason from the audit log, if they could be matched.
+        attribution: Option<AuditAttribution>,
+    },
+    MemberUnbanned {
+        guild_id: u64,
+        user_id: u64,
+        user_name: String,
+        avatar_url: Option<String>,
+        /// Moderator and reason from the audit log, if they could be matched.
+        attribution: Option<AuditAttribution>,
+    },
+    SlowmodeChanged {
+        guild_id: u64,
+        channel_id: u64,
+        moderator: String,
+        /// Seconds between messages per user; 0 means off.
+        previous_secs: u16,
+        new_secs: u16,
+    },
 }
 
 /// Minimal snapshot of a message that we keep in-memory so
@@ -76,3 +160,19 @@ pub struct TrackedMessage {
     pub attachments: Vec<String>,
     pub avatar_url: Option<String>,
 }
+
+/// Audit record for a single command invocation.
+///
+/// Written after every command (success or failure) so admins can see
+/// who ran sensitive commands like `give_xp` or `/antispam`.
+#[derive(Debug, Clone)]
+pub struct CommandAudit {
+    pub guild_id: u64,
+    pub user_id: u64,
+    pub user_name: String,
+    pub command_name: String,
+    pub success: bool,
+    /// Short error description when the command failed.
+    pub error: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
@@ -1,18 +1,54 @@
-use super::logging_models::{LogConfig, LogEvent, TrackedMessage};
+use super::logging_models::{CommandAudit, LogConfig, LogEvent, OptionalLogEvent, TrackedMessage};
 use anyhow::Result;
 use async_trait::async_trait;
 use dashmap::{DashMap, DashSet};
+use std::time::{Duration, Instant};
 
 // Hardcoded meeting stage channel ID from Python code
 const MEETING_STAGE_CHANNEL_ID: u64 = 1393369518297972758;
 // Cap how many messages we keep in memory for logging so we don't grow unbounded.
 const MAX_TRACKED_MESSAGES: usize = 5_000;
+// How many command audit entries we keep per guild before pruning the oldest.
+pub const MAX_AUDIT_ENTRIES_PER_GUILD: usize = 1_000;
+// How long a `/purge` waits for Discord's bulk delete event to attribute it.
+const PURGE_ATTRIBUTION_TTL: Duration = Duration::from_secs(60);
 
 #[async_trait]
 pub trait LogConfigStore: Send + Sync {
     async fn get_config(&self, guild_id: u64) -> Result<Option<LogConfig>>;
     #[allow(dead_code)]
     async fn save_config(&self, config: LogConfig) -> Result<()>;
+    /// Persist a command audit entry, pruning anything beyond `keep` per guild.
+    async fn record_command_audit(&self, entry: CommandAudit, keep: usize) -> Result<()>;
+    /// Most recent audit entries for a guild, newest first.
+    async fn get_recent_command_audits(
+        &self,
+        guild_id: u64,
+        limit: usize,
+    ) -> Result<Vec<CommandAudit>>;
+}
+
+#[async_trait]
+impl<S: LogConfigStore + ?Sized> LogConfigStore for Box<S> {
+    async fn get_config(&self, guild_id: u64) -> Result<Option<LogConfig>> {
+        (**self).get_config(guild_id).await
+    }
+
+    async fn save_config(&self, config: LogConfig) -> Result<()> {
+        (**self).save_config(config).await
+    }
+
+    async fn record_command_audit(&self, entry: CommandAudit, keep: usize) -> Result<()> {
+        (**self).record_command_audit(entry, keep).await
+    }
+
+    async fn get_recent_command_audits(
+        &self,
+        guild_id: u64,
+        limit: usize,
+    ) -> Result<Vec<CommandAudit>> {
+        (**self).get_recent_command_audits(guild_id, limit).await
+    }
 }
 
 pub struct LoggingService<S: LogConfigStore> {
@@ -23,6 +59,8 @@ pub struct LoggingService<S: LogConfigStore> {
     meeting_attendees: DashMap<u64, DashSet<u64>>,
     // Message ID -> Snapshot for logging edits/deletes even if Serenity's cache evicts them
     message_cache: DashMap<u64, TrackedMessage>,
+    // Channel ID -> (moderator mention, when) for bulk deletes started by `/purge`
+    pending_purges: DashMap<u64, (String, Instant)>,
 }
 
 pub struct VoiceUpdateParams {
@@ -41,6 +79,7 @@ impl<S: LogConfigStore> LoggingService<S> {
             active_voice_channels: DashMap::new(),
             meeting_attendees: DashMap::new(),
             message_cache: DashMap::new(),
+            pending_purges: DashMap::new(),
         }
     }
 
@@ -48,16 +87,74 @@ impl<S: LogConfigStore> LoggingService<S> {
         self.store.get_config(guild_id).await
     }
 
+    /// Log to `channel_id` and switch logging on, keeping per-event settings.
     #[allow(dead_code)]
     pub async fn set_log_channel(&self, guild_id: u64, channel_id: u64) -> Result<()> {
-        let config = LogConfig {
-            guild_id,
-            enabled: true,
-            channel_id: Some(channel_id),
-        };
+        let mut config = self
+            .store
+            .get_config(guild_id)
+            .await?
+            .unwrap_or_else(|| LogConfig::new(guild_id));
+        config.enabled = true;
+        config.channel_id = Some(channel_id);
         self.store.save_config(config).await
     }
 
+    /// Switch one optional event type on or off. Works before a log channel
+    /// is set, so admins can pick events up front.
+    pub async fn set_event_enabled(
+        &self,
+        guild_id: u64,
+        event: OptionalLogEvent,
+        enabled: bool,
+    ) -> Result<()> {
+        let mut config = self
+            .store
+            .get_config(guild_id)
+            .await?
+            .unwrap_or_else(|| LogConfig::new(guild_id));
+        match event {
+            OptionalLogEvent::Bans => config.log_bans = enabled,
+            OptionalLogEvent::Unbans => config.log_unbans = enabled,
+        }
+        self.store.save_config(config).await
+    }
+
+    /// Switch audit log attribution on or off. Returns `false` if logging
+    /// was never configured.
+    pub async fn set_audit_attribution(&self, guild_id: u64, enabled: bool) -> Result<bool> {
+        let Some(mut config) = self.store.get_config(guild_id).await? else {
+            return Ok(false);
+        };
+        config.audit_attribution = enabled;
+        self.store.save_config(config).await?;
+        Ok(true)
+    }
+
+    /// Whether to query the audit log for an event that would be posted.
+    pub async fn should_attribute(&self, guild_id: u64) -> Result<bool> {
+        Ok(self
+            .store
+            .get_config(guild_id)
+            .await?
+            .is_some_and(|config| {
+                config.enabled && config.channel_id.is_some() && config.audit_attribution
+            }))
+    }
+
+    /// Whether `event` would be posted: logging is on, a channel is set and
+    /// the guild hasn't switched this event type off. Lets callers skip
+    /// expensive lookups (like the audit log) for events nobody will see.
+    pub async fn should_log(&self, guild_id: u64, event: OptionalLogEvent) -> Result<bool> {
+        Ok(self
+            .store
+            .get_config(guild_id)
+            .await?
+            .is_some_and(|config| {
+                config.enabled && config.channel_id.is_some() && config.is_event_enabled(event)
+            }))
+    }
+
     #[allow(dead_code)]
     pub async fn set_enabled(&self, guild_id: u64, enabled: bool) -> Result<bool> {
         if let Some(mut config) = self.store.get_config(guild_id).await? {
@@ -72,6 +169,18 @@ impl<S: LogConfigStore> LoggingService<S> {
         }
     }
 
+    /// Record that a command was run in a guild.
+    pub async fn record_command(&self, entry: CommandAudit) -> Result<()> {
+        self.store
+            .record_command_audit(entry, MAX_AUDIT_ENTRIES_PER_GUILD)
+            .await
+    }
+
+    /// Fetch the latest command audit entries for a guild.
+    pub async fn recent_commands(&self, guild_id: u64, limit: usize) -> Result<Vec<CommandAudit>> {
+        self.store.get_recent_command_audits(guild_id, limit).await
+    }
+
     /// Store a message snapshot so we can later log deletes/edits reliably.
     pub fn remember_message(&self, message: TrackedMessage) {
         self.message_cache.insert(message.message_id, message);
@@ -94,6 +203,20 @@ impl<S: LogConfigStore> LoggingService<S> {
         self.message_cache.remove(&message_id).map(|(_, msg)| msg)
     }
 
+    /// Remember who is about to purge a channel, so the bulk delete log can name them.
+    pub fn note_purge(&self, channel_id: u64, moderator: String) {
+        self.pending_purges
+            .insert(channel_id, (moderator, Instant::now()));
+    }
+
+    /// Moderator behind a bulk delete in this channel, if `/purge` just ran there.
+    pub fn take_purge(&self, channel_id: u64) -> Option<String> {
+        self.pending_purges
+            .remove(&channel_id)
+            .filter(|(_, (_, noted_at))| noted_at.elapsed() <= PURGE_ATTRIBUTION_TTL)
+            .map(|(_, (moderator, _))| moderator)
+    }
+
     pub async fn process_voice_update(
         &self,
         params: VoiceUpdateParams,
@@ -1,5 +1,7 @@
+pub mod audit_attribution;
 pub mod logging_models;
 pub mod logging_service;
 
+pub use audit_attribution::*;
 pub use logging_models::*;
 pub use logging_service::*;
@@ -0,0 +1,92 @@
+// Bot self-metrics shown by `/stats`: uptime and counters for this session.
+//
+// Counters are plain atomics so the event handler can bump them without
+// locking. Nothing is persisted; a restart starts a new session.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+pub struct AppMetrics {
+    started_at: Instant,
+    messages_processed: AtomicU64,
+}
+
+impl AppMetrics {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            messages_processed: AtomicU64::new(0),
+        }
+    }
+
+    /// Count a message the event handler received.
+    pub fn record_message(&self) {
+        self.messages_processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn messages_processed(&self) -> u64 {
+        self.messages_processed.load(Ordering::Relaxed)
+    }
+
+    pub fn uptime(&self) -> Duration {
+        Instant::now() - self.started_at
+    }
+}
+
+impl Default for AppMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compact uptime such as "3d 4h 12m" or "45s".
+pub fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let (days, hours, mins) = (secs / 86_400, (secs % 86_400) / 3_600, (secs % 3_600) / 60);
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, mins)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {}s", mins, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Resident memory in bytes from the contents of `/proc/self/status`
+/// (the `VmRSS:` line, reported in kB). `None` if the line is missing.
+pub fn parse_resident_memory(proc_status: &str) -> Option<u64> {
+    proc_status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_and_formatting() {
+        let metrics = AppMetrics::new();
+        metrics.record_message();
+        metrics.record_message();
+        assert_eq!(metrics.messages_processed(), 2);
+
+        assert_eq!(format_uptime(Duration::from_secs(45)), "45s");
+        assert_eq!(format_uptime(Duration::from_secs(125)), "2m 5s");
+        assert_eq!(format_uptime(Duration::from_secs(3 * 3_600 + 60)), "3h 1m");
+        assert_eq!(
+            format_uptime(Duration::from_secs(2 * 86_400 + 5 * 3_600 + 7 * 60)),
+            "2d 5h 7m"
+        );
+
+        let status = "Name:\trust_discord_bot\nVmPeak:\t  200000 kB\nVmRSS:\t   51200 kB\n";
+        assert_eq!(parse_resident_memory(status), Some(51_200 * 1024));
+        assert_eq!(parse_resident_memory("Name:\tbot\n"), None);
+    }
+}
@@ -3,6 +3,10 @@
 
 pub mod moderation_models;
 pub mod moderation_service;
+pub mod purge;
+pub mod temp_roles;
 
 pub use moderation_models::*;
 pub use moderation_service::*;
+pub use purge::*;
+pub use temp_roles::*;
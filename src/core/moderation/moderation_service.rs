@@ -92,6 +92,69 @@ pub trait SpamStore: Send + Sync {
     async fn cleanup_old_records(&self, older_than: DateTime<Utc>) -> Result<u64, SpamError>;
 }
 
+#[async_trait]
+impl<S: SpamStore + ?Sized> SpamStore for Box<S> {
+    async fn record_message(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        record: MessageRecord,
+    ) -> Result<(), SpamError> {
+        (**self).record_message(user_id, guild_id, record).await
+    }
+
+    async fn get_recent_messages(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<MessageRecord>, SpamError> {
+        (**self).get_recent_messages(user_id, guild_id, since).await
+    }
+
+    async fn add_warning(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        spam_type: SpamType,
+    ) -> Result<u32, SpamError> {
+        (**self).add_warning(user_id, guild_id, spam_type).await
+    }
+
+    async fn get_warnings(&self, user_id: u64, guild_id: u64) -> Result<u32, SpamError> {
+        (**self).get_warnings(user_id, guild_id).await
+    }
+
+    async fn clear_warnings(&self, user_id: u64, guild_id: u64) -> Result<(), SpamError> {
+        (**self).clear_warnings(user_id, guild_id).await
+    }
+
+    async fn is_rate_limited(&self, user_id: u64, guild_id: u64) -> Result<bool, SpamError> {
+        (**self).is_rate_limited(user_id, guild_id).await
+    }
+
+    async fn set_rate_limited(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        until: DateTime<Utc>,
+    ) -> Result<(), SpamError> {
+        (**self).set_rate_limited(user_id, guild_id, until).await
+    }
+
+    async fn get_config(&self, guild_id: u64) -> Result<SpamConfig, SpamError> {
+        (**self).get_config(guild_id).await
+    }
+
+    async fn save_config(&self, guild_id: u64, config: SpamConfig) -> Result<(), SpamError> {
+        (**self).save_config(guild_id, config).await
+    }
+
+    async fn cleanup_old_records(&self, older_than: DateTime<Utc>) -> Result<u64, SpamError> {
+        (**self).cleanup_old_records(older_than).await
+    }
+}
+
 // ============================================================================
 // CORE SERVICE
 // ============================================================================
@@ -0,0 +1,121 @@
+// Message purge selection - which recent messages `/purge` should delete.
+//
+// Pure logic with no Discord dependencies: the Discord layer fetches the
+// messages, converts them to `PurgeCandidate`s and bulk-deletes the result.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Discord's bulk delete endpoint accepts at most this many messages.
+pub const MAX_PURGE_MESSAGES: usize = 100;
+
+/// Discord refuses to bulk delete messages older than 14 days. Keep a small
+/// margin so a message doesn't age out between selecting and deleting it.
+pub fn bulk_delete_cutoff(now: DateTime<Utc>) -> DateTime<Utc> {
+    now - Duration::days(14) + Duration::minutes(1)
+}
+
+/// A recently fetched message that may be purged.
+#[derive(Debug, Clone)]
+pub struct PurgeCandidate {
+    pub message_id: u64,
+    pub author_id: u64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Optional filters for a purge. Empty filters match every message.
+#[derive(Debug, Clone, Default)]
+pub struct PurgeFilter {
+    pub author_id: Option<u64>,
+    /// Case-insensitive substring the content must contain.
+    pub contains: Option<String>,
+}
+
+impl PurgeFilter {
+    pub fn matches(&self, candidate: &PurgeCandidate) -> bool {
+        if self
+            .author_id
+            .is_some_and(|author_id| author_id != candidate.author_id)
+        {
+            return false;
+        }
+        match &self.contains {
+            Some(text) => candidate
+                .content
+                .to_lowercase()
+                .contains(&text.to_lowercase()),
+            None => true,
+        }
+    }
+}
+
+/// Messages picked for deletion, plus matches skipped for being too old.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PurgeSelection {
+    pub message_ids: Vec<u64>,
+    pub too_old: usize,
+}
+
+/// Pick up to `count` matching messages (newest first, as Discord returns them).
+/// Matches older than the bulk delete cutoff count towards `count` but are
+/// reported as `too_old` instead of being deleted.
+pub fn select_purge_targets(
+    candidates: &[PurgeCandidate],
+    filter: &PurgeFilter,
+    count: usize,
+    now: DateTime<Utc>,
+) -> PurgeSelection {
+    let cutoff = bulk_delete_cutoff(now);
+    let mut selection = PurgeSelection::default();
+
+    for candidate in candidates
+        .iter()
+        .filter(|candidate| filter.matches(candidate))
+        .take(count.min(MAX_PURGE_MESSAGES))
+    {
+        if candidate.created_at < cutoff {
+            selection.too_old += 1;
+        } else {
+            selection.message_ids.push(candidate.message_id);
+        }
+    }
+
+    selection
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(message_id: u64, author_id: u64, content: &str, age_days: i64) -> PurgeCandidate {
+        PurgeCandidate {
+            message_id,
+            author_id,
+            content: content.to_string(),
+            created_at: Utc::now() - Duration::days(age_days),
+        }
+    }
+
+    #[test]
+    fn test_purge_filters_and_skips_old_messages() {
+        let candidates = vec![
+            candidate(5, 1, "buy cheap GOLD now", 0),
+            candidate(4, 2, "hello", 0),
+            candidate(3, 1, "more gold", 1),
+            candidate(2, 1, "unrelated", 2),
+            candidate(1, 1, "ancient gold", 20),
+        ];
+
+        let everything = select_purge_targets(&candidates, &PurgeFilter::default(), 3, Utc::now());
+        assert_eq!(everything.message_ids, vec![5, 4, 3]);
+        assert_eq!(everything.too_old, 0);
+
+        let filter = PurgeFilter {
+            author_id: Some(1),
+            contains: Some("gold".to_string()),
+        };
+        let selection = select_purge_targets(&candidates, &filter, 10, Utc::now());
+        assert_eq!(selection.message_ids, vec![5, 3]);
+        assert_eq!(selection.too_old, 1);
+    }
+}
@@ -0,0 +1,326 @@
+// Temporary roles - e.g. a "Muted" role handed out by `/tempmute` that is
+// taken away again after a while.
+//
+// Pending removals are persisted, so they survive restarts: the service loads
+// them on startup and a background task in the Discord layer asks for the due
+// ones every few seconds (`take_due`), removes the roles and then calls
+// `complete`. Anything that expired while the bot was offline is due straight
+// away.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::error::Error;
+use std::sync::Mutex;
+
+/// A role to remove from a member at `expires_at`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TempRole {
+    pub guild_id: u64,
+    pub user_id: u64,
+    pub role_id: u64,
+    pub expires_at: DateTime<Utc>,
+    pub reason: Option<String>,
+}
+
+impl TempRole {
+    fn same_assignment(&self, other: &TempRole) -> bool {
+        self.guild_id == other.guild_id
+            && self.user_id == other.user_id
+            && self.role_id == other.role_id
+    }
+}
+
+/// Persistence for pending role removals and each guild's mute role.
+#[async_trait]
+pub trait TempRoleStore: Send + Sync {
+    /// Every pending removal, in any order.
+    async fn pending_temp_roles(&self) -> Result<Vec<TempRole>, Box<dyn Error + Send + Sync>>;
+
+    /// Insert a removal, replacing the one for the same guild, user and role.
+    async fn save_temp_role(&self, entry: &TempRole) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    async fn delete_temp_role(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    async fn get_mute_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>>;
+
+    async fn set_mute_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: TempRoleStore + ?Sized> TempRoleStore for Box<S> {
+    async fn pending_temp_roles(&self) -> Result<Vec<TempRole>, Box<dyn Error + Send + Sync>> {
+        (**self).pending_temp_roles().await
+    }
+
+    async fn save_temp_role(&self, entry: &TempRole) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).save_temp_role(entry).await
+    }
+
+    async fn delete_temp_role(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).delete_temp_role(guild_id, user_id, role_id).await
+    }
+
+    async fn get_mute_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        (**self).get_mute_role(guild_id).await
+    }
+
+    async fn set_mute_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).set_mute_role(guild_id, role_id).await
+    }
+}
+
+/// How a member who already has the mute role got it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExistingMute {
+    /// A `/tempmute` that ends at the given time.
+    Temporary(DateTime<Utc>),
+    /// Given some other way, e.g. by hand, with no scheduled removal.
+    Indefinite,
+}
+
+/// The entries in `pending` that are due at `now`, soonest first.
+pub fn due_temp_roles(pending: &[TempRole], now: DateTime<Utc>) -> Vec<TempRole> {
+    let mut due: Vec<TempRole> = pending
+        .iter()
+        .filter(|entry| entry.expires_at <= now)
+        .cloned()
+        .collect();
+    due.sort_by_key(|entry| entry.expires_at);
+    due
+}
+
+/// Schedules and tracks temporary roles. The pending removals are kept in
+/// memory as well, so the expiry task doesn't query the store every tick.
+pub struct TempRoleService<S: TempRoleStore> {
+    store: S,
+    pending: Mutex<Vec<TempRole>>,
+}
+
+impl<S: TempRoleStore> TempRoleService<S> {
+    /// Load the pending removals from `store`, including ones that expired
+    /// while the bot was offline.
+    pub async fn load(store: S) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let pending = store.pending_temp_roles().await?;
+        Ok(Self {
+            store,
+            pending: Mutex::new(pending),
+        })
+    }
+
+    /// How many removals are waiting.
+    pub fn pending_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Remove `role_id` from the member `duration` after `now`. Re-muting a
+    /// muted member replaces the old expiry. Returns the scheduled entry.
+    pub async fn schedule(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        role_id: u64,
+        duration: Duration,
+        reason: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<TempRole, Box<dyn Error + Send + Sync>> {
+        let entry = TempRole {
+            guild_id,
+            user_id,
+            role_id,
+            expires_at: now + duration,
+            reason,
+        };
+        self.store.save_temp_role(&entry).await?;
+
+        let mut pending = self.lock();
+        pending.retain(|existing| !existing.same_assignment(&entry));
+        pending.push(entry.clone());
+        Ok(entry)
+    }
+
+    /// The mute a member is already under, if they have the role
+    /// (`has_role`). A pending removal for a member without the role is stale
+    /// (someone took the role off by hand) and doesn't count.
+    pub fn existing_mute(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        role_id: u64,
+        has_role: bool,
+    ) -> Option<ExistingMute> {
+        if !has_role {
+            return None;
+        }
+        let expires_at = self
+            .lock()
+            .iter()
+            .find(|entry| {
+                entry.guild_id == guild_id && entry.user_id == user_id && entry.role_id == role_id
+            })
+            .map(|entry| entry.expires_at);
+        Some(match expires_at {
+            Some(expires_at) => ExistingMute::Temporary(expires_at),
+            None => ExistingMute::Indefinite,
+        })
+    }
+
+    /// Removals that are due at `now`. They stay pending until `complete`,
+    /// so a removal that fails is offered again on the next call.
+    pub fn take_due(&self, now: DateTime<Utc>) -> Vec<TempRole> {
+        due_temp_roles(&self.lock(), now)
+    }
+
+    /// Forget a removal once it has been carried out (or can never be).
+    /// A member re-muted since `entry` was taken keeps the new expiry.
+    pub async fn complete(&self, entry: &TempRole) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let still_current = {
+            let mut pending = self.lock();
+            let before = pending.len();
+            pending.retain(|existing| existing != entry);
+            pending.len() != before
+        };
+        if still_current {
+            self.store
+                .delete_temp_role(entry.guild_id, entry.user_id, entry.role_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// The role `/tempmute` hands out in a guild.
+    pub async fn mute_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        self.store.get_mute_role(guild_id).await
+    }
+
+    pub async fn set_mute_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.store.set_mute_role(guild_id, role_id).await
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<TempRole>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::moderation::InMemoryTempRoleStore;
+
+    #[tokio::test]
+    async fn test_removal_is_due_after_duration_and_survives_restart() {
+        // Clones share their contents, like reopening the same database
+        let store = InMemoryTempRoleStore::new();
+        let service = TempRoleService::load(store.clone()).await.unwrap();
+        let start = Utc::now();
+
+        service
+            .schedule(1, 10, 99, Duration::minutes(10), None, start)
+            .await
+            .unwrap();
+        service
+            .schedule(1, 11, 99, Duration::hours(1), Some("spam".into()), start)
+            .await
+            .unwrap();
+        assert!(service.take_due(start + Duration::minutes(5)).is_empty());
+
+        // A restart picks up what was scheduled before it
+        let service = TempRoleService::load(store.clone()).await.unwrap();
+        assert_eq!(service.pending_count(), 2);
+
+        let due = service.take_due(start + Duration::minutes(10));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].user_id, 10);
+        // Not completed yet (e.g. Discord was down): offered again
+        assert_eq!(service.take_due(start + Duration::minutes(11)), due);
+
+        service.complete(&due[0]).await.unwrap();
+        assert!(service.take_due(start + Duration::minutes(11)).is_empty());
+        let service = TempRoleService::load(store.clone()).await.unwrap();
+        assert_eq!(service.pending_count(), 1);
+
+        // Offline past the expiry: due straight after startup
+        let due = service.take_due(start + Duration::days(1));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].reason.as_deref(), Some("spam"));
+    }
+
+    #[tokio::test]
+    async fn test_remute_replaces_expiry() {
+        let service = TempRoleService::load(InMemoryTempRoleStore::new())
+            .await
+            .unwrap();
+        let start = Utc::now();
+
+        let first = service
+            .schedule(1, 10, 99, Duration::minutes(10), None, start)
+            .await
+            .unwrap();
+        service
+            .schedule(1, 10, 99, Duration::hours(2), None, start)
+            .await
+            .unwrap();
+        assert_eq!(service.pending_count(), 1);
+        assert!(service.take_due(start + Duration::hours(1)).is_empty());
+
+        // Completing the stale entry doesn't cancel the newer one
+        service.complete(&first).await.unwrap();
+        assert_eq!(service.take_due(start + Duration::hours(2)).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_existing_mute_is_reported() {
+        let service = TempRoleService::load(InMemoryTempRoleStore::new())
+            .await
+            .unwrap();
+        let start = Utc::now();
+        let entry = service
+            .schedule(1, 10, 99, Duration::minutes(10), None, start)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            service.existing_mute(1, 10, 99, true),
+            Some(ExistingMute::Temporary(entry.expires_at))
+        );
+        // Muted by hand: no removal is scheduled, so it must be left alone
+        assert_eq!(
+            service.existing_mute(1, 11, 99, true),
+            Some(ExistingMute::Indefinite)
+        );
+        // The role was taken off early; the old entry doesn't block a new mute
+        assert_eq!(service.existing_mute(1, 10, 99, false), None);
+    }
+}
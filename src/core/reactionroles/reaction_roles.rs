@@ -0,0 +1,282 @@
+// Reaction roles: react to a message with an emoji to get a role, remove the
+// reaction to lose it again (`/reactionrole add`).
+//
+// A guild can bind any number of messages, each with several emoji → role
+// pairs. Every reaction in the guild is checked against the bindings, so the
+// service keeps them cached per guild. Emojis are compared by key: the ID of a
+// custom emoji, or the Unicode emoji itself without variation selectors.
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::error::Error;
+
+/// Discord allows at most this many different reactions on one message.
+pub const MAX_ROLES_PER_MESSAGE: usize = 20;
+
+/// One emoji → role pair on a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReactionRole {
+    pub channel_id: u64,
+    pub message_id: u64,
+    /// Emoji key, see `emoji_key`.
+    pub emoji: String,
+    pub role_id: u64,
+}
+
+/// Persistence for reaction-role bindings.
+#[async_trait]
+pub trait ReactionRoleStore: Send + Sync {
+    async fn list_reaction_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ReactionRole>, Box<dyn Error + Send + Sync>>;
+
+    /// Insert a binding, replacing the role of an existing message/emoji pair.
+    async fn set_reaction_role(
+        &self,
+        guild_id: u64,
+        binding: ReactionRole,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    /// Remove one emoji's binding, or every binding on the message when
+    /// `emoji` is `None`. Returns how many were removed.
+    async fn remove_reaction_roles(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        emoji: Option<&str>,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: ReactionRoleStore + ?Sized> ReactionRoleStore for Box<S> {
+    async fn list_reaction_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ReactionRole>, Box<dyn Error + Send + Sync>> {
+        (**self).list_reaction_roles(guild_id).await
+    }
+
+    async fn set_reaction_role(
+        &self,
+        guild_id: u64,
+        binding: ReactionRole,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).set_reaction_role(guild_id, binding).await
+    }
+
+    async fn remove_reaction_roles(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        emoji: Option<&str>,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        (**self)
+            .remove_reaction_roles(guild_id, message_id, emoji)
+            .await
+    }
+}
+
+/// The key an emoji is stored and looked up by: the ID for a custom emoji
+/// (`<:name:id>` or `<a:name:id>`), otherwise the trimmed text without
+/// variation selectors, so `❤` and `❤️` match. `None` for anything that can't
+/// be an emoji, such as empty or plain ASCII text.
+pub fn emoji_key(emoji: &str) -> Option<String> {
+    let emoji = emoji.trim();
+    if let Some(inner) = emoji.strip_prefix('<').and_then(|e| e.strip_suffix('>')) {
+        let id = inner.rsplit(':').next()?;
+        return id.parse::<u64>().ok().map(|id| id.to_string());
+    }
+
+    let key: String = emoji.chars().filter(|&c| c != '\u{fe0f}').collect();
+    (!key.is_empty() && !key.is_ascii() && !key.contains(char::is_whitespace)).then_some(key)
+}
+
+/// The role bound to `emoji` (a key from `emoji_key`) on `message_id`.
+pub fn find_reaction_role(bindings: &[ReactionRole], message_id: u64, emoji: &str) -> Option<u64> {
+    bindings
+        .iter()
+        .find(|binding| binding.message_id == message_id && binding.emoji == emoji)
+        .map(|binding| binding.role_id)
+}
+
+/// Outcome of `ReactionRoleService::add`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddReactionRole {
+    Added,
+    /// The emoji was already bound on that message; this is the old role.
+    Replaced(u64),
+    /// The message already has `MAX_ROLES_PER_MESSAGE` bindings.
+    MessageFull,
+}
+
+/// Reaction-role bindings with a per-guild cache, since every reaction in a
+/// guild is looked up.
+pub struct ReactionRoleService<S: ReactionRoleStore> {
+    store: S,
+    cache: DashMap<u64, Vec<ReactionRole>>,
+}
+
+impl<S: ReactionRoleStore> ReactionRoleService<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            cache: DashMap::new(),
+        }
+    }
+
+    /// Every binding in the guild.
+    pub async fn bindings(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ReactionRole>, Box<dyn Error + Send + Sync>> {
+        if let Some(bindings) = self.cache.get(&guild_id) {
+            return Ok(bindings.clone());
+        }
+
+        let bindings = self.store.list_reaction_roles(guild_id).await?;
+        self.cache.insert(guild_id, bindings.clone());
+        Ok(bindings)
+    }
+
+    /// The role to give for `emoji` (a key from `emoji_key`) on `message_id`.
+    pub async fn role_for(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        emoji: &str,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        if let Some(bindings) = self.cache.get(&guild_id) {
+            return Ok(find_reaction_role(&bindings, message_id, emoji));
+        }
+        let bindings = self.bindings(guild_id).await?;
+        Ok(find_reaction_role(&bindings, message_id, emoji))
+    }
+
+    /// Bind `binding.emoji` on the message to `binding.role_id`.
+    pub async fn add(
+        &self,
+        guild_id: u64,
+        binding: ReactionRole,
+    ) -> Result<AddReactionRole, Box<dyn Error + Send + Sync>> {
+        let bindings = self.bindings(guild_id).await?;
+        let replaced = find_reaction_role(&bindings, binding.message_id, &binding.emoji);
+        let on_message = bindings
+            .iter()
+            .filter(|b| b.message_id == binding.message_id)
+            .count();
+        if replaced.is_none() && on_message >= MAX_ROLES_PER_MESSAGE {
+            return Ok(AddReactionRole::MessageFull);
+        }
+
+        self.store.set_reaction_role(guild_id, binding).await?;
+        self.cache.remove(&guild_id);
+        Ok(match replaced {
+            Some(role_id) => AddReactionRole::Replaced(role_id),
+            None => AddReactionRole::Added,
+        })
+    }
+
+    /// Remove one emoji's binding, or all of the message's when `emoji` is
+    /// `None`. Returns how many were removed.
+    pub async fn remove(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        emoji: Option<&str>,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        let removed = self
+            .store
+            .remove_reaction_roles(guild_id, message_id, emoji)
+            .await?;
+        self.cache.remove(&guild_id);
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::reactionroles::InMemoryReactionRoleStore;
+
+    fn binding(message_id: u64, emoji: &str, role_id: u64) -> ReactionRole {
+        ReactionRole {
+            channel_id: 10,
+            message_id,
+            emoji: emoji_key(emoji).unwrap(),
+            role_id,
+        }
+    }
+
+    #[test]
+    fn test_emoji_keys() {
+        assert_eq!(emoji_key("<:pepe:123456>").as_deref(), Some("123456"));
+        assert_eq!(emoji_key("<a:dance:987>").as_deref(), Some("987"));
+        assert_eq!(emoji_key("<:broken:abc>"), None);
+        // With and without the variation selector are the same emoji
+        assert_eq!(emoji_key("❤️"), emoji_key("❤"));
+        assert_eq!(emoji_key(" 🎮 ").as_deref(), Some("🎮"));
+        assert_eq!(emoji_key("  "), None);
+        assert_eq!(emoji_key("gaming"), None);
+        assert_eq!(emoji_key("🎮 🎨"), None);
+        assert_eq!(emoji_key("1️⃣").as_deref(), Some("1\u{20e3}"));
+    }
+
+    #[test]
+    fn test_find_reaction_role() {
+        let bindings = vec![
+            binding(1, "🎮", 100),
+            binding(1, "<:pepe:55>", 200),
+            binding(2, "🎮", 300),
+        ];
+
+        assert_eq!(find_reaction_role(&bindings, 1, "🎮"), Some(100));
+        assert_eq!(find_reaction_role(&bindings, 1, "55"), Some(200));
+        assert_eq!(find_reaction_role(&bindings, 2, "🎮"), Some(300));
+        assert_eq!(find_reaction_role(&bindings, 2, "55"), None);
+        assert_eq!(find_reaction_role(&bindings, 3, "🎮"), None);
+    }
+
+    #[tokio::test]
+    async fn test_add_replace_and_remove() {
+        let service = ReactionRoleService::new(InMemoryReactionRoleStore::new());
+
+        assert_eq!(
+            service.add(1, binding(5, "🎮", 100)).await.unwrap(),
+            AddReactionRole::Added
+        );
+        service.add(1, binding(5, "🎨", 101)).await.unwrap();
+        assert_eq!(
+            service.add(1, binding(5, "🎮", 102)).await.unwrap(),
+            AddReactionRole::Replaced(100)
+        );
+        assert_eq!(service.role_for(1, 5, "🎮").await.unwrap(), Some(102));
+        // Bindings are per guild
+        assert_eq!(service.role_for(2, 5, "🎮").await.unwrap(), None);
+
+        assert_eq!(service.remove(1, 5, Some("🎮")).await.unwrap(), 1);
+        assert_eq!(service.role_for(1, 5, "🎮").await.unwrap(), None);
+        assert_eq!(service.role_for(1, 5, "🎨").await.unwrap(), Some(101));
+        assert_eq!(service.remove(1, 5, None).await.unwrap(), 1);
+        assert!(service.bindings(1).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_message_reaction_limit() {
+        let service = ReactionRoleService::new(InMemoryReactionRoleStore::new());
+        for role in 0..MAX_ROLES_PER_MESSAGE as u64 {
+            let emoji = format!("<:e{role}:{role}>");
+            service.add(1, binding(5, &emoji, role)).await.unwrap();
+        }
+
+        assert_eq!(
+            service.add(1, binding(5, "🎮", 99)).await.unwrap(),
+            AddReactionRole::MessageFull
+        );
+        // Rebinding an existing emoji is still allowed
+        assert_eq!(
+            service.add(1, binding(5, "<:e0:0>", 99)).await.unwrap(),
+            AddReactionRole::Replaced(0)
+        );
+    }
+}
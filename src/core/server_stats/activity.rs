@@ -0,0 +1,150 @@
+// Lightweight message-activity tracking for `/serverstats activity`.
+//
+// **How it works:**
+// - Every processed guild message bumps a counter for the current UTC hour.
+// - Each guild keeps at most `ACTIVITY_WINDOW_HOURS` hourly buckets (4 weeks);
+//   older buckets are dropped as new ones arrive, so memory stays bounded.
+// - The command folds the buckets into "per hour of day" and "per day of week"
+//   totals for the chart.
+//
+// Counts live in memory only - they are a rough "when are people around?"
+// signal, not an audit log, so losing them on restart is acceptable.
+
+use chrono::{DateTime, Datelike, TimeZone, Timelike, Utc};
+use dashmap::DashMap;
+use std::collections::VecDeque;
+
+/// How many hourly buckets each guild keeps (28 days).
+pub const ACTIVITY_WINDOW_HOURS: i64 = 28 * 24;
+
+/// Hourly message counts for one guild, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct GuildActivity {
+    /// (hours since the Unix epoch, messages in that hour)
+    buckets: VecDeque<(i64, u64)>,
+}
+
+impl GuildActivity {
+    /// Count one message at `at`.
+    pub fn record(&mut self, at: DateTime<Utc>) {
+        let hour = at.timestamp().div_euclid(3600);
+
+        match self.buckets.back_mut() {
+            Some((last, count)) if *last == hour => *count += 1,
+            // Clock went backwards or an old message: fold it into the latest bucket
+            Some((last, count)) if *last > hour => *count += 1,
+            _ => self.buckets.push_back((hour, 1)),
+        }
+
+        let oldest_allowed = hour - ACTIVITY_WINDOW_HOURS + 1;
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(bucket, _)| *bucket < oldest_allowed)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Total messages in the window.
+    pub fn total(&self) -> u64 {
+        self.buckets.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Number of distinct days (UTC) with at least one message.
+    pub fn days_tracked(&self) -> usize {
+        let mut days: Vec<i64> = self
+            .buckets
+            .iter()
+            .map(|(hour, _)| hour.div_euclid(24))
+            .collect();
+        days.dedup();
+        days.len()
+    }
+
+    /// Messages per hour of day (index 0 = 00:00-00:59 UTC).
+    pub fn by_hour_of_day(&self) -> [u64; 24] {
+        let mut totals = [0; 24];
+        for (start, count) in self.bucket_starts() {
+            totals[start.hour() as usize] += count;
+        }
+        totals
+    }
+
+    /// Messages per day of week (index 0 = Monday, UTC).
+    pub fn by_weekday(&self) -> [u64; 7] {
+        let mut totals = [0; 7];
+        for (start, count) in self.bucket_starts() {
+            totals[start.weekday().num_days_from_monday() as usize] += count;
+        }
+        totals
+    }
+
+    fn bucket_starts(&self) -> impl Iterator<Item = (DateTime<Utc>, u64)> + '_ {
+        self.buckets.iter().filter_map(|(hour, count)| {
+            Utc.timestamp_opt(hour * 3600, 0)
+                .single()
+                .map(|start| (start, *count))
+        })
+    }
+}
+
+/// Per-guild activity counters.
+#[derive(Debug, Default)]
+pub struct ActivityTracker {
+    guilds: DashMap<u64, GuildActivity>,
+}
+
+impl ActivityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, guild_id: u64, at: DateTime<Utc>) {
+        self.guilds.entry(guild_id).or_default().record(at);
+    }
+
+    /// Snapshot of a guild's counters (empty if nothing was recorded yet).
+    pub fn snapshot(&self, guild_id: u64) -> GuildActivity {
+        self.guilds
+            .get(&guild_id)
+            .map(|activity| activity.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_buckets_by_hour_and_weekday() {
+        // 2024-01-01 was a Monday
+        let monday_9am = Utc.with_ymd_and_hms(2024, 1, 1, 9, 15, 0).unwrap();
+        let mut activity = GuildActivity::default();
+        activity.record(monday_9am);
+        activity.record(monday_9am + Duration::minutes(30));
+        activity.record(monday_9am + Duration::days(1) + Duration::hours(5));
+
+        assert_eq!(activity.total(), 3);
+        assert_eq!(activity.days_tracked(), 2);
+        assert_eq!(activity.by_hour_of_day()[9], 2);
+        assert_eq!(activity.by_hour_of_day()[14], 1);
+        assert_eq!(activity.by_weekday()[0], 2);
+        assert_eq!(activity.by_weekday()[1], 1);
+    }
+
+    #[test]
+    fn test_old_buckets_are_dropped() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut activity = GuildActivity::default();
+        activity.record(start);
+        activity.record(start + Duration::hours(ACTIVITY_WINDOW_HOURS - 1));
+        assert_eq!(activity.total(), 2);
+
+        activity.record(start + Duration::hours(ACTIVITY_WINDOW_HOURS));
+        assert_eq!(activity.total(), 2);
+        assert!(activity.buckets.len() as i64 <= ACTIVITY_WINDOW_HOURS);
+    }
+}
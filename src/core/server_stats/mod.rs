@@ -1,7 +1,9 @@
+pub mod activity;
 pub mod server_stats_models;
 pub mod server_stats_service;
 pub mod server_stats_store;
 
+pub use activity::GuildActivity;
 pub use server_stats_models::ServerStatsConfig;
 pub use server_stats_service::ServerStatsService;
 pub use server_stats_store::{ServerStatsStore, StoreError};
@@ -1,5 +1,7 @@
+use super::activity::{ActivityTracker, GuildActivity};
 use super::server_stats_models::ServerStatsConfig;
 use super::server_stats_store::{ServerStatsStore, StoreError};
+use chrono::{DateTime, Utc};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ServerStatsError {
@@ -13,11 +15,15 @@ pub enum ServerStatsError {
 
 pub struct ServerStatsService<S: ServerStatsStore> {
     store: S,
+    activity: ActivityTracker,
 }
 
 impl<S: ServerStatsStore> ServerStatsService<S> {
     pub fn new(store: S) -> Self {
-        Self { store }
+        Self {
+            store,
+            activity: ActivityTracker::new(),
+        }
     }
 
     pub async fn get_config(
@@ -50,4 +56,14 @@ impl<S: ServerStatsStore> ServerStatsService<S> {
     pub async fn get_all_configs(&self) -> Result<Vec<ServerStatsConfig>, ServerStatsError> {
         Ok(self.store.get_all_configs().await?)
     }
+
+    /// Count a message towards the guild's activity chart.
+    pub fn record_message(&self, guild_id: u64, at: DateTime<Utc>) {
+        self.activity.record(guild_id, at);
+    }
+
+    /// Hourly message counts for `/serverstats activity`.
+    pub fn activity(&self, guild_id: u64) -> GuildActivity {
+        self.activity.snapshot(guild_id)
+    }
 }
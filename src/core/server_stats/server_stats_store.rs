@@ -18,3 +18,22 @@ pub trait ServerStatsStore: Send + Sync {
     async fn delete_config(&self, guild_id: u64) -> Result<(), StoreError>;
     async fn get_all_configs(&self) -> Result<Vec<ServerStatsConfig>, StoreError>;
 }
+
+#[async_trait]
+impl<S: ServerStatsStore + ?Sized> ServerStatsStore for Box<S> {
+    async fn get_config(&self, guild_id: u64) -> Result<Option<ServerStatsConfig>, StoreError> {
+        (**self).get_config(guild_id).await
+    }
+
+    async fn save_config(&self, config: ServerStatsConfig) -> Result<(), StoreError> {
+        (**self).save_config(config).await
+    }
+
+    async fn delete_config(&self, guild_id: u64) -> Result<(), StoreError> {
+        (**self).delete_config(guild_id).await
+    }
+
+    async fn get_all_configs(&self) -> Result<Vec<ServerStatsConfig>, StoreError> {
+        (**self).get_all_configs().await
+    }
+}
@@ -0,0 +1,223 @@
+// Human-friendly duration parsing shared by reminders, moderation timeouts and
+// anything else that asks a user "how long?".
+//
+// **Accepted input:** one or more `<number><unit>` pairs, optionally separated
+// by spaces, commas or "and". Case and surrounding whitespace are ignored.
+//
+// ```ignore
+// parse_human_duration("10m")?;                   // 10 minutes
+// parse_human_duration("2h30m")?;                 // 2.5 hours
+// parse_human_duration("1 day, 4 hours")?;        // 28 hours
+// parse_human_duration("1 hour and 15 minutes")?; // 75 minutes
+// ```
+//
+// Units: s/sec/second, m/min/minute, h/hr/hour, d/day, w/wk/week (plurals ok).
+// A bare number without a unit is rejected - "10" could mean anything.
+
+use std::time::Duration;
+
+/// Longest duration we accept (one year). Anything above is almost certainly a typo.
+pub const MAX_DURATION: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseError {
+    #[error("No duration given")]
+    Empty,
+    #[error("Expected a number before '{0}'")]
+    MissingNumber(String),
+    #[error("Missing unit after {0} (try s, m, h, d or w)")]
+    MissingUnit(u64),
+    #[error("Unknown time unit '{0}' (try s, m, h, d or w)")]
+    UnknownUnit(String),
+    #[error("Duration is too long (max {} days)", MAX_DURATION.as_secs() / 86_400)]
+    TooLarge,
+}
+
+/// Parse a duration like "10m", "2h30m" or "1 day 4 hours".
+pub fn parse_human_duration(input: &str) -> Result<Duration, ParseError> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let mut chars = input.chars().peekable();
+    let mut total_secs: u64 = 0;
+    let mut parsed_any = false;
+
+    loop {
+        skip_separators(&mut chars);
+        if chars.peek().is_none() {
+            break;
+        }
+
+        // Number
+        let mut digits = String::new();
+        while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+            digits.push(c);
+            chars.next();
+        }
+        if digits.is_empty() {
+            let rest: String = chars.collect();
+            return Err(ParseError::MissingNumber(rest.trim().to_string()));
+        }
+        // More digits than fit in a u64 is "too large", not "garbage"
+        let amount: u64 = digits.parse().map_err(|_| ParseError::TooLarge)?;
+
+        // Unit
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let mut unit = String::new();
+        while let Some(c) = chars.peek().copied().filter(|c| c.is_alphabetic()) {
+            unit.push(c);
+            chars.next();
+        }
+        if unit.is_empty() {
+            return Err(ParseError::MissingUnit(amount));
+        }
+        let multiplier = unit_seconds(&unit).ok_or(ParseError::UnknownUnit(unit))?;
+
+        total_secs = amount
+            .checked_mul(multiplier)
+            .and_then(|secs| total_secs.checked_add(secs))
+            .ok_or(ParseError::TooLarge)?;
+        if total_secs > MAX_DURATION.as_secs() {
+            return Err(ParseError::TooLarge);
+        }
+        parsed_any = true;
+    }
+
+    if !parsed_any {
+        return Err(ParseError::Empty);
+    }
+
+    Ok(Duration::from_secs(total_secs))
+}
+
+/// Skip whitespace, commas and the word "and" between parts.
+fn skip_separators(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    loop {
+        match chars.peek() {
+            Some(c) if c.is_whitespace() || *c == ',' => {
+                chars.next();
+            }
+            Some('a') => {
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                if lookahead.next() == Some('n')
+                    && lookahead.next() == Some('d')
+                    && lookahead.peek().is_none_or(|c| !c.is_alphabetic())
+                {
+                    *chars = lookahead;
+                } else {
+                    return;
+                }
+            }
+            _ => return,
+        }
+    }
+}
+
+fn unit_seconds(unit: &str) -> Option<u64> {
+    let secs = match unit {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3_600,
+        "d" | "day" | "days" => 86_400,
+        "w" | "wk" | "wks" | "week" | "weeks" => 604_800,
+        _ => return None,
+    };
+    Some(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(input: &str) -> u64 {
+        parse_human_duration(input)
+            .unwrap_or_else(|e| panic!("{:?} should parse: {}", input, e))
+            .as_secs()
+    }
+
+    #[test]
+    fn test_single_units() {
+        assert_eq!(secs("45s"), 45);
+        assert_eq!(secs("10m"), 600);
+        assert_eq!(secs("2h"), 7_200);
+        assert_eq!(secs("1d"), 86_400);
+        assert_eq!(secs("1w"), 604_800);
+        assert_eq!(secs("0s"), 0);
+    }
+
+    #[test]
+    fn test_long_unit_names() {
+        assert_eq!(secs("30 seconds"), 30);
+        assert_eq!(secs("1 minute"), 60);
+        assert_eq!(secs("5 mins"), 300);
+        assert_eq!(secs("2 hours"), 7_200);
+        assert_eq!(secs("3 hrs"), 10_800);
+        assert_eq!(secs("1 day"), 86_400);
+        assert_eq!(secs("2 weeks"), 1_209_600);
+    }
+
+    #[test]
+    fn test_combined_units() {
+        assert_eq!(secs("2h30m"), 9_000);
+        assert_eq!(secs("1d2h3m4s"), 93_784);
+        assert_eq!(secs("1h 30m"), 5_400);
+        assert_eq!(secs("1 day, 4 hours"), 100_800);
+        assert_eq!(secs("1 hour and 15 minutes"), 4_500);
+        // Order doesn't matter and repeated units add up
+        assert_eq!(secs("30m2h"), 9_000);
+        assert_eq!(secs("10m 10m"), 1_200);
+    }
+
+    #[test]
+    fn test_whitespace_and_case() {
+        assert_eq!(secs("  10m  "), 600);
+        assert_eq!(secs("10 m"), 600);
+        assert_eq!(secs("2H30M"), 9_000);
+        assert_eq!(secs("\t1 Day\n"), 86_400);
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert_eq!(parse_human_duration(""), Err(ParseError::Empty));
+        assert_eq!(parse_human_duration("   "), Err(ParseError::Empty));
+        assert_eq!(parse_human_duration(" , and "), Err(ParseError::Empty));
+        assert_eq!(parse_human_duration("10"), Err(ParseError::MissingUnit(10)));
+        assert_eq!(
+            parse_human_duration("10 parsecs"),
+            Err(ParseError::UnknownUnit("parsecs".to_string()))
+        );
+        assert_eq!(
+            parse_human_duration("soon"),
+            Err(ParseError::MissingNumber("soon".to_string()))
+        );
+        assert_eq!(
+            parse_human_duration("m10"),
+            Err(ParseError::MissingNumber("m10".to_string()))
+        );
+        assert!(parse_human_duration("-5m").is_err());
+        assert!(parse_human_duration("1.5h").is_err());
+        assert!(parse_human_duration("10m!").is_err());
+        assert!(parse_human_duration("andy 5m").is_err());
+    }
+
+    #[test]
+    fn test_rejects_overflow_and_absurd_values() {
+        assert_eq!(parse_human_duration("366d"), Err(ParseError::TooLarge));
+        assert_eq!(parse_human_duration("53w"), Err(ParseError::TooLarge));
+        assert_eq!(parse_human_duration("364d 2d"), Err(ParseError::TooLarge));
+        assert_eq!(
+            parse_human_duration("99999999999999999999999s"),
+            Err(ParseError::TooLarge)
+        );
+        assert_eq!(
+            parse_human_duration("18446744073709551615w"),
+            Err(ParseError::TooLarge)
+        );
+        assert_eq!(secs("365d"), MAX_DURATION.as_secs());
+    }
+}
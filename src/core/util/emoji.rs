@@ -0,0 +1,51 @@
+// Custom emoji detection for message content stats.
+//
+// Discord sends custom emojis in message content as `<:name:id>`, or
+// `<a:name:id>` when animated. Unicode emojis are plain characters and aren't
+// counted here.
+
+/// Number of custom (server) emojis in message content.
+pub fn count_custom_emojis(content: &str) -> usize {
+    content
+        .split('<')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('>').map(|(inner, _)| inner))
+        .filter(|inner| is_custom_emoji(inner))
+        .count()
+}
+
+/// Whether the text between `<` and `>` is `:name:id` or `a:name:id`.
+fn is_custom_emoji(inner: &str) -> bool {
+    let inner = inner.strip_prefix('a').unwrap_or(inner);
+    let Some(inner) = inner.strip_prefix(':') else {
+        return false;
+    };
+    let Some((name, id)) = inner.split_once(':') else {
+        return false;
+    };
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !id.is_empty()
+        && id.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_custom_emojis() {
+        assert_eq!(count_custom_emojis("no emojis here 🎉"), 0);
+        assert_eq!(count_custom_emojis("gg <:pog:123456789>"), 1);
+        assert_eq!(
+            count_custom_emojis("<a:party_parrot:42><:pog:1> and again <:pog:1>"),
+            3
+        );
+        // Mentions, channels, timestamps and broken emojis don't count
+        assert_eq!(
+            count_custom_emojis("<@123> <#456> <t:1700000000:R> <:pog:> <:bad name:1> <:x:1a>"),
+            0
+        );
+        assert_eq!(count_custom_emojis("a < b and <:ok:9"), 0);
+    }
+}
@@ -0,0 +1,71 @@
+// Keeping the underlying error behind a domain error.
+//
+// Store failures reach users as a short message ("Storage error: ..."), but the
+// logs should show what actually went wrong, e.g. the sqlx error with its
+// SQLite code. Domain errors keep that error as their `source`, and
+// `ErrorChain` prints the whole chain for `tracing`.
+
+use std::error::Error;
+use std::fmt;
+
+/// The original error kept by a domain error.
+pub type ErrorSource = Box<dyn Error + Send + Sync>;
+
+/// Displays an error followed by its sources: `outer: inner: root`. A source
+/// whose text its parent already includes is skipped, since wrappers often
+/// repeat it.
+pub struct ErrorChain<'a>(pub &'a (dyn Error + 'static));
+
+impl fmt::Display for ErrorChain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut shown = self.0.to_string();
+        write!(f, "{}", shown)?;
+
+        let mut current = self.0.source();
+        while let Some(source) = current {
+            let text = source.to_string();
+            if !shown.contains(&text) {
+                write!(f, ": {}", text)?;
+            }
+            shown = text;
+            current = source.source();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Wrapper(&'static str, Option<Box<dyn Error + 'static>>);
+
+    impl fmt::Display for Wrapper {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl Error for Wrapper {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.1.as_deref()
+        }
+    }
+
+    #[test]
+    fn test_chain_lists_sources_once() {
+        let root = Wrapper("database is locked", None);
+        let middle = Wrapper("error returned from database", Some(Box::new(root)));
+        let outer = Wrapper(
+            "Storage error: error returned from database",
+            Some(Box::new(middle)),
+        );
+
+        assert_eq!(
+            ErrorChain(&outer).to_string(),
+            "Storage error: error returned from database: database is locked"
+        );
+        assert_eq!(ErrorChain(&Wrapper("plain", None)).to_string(), "plain");
+    }
+}
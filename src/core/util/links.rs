@@ -0,0 +1,80 @@
+//! Link detection in message content.
+//!
+//! Used by the leveling system (the "shared a link" stat) and by the AI mention
+//! handler, which hands URLs from the user's message to Gemini's URL Context tool
+//! so the model can read the page.
+
+/// Gemini accepts at most 20 URLs per request for URL Context.
+pub const MAX_CONTEXT_URLS: usize = 20;
+
+/// Whether the text contains an http(s) link.
+pub fn contains_link(content: &str) -> bool {
+    content.contains("http://") || content.contains("https://")
+}
+
+/// Extract the http(s) URLs from message content, in order and without duplicates.
+///
+/// Handles Discord's `<https://...>` embed suppression and markdown links
+/// (`[title](https://...)`), and drops trailing punctuation like `.` or `,`.
+pub fn extract_urls(content: &str) -> Vec<String> {
+    if !contains_link(content) {
+        return Vec::new();
+    }
+
+    let mut urls: Vec<String> = Vec::new();
+
+    for word in content.split_whitespace() {
+        let Some(start) = word.find("https://").or_else(|| word.find("http://")) else {
+            continue;
+        };
+
+        let url = word[start..]
+            .split(['<', '>', '"', '\'', '`'])
+            .next()
+            .unwrap_or("")
+            .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '*', '_', '|']);
+
+        // A bare "https://" with no host is not a link
+        if url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .is_empty()
+        {
+            continue;
+        }
+
+        if !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
+        }
+    }
+
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_urls_from_message() {
+        let content = "<@123> what does https://example.com/post say? Also see \
+            <https://docs.rs/tokio>, [the guide](https://rust-lang.org/learn). \
+            Again: https://example.com/post";
+
+        assert_eq!(
+            extract_urls(content),
+            vec![
+                "https://example.com/post",
+                "https://docs.rs/tokio",
+                "https://rust-lang.org/learn",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_urls() {
+        assert!(extract_urls("no links here").is_empty());
+        assert!(extract_urls("just a bare https:// prefix").is_empty());
+        assert!(!contains_link("ftp://example.com"));
+    }
+}
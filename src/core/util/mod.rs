@@ -0,0 +1,17 @@
+// Small helpers shared by several core features.
+
+pub mod duration;
+pub mod emoji;
+pub mod errors;
+pub mod links;
+pub mod placeholders;
+pub mod text;
+
+pub use duration::parse_human_duration;
+pub use emoji::count_custom_emojis;
+pub use errors::{ErrorChain, ErrorSource};
+#[allow(unused_imports)]
+pub use links::{contains_link, extract_urls};
+pub use placeholders::{fill_placeholders, unknown_placeholders};
+#[allow(unused_imports)]
+pub use text::{is_long_message, truncate_chars};
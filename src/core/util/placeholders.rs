@@ -0,0 +1,104 @@
+// `{name}` placeholder substitution shared by locale strings and the
+// admin-written message templates (welcome messages, announcements).
+//
+// ```ignore
+// fill_placeholders("Welcome {user} to {guild}!", &[
+//     ("user", "<@42>".to_string()),
+//     ("guild", "Rustaceans".to_string()),
+// ]); // "Welcome <@42> to Rustaceans!"
+// ```
+//
+// The template is scanned once, so substituted values are never expanded
+// again: a server named "{member_count}" stays "{member_count}". Unknown
+// placeholders and unmatched braces are left as written.
+
+/// Replace every `{name}` in `template` with its value from `values`.
+pub fn fill_placeholders(template: &str, values: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after_open = &rest[open + 1..];
+
+        let value = after_open.find('}').and_then(|close| {
+            let name = &after_open[..close];
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (value, close))
+        });
+
+        match value {
+            Some((value, close)) => {
+                out.push_str(value);
+                rest = &after_open[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after_open;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Placeholders in `template` that aren't in `known`, in order of appearance.
+/// Used to warn admins about typos like `{users}` when they save a template.
+pub fn unknown_placeholders(template: &str, known: &[&str]) -> Vec<String> {
+    let mut unknown: Vec<String> = Vec::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        let after_open = &rest[open + 1..];
+        let Some(close) = after_open.find('}') else {
+            break;
+        };
+        let name = &after_open[..close];
+        // `{` inside the name means the first brace was a stray one
+        if name.contains('{') {
+            rest = after_open;
+            continue;
+        }
+        if !name.is_empty() && !known.contains(&name) && !unknown.iter().any(|n| n == name) {
+            unknown.push(name.to_string());
+        }
+        rest = &after_open[close + 1..];
+    }
+
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_placeholders() {
+        let values = [
+            ("user", "<@42>".to_string()),
+            ("guild", "{member_count} club".to_string()),
+            ("member_count", "7".to_string()),
+        ];
+
+        assert_eq!(
+            fill_placeholders("Hi {user}, you are #{member_count}!", &values),
+            "Hi <@42>, you are #7!"
+        );
+        // Values aren't expanded a second time
+        assert_eq!(
+            fill_placeholders("Welcome to {guild}", &values),
+            "Welcome to {member_count} club"
+        );
+        assert_eq!(
+            fill_placeholders("{unknown} {user} { {user", &values),
+            "{unknown} <@42> { {user"
+        );
+        assert_eq!(
+            unknown_placeholders("{user} {users} {} {{guild} {users}", &["user", "guild"]),
+            vec!["users".to_string()]
+        );
+    }
+}
@@ -0,0 +1,54 @@
+// Length checks and truncation that count characters, not bytes.
+//
+// `String::len()` is the UTF-8 byte length, so CJK text or emoji look two to
+// four times longer than they are, and `String::truncate` panics when the cut
+// lands inside a multibyte character.
+
+/// Messages with at least this many characters count as long.
+pub const LONG_MESSAGE_CHARS: usize = 100;
+
+/// Whether a message is long enough for the "long messages" stat.
+pub fn is_long_message(content: &str) -> bool {
+    content.chars().count() >= LONG_MESSAGE_CHARS
+}
+
+/// Cut `text` to at most `max_chars` characters, ending with "..." when
+/// anything was cut (the "..." is on top of `max_chars`).
+pub fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}...", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_messages_count_characters() {
+        // 50 CJK characters are 150 bytes but not a long message
+        let cjk = "漢".repeat(50);
+        assert_eq!(cjk.len(), 150);
+        assert!(!is_long_message(&cjk));
+        assert!(!is_long_message(&"🎉".repeat(99)));
+        assert!(is_long_message(&"🎉".repeat(100)));
+        assert!(is_long_message(&"a".repeat(100)));
+    }
+
+    #[test]
+    fn test_truncate_chars_on_multibyte_text() {
+        // Byte 4000 falls inside a 3-byte character here
+        let reasoning = format!("a{}", "漢".repeat(2000));
+        let truncated = truncate_chars(&reasoning, 4000);
+        assert_eq!(truncated, reasoning);
+
+        let long = "漢".repeat(4001);
+        let truncated = truncate_chars(&long, 4000);
+        assert_eq!(truncated.chars().count(), 4003);
+        assert!(truncated.ends_with("漢..."));
+
+        assert_eq!(truncate_chars("🎉🎉🎉", 2), "🎉🎉...");
+        assert_eq!(truncate_chars("short", 10), "short");
+    }
+}
@@ -0,0 +1,316 @@
+// Per-guild goodbye messages: the counterpart of welcome messages, posted when
+// a member leaves (`/config goodbye set #channel <template>`).
+//
+// Templates use the shared `{name}` placeholders:
+//
+// - `{user}` - the member's username (a mention wouldn't resolve once they're gone)
+// - `{member_count}` - the server's member count after they left
+// - `{guild}` - the server's name
+// - `{duration}` - how long they were in the server, e.g. "3 months"
+//
+// Discord only reports the join date when the member was cached, so
+// `{duration}` falls back to "an unknown time" instead of failing.
+//
+// Admins can skip the message for kicks and bans. The Discord layer looks
+// those up in the audit log and `is_moderated_removal` decides.
+
+use crate::core::util::fill_placeholders;
+use crate::core::welcome::validate_template;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use std::error::Error;
+
+/// Placeholders a goodbye template may use.
+pub const GOODBYE_PLACEHOLDERS: [&str; 4] = ["user", "member_count", "guild", "duration"];
+
+/// How recent a kick or ban audit log entry must be to explain a departure.
+pub const MODERATED_REMOVAL_WINDOW_SECS: i64 = 30;
+
+/// A guild's goodbye message setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoodbyeConfig {
+    pub channel_id: u64,
+    pub template: String,
+    /// Stay quiet when the member was kicked or banned.
+    pub skip_kicks_and_bans: bool,
+}
+
+/// Values substituted into a goodbye template.
+#[derive(Debug, Clone)]
+pub struct GoodbyeContext {
+    pub user_name: String,
+    /// `None` if the count couldn't be determined.
+    pub member_count: Option<u64>,
+    pub guild_name: String,
+    /// `None` if the member's join date wasn't cached.
+    pub time_in_server: Option<Duration>,
+}
+
+/// Fill in a goodbye template.
+pub fn render_goodbye(template: &str, context: &GoodbyeContext) -> String {
+    fill_placeholders(
+        template,
+        &[
+            ("user", context.user_name.clone()),
+            (
+                "member_count",
+                context
+                    .member_count
+                    .map(|count| count.to_string())
+                    .unwrap_or_else(|| "?".to_string()),
+            ),
+            ("guild", context.guild_name.clone()),
+            (
+                "duration",
+                context
+                    .time_in_server
+                    .map(format_time_in_server)
+                    .unwrap_or_else(|| "an unknown time".to_string()),
+            ),
+        ],
+    )
+}
+
+/// Check a goodbye template before saving it.
+pub fn validate_goodbye_template(template: &str) -> Result<String, String> {
+    validate_template(template, &GOODBYE_PLACEHOLDERS)
+}
+
+/// Roughly how long someone was a member, in the largest sensible unit:
+/// "5 minutes", "3 days", "2 months", "1 year and 4 months".
+pub fn format_time_in_server(duration: Duration) -> String {
+    fn plural(count: i64, unit: &str) -> String {
+        format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+    }
+
+    let days = duration.num_days();
+    if days >= 365 {
+        let years = days / 365;
+        let months = (days % 365) / 30;
+        if months > 0 {
+            format!("{} and {}", plural(years, "year"), plural(months, "month"))
+        } else {
+            plural(years, "year")
+        }
+    } else if days >= 30 {
+        plural(days / 30, "month")
+    } else if days >= 1 {
+        plural(days, "day")
+    } else if duration.num_hours() >= 1 {
+        plural(duration.num_hours(), "hour")
+    } else if duration.num_minutes() >= 1 {
+        plural(duration.num_minutes(), "minute")
+    } else {
+        "less than a minute".to_string()
+    }
+}
+
+/// Whether a departure at `now` is explained by a kick or ban logged at one of
+/// `action_times` (audit log entries targeting the member).
+pub fn is_moderated_removal(action_times: &[DateTime<Utc>], now: DateTime<Utc>) -> bool {
+    let window = Duration::seconds(MODERATED_REMOVAL_WINDOW_SECS);
+    action_times.iter().any(|&at| (now - at).abs() <= window)
+}
+
+/// Persistence for goodbye settings.
+#[async_trait]
+pub trait GoodbyeStore: Send + Sync {
+    async fn get_goodbye(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<GoodbyeConfig>, Box<dyn Error + Send + Sync>>;
+
+    async fn set_goodbye(
+        &self,
+        guild_id: u64,
+        config: &GoodbyeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    async fn remove_goodbye(&self, guild_id: u64) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: GoodbyeStore + ?Sized> GoodbyeStore for Box<S> {
+    async fn get_goodbye(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<GoodbyeConfig>, Box<dyn Error + Send + Sync>> {
+        (**self).get_goodbye(guild_id).await
+    }
+
+    async fn set_goodbye(
+        &self,
+        guild_id: u64,
+        config: &GoodbyeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).set_goodbye(guild_id, config).await
+    }
+
+    async fn remove_goodbye(&self, guild_id: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).remove_goodbye(guild_id).await
+    }
+}
+
+/// Goodbye settings with a per-guild cache, since every departure reads them.
+pub struct GoodbyeService<S: GoodbyeStore> {
+    store: S,
+    cache: DashMap<u64, Option<GoodbyeConfig>>,
+}
+
+impl<S: GoodbyeStore> GoodbyeService<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            cache: DashMap::new(),
+        }
+    }
+
+    /// The guild's goodbye setting, if goodbye messages are on.
+    pub async fn goodbye(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<GoodbyeConfig>, Box<dyn Error + Send + Sync>> {
+        if let Some(config) = self.cache.get(&guild_id) {
+            return Ok(config.clone());
+        }
+
+        let config = self.store.get_goodbye(guild_id).await?;
+        self.cache.insert(guild_id, config.clone());
+        Ok(config)
+    }
+
+    /// Post `template` in `channel_id` whenever someone leaves, keeping the
+    /// kick/ban setting if goodbye messages were already on. The template
+    /// must already have passed `validate_goodbye_template`.
+    pub async fn set_goodbye(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        template: String,
+    ) -> Result<GoodbyeConfig, Box<dyn Error + Send + Sync>> {
+        let skip_kicks_and_bans = self
+            .goodbye(guild_id)
+            .await?
+            .is_some_and(|config| config.skip_kicks_and_bans);
+        let config = GoodbyeConfig {
+            channel_id,
+            template,
+            skip_kicks_and_bans,
+        };
+        self.save(guild_id, config.clone()).await?;
+        Ok(config)
+    }
+
+    /// Choose whether kicks and bans get a goodbye message. Returns the
+    /// updated setting, or `None` if goodbye messages are off.
+    pub async fn set_skip_kicks_and_bans(
+        &self,
+        guild_id: u64,
+        skip: bool,
+    ) -> Result<Option<GoodbyeConfig>, Box<dyn Error + Send + Sync>> {
+        let Some(mut config) = self.goodbye(guild_id).await? else {
+            return Ok(None);
+        };
+        config.skip_kicks_and_bans = skip;
+        self.save(guild_id, config.clone()).await?;
+        Ok(Some(config))
+    }
+
+    /// Stop posting goodbye messages. Returns whether they were on.
+    pub async fn disable(&self, guild_id: u64) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let was_enabled = self.goodbye(guild_id).await?.is_some();
+        self.store.remove_goodbye(guild_id).await?;
+        self.cache.insert(guild_id, None);
+        Ok(was_enabled)
+    }
+
+    async fn save(
+        &self,
+        guild_id: u64,
+        config: GoodbyeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.store.set_goodbye(guild_id, &config).await?;
+        self.cache.insert(guild_id, Some(config));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::welcome::InMemoryGoodbyeStore;
+
+    #[test]
+    fn test_render_goodbye_with_and_without_join_date() {
+        let context = GoodbyeContext {
+            user_name: "ferris".to_string(),
+            member_count: Some(41),
+            guild_name: "Rustaceans".to_string(),
+            time_in_server: Some(Duration::days(400)),
+        };
+        let template = "{user} left {guild} after {duration}. We're now {member_count}.";
+
+        assert_eq!(
+            render_goodbye(template, &context),
+            "ferris left Rustaceans after 1 year and 1 month. We're now 41."
+        );
+        let uncached = GoodbyeContext {
+            time_in_server: None,
+            ..context
+        };
+        assert_eq!(
+            render_goodbye(template, &uncached),
+            "ferris left Rustaceans after an unknown time. We're now 41."
+        );
+
+        assert_eq!(
+            format_time_in_server(Duration::seconds(20)),
+            "less than a minute"
+        );
+        assert_eq!(format_time_in_server(Duration::hours(1)), "1 hour");
+        assert_eq!(format_time_in_server(Duration::days(65)), "2 months");
+        assert_eq!(format_time_in_server(Duration::days(730)), "2 years");
+
+        assert!(validate_goodbye_template("Bye {user}, {duration}").is_ok());
+        // `{duration}` only makes sense for goodbyes
+        assert!(crate::core::welcome::validate_welcome_template("{duration}").is_err());
+    }
+
+    #[test]
+    fn test_moderated_removal_window() {
+        let now = Utc::now();
+
+        assert!(is_moderated_removal(&[now - Duration::seconds(3)], now));
+        // Audit log clocks can run slightly ahead of ours
+        assert!(is_moderated_removal(&[now + Duration::seconds(1)], now));
+        // An old kick from a previous stay doesn't count
+        assert!(!is_moderated_removal(&[now - Duration::days(2)], now));
+        assert!(!is_moderated_removal(&[], now));
+    }
+
+    #[tokio::test]
+    async fn test_goodbye_keeps_kick_setting_when_template_changes() {
+        let service = GoodbyeService::new(InMemoryGoodbyeStore::new());
+
+        assert_eq!(
+            service.set_skip_kicks_and_bans(1, true).await.unwrap(),
+            None
+        );
+
+        service
+            .set_goodbye(1, 99, "Bye {user}".to_string())
+            .await
+            .unwrap();
+        service.set_skip_kicks_and_bans(1, true).await.unwrap();
+        let config = service
+            .set_goodbye(1, 100, "See you, {user}".to_string())
+            .await
+            .unwrap();
+        assert!(config.skip_kicks_and_bans);
+        assert_eq!(service.goodbye(1).await.unwrap().unwrap().channel_id, 100);
+
+        assert!(service.disable(1).await.unwrap());
+        assert_eq!(service.goodbye(1).await.unwrap(), None);
+    }
+}
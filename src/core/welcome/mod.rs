@@ -0,0 +1,8 @@
+// Messages posted when members join (`/config welcome`) or leave
+// (`/config goodbye`).
+
+pub mod goodbye_message;
+pub mod welcome_message;
+
+pub use goodbye_message::*;
+pub use welcome_message::*;
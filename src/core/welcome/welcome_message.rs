@@ -0,0 +1,243 @@
+// Per-guild welcome messages: an embed posted to a chosen channel when a
+// member joins (`/config welcome set #channel <template>`).
+//
+// Templates use the shared `{name}` placeholders (see
+// `core::util::placeholders`):
+//
+// - `{user}` - mention of the new member
+// - `{member_count}` - the server's member count, including the new member
+// - `{guild}` - the server's name
+//
+// Rendering is pure so the Discord layer only has to gather the values and
+// build the embed.
+
+use crate::core::util::{fill_placeholders, unknown_placeholders};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::error::Error;
+
+/// Placeholders a welcome template may use.
+pub const WELCOME_PLACEHOLDERS: [&str; 3] = ["user", "member_count", "guild"];
+
+/// Longest welcome or goodbye template accepted, in characters. Leaves room
+/// under Discord's 4096-character embed description once placeholders are
+/// filled in.
+pub const MAX_WELCOME_TEMPLATE_CHARS: usize = 1500;
+
+/// A guild's welcome message setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WelcomeConfig {
+    pub channel_id: u64,
+    pub template: String,
+}
+
+/// Values substituted into a welcome template.
+#[derive(Debug, Clone)]
+pub struct WelcomeContext {
+    pub user_id: u64,
+    /// `None` if the count couldn't be determined.
+    pub member_count: Option<u64>,
+    pub guild_name: String,
+}
+
+/// Fill in a welcome template. An unknown member count renders as "?".
+pub fn render_welcome(template: &str, context: &WelcomeContext) -> String {
+    fill_placeholders(
+        template,
+        &[
+            ("user", format!("<@{}>", context.user_id)),
+            (
+                "member_count",
+                context
+                    .member_count
+                    .map(|count| count.to_string())
+                    .unwrap_or_else(|| "?".to_string()),
+            ),
+            ("guild", context.guild_name.clone()),
+        ],
+    )
+}
+
+/// Check a welcome template before saving it. Returns the trimmed template,
+/// or a message explaining what's wrong.
+pub fn validate_welcome_template(template: &str) -> Result<String, String> {
+    validate_template(template, &WELCOME_PLACEHOLDERS)
+}
+
+/// Check a member message template against the placeholders it may use.
+/// Shared by welcome and goodbye messages.
+pub fn validate_template(template: &str, placeholders: &[&str]) -> Result<String, String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Err("The message can't be empty.".to_string());
+    }
+    if template.chars().count() > MAX_WELCOME_TEMPLATE_CHARS {
+        return Err(format!(
+            "The message is too long (max {} characters).",
+            MAX_WELCOME_TEMPLATE_CHARS
+        ));
+    }
+
+    let unknown = unknown_placeholders(template, placeholders);
+    if !unknown.is_empty() {
+        let quote = |name: &str| format!("`{{{}}}`", name);
+        let unknown: Vec<String> = unknown.iter().map(|name| quote(name)).collect();
+        let known: Vec<String> = placeholders.iter().map(|name| quote(name)).collect();
+        return Err(format!(
+            "Unknown placeholder {}. Available: {}.",
+            unknown.join(", "),
+            known.join(", ")
+        ));
+    }
+
+    Ok(template.to_string())
+}
+
+/// Persistence for welcome settings.
+#[async_trait]
+pub trait WelcomeStore: Send + Sync {
+    async fn get_welcome(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<WelcomeConfig>, Box<dyn Error + Send + Sync>>;
+
+    async fn set_welcome(
+        &self,
+        guild_id: u64,
+        config: &WelcomeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    async fn remove_welcome(&self, guild_id: u64) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl<S: WelcomeStore + ?Sized> WelcomeStore for Box<S> {
+    async fn get_welcome(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<WelcomeConfig>, Box<dyn Error + Send + Sync>> {
+        (**self).get_welcome(guild_id).await
+    }
+
+    async fn set_welcome(
+        &self,
+        guild_id: u64,
+        config: &WelcomeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).set_welcome(guild_id, config).await
+    }
+
+    async fn remove_welcome(&self, guild_id: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        (**self).remove_welcome(guild_id).await
+    }
+}
+
+/// Welcome settings with a per-guild cache, since every join reads them.
+pub struct WelcomeService<S: WelcomeStore> {
+    store: S,
+    cache: DashMap<u64, Option<WelcomeConfig>>,
+}
+
+impl<S: WelcomeStore> WelcomeService<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            cache: DashMap::new(),
+        }
+    }
+
+    /// The guild's welcome setting, if welcome messages are on.
+    pub async fn welcome(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<WelcomeConfig>, Box<dyn Error + Send + Sync>> {
+        if let Some(config) = self.cache.get(&guild_id) {
+            return Ok(config.clone());
+        }
+
+        let config = self.store.get_welcome(guild_id).await?;
+        self.cache.insert(guild_id, config.clone());
+        Ok(config)
+    }
+
+    /// Post `template` in `channel_id` whenever someone joins. The template
+    /// must already have passed `validate_welcome_template`.
+    pub async fn set_welcome(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        template: String,
+    ) -> Result<WelcomeConfig, Box<dyn Error + Send + Sync>> {
+        let config = WelcomeConfig {
+            channel_id,
+            template,
+        };
+        self.store.set_welcome(guild_id, &config).await?;
+        self.cache.insert(guild_id, Some(config.clone()));
+        Ok(config)
+    }
+
+    /// Stop posting welcome messages. Returns whether they were on.
+    pub async fn disable(&self, guild_id: u64) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let was_enabled = self.welcome(guild_id).await?.is_some();
+        self.store.remove_welcome(guild_id).await?;
+        self.cache.insert(guild_id, None);
+        Ok(was_enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::welcome::InMemoryWelcomeStore;
+
+    #[test]
+    fn test_render_and_validate_welcome_template() {
+        let context = WelcomeContext {
+            user_id: 42,
+            member_count: Some(128),
+            guild_name: "Rust {user}s".to_string(),
+        };
+
+        assert_eq!(
+            render_welcome(
+                "Welcome {user} to **{guild}**! You are member #{member_count}.",
+                &context
+            ),
+            "Welcome <@42> to **Rust {user}s**! You are member #128."
+        );
+        let unknown_count = WelcomeContext {
+            member_count: None,
+            ..context
+        };
+        assert_eq!(render_welcome("#{member_count}", &unknown_count), "#?");
+
+        assert_eq!(
+            validate_welcome_template("  Hi {user}!  ").unwrap(),
+            "Hi {user}!"
+        );
+        assert!(validate_welcome_template("   ").is_err());
+        assert!(validate_welcome_template("Hi {usr}!")
+            .unwrap_err()
+            .contains("`{usr}`"));
+        assert!(validate_welcome_template(&"a".repeat(MAX_WELCOME_TEMPLATE_CHARS + 1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_welcome_set_and_disable() {
+        let service = WelcomeService::new(InMemoryWelcomeStore::new());
+
+        assert_eq!(service.welcome(1).await.unwrap(), None);
+        assert!(!service.disable(1).await.unwrap());
+
+        service
+            .set_welcome(1, 99, "Hi {user}".to_string())
+            .await
+            .unwrap();
+        assert_eq!(service.welcome(1).await.unwrap().unwrap().channel_id, 99);
+        assert_eq!(service.welcome(2).await.unwrap(), None);
+
+        assert!(service.disable(1).await.unwrap());
+        assert_eq!(service.welcome(1).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,54 @@
+// Command checks for admin commands.
+//
+// `is_manager` replaces `required_permissions = "ADMINISTRATOR"` on admin
+// commands: members with ADMINISTRATOR still pass, and so do members holding
+// one of the guild's manager roles (`/config managers add @role`).
+
+use crate::discord::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// poise `check`: allow administrators and members with a manager role.
+/// Replies to everyone else, so the failed check needs no further handling.
+pub async fn is_manager(ctx: Context<'_>) -> Result<bool, Error> {
+    if has_manager_access(ctx).await {
+        return Ok(true);
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content("🔒 This command needs the Administrator permission or a manager role.")
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(false)
+}
+
+/// Whether the author is an administrator or holds a manager role, without
+/// replying. For commands where only part of the input is restricted.
+pub async fn has_manager_access(ctx: Context<'_>) -> bool {
+    let Some(guild_id) = ctx.guild_id() else {
+        return false;
+    };
+    let Some(member) = ctx.author_member().await else {
+        return false;
+    };
+
+    if is_administrator(ctx, &member) {
+        return true;
+    }
+
+    let role_ids: Vec<u64> = member.roles.iter().map(|role| role.get()).collect();
+    ctx.data()
+        .manager_roles
+        .is_manager(guild_id.get(), &role_ids)
+        .await
+}
+
+fn is_administrator(ctx: Context<'_>, member: &serenity::Member) -> bool {
+    // Slash commands carry the resolved permissions; prefix commands need the cache
+    if let Some(permissions) = member.permissions {
+        return permissions.administrator();
+    }
+    ctx.guild()
+        .is_some_and(|guild| guild.member_permissions(member).administrator())
+}
@@ -0,0 +1,109 @@
+// Image attachments for multimodal AI requests.
+//
+// When the configured model accepts images, the mention handler downloads the
+// images attached to the triggering message and sends them inline (base64)
+// alongside the text. Limits keep one message from blowing up the request size.
+
+use crate::core::ai::{AiImage, ImageSource};
+use base64::Engine;
+use poise::serenity_prelude as serenity;
+
+/// Maximum number of images forwarded from a single message.
+const MAX_IMAGES: usize = 4;
+
+/// Images larger than this are skipped (inline payloads count against the
+/// provider's request size limit).
+const MAX_IMAGE_BYTES: u32 = 5 * 1024 * 1024;
+
+/// MIME type of an attachment if it's an image we can forward.
+///
+/// Prefers Discord's `content_type`, falling back to the file extension.
+fn image_mime_type(attachment: &serenity::Attachment) -> Option<String> {
+    if let Some(content_type) = &attachment.content_type {
+        let mime = content_type.split(';').next().unwrap_or("").trim();
+        return mime.starts_with("image/").then(|| mime.to_string());
+    }
+
+    let extension = attachment.filename.rsplit_once('.')?.1.to_lowercase();
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => return None,
+    };
+    Some(mime.to_string())
+}
+
+/// Pick the attachments worth sending, with their MIME types.
+fn select_images(attachments: &[serenity::Attachment]) -> Vec<(&serenity::Attachment, String)> {
+    attachments
+        .iter()
+        .filter(|a| a.size <= MAX_IMAGE_BYTES)
+        .filter_map(|a| image_mime_type(a).map(|mime| (a, mime)))
+        .take(MAX_IMAGES)
+        .collect()
+}
+
+/// Download the message's image attachments. Failed downloads are logged and skipped.
+pub async fn download_images(attachments: &[serenity::Attachment]) -> Vec<AiImage> {
+    let mut images = Vec::new();
+
+    for (attachment, mime_type) in select_images(attachments) {
+        match attachment.download().await {
+            Ok(bytes) => images.push(AiImage {
+                mime_type,
+                source: ImageSource::Inline(
+                    base64::engine::general_purpose::STANDARD.encode(bytes),
+                ),
+            }),
+            Err(e) => tracing::warn!(
+                filename = %attachment.filename,
+                error = %e,
+                "Failed to download image attachment"
+            ),
+        }
+    }
+
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(filename: &str, content_type: Option<&str>, size: u32) -> serenity::Attachment {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "filename": filename,
+            "size": size,
+            "url": "https://cdn.example/a",
+            "proxy_url": "https://cdn.example/a",
+            "content_type": content_type,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_selects_only_small_images() {
+        let attachments = vec![
+            attachment("cat.png", Some("image/png"), 1024),
+            attachment("notes.txt", Some("text/plain"), 10),
+            attachment("huge.jpg", Some("image/jpeg"), MAX_IMAGE_BYTES + 1),
+            attachment("old.JPEG", None, 2048),
+        ];
+
+        let selected: Vec<(&str, String)> = select_images(&attachments)
+            .into_iter()
+            .map(|(a, mime)| (a.filename.as_str(), mime))
+            .collect();
+
+        assert_eq!(
+            selected,
+            vec![
+                ("cat.png", "image/png".to_string()),
+                ("old.JPEG", "image/jpeg".to_string()),
+            ]
+        );
+    }
+}
@@ -55,13 +55,10 @@ pub async fn fetch_context_channels(http: &serenity::Http, limit: u8) -> Vec<AiM
         {
             Ok(messages) => {
                 // Add a context header for this channel
-                context_messages.push(AiMessage {
-                    role: "system".to_string(),
-                    content: format!(
-                        "--- Context from #{} (for background information) ---",
-                        channel_name
-                    ),
-                });
+                context_messages.push(AiMessage::system(format!(
+                    "--- Context from #{} (for background information) ---",
+                    channel_name
+                )));
 
                 // Process messages oldest to newest for proper chronological order
                 for msg in messages.iter().rev() {
@@ -77,10 +74,7 @@ pub async fn fetch_context_channels(http: &serenity::Http, limit: u8) -> Vec<AiM
                         msg.content
                     );
 
-                    context_messages.push(AiMessage {
-                        role: "user".to_string(),
-                        content,
-                    });
+                    context_messages.push(AiMessage::user(content));
                 }
             }
             Err(e) => {
@@ -3,7 +3,10 @@
 // This module contains Discord-specific AI helpers, such as fetching
 // context from designated channels to give the AI background knowledge.
 
+#[path = "attachments.rs"]
+pub mod attachments;
 #[path = "context_channels.rs"]
 pub mod context_channels;
 
+pub use attachments::download_images;
 pub use context_channels::fetch_context_channels;
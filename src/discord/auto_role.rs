@@ -0,0 +1,114 @@
+// Gives joining members the guild's auto-role (`/config autorole`).
+//
+// The decision itself lives in `core::autorole::decide_auto_role`; this module
+// only gathers the Discord facts it needs (the bot's permissions and highest
+// role) and performs the HTTP call.
+
+use crate::core::autorole::{decide_auto_role, AutoRoleDecision, BotRoleStanding};
+use crate::discord::Data;
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+
+/// Assign the auto-role to a member who just joined. Never fails the join
+/// handler: problems are logged and the member is left as is.
+pub async fn assign_on_join(ctx: &serenity::Context, data: &Data, member: &serenity::Member) {
+    let guild_id = member.guild_id;
+    let config = match data.auto_roles.auto_role(guild_id.get()).await {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::warn!(guild_id = guild_id.get(), error = %e, "Failed to load auto-role");
+            return;
+        }
+    };
+    // Nothing to check against Discord if the feature is off
+    let Some(config) = config.filter(|config| config.enabled) else {
+        return;
+    };
+
+    let Some((role_position, bot)) = role_facts(ctx, guild_id, config.role_id).await else {
+        tracing::warn!(
+            guild_id = guild_id.get(),
+            "Could not load roles to apply the auto-role"
+        );
+        return;
+    };
+
+    let member_roles: Vec<u64> = member.roles.iter().map(|role| role.get()).collect();
+    match decide_auto_role(Some(config), &member_roles, role_position, bot) {
+        AutoRoleDecision::Assign(role_id) => {
+            if let Err(e) = member
+                .add_role(&ctx.http, serenity::RoleId::new(role_id))
+                .await
+            {
+                tracing::warn!(
+                    guild_id = guild_id.get(),
+                    user_id = member.user.id.get(),
+                    role_id,
+                    error = %e,
+                    "Failed to assign auto-role"
+                );
+            }
+        }
+        AutoRoleDecision::Disabled | AutoRoleDecision::AlreadyHasRole => {}
+        skipped => tracing::warn!(
+            guild_id = guild_id.get(),
+            role_id = config.role_id,
+            reason = ?skipped,
+            "Auto-role not assigned"
+        ),
+    }
+}
+
+/// The position of `role_id` (`None` if it was deleted) and what the bot may
+/// do with roles in the guild. Prefers the cache and falls back to HTTP.
+pub async fn role_facts(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    role_id: u64,
+) -> Option<(Option<u16>, BotRoleStanding)> {
+    let bot_id = ctx.cache.current_user().id;
+
+    let cached = ctx.cache.guild(guild_id).map(|guild| {
+        (
+            guild.roles.clone(),
+            guild.members.get(&bot_id).map(|bot| bot.roles.clone()),
+        )
+    });
+    let (roles, bot_roles) = match cached {
+        Some((roles, Some(bot_roles))) => (roles, bot_roles),
+        Some((roles, None)) => (roles, guild_id.member(ctx, bot_id).await.ok()?.roles),
+        None => (
+            guild_id.roles(&ctx.http).await.ok()?,
+            guild_id.member(ctx, bot_id).await.ok()?.roles,
+        ),
+    };
+
+    let role_position = roles
+        .get(&serenity::RoleId::new(role_id))
+        .map(|role| role.position);
+    Some((role_position, bot_standing(&roles, guild_id, &bot_roles)))
+}
+
+fn bot_standing(
+    roles: &HashMap<serenity::RoleId, serenity::Role>,
+    guild_id: serenity::GuildId,
+    bot_roles: &[serenity::RoleId],
+) -> BotRoleStanding {
+    // @everyone shares the guild's id and applies to the bot too
+    let everyone = serenity::RoleId::new(guild_id.get());
+    let mut permissions = roles
+        .get(&everyone)
+        .map(|role| role.permissions)
+        .unwrap_or_default();
+    let mut highest_position = 0;
+
+    for role in bot_roles.iter().filter_map(|id| roles.get(id)) {
+        permissions |= role.permissions;
+        highest_position = highest_position.max(role.position);
+    }
+
+    BotRoleStanding {
+        can_manage_roles: permissions.administrator() || permissions.manage_roles(),
+        highest_position,
+    }
+}
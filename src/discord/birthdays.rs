@@ -0,0 +1,124 @@
+// Announces birthdays at each guild's local midnight (`/config birthdays`).
+//
+// A background task started in `main` calls `celebrate_due` for every guild
+// with announcements on, once a minute. The day and the members come from
+// `core::birthdays`; this module posts the message and moves the birthday
+// role from yesterday's members to today's.
+//
+// A day counts as celebrated even if the announcement couldn't be posted, so
+// a channel the bot can't write to doesn't get retried every minute.
+
+use crate::core::birthdays::Celebration;
+use crate::discord::util::permissions::can_send_messages;
+use crate::discord::Data;
+use poise::serenity_prelude as serenity;
+
+/// The announcement for the members in `user_ids`.
+pub fn announcement_text(user_ids: &[u64]) -> String {
+    let mentions: Vec<String> = user_ids.iter().map(|id| format!("<@{}>", id)).collect();
+    let names = match mentions.as_slice() {
+        [] => String::new(),
+        [one] => one.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    };
+    format!("🎂 Happy birthday {}! 🎉", names)
+}
+
+/// Celebrate the guild's birthdays if its local day has changed since the
+/// last celebration.
+pub async fn celebrate_due(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: u64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let timezone = data.leveling.daily_timezone(guild_id).await?;
+    let today = chrono::Utc::now().with_timezone(&timezone).date_naive();
+    let Some(celebration) = data.birthdays.due_celebration(guild_id, today).await? else {
+        return Ok(());
+    };
+
+    if let Some(role_id) = celebration.role_id {
+        update_roles(&ctx.http, guild_id, role_id, &celebration).await;
+    }
+    if !celebration.user_ids.is_empty() {
+        announce(ctx, guild_id, &celebration).await;
+    }
+
+    data.birthdays.mark_celebrated(guild_id, &celebration).await
+}
+
+/// Post the announcement. Failures are logged: the day is over either way.
+async fn announce(ctx: &serenity::Context, guild_id: u64, celebration: &Celebration) {
+    let channel_id = serenity::ChannelId::new(celebration.channel_id);
+    if !can_send_messages(ctx, Some(serenity::GuildId::new(guild_id)), channel_id) {
+        tracing::warn!(
+            guild_id,
+            channel_id = celebration.channel_id,
+            "Can't send messages in the birthday channel, skipping the announcement"
+        );
+        return;
+    }
+
+    let message = serenity::CreateMessage::new()
+        .content(announcement_text(&celebration.user_ids))
+        .allowed_mentions(serenity::CreateAllowedMentions::new().all_users(true));
+    match channel_id.send_message(&ctx.http, message).await {
+        Ok(_) => tracing::info!(
+            guild_id,
+            members = celebration.user_ids.len(),
+            "Announced birthdays"
+        ),
+        Err(e) => tracing::warn!(
+            guild_id,
+            channel_id = celebration.channel_id,
+            error = %e,
+            "Failed to post birthday announcement"
+        ),
+    }
+}
+
+/// Take the birthday role from the members who got it last time and give it
+/// to today's.
+/// Failures (e.g. a member who left) are logged and skipped.
+async fn update_roles(
+    http: &serenity::Http,
+    guild_id: u64,
+    role_id: u64,
+    celebration: &Celebration,
+) {
+    let guild = serenity::GuildId::new(guild_id);
+    let role = serenity::RoleId::new(role_id);
+
+    for &user_id in &celebration.ended_user_ids {
+        let user = serenity::UserId::new(user_id);
+        if let Err(e) = http
+            .remove_member_role(guild, user, role, Some("Birthday is over"))
+            .await
+        {
+            tracing::warn!(guild_id, user_id, role_id, error = %e, "Failed to remove birthday role");
+        }
+    }
+    for &user_id in &celebration.user_ids {
+        let user = serenity::UserId::new(user_id);
+        if let Err(e) = http
+            .add_member_role(guild, user, role, Some("Birthday"))
+            .await
+        {
+            tracing::warn!(guild_id, user_id, role_id, error = %e, "Failed to give birthday role");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_announcement_lists_everyone() {
+        assert_eq!(announcement_text(&[1]), "🎂 Happy birthday <@1>! 🎉");
+        assert_eq!(
+            announcement_text(&[1, 2, 3]),
+            "🎂 Happy birthday <@1>, <@2> and <@3>! 🎉"
+        );
+    }
+}
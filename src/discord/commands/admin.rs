@@ -0,0 +1,324 @@
+use crate::core::feedback::Feedback;
+use crate::discord::util::Paginator;
+use crate::discord::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// Guilds shown per `/admin guilds` page.
+const GUILDS_PER_PAGE: usize = 15;
+
+/// Feedback entries shown per `/admin feedback recent` page.
+const FEEDBACK_PER_PAGE: usize = 5;
+
+/// Manage the bot itself (bot owners only).
+///
+/// Owners are the application's owner or team, plus anyone in `BOT_OWNER_IDS`.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    owners_only,
+    hide_in_help,
+    subcommands("guilds", "leave", "broadcast", "feedback")
+)]
+pub async fn admin(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say(
+        "Bot administration commands:\n\
+        `/admin guilds` - List the servers the bot is in\n\
+        `/admin leave <guild_id>` - Make the bot leave a server\n\
+        `/admin broadcast <message>` - Post a message in every server's system channel\n\
+        `/admin feedback recent [count]` - Read the latest `/feedback` entries",
+    )
+    .await?;
+    Ok(())
+}
+
+/// A guild as listed by `/admin guilds`.
+struct GuildSummary {
+    id: u64,
+    name: String,
+    members: u64,
+}
+
+/// Every guild in the cache, largest first.
+fn cached_guilds(ctx: Context<'_>) -> Vec<GuildSummary> {
+    let cache = &ctx.serenity_context().cache;
+    let mut guilds: Vec<GuildSummary> = cache
+        .guilds()
+        .into_iter()
+        .map(|guild_id| match cache.guild(guild_id) {
+            Some(guild) => GuildSummary {
+                id: guild_id.get(),
+                name: guild.name.clone(),
+                members: guild.member_count,
+            },
+            None => GuildSummary {
+                id: guild_id.get(),
+                name: "(unavailable)".to_string(),
+                members: 0,
+            },
+        })
+        .collect();
+    sort_guilds(&mut guilds);
+    guilds
+}
+
+/// Largest guilds first, then by name.
+fn sort_guilds(guilds: &mut [GuildSummary]) {
+    guilds.sort_by(|a, b| b.members.cmp(&a.members).then_with(|| a.name.cmp(&b.name)));
+}
+
+fn guild_line(guild: &GuildSummary) -> String {
+    let members = if guild.members == 1 {
+        "member"
+    } else {
+        "members"
+    };
+    format!(
+        "**{}** (`{}`) - {} {members}",
+        guild.name, guild.id, guild.members
+    )
+}
+
+/// List the servers the bot is in, with member counts.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn guilds(ctx: Context<'_>) -> Result<(), Error> {
+    let guilds = cached_guilds(ctx);
+    let total_members: u64 = guilds.iter().map(|guild| guild.members).sum();
+    let title = format!(
+        "In {} servers ({} members in total)",
+        guilds.len(),
+        total_members
+    );
+
+    let pages: Vec<serenity::CreateEmbed> = guilds
+        .chunks(GUILDS_PER_PAGE)
+        .map(|chunk| {
+            let lines: Vec<String> = chunk.iter().map(guild_line).collect();
+            serenity::CreateEmbed::new()
+                .title(title.clone())
+                .description(lines.join("\n"))
+                .color(serenity::Color::BLURPLE)
+        })
+        .collect();
+
+    if pages.is_empty() {
+        ctx.say("The bot isn't in any servers.").await?;
+        return Ok(());
+    }
+    Paginator::new(0).run_pages(ctx, pages).await
+}
+
+/// Make the bot leave a server.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn leave(
+    ctx: Context<'_>,
+    #[description = "ID of the server to leave"] guild_id: String,
+) -> Result<(), Error> {
+    let Some(guild_id) = guild_id
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|&id| id != 0)
+        .map(serenity::GuildId::new)
+    else {
+        ctx.say("❌ That isn't a valid server ID.").await?;
+        return Ok(());
+    };
+
+    let name = ctx
+        .serenity_context()
+        .cache
+        .guild(guild_id)
+        .map(|guild| guild.name.clone());
+    let Some(name) = name else {
+        ctx.say(format!(
+            "❌ The bot isn't in a server with ID `{guild_id}`."
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    guild_id.leave(ctx.http()).await?;
+    tracing::info!(
+        user_id = ctx.author().id.get(),
+        guild_id = guild_id.get(),
+        guild = %name,
+        "Left guild on owner request"
+    );
+    ctx.say(format!("👋 Left **{name}** (`{guild_id}`)."))
+        .await?;
+    Ok(())
+}
+
+/// Post a message in the system channel of every server.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn broadcast(
+    ctx: Context<'_>,
+    #[description = "Message to post"]
+    #[rest]
+    message: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    // Collect the channels first so no cache reference is held across awaits
+    let cache = &ctx.serenity_context().cache;
+    let mut channels = Vec::new();
+    let mut without_channel = 0;
+    for guild_id in cache.guilds() {
+        match cache
+            .guild(guild_id)
+            .and_then(|guild| guild.system_channel_id)
+        {
+            Some(channel_id) => channels.push((guild_id, channel_id)),
+            None => without_channel += 1,
+        }
+    }
+
+    // Mentions in the message stay plain text, so `@everyone` can't ping
+    // every member of every server
+    let post = serenity::CreateMessage::new()
+        .content(&message)
+        .allowed_mentions(serenity::CreateAllowedMentions::new());
+    let mut sent = 0;
+    let mut failed = 0;
+    for (guild_id, channel_id) in channels {
+        match channel_id.send_message(ctx.http(), post.clone()).await {
+            Ok(_) => sent += 1,
+            Err(e) => {
+                failed += 1;
+                tracing::warn!(
+                    guild_id = guild_id.get(),
+                    channel_id = channel_id.get(),
+                    error = %e,
+                    "Failed to broadcast to guild"
+                );
+            }
+        }
+    }
+
+    tracing::info!(
+        user_id = ctx.author().id.get(),
+        sent,
+        failed,
+        without_channel,
+        "Broadcast message to guilds"
+    );
+    ctx.say(format!(
+        "📣 Sent to {sent} servers. Failed: {failed}. Without a system channel: {without_channel}."
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Read feedback sent with `/feedback`.
+#[poise::command(slash_command, prefix_command, owners_only, subcommands("recent"))]
+pub async fn feedback(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use `/admin feedback recent [count]` to read the latest feedback.")
+        .await?;
+    Ok(())
+}
+
+/// One feedback entry in the `/admin feedback recent` list.
+fn feedback_entry(feedback: &Feedback) -> String {
+    let source = match feedback.guild_id {
+        Some(guild_id) => format!("server `{guild_id}`"),
+        None => "a DM".to_string(),
+    };
+    let quoted: Vec<String> = feedback
+        .message
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect();
+    format!(
+        "<@{}> in {} - <t:{}:R>\n{}",
+        feedback.user_id,
+        source,
+        feedback.created_at.timestamp(),
+        quoted.join("\n")
+    )
+}
+
+/// Show the latest feedback, newest first.
+#[poise::command(slash_command, prefix_command, owners_only)]
+pub async fn recent(
+    ctx: Context<'_>,
+    #[description = "How many entries to show (default 20)"]
+    #[min = 1]
+    #[max = 50]
+    count: Option<usize>,
+) -> Result<(), Error> {
+    let entries = ctx.data().feedback.recent(count.unwrap_or(20)).await?;
+    if entries.is_empty() {
+        ctx.say("No feedback yet.").await?;
+        return Ok(());
+    }
+
+    let title = format!("Latest feedback ({} entries)", entries.len());
+    let pages: Vec<serenity::CreateEmbed> = entries
+        .chunks(FEEDBACK_PER_PAGE)
+        .map(|chunk| {
+            let lines: Vec<String> = chunk.iter().map(feedback_entry).collect();
+            serenity::CreateEmbed::new()
+                .title(title.clone())
+                .description(lines.join("\n\n"))
+                .color(serenity::Color::BLURPLE)
+        })
+        .collect();
+    Paginator::new(0).run_pages(ctx, pages).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feedback_entry_quotes_every_line() {
+        let feedback = Feedback {
+            guild_id: Some(42),
+            user_id: 7,
+            message: "Great bot!\nPlease add birthdays.".to_string(),
+            created_at: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        };
+        assert_eq!(
+            feedback_entry(&feedback),
+            "<@7> in server `42` - <t:1700000000:R>\n> Great bot!\n> Please add birthdays."
+        );
+
+        let from_dm = Feedback {
+            guild_id: None,
+            ..feedback
+        };
+        assert!(feedback_entry(&from_dm).starts_with("<@7> in a DM - "));
+    }
+
+    #[test]
+    fn test_guilds_list_largest_first() {
+        let mut guilds = vec![
+            GuildSummary {
+                id: 1,
+                name: "Small".to_string(),
+                members: 1,
+            },
+            GuildSummary {
+                id: 2,
+                name: "Big".to_string(),
+                members: 500,
+            },
+            GuildSummary {
+                id: 3,
+                name: "Also big".to_string(),
+                members: 500,
+            },
+        ];
+        sort_guilds(&mut guilds);
+
+        let lines: Vec<String> = guilds.iter().map(guild_line).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "**Also big** (`3`) - 500 members",
+                "**Big** (`2`) - 500 members",
+                "**Small** (`1`) - 1 member",
+            ]
+        );
+    }
+}
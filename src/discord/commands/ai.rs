@@ -0,0 +1,341 @@
+// Focused AI commands.
+//
+// Unlike mentioning the bot (open-ended chat with the studio persona), each of
+// these builds its own prompt for one job and posts the result as an embed:
+// - `/summarize` - recap the last N messages in the channel
+// - `/tldr` - short summary of a web page (via Gemini URL Context)
+// - `/translate` - translate a piece of text
+//
+// `/ai forgetme` / `/ai rememberme` manage the per-user opt-out from AI context,
+// and `/ai responses` caps how long AI replies can get in a guild.
+
+use crate::core::ai::response_limits::MAX_RESPONSE_MESSAGES;
+use crate::core::ai::{
+    AiBusy, AiMessage, CircuitOpen, EmptyResponse, LongResponseMode, ResponseLimits,
+};
+use crate::core::util::extract_urls;
+use crate::discord::commands::leveling::{Context, Error};
+use crate::infra::http::RequestTimeout;
+use poise::serenity_prelude as serenity;
+
+const SUMMARIZE_PROMPT: &str = "You summarize Discord conversations. \
+    Write a concise recap of the transcript you are given: the main topics, any decisions \
+    or questions left open, and who said what when it matters. Use short bullet points, \
+    at most 8. Do not invent anything that is not in the transcript.";
+
+const TLDR_PROMPT: &str = "You write TL;DRs of web pages. Read the page at the given URL \
+    and reply with a one-sentence summary followed by up to 5 bullet points with the key \
+    facts. If you cannot access the page, say so instead of guessing.";
+
+const TRANSLATE_PROMPT: &str = "You are a translator. Translate the user's text into the \
+    requested language, keeping the tone, formatting, emoji and Discord mentions intact. \
+    Reply with the translation only.";
+
+/// Reply for AI commands when no AI provider is configured.
+pub const AI_NOT_CONFIGURED: &str = "AI is not configured on this bot.";
+
+/// What to tell the user when an AI request fails. While the provider is
+/// failing fast (see `CircuitOpen`), the bot is at its request limit (see
+/// `AiBusy`), or the provider times out or answers with nothing, the reply
+/// says so instead of a generic error.
+pub fn ai_error_reply(error: &(dyn std::error::Error + Send + Sync + 'static)) -> String {
+    if let Some(open) = error.downcast_ref::<CircuitOpen>() {
+        return format!(
+            "The AI is temporarily unavailable. Please try again in {} seconds.",
+            open.retry_after.as_secs().max(1)
+        );
+    }
+    if error.downcast_ref::<AiBusy>().is_some() {
+        return "I'm busy answering other messages right now, try again in a moment.".to_string();
+    }
+    if error.downcast_ref::<RequestTimeout>().is_some() {
+        return "The AI took too long to answer. Please try again.".to_string();
+    }
+    if error.downcast_ref::<EmptyResponse>().is_some() {
+        return "I couldn't generate a response, try again.".to_string();
+    }
+    "Sorry, I encountered an error processing your request.".to_string()
+}
+
+/// Embed descriptions are capped at 4096 characters.
+const MAX_RESULT_CHARS: usize = 4000;
+
+/// Default and maximum number of messages `/summarize` reads.
+const DEFAULT_SUMMARY_COUNT: u8 = 30;
+const MAX_SUMMARY_COUNT: u8 = 100;
+
+/// Manage how the AI assistant uses your messages.
+#[poise::command(
+    slash_command,
+    subcommands("forgetme", "rememberme", "responses"),
+    category = "AI Assistant"
+)]
+pub async fn ai(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Stop the AI from reading your messages as context or replying to your mentions.
+#[poise::command(slash_command)]
+pub async fn forgetme(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.data()
+        .ai_privacy
+        .set_opted_out(ctx.author().id.get(), true)
+        .await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(
+                "🔒 Done. Your messages will no longer be sent to the AI as context, \
+                and the bot won't answer your mentions. Use `/ai rememberme` to undo this.",
+            )
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Let the AI use your messages as context again.
+#[poise::command(slash_command)]
+pub async fn rememberme(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.data()
+        .ai_privacy
+        .set_opted_out(ctx.author().id.get(), false)
+        .await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content("🔓 You're opted back in. The AI can read your messages for context again.")
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum LongResponseChoice {
+    #[name = "Truncate with a notice"]
+    Truncate,
+    #[name = "Attach as response.txt"]
+    Attach,
+}
+
+impl From<LongResponseChoice> for LongResponseMode {
+    fn from(choice: LongResponseChoice) -> Self {
+        match choice {
+            LongResponseChoice::Truncate => LongResponseMode::Truncate,
+            LongResponseChoice::Attach => LongResponseMode::Attach,
+        }
+    }
+}
+
+/// Limit how many messages one AI reply may use in this server.
+///
+/// Run without options to see the current settings.
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn responses(
+    ctx: Context<'_>,
+    #[description = "Maximum messages per reply (1-10, default 3)"]
+    #[min = 1]
+    #[max = 10]
+    max_messages: Option<u8>,
+    #[description = "What to do with replies that are longer"] mode: Option<LongResponseChoice>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+    let limits_service = &ctx.data().response_limits;
+    let current = limits_service.get(guild_id).await;
+
+    let limits = if max_messages.is_none() && mode.is_none() {
+        current
+    } else {
+        let limits = ResponseLimits {
+            max_messages: max_messages
+                .unwrap_or(current.max_messages)
+                .clamp(1, MAX_RESPONSE_MESSAGES),
+            mode: mode.map(Into::into).unwrap_or(current.mode),
+        };
+        limits_service.set(guild_id, limits).await?;
+        limits
+    };
+
+    let overflow = match limits.mode {
+        LongResponseMode::Truncate => "truncated with a notice",
+        LongResponseMode::Attach => "attached as `response.txt`",
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "🤖 AI replies use at most **{}** message(s); longer replies are {}.",
+                limits.max_messages, overflow
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Summarize the recent conversation in this channel.
+#[poise::command(slash_command, guild_only, category = "AI Assistant")]
+pub async fn summarize(
+    ctx: Context<'_>,
+    #[description = "How many recent messages to read (default 30, max 100)"]
+    #[min = 5]
+    #[max = 100]
+    count: Option<u8>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let count = count
+        .unwrap_or(DEFAULT_SUMMARY_COUNT)
+        .min(MAX_SUMMARY_COUNT);
+    let messages = ctx
+        .channel_id()
+        .messages(ctx.http(), serenity::GetMessages::new().limit(count))
+        .await?;
+
+    // Opted-out users are never sent to the AI
+    let messages = ctx
+        .data()
+        .ai_privacy
+        .retain_allowed(messages, |msg| msg.author.id.get());
+
+    // Discord returns newest first
+    let transcript = build_transcript(messages.iter().rev());
+    if transcript.is_empty() {
+        ctx.say("There's nothing to summarize in this channel yet.")
+            .await?;
+        return Ok(());
+    }
+
+    let prompt = vec![AiMessage::user(format!(
+        "Summarize this conversation:\n\n{}",
+        transcript
+    ))];
+
+    run_task(
+        ctx,
+        "📝 Conversation Summary",
+        format!("Last {} messages", messages.len()),
+        SUMMARIZE_PROMPT,
+        &prompt,
+        &[],
+    )
+    .await
+}
+
+/// Get a short summary of a web page.
+#[poise::command(slash_command, category = "AI Assistant")]
+pub async fn tldr(
+    ctx: Context<'_>,
+    #[description = "Link to the page to summarize"] url: String,
+) -> Result<(), Error> {
+    let Some(url) = extract_urls(&url).into_iter().next() else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Please provide a link starting with `http://` or `https://`.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    ctx.defer().await?;
+
+    let prompt = vec![AiMessage::user(format!("Write a TL;DR of {}", url))];
+    run_task(
+        ctx,
+        "🔗 TL;DR",
+        url.clone(),
+        TLDR_PROMPT,
+        &prompt,
+        std::slice::from_ref(&url),
+    )
+    .await
+}
+
+/// Translate text into another language.
+#[poise::command(slash_command, category = "AI Assistant")]
+pub async fn translate(
+    ctx: Context<'_>,
+    #[description = "Text to translate"] text: String,
+    #[description = "Language to translate into (e.g. Spanish, German, ja)"] to: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let prompt = vec![AiMessage::user(format!(
+        "Translate into {}:\n\n{}",
+        to.trim(),
+        text
+    ))];
+    run_task(
+        ctx,
+        "🌐 Translation",
+        format!("Translated to {}", to.trim()),
+        TRANSLATE_PROMPT,
+        &prompt,
+        &[],
+    )
+    .await
+}
+
+/// Send the task to the AI and post the answer (or a friendly error).
+async fn run_task(
+    ctx: Context<'_>,
+    title: &str,
+    footer: String,
+    system_prompt: &str,
+    messages: &[AiMessage],
+    urls: &[String],
+) -> Result<(), Error> {
+    let Some(ai) = ctx.data().ai.as_deref() else {
+        ctx.say(AI_NOT_CONFIGURED).await?;
+        return Ok(());
+    };
+
+    let response = match ai.complete_task(system_prompt, messages, urls).await {
+        Ok(response) => response,
+        Err(e) => {
+            if e.downcast_ref::<CircuitOpen>().is_none() && e.downcast_ref::<AiBusy>().is_none() {
+                tracing::error!("AI task '{}' failed: {}", ctx.command().name, e);
+            }
+            ctx.say(ai_error_reply(e.as_ref())).await?;
+            return Ok(());
+        }
+    };
+
+    let answer = crate::core::util::truncate_chars(response.answer.trim(), MAX_RESULT_CHARS);
+
+    let embed = serenity::CreateEmbed::new()
+        .title(title)
+        .description(answer)
+        .color(0x5865F2)
+        .footer(serenity::CreateEmbedFooter::new(footer));
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Format messages (oldest first) as a plain-text transcript for the model.
+/// Messages without text (embeds, stickers, attachments only) are skipped.
+fn build_transcript<'a>(messages: impl Iterator<Item = &'a serenity::Message>) -> String {
+    messages
+        .filter(|msg| !msg.content.trim().is_empty())
+        .map(|msg| {
+            format!(
+                "[{}] {}: {}",
+                msg.timestamp.format("%H:%M"),
+                msg.author.name,
+                msg.content.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,97 @@
+// `/birthday` lets members store their birthday in a server, month and day
+// only. Admins choose where birthdays are announced with `/config birthdays`.
+
+use crate::core::birthdays::Birthday;
+use crate::discord::commands::leveling::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// Your birthday in this server.
+#[poise::command(slash_command, guild_only, subcommands("set", "remove", "show"))]
+pub async fn birthday(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set your birthday. No year needed.
+#[poise::command(slash_command, guild_only)]
+pub async fn set(
+    ctx: Context<'_>,
+    #[description = "Your birthday as MM-DD, e.g. 03-14"] date: String,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let birthday = match Birthday::parse(&date) {
+        Ok(birthday) => birthday,
+        Err(reason) => return send_ephemeral(ctx, format!("❌ {}", reason)).await,
+    };
+    ctx.data()
+        .birthdays
+        .set_birthday(guild_id, ctx.author().id.get(), birthday)
+        .await?;
+
+    let mut content = format!("🎂 Saved your birthday: **{}**.", birthday);
+    if (birthday.month(), birthday.day()) == (2, 29) {
+        content.push_str(" In years without a Feb 29 it's celebrated on March 1.");
+    }
+    send_ephemeral(ctx, content).await
+}
+
+/// Forget your birthday.
+#[poise::command(slash_command, guild_only)]
+pub async fn remove(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let content = if ctx
+        .data()
+        .birthdays
+        .remove_birthday(guild_id, ctx.author().id.get())
+        .await?
+    {
+        "🗑️ Your birthday is forgotten."
+    } else {
+        "You haven't set a birthday."
+    };
+    send_ephemeral(ctx, content.to_string()).await
+}
+
+/// Show your birthday or another member's.
+#[poise::command(slash_command, guild_only)]
+pub async fn show(
+    ctx: Context<'_>,
+    #[description = "Member to look up (default: you)"] user: Option<serenity::User>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+    let user = user.as_ref().unwrap_or_else(|| ctx.author());
+
+    let birthday = ctx
+        .data()
+        .birthdays
+        .birthday(guild_id, user.id.get())
+        .await?;
+    let content = match (birthday, user.id == ctx.author().id) {
+        (Some(birthday), true) => format!("🎂 Your birthday is **{}**.", birthday),
+        (Some(birthday), false) => format!("🎂 {}'s birthday is **{}**.", user, birthday),
+        (None, true) => "You haven't set a birthday. Use `/birthday set`.".to_string(),
+        (None, false) => format!("{} hasn't set a birthday.", user),
+    };
+    send_ephemeral(ctx, content).await
+}
+
+async fn send_ephemeral(ctx: Context<'_>, content: String) -> Result<(), Error> {
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
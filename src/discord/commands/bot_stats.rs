@@ -0,0 +1,43 @@
+use crate::core::metrics::{format_uptime, parse_resident_memory};
+use crate::discord::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// Show bot vitals: uptime, servers, users, messages seen and memory.
+#[poise::command(slash_command, prefix_command)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    let metrics = &ctx.data().metrics;
+    let cache = &ctx.serenity_context().cache;
+
+    let memory = resident_memory()
+        .map(|bytes| format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)))
+        .unwrap_or_else(|| "Unavailable".to_string());
+
+    let embed = serenity::CreateEmbed::new()
+        .title("Bot Stats")
+        .color(serenity::Color::BLURPLE)
+        .field("Uptime", format_uptime(metrics.uptime()), true)
+        .field("Servers", cache.guild_count().to_string(), true)
+        .field("Cached Users", cache.user_count().to_string(), true)
+        .field(
+            "Messages Processed",
+            metrics.messages_processed().to_string(),
+            true,
+        )
+        .field("Memory", memory, true)
+        .field("Version", env!("CARGO_PKG_VERSION"), true)
+        .footer(serenity::CreateEmbedFooter::new(
+            "Counts cover this session only",
+        ))
+        .timestamp(serenity::Timestamp::now());
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Resident memory of the bot process. Only available on Linux, where it's
+/// read from `/proc`.
+fn resident_memory() -> Option<u64> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| parse_resident_memory(&status))
+}
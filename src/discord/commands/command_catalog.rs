@@ -1,6 +1,10 @@
 // Discord commands module.
 // Each feature gets its own command file.
 
+#[cfg(feature = "ai")]
+pub mod ai;
+
+#[cfg(feature = "economy")]
 pub mod economy;
 
 pub mod leveling;
@@ -10,10 +14,12 @@ pub mod server_stats;
 // Add a new module for bot presence management
 pub mod presence;
 
+#[cfg(feature = "economy")]
 pub mod shop;
 
 pub mod timezones;
 
+#[cfg(feature = "github")]
 pub mod github;
 
 pub mod info;
@@ -21,3 +27,18 @@ pub mod info;
 pub mod help;
 
 pub mod remind;
+
+pub mod config;
+
+pub mod reaction_roles;
+
+pub mod bot_stats;
+
+pub mod admin;
+
+pub mod feedback;
+
+pub mod birthday;
+
+#[cfg(feature = "ai")]
+pub mod reload;
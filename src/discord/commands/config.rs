@@ -0,0 +1,537 @@
+// Server configuration commands.
+//
+// `/config managers` manages the roles that may run admin commands without
+// being full administrators (see `discord::access::is_manager`). Only
+// administrators can change the list.
+//
+// `/config autorole` sets the role given to members when they join (see
+// `discord::auto_role`).
+//
+// `/config welcome` and `/config goodbye` set the messages posted when
+// members join and leave (see `discord::welcome`).
+//
+// `/config birthdays` picks the channel (and optional role) for birthday
+// announcements (see `discord::birthdays`).
+
+use crate::core::welcome::{validate_goodbye_template, validate_welcome_template};
+use crate::discord::commands::leveling::{Context, Error};
+use crate::discord::util::roles::assignability_problem;
+use poise::serenity_prelude as serenity;
+
+/// Configure the bot for this server.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands("managers", "autorole", "welcome", "goodbye", "birthdays")
+)]
+pub async fn config(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Roles that may run admin commands.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands("add", "remove", "list")
+)]
+pub async fn managers(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Let members with this role run admin commands.
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+pub async fn add(
+    ctx: Context<'_>,
+    #[description = "Role to grant admin commands"] role: serenity::Role,
+) -> Result<(), Error> {
+    set_manager_role(ctx, role, true).await
+}
+
+/// Stop members with this role from running admin commands.
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+pub async fn remove(
+    ctx: Context<'_>,
+    #[description = "Role to remove"] role: serenity::Role,
+) -> Result<(), Error> {
+    set_manager_role(ctx, role, false).await
+}
+
+/// List the manager roles.
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let mut roles: Vec<u64> = ctx
+        .data()
+        .manager_roles
+        .manager_roles(guild_id)
+        .await?
+        .into_iter()
+        .collect();
+    roles.sort_unstable();
+
+    let content = if roles.is_empty() {
+        "No manager roles yet. Only administrators can run admin commands.".to_string()
+    } else {
+        let mentions: Vec<String> = roles.iter().map(|id| format!("<@&{}>", id)).collect();
+        format!("🛡️ Manager roles: {}", mentions.join(", "))
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn set_manager_role(
+    ctx: Context<'_>,
+    role: serenity::Role,
+    is_manager: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let changed = ctx
+        .data()
+        .manager_roles
+        .set_manager_role(guild_id, role.id.get(), is_manager)
+        .await?;
+
+    let content = match (is_manager, changed) {
+        (true, true) => format!("✅ Members with {} can now run admin commands.", role),
+        (true, false) => format!("{} is already a manager role.", role),
+        (false, true) => format!("✅ {} is no longer a manager role.", role),
+        (false, false) => format!("{} isn't a manager role.", role),
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Role given to members when they join.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands("set", "toggle", "status")
+)]
+pub async fn autorole(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Give this role to every member who joins (switches the auto-role on).
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+pub async fn set(
+    ctx: Context<'_>,
+    #[description = "Role for new members"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    ctx.data()
+        .auto_roles
+        .set_role(guild_id, role.id.get())
+        .await?;
+
+    let mut content = format!("✅ New members will get {}.", role);
+    if let Some(warning) = assignability_problem(ctx, role.id.get()).await {
+        content.push_str(&format!("\n⚠️ {}", warning));
+    }
+    send_ephemeral(ctx, content).await
+}
+
+/// Switch the auto-role on or off without forgetting the role.
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+pub async fn toggle(
+    ctx: Context<'_>,
+    #[description = "Give the auto-role to new members"] enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let content = match ctx.data().auto_roles.set_enabled(guild_id, enabled).await? {
+        None => "No auto-role set yet. Use `/config autorole set` first.".to_string(),
+        Some(config) if config.enabled => {
+            format!("✅ Auto-role <@&{}> is on.", config.role_id)
+        }
+        Some(config) => format!("⏸️ Auto-role <@&{}> is off.", config.role_id),
+    };
+    send_ephemeral(ctx, content).await
+}
+
+/// Show the auto-role and whether the bot can assign it.
+#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let content = match ctx.data().auto_roles.auto_role(guild_id).await? {
+        None => "No auto-role set. Use `/config autorole set @role`.".to_string(),
+        Some(config) => {
+            let state = if config.enabled { "on" } else { "off" };
+            let mut content = format!("Auto-role: <@&{}> ({}).", config.role_id, state);
+            if let Some(warning) = assignability_problem(ctx, config.role_id).await {
+                content.push_str(&format!("\n⚠️ {}", warning));
+            }
+            content
+        }
+    };
+    send_ephemeral(ctx, content).await
+}
+
+/// Message posted when members join.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands("welcome_set", "welcome_test", "welcome_disable")
+)]
+pub async fn welcome(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Post a welcome message when members join. Placeholders: {user}, {member_count}, {guild}.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "set"
+)]
+pub async fn welcome_set(
+    ctx: Context<'_>,
+    #[description = "Channel for welcome messages"] channel: serenity::GuildChannel,
+    #[description = "Message, e.g. \"Welcome {user} to {guild}! You are member #{member_count}.\""]
+    template: String,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let template = match validate_welcome_template(&template) {
+        Ok(template) => template,
+        Err(reason) => return send_ephemeral(ctx, format!("❌ {}", reason)).await,
+    };
+
+    ctx.data()
+        .welcome
+        .set_welcome(guild_id, channel.id.get(), template)
+        .await?;
+
+    send_ephemeral(
+        ctx,
+        format!(
+            "✅ New members will be welcomed in <#{}>. Use `/config welcome test` to preview it.",
+            channel.id
+        ),
+    )
+    .await
+}
+
+/// Post the welcome message for yourself to check how it looks.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "test"
+)]
+pub async fn welcome_test(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let Some(config) = ctx.data().welcome.welcome(guild_id).await? else {
+        return send_ephemeral(
+            ctx,
+            "No welcome message set. Use `/config welcome set` first.".to_string(),
+        )
+        .await;
+    };
+    let member = ctx
+        .author_member()
+        .await
+        .ok_or("Couldn't load your member")?;
+
+    let content =
+        match crate::discord::welcome::post_welcome(ctx.serenity_context(), &config, &member).await
+        {
+            Ok(message) => format!("✅ Posted a test welcome: {}", message.link()),
+            Err(e) => format!(
+                "❌ Couldn't post in <#{}>: {}. Check the bot can send messages and embeds there.",
+                config.channel_id, e
+            ),
+        };
+    send_ephemeral(ctx, content).await
+}
+
+/// Stop posting welcome messages.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "disable"
+)]
+pub async fn welcome_disable(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let content = if ctx.data().welcome.disable(guild_id).await? {
+        "⏸️ Welcome messages are off."
+    } else {
+        "Welcome messages were already off."
+    };
+    send_ephemeral(ctx, content.to_string()).await
+}
+
+/// Message posted when members leave.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands(
+        "goodbye_set",
+        "goodbye_test",
+        "goodbye_kicks_and_bans",
+        "goodbye_disable"
+    )
+)]
+pub async fn goodbye(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Post a goodbye when members leave. Placeholders: {user}, {member_count}, {guild}, {duration}.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "set"
+)]
+pub async fn goodbye_set(
+    ctx: Context<'_>,
+    #[description = "Channel for goodbye messages"] channel: serenity::GuildChannel,
+    #[description = "Message, e.g. \"{user} left after {duration}. We're now {member_count}.\""]
+    template: String,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let template = match validate_goodbye_template(&template) {
+        Ok(template) => template,
+        Err(reason) => return send_ephemeral(ctx, format!("❌ {}", reason)).await,
+    };
+
+    ctx.data()
+        .goodbye
+        .set_goodbye(guild_id, channel.id.get(), template)
+        .await?;
+
+    send_ephemeral(
+        ctx,
+        format!(
+            "✅ Departures will be announced in <#{}>. Use `/config goodbye test` to preview it.",
+            channel.id
+        ),
+    )
+    .await
+}
+
+/// Post the goodbye message for yourself to check how it looks.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "test"
+)]
+pub async fn goodbye_test(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("This command only works in servers")?;
+
+    let Some(config) = ctx.data().goodbye.goodbye(guild_id.get()).await? else {
+        return send_ephemeral(
+            ctx,
+            "No goodbye message set. Use `/config goodbye set` first.".to_string(),
+        )
+        .await;
+    };
+    let joined_at = ctx
+        .author_member()
+        .await
+        .and_then(|member| member.joined_at);
+
+    let content = match crate::discord::welcome::post_goodbye(
+        ctx.serenity_context(),
+        &config,
+        guild_id,
+        ctx.author(),
+        joined_at,
+    )
+    .await
+    {
+        Ok(message) => format!("✅ Posted a test goodbye: {}", message.link()),
+        Err(e) => format!(
+            "❌ Couldn't post in <#{}>: {}. Check the bot can send messages and embeds there.",
+            config.channel_id, e
+        ),
+    };
+    send_ephemeral(ctx, content).await
+}
+
+/// Choose whether kicked and banned members get a goodbye message.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "kicks_and_bans"
+)]
+pub async fn goodbye_kicks_and_bans(
+    ctx: Context<'_>,
+    #[description = "Announce members who were kicked or banned"] announce: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let content = match ctx
+        .data()
+        .goodbye
+        .set_skip_kicks_and_bans(guild_id, !announce)
+        .await?
+    {
+        None => "No goodbye message set yet. Use `/config goodbye set` first.".to_string(),
+        Some(config) if config.skip_kicks_and_bans => {
+            "✅ Kicked and banned members won't get a goodbye message. The bot needs the View Audit Log permission to tell them apart.".to_string()
+        }
+        Some(_) => "✅ Kicked and banned members get a goodbye message too.".to_string(),
+    };
+    send_ephemeral(ctx, content).await
+}
+
+/// Stop posting goodbye messages.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "disable"
+)]
+pub async fn goodbye_disable(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let content = if ctx.data().goodbye.disable(guild_id).await? {
+        "⏸️ Goodbye messages are off."
+    } else {
+        "Goodbye messages were already off."
+    };
+    send_ephemeral(ctx, content.to_string()).await
+}
+
+/// Birthday announcements.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands("birthdays_set", "birthdays_disable")
+)]
+pub async fn birthdays(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Announce members' birthdays at midnight, optionally giving them a role for the day.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "set"
+)]
+pub async fn birthdays_set(
+    ctx: Context<'_>,
+    #[description = "Channel for birthday announcements"] channel: serenity::GuildChannel,
+    #[description = "Role members get on their birthday"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let role_id = role.as_ref().map(|role| role.id.get());
+    ctx.data()
+        .birthdays
+        .enable(guild_id, channel.id.get(), role_id)
+        .await?;
+    let timezone = ctx.data().leveling.daily_timezone(guild_id).await?;
+
+    let mut content = format!(
+        "✅ Birthdays will be announced in <#{}> at midnight **{}** (change it with `/leveling daily_reset`).",
+        channel.id,
+        timezone.name()
+    );
+    if let Some(role) = &role {
+        content.push_str(&format!(" Members get {} for the day.", role));
+        if let Some(warning) = assignability_problem(ctx, role.id.get()).await {
+            content.push_str(&format!("\n⚠️ {}", warning));
+        }
+    }
+    send_ephemeral(ctx, content).await
+}
+
+/// Stop announcing birthdays.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "disable"
+)]
+pub async fn birthdays_disable(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let content = if ctx.data().birthdays.disable(guild_id).await? {
+        "⏸️ Birthday announcements are off."
+    } else {
+        "Birthday announcements were already off."
+    };
+    send_ephemeral(ctx, content.to_string()).await
+}
+
+async fn send_ephemeral(ctx: Context<'_>, content: String) -> Result<(), Error> {
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
@@ -5,6 +5,14 @@
 // 2. Call core service
 // 3. Format the response
 
+use crate::core::economy::{
+    wealth_ranks, CoinSide, EconomyError, Transaction, Wallet, WealthRanking,
+};
+use crate::core::leveling::StreakStatus;
+use crate::discord::access::has_manager_access;
+use crate::discord::commands::leveling::{is_bot_cached, rank_medal, resolve_display_name_cached};
+use crate::discord::i18n::tr;
+use crate::discord::util::Paginator;
 use poise::serenity_prelude as serenity;
 
 // Re-use the same type aliases from leveling commands
@@ -12,7 +20,7 @@ pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Context<'a> = poise::Context<'a, crate::discord::commands::leveling::Data, Error>;
 
 /// Check your GreyCoins balance
-#[poise::command(slash_command, guild_only)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn balance(
     ctx: Context<'_>,
     #[description = "User to check balance for (defaults to you)"] user: Option<serenity::User>,
@@ -77,77 +85,371 @@ pub async fn balance(
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum RichestCategory {
+    #[name = "Balance"]
+    Balance,
+    #[name = "Total earned"]
+    TotalEarned,
+}
+
+impl From<RichestCategory> for WealthRanking {
+    fn from(category: RichestCategory) -> Self {
+        match category {
+            RichestCategory::Balance => WealthRanking::Balance,
+            RichestCategory::TotalEarned => WealthRanking::TotalEarned,
+        }
+    }
+}
+
+/// How many wallets `/richest` ranks.
+const RICHEST_LIMIT: usize = 100;
+/// Wallets per `/richest` page.
+const RICHEST_PER_PAGE: usize = 10;
+
+/// See who has the most GreyCoins
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn richest(
+    ctx: Context<'_>,
+    #[description = "What to rank by (default: balance)"] by: Option<RichestCategory>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+    let ranking = WealthRanking::from(by.unwrap_or(RichestCategory::Balance));
+
+    // Drop bots before ranking so they don't leave gaps in the ranks
+    let wallets: Vec<Wallet> = ctx
+        .data()
+        .economy
+        .get_richest(guild_id, ranking, RICHEST_LIMIT)
+        .await?
+        .into_iter()
+        .filter(|wallet| !is_bot_cached(&ctx, guild_id, wallet.user_id))
+        .collect();
+
+    if wallets.is_empty() {
+        ctx.say("No one has any GreyCoins yet! Use `/daily` to get started. 🪙")
+            .await?;
+        return Ok(());
+    }
+
+    let ranks = wealth_ranks(&wallets, ranking);
+    let author_id = ctx.author().id.get();
+    let my_page = wallets
+        .iter()
+        .position(|wallet| wallet.user_id == author_id)
+        .map(|index| index / RICHEST_PER_PAGE + 1);
+
+    let title = match ranking {
+        WealthRanking::Balance => "💰 Richest Members",
+        WealthRanking::TotalEarned => "💰 Top Earners",
+    };
+    let pages = wallets
+        .chunks(RICHEST_PER_PAGE)
+        .zip(ranks.chunks(RICHEST_PER_PAGE))
+        .map(|(chunk, chunk_ranks)| {
+            let lines = chunk
+                .iter()
+                .zip(chunk_ranks)
+                .map(|(wallet, &rank)| {
+                    let name = resolve_display_name_cached(&ctx, guild_id, wallet.user_id);
+                    let name = if wallet.user_id == author_id {
+                        format!("**{}** (You)", name)
+                    } else {
+                        name
+                    };
+                    format!(
+                        "{} **#{}** {} — 🪙 {}",
+                        rank_medal(rank),
+                        rank,
+                        name,
+                        format_number(ranking.value(wallet))
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            serenity::CreateEmbed::new()
+                .title(title)
+                .description(lines)
+                .color(0xFFD700) // Gold color
+        })
+        .collect();
+
+    Paginator::new(0)
+        .jump_button("🔍 Find Me", my_page, "You don't have any GreyCoins yet!")
+        .run_pages(ctx, pages)
+        .await
+}
+
+/// How many transactions `/transactions` looks back over.
+const TRANSACTION_HISTORY_LIMIT: usize = 100;
+/// Transactions per `/transactions` page.
+const TRANSACTIONS_PER_PAGE: usize = 10;
+
+/// Show recent GreyCoin transactions
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn transactions(
+    ctx: Context<'_>,
+    #[description = "User to show transactions for (admins only, defaults to you)"] user: Option<
+        serenity::User,
+    >,
+) -> Result<(), Error> {
+    let target_user = user.as_ref().unwrap_or_else(|| ctx.author());
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    if target_user.id != ctx.author().id && !has_manager_access(ctx).await {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("🔒 Only admins can view other members' transactions.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if target_user.bot {
+        ctx.say("Bots don't have wallets! 🤖").await?;
+        return Ok(());
+    }
+
+    let transactions = ctx
+        .data()
+        .economy
+        .get_recent_transactions(target_user.id.get(), guild_id, TRANSACTION_HISTORY_LIMIT)
+        .await?;
+
+    if transactions.is_empty() {
+        ctx.say(format!("{} has no transactions yet.", target_user.name))
+            .await?;
+        return Ok(());
+    }
+
+    let pages = transactions
+        .chunks(TRANSACTIONS_PER_PAGE)
+        .map(|chunk| {
+            let lines = chunk
+                .iter()
+                .map(transaction_line)
+                .collect::<Vec<_>>()
+                .join("\n");
+            serenity::CreateEmbed::new()
+                .title(format!("🧾 {}'s Transactions", target_user.name))
+                .color(0xFFD700) // Gold color
+                .thumbnail(target_user.face())
+                .description(lines)
+        })
+        .collect();
+
+    Paginator::new(0).run_pages(ctx, pages).await
+}
+
+/// One transaction line: spending is marked red and bold so it stands out
+/// from earnings.
+fn transaction_line(transaction: &Transaction) -> String {
+    let when = format!("<t:{}:R>", transaction.timestamp.timestamp());
+    if transaction.amount < 0 {
+        format!(
+            "🔴 **{} 🪙** — {} · {}",
+            format_number(transaction.amount),
+            transaction.reason,
+            when
+        )
+    } else {
+        format!(
+            "🟢 +{} 🪙 — {} · {}",
+            format_number(transaction.amount),
+            transaction.reason,
+            when
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum CoinCall {
+    #[name = "Heads"]
+    Heads,
+    #[name = "Tails"]
+    Tails,
+}
+
+impl From<CoinCall> for CoinSide {
+    fn from(call: CoinCall) -> Self {
+        match call {
+            CoinCall::Heads => CoinSide::Heads,
+            CoinCall::Tails => CoinSide::Tails,
+        }
+    }
+}
+
+/// Bet GreyCoins on a coin flip and double them if you call it right
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn coinflip(
+    ctx: Context<'_>,
+    #[description = "How many GreyCoins to bet"]
+    #[min = 1]
+    bet: i64,
+    #[description = "Heads or tails"] call: CoinCall,
+) -> Result<(), Error> {
+    let user = ctx.author();
+    if user.bot {
+        ctx.say("Bots can't gamble! 🤖").await?;
+        return Ok(());
+    }
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let result = match ctx
+        .data()
+        .economy
+        .coinflip(user.id.get(), guild_id, bet, call.into())
+        .await
+    {
+        Ok(result) => result,
+        Err(e @ EconomyError::InvalidBet { .. }) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ {}.", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(EconomyError::InsufficientFunds { available, .. }) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!(
+                        "❌ You only have **{}** GreyCoins to bet.",
+                        format_number(available)
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(EconomyError::OnCooldown { available_at }) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!(
+                        "⏳ The coin is still spinning. Flip again <t:{}:R>.",
+                        available_at.timestamp()
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let side = match result.outcome {
+        CoinSide::Heads => "Heads",
+        CoinSide::Tails => "Tails",
+    };
+    let (title, outcome, color) = if result.won {
+        (
+            "🪙 You won!",
+            // The payout includes the stake they got back
+            format!(
+                "You won **{}** GreyCoins!",
+                format_number(result.payout - bet)
+            ),
+            0x00FF00, // Green
+        )
+    } else {
+        (
+            "🪙 You lost!",
+            format!("You lost **{}** GreyCoins.", format_number(bet)),
+            0xFF0000, // Red
+        )
+    };
+
+    let embed = serenity::CreateEmbed::new()
+        .title(title)
+        .description(format!(
+            "The coin landed on **{}**.\n{}\n\n💰 New balance: **{}** GreyCoins",
+            side,
+            outcome,
+            format_number(result.new_balance)
+        ))
+        .color(color);
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
 /// Claim your daily rewards (XP and GreyCoins)
-#[poise::command(slash_command, guild_only)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn daily(ctx: Context<'_>) -> Result<(), Error> {
     let user = ctx.author();
     if user.bot {
-        ctx.say("Bots don't need daily rewards! 🤖").await?;
+        ctx.say(tr(ctx, "daily.bot", &[])).await?;
         return Ok(());
     }
 
     let user_id = user.id.get();
     let guild_id = ctx
         .guild_id()
-        .ok_or("This command only works in servers")?
+        .ok_or_else(|| tr(ctx, "common.guild_only", &[]))?
         .get();
 
-    // Detect booster status for XP bonus
-    let boosted = ctx
-        .serenity_context()
-        .cache
-        .guild(serenity::GuildId::from(guild_id))
-        .and_then(|g| g.members.get(&serenity::UserId::from(user_id)).cloned())
-        .and_then(|m| m.premium_since)
-        .is_some();
+    // Detect booster status for XP bonus (cache first, HTTP fallback on a cache miss)
+    let boosted = crate::discord::boosters::is_boosting(
+        ctx.serenity_context(),
+        ctx.data(),
+        serenity::GuildId::from(guild_id),
+        serenity::UserId::from(user_id),
+    )
+    .await;
 
     let member_count = ctx.guild().map(|g| g.member_count).unwrap_or(0);
 
-    // Check if streak would be lost and use Daily Streak Saver if available
-    let mut streak_saver_used = false;
-    let profile_before = ctx
+    // Use a Daily Streak Saver if the streak is one day past the grace period
+    let streak_saver = crate::core::economy::ItemId::DailyStreakSaver;
+    let streak_status = ctx
         .data()
         .leveling
-        .get_user_profile(user_id, guild_id)
+        .daily_streak_status(user_id, guild_id)
         .await?;
-
-    // Check if daily was missed (>24hrs) and user has a streak to preserve
-    if let Some(last_daily) = profile_before.last_daily {
-        let now = chrono::Utc::now();
-        let hours_since_last = now.signed_duration_since(last_daily).num_hours();
-
-        if hours_since_last >= 24 && profile_before.daily_streak > 0 {
-            // Check if user has a Daily Streak Saver
-            if ctx
-                .data()
-                .inventory
-                .has_item(
-                    user_id,
-                    guild_id,
-                    &crate::core::economy::ItemId::DailyStreakSaver,
-                )
-                .await?
-            {
-                // Consume the item
-                ctx.data()
-                    .inventory
-                    .consume_item(
-                        user_id,
-                        guild_id,
-                        &crate::core::economy::ItemId::DailyStreakSaver,
-                    )
-                    .await?;
-                streak_saver_used = true;
-            }
-        }
-    }
+    let mut streak_saver_used = streak_status == Some(StreakStatus::NeedsFreeze)
+        && ctx
+            .data()
+            .inventory
+            .consume_item(user_id, guild_id, &streak_saver)
+            .await?;
 
     // Attempt to claim XP daily reward
-    let (xp_award, levelup_opt) = ctx
+    let claim = ctx
         .data()
         .leveling
-        .claim_daily(user_id, guild_id, boosted, member_count)
-        .await?;
+        .claim_daily(user_id, guild_id, boosted, member_count, streak_saver_used)
+        .await;
+
+    // The saver only counts if the claim went through; a failed claim, or one
+    // that lost a race with another claim today, gives it back
+    if streak_saver_used && !matches!(claim, Ok((xp, _)) if xp > 0) {
+        streak_saver_used = false;
+        if let Err(e) = ctx
+            .data()
+            .inventory
+            .add_item(user_id, guild_id, streak_saver)
+            .await
+        {
+            tracing::error!(
+                user_id,
+                guild_id,
+                error = %e,
+                "Failed to give back an unused Daily Streak Saver"
+            );
+        }
+    }
+    let (xp_award, levelup_opt) = claim?;
 
     // Attempt to claim GreyCoins daily reward
     let coin_result = ctx.data().economy.claim_daily(user_id, guild_id).await?;
@@ -169,10 +471,26 @@ pub async fn daily(ctx: Context<'_>) -> Result<(), Error> {
         .get_user_profile(user_id, guild_id)
         .await?;
 
+    let streak_value = tr(
+        ctx,
+        "daily.streak_value",
+        &[("days", profile.daily_streak.to_string())],
+    );
+    let goal_value = tr(
+        ctx,
+        "daily.server_goal_value",
+        &[
+            ("progress", daily_goal.progress.to_string()),
+            ("target", daily_goal.target.to_string()),
+            ("bar", progress_bar),
+        ],
+    );
+
     // Both are on cooldown
     if xp_award == 0 && coin_result.is_none() {
         let now = chrono::Utc::now();
-        let next_xp_claim = profile.last_daily.map(|d| d + chrono::Duration::days(1));
+        // Daily XP resets at the guild's local midnight, not 24h after the last claim
+        let next_xp_claim = Some(ctx.data().leveling.next_daily_reset(guild_id).await?);
         let next_coin_claim = ctx
             .data()
             .economy
@@ -190,7 +508,7 @@ pub async fn daily(ctx: Context<'_>) -> Result<(), Error> {
         let time_str = if let Some(next_time) = next_claim {
             let time_remaining = next_time.signed_duration_since(now);
             if time_remaining.num_seconds() <= 0 {
-                "Ready soon".to_string()
+                tr(ctx, "daily.ready_soon", &[])
             } else if time_remaining.num_minutes() < 60 {
                 format!(
                     "{}m {}s",
@@ -211,25 +529,19 @@ pub async fn daily(ctx: Context<'_>) -> Result<(), Error> {
                 )
             }
         } else {
-            "Unknown".to_string()
+            tr(ctx, "daily.unknown", &[])
         };
 
         let embed = serenity::CreateEmbed::new()
-            .title("⏰ Daily Reward Already Claimed")
-            .description(format!(
-                "You have already claimed your daily reward. Time until next claim: {}",
-                time_str
+            .title(tr(ctx, "daily.already_claimed_title", &[]))
+            .description(tr(
+                ctx,
+                "daily.already_claimed_description",
+                &[("time", time_str)],
             ))
             .color(0xFFA500) // Orange
-            .field("Streak", format!("{} days 🔥", profile.daily_streak), true)
-            .field(
-                "Server Goal",
-                format!(
-                    "{}/{} claims\n{}",
-                    daily_goal.progress, daily_goal.target, progress_bar
-                ),
-                false,
-            );
+            .field(tr(ctx, "daily.streak", &[]), streak_value, true)
+            .field(tr(ctx, "daily.server_goal", &[]), goal_value, false);
 
         ctx.send(poise::CreateReply::default().embed(embed)).await?;
         return Ok(());
@@ -240,52 +552,54 @@ pub async fn daily(ctx: Context<'_>) -> Result<(), Error> {
 
     if xp_award > 0 {
         if let Some(ref level_up) = levelup_opt {
-            description_parts.push(format!(
-                "✨ **+{} XP** — Leveled up to **{}**!",
-                xp_award, level_up.new_level
+            description_parts.push(tr(
+                ctx,
+                "daily.xp_levelup",
+                &[
+                    ("xp", xp_award.to_string()),
+                    ("level", level_up.new_level.to_string()),
+                ],
             ));
         } else {
-            description_parts.push(format!("✨ **+{} XP**", xp_award));
+            description_parts.push(tr(ctx, "daily.xp", &[("xp", xp_award.to_string())]));
         }
     }
 
     if let Some(ref claim) = coin_result {
-        description_parts.push(format!(
-            "🪙 **+{} GreyCoins** (Balance: {})",
-            claim.coins_awarded,
-            format_number(claim.new_balance)
+        description_parts.push(tr(
+            ctx,
+            "daily.coins",
+            &[
+                ("coins", claim.coins_awarded.to_string()),
+                ("balance", format_number(claim.new_balance)),
+            ],
         ));
     }
 
     let description = description_parts.join("\n");
 
     let mut embed = serenity::CreateEmbed::new()
-        .title("✅ Daily Reward Claimed!")
+        .title(tr(ctx, "daily.claimed_title", &[]))
         .description(description)
         .color(0x00FF00); // Green
 
     // Add streak saver notification if it was used
     if streak_saver_used {
         embed = embed.field(
-            "🛡️ Streak Saver Used!",
-            "Your Daily Streak Saver protected your streak!",
+            tr(ctx, "daily.streak_saver_title", &[]),
+            tr(ctx, "daily.streak_saver_description", &[]),
             false,
         );
     }
 
     embed = embed
-        .field("Streak", format!("{} days 🔥", profile.daily_streak), true)
-        .field(
-            "Server Goal",
-            format!(
-                "{}/{} claims\n{}",
-                daily_goal.progress, daily_goal.target, progress_bar
-            ),
-            false,
-        )
-        .footer(serenity::CreateEmbedFooter::new(
-            "Come back tomorrow for more!",
-        ));
+        .field(tr(ctx, "daily.streak", &[]), streak_value, true)
+        .field(tr(ctx, "daily.server_goal", &[]), goal_value, false)
+        .footer(serenity::CreateEmbedFooter::new(tr(
+            ctx,
+            "daily.footer",
+            &[],
+        )));
 
     ctx.send(poise::CreateReply::default().embed(embed)).await?;
 
@@ -341,4 +655,24 @@ mod tests {
         assert_eq!(format_number(1234567), "1,234,567");
         assert_eq!(format_number(-1234567), "-1,234,567");
     }
+
+    #[test]
+    fn test_transaction_line_marks_spending() {
+        let transaction = |amount| Transaction {
+            user_id: 1,
+            guild_id: 1,
+            amount,
+            reason: "Shop purchase".to_string(),
+            timestamp: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        };
+
+        assert_eq!(
+            transaction_line(&transaction(1500)),
+            "🟢 +1,500 🪙 — Shop purchase · <t:1700000000:R>"
+        );
+        assert_eq!(
+            transaction_line(&transaction(-200)),
+            "🔴 **-200 🪙** — Shop purchase · <t:1700000000:R>"
+        );
+    }
 }
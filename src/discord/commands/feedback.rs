@@ -0,0 +1,89 @@
+use crate::core::feedback::{validate_feedback, Feedback};
+use crate::discord::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// Send feedback about the bot to its owners.
+///
+/// The feedback is stored for `/admin feedback recent` and posted in
+/// `FEEDBACK_CHANNEL_ID` when that's set.
+#[poise::command(slash_command, prefix_command)]
+pub async fn feedback(
+    ctx: Context<'_>,
+    #[description = "What you'd like the bot owners to know"]
+    #[rest]
+    message: String,
+) -> Result<(), Error> {
+    let message = match validate_feedback(&message) {
+        Ok(message) => message,
+        Err(problem) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ {problem}"))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let feedback = ctx
+        .data()
+        .feedback
+        .submit(
+            ctx.guild_id().map(|id| id.get()),
+            ctx.author().id.get(),
+            message,
+            chrono::Utc::now(),
+        )
+        .await?;
+    tracing::info!(
+        user_id = feedback.user_id,
+        guild_id = ?feedback.guild_id,
+        "Received feedback"
+    );
+
+    // The feedback is saved either way, so a failed forward is only logged
+    if let Some(channel_id) = ctx.data().config.discord.feedback_channel_id {
+        let embed = forward_embed(ctx, &feedback);
+        if let Err(e) = serenity::ChannelId::new(channel_id)
+            .send_message(ctx.http(), serenity::CreateMessage::new().embed(embed))
+            .await
+        {
+            tracing::warn!(channel_id, error = %e, "Failed to forward feedback");
+        }
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content("🙏 Thanks! Your feedback was sent to the bot owners.")
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// The embed posted in the feedback channel.
+fn forward_embed(ctx: Context<'_>, feedback: &Feedback) -> serenity::CreateEmbed {
+    let author = ctx.author();
+    let source = match ctx.guild_id() {
+        Some(guild_id) => {
+            let name = ctx
+                .serenity_context()
+                .cache
+                .guild(guild_id)
+                .map(|guild| guild.name.clone())
+                .unwrap_or_else(|| "Unknown server".to_string());
+            format!("{name} (`{guild_id}`)")
+        }
+        None => "Direct message".to_string(),
+    };
+
+    serenity::CreateEmbed::new()
+        .title("New feedback")
+        .author(serenity::CreateEmbedAuthor::new(&author.name).icon_url(author.face()))
+        .description(&feedback.message)
+        .field("From", format!("<@{}> (`{}`)", author.id, author.id), true)
+        .field("Server", source, true)
+        .color(serenity::Color::BLURPLE)
+        .timestamp(serenity::Timestamp::now())
+}
@@ -1,30 +1,56 @@
+use crate::core::github::{GithubEvent, GithubTrackingEntry, GithubUpdate, IssueActivity};
+use crate::core::util::parse_human_duration;
 use crate::discord::commands::leveling::{Context, Error};
 use crate::discord::github::dispatcher;
+use crate::discord::util::Paginator;
 use poise::serenity_prelude as serenity;
 
 /// Root `/github` command. Subcommands handle all configuration tasks.
 #[poise::command(
     slash_command,
     guild_only,
-    required_permissions = "ADMINISTRATOR",
-    subcommands("track", "track_org", "remove", "remove_org", "list", "check")
+    check = "crate::discord::access::is_manager",
+    subcommands(
+        "track",
+        "track_org",
+        "remove",
+        "remove_org",
+        "list",
+        "branches",
+        "mute",
+        "unmute",
+        "digest",
+        "check",
+        "poll",
+        "preview"
+    )
 )]
 pub async fn github(ctx: Context<'_>) -> Result<(), Error> {
     ctx.say(
         "GitHub tracking commands:\n\
         `/github track <owner> <repo>` - Track commits and issues for a repo\n\
         `/github track_org <org>` - Track every repo in an organization\n\
-        `/github remove <owner> <repo>` - Stop tracking a repo\n\
+        `/github remove [filter]` - Pick a tracked repo or org to stop tracking\n\
         `/github remove_org <org>` - Stop tracking an organization\n\
         `/github list` - Show what is tracked in this guild\n\
-        `/github check` - Force an immediate poll (admins only)",
+        `/github branches <owner> [repo] [filter]` - Only report matching branches\n\
+        `/github mute <owner> [repo] [duration]` - Pause notifications (until unmuted if no duration)\n\
+        `/github unmute <owner> [repo]` - Resume notifications\n\
+        `/github digest <owner> [repo] <enabled>` - One summary of new commits per poll\n\
+        `/github check` - Force an immediate poll (admins only)\n\
+        `/github poll [dry_run]` - Poll now and report what was found (admins only)\n\
+        `/github preview <owner> <repo>` - Preview notifications without tracking",
     )
     .await?;
     Ok(())
 }
 
 /// Track a specific repository (all branches).
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn track(
     ctx: Context<'_>,
     #[description = "Repository owner (user or org)"] owner: String,
@@ -50,7 +76,11 @@ pub async fn track(
 }
 
 /// Track every repository inside an organization.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn track_org(
     ctx: Context<'_>,
     #[description = "Organization login"] org: String,
@@ -84,37 +114,185 @@ pub async fn track_org(
     Ok(())
 }
 
-/// Stop tracking a single repository.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+/// Discord allows at most 25 options in a select menu.
+const MAX_SELECT_OPTIONS: usize = 25;
+
+/// Stop tracking a repository or organization, picked from a list.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn remove(
     ctx: Context<'_>,
-    #[description = "Repository owner (user or org)"] owner: String,
-    #[description = "Repository name"] repo: String,
+    #[description = "Only list entries containing this text"] filter: Option<String>,
 ) -> Result<(), Error> {
-    ctx.defer().await?;
     let guild_id = ctx
         .guild_id()
         .ok_or("This command only works in servers")?
         .get();
 
-    let removed = ctx
-        .data()
-        .github
-        .remove_repository(guild_id, &owner, &repo)
+    let mut entries = ctx.data().github.list_entries(guild_id).await;
+    if entries.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("Nothing is tracked in this guild.")
+                .ephemeral(true),
+        )
         .await?;
+        return Ok(());
+    }
 
-    if removed {
-        ctx.say(format!("Stopped tracking `{owner}/{repo}`."))
+    if let Some(filter) = filter.as_deref().map(str::to_lowercase) {
+        entries.retain(|entry| entry.display_name().to_lowercase().contains(&filter));
+        if entries.is_empty() {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content("No tracked repository or organization matches that filter.")
+                    .ephemeral(true),
+            )
             .await?;
-    } else {
-        ctx.say("No matching repository entry found.").await?;
+            return Ok(());
+        }
+    }
+
+    let mut prompt = "Pick the repository or organization to stop tracking.".to_string();
+    if entries.len() > MAX_SELECT_OPTIONS {
+        prompt.push_str(&format!(
+            "\nShowing the first {} of {}; use `filter` to find the others.",
+            MAX_SELECT_OPTIONS,
+            entries.len()
+        ));
     }
 
+    let options = entries
+        .iter()
+        .take(MAX_SELECT_OPTIONS)
+        .map(|entry| {
+            let description = if entry.is_org {
+                format!("Organization ({} repos)", entry.org_repos.len())
+            } else {
+                "Repository".to_string()
+            };
+            serenity::CreateSelectMenuOption::new(entry.display_name(), entry.key())
+                .description(description)
+        })
+        .collect();
+    let menu = serenity::CreateSelectMenu::new(
+        "github_remove_select",
+        serenity::CreateSelectMenuKind::String { options },
+    )
+    .placeholder("Tracked repository or organization");
+
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .content(prompt)
+                .components(vec![serenity::CreateActionRow::SelectMenu(menu)])
+                .ephemeral(true),
+        )
+        .await?;
+    let msg_id = reply.message().await?.id;
+
+    // Step 1: pick an entry
+    let Some(mci) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(std::time::Duration::from_secs(60))
+        .filter(move |mci| mci.message.id == msg_id)
+        .await
+    else {
+        reply
+            .edit(
+                ctx,
+                poise::CreateReply::default()
+                    .content("Timed out, nothing was removed.")
+                    .components(vec![]),
+            )
+            .await?;
+        return Ok(());
+    };
+    mci.defer(&ctx.http()).await?;
+
+    let key = match &mci.data.kind {
+        serenity::ComponentInteractionDataKind::StringSelect { values } => values.first(),
+        _ => None,
+    };
+    let Some(entry) = key.and_then(|key| entries.iter().find(|entry| &entry.key() == key)) else {
+        return Ok(());
+    };
+    let name = entry.display_name();
+    let kind = if entry.is_org {
+        "organization"
+    } else {
+        "repository"
+    };
+
+    // Step 2: confirm
+    reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .content(format!(
+                    "Stop tracking the {kind} `{name}`? Its updates will no longer be posted in <#{}>.",
+                    entry.channel_id
+                ))
+                .components(vec![serenity::CreateActionRow::Buttons(vec![
+                    serenity::CreateButton::new("github_remove_confirm")
+                        .label("Remove")
+                        .style(serenity::ButtonStyle::Danger),
+                    serenity::CreateButton::new("github_remove_cancel")
+                        .label("Cancel")
+                        .style(serenity::ButtonStyle::Secondary),
+                ])]),
+        )
+        .await?;
+
+    let confirmed = match serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .timeout(std::time::Duration::from_secs(60))
+        .filter(move |mci| mci.message.id == msg_id)
+        .await
+    {
+        Some(mci) => {
+            mci.defer(&ctx.http()).await?;
+            mci.data.custom_id == "github_remove_confirm"
+        }
+        None => false,
+    };
+
+    let outcome = if !confirmed {
+        "Nothing was removed.".to_string()
+    } else if ctx
+        .data()
+        .github
+        .remove_entry(guild_id, &entry.key())
+        .await?
+        .is_some()
+    {
+        format!("Stopped tracking the {kind} `{name}`.")
+    } else {
+        format!("`{name}` is no longer tracked.")
+    };
+
+    reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .content(outcome)
+                .components(vec![]),
+        )
+        .await?;
     Ok(())
 }
 
 /// Stop tracking an organization.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn remove_org(
     ctx: Context<'_>,
     #[description = "Organization login"] org: String,
@@ -142,35 +320,77 @@ pub async fn remove_org(
 }
 
 /// Show all tracked repositories and organizations for this guild.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
     let guild_id = ctx
         .guild_id()
         .ok_or("This command only works in servers")?
         .get();
 
-    let entries = ctx.data().github.list_entries(guild_id).await;
+    let mut entries = ctx.data().github.list_entries(guild_id).await;
     if entries.is_empty() {
         ctx.say("No repositories are being tracked in this guild.")
             .await?;
         return Ok(());
     }
 
+    // Organizations first, then a page at a time so long lists fit in an embed
+    entries.sort_by_key(|entry| !entry.is_org);
+    let pages = entries
+        .chunks(LIST_ENTRIES_PER_PAGE)
+        .map(|chunk| {
+            serenity::CreateEmbed::new()
+                .title("Tracked GitHub targets")
+                .description(tracked_entries_description(chunk))
+                .color(serenity::Colour::from_rgb(88, 101, 242))
+        })
+        .collect();
+
+    Paginator::new(0).run_pages(ctx, pages).await
+}
+
+/// Tracked entries shown per `/github list` page.
+const LIST_ENTRIES_PER_PAGE: usize = 10;
+
+/// Organization and repository lines for `/github list`.
+fn tracked_entries_description(entries: &[GithubTrackingEntry]) -> String {
     let mut repo_lines = Vec::new();
     let mut org_lines = Vec::new();
 
+    let now = chrono::Utc::now();
     for entry in entries {
+        let mut branches = if entry.branches.is_empty() {
+            String::new()
+        } else {
+            format!(" (branches: `{}`)", entry.branches.join("`, `"))
+        };
+        if entry.commit_digest {
+            branches.push_str(" (digest)");
+        }
+        if entry.is_muted(now) {
+            match entry.muted_until {
+                Some(until) if !entry.muted_indefinitely => {
+                    branches.push_str(&format!(" 🔇 muted until <t:{}:R>", until.timestamp()))
+                }
+                _ => branches.push_str(" 🔇 muted"),
+            }
+        }
         if entry.is_org {
             org_lines.push(format!(
-                "- `{}` ({} repos) -> <#{}>",
+                "- `{}` ({} repos) -> <#{}>{}",
                 entry.owner,
                 entry.org_repos.len(),
-                entry.channel_id
+                entry.channel_id,
+                branches
             ));
-        } else if let Some(repo) = entry.repo {
+        } else if let Some(repo) = &entry.repo {
             repo_lines.push(format!(
-                "- `{}/{}` -> <#{}>",
-                entry.owner, repo, entry.channel_id
+                "- `{}/{}` -> <#{}>{}",
+                entry.owner, repo, entry.channel_id, branches
             ));
         }
     }
@@ -187,27 +407,370 @@ pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
         description.push_str(&repo_lines.join("\n"));
     }
 
-    let embed = serenity::CreateEmbed::new()
-        .title("Tracked GitHub targets")
-        .description(description)
-        .color(serenity::Colour::from_rgb(88, 101, 242));
+    description
+}
+
+/// Only report commits on matching branches of a tracked repo or org.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn branches(
+    ctx: Context<'_>,
+    #[description = "Repository owner, or the organization login"] owner: String,
+    #[description = "Repository name (leave empty for an organization)"] repo: Option<String>,
+    #[description = "Comma-separated branches, `*` globs allowed, e.g. main, release/* (empty: all)"]
+    filter: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let patterns: Vec<String> = filter
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect();
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    let target = match &repo {
+        Some(repo) => format!("`{owner}/{repo}`"),
+        None => format!("organization `{owner}`"),
+    };
+    let content = if !ctx
+        .data()
+        .github
+        .set_branch_filter(guild_id, &owner, repo.as_deref(), patterns.clone())
+        .await?
+    {
+        format!("{target} isn't tracked in this server.")
+    } else if patterns.is_empty() {
+        format!("Now reporting commits on every branch of {target}.")
+    } else {
+        let list: Vec<String> = patterns.iter().map(|p| format!("`{p}`")).collect();
+        format!(
+            "Now only reporting commits on {} for {target}.",
+            list.join(", ")
+        )
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Pause notifications for a tracked repo or org without untracking it.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn mute(
+    ctx: Context<'_>,
+    #[description = "Repository owner, or the organization login"] owner: String,
+    #[description = "Repository name (leave empty for an organization)"] repo: Option<String>,
+    #[description = "How long, e.g. 2h or 1 day (empty: until /github unmute)"] duration: Option<
+        String,
+    >,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let until = match duration.as_deref() {
+        Some(duration) => match parse_human_duration(duration) {
+            Ok(duration) => {
+                let duration = chrono::Duration::from_std(duration)
+                    .map_err(|_| "That duration is too long")?;
+                Some(chrono::Utc::now() + duration)
+            }
+            Err(e) => {
+                ctx.send(
+                    poise::CreateReply::default()
+                        .content(format!("❌ Invalid duration: {e}"))
+                        .ephemeral(true),
+                )
+                .await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let target = match &repo {
+        Some(repo) => format!("`{owner}/{repo}`"),
+        None => format!("organization `{owner}`"),
+    };
+    let content = if !ctx
+        .data()
+        .github
+        .mute(guild_id, &owner, repo.as_deref(), until)
+        .await?
+    {
+        format!("{target} isn't tracked in this server.")
+    } else {
+        match until {
+            Some(until) => format!(
+                "🔇 Muted {target} until <t:{}:f>. New activity is skipped, not queued.",
+                until.timestamp()
+            ),
+            None => format!(
+                "🔇 Muted {target} until `/github unmute`. New activity is skipped, not queued."
+            ),
+        }
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Resume notifications for a muted repo or org.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn unmute(
+    ctx: Context<'_>,
+    #[description = "Repository owner, or the organization login"] owner: String,
+    #[description = "Repository name (leave empty for an organization)"] repo: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let target = match &repo {
+        Some(repo) => format!("`{owner}/{repo}`"),
+        None => format!("organization `{owner}`"),
+    };
+    let content = if ctx
+        .data()
+        .github
+        .unmute(guild_id, &owner, repo.as_deref())
+        .await?
+    {
+        format!("🔔 Notifications for {target} are back on.")
+    } else {
+        format!("{target} isn't tracked in this server.")
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Post one digest of new commits per poll instead of one message per commit.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn digest(
+    ctx: Context<'_>,
+    #[description = "Repository owner, or the organization login"] owner: String,
+    #[description = "Repository name (leave empty for an organization)"] repo: Option<String>,
+    #[description = "Batch commits into one digest per poll"] enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let target = match &repo {
+        Some(repo) => format!("`{owner}/{repo}`"),
+        None => format!("organization `{owner}`"),
+    };
+    let content = if !ctx
+        .data()
+        .github
+        .set_commit_digest(guild_id, &owner, repo.as_deref(), enabled)
+        .await?
+    {
+        format!("{target} isn't tracked in this server.")
+    } else if enabled {
+        format!("New commits in {target} are now posted as one digest per poll.")
+    } else {
+        format!("New commits in {target} are now posted one by one.")
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true),
+    )
+    .await?;
     Ok(())
 }
 
 /// Force an immediate poll for this guild.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn check(ctx: Context<'_>) -> Result<(), Error> {
     ctx.defer().await?;
-    let updates = ctx.data().github.poll_updates().await?;
+    let github = &ctx.data().github;
+    let poll = github.poll_updates().await?;
+    let found = !poll.updates.is_empty();
+    dispatcher::dispatch_poll(&ctx.serenity_context().http, github, poll).await?;
 
-    if updates.is_empty() {
+    if found {
+        ctx.say("Posted new GitHub events.").await?;
+    } else {
         ctx.say("No new GitHub events detected.").await?;
+    }
+
+    Ok(())
+}
+
+/// Poll now and report which events were found for this guild.
+///
+/// With `dry_run:true` nothing is posted and the poll watermarks are left
+/// untouched, so the regular poller still announces the same events.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn poll(
+    ctx: Context<'_>,
+    #[description = "Only report what would be posted (default: false)"] dry_run: Option<bool>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+    let dry_run = dry_run.unwrap_or(false);
+
+    let summary = if dry_run {
+        let updates = ctx.data().github.preview_updates(guild_id).await?;
+        summarize_updates(&updates)
     } else {
-        dispatcher::send_updates(&ctx.serenity_context().http, updates).await;
-        ctx.say("Posted new GitHub events.").await?;
+        // A real poll covers every guild, so post everything it found
+        let github = &ctx.data().github;
+        let poll = github.poll_updates().await?;
+        let guild_updates: Vec<GithubUpdate> = poll
+            .updates
+            .iter()
+            .filter(|update| update.guild_id == guild_id)
+            .cloned()
+            .collect();
+        dispatcher::dispatch_poll(&ctx.serenity_context().http, github, poll).await?;
+        summarize_updates(&guild_updates)
+    };
+
+    let header = if dry_run {
+        "🔍 **Dry run** - nothing was posted."
+    } else {
+        "📬 Poll complete."
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!("{header}\n{summary}"))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Preview the notifications a repository would produce, without tracking it.
+///
+/// Renders the latest commit and newest issue with the same embeds the poller posts.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn preview(
+    ctx: Context<'_>,
+    #[description = "Repository owner (user or org)"] owner: String,
+    #[description = "Repository name"] repo: String,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let events = ctx.data().github.preview_repository(&owner, &repo).await?;
+
+    let mut reply = poise::CreateReply::default().ephemeral(true);
+    if events.is_empty() {
+        reply = reply.content(format!(
+            "`{owner}/{repo}` has no commits or issues to preview."
+        ));
+    } else {
+        reply = reply.content(format!(
+            "Preview of `{owner}/{repo}` notifications (nothing is tracked yet):"
+        ));
+        for event in &events {
+            reply = reply.embed(dispatcher::build_embed(event));
+        }
     }
 
+    ctx.send(reply).await?;
     Ok(())
 }
+
+/// Per-type event counts and target channels for `/github poll`.
+fn summarize_updates(updates: &[GithubUpdate]) -> String {
+    if updates.is_empty() {
+        return "No new GitHub events detected.".to_string();
+    }
+
+    let mut commits = 0;
+    let mut bugs_closed = 0;
+    let mut issues_opened = 0;
+    let mut issues_updated = 0;
+    let mut issues_closed = 0;
+    let mut channels: Vec<u64> = Vec::new();
+
+    for update in updates {
+        match &update.event {
+            GithubEvent::CommitPushed { .. } => commits += 1,
+            GithubEvent::CommitDigest {
+                commits: listed, ..
+            } => commits += listed.len(),
+            GithubEvent::BugClosed { .. } => bugs_closed += 1,
+            GithubEvent::IssueActivity { activity, .. } => match activity {
+                IssueActivity::Opened => issues_opened += 1,
+                IssueActivity::Updated => issues_updated += 1,
+                IssueActivity::Closed => issues_closed += 1,
+            },
+        }
+        if !channels.contains(&update.channel_id) {
+            channels.push(update.channel_id);
+        }
+    }
+
+    let lines: Vec<String> = [
+        ("Commits", commits),
+        ("Bugs closed", bugs_closed),
+        ("Issues opened", issues_opened),
+        ("Issues updated", issues_updated),
+        ("Issues closed", issues_closed),
+    ]
+    .iter()
+    .filter(|(_, count)| *count > 0)
+    .map(|(label, count)| format!("- {label}: {count}"))
+    .collect();
+    let channels: Vec<String> = channels.iter().map(|id| format!("<#{id}>")).collect();
+
+    format!("{}\nChannels: {}", lines.join("\n"), channels.join(", "))
+}
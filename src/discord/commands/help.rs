@@ -1,4 +1,4 @@
-use crate::discord::commands::leveling::{Context, Error};
+use crate::discord::commands::leveling::{Context, Data, Error};
 use poise::serenity_prelude as serenity;
 use std::collections::HashMap;
 
@@ -8,7 +8,10 @@ const CATEGORY_ORDER: &[&str] = &[
     "Music Controls",
     "Music Tools",
     "Progress & Rewards",
+    "Economy",
+    "Moderation",
     "GitHub Automation",
+    "AI Assistant",
     "Utilities",
 ];
 
@@ -18,7 +21,10 @@ fn get_category_emoji(category: &str) -> &'static str {
         "Music Controls" => "🎵",
         "Music Tools" => "🎛️",
         "Progress & Rewards" => "📈",
+        "Economy" => "🪙",
+        "Moderation" => "🛡️",
         "GitHub Automation" => "📡",
+        "AI Assistant" => "🤖",
         "Utilities" => "🧰",
         _ => "•",
     }
@@ -51,13 +57,13 @@ fn get_command_metadata(name: &str) -> CommandMetadata {
             category: "Progress & Rewards",
             priority: 80,
             description: Some("Open an embedded version of your Greybeard profile stats."),
-            note: None,
+            note: Some("Add private:true to only show the reply to you."),
         },
         "level" => CommandMetadata {
             category: "Progress & Rewards",
             priority: 79,
             description: Some("Check your current level and XP."),
-            note: None,
+            note: Some("Add private:true to only show the reply to you."),
         },
         "daily" => CommandMetadata {
             category: "Progress & Rewards",
@@ -69,25 +75,33 @@ fn get_command_metadata(name: &str) -> CommandMetadata {
             category: "Progress & Rewards",
             priority: 65,
             description: Some("Display the top 10 community members by level and XP."),
-            note: None,
+            note: Some("Pick the Achievements category to rank by unlocked achievements instead."),
         },
         "xpstats" => CommandMetadata {
             category: "Progress & Rewards",
             priority: 60,
             description: Some("Show detailed XP analytics for yourself or another member."),
-            note: None,
+            note: Some("Add private:true to only show the reply to you."),
+        },
+        "voicestats" => CommandMetadata {
+            category: "Progress & Rewards",
+            priority: 58,
+            description: Some("Show time spent in voice, voice XP and the most active channel."),
+            note: Some("Add private:true to only show the reply to you."),
         },
         "achievements" => CommandMetadata {
             category: "Progress & Rewards",
             priority: 55,
             description: Some("View your unlocked achievements."),
-            note: None,
+            note: Some(
+                "Use /achievements progress to see the 5 goals you're closest to. Add private:true to only show the reply to you.",
+            ),
         },
         "next_achievement" => CommandMetadata {
             category: "Progress & Rewards",
             priority: 50,
             description: Some("Show the closest achievement you can earn."),
-            note: Some("Aliases: /nextach"),
+            note: Some("Aliases: /nextach. Add private:true to only show the reply to you."),
         },
         "prestige" => CommandMetadata {
             category: "Progress & Rewards",
@@ -95,11 +109,113 @@ fn get_command_metadata(name: &str) -> CommandMetadata {
             description: Some("Reset your level for permanent bonuses (requires level 50)."),
             note: Some("Gain XP multipliers, daily bonuses, and coin bonuses."),
         },
+        "dailyleaderboard" => CommandMetadata {
+            category: "Progress & Rewards",
+            priority: 64,
+            description: Some("Show the longest daily streaks in the server."),
+            note: None,
+        },
+        "balance" => CommandMetadata {
+            category: "Economy",
+            priority: 60,
+            description: Some("Check your GreyCoins balance."),
+            note: None,
+        },
+        "richest" => CommandMetadata {
+            category: "Economy",
+            priority: 57,
+            description: Some("Show who has the most GreyCoins."),
+            note: Some("Rank by balance or total earned"),
+        },
+        "coinflip" => CommandMetadata {
+            category: "Economy",
+            priority: 54,
+            description: Some("Bet GreyCoins on heads or tails and double them on a win."),
+            note: Some("Bets are capped and there's a short cooldown between flips"),
+        },
+        "transactions" => CommandMetadata {
+            category: "Economy",
+            priority: 58,
+            description: Some("See your recent GreyCoin transactions."),
+            note: Some("Admins can view another member's transactions"),
+        },
+        "shop" => CommandMetadata {
+            category: "Economy",
+            priority: 55,
+            description: Some("Browse and buy items with GreyCoins."),
+            note: Some("Subcommands: list, buy"),
+        },
+        "inventory" => CommandMetadata {
+            category: "Economy",
+            priority: 50,
+            description: Some("See the items you own."),
+            note: None,
+        },
+        "use" => CommandMetadata {
+            category: "Economy",
+            priority: 45,
+            description: Some("Use an item from your inventory, like an XP Boost Potion."),
+            note: None,
+        },
+        "gift" => CommandMetadata {
+            category: "Economy",
+            priority: 44,
+            description: Some("Give items from your inventory to another member."),
+            note: None,
+        },
+        "antispam" => CommandMetadata {
+            category: "Moderation",
+            priority: 40,
+            description: Some("Configure the anti-spam filter (Admin only)."),
+            note: None,
+        },
+        "purge" => CommandMetadata {
+            category: "Moderation",
+            priority: 39,
+            description: Some("Bulk-delete recent messages, optionally by user or text."),
+            note: Some("Messages older than 14 days can't be bulk deleted and are skipped."),
+        },
+        "slowmode" => CommandMetadata {
+            category: "Moderation",
+            priority: 38,
+            description: Some("Set or disable a channel's slowmode."),
+            note: Some("Use seconds:0 to turn slowmode off (max 21600 = 6 hours)."),
+        },
+        "tempmute" => CommandMetadata {
+            category: "Moderation",
+            priority: 37,
+            description: Some("Give a member the mute role for a while, e.g. 30m or 2h."),
+            note: Some("Set the role with /muterole first. It's removed on time, even after a restart."),
+        },
+        "muterole" => CommandMetadata {
+            category: "Moderation",
+            priority: 36,
+            description: Some("Set or show the role /tempmute gives."),
+            note: None,
+        },
         "github" => CommandMetadata {
             category: "GitHub Automation",
             priority: 60,
             description: Some("Manage GitHub repository tracking."),
-            note: Some("Subcommands: track, remove, list, check"),
+            note: Some("Subcommands: track, remove, list, branches, check, poll [dry_run], preview"),
+        },
+        "stats" => CommandMetadata {
+            category: "Utilities",
+            priority: 40,
+            description: Some("Show bot uptime, server and user counts, messages seen and memory use."),
+            note: None,
+        },
+        "birthday" => CommandMetadata {
+            category: "Utilities",
+            priority: 39,
+            description: Some("Set your birthday (MM-DD) so the server can celebrate it."),
+            note: Some("Subcommands: set, remove, show"),
+        },
+        "feedback" => CommandMetadata {
+            category: "Utilities",
+            priority: 38,
+            description: Some("Send feedback about the bot to its owners."),
+            note: None,
         },
         "timezones" => CommandMetadata {
             category: "Utilities",
@@ -110,21 +226,45 @@ fn get_command_metadata(name: &str) -> CommandMetadata {
         "serverstats" => CommandMetadata {
             category: "Utilities",
             priority: 30,
-            description: Some("Check that the server stats module is responding."),
+            description: Some(
+                "Manage member-count channels and see when the server is most active.",
+            ),
             note: None,
         },
         "logging" => CommandMetadata {
-            category: "Utilities",
+            category: "Moderation",
             priority: 20,
             description: Some("Configure logging channels."),
             note: None,
         },
+        "leveling" => CommandMetadata {
+            category: "Progress & Rewards",
+            priority: 10,
+            description: Some("Tune how much XP each channel grants (Admin only)."),
+            note: Some(
+                "Subcommands: channel exclude/include, channel multiplier, channel multipliers, announcements, daily_reset",
+            ),
+        },
         "give_xp" => CommandMetadata {
             category: "Utilities",
             priority: 0, // Low priority, admin only
             description: Some("Award XP to a user (Admin only)."),
             note: None,
         },
+        "config" => CommandMetadata {
+            category: "Moderation",
+            priority: 19,
+            description: Some("Manager roles, the join auto-role, welcome/goodbye messages and birthday announcements (Admin only)."),
+            note: Some(
+                "Subcommands: managers add/remove/list, autorole set/toggle/status, welcome set/test/disable, goodbye set/test/kicks_and_bans/disable, birthdays set/disable",
+            ),
+        },
+        "reactionrole" => CommandMetadata {
+            category: "Moderation",
+            priority: 18,
+            description: Some("Let members pick roles by reacting to a message (Managers only)."),
+            note: Some("Subcommands: add, remove, list"),
+        },
         _ => CommandMetadata {
             category: "Utilities",
             priority: 0,
@@ -134,10 +274,57 @@ fn get_command_metadata(name: &str) -> CommandMetadata {
     }
 }
 
-/// Show a categorized list of commands.
+/// Show a categorized list of commands, or detailed help for one command.
 #[poise::command(slash_command, prefix_command)]
-pub async fn help(ctx: Context<'_>) -> Result<(), Error> {
-    let mut categories: HashMap<&str, Vec<(i32, String)>> = HashMap::new();
+pub async fn help(
+    ctx: Context<'_>,
+    #[description = "Command to show detailed help for"]
+    #[autocomplete = "autocomplete_commands"]
+    command: Option<String>,
+    #[description = "Only show the help to you (default: true)"] ephemeral: Option<bool>,
+) -> Result<(), Error> {
+    let ephemeral = ephemeral.unwrap_or(true);
+
+    let prefix = ctx
+        .framework()
+        .options()
+        .prefix_options
+        .prefix
+        .clone()
+        .unwrap_or_else(|| "/".to_string());
+
+    let embed = match command {
+        Some(name) => match find_command(&ctx.framework().options().commands, &name) {
+            Some(found) => command_detail_embed(found, &prefix),
+            None => {
+                ctx.send(
+                    poise::CreateReply::default()
+                        .content(format!(
+                            "❓ Unknown command `{}`. Use `/help` to see every command.",
+                            name.trim()
+                        ))
+                        .ephemeral(true),
+                )
+                .await?;
+                return Ok(());
+            }
+        },
+        None => command_overview_embed(ctx, &prefix).await,
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .ephemeral(ephemeral),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Build the categorized overview of all visible commands.
+async fn command_overview_embed(ctx: Context<'_>, prefix: &str) -> serenity::CreateEmbed {
+    let mut categories: HashMap<String, Vec<(i32, String)>> = HashMap::new();
 
     for command in &ctx.framework().options().commands {
         if command.hide_in_help {
@@ -163,19 +350,26 @@ pub async fn help(ctx: Context<'_>) -> Result<(), Error> {
             entry.push_str(&format!("\n  ⤷ {}", note));
         }
 
+        // poise's `category = "..."` attribute wins over the table above
+        let category = command
+            .category
+            .clone()
+            .unwrap_or_else(|| metadata.category.to_string());
+
         categories
-            .entry(metadata.category)
+            .entry(category)
             .or_default()
             .push((metadata.priority, entry));
     }
 
     let mut embed = serenity::CreateEmbed::new()
         .title("Greybeard Command Guide")
-        .description(
-            "Use slash commands with `/`. \
+        .description(format!(
+            "Use slash commands with `/` (most also work as text commands, e.g. `{}level`). \
             Everything is organised by what you want to do, and the most helpful commands \
-            sit at the top of each section.",
-        )
+            sit at the top of each section. Use `/help command:<name>` for details on one command.",
+            prefix
+        ))
         .color(serenity::Colour::from_rgb(88, 101, 242))
         .timestamp(serenity::Timestamp::now());
 
@@ -184,19 +378,19 @@ pub async fn help(ctx: Context<'_>) -> Result<(), Error> {
     }
 
     // Sort categories based on defined order, then alphabetically for others
-    let mut sorted_categories: Vec<_> = categories.keys().cloned().collect();
+    let mut sorted_categories: Vec<String> = categories.keys().cloned().collect();
     sorted_categories.sort_by(|a, b| {
-        let pos_a = CATEGORY_ORDER.iter().position(|&x| x == *a).unwrap_or(999);
-        let pos_b = CATEGORY_ORDER.iter().position(|&x| x == *b).unwrap_or(999);
+        let pos_a = CATEGORY_ORDER.iter().position(|x| x == a).unwrap_or(999);
+        let pos_b = CATEGORY_ORDER.iter().position(|x| x == b).unwrap_or(999);
         pos_a.cmp(&pos_b).then(a.cmp(b))
     });
 
     for category in sorted_categories {
-        if let Some(entries) = categories.get_mut(category) {
+        if let Some(entries) = categories.get_mut(&category) {
             // Sort by priority (descending), then name (ascending)
             entries.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
 
-            let emoji = get_category_emoji(category);
+            let emoji = get_category_emoji(&category);
             let title = format!("{} {}", emoji, category);
 
             let formatted_entries: Vec<String> = entries.iter().map(|(_, s)| s.clone()).collect();
@@ -216,13 +410,158 @@ pub async fn help(ctx: Context<'_>) -> Result<(), Error> {
         }
     }
 
-    embed = embed.footer(serenity::CreateEmbedFooter::new(
+    // The AI assistant has no slash command - it answers mentions
+    embed = embed.field(
+        format!("{} AI Assistant", get_category_emoji("AI Assistant")),
+        "• Mention the bot with a question to get an AI answer with channel context.",
+        false,
+    );
+
+    embed.footer(serenity::CreateEmbedFooter::new(
         "Need a hand? Ping a moderator.",
-    ));
+    ))
+}
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+/// Find a command by name, alias or qualified name (e.g. `github track`).
+fn find_command<'a>(
+    commands: &'a [poise::Command<Data, Error>],
+    query: &str,
+) -> Option<&'a poise::Command<Data, Error>> {
+    let query = query.trim().trim_start_matches('/').to_lowercase();
+    let mut words = query.split_whitespace();
+    let first = words.next()?;
 
-    Ok(())
+    let mut found = commands.iter().find(|command| {
+        command.name == first || command.aliases.iter().any(|alias| alias == first)
+    })?;
+
+    for word in words {
+        found = found
+            .subcommands
+            .iter()
+            .find(|sub| sub.name == word || sub.aliases.iter().any(|alias| alias == word))?;
+    }
+
+    Some(found)
+}
+
+/// Build the detailed help embed for a single command.
+fn command_detail_embed(
+    command: &poise::Command<Data, Error>,
+    prefix: &str,
+) -> serenity::CreateEmbed {
+    let metadata = get_command_metadata(&command.name);
+    let category = command
+        .category
+        .clone()
+        .unwrap_or_else(|| metadata.category.to_string());
+
+    let description = metadata
+        .description
+        .or(command.description.as_deref())
+        .unwrap_or("No description provided.");
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("/{}", command.qualified_name))
+        .description(description)
+        .color(serenity::Colour::from_rgb(88, 101, 242))
+        .field(
+            "Category",
+            format!("{} {}", get_category_emoji(&category), category),
+            true,
+        );
+
+    // Longer doc-comment text (everything after the first paragraph)
+    if let Some(help_text) = command.help_text.as_deref() {
+        embed = embed.field(
+            "Details",
+            crate::core::util::truncate_chars(help_text, 1000),
+            false,
+        );
+    }
+
+    if let Some(note) = metadata.note {
+        embed = embed.field("Note", note, false);
+    }
+
+    if !command.parameters.is_empty() {
+        let options: Vec<String> = command
+            .parameters
+            .iter()
+            .map(|param| {
+                format!(
+                    "• `{}`{} — {}",
+                    param.name,
+                    if param.required { "" } else { " (optional)" },
+                    param.description.as_deref().unwrap_or("No description")
+                )
+            })
+            .collect();
+        embed = embed.field("Options", options.join("\n"), false);
+    }
+
+    if !command.subcommands.is_empty() {
+        let subcommands: Vec<String> = command
+            .subcommands
+            .iter()
+            .filter(|sub| !sub.hide_in_help)
+            .map(|sub| {
+                format!(
+                    "• **/{}** — {}",
+                    sub.qualified_name,
+                    sub.description.as_deref().unwrap_or("No description")
+                )
+            })
+            .collect();
+        for chunk in chunk_entries(&subcommands) {
+            embed = embed.field("Subcommands", chunk.join("\n"), false);
+        }
+    }
+
+    let mut usage = Vec::new();
+    if command.slash_action.is_some() || !command.subcommands.is_empty() {
+        usage.push(format!("`/{}`", command.qualified_name));
+    }
+    if command.prefix_action.is_some() {
+        usage.push(format!("`{}{}`", prefix, command.qualified_name));
+    }
+    if !command.aliases.is_empty() {
+        usage.push(format!("aliases: {}", command.aliases.join(", ")));
+    }
+    if !usage.is_empty() {
+        embed = embed.field("Usage", usage.join(" · "), false);
+    }
+
+    let mut restrictions = Vec::new();
+    if command.guild_only {
+        restrictions.push("Servers only".to_string());
+    }
+    if !command.required_permissions.is_empty() {
+        restrictions.push(format!("Requires: {}", command.required_permissions));
+    }
+    if !restrictions.is_empty() {
+        embed = embed.field("Restrictions", restrictions.join("\n"), false);
+    }
+
+    embed
+}
+
+/// Autocomplete for the `command` argument of `/help`
+async fn autocomplete_commands<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    let partial = partial.to_lowercase();
+    ctx.framework()
+        .options()
+        .commands
+        .iter()
+        .filter(|command| !command.hide_in_help)
+        .filter(move |command| command.name.contains(&partial))
+        .map(|command| command.name.clone())
+        .take(25)
+        .collect::<Vec<_>>()
+        .into_iter()
 }
 
 fn chunk_entries(entries: &[String]) -> Vec<Vec<String>> {
@@ -249,3 +588,20 @@ fn chunk_entries(entries: &[String]) -> Vec<Vec<String>> {
 
     chunks
 }
+
+// The test uses `/shop` for its subcommands
+#[cfg(all(test, feature = "economy"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_command_by_name_and_subcommand() {
+        let commands = vec![help(), crate::discord::commands::shop::shop()];
+
+        assert_eq!(find_command(&commands, "help").unwrap().name, "help");
+        assert_eq!(find_command(&commands, "/Shop").unwrap().name, "shop");
+        assert_eq!(find_command(&commands, "shop buy").unwrap().name, "buy");
+        assert!(find_command(&commands, "shop sell").is_none());
+        assert!(find_command(&commands, "unknown").is_none());
+    }
+}
@@ -7,51 +7,61 @@
 //
 // This layer is THIN - no business logic, just translation.
 
-use crate::core::ai::ai_service::AiService;
-use crate::core::ai::AiProvider;
-use crate::core::github::GithubService;
-use crate::core::leveling::achievements::get_all_achievements;
-use crate::core::leveling::{Difficulty, LevelingService, XpSource};
-use crate::core::logging::LoggingService;
-use crate::core::server_stats::ServerStatsService;
+#[cfg(feature = "ai")]
+use crate::core::ai::DynAiService;
+#[cfg(feature = "github")]
+use crate::core::github::{GithubConfigStore, GithubService};
+use crate::core::leveling::{
+    AchievementStanding, Difficulty, LevelUpMention, LevelingError, LevelingService, XpSource,
+    XpStore,
+};
+use crate::core::logging::{LogConfigStore, LoggingService};
+use crate::core::server_stats::{ServerStatsService, ServerStatsStore};
 use crate::core::timezones::TimezoneService;
-use crate::infra::github::file_store::GithubFileStore;
+use crate::discord::i18n::tr;
+use crate::discord::user_identity::{resolve_identity, DisplayIdentity};
+use crate::discord::util::Paginator;
+#[cfg(feature = "github")]
 use crate::infra::github::github_client::GithubApiClient;
-use crate::infra::leveling::SqliteXpStore;
-use crate::infra::logging::sqlite_store::SqliteLogStore;
-use crate::infra::server_stats::JsonServerStatsStore;
 use poise::serenity_prelude as serenity;
 use std::collections::HashMap;
 
 /// Show your current level and XP.
-#[poise::command(slash_command, guild_only)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn level(
     ctx: Context<'_>,
     #[description = "User to check (defaults to you)"] user: Option<serenity::User>,
+    #[description = "Only show the reply to you"] private: Option<bool>,
 ) -> Result<(), Error> {
-    show_profile(ctx, user).await
+    show_profile(ctx, user, private).await
 }
 
 /// Display user's profile including level, XP, and stats.
-#[poise::command(slash_command, guild_only)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn profile(
     ctx: Context<'_>,
     #[description = "User to check (defaults to you)"] user: Option<serenity::User>,
+    #[description = "Only show the reply to you"] private: Option<bool>,
 ) -> Result<(), Error> {
-    show_profile(ctx, user).await
+    show_profile(ctx, user, private).await
 }
 
 /// Shared logic for level and profile commands
-async fn show_profile(ctx: Context<'_>, user: Option<serenity::User>) -> Result<(), Error> {
+async fn show_profile(
+    ctx: Context<'_>,
+    user: Option<serenity::User>,
+    private: Option<bool>,
+) -> Result<(), Error> {
     let target_user = user.as_ref().unwrap_or_else(|| ctx.author());
     let user_id = target_user.id.get();
     let guild_id = ctx
         .guild_id()
-        .ok_or("This command only works in servers")?
+        .ok_or_else(|| tr(ctx, "common.guild_only", &[]))?
         .get();
 
     if target_user.bot {
-        ctx.say("Bots don't have profiles! 🤖").await?;
+        ctx.send(reply_base(private).content(tr(ctx, "profile.bot", &[])))
+            .await?;
         return Ok(());
     }
 
@@ -62,6 +72,7 @@ async fn show_profile(ctx: Context<'_>, user: Option<serenity::User>) -> Result<
         .await?;
 
     // Pull GreyCoin balance from the economy service so the profile shows wallet info too
+    #[cfg(feature = "economy")]
     let wallet = ctx.data().economy.get_wallet(user_id, guild_id).await?;
 
     let leveling = &ctx.data().leveling;
@@ -80,24 +91,49 @@ async fn show_profile(ctx: Context<'_>, user: Option<serenity::User>) -> Result<
     // Get prestige tier info
     let tier_info = crate::core::leveling::LevelingService::<crate::infra::leveling::SqliteXpStore>::get_prestige_tier_info(profile.prestige_level);
     let prestige_display = if profile.prestige_level > 0 {
-        format!(
-            "{} {} (Prestige {})",
-            tier_info.badge_emoji, tier_info.tier_name, profile.prestige_level
+        tr(
+            ctx,
+            "profile.prestige_value",
+            &[
+                ("emoji", tier_info.badge_emoji.to_string()),
+                ("tier", tier_info.tier_name.to_string()),
+                ("level", profile.prestige_level.to_string()),
+            ],
         )
     } else {
-        "None".to_string()
+        tr(ctx, "profile.prestige_none", &[])
     };
 
+    let identity = DisplayIdentity::for_user(
+        ctx.serenity_context(),
+        serenity::GuildId::new(guild_id),
+        target_user,
+    );
+
     let embed = serenity::CreateEmbed::new()
-        .title(format!("Profile of {}", target_user.name))
+        .title(tr(ctx, "profile.title", &[("name", identity.name)]))
         .color(0x00ff00)
-        .thumbnail(target_user.face())
-        .field("Prestige", prestige_display, true)
-        .field("Level", format!("**{}**", profile.level), true)
-        .field("GreyCoins", format!("🪙 {}", wallet.balance), true)
-        .field("Total XP", format!("**{}**", profile.total_xp), false)
+        .thumbnail(identity.avatar_url)
+        .field(tr(ctx, "profile.prestige", &[]), prestige_display, true)
+        .field(
+            tr(ctx, "profile.level", &[]),
+            format!("**{}**", profile.level),
+            true,
+        );
+    #[cfg(feature = "economy")]
+    let embed = embed.field(
+        tr(ctx, "profile.coins", &[]),
+        format!("🪙 {}", wallet.balance),
+        true,
+    );
+    let embed = embed
         .field(
-            "Progress",
+            tr(ctx, "profile.total_xp", &[]),
+            format!("**{}**", profile.total_xp),
+            false,
+        )
+        .field(
+            tr(ctx, "profile.progress", &[]),
             format!(
                 "{}/{} XP\n{}",
                 xp_progress,
@@ -106,37 +142,47 @@ async fn show_profile(ctx: Context<'_>, user: Option<serenity::User>) -> Result<
             ),
             false,
         )
-        .field("XP to next level", format!("{}", xp_needed), false)
         .field(
-            "Total commands",
+            tr(ctx, "profile.xp_to_next", &[]),
+            format!("{}", xp_needed),
+            false,
+        )
+        .field(
+            tr(ctx, "profile.total_commands", &[]),
             format!("{}", profile.total_commands_used),
             true,
         )
         .field(
-            "Total messages",
+            tr(ctx, "profile.total_messages", &[]),
             format!("{}", profile.total_messages),
             true,
         )
         .field(
-            "Daily streak",
-            format!("{} days", profile.daily_streak),
+            tr(ctx, "profile.daily_streak", &[]),
+            tr(
+                ctx,
+                "common.days",
+                &[("days", profile.daily_streak.to_string())],
+            ),
             true,
         );
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    ctx.send(reply_base(private).embed(embed)).await?;
 
     Ok(())
 }
 
 /// Show XP analytics for yourself or another member.
-#[poise::command(slash_command, guild_only)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn xpstats(
     ctx: Context<'_>,
     #[description = "User to check"] user: Option<serenity::User>,
+    #[description = "Only show the reply to you"] private: Option<bool>,
 ) -> Result<(), Error> {
     let target_user = user.as_ref().unwrap_or_else(|| ctx.author());
     if target_user.bot {
-        ctx.say("Bots don't have XP stats! 🤖").await?;
+        ctx.send(reply_base(private).content("Bots don't have XP stats! 🤖"))
+            .await?;
         return Ok(());
     }
 
@@ -145,6 +191,11 @@ pub async fn xpstats(
         .guild_id()
         .ok_or("This command only works in servers")?
         .get();
+    let identity = DisplayIdentity::for_user(
+        ctx.serenity_context(),
+        serenity::GuildId::new(guild_id),
+        target_user,
+    );
 
     let profile = ctx
         .data()
@@ -230,9 +281,9 @@ pub async fn xpstats(
     };
 
     let embed = serenity::CreateEmbed::new()
-        .title(format!("XP Analytics — {}", target_user.name))
+        .title(format!("XP Analytics — {}", identity.name))
         .color(0x008080) // Teal
-        .thumbnail(target_user.face())
+        .thumbnail(identity.avatar_url)
         .field("All-time XP", format!("{}", profile.total_xp), true)
         .field("Last 7 days", format!("{} XP", recent_total), true)
         .field("Avg per active day", format!("{:.1} XP", avg_per_day), true)
@@ -243,331 +294,375 @@ pub async fn xpstats(
             "Analytics based on last 120 events",
         ));
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    ctx.send(reply_base(private).embed(embed)).await?;
 
     Ok(())
 }
 
-/// Show the server's XP leaderboard.
-#[poise::command(slash_command, guild_only)]
-pub async fn leaderboard(
+/// Show voice activity for yourself or another member.
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn voicestats(
     ctx: Context<'_>,
-    #[description = "Page number (default: 1)"]
-    #[min = 1]
-    page: Option<usize>,
+    #[description = "User to check"] user: Option<serenity::User>,
+    #[description = "Only show the reply to you"] private: Option<bool>,
 ) -> Result<(), Error> {
-    // 1. Extract primitive data
+    let target_user = user.as_ref().unwrap_or_else(|| ctx.author());
+    if target_user.bot {
+        ctx.send(reply_base(private).content("Bots don't have voice stats! 🤖"))
+            .await?;
+        return Ok(());
+    }
+
     let guild_id = ctx
         .guild_id()
         .ok_or("This command only works in servers")?
         .get();
+    let identity = DisplayIdentity::for_user(
+        ctx.serenity_context(),
+        serenity::GuildId::new(guild_id),
+        target_user,
+    );
 
-    // Defer response since recalculating ranks might take a moment
-    ctx.defer().await?;
-
-    // 2. Fetch leaderboard (read-only, fast)
-    // We fetch a large number to support pagination, but avoid the expensive
-    // O(N) write operation of recalculating rank history on every view.
-    let all_profiles = ctx.data().leveling.get_leaderboard(guild_id, 1000).await?;
-
-    // OPTIMIZATION: Filter bots using cache only - don't make HTTP calls.
-    // Bots shouldn't have XP entries anyway (we filter them in process_message),
-    // but if they do, just display them rather than making slow API calls.
-    // We use a quick cache-only check that returns false (not a bot) if unknown.
-    let profiles: Vec<_> = all_profiles
-        .into_iter()
-        .filter(|profile| !is_bot_cached(&ctx, guild_id, profile.user_id))
-        .collect();
+    let stats = ctx
+        .data()
+        .leveling
+        .get_voice_stats(target_user.id.get(), guild_id)
+        .await?;
 
-    // Check if we have any data
-    if profiles.is_empty() {
-        ctx.say("No one has earned XP yet! Start chatting to get on the leaderboard! 💬")
-            .await?;
+    if stats.total_minutes == 0 {
+        let message = if target_user.id == ctx.author().id {
+            "You haven't spent any time in voice yet. Hop into a voice channel! 🎙️".to_string()
+        } else {
+            format!("{} hasn't spent any time in voice yet. 🎙️", identity.name)
+        };
+        ctx.send(reply_base(private).content(message)).await?;
         return Ok(());
     }
 
-    let per_page = 5;
-    let total_pages = (profiles.len() + per_page - 1) / per_page;
-    let mut current_page = page.unwrap_or(1).clamp(1, total_pages);
+    let top_channel = stats
+        .top_channel
+        .map(|(channel_id, minutes)| {
+            format!("<#{}> ({})", channel_id, format_voice_minutes(minutes))
+        })
+        .unwrap_or_else(|| "—".to_string());
 
-    // OPTIMIZATION: We use synchronous cache-only display name resolution.
-    // This avoids slow HTTP calls and makes the leaderboard respond instantly.
-
-    let msg = {
-        let offset = (current_page - 1) * per_page;
-        let mut description = String::new();
-
-        // Add user's rank at the top
-        let user_id = ctx.author().id.get();
-        if let Some(rank) = profiles
-            .iter()
-            .position(|p| p.user_id == user_id)
-            .map(|i| i + 1)
-        {
-            description.push_str(&format!("Your rank: **#{}**\n\n", rank));
-        } else {
-            description.push_str("You are not ranked yet.\n\n");
-        }
-
-        for (index, stats) in profiles.iter().skip(offset).take(per_page).enumerate() {
-            let rank = offset + index + 1;
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("🎙️ Voice Stats — {}", identity.name))
+        .color(0x008080) // Teal
+        .thumbnail(identity.avatar_url)
+        .field(
+            "Time in voice",
+            format_voice_minutes(stats.total_minutes),
+            true,
+        )
+        .field("Voice XP", format!("{} XP", stats.voice_xp), true)
+        .field("Most active channel", top_channel, false)
+        .footer(serenity::CreateEmbedFooter::new(
+            "Voice XP is based on the last 120 XP events",
+        ));
 
-            let user_name = resolve_display_name_cached(&ctx, guild_id, stats.user_id);
+    ctx.send(reply_base(private).embed(embed)).await?;
 
-            // Add medal emojis for top 3
-            let medal = match rank {
-                1 => "🥇",
-                2 => "🥈",
-                3 => "🥉",
-                _ => "  ",
-            };
+    Ok(())
+}
 
-            // Get prestige info
-            let tier_info = crate::core::leveling::LevelingService::<
-                crate::infra::leveling::SqliteXpStore,
-            >::get_prestige_tier_info(stats.prestige_level);
+/// Base reply for commands with a `private` option, ephemeral when it's set.
+///
+/// `/level`, `/profile`, `/xpstats`, `/voicestats`, `/achievements` and
+/// `/next_achievement` take the option. The button-driven commands
+/// (`/leaderboard`, `/dailyleaderboard`, `/prestige`) stay public: they page or
+/// confirm by editing one message, and "Find Me" already answers privately.
+/// Prefix commands can't be ephemeral, so they always reply publicly.
+fn reply_base(private: Option<bool>) -> poise::CreateReply {
+    poise::CreateReply::default().ephemeral(private.unwrap_or(false))
+}
 
-            // Highlight the user if it's them
-            let is_me = stats.user_id == ctx.author().id.get();
-            let name_display = if is_me {
-                format!("**{}** (You)", user_name)
-            } else {
-                user_name
-            };
+/// "2h 5m" style duration for voice time.
+fn format_voice_minutes(minutes: u64) -> String {
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
 
-            // Progress bar for the level
-            let leveling = &ctx.data().leveling;
-            let previous_threshold = leveling.xp_for_level(stats.level);
-            let next_threshold = leveling.xp_for_next_level(stats.level);
-            let xp_progress = stats.xp.saturating_sub(previous_threshold);
-            let level_span = next_threshold.saturating_sub(previous_threshold);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum LeaderboardCategory {
+    #[name = "XP"]
+    Xp,
+    #[name = "Achievements"]
+    Achievements,
+}
 
-            let progress_pct = if level_span > 0 {
-                xp_progress as f64 / level_span as f64
-            } else {
-                0.0
-            };
+/// What a leaderboard ranks. Achievement standings are computed once per
+/// command and paged in memory; XP pages are fetched from the store, leaving
+/// out the bots listed here.
+enum LeaderboardSource {
+    Xp(Vec<u64>),
+    Achievements(Vec<AchievementStanding>),
+}
 
-            let bar = build_progress_bar(progress_pct, 10);
+/// Show the server's XP or achievements leaderboard.
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn leaderboard(
+    ctx: Context<'_>,
+    #[description = "Page number (default: 1)"]
+    #[min = 1]
+    page: Option<usize>,
+    #[description = "What to rank by (default: XP)"] category: Option<LeaderboardCategory>,
+) -> Result<(), Error> {
+    // 1. Extract primitive data
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
 
-            let level_line = if stats.prestige_level > 0 {
-                format!(
-                    "Prestige {} {} | Level {} | {} XP",
-                    stats.prestige_level, tier_info.badge_emoji, stats.level, stats.xp
-                )
-            } else {
-                format!("Level {} | {} XP", stats.level, stats.xp)
-            };
+    // Defer response since recalculating ranks might take a moment
+    ctx.defer().await?;
 
-            description.push_str(&format!(
-                "{} **#{}** {}\n{}\n{}\n\n",
-                medal, rank, name_display, level_line, bar
-            ));
+    // 2. Count entries and find the caller's rank (two cheap COUNT queries).
+    // Only the page being shown is fetched, so large guilds don't load every
+    // profile just to display five of them.
+    // Bots are left out before ranking, so nobody's rank skips a number.
+    let leveling = &ctx.data().leveling;
+    let author_id = ctx.author().id.get();
+    let bots = cached_bot_ids(&ctx, guild_id);
+    let (source, total_users, my_rank) = match category.unwrap_or(LeaderboardCategory::Xp) {
+        LeaderboardCategory::Xp => {
+            let total_users = leveling.count_ranked_users(guild_id, &bots).await? as usize;
+            let my_rank = leveling
+                .get_user_rank(author_id, guild_id, &bots)
+                .await?
+                .map(|rank| rank as usize);
+            (LeaderboardSource::Xp(bots), total_users, my_rank)
+        }
+        LeaderboardCategory::Achievements => {
+            let standings: Vec<AchievementStanding> = leveling
+                .get_achievement_leaderboard(guild_id)
+                .await?
+                .into_iter()
+                .filter(|standing| !bots.contains(&standing.user_id))
+                .collect();
+            let my_rank = standings
+                .iter()
+                .position(|standing| standing.user_id == author_id)
+                .map(|index| index + 1);
+            let total_users = standings.len();
+            (
+                LeaderboardSource::Achievements(standings),
+                total_users,
+                my_rank,
+            )
         }
+    };
 
-        let embed = serenity::CreateEmbed::new()
-            .title(format!("📊 Leaderboard"))
-            .description(description)
-            .color(0xffd700) // Gold color
-            .footer(serenity::CreateEmbedFooter::new(format!(
-                "Page {}/{}",
-                current_page, total_pages
-            )));
+    // Check if we have any data
+    if total_users == 0 {
+        let empty_message = match source {
+            LeaderboardSource::Xp(_) => {
+                "No one has earned XP yet! Start chatting to get on the leaderboard! 💬"
+            }
+            LeaderboardSource::Achievements(_) => {
+                "No one has unlocked an achievement yet! Check `/next_achievement` for ideas. 🏆"
+            }
+        };
+        ctx.say(empty_message).await?;
+        return Ok(());
+    }
 
-        let components = vec![serenity::CreateActionRow::Buttons(vec![
-            serenity::CreateButton::new("prev")
-                .label("◀ Previous")
-                .style(serenity::ButtonStyle::Primary)
-                .disabled(current_page == 1),
-            serenity::CreateButton::new("next")
-                .label("Next ▶")
-                .style(serenity::ButtonStyle::Primary)
-                .disabled(current_page == total_pages),
-            serenity::CreateButton::new("find_me")
-                .label("🔍 Find Me")
-                .style(serenity::ButtonStyle::Secondary),
-        ])];
+    let total_pages = total_users.div_ceil(LEADERBOARD_PER_PAGE);
+    let my_page = my_rank.map(|rank| (rank - 1) / LEADERBOARD_PER_PAGE + 1);
 
-        ctx.send(
-            poise::CreateReply::default()
-                .embed(embed)
-                .components(components),
-        )
-        .await?
-    };
+    Paginator::new(total_pages)
+        .start_at(page.unwrap_or(1))
+        .jump_button("🔍 Find Me", my_page, "You are not on the leaderboard yet!")
+        .run(ctx, |page| {
+            leaderboard_page_embed(ctx, guild_id, &source, page, my_rank)
+        })
+        .await
+}
 
-    let msg_id = msg.message().await?.id;
+/// Entries shown per leaderboard page.
+const LEADERBOARD_PER_PAGE: usize = 5;
 
-    // Interaction loop
-    while let Some(mci) = serenity::ComponentInteractionCollector::new(ctx)
-        .author_id(ctx.author().id)
-        .channel_id(ctx.channel_id())
-        .timeout(std::time::Duration::from_secs(60 * 2)) // 2 minutes
-        .filter(move |mci| mci.message.id == msg_id)
-        .await
-    {
-        // Update page based on interaction
-        match mci.data.custom_id.as_str() {
-            "prev" => {
-                if current_page > 1 {
-                    current_page -= 1;
-                }
-            }
-            "next" => {
-                if current_page < total_pages {
-                    current_page += 1;
-                }
-            }
-            "find_me" => {
-                let user_id = ctx.author().id.get();
-                if let Some(idx) = profiles.iter().position(|p| p.user_id == user_id) {
-                    current_page = (idx / per_page) + 1;
-                } else {
-                    // User not on leaderboard (shouldn't happen if they have XP, but maybe they don't)
-                    if let Err(e) = mci
-                        .create_response(
-                            &ctx,
-                            serenity::CreateInteractionResponse::Message(
-                                serenity::CreateInteractionResponseMessage::new()
-                                    .content("You are not on the leaderboard yet!")
-                                    .ephemeral(true),
-                            ),
-                        )
-                        .await
-                    {
-                        println!("Error sending ephemeral response: {:?}", e);
-                    }
-                    continue;
-                }
-            }
-            _ => {}
+/// Build the embed for one leaderboard page.
+async fn leaderboard_page_embed(
+    ctx: Context<'_>,
+    guild_id: u64,
+    source: &LeaderboardSource,
+    current_page: usize,
+    my_rank: Option<usize>,
+) -> Result<serenity::CreateEmbed, Error> {
+    let (title, description, leader_id) = match source {
+        LeaderboardSource::Xp(bots) => {
+            xp_leaderboard_page(ctx, guild_id, bots, current_page, my_rank).await?
         }
-
-        // Defer the update to prevent "Unknown interaction" errors if processing takes > 3s
-        if let Err(e) = mci.defer(&ctx.http()).await {
-            println!("Error deferring interaction: {:?}", e);
-            continue;
+        LeaderboardSource::Achievements(standings) => {
+            achievement_leaderboard_page(ctx, guild_id, standings, current_page, my_rank)
         }
+    };
 
-        // Rebuild the message content
-        let offset = (current_page - 1) * per_page;
-        let mut description = String::new();
+    let mut embed = serenity::CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .color(0xffd700); // Gold color
+
+    // Show the page leader's avatar (placeholder if they've left the server)
+    if let Some(leader_id) = leader_id {
+        let identity = resolve_identity(
+            ctx.serenity_context(),
+            ctx.data(),
+            serenity::GuildId::new(guild_id),
+            leader_id,
+        )
+        .await;
+        embed = embed.thumbnail(identity.avatar_url);
+    }
 
-        // Add user's rank at the top
-        let user_id = ctx.author().id.get();
-        if let Some(rank) = profiles
-            .iter()
-            .position(|p| p.user_id == user_id)
-            .map(|i| i + 1)
-        {
-            description.push_str(&format!("Your rank: **#{}**\n\n", rank));
-        } else {
-            description.push_str("You are not ranked yet.\n\n");
-        }
+    Ok(embed)
+}
 
-        for (index, stats) in profiles.iter().skip(offset).take(per_page).enumerate() {
-            let rank = offset + index + 1;
+/// "Your rank" line shown above every leaderboard page.
+fn rank_header(my_rank: Option<usize>) -> String {
+    match my_rank {
+        Some(rank) => format!("Your rank: **#{}**\n\n", rank),
+        None => "You are not ranked yet.\n\n".to_string(),
+    }
+}
 
-            let user_name = resolve_display_name_cached(&ctx, guild_id, stats.user_id);
+/// Medal emojis for the top 3.
+pub(crate) fn rank_medal(rank: usize) -> &'static str {
+    match rank {
+        1 => "🥇",
+        2 => "🥈",
+        3 => "🥉",
+        _ => "  ",
+    }
+}
 
-            let medal = match rank {
-                1 => "🥇",
-                2 => "🥈",
-                3 => "🥉",
-                _ => "  ",
-            };
+/// Title, description and leader of one XP leaderboard page.
+async fn xp_leaderboard_page(
+    ctx: Context<'_>,
+    guild_id: u64,
+    bots: &[u64],
+    current_page: usize,
+    my_rank: Option<usize>,
+) -> Result<(&'static str, String, Option<u64>), Error> {
+    let leveling = &ctx.data().leveling;
+    let offset = (current_page - 1) * LEADERBOARD_PER_PAGE;
+    let page_entries = leveling
+        .get_leaderboard_page(guild_id, current_page, LEADERBOARD_PER_PAGE, bots)
+        .await?;
 
-            // Get prestige info
-            let tier_info = crate::core::leveling::LevelingService::<
-                crate::infra::leveling::SqliteXpStore,
-            >::get_prestige_tier_info(stats.prestige_level);
+    // Add user's rank at the top
+    let mut description = rank_header(my_rank);
 
-            let is_me = stats.user_id == ctx.author().id.get();
-            let name_display = if is_me {
-                format!("**{}** (You)", user_name)
-            } else {
-                user_name
-            };
+    // OPTIMIZATION: Resolve names using cache only - don't make HTTP calls.
+    for (index, stats) in page_entries.iter().enumerate() {
+        let rank = offset + index + 1;
 
-            let leveling = &ctx.data().leveling;
-            let previous_threshold = leveling.xp_for_level(stats.level);
-            let next_threshold = leveling.xp_for_next_level(stats.level);
-            let xp_progress = stats.xp.saturating_sub(previous_threshold);
-            let level_span = next_threshold.saturating_sub(previous_threshold);
+        let user_name = resolve_display_name_cached(&ctx, guild_id, stats.user_id);
 
-            let progress_pct = if level_span > 0 {
-                xp_progress as f64 / level_span as f64
-            } else {
-                0.0
-            };
+        // Add medal emojis for top 3
+        let medal = rank_medal(rank);
 
-            let bar = build_progress_bar(progress_pct, 10);
+        // Get prestige info
+        let tier_info = crate::core::leveling::LevelingService::<
+            crate::infra::leveling::SqliteXpStore,
+        >::get_prestige_tier_info(stats.prestige_level);
 
-            let level_line = if stats.prestige_level > 0 {
-                format!(
-                    "Prestige {} {} | Level {} | {} XP",
-                    stats.prestige_level, tier_info.badge_emoji, stats.level, stats.xp
-                )
-            } else {
-                format!("Level {} | {} XP", stats.level, stats.xp)
-            };
+        // Highlight the user if it's them
+        let is_me = stats.user_id == ctx.author().id.get();
+        let name_display = if is_me {
+            format!("**{}** (You)", user_name)
+        } else {
+            user_name
+        };
 
-            description.push_str(&format!(
-                "{} **#{}** {}\n{}\n{}\n\n",
-                medal, rank, name_display, level_line, bar
-            ));
-        }
+        // Progress bar for the level
+        let previous_threshold = leveling.xp_for_level(stats.level);
+        let next_threshold = leveling.xp_for_next_level(stats.level);
+        let xp_progress = stats.xp.saturating_sub(previous_threshold);
+        let level_span = next_threshold.saturating_sub(previous_threshold);
 
-        let embed = serenity::CreateEmbed::new()
-            .title(format!("📊 Leaderboard"))
-            .description(description)
-            .color(0xffd700)
-            .footer(serenity::CreateEmbedFooter::new(format!(
-                "Page {}/{}",
-                current_page, total_pages
-            )));
+        let progress_pct = if level_span > 0 {
+            xp_progress as f64 / level_span as f64
+        } else {
+            0.0
+        };
 
-        let components = vec![serenity::CreateActionRow::Buttons(vec![
-            serenity::CreateButton::new("prev")
-                .label("◀ Previous")
-                .style(serenity::ButtonStyle::Primary)
-                .disabled(current_page == 1),
-            serenity::CreateButton::new("next")
-                .label("Next ▶")
-                .style(serenity::ButtonStyle::Primary)
-                .disabled(current_page == total_pages),
-            serenity::CreateButton::new("find_me")
-                .label("🔍 Find Me")
-                .style(serenity::ButtonStyle::Secondary),
-        ])];
+        let bar = build_progress_bar(progress_pct, 10);
 
-        // Update the message using the handle since we deferred the interaction
-        if let Err(e) = msg
-            .edit(
-                ctx,
-                poise::CreateReply::default()
-                    .embed(embed)
-                    .components(components),
+        let level_line = if stats.prestige_level > 0 {
+            format!(
+                "Prestige {} {} | Level {} | {} XP",
+                stats.prestige_level, tier_info.badge_emoji, stats.level, stats.xp
             )
-            .await
-        {
-            println!("Error updating leaderboard: {:?}", e);
-        }
+        } else {
+            format!("Level {} | {} XP", stats.level, stats.xp)
+        };
+
+        description.push_str(&format!(
+            "{} **#{}** {}\n{}\n{}\n\n",
+            medal, rank, name_display, level_line, bar
+        ));
     }
 
-    // Remove components after timeout
-    let _ = msg
-        .edit(
-            ctx,
-            poise::CreateReply::default().components(vec![]), // Empty components to remove them
-        )
-        .await;
+    Ok((
+        "📊 Leaderboard",
+        description,
+        page_entries.first().map(|leader| leader.user_id),
+    ))
+}
 
-    Ok(())
+/// Title, description and leader of one achievements leaderboard page.
+fn achievement_leaderboard_page(
+    ctx: Context<'_>,
+    guild_id: u64,
+    standings: &[AchievementStanding],
+    current_page: usize,
+    my_rank: Option<usize>,
+) -> (&'static str, String, Option<u64>) {
+    let offset = (current_page - 1) * LEADERBOARD_PER_PAGE;
+    let page_entries: Vec<&AchievementStanding> = standings
+        .iter()
+        .skip(offset)
+        .take(LEADERBOARD_PER_PAGE)
+        .collect();
+    let total_achievements = ctx.data().leveling.achievements().len();
+
+    let mut description = rank_header(my_rank);
+
+    for (index, standing) in page_entries.iter().enumerate() {
+        let rank = offset + index + 1;
+        let user_name = resolve_display_name_cached(&ctx, guild_id, standing.user_id);
+        let name_display = if standing.user_id == ctx.author().id.get() {
+            format!("**{}** (You)", user_name)
+        } else {
+            user_name
+        };
+
+        // Achievement XP breaks ties between equal counts
+        description.push_str(&format!(
+            "{} **#{}** {}\n🏆 {}/{} achievements · {} achievement XP\n\n",
+            rank_medal(rank),
+            rank,
+            name_display,
+            standing.unlocked,
+            total_achievements,
+            standing.achievement_xp
+        ));
+    }
+
+    (
+        "🏆 Achievements Leaderboard",
+        description,
+        page_entries.first().map(|leader| leader.user_id),
+    )
 }
 
 /// Show the server's daily streak leaderboard.
-#[poise::command(slash_command, guild_only)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn dailyleaderboard(
     ctx: Context<'_>,
     #[description = "Page number (default: 1)"]
@@ -738,7 +833,12 @@ pub async fn dailyleaderboard(
                         )
                         .await
                     {
-                        println!("Error sending ephemeral response: {:?}", e);
+                        tracing::warn!(
+                            guild_id,
+                            user_id = mci.user.id.get(),
+                            error = ?e,
+                            "Failed to send ephemeral leaderboard response"
+                        );
                     }
                     continue;
                 }
@@ -748,7 +848,7 @@ pub async fn dailyleaderboard(
 
         // Defer the update to prevent "Unknown interaction" errors if processing takes > 3s
         if let Err(e) = mci.defer(&ctx.http()).await {
-            println!("Error deferring interaction: {:?}", e);
+            tracing::warn!(guild_id, error = ?e, "Failed to defer leaderboard interaction");
             continue;
         }
 
@@ -848,7 +948,7 @@ pub async fn dailyleaderboard(
             )
             .await
         {
-            println!("Error updating streak leaderboard: {:?}", e);
+            tracing::warn!(guild_id, error = ?e, "Failed to update streak leaderboard message");
         }
     }
 
@@ -873,7 +973,11 @@ pub async fn dailyleaderboard(
 ///
 /// OPTIMIZATION: This function uses cache ONLY to avoid slow HTTP calls
 /// that would block the leaderboard command.
-fn resolve_display_name_cached(ctx: &Context<'_>, guild_id: u64, user_id: u64) -> String {
+pub(crate) fn resolve_display_name_cached(
+    ctx: &Context<'_>,
+    guild_id: u64,
+    user_id: u64,
+) -> String {
     let guild_id_s = serenity::GuildId::from(guild_id);
     let user_id_s = serenity::UserId::from(user_id);
 
@@ -967,7 +1071,7 @@ async fn resolve_display_name(ctx: &Context<'_>, guild_id: u64, user_id: u64) ->
 /// 1. Bots shouldn't have XP entries anyway (filtered in process_message)
 /// 2. Even if a bot slips through, showing them on leaderboard is harmless
 /// 3. Fast response is more important than perfect bot filtering
-fn is_bot_cached(ctx: &Context<'_>, guild_id: u64, user_id: u64) -> bool {
+pub(crate) fn is_bot_cached(ctx: &Context<'_>, guild_id: u64, user_id: u64) -> bool {
     let user_id_s = serenity::UserId::from(user_id);
     let guild_id_s = serenity::GuildId::from(guild_id);
 
@@ -986,6 +1090,23 @@ fn is_bot_cached(ctx: &Context<'_>, guild_id: u64, user_id: u64) -> bool {
     false
 }
 
+/// IDs of the guild's members that the cache knows are bots. Like
+/// `is_bot_cached`, a bot missing from the cache is treated as a member.
+fn cached_bot_ids(ctx: &Context<'_>, guild_id: u64) -> Vec<u64> {
+    ctx.serenity_context()
+        .cache
+        .guild(serenity::GuildId::new(guild_id))
+        .map(|guild| {
+            guild
+                .members
+                .values()
+                .filter(|member| member.user.bot)
+                .map(|member| member.user.id.get())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Check if a user is a bot (async version with HTTP fallback)
 #[allow(dead_code)]
 async fn is_bot(ctx: &Context<'_>, guild_id: u64, user_id: u64) -> bool {
@@ -1029,23 +1150,87 @@ pub type Context<'a> = poise::Context<'a, Data, Error>;
 /// This is where we store our services and configuration.
 use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct Data {
-    pub leveling: Arc<LevelingService<SqliteXpStore>>,
-    pub server_stats: Arc<ServerStatsService<JsonServerStatsStore>>,
+    // Stores are boxed trait objects so `STORAGE_BACKEND` can pick SQLite or
+    // in-memory implementations at startup (see main.rs).
+    pub leveling: Arc<LevelingService<Box<dyn XpStore>>>,
+    pub server_stats: Arc<ServerStatsService<Box<dyn ServerStatsStore>>>,
     pub timezones: Arc<TimezoneService>,
-    pub logging: Arc<LoggingService<SqliteLogStore>>,
-    pub github: Arc<GithubService<GithubApiClient, GithubFileStore>>,
+    pub logging: Arc<LoggingService<Box<dyn LogConfigStore>>>,
+    #[cfg(feature = "github")]
+    pub github: Arc<GithubService<GithubApiClient, Box<dyn GithubConfigStore>>>,
     /// AI service with dynamic provider (supports OpenRouter, Gemini, etc.)
     /// Uses a trait object to allow switching providers at runtime via config.
-    pub ai: Arc<AiService<Box<dyn AiProvider>>>,
-    pub economy: Arc<crate::core::economy::EconomyService<crate::infra::economy::SqliteCoinStore>>,
+    /// `None` when no AI provider is configured.
+    #[cfg(feature = "ai")]
+    pub ai: Option<Arc<DynAiService>>,
+    /// Startup configuration (`config.toml` and environment)
+    pub config: Arc<crate::infra::config::Config>,
+    #[cfg(feature = "economy")]
+    pub economy:
+        Arc<crate::core::economy::EconomyService<Box<dyn crate::core::economy::CoinStore>>>,
+    #[cfg(feature = "economy")]
     pub inventory:
-        Arc<crate::core::economy::InventoryService<crate::infra::economy::SqliteInventoryStore>>,
+        Arc<crate::core::economy::InventoryService<Box<dyn crate::core::economy::InventoryStore>>>,
     /// Anti-spam moderation service
+    #[cfg(feature = "moderation")]
     pub anti_spam:
-        Arc<crate::core::moderation::AntiSpamService<crate::infra::moderation::SqliteSpamStore>>,
+        Arc<crate::core::moderation::AntiSpamService<Box<dyn crate::core::moderation::SpamStore>>>,
+    /// Temporary roles waiting to be removed (`/tempmute`)
+    #[cfg(feature = "moderation")]
+    pub temp_roles: Arc<
+        crate::core::moderation::TempRoleService<Box<dyn crate::core::moderation::TempRoleStore>>,
+    >,
     /// Knowledge store for RAG-lite retrieval
-    pub knowledge: Arc<crate::infra::ai::SqliteKnowledgeStore>,
+    #[cfg(feature = "ai")]
+    #[allow(dead_code)]
+    pub knowledge: Arc<dyn crate::core::ai::KnowledgeStore>,
+    /// Channel that receives detailed command error reports (`ERROR_LOG_CHANNEL_ID`)
+    pub error_log_channel_id: Option<u64>,
+    /// Localized strings for user-facing replies (`LOCALES_DIR`)
+    pub i18n: Arc<crate::core::i18n::Localizer>,
+    /// Booster status fetched over HTTP when the member isn't cached
+    pub boost_cache: Arc<crate::discord::boosters::BoostStatusCache>,
+    /// Users whose account lookup failed recently (shown as former members)
+    pub unknown_users: Arc<crate::discord::user_identity::UnknownUsers>,
+    /// Users who opted out of AI context and replies (`/ai forgetme`)
+    #[cfg(feature = "ai")]
+    pub ai_privacy: Arc<crate::core::ai::AiPrivacyService<Box<dyn crate::core::ai::AiOptOutStore>>>,
+    /// Per-guild cap on how many messages one AI reply may use (`/ai responses`)
+    #[cfg(feature = "ai")]
+    pub response_limits:
+        Arc<crate::core::ai::ResponseLimitsService<Box<dyn crate::core::ai::ResponseLimitsStore>>>,
+    /// In-flight AI mentions per (channel, user), to debounce rapid pings
+    #[cfg(feature = "ai")]
+    pub mention_throttle: Arc<crate::core::ai::MentionThrottle>,
+    /// Roles that may run admin commands (`/config managers`)
+    pub manager_roles:
+        Arc<crate::core::access::ManagerRoleService<Box<dyn crate::core::access::ManagerRoleStore>>>,
+    /// Role given to members when they join (`/config autorole`)
+    pub auto_roles:
+        Arc<crate::core::autorole::AutoRoleService<Box<dyn crate::core::autorole::AutoRoleStore>>>,
+    /// Roles handed out for reactions on bound messages (`/reactionrole`)
+    pub reaction_roles: Arc<
+        crate::core::reactionroles::ReactionRoleService<
+            Box<dyn crate::core::reactionroles::ReactionRoleStore>,
+        >,
+    >,
+    /// Message posted when members join (`/config welcome`)
+    pub welcome:
+        Arc<crate::core::welcome::WelcomeService<Box<dyn crate::core::welcome::WelcomeStore>>>,
+    /// Message posted when members leave (`/config goodbye`)
+    pub goodbye:
+        Arc<crate::core::welcome::GoodbyeService<Box<dyn crate::core::welcome::GoodbyeStore>>>,
+    /// Member birthdays and announcement settings (`/birthday`, `/config birthdays`)
+    pub birthdays: Arc<
+        crate::core::birthdays::BirthdayService<Box<dyn crate::core::birthdays::BirthdayStore>>,
+    >,
+    /// Feedback sent with `/feedback`
+    pub feedback:
+        Arc<crate::core::feedback::FeedbackService<Box<dyn crate::core::feedback::FeedbackStore>>>,
+    /// Uptime and session counters for `/stats`
+    pub metrics: Arc<crate::core::metrics::AppMetrics>,
 }
 
 
@@ -1053,7 +1238,11 @@ pub struct Data {
 ///
 /// Scans the leaderboard and ensures everyone has the correct prestige role
 /// based on their current prestige level.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn sync_prestige(ctx: Context<'_>) -> Result<(), Error> {
     let guild_id = ctx
         .guild_id()
@@ -1126,7 +1315,12 @@ pub async fn sync_prestige(ctx: Context<'_>) -> Result<(), Error> {
                     role.id
                 }
                 Err(e) => {
-                    println!("Failed to create role {}: {:?}", target_role_name, e);
+                    tracing::error!(
+                        guild_id,
+                        role = %target_role_name,
+                        error = ?e,
+                        "Failed to create prestige role"
+                    );
                     errors += 1;
                     continue;
                 }
@@ -1150,7 +1344,12 @@ pub async fn sync_prestige(ctx: Context<'_>) -> Result<(), Error> {
                         )
                         .await
                     {
-                        println!("Failed to add role to {}: {:?}", user_id, e);
+                        tracing::error!(
+                            guild_id,
+                            user_id,
+                            error = ?e,
+                            "Failed to add prestige role"
+                        );
                         errors += 1;
                     } else {
                         added = true;
@@ -1177,7 +1376,13 @@ pub async fn sync_prestige(ctx: Context<'_>) -> Result<(), Error> {
                                     )
                                     .await
                                 {
-                                    println!("Failed to remove role {} from {}: {:?}", role.name, user_id, e);
+                                    tracing::error!(
+                                        guild_id,
+                                        user_id,
+                                        role = %role.name,
+                                        error = ?e,
+                                        "Failed to remove old prestige role"
+                                    );
                                 } else {
                                     removed = true;
                                 }
@@ -1191,7 +1396,12 @@ pub async fn sync_prestige(ctx: Context<'_>) -> Result<(), Error> {
                 }
             }
             Err(e) => {
-                println!("Failed to fetch member {}: {:?}", user_id, e);
+                tracing::error!(
+                    guild_id,
+                    user_id,
+                    error = ?e,
+                    "Failed to fetch member for prestige sync"
+                );
                 errors += 1;
             }
         }
@@ -1240,7 +1450,11 @@ impl From<ChallengeDifficultyChoice> for Difficulty {
 /// Manually award XP to a user (admin only - for testing).
 ///
 /// **Command syntax:** `/give_xp @user 100`
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn give_xp(
     ctx: Context<'_>,
     #[description = "User to give XP to"] user: serenity::User,
@@ -1305,15 +1519,358 @@ pub async fn give_xp(
     Ok(())
 }
 
-/// Show user's achievements
+/// Leveling settings for this server (Admin only).
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager",
+    subcommands("channel", "announcements", "daily_reset")
+)]
+pub async fn leveling(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Per-channel XP settings.
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("exclude", "include", "multiplier", "multipliers")
+)]
+pub async fn channel(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Stop messages in a channel from earning XP. Its multiplier is kept.
+#[poise::command(slash_command, guild_only)]
+pub async fn exclude(
+    ctx: Context<'_>,
+    #[description = "Channel where messages should not earn XP"] channel: serenity::GuildChannel,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    ctx.data()
+        .leveling
+        .set_channel_excluded(guild_id, channel.id.get(), true)
+        .await?;
+
+    ctx.say(format!(
+        "🚫 Messages in <#{}> no longer earn XP. Message counts are still tracked.",
+        channel.id
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Let an excluded channel earn XP again, at its multiplier.
+#[poise::command(slash_command, guild_only)]
+pub async fn include(
+    ctx: Context<'_>,
+    #[description = "Channel that should earn XP again"] channel: serenity::GuildChannel,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let leveling = &ctx.data().leveling;
+    leveling
+        .set_channel_excluded(guild_id, channel.id.get(), false)
+        .await?;
+
+    let multiplier = leveling
+        .channel_multiplier(guild_id, channel.id.get())
+        .await?;
+    let message = if multiplier == 1.0 {
+        format!("✅ <#{}> earns normal XP again.", channel.id)
+    } else {
+        format!(
+            "✅ <#{}> earns XP again, at its **{}x** multiplier.",
+            channel.id, multiplier
+        )
+    };
+    ctx.say(message).await?;
+    Ok(())
+}
+
+/// Scale the XP messages earn in a channel (0 disables XP, 1 is normal).
 #[poise::command(slash_command, guild_only)]
+pub async fn multiplier(
+    ctx: Context<'_>,
+    #[description = "Channel to configure"] channel: serenity::GuildChannel,
+    #[description = "XP multiplier, e.g. 2 for double XP or 0 for none (max 5)"]
+    #[min = 0.0]
+    #[max = 5.0]
+    value: f64,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    ctx.data()
+        .leveling
+        .set_channel_multiplier(guild_id, channel.id.get(), value)
+        .await?;
+
+    let message = if value == 0.0 {
+        format!("🚫 Messages in <#{}> no longer earn XP.", channel.id)
+    } else if value == 1.0 {
+        format!("✅ <#{}> is back to normal XP.", channel.id)
+    } else {
+        format!(
+            "✅ Messages in <#{}> now earn **{}x** XP.",
+            channel.id, value
+        )
+    };
+    ctx.say(message).await?;
+    Ok(())
+}
+
+/// List channels with a custom XP multiplier.
+#[poise::command(slash_command, guild_only)]
+pub async fn multipliers(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let leveling = &ctx.data().leveling;
+    let mut channels: Vec<(u64, f64)> = leveling
+        .channel_multipliers(guild_id)
+        .await?
+        .into_iter()
+        .collect();
+    let mut excluded: Vec<u64> = leveling
+        .excluded_channels(guild_id)
+        .await?
+        .into_iter()
+        .collect();
+
+    if channels.is_empty() && excluded.is_empty() {
+        ctx.say("Every channel earns normal (1x) XP.").await?;
+        return Ok(());
+    }
+
+    channels.sort_by(|a, b| b.1.total_cmp(&a.1));
+    excluded.sort_unstable();
+    let mut lines: Vec<String> = channels
+        .iter()
+        .filter(|(channel_id, _)| !excluded.contains(channel_id))
+        .map(|(channel_id, multiplier)| format!("<#{}> — **{}x**", channel_id, multiplier))
+        .collect();
+    lines.extend(
+        excluded
+            .iter()
+            .map(|channel_id| format!("<#{}> — **excluded**", channel_id)),
+    );
+
+    let embed = serenity::CreateEmbed::new()
+        .title("⚖️ Channel XP Multipliers")
+        .description(lines.join("\n"))
+        .color(0x5865F2)
+        .footer(serenity::CreateEmbedFooter::new(
+            "All other channels earn 1x XP",
+        ));
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum LevelUpMentionChoice {
+    #[name = "Ping the member"]
+    Ping,
+    #[name = "Display name, no ping"]
+    Name,
+    #[name = "Mention without a notification"]
+    Silent,
+}
+
+impl From<LevelUpMentionChoice> for LevelUpMention {
+    fn from(choice: LevelUpMentionChoice) -> Self {
+        match choice {
+            LevelUpMentionChoice::Ping => LevelUpMention::Ping,
+            LevelUpMentionChoice::Name => LevelUpMention::Name,
+            LevelUpMentionChoice::Silent => LevelUpMention::Silent,
+        }
+    }
+}
+
+/// Choose whether level-up announcements ping the member.
+///
+/// Run without options to see the current setting.
+#[poise::command(slash_command, guild_only)]
+pub async fn announcements(
+    ctx: Context<'_>,
+    #[description = "How level-ups mention the member (default: display name, no ping)"]
+    mention: Option<LevelUpMentionChoice>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+    let leveling = &ctx.data().leveling;
+
+    let mention = match mention {
+        Some(choice) => {
+            let mention = choice.into();
+            leveling.set_level_up_mention(guild_id, mention).await?;
+            mention
+        }
+        None => leveling.level_up_mention(guild_id).await?,
+    };
+
+    let description = match mention {
+        LevelUpMention::Ping => "mention and ping the member",
+        LevelUpMention::Name => "show the member's display name without a ping",
+        LevelUpMention::Silent => "mention the member without sending a notification",
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!("🔔 Level-up announcements {}.", description))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set the timezone whose midnight resets `/daily` for this server.
+///
+/// Run without options to see the current timezone.
+#[poise::command(slash_command, guild_only)]
+pub async fn daily_reset(
+    ctx: Context<'_>,
+    #[description = "IANA timezone name, e.g. Europe/Berlin or America/Los_Angeles (default: UTC)"]
+    timezone: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+    let leveling = &ctx.data().leveling;
+
+    let timezone = match timezone {
+        Some(name) => match leveling.set_daily_timezone(guild_id, name.trim()).await {
+            Ok(tz) => tz,
+            Err(LevelingError::InvalidTimezone(name)) => {
+                ctx.send(
+                    poise::CreateReply::default()
+                        .content(format!(
+                            "❌ `{}` isn't a known timezone. Use an IANA name such as `Europe/Berlin`.",
+                            name
+                        ))
+                        .ephemeral(true),
+                )
+                .await?;
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        },
+        None => leveling.daily_timezone(guild_id).await?,
+    };
+
+    let next_reset = leveling.next_daily_reset(guild_id).await?;
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "🕛 Daily rewards reset at midnight **{}** (next reset <t:{}:R>).",
+                timezone.name(),
+                next_reset.timestamp()
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Locked achievements listed by `/achievements progress`.
+const NEAREST_ACHIEVEMENTS: usize = 5;
+
+/// Show user's achievements
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    subcommands("list", "progress")
+)]
 pub async fn achievements(
     ctx: Context<'_>,
     #[description = "User to check"] member: Option<serenity::User>,
+    #[description = "Only show the reply to you"] private: Option<bool>,
+) -> Result<(), Error> {
+    show_achievements(ctx, member, private).await
+}
+
+/// Show a member's unlocked and locked achievements.
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn list(
+    ctx: Context<'_>,
+    #[description = "User to check"] member: Option<serenity::User>,
+    #[description = "Only show the reply to you"] private: Option<bool>,
+) -> Result<(), Error> {
+    show_achievements(ctx, member, private).await
+}
+
+/// Show the locked achievements you're closest to unlocking.
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn progress(
+    ctx: Context<'_>,
+    #[description = "Only show the reply to you"] private: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let leveling = &ctx.data().leveling;
+    let profile = leveling
+        .get_user_profile(ctx.author().id.get(), guild_id)
+        .await?;
+    let nearest = leveling.get_nearest_achievements(&profile, NEAREST_ACHIEVEMENTS);
+
+    if nearest.is_empty() {
+        ctx.send(reply_base(private).content("You've unlocked all trackable achievements! 🎉"))
+            .await?;
+        return Ok(());
+    }
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title("🎯 Achievement Progress")
+        .description(format!(
+            "Your {} closest achievements to unlock:",
+            nearest.len()
+        ))
+        .color(0x3498db); // Blue
+
+    for (ach, progress, current, target) in nearest {
+        embed = embed.field(
+            format!("{} {} (+{} XP)", ach.emoji, ach.name, ach.reward_xp),
+            format!(
+                "_{}_\n{} {}/{}",
+                ach.description,
+                build_progress_bar(progress, 15),
+                current,
+                target
+            ),
+            false,
+        );
+    }
+
+    ctx.send(reply_base(private).embed(embed)).await?;
+    Ok(())
+}
+
+async fn show_achievements(
+    ctx: Context<'_>,
+    member: Option<serenity::User>,
+    private: Option<bool>,
 ) -> Result<(), Error> {
     let target_user = member.as_ref().unwrap_or_else(|| ctx.author());
     if target_user.bot {
-        ctx.say("Bots don't have achievements").await?;
+        ctx.send(reply_base(private).content("Bots don't have achievements"))
+            .await?;
         return Ok(());
     }
 
@@ -1322,6 +1879,11 @@ pub async fn achievements(
         .guild_id()
         .ok_or("This command only works in servers")?
         .get();
+    let identity = DisplayIdentity::for_user(
+        ctx.serenity_context(),
+        serenity::GuildId::new(guild_id),
+        target_user,
+    );
 
     let profile = ctx
         .data()
@@ -1329,7 +1891,7 @@ pub async fn achievements(
         .get_user_profile(user_id, guild_id)
         .await?;
 
-    let all_achievements = get_all_achievements();
+    let all_achievements = ctx.data().leveling.achievements();
     let earned_ids: Vec<&String> = profile.achievements.iter().collect();
     let earned_count = earned_ids.len();
     let total_count = all_achievements.len();
@@ -1340,7 +1902,7 @@ pub async fn achievements(
     };
 
     let mut embed = serenity::CreateEmbed::new()
-        .title(format!("🏆 {}'s Achievements", target_user.name))
+        .title(format!("🏆 {}'s Achievements", identity.name))
         .description(format!(
             "**{}/{}** achievements unlocked ({:.1}%)\n{}",
             earned_count,
@@ -1349,14 +1911,14 @@ pub async fn achievements(
             build_progress_bar(completion_pct / 100.0, 15)
         ))
         .color(0xffd700) // Gold
-        .thumbnail(target_user.face());
+        .thumbnail(identity.avatar_url);
 
     // Group by category
     let mut by_category: HashMap<String, Vec<String>> = HashMap::new();
 
     // Sort achievements by category then name to ensure consistent order
     // We iterate over all defined achievements to show locked ones too
-    for ach in &all_achievements {
+    for ach in all_achievements {
         let is_earned = profile.achievements.contains(&ach.id);
         let status = if is_earned { "✅" } else { "🔒" };
         let emoji = if is_earned { &ach.emoji } else { "❓" };
@@ -1400,7 +1962,7 @@ pub async fn achievements(
         total_ach_xp
     )));
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    ctx.send(reply_base(private).embed(embed)).await?;
 
     Ok(())
 }
@@ -1543,7 +2105,12 @@ pub async fn prestige(ctx: Context<'_>) -> Result<(), Error> {
 
                 // Manage prestige roles
                 if let Err(e) = manage_prestige_roles(&ctx, guild_id, user_id, prestige_event.new_prestige_level).await {
-                    println!("Failed to update prestige roles: {:?}", e);
+                    tracing::error!(
+                        guild_id,
+                        user_id,
+                        error = ?e,
+                        "Failed to update prestige roles"
+                    );
                 }
 
                 let new_tier = crate::core::leveling::LevelingService::<
@@ -1612,8 +2179,11 @@ pub async fn prestige(ctx: Context<'_>) -> Result<(), Error> {
 }
 
 /// Show the closest achievement you can earn.
-#[poise::command(slash_command, guild_only, aliases("nextach"))]
-pub async fn next_achievement(ctx: Context<'_>) -> Result<(), Error> {
+#[poise::command(slash_command, prefix_command, guild_only, aliases("nextach"))]
+pub async fn next_achievement(
+    ctx: Context<'_>,
+    #[description = "Only show the reply to you"] private: Option<bool>,
+) -> Result<(), Error> {
     let user_id = ctx.author().id.get();
     let guild_id = ctx
         .guild_id()
@@ -1649,9 +2219,9 @@ pub async fn next_achievement(ctx: Context<'_>) -> Result<(), Error> {
             .field("Reward", format!("+{} XP", ach.reward_xp), true)
             .field("Category", ach.category.title(), true);
 
-        ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        ctx.send(reply_base(private).embed(embed)).await?;
     } else {
-        ctx.say("You've unlocked all trackable achievements! 🎉")
+        ctx.send(reply_base(private).content("You've unlocked all trackable achievements! 🎉"))
             .await?;
     }
 
@@ -1714,9 +2284,11 @@ async fn manage_prestige_roles(
     } else {
         // Create the role if it doesn't exist
         let color = get_prestige_color(new_level);
-        println!(
-            "Role '{}' not found. Creating it with color {:06X}...",
-            new_role_name, color
+        tracing::info!(
+            guild_id = guild_id.get(),
+            role = %new_role_name,
+            "Prestige role not found, creating it with color {:06X}",
+            color
         );
         let new_role = guild_id
             .create_role(
@@ -1734,7 +2306,12 @@ async fn manage_prestige_roles(
     // Add the new role
     http.add_member_role(guild_id, user_id, role_to_add_id, Some("Prestige Level Up"))
         .await?;
-    println!("Added role '{}' to user {}", new_role_name, user_id);
+    tracing::info!(
+        guild_id = guild_id.get(),
+        user_id = user_id.get(),
+        role = %new_role_name,
+        "Added prestige role"
+    );
 
     // 2. Identify the old role to remove (if applicable)
     // If they just prestiged to 1, there is no "Prestige 0" role to remove usually.
@@ -1747,7 +2324,12 @@ async fn manage_prestige_roles(
             // Remove the old role
             http.remove_member_role(guild_id, user_id, role.id, Some("Prestige Level Up"))
                 .await?;
-            println!("Removed role '{}' from user {}", old_role_name, user_id);
+            tracing::info!(
+                guild_id = guild_id.get(),
+                user_id = user_id.get(),
+                role = %old_role_name,
+                "Removed old prestige role"
+            );
         }
     }
 
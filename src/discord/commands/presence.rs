@@ -26,7 +26,7 @@ pub fn reset_status(ctx: &serenity::Context) {
 
 /// Called when the bot is ready.
 pub async fn on_ready(ctx: &serenity::Context, data: &crate::discord::Data) {
-    println!("Bot is ready!");
+    tracing::info!("Bot is ready!");
     reset_status(ctx);
 
     // Update server stats channels on startup for all configured guilds
@@ -43,12 +43,16 @@ pub async fn on_ready(ctx: &serenity::Context, data: &crate::discord::Data) {
                     )
                         .await
                 {
-                    eprintln!("Failed to update stats for guild {}: {}", cfg.guild_id, e);
+                    tracing::error!(
+                        guild_id = cfg.guild_id,
+                        error = %e,
+                        "Failed to update server stats on startup"
+                    );
                 }
             }
         }
         Err(e) => {
-            eprintln!("Failed to load server stats configs: {}", e);
+            tracing::error!(error = %e, "Failed to load server stats configs");
         }
     }
 }
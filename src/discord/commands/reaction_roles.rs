@@ -0,0 +1,270 @@
+// Reaction-role commands (`/reactionrole add/remove/list`).
+//
+// Binding an emoji makes the bot react to the message with it, so members can
+// just click the reaction. The roles themselves are handed out by
+// `discord::reaction_roles` when the reaction events arrive.
+
+use crate::core::reactionroles::{AddReactionRole, ReactionRole, MAX_ROLES_PER_MESSAGE};
+use crate::discord::commands::leveling::{Context, Error};
+use crate::discord::reaction_roles::reaction_emoji_key;
+use crate::discord::util::roles::{assignability_problem, grant_problem, send_ephemeral};
+use crate::discord::util::Paginator;
+use poise::serenity_prelude as serenity;
+
+/// Roles members get by reacting to a message.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager",
+    subcommands("add", "remove", "list")
+)]
+pub async fn reactionrole(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Give a role to everyone who reacts to a message with an emoji.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn add(
+    ctx: Context<'_>,
+    #[description = "Message ID or link (an ID alone means this channel)"] message: String,
+    #[description = "Emoji to react with"] emoji: String,
+    #[description = "Role to give"] role: serenity::Role,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("This command only works in servers")?;
+
+    let Some((channel_id, message_id)) = parse_message_ref(&message, ctx.channel_id().get()) else {
+        return send_ephemeral(ctx, "❌ That isn't a message ID or link.".to_string()).await;
+    };
+    let Ok(reaction) = serenity::ReactionType::try_from(emoji.trim()) else {
+        return send_ephemeral(ctx, "❌ That isn't an emoji.".to_string()).await;
+    };
+    let Some(key) = reaction_emoji_key(&reaction) else {
+        return send_ephemeral(ctx, "❌ That isn't an emoji.".to_string()).await;
+    };
+    if role.managed || role.id.get() == guild_id.get() {
+        return send_ephemeral(
+            ctx,
+            "❌ That role is managed by Discord or an integration and can't be handed out."
+                .to_string(),
+        )
+        .await;
+    }
+    if let Some(problem) = grant_problem(ctx, &role).await {
+        return send_ephemeral(ctx, format!("❌ {problem}")).await;
+    }
+    if let Some(problem) = assignability_problem(ctx, role.id.get()).await {
+        return send_ephemeral(ctx, format!("❌ {problem}")).await;
+    }
+
+    let channel = serenity::ChannelId::new(channel_id);
+    let target = match channel.message(ctx, message_id).await {
+        Ok(target) => target,
+        Err(e) => {
+            tracing::debug!(channel_id, message_id, error = %e, "Reaction role message not found");
+            return send_ephemeral(
+                ctx,
+                "❌ Couldn't find that message. Use a message link, or run this in its channel."
+                    .to_string(),
+            )
+            .await;
+        }
+    };
+
+    let outcome = ctx
+        .data()
+        .reaction_roles
+        .add(
+            guild_id.get(),
+            ReactionRole {
+                channel_id,
+                message_id,
+                emoji: key,
+                role_id: role.id.get(),
+            },
+        )
+        .await?;
+    let mut content = match outcome {
+        AddReactionRole::MessageFull => {
+            return send_ephemeral(
+                ctx,
+                format!(
+                    "❌ That message already has {MAX_ROLES_PER_MESSAGE} reaction roles, Discord's limit."
+                ),
+            )
+            .await;
+        }
+        AddReactionRole::Added => format!("✅ Reacting with {reaction} now gives {role}."),
+        AddReactionRole::Replaced(old) => {
+            format!("✅ Reacting with {reaction} now gives {role} instead of <@&{old}>.")
+        }
+    };
+
+    // React ourselves so members only need to click
+    if let Err(e) = target.react(ctx, reaction).await {
+        tracing::warn!(channel_id, message_id, error = %e, "Failed to add reaction role emoji");
+        content.push_str(
+            "\n⚠️ The bot couldn't react to the message (missing permission or unknown emoji). \
+            Members can still add the reaction themselves.",
+        );
+    }
+    send_ephemeral(ctx, content).await
+}
+
+/// Stop giving roles for one emoji on a message, or for all of them.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn remove(
+    ctx: Context<'_>,
+    #[description = "Message ID or link"] message: String,
+    #[description = "Emoji to unbind (empty: every emoji on the message)"] emoji: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let Some((_, message_id)) = parse_message_ref(&message, ctx.channel_id().get()) else {
+        return send_ephemeral(ctx, "❌ That isn't a message ID or link.".to_string()).await;
+    };
+    let key = match emoji.as_deref() {
+        Some(emoji) => {
+            let key = serenity::ReactionType::try_from(emoji.trim())
+                .ok()
+                .and_then(|reaction| reaction_emoji_key(&reaction));
+            let Some(key) = key else {
+                return send_ephemeral(ctx, "❌ That isn't an emoji.".to_string()).await;
+            };
+            Some(key)
+        }
+        None => None,
+    };
+
+    let removed = ctx
+        .data()
+        .reaction_roles
+        .remove(guild_id, message_id, key.as_deref())
+        .await?;
+    let content = match removed {
+        0 => "Nothing to remove: no matching reaction role on that message.".to_string(),
+        1 => "🗑️ Removed 1 reaction role. Roles members already have are kept.".to_string(),
+        n => format!("🗑️ Removed {n} reaction roles. Roles members already have are kept."),
+    };
+    send_ephemeral(ctx, content).await
+}
+
+/// List this server's reaction roles.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("This command only works in servers")?;
+
+    let bindings = ctx.data().reaction_roles.bindings(guild_id.get()).await?;
+    if bindings.is_empty() {
+        return send_ephemeral(
+            ctx,
+            "No reaction roles yet. Use `/reactionrole add`.".to_string(),
+        )
+        .await;
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut current_message = None;
+    for binding in &bindings {
+        if current_message != Some(binding.message_id) {
+            current_message = Some(binding.message_id);
+            lines.push(format!(
+                "**https://discord.com/channels/{}/{}/{}**",
+                guild_id, binding.channel_id, binding.message_id
+            ));
+        }
+        lines.push(format!(
+            "- {} → <@&{}>",
+            display_emoji(&binding.emoji),
+            binding.role_id
+        ));
+    }
+
+    let pages = lines
+        .chunks(LIST_LINES_PER_PAGE)
+        .map(|chunk| {
+            serenity::CreateEmbed::new()
+                .title("Reaction roles")
+                .description(chunk.join("\n"))
+                .color(serenity::Colour::from_rgb(88, 101, 242))
+        })
+        .collect();
+    Paginator::new(0).run_pages(ctx, pages).await
+}
+
+/// Lines shown per `/reactionrole list` page.
+const LIST_LINES_PER_PAGE: usize = 30;
+
+/// `(channel_id, message_id)` from a message link, or from a bare ID in
+/// `current_channel`.
+fn parse_message_ref(input: &str, current_channel: u64) -> Option<(u64, u64)> {
+    let input = input.trim();
+    if let Ok(message_id) = input.parse::<u64>() {
+        return (message_id != 0).then_some((current_channel, message_id));
+    }
+
+    // https://discord.com/channels/<guild>/<channel>/<message>
+    let mut parts = input.trim_end_matches('/').rsplit('/');
+    let message_id = parts.next()?.parse::<u64>().ok()?;
+    let channel_id = parts.next()?.parse::<u64>().ok()?;
+    (parts.next().is_some() && channel_id != 0 && message_id != 0)
+        .then_some((channel_id, message_id))
+}
+
+/// A stored emoji key as it renders in a message: custom emojis are stored by
+/// ID, which Discord renders from `<:_:id>`.
+fn display_emoji(key: &str) -> String {
+    if key.parse::<u64>().is_ok() {
+        format!("<:_:{key}>")
+    } else {
+        key.to_string()
+    }
+}
+
+/// Why the bot couldn't hand out `role_id`, if it couldn't.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::reactionroles::emoji_key;
+
+    #[test]
+    fn test_parse_message_ref() {
+        assert_eq!(parse_message_ref("123", 7), Some((7, 123)));
+        assert_eq!(
+            parse_message_ref("https://discord.com/channels/1/22/333", 7),
+            Some((22, 333))
+        );
+        assert_eq!(
+            parse_message_ref(" https://ptb.discord.com/channels/1/22/333/ ", 7),
+            Some((22, 333))
+        );
+        assert_eq!(parse_message_ref("22/333", 7), None);
+        assert_eq!(parse_message_ref("not a message", 7), None);
+        assert_eq!(parse_message_ref("0", 7), None);
+    }
+
+    #[test]
+    fn test_display_emoji() {
+        assert_eq!(display_emoji("123456"), "<:_:123456>");
+        assert_eq!(display_emoji("🎮"), "🎮");
+        // The stored key round-trips through emoji_key
+        assert_eq!(
+            emoji_key(&display_emoji("123456")).as_deref(),
+            Some("123456")
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::discord::commands::ai::AI_NOT_CONFIGURED;
+use crate::discord::{Context, Error};
+use crate::infra::ai::{load_ai_settings, SettingsSource};
+use poise::serenity_prelude as serenity;
+
+/// Re-read the AI system prompt and reasoning settings without restarting.
+///
+/// Loads the configuration again, with `.env` taking precedence over the
+/// environment the bot started with, and applies only the system prompt and
+/// reasoning options. The model, provider and keys need a restart.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub async fn reload(ctx: Context<'_>) -> Result<(), Error> {
+    let data = ctx.data();
+    let Some(ai) = data.ai.as_deref() else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(format!("{} Nothing to reload.", AI_NOT_CONFIGURED))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let settings = match load_ai_settings(&SettingsSource::with_dotenv()) {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::warn!(error = %e, "Reload failed, keeping current AI settings");
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ {}. Nothing was changed.", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let changes = ai.apply_settings(settings);
+    tracing::info!(
+        user_id = ctx.author().id.get(),
+        ?changes,
+        "Reloaded AI settings"
+    );
+
+    let description = if changes.is_empty() {
+        "Nothing changed.".to_string()
+    } else {
+        changes
+            .iter()
+            .map(|change| format!("• {}", change))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let embed = serenity::CreateEmbed::new()
+        .title("Configuration Reloaded")
+        .description(description)
+        .color(serenity::Color::DARK_GREEN)
+        .footer(serenity::CreateEmbedFooter::new(
+            "Model, provider and API keys still need a restart",
+        ));
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
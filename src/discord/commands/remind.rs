@@ -3,6 +3,7 @@
 // Users can set a reminder with an optional time duration.
 // After the time passes, the bot pings them in the same channel.
 
+use crate::core::util::parse_human_duration;
 use crate::discord::commands::leveling::{Context, Error};
 use poise::serenity_prelude::{self as serenity, Mentionable};
 
@@ -12,25 +13,28 @@ use poise::serenity_prelude::{self as serenity, Mentionable};
 /// - `/remind message:"Take a break" time:"30 minutes"`
 /// - `/remind message:"Check the oven" time:"1 hour"`
 /// - `/remind message:"Meeting tomorrow" time:"1 day"`
+/// - `/remind message:"Stand-up" time:"1h30m"`
 #[poise::command(slash_command, guild_only)]
 pub async fn remind(
     ctx: Context<'_>,
     #[description = "What to remind you about"] message: String,
-    #[description = "When to remind you (e.g. '30 minutes', '2 hours', '1 day')"] time: Option<
+    #[description = "When to remind you (e.g. '30 minutes', '2h', '1h30m', '1 day')"] time: Option<
         String,
     >,
 ) -> Result<(), Error> {
     let duration = match &time {
-        Some(time_str) => match parse_duration(time_str) {
-            Some(dur) => dur,
-            None => {
-                ctx.say(
-                    "Invalid time format. Use formats like:\n\
+        Some(time_str) => match parse_human_duration(time_str) {
+            Ok(dur) => dur,
+            Err(e) => {
+                ctx.say(format!(
+                    "Invalid time: {}. Use formats like:\n\
                     - `30 seconds` or `30s`\n\
                     - `5 minutes` or `5m`\n\
                     - `2 hours` or `2h`\n\
-                    - `1 day` or `1d`",
-                )
+                    - `1 day` or `1d`\n\
+                    - `1h30m` or `1 day 4 hours`",
+                    e
+                ))
                 .await?;
                 return Ok(());
             }
@@ -93,76 +97,6 @@ pub async fn remind(
     Ok(())
 }
 
-/// Parse a duration string like "30 minutes", "2h", "1 day" into a Duration.
-fn parse_duration(input: &str) -> Option<std::time::Duration> {
-    let input = input.trim().to_lowercase();
-
-    // Try to parse formats like "30m", "2h", "1d", "45s"
-    if let Some(duration) = parse_compact_format(&input) {
-        return Some(duration);
-    }
-
-    // Try to parse formats like "30 minutes", "2 hours", "1 day"
-    if let Some(duration) = parse_verbose_format(&input) {
-        return Some(duration);
-    }
-
-    None
-}
-
-/// Parse compact formats like "30m", "2h", "1d", "45s"
-fn parse_compact_format(input: &str) -> Option<std::time::Duration> {
-    let input = input.trim();
-
-    // Check for unit suffix
-    let (num_str, multiplier) = if input.ends_with('s') && !input.ends_with("seconds") {
-        // Could be just "s" for seconds or a number ending in 's'
-        let num_part = input.trim_end_matches('s').trim();
-        // Check if it's a number (not "minute" etc)
-        if num_part.chars().all(|c| c.is_ascii_digit()) {
-            (num_part, 1u64)
-        } else {
-            return None;
-        }
-    } else if input.ends_with('m') && !input.ends_with("minutes") {
-        (input.trim_end_matches('m').trim(), 60)
-    } else if input.ends_with('h') {
-        (input.trim_end_matches('h').trim(), 3600)
-    } else if input.ends_with('d') {
-        (input.trim_end_matches('d').trim(), 86400)
-    } else if input.ends_with('w') {
-        (input.trim_end_matches('w').trim(), 604800)
-    } else {
-        return None;
-    };
-
-    let number: u64 = num_str.parse().ok()?;
-    Some(std::time::Duration::from_secs(number * multiplier))
-}
-
-/// Parse verbose formats like "30 minutes", "2 hours", "1 day"
-fn parse_verbose_format(input: &str) -> Option<std::time::Duration> {
-    let parts: Vec<&str> = input.split_whitespace().collect();
-
-    if parts.len() != 2 {
-        return None;
-    }
-
-    let number: u64 = parts[0].parse().ok()?;
-    let unit = parts[1];
-
-    let multiplier = match unit {
-        "second" | "seconds" | "sec" | "secs" => 1,
-        "minute" | "minutes" | "min" | "mins" => 60,
-        "hour" | "hours" | "hr" | "hrs" => 3600,
-        "day" | "days" => 86400,
-        "week" | "weeks" => 604800,
-        _ => return None,
-    };
-
-    Some(std::time::Duration::from_secs(number * multiplier))
-}
-
 /// Format a Duration into a human-readable string
 fn format_duration(duration: std::time::Duration) -> String {
     let total_secs = duration.as_secs();
@@ -1,4 +1,4 @@
-use crate::core::server_stats::ServerStatsConfig;
+use crate::core::server_stats::{GuildActivity, ServerStatsConfig};
 use crate::discord::{Context, Data, Error};
 use poise::serenity_prelude as serenity;
 
@@ -6,15 +6,19 @@ use poise::serenity_prelude as serenity;
 #[poise::command(
     slash_command,
     guild_only,
-    required_permissions = "ADMINISTRATOR",
-    subcommands("setup", "remove", "status")
+    check = "crate::discord::access::is_manager",
+    subcommands("setup", "remove", "status", "activity")
 )]
 pub async fn serverstats(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
 /// Set up server stats channels
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn setup(ctx: Context<'_>) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be in a guild")?;
     let guild = ctx.guild().ok_or("Guild not found")?.clone();
@@ -128,7 +132,11 @@ pub async fn setup(ctx: Context<'_>) -> Result<(), Error> {
 }
 
 /// Remove server stats channels
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn remove(ctx: Context<'_>) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be in a guild")?;
 
@@ -155,7 +163,12 @@ pub async fn remove(ctx: Context<'_>) -> Result<(), Error> {
     for channel_id in channels {
         if let Ok(channel) = serenity::ChannelId::new(channel_id).to_channel(&ctx).await {
             if let Err(e) = channel.delete(&ctx).await {
-                println!("Failed to delete channel {}: {}", channel_id, e);
+                tracing::warn!(
+                    guild_id = guild_id.get(),
+                    channel_id,
+                    error = %e,
+                    "Failed to delete server stats channel"
+                );
             }
         }
     }
@@ -173,7 +186,11 @@ pub async fn remove(ctx: Context<'_>) -> Result<(), Error> {
 }
 
 /// Show server stats status
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be in a guild")?;
 
@@ -223,6 +240,115 @@ pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ActivityView {
+    #[name = "Hour of day"]
+    Hourly,
+    #[name = "Day of week"]
+    Weekly,
+}
+
+/// Below this many messages the chart is mostly noise.
+const MIN_MESSAGES_FOR_CHART: u64 = 20;
+
+/// Width (in characters) of the longest bar.
+const CHART_BAR_WIDTH: usize = 20;
+
+/// Show when the server is most active
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn activity(
+    ctx: Context<'_>,
+    #[description = "Group messages by hour of day or day of week"] view: Option<ActivityView>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be in a guild")?;
+    let activity = ctx.data().server_stats.activity(guild_id.get());
+
+    if activity.total() == 0 {
+        ctx.say("📭 No messages recorded yet. Activity is tracked from the moment the bot starts, check back in a little while!")
+            .await?;
+        return Ok(());
+    }
+
+    let view = view.unwrap_or(ActivityView::Hourly);
+    let (title, chart) = render_activity_chart(&activity, view);
+
+    let mut embed = serenity::CreateEmbed::default()
+        .title(title)
+        .color(0x3498db)
+        .description(format!("```\n{}```", chart))
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "{} messages over {} day(s) • times in UTC • resets when the bot restarts",
+            activity.total(),
+            activity.days_tracked()
+        )));
+
+    if activity.total() < MIN_MESSAGES_FOR_CHART {
+        embed = embed.field(
+            "Not much data yet",
+            "The chart will become more meaningful as more messages come in.",
+            false,
+        );
+    }
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+fn render_activity_chart(activity: &GuildActivity, view: ActivityView) -> (&'static str, String) {
+    match view {
+        ActivityView::Hourly => {
+            let labels: Vec<String> = (0..24).map(|hour| format!("{:02}:00", hour)).collect();
+            (
+                "📊 Messages by Hour of Day",
+                render_bar_chart(&labels, &activity.by_hour_of_day()),
+            )
+        }
+        ActivityView::Weekly => {
+            let labels: Vec<String> = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+                .iter()
+                .map(|day| day.to_string())
+                .collect();
+            (
+                "📊 Messages by Day of Week",
+                render_bar_chart(&labels, &activity.by_weekday()),
+            )
+        }
+    }
+}
+
+/// Render `label ███████ count` rows, scaled so the busiest row fills `CHART_BAR_WIDTH`.
+fn render_bar_chart(labels: &[String], values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+
+    let mut chart = String::new();
+    for (label, value) in labels.iter().zip(values) {
+        let mut width = (*value as f64 / max as f64 * CHART_BAR_WIDTH as f64).round() as usize;
+        // Keep non-zero rows visible even next to a very busy one
+        if *value > 0 && width == 0 {
+            width = 1;
+        }
+        let bar = format!(
+            "{}{}",
+            "█".repeat(width),
+            "·".repeat(CHART_BAR_WIDTH - width)
+        );
+        chart.push_str(&format!(
+            "{:<label_width$} {} {}\n",
+            label,
+            bar,
+            value,
+            label_width = label_width
+        ));
+    }
+    chart
+}
+
 /// Event that triggered the stats update
 pub enum StatsUpdateEvent<'a> {
     GuildUpdate(&'a serenity::PartialGuild),
@@ -231,32 +357,18 @@ pub enum StatsUpdateEvent<'a> {
     None,
 }
 
-/// Helper function to update stats for a guild
-pub async fn update_guild_stats(
-    ctx: &serenity::Context,
-    data: &Data,
-    guild_id: serenity::GuildId,
-    event: StatsUpdateEvent<'_>,
-) -> Result<(), Error> {
-    let config = match data.server_stats.get_config(guild_id.get()).await? {
-        Some(c) => c,
-        None => return Ok(()), // Not configured
-    };
-
-    if !config.enabled {
-        return Ok(());
-    }
-
-    // Fetch guild to get latest counts
-    // We use the cache if possible, or fetch from API
-    let guild = if let Some(g) = guild_id.to_guild_cached(&ctx.cache) {
-        g.clone()
-    } else {
-        // If not in cache, we can't easily get member list without fetching
-        // For now, just return if not in cache (it should be if we received an event)
-        return Ok(());
-    };
+/// Member, bot and boost counts for a guild, as shown in the stats channels.
+pub struct GuildCounts {
+    pub total_members: u64,
+    pub members: usize,
+    pub bots: usize,
+    pub boosts: u64,
+}
 
+/// Count a cached guild's members, correcting for `event` when the cache
+/// hasn't caught up with it yet. Also used for `{member_count}` in welcome
+/// messages.
+pub fn guild_counts(guild: &serenity::Guild, event: &StatsUpdateEvent<'_>) -> GuildCounts {
     let mut total_members = guild.member_count;
     let mut bots_count = guild.members.values().filter(|m| m.user.bot).count();
     let mut boosts = guild.premium_subscription_count.unwrap_or(0);
@@ -297,6 +409,47 @@ pub async fn update_guild_stats(
         guild.members.values().filter(|m| !m.user.bot).count()
     };
 
+    GuildCounts {
+        total_members,
+        members: members_count,
+        bots: bots_count,
+        boosts,
+    }
+}
+
+/// Helper function to update stats for a guild
+pub async fn update_guild_stats(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    event: StatsUpdateEvent<'_>,
+) -> Result<(), Error> {
+    let config = match data.server_stats.get_config(guild_id.get()).await? {
+        Some(c) => c,
+        None => return Ok(()), // Not configured
+    };
+
+    if !config.enabled {
+        return Ok(());
+    }
+
+    // Fetch guild to get latest counts
+    // We use the cache if possible, or fetch from API
+    let guild = if let Some(g) = guild_id.to_guild_cached(&ctx.cache) {
+        g.clone()
+    } else {
+        // If not in cache, we can't easily get member list without fetching
+        // For now, just return if not in cache (it should be if we received an event)
+        return Ok(());
+    };
+
+    let GuildCounts {
+        total_members,
+        members: members_count,
+        bots: bots_count,
+        boosts,
+    } = guild_counts(&guild, &event);
+
     // Update channels
     let _ = serenity::ChannelId::new(config.total_members_channel_id)
         .edit(
@@ -328,3 +481,22 @@ pub async fn update_guild_stats(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_chart_scales_to_busiest_row() {
+        let labels = vec!["Mon".to_string(), "Tue".to_string(), "Wed".to_string()];
+        let chart = render_bar_chart(&labels, &[10, 1, 0]);
+        let rows: Vec<&str> = chart.lines().collect();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].matches('█').count(), CHART_BAR_WIDTH);
+        assert!(rows[0].ends_with(" 10"));
+        // Small but non-zero rows still get a visible bar
+        assert_eq!(rows[1].matches('█').count(), 2);
+        assert_eq!(rows[2].matches('█').count(), 0);
+    }
+}
@@ -1,7 +1,7 @@
 // Discord commands for the shop system
 
-use crate::core::economy::{ItemId, ShopItem};
-use poise::serenity_prelude as serenity;
+use crate::core::economy::{EconomyError, ItemEffect, ItemId, ShopItem};
+use poise::serenity_prelude::{self as serenity, Mentionable};
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Context<'a> = poise::Context<'a, crate::discord::commands::leveling::Data, Error>;
@@ -114,7 +114,7 @@ pub async fn buy(
 }
 
 /// Show your inventory
-#[poise::command(slash_command, guild_only)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn inventory(ctx: Context<'_>) -> Result<(), Error> {
     let user = ctx.author();
     let user_id = user.id.get();
@@ -149,6 +149,24 @@ pub async fn inventory(ctx: Context<'_>) -> Result<(), Error> {
         .title(format!("🎒 {}'s Inventory", user.name))
         .color(0x5865F2); // Blurple
 
+    // The running boost first, then the ones queued behind it
+    let boosts = ctx.data().leveling.xp_boosts(user_id, guild_id).await?;
+    if !boosts.is_empty() {
+        let lines: Vec<String> = boosts
+            .iter()
+            .enumerate()
+            .map(|(i, boost)| {
+                format!(
+                    "{}{}x message XP, ends <t:{}:R>",
+                    if i == 0 { "" } else { "then " },
+                    boost.multiplier,
+                    boost.expires_at.timestamp()
+                )
+            })
+            .collect();
+        embed = embed.field("✨ Active Effects", lines.join("\n"), false);
+    }
+
     for (item_id, count) in item_counts {
         let shop_item = ShopItem::get(item_id);
         let field_value = format!(
@@ -162,6 +180,172 @@ pub async fn inventory(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Use an item from your inventory
+#[poise::command(slash_command, prefix_command, guild_only, rename = "use")]
+pub async fn use_item(
+    ctx: Context<'_>,
+    #[description = "Item to use"]
+    #[autocomplete = "autocomplete_usable_items"]
+    item_name: String,
+) -> Result<(), Error> {
+    let user_id = ctx.author().id.get();
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let item_id =
+        ItemId::from_str(&item_name).ok_or_else(|| format!("Unknown item: {}", item_name))?;
+    let item = ShopItem::get(&item_id);
+
+    let data = ctx.data();
+    let used = match data
+        .inventory
+        .use_item(user_id, guild_id, &item_id, &*data.leveling)
+        .await
+    {
+        Ok(used) => used,
+        Err(EconomyError::ItemNotOwned) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!(
+                        "You don't have a {} **{}**. Buy one with `/shop buy {}`.",
+                        item.emoji,
+                        item.name,
+                        item.id.as_str()
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(EconomyError::ItemNotUsable) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!(
+                        "{} **{}** works on its own, there's no need to use it.",
+                        item.emoji, item.name
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let effect = match used.effect {
+        ItemEffect::XpBoost { multiplier, .. } => format!("**{}x** XP from messages", multiplier),
+        ItemEffect::StreakFreeze => "Your daily streak is protected".to_string(),
+    };
+    let description = match used.active_until {
+        Some(until) => format!("{}, ends <t:{}:R>.", effect, until.timestamp()),
+        None => format!("{}.", effect),
+    };
+
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("{} {} used!", item.emoji, item.name))
+        .description(description)
+        .color(0x00FF00); // Green
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Give items from your inventory to another member
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn gift(
+    ctx: Context<'_>,
+    #[description = "Who to give the items to"] user: serenity::User,
+    #[description = "Item to give"]
+    #[autocomplete = "autocomplete_items"]
+    item_name: String,
+    #[description = "How many to give (default 1)"]
+    #[min = 1]
+    #[max = 100]
+    quantity: Option<u32>,
+) -> Result<(), Error> {
+    let quantity = quantity.unwrap_or(1);
+    let guild_id = ctx
+        .guild_id()
+        .ok_or("This command only works in servers")?
+        .get();
+
+    let refusal = if user.bot {
+        Some("Bots don't need items.")
+    } else if user.id == ctx.author().id {
+        Some("You can't gift items to yourself.")
+    } else {
+        None
+    };
+    if let Some(refusal) = refusal {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(refusal)
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let item_id =
+        ItemId::from_str(&item_name).ok_or_else(|| format!("Unknown item: {}", item_name))?;
+    let item = ShopItem::get(&item_id);
+
+    match ctx
+        .data()
+        .inventory
+        .gift_items(
+            ctx.author().id.get(),
+            user.id.get(),
+            guild_id,
+            &item_id,
+            quantity,
+        )
+        .await
+    {
+        Ok(()) => {}
+        Err(EconomyError::NotEnoughItems { available, .. }) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!(
+                        "You only have **{}** {} **{}**.",
+                        available, item.emoji, item.name
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let embed = serenity::CreateEmbed::new()
+        .title("🎁 Gift Sent!")
+        .description(format!(
+            "{} gave {} **{}x** {} **{}**.",
+            ctx.author().mention(),
+            user.mention(),
+            quantity,
+            item.emoji,
+            item.name
+        ))
+        .color(0x00FF00); // Green
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Autocomplete for `/use`: items that can be used by hand
+async fn autocomplete_usable_items<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    autocomplete_items(ctx, partial)
+        .await
+        .filter(|id| ItemId::from_str(id).is_some_and(|id| id.effect().is_usable()))
+}
+
 /// Autocomplete function for item names
 async fn autocomplete_items<'a>(
     _ctx: Context<'_>,
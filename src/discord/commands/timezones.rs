@@ -2,7 +2,7 @@ use crate::discord::{Context, Error};
 use poise::serenity_prelude as serenity;
 
 /// Display the current time across the core team timezones.
-#[poise::command(slash_command, aliases("tz", "times"))]
+#[poise::command(slash_command, prefix_command, aliases("tz", "times"))]
 pub async fn timezones(ctx: Context<'_>) -> Result<(), Error> {
     let timezones = ctx.data().timezones.get_team_timezones();
 
@@ -0,0 +1,98 @@
+// Per-command cooldowns from `[cooldowns]` in the config.
+//
+// poise only reads cooldowns from the command attribute, which would hard-code
+// them, so they're written into the built commands at startup instead. When a
+// cooldown is hit, `error_handler` tells the user how long to wait.
+
+use crate::discord::{Data, Error};
+use crate::infra::config::CooldownSettings;
+use std::time::Duration;
+
+/// Set the configured cooldowns on `commands` and their subcommands. Returns
+/// the configured names that match no command, so typos can be reported.
+pub fn apply_cooldowns(
+    commands: &mut [poise::Command<Data, Error>],
+    settings: &CooldownSettings,
+) -> Vec<String> {
+    let mut applied = Vec::new();
+    apply_with_prefix(commands, "", settings, &mut applied);
+
+    settings
+        .user_secs
+        .keys()
+        .chain(settings.channel_secs.keys())
+        .filter(|name| !applied.contains(name))
+        .cloned()
+        .collect()
+}
+
+fn apply_with_prefix(
+    commands: &mut [poise::Command<Data, Error>],
+    parents: &str,
+    settings: &CooldownSettings,
+    applied: &mut Vec<String>,
+) {
+    for command in commands {
+        let name = if parents.is_empty() {
+            command.name.clone()
+        } else {
+            format!("{} {}", parents, command.name)
+        };
+
+        let user = settings.user_secs.get(&name);
+        let channel = settings.channel_secs.get(&name);
+        if user.is_some() || channel.is_some() {
+            let mut config = command
+                .cooldown_config
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            // 0 turns a cooldown off
+            let seconds = |secs: Option<&u64>| {
+                secs.filter(|&&secs| secs > 0)
+                    .map(|&secs| Duration::from_secs(secs))
+            };
+            config.user = seconds(user);
+            config.channel = seconds(channel);
+            applied.push(name.clone());
+        }
+
+        apply_with_prefix(&mut command.subcommands, &name, settings, applied);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_cooldowns_reach_commands_and_subcommands() {
+        let mut commands = vec![
+            crate::discord::commands::leveling::leaderboard(),
+            crate::discord::commands::reaction_roles::reactionrole(),
+        ];
+        let settings = CooldownSettings {
+            user_secs: BTreeMap::from([
+                ("leaderboard".to_string(), 10),
+                ("reactionrole list".to_string(), 0),
+                ("leaderbord".to_string(), 5),
+            ]),
+            channel_secs: BTreeMap::from([("reactionrole list".to_string(), 3)]),
+        };
+
+        let unknown = apply_cooldowns(&mut commands, &settings);
+        assert_eq!(unknown, vec!["leaderbord".to_string()]);
+
+        let leaderboard = commands[0].cooldown_config.read().unwrap().clone();
+        assert_eq!(leaderboard.user, Some(Duration::from_secs(10)));
+        assert_eq!(leaderboard.channel, None);
+
+        let list = &commands[1].subcommands[2];
+        assert_eq!(list.name, "list");
+        let list = list.cooldown_config.read().unwrap().clone();
+        assert_eq!(list.user, None);
+        assert_eq!(list.channel, Some(Duration::from_secs(3)));
+        // The parent isn't throttled by its subcommand's cooldown
+        assert_eq!(commands[1].cooldown_config.read().unwrap().user, None);
+    }
+}
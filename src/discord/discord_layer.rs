@@ -6,18 +6,57 @@ pub mod commands;
 #[path = "leveling/leveling_announcements.rs"]
 pub mod leveling_announcements;
 
+#[path = "leveling/boosters.rs"]
+pub mod boosters;
+
 #[path = "logging/mod.rs"]
 pub mod logging;
 
+#[cfg(feature = "github")]
 #[path = "github/mod.rs"]
 pub mod github;
 
+#[cfg(feature = "ai")]
 #[path = "ai/mod.rs"]
 pub mod ai;
 
+#[cfg(feature = "moderation")]
 #[path = "moderation/mod.rs"]
 pub mod moderation;
 
+#[path = "error_handler.rs"]
+pub mod error_handler;
+
+#[path = "cooldowns.rs"]
+pub mod cooldowns;
+
+#[path = "prefix_commands.rs"]
+pub mod prefix_commands;
+
+#[path = "i18n.rs"]
+pub mod i18n;
+
+#[path = "user_identity.rs"]
+pub mod user_identity;
+
+#[path = "access.rs"]
+pub mod access;
+
+#[path = "auto_role.rs"]
+pub mod auto_role;
+
+#[path = "reaction_roles.rs"]
+pub mod reaction_roles;
+
+#[path = "welcome.rs"]
+pub mod welcome;
+
+#[path = "birthdays.rs"]
+pub mod birthdays;
+
+#[path = "util/mod.rs"]
+pub mod util;
+
 // Re-export command types for convenience
 pub use commands::leveling::Context;
 pub use commands::leveling::{Data, Error};
@@ -0,0 +1,155 @@
+// Central error handler for the poise framework.
+//
+// Without this, command errors either surface as raw error strings to the user
+// (poise's default) or get lost in the console. This handler:
+// - replies to the user with a friendly, generic message (no internal details)
+// - records the failure in the command audit log
+// - optionally posts a detailed embed to a bot-owner/log channel
+//
+// The log channel is configured with `ERROR_LOG_CHANNEL_ID`.
+
+use crate::discord::logging::events as logging_events;
+use crate::discord::{Context, Data, Error};
+use poise::serenity_prelude as serenity;
+
+/// Message shown to users when a command fails unexpectedly.
+const USER_FACING_ERROR: &str = "⚠️ Something went wrong while running that command.";
+
+/// The same, once the error has been posted to the log channel.
+const USER_FACING_ERROR_REPORTED: &str =
+    "⚠️ Something went wrong while running that command. The error has been reported.";
+
+/// Entry point used by `FrameworkOptions::on_error`.
+pub async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
+    match error {
+        poise::FrameworkError::Command { error, ctx, .. } => {
+            let error_text = error.to_string();
+
+            // The log gets the whole chain, e.g. the sqlx error behind a
+            // storage error; the audit log and report keep the short text
+            tracing::error!(
+                command = %ctx.command().qualified_name,
+                user_id = ctx.author().id.get(),
+                guild_id = ctx.guild_id().map(|g| g.get()),
+                error = %crate::core::util::ErrorChain(error.as_ref()),
+                "Command failed"
+            );
+
+            logging_events::record_command_audit(ctx, Some(error_text.clone())).await;
+
+            // Only tell the user it was reported if it actually was
+            let content = if report_to_log_channel(ctx, &error_text).await {
+                USER_FACING_ERROR_REPORTED
+            } else {
+                USER_FACING_ERROR
+            };
+            if let Err(e) = ctx
+                .send(
+                    poise::CreateReply::default()
+                        .content(content)
+                        .ephemeral(true),
+                )
+                .await
+            {
+                tracing::warn!("Failed to send error reply: {}", e);
+            }
+        }
+        // A check said no (e.g. `access::is_manager`). Checks reply to the
+        // user themselves, and a denied member isn't a bot error.
+        poise::FrameworkError::CommandCheckFailed {
+            error: None, ctx, ..
+        } => {
+            tracing::debug!(
+                command = %ctx.command().qualified_name,
+                user_id = ctx.author().id.get(),
+                "Command check denied"
+            );
+        }
+        // Cooldowns from `[cooldowns]`: say when the command works again
+        poise::FrameworkError::CooldownHit {
+            remaining_cooldown,
+            ctx,
+            ..
+        } => {
+            tracing::debug!(
+                command = %ctx.command().qualified_name,
+                user_id = ctx.author().id.get(),
+                remaining_secs = remaining_cooldown.as_secs(),
+                "Command cooldown hit"
+            );
+            // Round up, so it never says "0s"
+            let seconds =
+                remaining_cooldown.as_secs() + u64::from(remaining_cooldown.subsec_nanos() > 0);
+            let content = format!(
+                "⏳ Slow down! Try `/{}` again in {}s.",
+                ctx.command().qualified_name,
+                seconds
+            );
+            if let Err(e) = ctx
+                .send(
+                    poise::CreateReply::default()
+                        .content(content)
+                        .ephemeral(true),
+                )
+                .await
+            {
+                tracing::warn!("Failed to send cooldown reply: {}", e);
+            }
+        }
+        // Permission checks, argument parsing etc. already produce
+        // user-friendly messages, so let poise handle them.
+        other => {
+            if let Err(e) = poise::builtins::on_error(other).await {
+                tracing::error!("Error while handling error: {}", e);
+            }
+        }
+    }
+}
+
+/// Post a detailed error embed to the configured error log channel (if any).
+/// Returns whether the report was posted.
+async fn report_to_log_channel(ctx: Context<'_>, error_text: &str) -> bool {
+    let Some(channel_id) = ctx.data().error_log_channel_id else {
+        return false;
+    };
+
+    let guild = ctx
+        .guild_id()
+        .map(|id| id.get().to_string())
+        .unwrap_or_else(|| "DM".to_string());
+
+    // Embed field values are capped at 1024 characters.
+    let error_display = crate::core::util::truncate_chars(error_text, 1000);
+
+    let embed = serenity::CreateEmbed::new()
+        .title("Command Error")
+        .color(serenity::Colour::RED)
+        .field(
+            "Command",
+            format!("`/{}`", ctx.command().qualified_name),
+            true,
+        )
+        .field(
+            "User",
+            format!("{} (`{}`)", ctx.author().name, ctx.author().id),
+            true,
+        )
+        .field("Guild", guild, true)
+        .field("Error", format!("```{}```", error_display), false)
+        .timestamp(serenity::Timestamp::now());
+
+    match serenity::ChannelId::new(channel_id)
+        .send_message(ctx.http(), serenity::CreateMessage::new().embed(embed))
+        .await
+    {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to post error report to channel {}: {}",
+                channel_id,
+                e
+            );
+            false
+        }
+    }
+}
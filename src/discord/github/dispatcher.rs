@@ -1,18 +1,164 @@
-use crate::core::github::{GithubEvent, GithubUpdate, IssueActivity};
+use crate::core::github::{
+    DigestCommit, GithubClient, GithubConfigStore, GithubError, GithubEvent, GithubPoll,
+    GithubService, GithubUpdate, IssueActivity,
+};
 use poise::serenity_prelude as serenity;
 
+/// Post a poll's updates (retrying transient failures), then commit the
+/// watermarks. A repository whose update failed keeps its watermark just
+/// before that update, so only it and what came after are reported again next
+/// poll.
+pub async fn dispatch_poll<C, S>(
+    http: &serenity::Http,
+    github: &GithubService<C, S>,
+    poll: GithubPoll,
+) -> Result<(), GithubError>
+where
+    C: GithubClient,
+    S: GithubConfigStore,
+{
+    let GithubPoll {
+        updates,
+        mut watermarks,
+    } = poll;
+
+    for failed in send_updates(http, updates).await {
+        watermarks.hold_back(&failed);
+    }
+    github.commit_watermarks(watermarks).await
+}
+
+/// Pauses before each retry of updates that failed to post. Discord errors
+/// such as rate limits and outages usually clear within seconds.
+const RETRY_DELAYS: [std::time::Duration; 3] = [
+    std::time::Duration::from_secs(2),
+    std::time::Duration::from_secs(5),
+    std::time::Duration::from_secs(15),
+];
+
 /// Send all GitHub updates to their target channels with user-friendly embeds.
-pub async fn send_updates(http: &serenity::Http, updates: Vec<GithubUpdate>) {
+///
+/// Updates that fail with a transient error (rate limit, Discord outage,
+/// network) are queued and retried with backoff. Returns the ones that still
+/// could not be posted, in order, so their watermarks are held back and the next poll
+/// reports them again. Updates Discord rejects outright (deleted channel,
+/// missing permissions) are logged and dropped: retrying can't fix those.
+pub async fn send_updates(http: &serenity::Http, updates: Vec<GithubUpdate>) -> Vec<GithubUpdate> {
+    let mut queue = send_batch(http, updates).await;
+    for delay in RETRY_DELAYS {
+        if queue.is_empty() {
+            break;
+        }
+        tracing::info!(
+            pending = queue.len(),
+            "Retrying GitHub updates in {}s",
+            delay.as_secs()
+        );
+        tokio::time::sleep(delay).await;
+        queue = send_batch(http, queue).await;
+    }
+
+    for update in &queue {
+        tracing::error!(
+            guild_id = update.guild_id,
+            channel_id = update.channel_id,
+            event = %describe_event(&update.event),
+            "Giving up on GitHub update for now, it will be reported again next poll"
+        );
+    }
+    queue
+}
+
+/// Post each update once, in order. Returns the ones worth retrying.
+///
+/// Once an update fails, the rest of its repository's updates are held back
+/// with it so they still go out in order and the watermark can stop at it.
+async fn send_batch(http: &serenity::Http, updates: Vec<GithubUpdate>) -> Vec<GithubUpdate> {
+    let mut retry = Vec::new();
+    let mut stopped = std::collections::HashSet::new();
     for update in updates {
-        if let Err(err) = send_single(http, &update).await {
+        let keys: Vec<_> = update
+            .repositories()
+            .map(|(owner, repo)| {
+                (
+                    update.guild_id,
+                    owner.to_ascii_lowercase(),
+                    repo.to_string(),
+                )
+            })
+            .collect();
+        if keys.iter().any(|key| stopped.contains(key)) {
+            stopped.extend(keys);
+            retry.push(update);
+            continue;
+        }
+
+        let Err(err) = send_single(http, &update).await else {
+            continue;
+        };
+        if is_transient(&err) {
             tracing::warn!(
                 guild_id = update.guild_id,
                 channel_id = update.channel_id,
                 error = %err,
-                "Failed to send GitHub update"
+                "Failed to send GitHub update, will retry"
+            );
+            stopped.extend(keys);
+            retry.push(update);
+        } else {
+            tracing::error!(
+                guild_id = update.guild_id,
+                channel_id = update.channel_id,
+                event = %describe_event(&update.event),
+                error = %err,
+                "GitHub update permanently failed and was dropped"
             );
         }
     }
+    retry
+}
+
+/// Whether a failed send may succeed if tried again later.
+fn is_transient(err: &serenity::Error) -> bool {
+    match err {
+        serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)) => {
+            is_transient_status(response.status_code.as_u16())
+        }
+        serenity::Error::Http(serenity::HttpError::Request(_)) | serenity::Error::Io(_) => true,
+        _ => false,
+    }
+}
+
+/// Rate limits and server errors are temporary; other 4xx responses mean the
+/// request itself is rejected.
+fn is_transient_status(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+/// Short description of an event for logs, e.g. `owner/repo commit 0123456 on main`.
+fn describe_event(event: &GithubEvent) -> String {
+    match event {
+        GithubEvent::CommitPushed {
+            owner,
+            repo,
+            branch,
+            commit,
+        } => format!(
+            "{owner}/{repo} commit {} on {branch}",
+            commit.sha.chars().take(7).collect::<String>()
+        ),
+        GithubEvent::BugClosed { owner, repo, issue } => {
+            format!("{owner}/{repo} bug #{} closed", issue.number)
+        }
+        GithubEvent::IssueActivity {
+            owner, repo, issue, ..
+        } => {
+            format!("{owner}/{repo} issue #{}", issue.number)
+        }
+        GithubEvent::CommitDigest { owner, commits } => {
+            format!("{owner} digest of {} commits", commits.len())
+        }
+    }
 }
 
 async fn send_single(
@@ -20,7 +166,18 @@ async fn send_single(
     update: &GithubUpdate,
 ) -> Result<(), serenity::Error> {
     let channel_id = serenity::ChannelId::new(update.channel_id);
-    let embed = match &update.event {
+    channel_id
+        .send_message(
+            http,
+            serenity::CreateMessage::new().embed(build_embed(&update.event)),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Build the notification embed for an event, exactly as it is posted.
+pub fn build_embed(event: &GithubEvent) -> serenity::CreateEmbed {
+    match event {
         GithubEvent::CommitPushed {
             owner,
             repo,
@@ -36,12 +193,8 @@ async fn send_single(
             issue,
             activity,
         } => build_issue_embed(owner, repo, issue, *activity),
-    };
-
-    channel_id
-        .send_message(http, serenity::CreateMessage::new().embed(embed))
-        .await?;
-    Ok(())
+        GithubEvent::CommitDigest { owner, commits } => build_digest_embed(owner, commits),
+    }
 }
 
 fn build_commit_embed(
@@ -69,9 +222,9 @@ fn build_commit_embed(
         .footer(serenity::CreateEmbedFooter::new(format!("{owner}/{repo}")));
 
     if let Some(avatar) = &commit.avatar_url {
-        embed = embed.author(
-            serenity::CreateEmbedAuthor::new(&commit.author_name).icon_url(avatar.clone()),
-        );
+        embed = embed
+            .author(serenity::CreateEmbedAuthor::new(&commit.author_name).icon_url(avatar.clone()))
+            .thumbnail(avatar.clone());
     } else {
         embed = embed.author(serenity::CreateEmbedAuthor::new(&commit.author_name));
     }
@@ -80,9 +233,82 @@ fn build_commit_embed(
         embed = embed.field("Committed at", committed_at, true);
     }
 
+    // Only present when the service fetches commit stats (`GITHUB_COMMIT_STATS`)
+    if let Some(stats) = commit.stats {
+        let files = if stats.files_changed == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        embed = embed.field(
+            "Changes",
+            format!(
+                "{} {files} · `+{}` / `-{}`",
+                stats.files_changed, stats.additions, stats.deletions
+            ),
+            true,
+        );
+    }
+
     embed
 }
 
+/// Room left in a digest's description for the "... and N more" line; Discord
+/// caps embed descriptions at 4096 characters.
+const DIGEST_DESCRIPTION_LIMIT: usize = 4000;
+
+fn build_digest_embed(owner: &str, commits: &[DigestCommit]) -> serenity::CreateEmbed {
+    let single_repo = commits
+        .first()
+        .map(|first| first.repo.as_str())
+        .filter(|repo| commits.iter().all(|c| c.repo == *repo));
+    let single_branch = commits
+        .first()
+        .map(|first| first.branch.as_str())
+        .filter(|branch| single_repo.is_some() && commits.iter().all(|c| c.branch == *branch));
+    let count = commits.len();
+    let noun = if count == 1 { "commit" } else { "commits" };
+    let (title, footer) = match (single_repo, single_branch) {
+        (Some(repo), Some(branch)) => (
+            format!("[{repo}:{branch}] {count} new {noun}"),
+            format!("{owner}/{repo}"),
+        ),
+        (Some(repo), None) => (
+            format!("[{repo}] {count} new {noun}"),
+            format!("{owner}/{repo}"),
+        ),
+        (None, _) => (format!("[{owner}] {count} new {noun}"), owner.to_string()),
+    };
+
+    let mut description = String::new();
+    for (listed, entry) in commits.iter().enumerate() {
+        let commit = &entry.commit;
+        let short_sha = commit.sha.chars().take(7).collect::<String>();
+        let first_line = commit.message.lines().next().unwrap_or("No commit message");
+        let location = match (single_repo, single_branch) {
+            (Some(_), Some(_)) => String::new(),
+            (Some(_), None) => format!("`{}` ", entry.branch),
+            (None, _) => format!("`{}:{}` ", entry.repo, entry.branch),
+        };
+        let line = format!(
+            "{location}[`{short_sha}`]({}) {first_line} - {}\n",
+            commit.html_url, commit.author_name
+        );
+        if description.chars().count() + line.chars().count() > DIGEST_DESCRIPTION_LIMIT {
+            description.push_str(&format!("... and {} more", count - listed));
+            break;
+        }
+        description.push_str(&line);
+    }
+
+    serenity::CreateEmbed::new()
+        .title(title)
+        .description(description.trim_end())
+        .color(serenity::Colour::from_rgb(88, 101, 242))
+        .timestamp(serenity::Timestamp::now())
+        .footer(serenity::CreateEmbedFooter::new(footer))
+}
+
 fn build_bug_embed(
     owner: &str,
     repo: &str,
@@ -166,3 +392,212 @@ fn build_issue_embed(
 fn format_dt(dt: Option<chrono::DateTime<chrono::Utc>>) -> Option<String> {
     dt.map(|d| format!("<t:{}:F>", d.timestamp()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::github::{Commit, CommitStats, Issue, IssueState};
+    use chrono::DateTime;
+
+    /// The value of the embed field called `name`.
+    fn field<'a>(embed: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+        embed["fields"]
+            .as_array()?
+            .iter()
+            .find(|f| f["name"] == name)?["value"]
+            .as_str()
+    }
+
+    fn issue(state: IssueState) -> Issue {
+        let at = DateTime::from_timestamp(1_700_000_000, 0);
+        Issue {
+            number: 17,
+            title: "Crash on startup".to_string(),
+            html_url: "https://github.com/owner/repo/issues/17".to_string(),
+            reporter: Some("reporter".to_string()),
+            assignee: Some("fixer".to_string()),
+            closed_by: Some("fixer".to_string()),
+            labels: vec!["bug".to_string(), "p1".to_string()],
+            state,
+            created_at: at,
+            updated_at: at,
+            closed_at: (state == IssueState::Closed).then_some(at).flatten(),
+            is_bug: true,
+        }
+    }
+
+    fn render(event: GithubEvent) -> serde_json::Value {
+        serde_json::to_value(build_embed(&event)).expect("embed serializes")
+    }
+
+    #[test]
+    fn test_only_rate_limits_and_server_errors_are_retried() {
+        assert!(is_transient_status(429));
+        assert!(is_transient_status(500));
+        assert!(is_transient_status(503));
+        // Missing access, unknown channel, invalid embed
+        assert!(!is_transient_status(403));
+        assert!(!is_transient_status(404));
+        assert!(!is_transient_status(400));
+    }
+
+    #[test]
+    fn test_commit_embed_shows_avatar_link_and_stats() {
+        let commit = Commit {
+            sha: "0123456789abcdef".to_string(),
+            message: "Fix the thing\n\nLonger explanation".to_string(),
+            author_name: "octocat".to_string(),
+            html_url: "https://github.com/owner/repo/commit/0123456789abcdef".to_string(),
+            avatar_url: Some("https://avatars.githubusercontent.com/u/1".to_string()),
+            committed_at: None,
+            stats: Some(CommitStats {
+                files_changed: 3,
+                additions: 42,
+                deletions: 7,
+            }),
+        };
+
+        let embed = render(GithubEvent::CommitPushed {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            branch: "main".to_string(),
+            commit,
+        });
+
+        assert_eq!(embed["title"], "[repo:main] new commit");
+        assert_eq!(embed["author"]["name"], "octocat");
+        assert_eq!(embed["footer"]["text"], "owner/repo");
+        assert_eq!(
+            embed["thumbnail"]["url"],
+            "https://avatars.githubusercontent.com/u/1"
+        );
+        assert_eq!(
+            embed["description"],
+            "[`0123456`](https://github.com/owner/repo/commit/0123456789abcdef) Fix the thing"
+        );
+        let fields = embed["fields"].as_array().expect("embed has fields");
+        assert!(fields
+            .iter()
+            .any(|f| f["name"] == "Changes" && f["value"] == "3 files · `+42` / `-7`"));
+    }
+
+    #[test]
+    fn test_bug_embed_links_issue_and_credits_people() {
+        let embed = render(GithubEvent::BugClosed {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            issue: issue(IssueState::Closed),
+        });
+
+        assert_eq!(embed["title"], "Bug fixed: #17");
+        assert_eq!(
+            embed["description"],
+            "[Crash on startup](https://github.com/owner/repo/issues/17)"
+        );
+        assert_eq!(embed["footer"]["text"], "owner/repo");
+        assert_eq!(field(&embed, "Opened by"), Some("`reporter`"));
+        assert_eq!(field(&embed, "Closed by"), Some("`fixer`"));
+        assert_eq!(field(&embed, "Closed at"), Some("<t:1700000000:F>"));
+        assert_eq!(field(&embed, "Labels"), Some("bug, p1"));
+    }
+
+    #[test]
+    fn test_issue_embeds_for_each_activity() {
+        let render_activity = |activity, state| {
+            render(GithubEvent::IssueActivity {
+                owner: "owner".to_string(),
+                repo: "repo".to_string(),
+                issue: Issue {
+                    is_bug: false,
+                    ..issue(state)
+                },
+                activity,
+            })
+        };
+
+        for (activity, state, status) in [
+            (IssueActivity::Opened, IssueState::Open, "opened"),
+            (IssueActivity::Updated, IssueState::Open, "updated"),
+            (IssueActivity::Closed, IssueState::Closed, "closed"),
+        ] {
+            let embed = render_activity(activity, state);
+            assert_eq!(embed["title"], format!("Issue #17 {status}"));
+            assert_eq!(
+                embed["description"],
+                "[Crash on startup](https://github.com/owner/repo/issues/17)"
+            );
+            assert_eq!(field(&embed, "Author"), Some("`reporter`"));
+            assert_eq!(field(&embed, "Assigned to"), Some("`fixer`"));
+            assert_eq!(field(&embed, "Labels"), Some("bug, p1"));
+
+            // Closed issues show when they were closed, others the last update
+            let (shown, hidden) = if activity == IssueActivity::Closed {
+                ("Closed at", "Updated at")
+            } else {
+                ("Updated at", "Closed at")
+            };
+            assert_eq!(field(&embed, shown), Some("<t:1700000000:F>"));
+            assert_eq!(field(&embed, hidden), None);
+        }
+
+        let opened = render_activity(IssueActivity::Opened, IssueState::Open);
+        let closed = render_activity(IssueActivity::Closed, IssueState::Closed);
+        assert_ne!(opened["color"], closed["color"]);
+    }
+
+    #[test]
+    fn test_digest_embed_stays_within_discord_limits() {
+        let commits: Vec<DigestCommit> = (0..100)
+            .map(|i| DigestCommit {
+                repo: "repo".to_string(),
+                branch: "main".to_string(),
+                commit: Commit {
+                    sha: format!("{i:040}"),
+                    message: format!("Change number {i}\n\nDetails"),
+                    author_name: "octocat".to_string(),
+                    html_url: format!("https://github.com/owner/repo/commit/{i:040}"),
+                    avatar_url: None,
+                    committed_at: None,
+                    stats: None,
+                },
+            })
+            .collect();
+
+        let embed =
+            serde_json::to_value(build_digest_embed("owner", &commits)).expect("embed serializes");
+
+        assert_eq!(embed["title"], "[repo:main] 100 new commits");
+        let description = embed["description"].as_str().unwrap();
+        assert!(description.chars().count() <= 4096);
+        assert!(description.starts_with(
+            "[`0000000`](https://github.com/owner/repo/commit/0000000000000000000000000000000000000000) Change number 0 - octocat\n"
+        ));
+        assert!(description.ends_with("more"));
+    }
+
+    #[test]
+    fn test_org_digest_names_each_repo() {
+        let commit = |repo: &str, sha: &str| DigestCommit {
+            repo: repo.to_string(),
+            branch: "main".to_string(),
+            commit: Commit {
+                sha: sha.repeat(40),
+                message: "Fix".to_string(),
+                author_name: "octocat".to_string(),
+                html_url: "url".to_string(),
+                avatar_url: None,
+                committed_at: None,
+                stats: None,
+            },
+        };
+        let commits = [commit("api", "a"), commit("web", "b")];
+
+        let embed = serde_json::to_value(build_digest_embed("org", &commits)).unwrap();
+        assert_eq!(embed["title"], "[org] 2 new commits");
+        assert_eq!(
+            embed["description"],
+            "`api:main` [`aaaaaaa`](url) Fix - octocat\n`web:main` [`bbbbbbb`](url) Fix - octocat"
+        );
+        assert_eq!(embed["footer"]["text"], "org");
+    }
+}
@@ -0,0 +1,51 @@
+// Discord-side helpers for localized replies.
+//
+// The `Localizer` (core) only knows about locale codes. This module figures out
+// which code to use for a given interaction:
+// 1. The user's client locale (`interaction.locale`, slash commands only)
+// 2. The guild's preferred locale (community servers set this in settings)
+// 3. English (handled by the localizer's fallback chain)
+
+use crate::discord::{Context, Data};
+use poise::serenity_prelude as serenity;
+
+/// Pick the locale for a command invocation.
+pub fn command_locale(ctx: Context<'_>) -> Option<String> {
+    if let Some(locale) = ctx.locale() {
+        return Some(locale.to_string());
+    }
+
+    ctx.guild_id()
+        .and_then(|guild_id| guild_locale(ctx.serenity_context(), guild_id))
+}
+
+/// The guild's preferred locale from the cache (used for channel announcements
+/// where there is no interaction to read a locale from).
+pub fn guild_locale(ctx: &serenity::Context, guild_id: serenity::GuildId) -> Option<String> {
+    ctx.cache
+        .guild(guild_id)
+        .map(|guild| guild.preferred_locale.clone())
+}
+
+/// Translate `key` for the user who invoked the command.
+///
+/// ```ignore
+/// let title = tr(ctx, "daily.claimed_title", &[]);
+/// let line = tr(ctx, "daily.xp", &[("xp", xp.to_string())]);
+/// ```
+pub fn tr(ctx: Context<'_>, key: &str, args: &[(&str, String)]) -> String {
+    let locale = command_locale(ctx);
+    ctx.data().i18n.t(locale.as_deref(), key, args)
+}
+
+/// Translate `key` for a guild (announcements, background messages).
+pub fn tr_guild(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: Option<serenity::GuildId>,
+    key: &str,
+    args: &[(&str, String)],
+) -> String {
+    let locale = guild_id.and_then(|id| guild_locale(ctx, id));
+    data.i18n.t(locale.as_deref(), key, args)
+}
@@ -0,0 +1,182 @@
+// Reliable Nitro booster detection.
+//
+// Boosters get a 1.5x XP multiplier, but `premium_since` is only available when
+// the member is in the serenity cache. Right after a restart (or in large guilds)
+// the cache is often incomplete, so boosters silently lost their bonus until the
+// daily booster sweep ran.
+//
+// `is_boosting` checks the cache first (free, no I/O). Only on a cache miss does
+// it fall back to a single HTTP `get_member` call, whose result is remembered
+// for a few minutes so a chatty user doesn't trigger a request per message.
+// Expired results are dropped whenever a new one is stored, and a member's
+// (or a whole guild's) are dropped when they leave, so the cache stays small.
+
+use crate::discord::Data;
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// How long an HTTP lookup result is trusted.
+const LOOKUP_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Maximum number of `get_member` requests in flight at once. Extra lookups
+/// are skipped (treated as "not boosting") instead of queueing behind Discord's
+/// rate limiter on the message hot path.
+const MAX_CONCURRENT_LOOKUPS: usize = 2;
+
+/// Short-lived cache of booster status fetched over HTTP.
+pub struct BoostStatusCache {
+    /// (guild_id, user_id) -> (is_boosting, fetched_at)
+    entries: DashMap<(u64, u64), (bool, Instant)>,
+    lookups: Semaphore,
+    ttl: Duration,
+}
+
+impl BoostStatusCache {
+    pub fn new() -> Self {
+        Self::with_ttl(LOOKUP_TTL)
+    }
+
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            lookups: Semaphore::new(MAX_CONCURRENT_LOOKUPS),
+            ttl,
+        }
+    }
+
+    /// Return a remembered status if it hasn't expired yet.
+    fn cached(&self, guild_id: u64, user_id: u64) -> Option<bool> {
+        let entry = self.entries.get(&(guild_id, user_id))?;
+        let (boosting, fetched_at) = *entry;
+        drop(entry);
+
+        if fetched_at.elapsed() < self.ttl {
+            Some(boosting)
+        } else {
+            self.entries.remove(&(guild_id, user_id));
+            None
+        }
+    }
+
+    fn remember(&self, guild_id: u64, user_id: u64, boosting: bool) {
+        // Lookups are rare (cache misses only), so sweeping here is cheap
+        self.entries
+            .retain(|_, (_, fetched_at)| fetched_at.elapsed() < self.ttl);
+        self.entries
+            .insert((guild_id, user_id), (boosting, Instant::now()));
+    }
+
+    /// Drop the status of a member who left the guild.
+    pub fn forget_member(&self, guild_id: u64, user_id: u64) {
+        self.entries.remove(&(guild_id, user_id));
+    }
+
+    /// Drop every status in a guild the bot left.
+    pub fn forget_guild(&self, guild_id: u64) {
+        self.entries.retain(|&(guild, _), _| guild != guild_id);
+    }
+}
+
+impl Default for BoostStatusCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check whether `user_id` is currently boosting `guild_id`.
+///
+/// Cache hit: no I/O. Cache miss: one rate-limit-guarded HTTP request, cached
+/// for `LOOKUP_TTL`. Failures are treated as "not boosting".
+pub async fn is_boosting(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+) -> bool {
+    // 1. Fast path: member is in the gateway cache
+    if let Some(cached) = ctx.cache.guild(guild_id).and_then(|guild| {
+        guild
+            .members
+            .get(&user_id)
+            .map(|member| member.premium_since.is_some())
+    }) {
+        return cached;
+    }
+
+    let cache = &data.boost_cache;
+
+    // 2. Recently fetched over HTTP
+    if let Some(boosting) = cache.cached(guild_id.get(), user_id.get()) {
+        return boosting;
+    }
+
+    // 3. Fetch from the API, unless too many lookups are already running
+    let Ok(_permit) = cache.lookups.try_acquire() else {
+        tracing::debug!(
+            guild_id = guild_id.get(),
+            user_id = user_id.get(),
+            "Skipping booster lookup, too many in flight"
+        );
+        return false;
+    };
+
+    let boosting = match ctx.http.get_member(guild_id, user_id).await {
+        Ok(member) => member.premium_since.is_some(),
+        Err(e) => {
+            tracing::warn!(
+                guild_id = guild_id.get(),
+                user_id = user_id.get(),
+                error = %e,
+                "Booster lookup failed"
+            );
+            false
+        }
+    };
+
+    // Cache failures too, so a broken lookup doesn't repeat on every message
+    cache.remember(guild_id.get(), user_id.get(), boosting);
+    boosting
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remembers_status_until_ttl() {
+        let cache = BoostStatusCache::new();
+        assert_eq!(cache.cached(1, 2), None);
+
+        cache.remember(1, 2, true);
+        assert_eq!(cache.cached(1, 2), Some(true));
+        assert_eq!(cache.cached(1, 3), None);
+
+        let expired = BoostStatusCache::with_ttl(Duration::ZERO);
+        expired.remember(1, 2, true);
+        assert_eq!(expired.cached(1, 2), None);
+    }
+
+    #[test]
+    fn test_forgets_members_and_guilds() {
+        let cache = BoostStatusCache::new();
+        cache.remember(1, 2, true);
+        cache.remember(1, 3, false);
+        cache.remember(4, 2, true);
+
+        cache.forget_member(1, 2);
+        assert_eq!(cache.cached(1, 2), None);
+        assert_eq!(cache.cached(1, 3), Some(false));
+
+        cache.forget_guild(1);
+        assert_eq!(cache.cached(1, 3), None);
+        assert_eq!(cache.cached(4, 2), Some(true));
+
+        // Storing a result sweeps out expired ones
+        let expired = BoostStatusCache::with_ttl(Duration::ZERO);
+        expired.remember(1, 2, true);
+        expired.remember(1, 3, true);
+        assert_eq!(expired.entries.len(), 1);
+    }
+}
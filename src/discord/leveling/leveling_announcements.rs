@@ -1,16 +1,32 @@
-use crate::core::leveling::LevelUpEvent;
+use crate::core::leveling::{LevelUpEvent, LevelUpMention};
+use crate::discord::i18n::tr_guild;
+use crate::discord::user_identity::resolve_identity;
+use crate::discord::util::permissions::can_send_messages;
 use crate::discord::Data;
 use poise::serenity_prelude::{self as serenity, builder::CreateMessage};
 use rand::seq::SliceRandom;
 
 /// Send a Greybeard-style level-up embed to the leveling announcements channel.
+///
+/// The guild's `LevelUpMention` setting decides whether the member is pinged.
+/// Mentions inside an embed never notify anyone, so `Ping` and `Silent` put the
+/// mention in the message content, and `AllowedMentions` is always limited to
+/// what the setting allows.
 pub async fn send_level_up_embed(
     ctx: &serenity::Context,
-    message: &serenity::Message,
     data: &Data,
     level_up: &LevelUpEvent,
 ) -> Result<(), serenity::Error> {
     let announcement_channel_id = serenity::ChannelId::from(1456341010262266114u64);
+    let guild_id = serenity::GuildId::new(level_up.guild_id);
+    if !can_send_messages(ctx, Some(guild_id), announcement_channel_id) {
+        tracing::debug!(
+            guild_id = level_up.guild_id,
+            "No permission to post in the level-up channel, skipping the announcement"
+        );
+        return Ok(());
+    }
+
     let leveling = &data.leveling;
     let previous_threshold = leveling.xp_for_level(level_up.new_level);
     let next_threshold = leveling.xp_for_next_level(level_up.new_level);
@@ -21,16 +37,48 @@ pub async fn send_level_up_embed(
         .min(level_span);
     let progress = xp_in_level as f64 / level_span as f64;
 
-    let embed = serenity::CreateEmbed::new()
-        .title("Level Up!")
-        .description(format!(
-            "<@{}> reached level {}!",
-            level_up.user_id, level_up.new_level
-        ))
+    // Announcements have no interaction locale, so use the guild's preferred locale
+    let t = |key: &str, args: &[(&str, String)]| tr_guild(ctx, data, Some(guild_id), key, args);
+
+    let mention = match leveling.level_up_mention(level_up.guild_id).await {
+        Ok(mention) => mention,
+        Err(e) => {
+            tracing::warn!(guild_id = level_up.guild_id, error = %e, "Failed to load level-up mention setting");
+            LevelUpMention::default()
+        }
+    };
+
+    let description = match mention {
+        LevelUpMention::Ping | LevelUpMention::Silent => t(
+            "levelup.description",
+            &[
+                ("user_id", level_up.user_id.to_string()),
+                ("level", level_up.new_level.to_string()),
+            ],
+        ),
+        LevelUpMention::Name => {
+            let identity = resolve_identity(ctx, data, guild_id, level_up.user_id).await;
+            t(
+                "levelup.description_name",
+                &[
+                    ("name", identity.name),
+                    ("level", level_up.new_level.to_string()),
+                ],
+            )
+        }
+    };
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(t("levelup.title", &[]))
+        .description(description)
         .color(level_color(level_up.new_level))
-        .field("Total XP", level_up.total_xp.to_string(), true)
         .field(
-            "Progress",
+            t("levelup.total_xp", &[]),
+            level_up.total_xp.to_string(),
+            true,
+        )
+        .field(
+            t("levelup.progress", &[]),
             format!(
                 "{}/{} XP\n{}",
                 xp_in_level,
@@ -39,10 +87,37 @@ pub async fn send_level_up_embed(
             ),
             false,
         )
-        .footer(serenity::CreateEmbedFooter::new(random_flavor_line()));
+        .footer(serenity::CreateEmbedFooter::new(t(
+            random_flavor_key(),
+            &[],
+        )));
+
+    // Multi-level jumps (or batched announcements) list every level crossed
+    if level_up.levels_reached.len() > 1 {
+        let levels: Vec<String> = level_up
+            .levels_reached
+            .iter()
+            .map(|level| level.to_string())
+            .collect();
+        embed = embed.field(t("levelup.levels_gained", &[]), levels.join(" → "), false);
+    }
+
+    let user_id = serenity::UserId::new(level_up.user_id);
+    let message = match mention {
+        LevelUpMention::Ping => CreateMessage::new()
+            .content(format!("<@{}>", level_up.user_id))
+            .allowed_mentions(serenity::CreateAllowedMentions::new().users([user_id])),
+        LevelUpMention::Silent => CreateMessage::new()
+            .content(format!("<@{}>", level_up.user_id))
+            .allowed_mentions(serenity::CreateAllowedMentions::new().users([user_id]))
+            .flags(serenity::MessageFlags::SUPPRESS_NOTIFICATIONS),
+        LevelUpMention::Name => {
+            CreateMessage::new().allowed_mentions(serenity::CreateAllowedMentions::new())
+        }
+    };
 
     announcement_channel_id
-        .send_message(ctx, CreateMessage::new().embed(embed))
+        .send_message(ctx, message.embed(embed))
         .await
         .map(|_| ())
 }
@@ -74,16 +149,16 @@ fn build_progress_bar(progress: f64, length: usize) -> String {
     format!("{} ({}%)", bar, (clamped * 100.0).round() as u32)
 }
 
-fn random_flavor_line() -> &'static str {
-    const FLAVOR_LINES: [&str; 4] = [
-        "Keep the streak going!",
-        "Your grind is paying off.",
-        "Another level, another flex.",
-        "That XP bar never stood a chance.",
+fn random_flavor_key() -> &'static str {
+    const FLAVOR_KEYS: [&str; 4] = [
+        "levelup.flavor_1",
+        "levelup.flavor_2",
+        "levelup.flavor_3",
+        "levelup.flavor_4",
     ];
 
-    FLAVOR_LINES
+    FLAVOR_KEYS
         .choose(&mut rand::thread_rng())
         .copied()
-        .unwrap_or(FLAVOR_LINES[0])
+        .unwrap_or(FLAVOR_KEYS[0])
 }
@@ -1,3 +1,4 @@
+use crate::core::logging::OptionalLogEvent;
 use crate::discord::{Context, Error};
 use poise::serenity_prelude as serenity;
 
@@ -5,42 +6,63 @@ use poise::serenity_prelude as serenity;
 #[poise::command(
     slash_command,
     guild_only,
-    required_permissions = "ADMINISTRATOR",
-    subcommands("status", "set_channel", "enable", "disable")
+    check = "crate::discord::access::is_manager",
+    subcommands(
+        "status",
+        "set_channel",
+        "enable",
+        "disable",
+        "events",
+        "attribution",
+        "audit"
+    )
 )]
 pub async fn logging(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
 /// Show current logging configuration.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be in a guild")?.get();
     let config = ctx.data().logging.get_config(guild_id).await?;
 
-    let (status, channel_mention) = if let Some(cfg) = config {
-        let status = if cfg.enabled && cfg.channel_id.is_some() {
-            "Enabled"
-        } else {
-            "Disabled"
-        };
-        let mention = cfg
-            .channel_id
-            .map(|id| format!("<#{}>", id))
-            .unwrap_or_else(|| "Not set".to_string());
-        (status, mention)
+    let config = config.unwrap_or_else(|| crate::core::logging::LogConfig::new(guild_id));
+    let status = if config.enabled && config.channel_id.is_some() {
+        "Enabled"
     } else {
-        ("Disabled", "Not set".to_string())
+        "Disabled"
     };
+    let channel_mention = config
+        .channel_id
+        .map(|id| format!("<#{}>", id))
+        .unwrap_or_else(|| "Not set".to_string());
+    let on_off = |enabled: bool| if enabled { "on" } else { "off" };
 
     let embed = serenity::CreateEmbed::default()
         .title("Activity Logging Configuration")
         .color(serenity::Color::BLURPLE)
         .field("Status", status, false)
         .field("Log Channel", channel_mention, false)
+        .field(
+            "Moderator Attribution",
+            format!(
+                "{} (audit log lookups for deletes, kicks and bans)",
+                on_off(config.audit_attribution)
+            ),
+            false,
+        )
         .field(
             "Tracked Events",
-            "• Member Join/Leave\n• Message Edit/Delete\n• Voice Activity",
+            format!(
+                "• Member Join/Leave\n• Message Edit/Delete\n• Voice Activity\n• Bans ({})\n• Unbans ({})\n• Command Audit (`/logging audit recent`)",
+                on_off(config.log_bans),
+                on_off(config.log_unbans)
+            ),
             false,
         )
         .footer(serenity::CreateEmbedFooter::new(format!(
@@ -54,7 +76,11 @@ pub async fn status(ctx: Context<'_>) -> Result<(), Error> {
 }
 
 /// Select the text channel used for logging.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn set_channel(
     ctx: Context<'_>,
     #[description = "Channel to log to"] channel: serenity::Channel,
@@ -72,7 +98,11 @@ pub async fn set_channel(
 }
 
 /// Enable activity logging.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn enable(ctx: Context<'_>) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be in a guild")?.get();
 
@@ -86,7 +116,11 @@ pub async fn enable(ctx: Context<'_>) -> Result<(), Error> {
 }
 
 /// Disable activity logging.
-#[poise::command(slash_command, guild_only, required_permissions = "ADMINISTRATOR")]
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
 pub async fn disable(ctx: Context<'_>) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be in a guild")?.get();
 
@@ -98,3 +132,155 @@ pub async fn disable(ctx: Context<'_>) -> Result<(), Error> {
     }
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum LoggedEvent {
+    Bans,
+    Unbans,
+}
+
+impl From<LoggedEvent> for OptionalLogEvent {
+    fn from(event: LoggedEvent) -> Self {
+        match event {
+            LoggedEvent::Bans => OptionalLogEvent::Bans,
+            LoggedEvent::Unbans => OptionalLogEvent::Unbans,
+        }
+    }
+}
+
+/// Choose which optional events are logged.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn events(
+    ctx: Context<'_>,
+    #[description = "Event type"] event: LoggedEvent,
+    #[description = "Log this event type"] enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be in a guild")?.get();
+
+    ctx.data()
+        .logging
+        .set_event_enabled(guild_id, event.into(), enabled)
+        .await?;
+
+    let name = match event {
+        LoggedEvent::Bans => "Bans",
+        LoggedEvent::Unbans => "Unbans",
+    };
+    let reply = if enabled {
+        format!(
+            "✅ {} will be logged. The bot needs View Audit Log to show who did it and why.",
+            name
+        )
+    } else {
+        format!("🛑 {} will no longer be logged.", name)
+    };
+    ctx.say(reply).await?;
+    Ok(())
+}
+
+/// Name the likely moderator behind deletes, kicks and bans using the audit log.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn attribution(
+    ctx: Context<'_>,
+    #[description = "Look up moderators in the audit log (one extra API call per event)"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be in a guild")?.get();
+
+    if !ctx
+        .data()
+        .logging
+        .set_audit_attribution(guild_id, enabled)
+        .await?
+    {
+        ctx.say("Please configure a logging channel first using `/logging set_channel #channel`.")
+            .await?;
+    } else if enabled {
+        ctx.say("✅ Deletes, kicks and bans will name the likely moderator. The bot needs View Audit Log.")
+            .await?;
+    } else {
+        ctx.say("🛑 Moderator attribution disabled; no more audit log lookups.")
+            .await?;
+    }
+    Ok(())
+}
+
+/// Inspect the command audit trail.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager",
+    subcommands("recent")
+)]
+pub async fn audit(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show the most recently used commands in this server.
+#[poise::command(
+    slash_command,
+    guild_only,
+    check = "crate::discord::access::is_manager"
+)]
+pub async fn recent(
+    ctx: Context<'_>,
+    #[description = "How many entries to show (default 15, max 25)"]
+    #[min = 1]
+    #[max = 25]
+    limit: Option<usize>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be in a guild")?.get();
+    let limit = limit.unwrap_or(15).clamp(1, 25);
+
+    let entries = ctx.data().logging.recent_commands(guild_id, limit).await?;
+    if entries.is_empty() {
+        ctx.say("No commands have been recorded for this server yet.")
+            .await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let status = if entry.success { "✅" } else { "❌" };
+            let mut line = format!(
+                "{} <t:{}:R> `/{}` by <@{}>",
+                status,
+                entry.timestamp.timestamp(),
+                entry.command_name,
+                entry.user_id
+            );
+            if let Some(error) = &entry.error {
+                let short: String = error.chars().take(80).collect();
+                line.push_str(&format!(" — {}", short));
+            }
+            line
+        })
+        .collect();
+
+    let embed = serenity::CreateEmbed::default()
+        .title("Recent Commands")
+        .description(lines.join("\n"))
+        .color(serenity::Color::BLURPLE)
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "Guild ID: {}",
+            guild_id
+        )))
+        .timestamp(serenity::Timestamp::now());
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
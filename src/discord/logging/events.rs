@@ -1,7 +1,12 @@
-use crate::core::logging::{LogEvent, TrackedMessage};
+use crate::core::logging::{
+    match_audit_entry, AuditAttribution, AuditEntry, AuditQuery, CommandAudit, LogEvent,
+    OptionalLogEvent, TrackedMessage, MEMBER_ACTION_LOOKBACK_SECS, MESSAGE_DELETE_LOOKBACK_SECS,
+};
+use crate::core::welcome::is_moderated_removal;
 use crate::discord::logging::formatter::format_log_event;
-use crate::discord::Data;
+use crate::discord::{Context as CommandContext, Data};
 use anyhow::Result;
+use poise::serenity_prelude::audit_log::{Action, MemberAction, MessageAction};
 use poise::serenity_prelude::{self as serenity, Context, Mentionable};
 
 pub async fn handle_voice_state_update(
@@ -107,7 +112,11 @@ pub async fn handle_member_remove(
     guild_id: serenity::GuildId,
     user: &serenity::User,
     member_data: Option<&serenity::Member>,
+    removal: &RemovalAudit,
 ) -> Result<()> {
+    // Kicks look like any other departure; only the audit log tells them apart
+    let kicked_by = removal.kicked_by(ctx, data).await;
+
     let guild_id = guild_id.get();
     let user_id = user.id.get();
 
@@ -117,12 +126,273 @@ pub async fn handle_member_remove(
         user_mention: user.mention().to_string(),
         avatar_url: user.avatar_url(),
         joined_at: member_data.and_then(|m| m.joined_at).map(|t| *t),
+        kicked_by,
     };
 
     send_log(ctx, data, guild_id, event).await?;
     Ok(())
 }
 
+/// The kick and ban audit log entries about a member who just left. The leave
+/// log (who kicked them) and the goodbye message (skip kicks and bans) both
+/// need them, so they are read at most once per departure, and only if one of
+/// them asks.
+pub struct RemovalAudit {
+    guild_id: serenity::GuildId,
+    user_id: u64,
+    left_at: chrono::DateTime<chrono::Utc>,
+    /// (is a kick, entry); `None` if the audit log couldn't be read
+    entries: tokio::sync::OnceCell<Option<Vec<(bool, AuditEntry)>>>,
+}
+
+impl RemovalAudit {
+    pub fn new(guild_id: serenity::GuildId, user_id: serenity::UserId) -> Self {
+        Self {
+            guild_id,
+            user_id: user_id.get(),
+            left_at: chrono::Utc::now(),
+            entries: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    /// Who likely kicked the member. `None` when attribution is off, the
+    /// audit log can't be read or they weren't kicked.
+    pub async fn kicked_by(&self, ctx: &Context, data: &Data) -> Option<AuditAttribution> {
+        match data.logging.should_attribute(self.guild_id.get()).await {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(e) => {
+                tracing::warn!(guild_id = self.guild_id.get(), error = %e, "Failed to load logging config");
+                return None;
+            }
+        }
+
+        let kicks: Vec<AuditEntry> = self
+            .entries(ctx)
+            .await?
+            .iter()
+            .filter(|(is_kick, _)| *is_kick)
+            .map(|(_, entry)| entry.clone())
+            .collect();
+        let query = AuditQuery {
+            target_id: self.user_id,
+            channel_id: None,
+            event_at: self.left_at,
+            lookback: chrono::Duration::seconds(MEMBER_ACTION_LOOKBACK_SECS),
+        };
+        match_audit_entry(&kicks, &query)
+    }
+
+    /// Whether the member was just kicked or banned. False if the audit log
+    /// can't be read (needs View Audit Log).
+    pub async fn kicked_or_banned(&self, ctx: &Context) -> bool {
+        let Some(entries) = self.entries(ctx).await else {
+            return false;
+        };
+        let action_times: Vec<_> = entries.iter().map(|(_, entry)| entry.created_at).collect();
+        is_moderated_removal(&action_times, self.left_at)
+    }
+
+    async fn entries(&self, ctx: &Context) -> Option<&[(bool, AuditEntry)]> {
+        self.entries
+            .get_or_init(|| self.fetch(ctx))
+            .await
+            .as_deref()
+    }
+
+    async fn fetch(&self, ctx: &Context) -> Option<Vec<(bool, AuditEntry)>> {
+        // The entry can land a moment after the removal event; give it one
+        // more chance
+        let mut entries = Vec::new();
+        for attempt in 0..2 {
+            if attempt > 0 {
+                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            }
+
+            entries.clear();
+            for (action, is_kick) in [(MemberAction::Kick, true), (MemberAction::BanAdd, false)] {
+                let logs = match self
+                    .guild_id
+                    .audit_logs(
+                        &ctx.http,
+                        Some(Action::Member(action)),
+                        None,
+                        None,
+                        Some(10),
+                    )
+                    .await
+                {
+                    Ok(logs) => logs,
+                    Err(e) => {
+                        tracing::warn!(
+                            guild_id = self.guild_id.get(),
+                            error = %e,
+                            "Could not read the audit log to check for a kick or ban"
+                        );
+                        return None;
+                    }
+                };
+                entries.extend(
+                    audit_entries(&logs)
+                        .into_iter()
+                        .filter(|entry| entry.target_id == Some(self.user_id))
+                        .map(|entry| (is_kick, entry)),
+                );
+            }
+            if !entries.is_empty() {
+                break;
+            }
+        }
+        Some(entries)
+    }
+}
+
+/// Log a ban, naming the likely moderator and reason from the audit log when
+/// attribution is on and the bot can read it (View Audit Log).
+pub async fn handle_member_ban(
+    ctx: &Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+) -> Result<()> {
+    // Skip the audit log lookup when nobody will see the log
+    if !data
+        .logging
+        .should_log(guild_id.get(), OptionalLogEvent::Bans)
+        .await?
+    {
+        return Ok(());
+    }
+
+    let attribution = attribute(
+        ctx,
+        data,
+        guild_id,
+        Action::Member(MemberAction::BanAdd),
+        user.id.get(),
+        None,
+    )
+    .await;
+    let event = LogEvent::MemberBanned {
+        guild_id: guild_id.get(),
+        user_id: user.id.get(),
+        user_name: user.name.clone(),
+        avatar_url: user.avatar_url(),
+        attribution,
+    };
+
+    send_log(ctx, data, guild_id.get(), event).await
+}
+
+/// Log an unban, naming the likely moderator and reason from the audit log
+/// when attribution is on and the bot can read it (View Audit Log).
+pub async fn handle_member_unban(
+    ctx: &Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+) -> Result<()> {
+    if !data
+        .logging
+        .should_log(guild_id.get(), OptionalLogEvent::Unbans)
+        .await?
+    {
+        return Ok(());
+    }
+
+    let attribution = attribute(
+        ctx,
+        data,
+        guild_id,
+        Action::Member(MemberAction::BanRemove),
+        user.id.get(),
+        None,
+    )
+    .await;
+    let event = LogEvent::MemberUnbanned {
+        guild_id: guild_id.get(),
+        user_id: user.id.get(),
+        user_name: user.name.clone(),
+        avatar_url: user.avatar_url(),
+        attribution,
+    };
+
+    send_log(ctx, data, guild_id.get(), event).await
+}
+
+/// Guess who performed `action` on `target_id` from the audit log. `None`
+/// when attribution is off, the audit log can't be read or nothing matches.
+async fn attribute(
+    ctx: &Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    action: Action,
+    target_id: u64,
+    channel_id: Option<u64>,
+) -> Option<AuditAttribution> {
+    match data.logging.should_attribute(guild_id.get()).await {
+        Ok(true) => {}
+        Ok(false) => return None,
+        Err(e) => {
+            tracing::warn!(guild_id = guild_id.get(), error = %e, "Failed to load logging config");
+            return None;
+        }
+    }
+
+    let lookback = match action {
+        Action::Message(_) => MESSAGE_DELETE_LOOKBACK_SECS,
+        _ => MEMBER_ACTION_LOOKBACK_SECS,
+    };
+    let query = AuditQuery {
+        target_id,
+        channel_id,
+        event_at: chrono::Utc::now(),
+        lookback: chrono::Duration::seconds(lookback),
+    };
+
+    // Discord usually writes the entry before the gateway event arrives, but
+    // not always; give it one more chance
+    for attempt in 0..2 {
+        if attempt > 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+
+        let logs = match guild_id
+            .audit_logs(&ctx.http, Some(action), None, None, Some(25))
+            .await
+        {
+            Ok(logs) => logs,
+            Err(e) => {
+                tracing::debug!(guild_id = guild_id.get(), error = %e, "Could not read audit log");
+                return None;
+            }
+        };
+
+        if let Some(attribution) = match_audit_entry(&audit_entries(&logs), &query) {
+            return Some(attribution);
+        }
+    }
+
+    None
+}
+
+fn audit_entries(logs: &serenity::AuditLogs) -> Vec<AuditEntry> {
+    logs.entries
+        .iter()
+        .map(|entry| AuditEntry {
+            moderator_id: entry.user_id.get(),
+            target_id: entry.target_id.map(|id| id.get()),
+            channel_id: entry
+                .options
+                .as_ref()
+                .and_then(|options| options.channel_id)
+                .map(|id| id.get()),
+            reason: entry.reason.clone(),
+            created_at: *entry.id.created_at(),
+        })
+        .collect()
+}
+
 pub async fn handle_message_delete(
     ctx: &Context,
     data: &Data,
@@ -175,6 +445,16 @@ pub async fn handle_message_delete(
         return Ok(());
     }
 
+    let deleted_by = attribute(
+        ctx,
+        data,
+        serenity::GuildId::new(guild_id),
+        Action::Message(MessageAction::Delete),
+        snapshot.author_id,
+        Some(snapshot.channel_id),
+    )
+    .await;
+
     let event = LogEvent::MessageDeleted {
         guild_id,
         author_id: snapshot.author_id,
@@ -183,12 +463,76 @@ pub async fn handle_message_delete(
         content: snapshot.content,
         attachments: snapshot.attachments,
         avatar_url: snapshot.avatar_url,
+        deleted_by,
     };
 
     send_log(ctx, data, guild_id, event).await?;
     Ok(())
 }
 
+/// Log a bulk delete as one summary entry. Bulk deletes don't emit per-message
+/// delete events, so this also consumes the tracked snapshots. If `/purge` just
+/// ran in the channel, the moderator is named.
+pub async fn handle_message_delete_bulk(
+    ctx: &Context,
+    data: &Data,
+    channel_id: serenity::ChannelId,
+    message_ids: &[serenity::MessageId],
+    guild_id: Option<serenity::GuildId>,
+) -> Result<()> {
+    let Some(guild_id) = guild_id.map(|id| id.get()) else {
+        return Ok(());
+    };
+
+    // Tally known authors, most deleted first
+    let mut authors: Vec<(String, usize)> = Vec::new();
+    for message_id in message_ids {
+        let Some(snapshot) = data.logging.take_tracked_message(message_id.get()) else {
+            continue;
+        };
+        match authors
+            .iter_mut()
+            .find(|(name, _)| *name == snapshot.author_name)
+        {
+            Some((_, count)) => *count += 1,
+            None => authors.push((snapshot.author_name, 1)),
+        }
+    }
+    authors.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    let event = LogEvent::MessagesBulkDeleted {
+        guild_id,
+        channel_id: channel_id.get(),
+        count: message_ids.len(),
+        authors,
+        moderator: data.logging.take_purge(channel_id.get()),
+    };
+
+    send_log(ctx, data, guild_id, event).await?;
+    Ok(())
+}
+
+/// Log a slowmode change made with `/slowmode`.
+pub async fn handle_slowmode_change(
+    ctx: &Context,
+    data: &Data,
+    guild_id: u64,
+    channel_id: u64,
+    moderator_id: serenity::UserId,
+    previous_secs: u16,
+    new_secs: u16,
+) -> Result<()> {
+    let event = LogEvent::SlowmodeChanged {
+        guild_id,
+        channel_id,
+        moderator: moderator_id.mention().to_string(),
+        previous_secs,
+        new_secs,
+    };
+
+    send_log(ctx, data, guild_id, event).await
+}
+
 pub async fn handle_message_update(
     ctx: &Context,
     data: &Data,
@@ -279,10 +623,33 @@ pub async fn handle_message_update(
     Ok(())
 }
 
+/// Write an audit entry for a command invocation. Called from the framework's
+/// `post_command` hook (success) and `on_error` hook (failure).
+pub async fn record_command_audit(ctx: CommandContext<'_>, error: Option<String>) {
+    let Some(guild_id) = ctx.guild_id() else {
+        return;
+    };
+
+    let entry = CommandAudit {
+        guild_id: guild_id.get(),
+        user_id: ctx.author().id.get(),
+        user_name: ctx.author().name.clone(),
+        command_name: ctx.command().qualified_name.clone(),
+        success: error.is_none(),
+        // Keep stored errors short; the full error is already in the tracing logs.
+        error: error.map(|e| e.chars().take(200).collect()),
+        timestamp: chrono::Utc::now(),
+    };
+
+    if let Err(e) = ctx.data().logging.record_command(entry).await {
+        tracing::warn!("Failed to record command audit entry: {}", e);
+    }
+}
+
 async fn send_log(ctx: &Context, data: &Data, guild_id: u64, event: LogEvent) -> Result<()> {
     let config = data.logging.get_config(guild_id).await?;
     if let Some(cfg) = config {
-        if cfg.enabled {
+        if cfg.enabled && cfg.logs(&event) {
             if let Some(channel_id) = cfg.channel_id {
                 let embed = format_log_event(&event);
                 let channel = serenity::ChannelId::new(channel_id);
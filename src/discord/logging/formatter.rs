@@ -1,4 +1,5 @@
-use crate::core::logging::LogEvent;
+use crate::core::logging::{AuditAttribution, LogEvent};
+use crate::core::util::truncate_chars;
 use poise::serenity_prelude::{self as serenity, CreateEmbed, CreateEmbedFooter};
 
 pub fn format_log_event(event: &LogEvent) -> CreateEmbed {
@@ -89,6 +90,7 @@ pub fn format_log_event(event: &LogEvent) -> CreateEmbed {
             user_mention,
             avatar_url,
             joined_at,
+            kicked_by,
             ..
         } => {
             let joined_str = if let Some(joined) = joined_at {
@@ -97,14 +99,31 @@ pub fn format_log_event(event: &LogEvent) -> CreateEmbed {
                 "Unknown".to_string()
             };
 
+            let (title, description) = match kicked_by {
+                Some(_) => ("Member Kicked", format!("{} was kicked.", user_mention)),
+                None => (
+                    "Member Left Server",
+                    format!("{} has left the server.", user_mention),
+                ),
+            };
             let mut embed = CreateEmbed::default()
-                .title("Member Left Server")
-                .description(format!("{} has left the server.", user_mention))
+                .title(title)
+                .description(description)
                 .color(serenity::Color::RED)
                 .field("Joined Server", joined_str, false)
                 .footer(CreateEmbedFooter::new(format!("Guild ID: {}", guild_id)))
                 .timestamp(serenity::Timestamp::now());
 
+            if let Some(attribution) = kicked_by {
+                embed = embed
+                    .field("Kicked by", attribution.moderator_label(), true)
+                    .field(
+                        "Reason",
+                        attribution.reason.as_deref().unwrap_or("No reason given"),
+                        true,
+                    );
+            }
+
             if let Some(url) = avatar_url {
                 embed = embed.thumbnail(url);
             }
@@ -119,16 +138,13 @@ pub fn format_log_event(event: &LogEvent) -> CreateEmbed {
             content,
             attachments,
             avatar_url,
+            deleted_by,
         } => {
+            // Descriptions are capped at 4096 characters, "..." included
             let content_display = if content.is_empty() {
-                "*No content*"
+                "*No content*".to_string()
             } else {
-                // Truncate to 4096 chars
-                if content.len() > 4096 {
-                    &content[..4096]
-                } else {
-                    content
-                }
+                truncate_chars(content, 4093)
             };
 
             let mut embed = CreateEmbed::default()
@@ -148,6 +164,10 @@ pub fn format_log_event(event: &LogEvent) -> CreateEmbed {
                 embed = embed.field("Attachments", attachments.join("\n"), false);
             }
 
+            if let Some(attribution) = deleted_by {
+                embed = embed.field("Deleted by", attribution.moderator_label(), false);
+            }
+
             if let Some(url) = avatar_url {
                 embed = embed.thumbnail(url);
             }
@@ -163,21 +183,16 @@ pub fn format_log_event(event: &LogEvent) -> CreateEmbed {
             after_content,
             avatar_url,
         } => {
-            let before_display = if before_content.is_empty() {
-                "*No content*"
-            } else if before_content.len() > 1024 {
-                &before_content[..1024]
-            } else {
-                before_content
-            };
-
-            let after_display = if after_content.is_empty() {
-                "*No content*"
-            } else if after_content.len() > 1024 {
-                &after_content[..1024]
-            } else {
-                after_content
+            // Field values are capped at 1024 characters, "..." included
+            let field_display = |content: &str| {
+                if content.is_empty() {
+                    "*No content*".to_string()
+                } else {
+                    truncate_chars(content, 1021)
+                }
             };
+            let before_display = field_display(before_content);
+            let after_display = field_display(after_content);
 
             let mut embed = CreateEmbed::default()
                 .title("Message Edited")
@@ -199,5 +214,163 @@ pub fn format_log_event(event: &LogEvent) -> CreateEmbed {
             }
             embed
         }
+
+        LogEvent::MessagesBulkDeleted {
+            guild_id,
+            channel_id,
+            count,
+            authors,
+            moderator,
+        } => {
+            let mut embed = CreateEmbed::default()
+                .title("Messages Bulk Deleted")
+                .description(format!("{} messages deleted in <#{}>", count, channel_id))
+                .color(serenity::Color::from_rgb(255, 165, 0)) // Orange
+                .footer(CreateEmbedFooter::new(format!("Guild ID: {}", guild_id)))
+                .timestamp(serenity::Timestamp::now());
+
+            if let Some(moderator) = moderator {
+                embed = embed.field("Purged by", moderator, false);
+            }
+
+            if !authors.is_empty() {
+                let lines: Vec<String> = authors
+                    .iter()
+                    .take(10)
+                    .map(|(name, count)| format!("{} ({})", name, count))
+                    .collect();
+                embed = embed.field("Authors", lines.join("\n"), false);
+            }
+            embed
+        }
+
+        LogEvent::MemberBanned {
+            guild_id,
+            user_id,
+            user_name,
+            avatar_url,
+            attribution,
+        } => moderation_embed(
+            "Member Banned",
+            format!("<@{}> ({}) was banned.", user_id, user_name),
+            serenity::Color::DARK_RED,
+            *guild_id,
+            avatar_url.as_deref(),
+            attribution.as_ref(),
+        ),
+
+        LogEvent::MemberUnbanned {
+            guild_id,
+            user_id,
+            user_name,
+            avatar_url,
+            attribution,
+        } => moderation_embed(
+            "Member Unbanned",
+            format!("<@{}> ({}) was unbanned.", user_id, user_name),
+            serenity::Color::DARK_GREEN,
+            *guild_id,
+            avatar_url.as_deref(),
+            attribution.as_ref(),
+        ),
+
+        LogEvent::SlowmodeChanged {
+            guild_id,
+            channel_id,
+            moderator,
+            previous_secs,
+            new_secs,
+        } => {
+            let describe = |secs: &u16| match secs {
+                0 => "Off".to_string(),
+                secs => format!("{}s", secs),
+            };
+
+            CreateEmbed::default()
+                .title("Slowmode Changed")
+                .description(format!("Slowmode updated in <#{}>", channel_id))
+                .color(serenity::Color::BLURPLE)
+                .field("Before", describe(previous_secs), true)
+                .field("After", describe(new_secs), true)
+                .field("Changed by", moderator, false)
+                .footer(CreateEmbedFooter::new(format!("Guild ID: {}", guild_id)))
+                .timestamp(serenity::Timestamp::now())
+        }
+    }
+}
+
+/// Shared layout for ban/unban logs. The moderator and reason come from the
+/// audit log and may be missing.
+fn moderation_embed(
+    title: &str,
+    description: String,
+    color: serenity::Color,
+    guild_id: u64,
+    avatar_url: Option<&str>,
+    attribution: Option<&AuditAttribution>,
+) -> CreateEmbed {
+    let moderator = attribution
+        .map(AuditAttribution::moderator_label)
+        .unwrap_or_else(|| "Unknown moderator".to_string());
+    let reason = attribution
+        .and_then(|attribution| attribution.reason.as_deref())
+        .unwrap_or("No reason given");
+
+    let mut embed = CreateEmbed::default()
+        .title(title)
+        .description(description)
+        .color(color)
+        .field("Moderator", moderator, true)
+        .field("Reason", reason, true)
+        .footer(CreateEmbedFooter::new(format!("Guild ID: {}", guild_id)))
+        .timestamp(serenity::Timestamp::now());
+
+    if let Some(url) = avatar_url {
+        embed = embed.thumbnail(url);
+    }
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_multibyte_messages_are_cut_on_char_boundaries() {
+        // 3-byte characters: byte 4096 and byte 1024 both fall mid-character
+        let content = "漢".repeat(2000);
+        let deleted = format_log_event(&LogEvent::MessageDeleted {
+            guild_id: 1,
+            author_id: 2,
+            author_name: "someone".to_string(),
+            channel_id: 3,
+            content: content.clone(),
+            attachments: Vec::new(),
+            avatar_url: None,
+            deleted_by: None,
+        });
+        let embed = serde_json::to_value(&deleted).unwrap();
+        let description = embed["description"].as_str().unwrap();
+        assert_eq!(description.chars().count(), 2000);
+
+        let edited = format_log_event(&LogEvent::MessageEdited {
+            guild_id: 1,
+            author_id: 2,
+            author_name: "someone".to_string(),
+            channel_id: 3,
+            before_content: content,
+            after_content: "🎉".to_string(),
+            avatar_url: None,
+        });
+        let embed = serde_json::to_value(&edited).unwrap();
+        let before = embed["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|field| field["name"] == "Before")
+            .unwrap();
+        let before = before["value"].as_str().unwrap();
+        assert_eq!(before.chars().count(), 1024);
+        assert!(before.ends_with("漢..."));
     }
 }
@@ -1,10 +1,25 @@
-// Anti-spam slash commands for configuration.
+// Moderation slash commands: anti-spam configuration, `/purge`, `/slowmode`
+// and `/tempmute`.
 
+use crate::core::moderation::{
+    select_purge_targets, ExistingMute, PurgeCandidate, PurgeFilter, MAX_PURGE_MESSAGES,
+};
+use crate::core::util::parse_human_duration;
+use crate::discord::util::roles::{
+    assignability_problem, grant_problem, member_standing, send_ephemeral,
+};
 use crate::discord::{Data, Error};
 use poise::serenity_prelude as serenity;
+use std::time::Duration;
 
 type Context<'a> = poise::Context<'a, Data, Error>;
 
+/// Discord rejects member timeouts longer than 28 days.
+const MAX_DISCORD_TIMEOUT: Duration = Duration::from_secs(28 * 24 * 60 * 60);
+
+/// Longest slowmode Discord allows (6 hours).
+const MAX_SLOWMODE_SECS: u16 = 21_600;
+
 /// Anti-spam configuration commands.
 ///
 /// Configure anti-spam settings for your server.
@@ -127,7 +142,9 @@ pub async fn config(
     #[description = "Max duplicate messages (default: 3)"] max_duplicates: Option<u32>,
     #[description = "Max mentions per message (default: 10)"] max_mentions: Option<u32>,
     #[description = "Warnings before timeout (default: 3)"] max_warnings: Option<u32>,
-    #[description = "Timeout duration in seconds (default: 300)"] timeout_secs: Option<u64>,
+    #[description = "Timeout duration, e.g. '5m' or '1h30m' (default: 5m, max: 28d)"]
+    timeout: Option<String>,
+    #[description = "Timeout duration in seconds (same as timeout)"] timeout_secs: Option<u64>,
 ) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Must be used in a server")?;
 
@@ -157,8 +174,29 @@ pub async fn config(
     if let Some(v) = max_warnings {
         current_config.warnings_before_timeout = v;
     }
-    if let Some(v) = timeout_secs {
-        current_config.timeout_duration_secs = v;
+    // `timeout_secs` is the older way to set it and still works
+    let timeout = match (timeout, timeout_secs) {
+        (Some(_), Some(_)) => {
+            ctx.say("❌ Set either timeout or timeout_secs, not both.")
+                .await?;
+            return Ok(());
+        }
+        (Some(v), None) => match parse_human_duration(&v) {
+            Ok(duration) => Some(duration),
+            Err(e) => {
+                ctx.say(format!("❌ Invalid timeout: {}", e)).await?;
+                return Ok(());
+            }
+        },
+        (None, secs) => secs.map(Duration::from_secs),
+    };
+    if let Some(duration) = timeout {
+        if duration.is_zero() || duration > MAX_DISCORD_TIMEOUT {
+            ctx.say("❌ Timeout must be between 1 second and 28 days (Discord's limit).")
+                .await?;
+            return Ok(());
+        }
+        current_config.timeout_duration_secs = duration.as_secs();
     }
 
     ctx.data()
@@ -204,3 +242,363 @@ pub async fn clear_warnings(
         .await?;
     Ok(())
 }
+
+/// Bulk-delete recent messages in this channel.
+///
+/// Scans the last 100 messages. Discord can't bulk delete messages older than
+/// 14 days, so those are skipped and reported.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "MANAGE_MESSAGES",
+    required_bot_permissions = "MANAGE_MESSAGES"
+)]
+pub async fn purge(
+    ctx: Context<'_>,
+    #[description = "How many matching messages to delete (max 100)"]
+    #[min = 1]
+    #[max = 100]
+    count: u8,
+    #[description = "Only delete messages from this user"] user: Option<serenity::User>,
+    #[description = "Only delete messages containing this text"] contains: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let channel_id = ctx.channel_id();
+
+    let messages = channel_id
+        .messages(
+            ctx,
+            serenity::GetMessages::new().limit(MAX_PURGE_MESSAGES as u8),
+        )
+        .await?;
+    let candidates: Vec<PurgeCandidate> = messages
+        .iter()
+        .map(|message| PurgeCandidate {
+            message_id: message.id.get(),
+            author_id: message.author.id.get(),
+            content: message.content.clone(),
+            created_at: *message.timestamp,
+        })
+        .collect();
+
+    let filter = PurgeFilter {
+        author_id: user.as_ref().map(|user| user.id.get()),
+        contains: contains.filter(|text| !text.trim().is_empty()),
+    };
+    let selection = select_purge_targets(&candidates, &filter, count as usize, chrono::Utc::now());
+
+    let ids: Vec<serenity::MessageId> = selection
+        .message_ids
+        .iter()
+        .map(|&id| serenity::MessageId::new(id))
+        .collect();
+    match ids.as_slice() {
+        [] => {}
+        // Bulk delete needs at least two messages; a single delete is logged as usual
+        [id] => channel_id.delete_message(ctx, *id).await?,
+        _ => {
+            // Discord's bulk delete event doesn't say who deleted the messages
+            ctx.data()
+                .logging
+                .note_purge(channel_id.get(), format!("<@{}>", ctx.author().id));
+            channel_id.delete_messages(ctx, &ids).await?;
+        }
+    }
+
+    let mut reply = format!("🧹 Deleted {} message(s).", ids.len());
+    if selection.too_old > 0 {
+        reply.push_str(&format!(
+            "\n⚠️ Skipped {} message(s) older than 14 days - Discord doesn't allow bulk deleting those.",
+            selection.too_old
+        ));
+    }
+    if ids.is_empty() && selection.too_old == 0 {
+        reply = "No matching messages in the last 100 messages of this channel.".to_string();
+    }
+
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Set a channel's slowmode (0 turns it off).
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "MANAGE_CHANNELS",
+    required_bot_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn slowmode(
+    ctx: Context<'_>,
+    #[description = "Seconds between messages per user, 0 to disable (max 21600)"]
+    #[min = 0]
+    #[max = 21600]
+    seconds: u16,
+    #[description = "Channel to change (default: this channel)"] channel: Option<
+        serenity::GuildChannel,
+    >,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a server")?;
+
+    if seconds > MAX_SLOWMODE_SECS {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(format!(
+                    "❌ Slowmode must be between 0 and {} seconds (6 hours).",
+                    MAX_SLOWMODE_SECS
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let channel = match channel {
+        Some(channel) => Some(channel),
+        None => ctx.channel_id().to_channel(ctx).await?.guild(),
+    };
+    let Some(mut channel) = channel else {
+        return send_ephemeral(
+            ctx,
+            "❌ Slowmode only works in server channels.".to_string(),
+        )
+        .await;
+    };
+    if channel.guild_id != guild_id {
+        return send_ephemeral(ctx, "❌ That channel isn't in this server.".to_string()).await;
+    }
+
+    let previous = channel.rate_limit_per_user.unwrap_or(0);
+    channel
+        .edit(
+            ctx,
+            serenity::EditChannel::new().rate_limit_per_user(seconds),
+        )
+        .await?;
+
+    if let Err(e) = crate::discord::logging::events::handle_slowmode_change(
+        ctx.serenity_context(),
+        ctx.data(),
+        guild_id.get(),
+        channel.id.get(),
+        ctx.author().id,
+        previous,
+        seconds,
+    )
+    .await
+    {
+        tracing::warn!("Failed to log slowmode change: {}", e);
+    }
+
+    let describe = |secs: u16| match secs {
+        0 => "off".to_string(),
+        secs => format!("{} seconds", secs),
+    };
+    let content = if seconds == 0 {
+        format!("🐇 Slowmode disabled in <#{}>.", channel.id)
+    } else {
+        format!(
+            "🐢 Slowmode in <#{}> set to {}.",
+            channel.id,
+            describe(seconds)
+        )
+    };
+    ctx.say(format!(
+        "{}\nPrevious value: {} (run `/slowmode seconds:{}` to revert).",
+        content,
+        describe(previous),
+        previous
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Longest `/tempmute`.
+const MAX_TEMPMUTE: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Give a member the mute role for a while.
+///
+/// The role is removed automatically when the time is up, also after a
+/// restart. Set the role with `/muterole` first. Members who already have the
+/// role are left alone.
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "MODERATE_MEMBERS",
+    required_bot_permissions = "MANAGE_ROLES"
+)]
+pub async fn tempmute(
+    ctx: Context<'_>,
+    #[description = "Member to mute"] user: serenity::Member,
+    #[description = "How long, e.g. 30m, 2h or 1d"] duration: String,
+    #[description = "Reason (shown in the audit log)"] reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a server")?;
+
+    let length = match parse_human_duration(&duration) {
+        Ok(length) => length,
+        Err(e) => return send_ephemeral(ctx, format!("❌ Invalid duration: {}", e)).await,
+    };
+    if length.is_zero() || length > MAX_TEMPMUTE {
+        return send_ephemeral(
+            ctx,
+            "❌ A temporary mute must last between 1 second and a year.".to_string(),
+        )
+        .await;
+    }
+    if user.user.bot || user.user.id == ctx.author().id {
+        return send_ephemeral(ctx, "❌ You can't mute that member.".to_string()).await;
+    }
+
+    let temp_roles = &ctx.data().temp_roles;
+    let Some(role_id) = temp_roles.mute_role(guild_id.get()).await? else {
+        return send_ephemeral(
+            ctx,
+            "❌ No mute role is set. Use `/muterole` to choose one.".to_string(),
+        )
+        .await;
+    };
+    let role = serenity::RoleId::new(role_id);
+    // Scheduling a removal would cut short a longer or indefinite mute
+    let has_role = user.roles.contains(&role);
+    match temp_roles.existing_mute(guild_id.get(), user.user.id.get(), role_id, has_role) {
+        Some(ExistingMute::Temporary(expires_at)) => {
+            return send_ephemeral(
+                ctx,
+                format!(
+                    "❌ <@{}> is already muted until <t:{}:f>.",
+                    user.user.id,
+                    expires_at.timestamp()
+                ),
+            )
+            .await;
+        }
+        Some(ExistingMute::Indefinite) => {
+            return send_ephemeral(
+                ctx,
+                format!(
+                    "❌ <@{}> already has the mute role without an end time, so it was left alone.",
+                    user.user.id
+                ),
+            )
+            .await;
+        }
+        None => {}
+    }
+    if let Some(problem) = assignability_problem(ctx, role_id).await {
+        return send_ephemeral(ctx, format!("❌ Mute role: {problem}")).await;
+    }
+    let Some(author) = ctx.author_member().await else {
+        return send_ephemeral(ctx, "❌ Couldn't check your roles.".to_string()).await;
+    };
+    match (
+        member_standing(ctx, &author).await,
+        member_standing(ctx, &user).await,
+    ) {
+        (Some(moderator), Some(target)) if moderator.outranks(&target) => {}
+        (Some(_), Some(_)) => {
+            return send_ephemeral(
+                ctx,
+                "❌ You can only mute members whose highest role is below yours.".to_string(),
+            )
+            .await;
+        }
+        _ => return send_ephemeral(ctx, "❌ Couldn't check your roles.".to_string()).await,
+    }
+
+    let audit_reason = reason.as_deref().unwrap_or("Temporary mute");
+    ctx.http()
+        .add_member_role(guild_id, user.user.id, role, Some(audit_reason))
+        .await?;
+    let scheduled = temp_roles
+        .schedule(
+            guild_id.get(),
+            user.user.id.get(),
+            role_id,
+            chrono::Duration::from_std(length)?,
+            reason.clone(),
+            chrono::Utc::now(),
+        )
+        .await;
+    let entry = match scheduled {
+        Ok(entry) => entry,
+        Err(e) => {
+            // Without a scheduled removal the mute would never end
+            if let Err(undo) = ctx
+                .http()
+                .remove_member_role(guild_id, user.user.id, role, Some("Temporary mute failed"))
+                .await
+            {
+                tracing::warn!(
+                    guild_id = guild_id.get(),
+                    user_id = user.user.id.get(),
+                    error = %undo,
+                    "Failed to take back the mute role after scheduling failed"
+                );
+            }
+            return Err(e);
+        }
+    };
+
+    let mut content = format!(
+        "🔇 Muted <@{}> until <t:{}:f> (<t:{}:R>).",
+        user.user.id,
+        entry.expires_at.timestamp(),
+        entry.expires_at.timestamp()
+    );
+    if let Some(reason) = reason {
+        content.push_str(&format!("\nReason: {}", reason));
+    }
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set or show the role `/tempmute` gives.
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_ROLES")]
+pub async fn muterole(
+    ctx: Context<'_>,
+    #[description = "Role to use (empty: show the current one)"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Must be used in a server")?;
+    let temp_roles = &ctx.data().temp_roles;
+
+    let Some(role) = role else {
+        let content = match temp_roles.mute_role(guild_id.get()).await? {
+            Some(role_id) => format!("The mute role is <@&{}>.", role_id),
+            None => "No mute role is set. Use `/muterole role:<role>` to choose one.".to_string(),
+        };
+        return send_ephemeral(ctx, content).await;
+    };
+
+    if role.managed || role.id.get() == guild_id.get() {
+        return send_ephemeral(
+            ctx,
+            "❌ That role is managed by Discord or an integration and can't be handed out."
+                .to_string(),
+        )
+        .await;
+    }
+    if let Some(problem) = grant_problem(ctx, &role).await {
+        return send_ephemeral(ctx, format!("❌ {problem}")).await;
+    }
+    if let Some(problem) = assignability_problem(ctx, role.id.get()).await {
+        return send_ephemeral(ctx, format!("❌ {problem}")).await;
+    }
+
+    temp_roles
+        .set_mute_role(guild_id.get(), role.id.get())
+        .await?;
+    send_ephemeral(
+        ctx,
+        format!(
+            "✅ `/tempmute` now gives {}. Make sure the role can't send messages in your channels.",
+            role
+        ),
+    )
+    .await
+}
@@ -0,0 +1,76 @@
+// Helpers for text (prefix) commands.
+//
+// Slash commands are the primary interface, but some users prefer typing
+// `!level` or `@Bot level`. Poise handles the actual parsing/dispatch; this
+// module only answers "is this message a prefix command?" so the AI mention
+// handler in `event_handler` doesn't also reply to `@Bot leaderboard`.
+
+use crate::discord::{Data, Error};
+
+/// Strip either the configured prefix or a leading bot mention (`<@id>` / `<@!id>`).
+///
+/// Returns the remaining text (trimmed) or `None` if the message doesn't start
+/// with a command prefix.
+pub fn strip_command_prefix<'a>(content: &'a str, prefix: &str, bot_id: u64) -> Option<&'a str> {
+    let content = content.trim_start();
+
+    if !prefix.is_empty() {
+        if let Some(rest) = content.strip_prefix(prefix) {
+            return Some(rest.trim_start());
+        }
+    }
+
+    let mention = format!("<@{}>", bot_id);
+    let nick_mention = format!("<@!{}>", bot_id);
+    content
+        .strip_prefix(&mention)
+        .or_else(|| content.strip_prefix(&nick_mention))
+        .map(str::trim_start)
+}
+
+/// Check whether `name` is the name or alias of a command with a prefix implementation.
+fn matches_prefix_command(name: &str, commands: &[poise::Command<Data, Error>]) -> bool {
+    commands.iter().any(|command| {
+        command.prefix_action.is_some()
+            && (command.name == name || command.aliases.iter().any(|alias| alias == name))
+    })
+}
+
+/// Returns true if the message will be handled by poise as a prefix command.
+pub fn is_prefix_command_invocation(
+    content: &str,
+    prefix: &str,
+    bot_id: u64,
+    commands: &[poise::Command<Data, Error>],
+) -> bool {
+    let Some(rest) = strip_command_prefix(content, prefix, bot_id) else {
+        return false;
+    };
+
+    rest.split_whitespace()
+        .next()
+        .is_some_and(|name| matches_prefix_command(name, commands))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_configured_prefix() {
+        assert_eq!(strip_command_prefix("!level", "!", 42), Some("level"));
+        assert_eq!(
+            strip_command_prefix("  !! leaderboard", "!!", 42),
+            Some("leaderboard")
+        );
+        assert_eq!(strip_command_prefix("hello !level", "!", 42), None);
+    }
+
+    #[test]
+    fn strips_bot_mention() {
+        assert_eq!(strip_command_prefix("<@42> level", "!", 42), Some("level"));
+        assert_eq!(strip_command_prefix("<@!42>   help", "!", 42), Some("help"));
+        // Mentions of other users are not a prefix
+        assert_eq!(strip_command_prefix("<@7> level", "!", 42), None);
+    }
+}
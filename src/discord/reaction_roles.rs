@@ -0,0 +1,123 @@
+// Gives and takes reaction roles (`/reactionrole`) when members react.
+//
+// The bindings live in `core::reactionroles`; this module turns a reaction
+// event into a lookup, checks that the bot can hand out the role (reusing the
+// auto-role checks) and performs the HTTP call.
+
+use crate::core::autorole::{decide_auto_role, AutoRoleConfig, AutoRoleDecision};
+use crate::core::reactionroles::emoji_key;
+use crate::discord::auto_role::role_facts;
+use crate::discord::Data;
+use poise::serenity_prelude as serenity;
+
+/// The key a reaction's emoji is bound under (see `core::reactionroles::emoji_key`).
+pub fn reaction_emoji_key(emoji: &serenity::ReactionType) -> Option<String> {
+    match emoji {
+        serenity::ReactionType::Custom { id, .. } => Some(id.get().to_string()),
+        serenity::ReactionType::Unicode(text) => emoji_key(text),
+        _ => None,
+    }
+}
+
+/// Give (`added`) or take away the role bound to a reaction. Never fails the
+/// event handler: problems are logged and the member is left as is.
+pub async fn handle_reaction(
+    ctx: &serenity::Context,
+    data: &Data,
+    reaction: &serenity::Reaction,
+    added: bool,
+) {
+    let (Some(guild_id), Some(user_id)) = (reaction.guild_id, reaction.user_id) else {
+        return;
+    };
+    // The bot reacts to bound messages itself so members can click along
+    if user_id == ctx.cache.current_user().id {
+        return;
+    }
+    if reaction
+        .member
+        .as_ref()
+        .is_some_and(|member| member.user.bot)
+    {
+        return;
+    }
+    let Some(emoji) = reaction_emoji_key(&reaction.emoji) else {
+        return;
+    };
+
+    let role_id = match data
+        .reaction_roles
+        .role_for(guild_id.get(), reaction.message_id.get(), &emoji)
+        .await
+    {
+        Ok(Some(role_id)) => role_id,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(guild_id = guild_id.get(), error = %e, "Failed to load reaction roles");
+            return;
+        }
+    };
+
+    let Some((role_position, bot)) = role_facts(ctx, guild_id, role_id).await else {
+        tracing::warn!(
+            guild_id = guild_id.get(),
+            "Could not load roles to apply a reaction role"
+        );
+        return;
+    };
+    let config = AutoRoleConfig {
+        role_id,
+        enabled: true,
+    };
+    match decide_auto_role(Some(config), &[], role_position, bot) {
+        AutoRoleDecision::Assign(_) => {}
+        skipped => {
+            tracing::warn!(
+                guild_id = guild_id.get(),
+                role_id,
+                reason = ?skipped,
+                "Reaction role not applied"
+            );
+            return;
+        }
+    }
+
+    let role = serenity::RoleId::new(role_id);
+    let result = if added {
+        ctx.http
+            .add_member_role(guild_id, user_id, role, Some("Reaction role"))
+            .await
+    } else {
+        ctx.http
+            .remove_member_role(guild_id, user_id, role, Some("Reaction role removed"))
+            .await
+    };
+    if let Err(e) = result {
+        tracing::warn!(
+            guild_id = guild_id.get(),
+            user_id = user_id.get(),
+            role_id,
+            added,
+            error = %e,
+            "Failed to update reaction role"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reaction_keys_match_command_input() {
+        let custom = serenity::ReactionType::Custom {
+            animated: false,
+            id: serenity::EmojiId::new(123456),
+            name: Some("pepe".to_string()),
+        };
+        assert_eq!(reaction_emoji_key(&custom), emoji_key("<:pepe:123456>"));
+
+        let unicode = serenity::ReactionType::Unicode("❤".to_string());
+        assert_eq!(reaction_emoji_key(&unicode), emoji_key("❤️"));
+    }
+}
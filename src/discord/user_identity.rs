@@ -0,0 +1,194 @@
+// Names and avatars for profile-like embeds.
+//
+// Most of the time we have a live `User` or a cached `Member`. But stored XP
+// outlives guild membership: leaderboards and rank cards only know a
+// `user_id`, and some of those users have left (or deleted their account).
+// Passing an unknown user straight to `.thumbnail()` gives a broken image.
+//
+// `resolve_identity` tries the cache, then a single HTTP `get_user`, and
+// finally falls back to Discord's default avatar and a "Former member" label.
+// A failed lookup is remembered for a while (`UnknownUsers`), so paging a
+// leaderboard full of deleted accounts doesn't repeat the same requests.
+
+use crate::discord::i18n::tr_guild;
+use crate::discord::Data;
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use std::time::{Duration, Instant};
+
+/// How long a failed `get_user` lookup is trusted.
+const UNKNOWN_USER_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Users whose `get_user` lookup failed recently.
+pub struct UnknownUsers {
+    /// user_id -> when the lookup failed
+    failed_at: DashMap<u64, Instant>,
+    ttl: Duration,
+}
+
+impl UnknownUsers {
+    pub fn new() -> Self {
+        Self::with_ttl(UNKNOWN_USER_TTL)
+    }
+
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            failed_at: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Whether a lookup for `user_id` failed within the TTL.
+    fn contains(&self, user_id: u64) -> bool {
+        self.failed_at
+            .get(&user_id)
+            .is_some_and(|failed_at| failed_at.elapsed() < self.ttl)
+    }
+
+    fn remember(&self, user_id: u64) {
+        // Failures are rare, so sweeping expired ones here is cheap
+        self.failed_at
+            .retain(|_, failed_at| failed_at.elapsed() < self.ttl);
+        self.failed_at.insert(user_id, Instant::now());
+    }
+}
+
+impl Default for UnknownUsers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Display name and avatar URL to show for a user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayIdentity {
+    pub name: String,
+    pub avatar_url: String,
+    /// True when the user couldn't be found at all (left the guild and the
+    /// account lookup failed).
+    pub former_member: bool,
+}
+
+impl DisplayIdentity {
+    /// Identity from a known user, preferring their guild nickname and avatar
+    /// when the member is cached.
+    pub fn for_user(
+        ctx: &serenity::Context,
+        guild_id: serenity::GuildId,
+        user: &serenity::User,
+    ) -> Self {
+        if let Some(guild) = ctx.cache.guild(guild_id) {
+            if let Some(member) = guild.members.get(&user.id) {
+                return Self {
+                    name: member.display_name().to_string(),
+                    avatar_url: member.face(),
+                    former_member: false,
+                };
+            }
+        }
+
+        Self::from_user(user)
+    }
+
+    fn from_user(user: &serenity::User) -> Self {
+        Self {
+            name: user
+                .global_name
+                .clone()
+                .unwrap_or_else(|| user.name.clone()),
+            avatar_url: user.face(),
+            former_member: false,
+        }
+    }
+
+    fn former_member(user_id: u64, label: String) -> Self {
+        Self {
+            name: label,
+            avatar_url: placeholder_avatar_url(user_id),
+            former_member: true,
+        }
+    }
+}
+
+/// Resolve a name and avatar for `user_id`, even if they left the guild.
+///
+/// Cache first (no I/O), then one HTTP `get_user` unless one failed recently.
+/// If that fails too, returns a placeholder avatar and a localized "Former
+/// member" label.
+pub async fn resolve_identity(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    user_id: u64,
+) -> DisplayIdentity {
+    let user_id_s = serenity::UserId::new(user_id);
+
+    if let Some(guild) = ctx.cache.guild(guild_id) {
+        if let Some(member) = guild.members.get(&user_id_s) {
+            return DisplayIdentity {
+                name: member.display_name().to_string(),
+                avatar_url: member.face(),
+                former_member: false,
+            };
+        }
+    }
+
+    if let Some(user) = ctx.cache.user(user_id_s).map(|user| user.clone()) {
+        return DisplayIdentity::from_user(&user);
+    }
+
+    if !data.unknown_users.contains(user_id) {
+        match ctx.http.get_user(user_id_s).await {
+            Ok(user) => return DisplayIdentity::from_user(&user),
+            Err(e) => {
+                tracing::debug!(user_id, error = %e, "User lookup failed, using placeholder");
+                data.unknown_users.remember(user_id);
+            }
+        }
+    }
+
+    let label = tr_guild(ctx, data, Some(guild_id), "common.former_member", &[]);
+    DisplayIdentity::former_member(user_id, label)
+}
+
+/// One of Discord's built-in default avatars, picked deterministically from
+/// the user id (same formula Discord uses for accounts without an avatar).
+fn placeholder_avatar_url(user_id: u64) -> String {
+    format!(
+        "https://cdn.discordapp.com/embed/avatars/{}.png",
+        (user_id >> 22) % 6
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_former_member_uses_default_avatar() {
+        let identity = DisplayIdentity::former_member(0, "Former member".to_string());
+        assert!(identity.former_member);
+        assert_eq!(identity.name, "Former member");
+        assert_eq!(
+            identity.avatar_url,
+            "https://cdn.discordapp.com/embed/avatars/0.png"
+        );
+
+        // Always one of the six default avatars
+        let url = placeholder_avatar_url(u64::MAX);
+        assert!(url.ends_with(&format!("/{}.png", (u64::MAX >> 22) % 6)));
+    }
+
+    #[test]
+    fn test_unknown_users_expire() {
+        let unknown = UnknownUsers::new();
+        assert!(!unknown.contains(1));
+        unknown.remember(1);
+        assert!(unknown.contains(1));
+        assert!(!unknown.contains(2));
+
+        let expired = UnknownUsers::with_ttl(Duration::ZERO);
+        expired.remember(1);
+        assert!(!expired.contains(1));
+    }
+}
@@ -0,0 +1,111 @@
+// What the event handler reads from an incoming message.
+//
+// The decisions are plain functions over text and file names, so they can be
+// tested without building serenity types; the `serenity::Message` wrappers
+// just pick out the fields.
+
+use crate::core::leveling::MessageContentStats;
+use crate::core::util::{contains_link, count_custom_emojis, is_long_message};
+use poise::serenity_prelude as serenity;
+
+/// Attachment extensions that count as an image for the XP content bonus.
+const IMAGE_EXTENSIONS: &[&str] = &[".png", ".jpg", ".jpeg", ".gif", ".webp"];
+
+/// Whether an attachment is an image, judged by its file name.
+pub fn is_image_filename(filename: &str) -> bool {
+    let name = filename.to_lowercase();
+    IMAGE_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
+
+/// Content stats for the leveling achievements and bonuses.
+pub fn content_stats<'a>(
+    content: &str,
+    attachment_filenames: impl IntoIterator<Item = &'a str>,
+    stickers: usize,
+) -> MessageContentStats {
+    MessageContentStats {
+        has_image: attachment_filenames.into_iter().any(is_image_filename),
+        is_long: is_long_message(content),
+        has_link: contains_link(content),
+        custom_emojis: count_custom_emojis(content) as u64,
+        stickers: stickers as u64,
+    }
+}
+
+/// `content_stats` for a received message.
+pub fn message_content_stats(message: &serenity::Message) -> MessageContentStats {
+    content_stats(
+        &message.content,
+        message.attachments.iter().map(|a| a.filename.as_str()),
+        message.sticker_items.len(),
+    )
+}
+
+/// A fetched channel message as AI conversation history.
+#[cfg(feature = "ai")]
+pub fn history_message(message: &serenity::Message) -> crate::core::ai::HistoryMessage {
+    crate::core::ai::HistoryMessage {
+        author_id: message.author.id.get(),
+        author_name: message.author.name.clone(),
+        author_is_bot: message.author.bot,
+        content: message.content.clone(),
+        timestamp: message.timestamp.unix_timestamp() as u64,
+        images: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_detection_by_extension() {
+        assert!(is_image_filename("screenshot.png"));
+        assert!(is_image_filename("PHOTO.JPEG"));
+        assert!(is_image_filename("meme.final.webp"));
+        assert!(is_image_filename("dance.gif"));
+        assert!(!is_image_filename("notes.pdf"));
+        assert!(!is_image_filename("png"));
+        assert!(!is_image_filename("image.png.zip"));
+    }
+
+    #[test]
+    fn test_content_stats() {
+        let stats = content_stats(
+            "look at this https://example.com <:pepe:123> <a:dance:456>",
+            ["readme.txt", "shot.PNG"],
+            2,
+        );
+        assert!(stats.has_image);
+        assert!(stats.has_link);
+        assert!(!stats.is_long);
+        assert_eq!(stats.custom_emojis, 2);
+        assert_eq!(stats.stickers, 2);
+
+        let long = "word ".repeat(100);
+        let stats = content_stats(&long, ["video.mp4"], 0);
+        assert!(stats.is_long);
+        assert!(!stats.has_image && !stats.has_link);
+        assert_eq!(stats.custom_emojis, 0);
+
+        // A bare domain isn't a link
+        assert!(!content_stats("example.com", [], 0).has_link);
+    }
+
+    #[cfg(feature = "ai")]
+    #[test]
+    fn test_history_message_copies_author_and_content() {
+        let mut message = serenity::Message::default();
+        message.author.id = serenity::UserId::new(42);
+        message.author.name = "greybeard".to_string();
+        message.author.bot = true;
+        message.content = "hello".to_string();
+
+        let history = history_message(&message);
+        assert_eq!(history.author_id, 42);
+        assert_eq!(history.author_name, "greybeard");
+        assert!(history.author_is_bot);
+        assert_eq!(history.content, "hello");
+        assert_eq!(history.timestamp, message.timestamp.unix_timestamp() as u64);
+    }
+}
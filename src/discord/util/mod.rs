@@ -0,0 +1,9 @@
+// Small building blocks shared by several Discord commands.
+
+pub mod messages;
+pub mod paginator;
+pub mod permissions;
+pub mod roles;
+
+pub use messages::message_content_stats;
+pub use paginator::Paginator;
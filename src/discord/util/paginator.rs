@@ -0,0 +1,238 @@
+// Button-driven paging for embeds.
+//
+// A command works out how many pages it has and how to build one, and the
+// paginator does the rest: it posts the first page with ◀ / ▶ buttons (plus an
+// optional jump button such as "Find Me"), swaps the embed when the command's
+// author clicks, and strips the buttons once nobody has clicked for a while.
+//
+// ```rust
+// Paginator::new(total_pages)
+//     .jump_button("🔍 Find Me", my_page, "You are not on the leaderboard yet!")
+//     .run(ctx, |page| build_page(ctx, page))
+//     .await?;
+// ```
+
+use crate::discord::{Context, Error};
+use poise::serenity_prelude as serenity;
+use std::future::Future;
+use std::time::Duration;
+
+/// How long the buttons stay active after the last click.
+const TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+const PREV_ID: &str = "paginator_prev";
+const NEXT_ID: &str = "paginator_next";
+const JUMP_ID: &str = "paginator_jump";
+
+/// Extra button that jumps straight to one page, e.g. the caller's own rank.
+struct JumpButton {
+    label: String,
+    /// `None` when there is nowhere to jump to; clicking then explains why.
+    page: Option<usize>,
+    unavailable: String,
+}
+
+/// Posts a paged embed and handles its navigation buttons.
+pub struct Paginator {
+    total_pages: usize,
+    page: usize,
+    jump: Option<JumpButton>,
+}
+
+impl Paginator {
+    /// A paginator over `total_pages` pages (at least one), starting on page 1.
+    pub fn new(total_pages: usize) -> Self {
+        Self {
+            total_pages: total_pages.max(1),
+            page: 1,
+            jump: None,
+        }
+    }
+
+    /// Open on `page` (1-based, clamped to the valid range) instead of page 1.
+    pub fn start_at(mut self, page: usize) -> Self {
+        self.page = page.clamp(1, self.total_pages);
+        self
+    }
+
+    /// Add a button that jumps to `page`. When `page` is `None` the click is
+    /// answered privately with `unavailable` instead.
+    pub fn jump_button(
+        mut self,
+        label: impl Into<String>,
+        page: Option<usize>,
+        unavailable: impl Into<String>,
+    ) -> Self {
+        self.jump = Some(JumpButton {
+            label: label.into(),
+            page,
+            unavailable: unavailable.into(),
+        });
+        self
+    }
+
+    /// Page through embeds that are already built.
+    pub async fn run_pages(
+        self,
+        ctx: Context<'_>,
+        pages: Vec<serenity::CreateEmbed>,
+    ) -> Result<(), Error> {
+        let paginator = Self {
+            total_pages: pages.len().max(1),
+            ..self
+        };
+        paginator
+            .run(ctx, |page| {
+                let embed = pages.get(page - 1).cloned().unwrap_or_default();
+                async move { Ok(embed) }
+            })
+            .await
+    }
+
+    /// Post the current page and handle clicks until the timeout. `build_page`
+    /// gets a 1-based page number; a "Page x/y" footer is added to its embed.
+    pub async fn run<F, Fut>(mut self, ctx: Context<'_>, mut build_page: F) -> Result<(), Error>
+    where
+        F: FnMut(usize) -> Fut,
+        Fut: Future<Output = Result<serenity::CreateEmbed, Error>>,
+    {
+        let embed = build_page(self.page).await?;
+        let msg = ctx.send(self.reply(embed)).await?;
+        let msg_id = msg.message().await?.id;
+
+        while let Some(mci) = serenity::ComponentInteractionCollector::new(ctx)
+            .author_id(ctx.author().id)
+            .channel_id(ctx.channel_id())
+            .timeout(TIMEOUT)
+            .filter(move |mci| mci.message.id == msg_id)
+            .await
+        {
+            let target = match mci.data.custom_id.as_str() {
+                PREV_ID => self.page.saturating_sub(1),
+                NEXT_ID => self.page + 1,
+                JUMP_ID => match self.jump.as_ref().and_then(|jump| jump.page) {
+                    Some(page) => page,
+                    None => {
+                        let unavailable = self
+                            .jump
+                            .as_ref()
+                            .map(|jump| jump.unavailable.clone())
+                            .unwrap_or_default();
+                        let response = serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new()
+                                .content(unavailable)
+                                .ephemeral(true),
+                        );
+                        if let Err(e) = mci.create_response(&ctx, response).await {
+                            tracing::warn!(error = ?e, "Failed to answer paginator jump");
+                        }
+                        continue;
+                    }
+                },
+                _ => continue,
+            };
+            self.page = target.clamp(1, self.total_pages);
+
+            // Defer the update to prevent "Unknown interaction" errors if building takes > 3s
+            if let Err(e) = mci.defer(&ctx.http()).await {
+                tracing::warn!(error = ?e, "Failed to defer paginator interaction");
+                continue;
+            }
+
+            let embed = match build_page(self.page).await {
+                Ok(embed) => embed,
+                Err(e) => {
+                    tracing::warn!(page = self.page, error = ?e, "Failed to build page");
+                    continue;
+                }
+            };
+            if let Err(e) = msg.edit(ctx, self.reply(embed)).await {
+                tracing::warn!(error = ?e, "Failed to update paginated message");
+            }
+        }
+
+        // Remove the buttons once nobody is clicking any more
+        let _ = msg
+            .edit(ctx, poise::CreateReply::default().components(vec![]))
+            .await;
+        Ok(())
+    }
+
+    /// The current page's embed with its footer and navigation buttons.
+    fn reply(&self, embed: serenity::CreateEmbed) -> poise::CreateReply {
+        let embed = embed.footer(serenity::CreateEmbedFooter::new(format!(
+            "Page {}/{}",
+            self.page, self.total_pages
+        )));
+
+        let mut buttons = vec![
+            serenity::CreateButton::new(PREV_ID)
+                .label("◀ Previous")
+                .style(serenity::ButtonStyle::Primary)
+                .disabled(self.page == 1),
+            serenity::CreateButton::new(NEXT_ID)
+                .label("Next ▶")
+                .style(serenity::ButtonStyle::Primary)
+                .disabled(self.page == self.total_pages),
+        ];
+        if let Some(jump) = &self.jump {
+            buttons.push(
+                serenity::CreateButton::new(JUMP_ID)
+                    .label(jump.label.clone())
+                    .style(serenity::ButtonStyle::Secondary),
+            );
+        }
+
+        poise::CreateReply::default()
+            .embed(embed)
+            .components(vec![serenity::CreateActionRow::Buttons(buttons)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn button_states(reply: poise::CreateReply) -> Vec<(String, bool)> {
+        let components = serde_json::to_value(reply.components.unwrap()).unwrap();
+        components[0]["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|button| {
+                (
+                    button["custom_id"].as_str().unwrap().to_string(),
+                    button["disabled"].as_bool().unwrap_or(false),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_buttons_follow_the_current_page() {
+        let first = Paginator::new(3);
+        assert_eq!(
+            button_states(first.reply(serenity::CreateEmbed::new())),
+            vec![(PREV_ID.to_string(), true), (NEXT_ID.to_string(), false)]
+        );
+
+        let last = Paginator::new(3)
+            .start_at(7)
+            .jump_button("Find Me", Some(2), "Not ranked");
+        assert_eq!(last.page, 3);
+        let reply = last.reply(serenity::CreateEmbed::new());
+        let embed = serde_json::to_value(&reply.embeds[0]).unwrap();
+        assert_eq!(embed["footer"]["text"], "Page 3/3");
+        assert_eq!(
+            button_states(reply),
+            vec![
+                (PREV_ID.to_string(), false),
+                (NEXT_ID.to_string(), true),
+                (JUMP_ID.to_string(), false),
+            ]
+        );
+
+        // No pages still shows one (empty) page rather than "Page 1/0"
+        assert_eq!(Paginator::new(0).total_pages, 1);
+    }
+}
@@ -0,0 +1,81 @@
+// Can the bot post in a channel?
+//
+// Checked from the cache before work whose only result is a message, like an
+// AI reply or a level-up announcement, so the bot doesn't pay for an API call
+// it can't deliver. When the cache can't tell (cold cache, DMs) the answer is
+// yes and the send itself reports any error.
+
+use poise::serenity_prelude as serenity;
+
+/// Whether the bot may send messages in `channel_id`, which can be a thread.
+pub fn can_send_messages(
+    ctx: &serenity::Context,
+    guild_id: Option<serenity::GuildId>,
+    channel_id: serenity::ChannelId,
+) -> bool {
+    let Some(guild_id) = guild_id else {
+        return true;
+    };
+    let bot_id = ctx.cache.current_user().id;
+    let Some(guild) = ctx.cache.guild(guild_id) else {
+        return true;
+    };
+    let Some(member) = guild.members.get(&bot_id) else {
+        return true;
+    };
+
+    // Threads take their permission overwrites from the parent channel
+    let (channel, in_thread) = match guild.channels.get(&channel_id) {
+        Some(channel) => (channel, false),
+        None => {
+            let parent = guild
+                .threads
+                .iter()
+                .find(|thread| thread.id == channel_id)
+                .and_then(|thread| thread.parent_id)
+                .and_then(|parent_id| guild.channels.get(&parent_id));
+            match parent {
+                Some(parent) => (parent, true),
+                None => return true,
+            }
+        }
+    };
+
+    allows_sending(guild.user_permissions_in(channel, member), in_thread)
+}
+
+/// Whether `permissions` in a channel are enough to post a message there.
+fn allows_sending(permissions: serenity::Permissions, in_thread: bool) -> bool {
+    if permissions.administrator() {
+        return true;
+    }
+    let can_send = if in_thread {
+        permissions.send_messages_in_threads()
+    } else {
+        permissions.send_messages()
+    };
+    permissions.view_channel() && can_send
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poise::serenity_prelude::Permissions;
+
+    #[test]
+    fn test_sending_needs_view_and_send() {
+        let chat = Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES;
+        assert!(allows_sending(chat, false));
+        assert!(!allows_sending(Permissions::VIEW_CHANNEL, false));
+        assert!(!allows_sending(Permissions::SEND_MESSAGES, false));
+
+        // Threads have their own send permission
+        assert!(!allows_sending(chat, true));
+        assert!(allows_sending(
+            Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES_IN_THREADS,
+            true
+        ));
+
+        assert!(allows_sending(Permissions::ADMINISTRATOR, true));
+    }
+}
@@ -0,0 +1,238 @@
+// Checks for commands that let the bot hand out a role on someone's behalf
+// (reaction roles, the auto-role, the mute role).
+//
+// The bot can assign any role below its own, which is often more than the
+// member setting things up may give out. These checks keep those commands
+// from being a way around Discord's role hierarchy: the member must be able
+// to manage the role themselves, and roles carrying moderation powers are
+// refused outright.
+
+use crate::core::autorole::{decide_auto_role, AutoRoleConfig, AutoRoleDecision};
+use crate::discord::auto_role::role_facts;
+use crate::discord::commands::leveling::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// Permissions a self-service role must not grant.
+pub const ELEVATED_PERMISSIONS: serenity::Permissions = serenity::Permissions::ADMINISTRATOR
+    .union(serenity::Permissions::MANAGE_GUILD)
+    .union(serenity::Permissions::MANAGE_ROLES)
+    .union(serenity::Permissions::MANAGE_CHANNELS)
+    .union(serenity::Permissions::MANAGE_WEBHOOKS)
+    .union(serenity::Permissions::MANAGE_MESSAGES)
+    .union(serenity::Permissions::MANAGE_NICKNAMES)
+    .union(serenity::Permissions::BAN_MEMBERS)
+    .union(serenity::Permissions::KICK_MEMBERS)
+    .union(serenity::Permissions::MODERATE_MEMBERS)
+    .union(serenity::Permissions::MENTION_EVERYONE);
+
+/// The elevated permissions `permissions` includes, empty if none.
+pub fn elevated_permissions(permissions: serenity::Permissions) -> serenity::Permissions {
+    permissions & ELEVATED_PERMISSIONS
+}
+
+/// Where a member stands in the guild's role hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemberStanding {
+    pub is_owner: bool,
+    /// MANAGE_ROLES or ADMINISTRATOR.
+    pub can_manage_roles: bool,
+    /// Position of the member's highest role, 0 with only @everyone.
+    pub highest_position: u16,
+}
+
+impl MemberStanding {
+    /// Whether the member may hand out a role at `role_position`: it's below
+    /// their highest role. Like in Discord, Manage Roles doesn't lift that
+    /// limit; only the owner is above it.
+    pub fn can_grant(&self, role_position: u16) -> bool {
+        self.is_owner || self.highest_position > role_position
+    }
+
+    /// Whether the member's highest role is above `other`'s. Nobody outranks
+    /// the owner.
+    pub fn outranks(&self, other: &MemberStanding) -> bool {
+        if other.is_owner {
+            return false;
+        }
+        self.is_owner || self.highest_position > other.highest_position
+    }
+}
+
+/// The standing of `member` in the command's guild. Prefers the cache and
+/// falls back to HTTP; `None` if neither worked.
+pub async fn member_standing(
+    ctx: Context<'_>,
+    member: &serenity::Member,
+) -> Option<MemberStanding> {
+    let cached = ctx.guild().map(|guild| {
+        standing(
+            &guild.roles,
+            guild.owner_id,
+            guild.member_permissions(member),
+            member,
+        )
+    });
+    if cached.is_some() {
+        return cached;
+    }
+
+    let guild = ctx.guild_id()?.to_partial_guild(ctx).await.ok()?;
+    Some(standing(
+        &guild.roles,
+        guild.owner_id,
+        guild.member_permissions(member),
+        member,
+    ))
+}
+
+fn standing(
+    roles: &std::collections::HashMap<serenity::RoleId, serenity::Role>,
+    owner_id: serenity::UserId,
+    permissions: serenity::Permissions,
+    member: &serenity::Member,
+) -> MemberStanding {
+    let highest_position = member
+        .roles
+        .iter()
+        .filter_map(|id| roles.get(id))
+        .map(|role| role.position)
+        .max()
+        .unwrap_or(0);
+
+    MemberStanding {
+        is_owner: member.user.id == owner_id,
+        can_manage_roles: permissions.administrator() || permissions.manage_roles(),
+        highest_position,
+    }
+}
+
+/// Explain why the bot couldn't hand out `role_id` right now, if it couldn't.
+pub async fn assignability_problem(ctx: Context<'_>, role_id: u64) -> Option<String> {
+    let guild_id = ctx.guild_id()?;
+    let Some((position, bot)) = role_facts(ctx.serenity_context(), guild_id, role_id).await else {
+        return Some("Couldn't check the bot's permissions.".to_string());
+    };
+
+    let config = AutoRoleConfig {
+        role_id,
+        enabled: true,
+    };
+    match decide_auto_role(Some(config), &[], position, bot) {
+        AutoRoleDecision::MissingPermission => {
+            Some("The bot needs the Manage Roles permission to assign it.".to_string())
+        }
+        AutoRoleDecision::RoleTooHigh => Some(
+            "That role is at or above the bot's highest role. Move the bot's role above it."
+                .to_string(),
+        ),
+        AutoRoleDecision::RoleMissing => Some("That role no longer exists.".to_string()),
+        _ => None,
+    }
+}
+
+/// Explain why the command's author may not hand out `role`, if they may not.
+/// Checked before the bot hands the role out on their behalf.
+pub async fn grant_problem(ctx: Context<'_>, role: &serenity::Role) -> Option<String> {
+    let elevated = elevated_permissions(role.permissions);
+    if !elevated.is_empty() {
+        return Some(format!(
+            "{} has moderation permissions ({}), so the bot won't hand it out.",
+            role,
+            elevated.get_permission_names().join(", ")
+        ));
+    }
+
+    let Some(author) = ctx.author_member().await else {
+        return Some("Couldn't check your roles.".to_string());
+    };
+    match member_standing(ctx, &author).await {
+        Some(standing) if standing.can_grant(role.position) => None,
+        Some(_) => Some(format!(
+            "{} is at or above your highest role, so you can't hand it out.",
+            role
+        )),
+        None => Some("Couldn't check your roles.".to_string()),
+    }
+}
+
+/// Reply only the author sees, without pinging anyone mentioned in it.
+pub async fn send_ephemeral(ctx: Context<'_>, content: String) -> Result<(), Error> {
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .ephemeral(true)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poise::serenity_prelude::Permissions;
+
+    const MEMBER: MemberStanding = MemberStanding {
+        is_owner: false,
+        can_manage_roles: false,
+        highest_position: 5,
+    };
+
+    #[test]
+    fn test_elevated_permissions() {
+        let harmless = Permissions::SEND_MESSAGES | Permissions::ADD_REACTIONS;
+        assert!(elevated_permissions(harmless).is_empty());
+        assert_eq!(
+            elevated_permissions(harmless | Permissions::BAN_MEMBERS),
+            Permissions::BAN_MEMBERS
+        );
+        assert!(!elevated_permissions(Permissions::ADMINISTRATOR).is_empty());
+    }
+
+    #[test]
+    fn test_can_grant_needs_a_higher_role() {
+        assert!(MEMBER.can_grant(4));
+        // Equal position is out of reach, like for the bot
+        assert!(!MEMBER.can_grant(5));
+        assert!(!MEMBER.can_grant(9));
+
+        // Manage Roles doesn't reach above the manager's own roles
+        let manager = MemberStanding {
+            can_manage_roles: true,
+            ..MEMBER
+        };
+        assert!(!manager.can_grant(9));
+        assert!(!manager.can_grant(5));
+        assert!(manager.can_grant(4));
+        let owner = MemberStanding {
+            is_owner: true,
+            ..MEMBER
+        };
+        assert!(owner.can_grant(9));
+    }
+
+    #[test]
+    fn test_outranks() {
+        let lower = MemberStanding {
+            highest_position: 2,
+            ..MEMBER
+        };
+        assert!(MEMBER.outranks(&lower));
+        assert!(!lower.outranks(&MEMBER));
+        assert!(!MEMBER.outranks(&MEMBER));
+
+        let owner = MemberStanding {
+            is_owner: true,
+            highest_position: 0,
+            ..MEMBER
+        };
+        assert!(owner.outranks(&MEMBER));
+        // Managing roles doesn't lift anyone above the owner
+        let admin = MemberStanding {
+            can_manage_roles: true,
+            highest_position: 50,
+            ..MEMBER
+        };
+        assert!(!admin.outranks(&owner));
+    }
+}
@@ -0,0 +1,171 @@
+// Posts the guild's welcome message when a member joins (`/config welcome`)
+// and its goodbye message when one leaves (`/config goodbye`).
+//
+// Rendering lives in `core::welcome`; this module gathers the member count,
+// server name and join date, asks the removal's audit lookup whether it was a
+// kick or ban, and sends the embed.
+
+use crate::core::welcome::{
+    render_goodbye, render_welcome, GoodbyeConfig, GoodbyeContext, WelcomeConfig, WelcomeContext,
+};
+use crate::discord::commands::server_stats::{guild_counts, StatsUpdateEvent};
+use crate::discord::logging::events::RemovalAudit;
+use crate::discord::Data;
+use poise::serenity_prelude as serenity;
+
+/// Post the welcome message for a member who just joined. Never fails the
+/// join handler: problems are logged and the join goes unannounced.
+pub async fn send_on_join(ctx: &serenity::Context, data: &Data, member: &serenity::Member) {
+    let guild_id = member.guild_id.get();
+    let config = match data.welcome.welcome(guild_id).await {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(guild_id, error = %e, "Failed to load welcome message");
+            return;
+        }
+    };
+
+    if let Err(e) = post_welcome(ctx, &config, member).await {
+        tracing::warn!(
+            guild_id,
+            channel_id = config.channel_id,
+            user_id = member.user.id.get(),
+            error = %e,
+            "Failed to post welcome message"
+        );
+    }
+}
+
+/// Render `config` for `member` and post it in the configured channel.
+pub async fn post_welcome(
+    ctx: &serenity::Context,
+    config: &WelcomeConfig,
+    member: &serenity::Member,
+) -> Result<serenity::Message, serenity::Error> {
+    let (member_count, guild_name) =
+        guild_facts(ctx, member.guild_id, StatsUpdateEvent::MemberJoin(member)).await;
+    let description = render_welcome(
+        &config.template,
+        &WelcomeContext {
+            user_id: member.user.id.get(),
+            member_count,
+            guild_name,
+        },
+    );
+
+    // `face()` falls back to Discord's default avatar for members without one
+    let embed = serenity::CreateEmbed::new()
+        .description(description)
+        .thumbnail(member.face())
+        .color(0x57F287)
+        .timestamp(serenity::Timestamp::now());
+
+    serenity::ChannelId::new(config.channel_id)
+        .send_message(
+            ctx,
+            serenity::CreateMessage::new()
+                .embed(embed)
+                .allowed_mentions(serenity::CreateAllowedMentions::new()),
+        )
+        .await
+}
+
+/// Post the goodbye message for a member who just left, unless they were
+/// kicked or banned and the guild skips those. Never fails the removal
+/// handler.
+pub async fn send_on_leave(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+    member: Option<&serenity::Member>,
+    removal: &RemovalAudit,
+) {
+    let config = match data.goodbye.goodbye(guild_id.get()).await {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(guild_id = guild_id.get(), error = %e, "Failed to load goodbye message");
+            return;
+        }
+    };
+
+    if config.skip_kicks_and_bans && removal.kicked_or_banned(ctx).await {
+        return;
+    }
+
+    let joined_at = member.and_then(|member| member.joined_at);
+    if let Err(e) = post_goodbye(ctx, &config, guild_id, user, joined_at).await {
+        tracing::warn!(
+            guild_id = guild_id.get(),
+            channel_id = config.channel_id,
+            user_id = user.id.get(),
+            error = %e,
+            "Failed to post goodbye message"
+        );
+    }
+}
+
+/// Render `config` for `user` and post it in the configured channel.
+/// `joined_at` is `None` when Discord didn't have the member cached.
+pub async fn post_goodbye(
+    ctx: &serenity::Context,
+    config: &GoodbyeConfig,
+    guild_id: serenity::GuildId,
+    user: &serenity::User,
+    joined_at: Option<serenity::Timestamp>,
+) -> Result<serenity::Message, serenity::Error> {
+    let (member_count, guild_name) =
+        guild_facts(ctx, guild_id, StatsUpdateEvent::MemberLeave(user)).await;
+    let time_in_server = joined_at.map(|joined_at| chrono::Utc::now() - *joined_at);
+    let description = render_goodbye(
+        &config.template,
+        &GoodbyeContext {
+            user_name: user.name.clone(),
+            member_count,
+            guild_name,
+            time_in_server,
+        },
+    );
+
+    let embed = serenity::CreateEmbed::new()
+        .description(description)
+        .thumbnail(user.face())
+        .color(0x99AAB5)
+        .timestamp(serenity::Timestamp::now());
+
+    serenity::ChannelId::new(config.channel_id)
+        .send_message(
+            ctx,
+            serenity::CreateMessage::new()
+                .embed(embed)
+                .allowed_mentions(serenity::CreateAllowedMentions::new()),
+        )
+        .await
+}
+
+/// The member count (counted the same way as the server stats channels,
+/// corrected for `event`) and the server name. Prefers the cache and falls
+/// back to HTTP.
+async fn guild_facts(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    event: StatsUpdateEvent<'_>,
+) -> (Option<u64>, String) {
+    let cached = guild_id.to_guild_cached(&ctx.cache).map(|guild| {
+        let counts = guild_counts(&guild, &event);
+        (counts.total_members, guild.name.clone())
+    });
+    if let Some((total_members, name)) = cached {
+        return (Some(total_members), name);
+    }
+
+    match guild_id.to_partial_guild_with_counts(&ctx.http).await {
+        Ok(guild) => (guild.approximate_member_count, guild.name),
+        Err(e) => {
+            tracing::warn!(guild_id = guild_id.get(), error = %e, "Failed to fetch guild for member message");
+            (None, "this server".to_string())
+        }
+    }
+}
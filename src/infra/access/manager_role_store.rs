@@ -0,0 +1,151 @@
+// The roles allowed to run admin commands in each guild (`/config managers`),
+// one `manager_roles` row per (guild, role) pair. Adding a role twice is a
+// no-op and removing one that isn't listed does nothing, so the commands never
+// have to check first.
+
+use crate::core::access::ManagerRoleStore;
+use async_trait::async_trait;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use tokio::sync::RwLock;
+
+pub struct SqliteManagerRoleStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteManagerRoleStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS manager_roles (
+                guild_id INTEGER NOT NULL,
+                role_id INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, role_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ManagerRoleStore for SqliteManagerRoleStore {
+    async fn get_manager_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query("SELECT role_id FROM manager_roles WHERE guild_id = ?")
+            .bind(guild_id as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<i64, _>("role_id") as u64)
+            .collect())
+    }
+
+    async fn set_manager_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+        is_manager: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let query = if is_manager {
+            "INSERT INTO manager_roles (guild_id, role_id) VALUES (?, ?) ON CONFLICT DO NOTHING"
+        } else {
+            "DELETE FROM manager_roles WHERE guild_id = ? AND role_id = ?"
+        };
+
+        sqlx::query(query)
+            .bind(guild_id as i64)
+            .bind(role_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryManagerRoleStore {
+    roles: RwLock<HashMap<u64, HashSet<u64>>>,
+}
+
+impl InMemoryManagerRoleStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ManagerRoleStore for InMemoryManagerRoleStore {
+    async fn get_manager_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .roles
+            .read()
+            .await
+            .get(&guild_id)
+            .map(|roles| roles.iter().copied().collect())
+            .unwrap_or_default())
+    }
+
+    async fn set_manager_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+        is_manager: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut roles = self.roles.write().await;
+        let guild_roles = roles.entry(guild_id).or_default();
+        if is_manager {
+            guild_roles.insert(role_id);
+        } else {
+            guild_roles.remove(&role_id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_manager_roles_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = SqliteManagerRoleStore::new(pool);
+        store.migrate().await.unwrap();
+        assert!(store.get_manager_roles(1).await.unwrap().is_empty());
+
+        store.set_manager_role(1, 10, true).await.unwrap();
+        store.set_manager_role(1, 20, true).await.unwrap();
+        store.set_manager_role(1, 10, true).await.unwrap();
+        store.set_manager_role(2, 30, true).await.unwrap();
+        let mut roles = store.get_manager_roles(1).await.unwrap();
+        roles.sort_unstable();
+        assert_eq!(roles, vec![10, 20]);
+
+        store.set_manager_role(1, 10, false).await.unwrap();
+        // Removing a role that isn't listed is fine
+        store.set_manager_role(1, 99, false).await.unwrap();
+        assert_eq!(store.get_manager_roles(1).await.unwrap(), vec![20]);
+        assert_eq!(store.get_manager_roles(2).await.unwrap(), vec![30]);
+    }
+}
@@ -0,0 +1,55 @@
+// Reads the reloadable AI settings (`core::ai::AiSettings`) for `/reload` by
+// loading the configuration again.
+//
+// At startup `load_config` uses the process environment, with `.env` filling in
+// what it doesn't set. `/reload` re-reads `.env` so edits to it take effect,
+// but variables set in the environment the bot was started with still win,
+// the same as at startup.
+
+use crate::core::ai::AiSettings;
+use crate::infra::config::{load_config_with, set_outside_dotenv};
+use std::collections::HashMap;
+
+/// Where setting values are looked up: the process environment, optionally
+/// with a fresh read of `.env` for what the environment didn't set itself.
+#[derive(Debug, Default)]
+pub struct SettingsSource {
+    overrides: HashMap<String, String>,
+}
+
+impl SettingsSource {
+    /// The process environment with `.env` (if there is one) re-read. Values
+    /// loaded from `.env` at startup are replaced; the rest of the environment
+    /// wins over the file.
+    pub fn with_dotenv() -> Self {
+        // `dotenv::dotenv()` never overwrites variables that are already set,
+        // including the ones it set itself at startup, so read the pairs
+        // ourselves instead.
+        #[allow(deprecated)]
+        let overrides = match dotenv::dotenv_iter() {
+            Ok(iter) => iter
+                .filter_map(Result::ok)
+                .filter(|(name, _)| !set_outside_dotenv(name))
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+        Self { overrides }
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        self.overrides
+            .get(name)
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+    }
+}
+
+/// Load the system prompt and reasoning options from a fresh `Config` read
+/// through `source`.
+///
+/// Fails if that configuration is invalid, e.g. `AI_SYSTEM_PROMPT_FILE` is
+/// set but can't be read.
+pub fn load_ai_settings(source: &SettingsSource) -> Result<AiSettings, String> {
+    let config = load_config_with(|name| source.get(name)).map_err(|e| e.problems.join("; "))?;
+    config.ai_settings()
+}
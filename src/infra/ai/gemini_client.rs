@@ -27,15 +27,18 @@
 
 use crate::core::ai::{
     models::{
-        AiConfig, AiMessage, AiProviderResponse, AiTool, FunctionCall, GroundingMetadata,
-        ToolConfig, ToolMode, WebSource,
+        AiConfig, AiImage, AiMessage, AiProviderResponse, AiTool, EmptyResponse, FunctionCall,
+        GroundingChunk, GroundingMetadata, GroundingSupport, ImageSource, Role, ToolConfig,
+        ToolMode, UrlContextMetadata, UrlRetrieval, UrlRetrievalStatus, WebSource,
     },
     AiProvider,
 };
+use crate::infra::http;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::time::Duration;
 
 // =============================================================================
 // GEMINI API DATA STRUCTURES
@@ -63,6 +66,36 @@ struct Part {
     /// Function response (when sending function results back).
     #[serde(skip_serializing_if = "Option::is_none")]
     function_response: Option<GeminiFunctionResponse>,
+
+    /// Base64 image data sent with the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inline_data: Option<InlineData>,
+
+    /// Image referenced by URI (Files API / GCS).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_data: Option<FileData>,
+
+    /// Set by Gemini on parts that are the model's reasoning rather than the
+    /// answer (only when thoughts are requested via `includeThoughts`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thought: Option<bool>,
+}
+
+/// Inline media for multimodal requests.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct InlineData {
+    mime_type: String,
+    /// Base64-encoded bytes.
+    data: String,
+}
+
+/// Media referenced by URI for multimodal requests.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FileData {
+    mime_type: String,
+    file_uri: String,
 }
 
 /// Function call requested by the model.
@@ -353,6 +386,28 @@ struct Candidate {
 
     /// Grounding metadata when Google Search was used.
     grounding_metadata: Option<GeminiGroundingMetadata>,
+
+    /// Which URLs were fetched when the URL Context tool was used.
+    url_context_metadata: Option<GeminiUrlContextMetadata>,
+}
+
+/// URL Context results: one entry per URL the model tried to read.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUrlContextMetadata {
+    #[serde(default)]
+    url_metadata: Vec<GeminiUrlMetadata>,
+}
+
+/// The retrieval result for a single URL.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUrlMetadata {
+    /// The URL as the model fetched it.
+    retrieved_url: String,
+
+    /// e.g. "URL_RETRIEVAL_STATUS_SUCCESS" or "URL_RETRIEVAL_STATUS_ERROR".
+    url_retrieval_status: Option<String>,
 }
 
 /// Grounding metadata returned when Google Search tool is used.
@@ -373,7 +428,7 @@ struct GeminiGroundingMetadata {
 
     /// Support for specific parts of the response.
     #[serde(default)]
-    grounding_supports: Vec<GroundingSupport>,
+    grounding_supports: Vec<GeminiGroundingSupport>,
 
     /// Retrieval metadata (for URL context).
     #[serde(default)]
@@ -394,6 +449,9 @@ struct SearchEntryPoint {
 struct GeminiGroundingChunk {
     /// Web source information.
     web: Option<WebChunk>,
+
+    /// A retrieved document, which comes with its text.
+    retrieved_context: Option<RetrievedContextChunk>,
 }
 
 /// Web source information in a grounding chunk.
@@ -407,11 +465,23 @@ struct WebChunk {
     title: Option<String>,
 }
 
+/// Retrieved document information in a grounding chunk.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RetrievedContextChunk {
+    uri: Option<String>,
+    title: Option<String>,
+    /// The retrieved text.
+    text: Option<String>,
+}
+
 /// Support information linking response parts to sources.
-#[allow(dead_code)]
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-struct GroundingSupport {
+struct GeminiGroundingSupport {
+    /// The part of the response this support is for.
+    segment: Option<Segment>,
+
     /// Indices into grounding_chunks that support this text.
     #[serde(default)]
     grounding_chunk_indices: Vec<usize>,
@@ -421,6 +491,18 @@ struct GroundingSupport {
     confidence_scores: Vec<f64>,
 }
 
+/// A span of the response text. Indices are byte offsets; Gemini leaves out
+/// `startIndex` when it is 0.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Segment {
+    #[serde(default)]
+    start_index: usize,
+    #[serde(default)]
+    end_index: usize,
+    text: Option<String>,
+}
+
 /// Metadata about URL retrieval.
 #[allow(dead_code)]
 #[derive(Debug, Deserialize, Clone)]
@@ -481,11 +563,12 @@ struct GeminiErrorResponse {
 ///
 /// # Example
 /// ```ignore
-/// let client = GeminiClient::new("your-api-key".to_string());
-/// let messages = vec![AiMessage {
-///     role: "user".to_string(),
-///     content: "Hello!".to_string(),
-/// }];
+/// let client = GeminiClient::new(
+///     http::shared_client()?,
+///     "your-api-key".to_string(),
+///     Duration::from_secs(60),
+/// );
+/// let messages = vec![AiMessage::user("Hello!")];
 /// let config = AiConfig {
 ///     model: "gemini-2.5-flash".to_string(),
 ///     temperature: 0.7,
@@ -499,31 +582,108 @@ pub struct GeminiClient {
 
     /// API key for authentication.
     api_key: String,
+
+    /// How long a request may take before it fails with `RequestTimeout`.
+    timeout: Duration,
+
+    /// Send built-in tools alongside function declarations.
+    allow_mixed_tools: bool,
 }
 
 impl GeminiClient {
     /// Creates a new Gemini client with the given API key.
     ///
     /// # Arguments
+    /// * `client` - The bot's shared HTTP client (`http::shared_client`)
     /// * `api_key` - Your Gemini API key from https://aistudio.google.com/apikey
+    /// * `timeout` - How long a request may take before it fails
     ///
     /// # Example
     /// ```ignore
-    /// let client = GeminiClient::new(std::env::var("GEMINI_API_KEY")?);
+    /// let client = GeminiClient::new(
+    ///     http::shared_client()?,
+    ///     std::env::var("GEMINI_API_KEY")?,
+    ///     Duration::from_secs(60),
+    /// );
     /// ```
-    pub fn new(api_key: String) -> Self {
+    pub fn new(client: Client, api_key: String, timeout: Duration) -> Self {
         Self {
-            client: Client::new(),
+            client,
             api_key,
+            timeout,
+            allow_mixed_tools: false,
         }
     }
 
+    /// Keep built-in tools (google_search/url_context) in requests that also
+    /// declare functions (`GEMINI_ALLOW_MIXED_TOOLS_AND_FUNCTIONS`). Off by
+    /// default because Gemini may reject such requests.
+    pub fn with_mixed_tools(mut self, allow: bool) -> Self {
+        self.allow_mixed_tools = allow;
+        self
+    }
+
+    fn request_error(&self, error: reqwest::Error) -> Box<dyn Error + Send + Sync> {
+        http::request_error("Gemini", self.timeout, error)
+    }
+
     /// Creates a Part with just text content.
     fn text_part(text: String) -> Part {
         Part {
             text: Some(text),
-            function_call: None,
-            function_response: None,
+            ..Default::default()
+        }
+    }
+
+    /// Builds an `inlineData` or `fileData` part for an attached image.
+    fn image_part(image: &AiImage) -> Part {
+        match &image.source {
+            ImageSource::Inline(data) => Part {
+                inline_data: Some(InlineData {
+                    mime_type: image.mime_type.clone(),
+                    data: data.clone(),
+                }),
+                ..Default::default()
+            },
+            ImageSource::Url(uri) => Part {
+                file_data: Some(FileData {
+                    mime_type: image.mime_type.clone(),
+                    file_uri: uri.clone(),
+                }),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Splits the text parts of a response into `(content, thinking)`.
+    ///
+    /// Gemini marks reasoning parts with `thought: true`. When any part carries
+    /// the flag, it alone decides: the remaining parts are all answer text (a
+    /// long answer can arrive in several parts) and are joined back together.
+    ///
+    /// Without the flag we fall back to the positional guess: with 2+ text
+    /// parts, all but the last are thinking and the last is the response.
+    fn split_thinking(parts: &[Part]) -> (String, Option<String>) {
+        let text_parts: Vec<&Part> = parts.iter().filter(|p| p.text.is_some()).collect();
+        let join = |parts: &[&Part], separator: &str| {
+            let texts: Vec<&str> = parts.iter().filter_map(|p| p.text.as_deref()).collect();
+            texts.join(separator)
+        };
+
+        if text_parts.iter().any(|p| p.thought.is_some()) {
+            let (thoughts, answer): (Vec<&Part>, Vec<&Part>) = text_parts
+                .into_iter()
+                .partition(|p| p.thought == Some(true));
+            let thinking = Some(join(&thoughts, "\n\n")).filter(|t| !t.is_empty());
+            return (join(&answer, ""), thinking);
+        }
+
+        match text_parts.split_last() {
+            Some((last, thoughts)) => {
+                let thinking = Some(join(thoughts, "\n\n")).filter(|t| !t.is_empty());
+                (last.text.clone().unwrap_or_default(), thinking)
+            }
+            None => (String::new(), None),
         }
     }
 
@@ -533,23 +693,30 @@ impl GeminiClient {
     /// - "assistant" role → "model" (Gemini's terminology)
     /// - "system" messages are filtered out (handled separately)
     fn convert_message(msg: &AiMessage) -> Content {
-        // Gemini uses "model" instead of "assistant"
-        let role = match msg.role.as_str() {
-            "assistant" => "model".to_string(),
-            other => other.to_string(),
-        };
+        let mut parts = vec![Self::text_part(msg.content.clone())];
+        parts.extend(msg.images.iter().map(Self::image_part));
 
         Content {
-            role,
-            parts: vec![Self::text_part(msg.content.clone())],
+            role: Self::gemini_role(msg.role).to_string(),
+            parts,
+        }
+    }
+
+    /// Gemini's name for a role. Gemini only knows "user" and "model"; system
+    /// prompts go in `systemInstruction`, which also uses "user".
+    fn gemini_role(role: Role) -> &'static str {
+        match role {
+            Role::Assistant => "model",
+            Role::User | Role::System => "user",
         }
     }
 
     /// Converts our core `AiTool` types to Gemini's tool format.
     ///
     /// This handles the translation between our platform-agnostic tool
-    /// representation and Gemini's specific API format.
-    fn convert_tools(tools: &[AiTool]) -> Vec<GeminiTool> {
+    /// representation and Gemini's specific API format. Built-in tools are
+    /// dropped when functions are declared, unless `allow_mixed_tools`.
+    fn convert_tools(tools: &[AiTool], allow_mixed_tools: bool) -> Vec<GeminiTool> {
         let mut gemini_tools = Vec::new();
 
         // Group tools by type - Gemini expects each tool type as a separate object
@@ -601,15 +768,8 @@ impl GeminiClient {
         // (google_search/url_context) with function calling (function_declarations).
         // To avoid the 400 Bad Request error "Tool use with function calling is
         // unsupported", prefer custom function_declarations over built-in tools.
-        // If function_declarations are present, we omit google_search/url_context.
-        // The default behavior is to avoid mixing built-in tools and custom
-        // function declarations, because Gemini may return 400 when mixing
-        // them. You can override this behavior by setting
-        // GEMINI_ALLOW_MIXED_TOOLS_AND_FUNCTIONS=true in the environment.
-        let allow_mixed_tools = std::env::var("GEMINI_ALLOW_MIXED_TOOLS_AND_FUNCTIONS")
-            .map(|v| v.to_lowercase() == "true")
-            .unwrap_or(false);
-
+        // If function_declarations are present, we omit google_search/url_context
+        // unless mixing was allowed (see `with_mixed_tools`).
         if !function_declarations.is_empty() && !allow_mixed_tools {
             tracing::warn!("Gemini request: function_declarations present - omitting built-in tools (google_search/url_context) to avoid API error");
             gemini_tools.push(GeminiTool {
@@ -686,12 +846,75 @@ impl GeminiClient {
             })
             .collect();
 
+        // Every chunk is kept, even without a link, so support indices still
+        // point at the right one
+        let grounding_chunks = metadata
+            .grounding_chunks
+            .iter()
+            .map(|chunk| {
+                if let Some(context) = &chunk.retrieved_context {
+                    return GroundingChunk {
+                        content: context.text.clone(),
+                        source: context.uri.as_ref().map(|uri| WebSource {
+                            uri: uri.clone(),
+                            title: context.title.clone(),
+                        }),
+                    };
+                }
+                GroundingChunk {
+                    content: None,
+                    source: chunk.web.as_ref().and_then(|web| {
+                        web.uri.as_ref().map(|uri| WebSource {
+                            uri: uri.clone(),
+                            title: web.title.clone(),
+                        })
+                    }),
+                }
+            })
+            .collect();
+
+        let supports = metadata
+            .grounding_supports
+            .iter()
+            .filter_map(|support| {
+                let segment = support.segment.as_ref()?;
+                Some(GroundingSupport {
+                    start_index: segment.start_index,
+                    end_index: segment.end_index,
+                    text: segment.text.clone(),
+                    chunk_indices: support.grounding_chunk_indices.clone(),
+                    confidence_scores: support.confidence_scores.clone(),
+                })
+            })
+            .collect();
+
         GroundingMetadata {
             search_queries,
             web_sources,
-            grounding_chunks: Vec::new(), // TODO: Parse grounding chunks with content
+            grounding_chunks,
+            supports,
         }
     }
+
+    /// Converts Gemini's URL Context metadata to our core format.
+    fn convert_url_context_metadata(metadata: &GeminiUrlContextMetadata) -> UrlContextMetadata {
+        let urls = metadata
+            .url_metadata
+            .iter()
+            .map(|entry| UrlRetrieval {
+                url: entry.retrieved_url.clone(),
+                status: match entry.url_retrieval_status.as_deref() {
+                    Some("URL_RETRIEVAL_STATUS_SUCCESS") => UrlRetrievalStatus::Success,
+                    Some("URL_RETRIEVAL_STATUS_ERROR") => UrlRetrievalStatus::Error,
+                    Some("URL_RETRIEVAL_STATUS_PAYWALL") => UrlRetrievalStatus::Paywall,
+                    Some("URL_RETRIEVAL_STATUS_UNSAFE") => UrlRetrievalStatus::Unsafe,
+                    _ => UrlRetrievalStatus::Unknown,
+                },
+            })
+            .collect();
+
+        UrlContextMetadata { urls }
+    }
 }
 
 #[async_trait]
@@ -725,16 +948,16 @@ impl AiProvider for GeminiClient {
             // Gemini handles system instructions differently - they're a separate field
             let system_instruction: Option<Content> = messages
                 .iter()
-                .find(|m| m.role == "system")
+                .find(|m| m.role == Role::System)
                 .map(|m| Content {
-                    role: "user".to_string(), // System instruction uses "user" role internally
+                    role: Self::gemini_role(Role::System).to_string(),
                     parts: vec![Self::text_part(m.content.clone())],
                 });
 
             // Convert non-system messages to Gemini format
             let contents: Vec<Content> = messages
                 .iter()
-                .filter(|m| m.role != "system")
+                .filter(|m| m.role != Role::System)
                 .map(Self::convert_message)
                 .collect();
 
@@ -790,7 +1013,7 @@ impl AiProvider for GeminiClient {
             let tools = current_config
                 .tools
                 .as_ref()
-                .map(|t| Self::convert_tools(t))
+                .map(|t| Self::convert_tools(t, self.allow_mixed_tools))
                 .filter(|t| !t.is_empty());
 
             // Convert tool config if provided
@@ -822,8 +1045,10 @@ impl AiProvider for GeminiClient {
                 .post(&url)
                 .header("Content-Type", "application/json")
                 .json(&request)
+                .timeout(self.timeout)
                 .send()
-                .await?;
+                .await
+                .map_err(|e| self.request_error(e))?;
 
             // Handle rate limits (429) or missing models (404/400) with fallback
             let status = response.status();
@@ -861,32 +1086,34 @@ impl AiProvider for GeminiClient {
             // Check for HTTP errors
             if !response.status().is_success() {
                 let status = response.status();
-                let error_text = response.text().await?;
+                let error_text = response.text().await.map_err(|e| self.request_error(e))?;
 
                 // Try to parse as Gemini error response for better error messages
-                if let Ok(error_response) = serde_json::from_str::<GeminiErrorResponse>(&error_text)
-                {
-                    return Err(format!(
-                        "Gemini API error ({}): {}",
-                        status, error_response.error.message
-                    )
-                    .into());
+                let message = match serde_json::from_str::<GeminiErrorResponse>(&error_text) {
+                    Ok(error_response) => error_response.error.message,
+                    Err(_) => error_text,
+                };
+                return Err(http::ApiError {
+                    service: "Gemini",
+                    status: status.as_u16(),
+                    message,
                 }
-
-                return Err(format!("Gemini API error: {} - {}", status, error_text).into());
+                .into());
             }
 
             // Parse the response
-            let response_json: GenerateContentResponse = response.json().await?;
+            let response_json: GenerateContentResponse =
+                response.json().await.map_err(|e| self.request_error(e))?;
 
             // Get the first candidate (usually the only one)
             let candidate = response_json
                 .candidates
                 .as_ref()
                 .and_then(|c| c.first())
-                .ok_or(
-                "No content in Gemini response - the model may have been blocked by safety filters",
-            )?;
+                .ok_or_else(|| EmptyResponse {
+                    provider: "Gemini",
+                    reason: Some("the model may have been blocked by safety filters".to_string()),
+                })?;
 
             let parts = &candidate.content.parts;
 
@@ -915,35 +1142,15 @@ impl AiProvider for GeminiClient {
                 .as_ref()
                 .map(Self::convert_grounding_metadata);
 
-            // Extract text parts only (filter out function calls)
-            let text_parts: Vec<&Part> = parts.iter().filter(|p| p.text.is_some()).collect();
-
-            // Extract thinking and content from the response parts.
-            //
-            // When thinking/reasoning is enabled, Gemini returns multiple parts:
-            // - If there are 2+ parts: parts[0..n-1] are thinking, parts[n-1] is the response
-            // - If there's 1 part: it's just the response (no thinking)
-            //
-            // We extract thinking from all but the last part, and content from the last part.
-            let thinking = if text_parts.len() > 1 {
-                let thinking_parts: Vec<&str> = text_parts[..text_parts.len() - 1]
-                    .iter()
-                    .filter_map(|p| p.text.as_deref())
-                    .collect();
-                if thinking_parts.is_empty() {
-                    None
-                } else {
-                    Some(thinking_parts.join("\n\n"))
-                }
-            } else {
-                None
-            };
+            // Extract which URLs were read if URL Context was used
+            let url_context_metadata = candidate
+                .url_context_metadata
+                .as_ref()
+                .map(Self::convert_url_context_metadata);
 
-            // Extract content: the last text part is always the actual response
-            let content = text_parts
-                .last()
-                .and_then(|p| p.text.clone())
-                .unwrap_or_default();
+            // Separate the model's reasoning from the answer (function call
+            // parts carry no text and are skipped)
+            let (content, thinking) = Self::split_thinking(parts);
 
             tracing::debug!(
                 "Gemini response received: {} chars content, {} chars thinking, {} function calls",
@@ -956,11 +1163,20 @@ impl AiProvider for GeminiClient {
                 content,
                 thinking,
                 grounding_metadata,
-                url_context_metadata: None, // TODO: Parse URL context metadata when available
+                url_context_metadata,
                 function_calls,
             });
         }
     }
+
+    /// Every Gemini model we use (1.5 and later) accepts images.
+    fn supports_images(&self, model: &str) -> bool {
+        model.starts_with("gemini-")
+    }
+
+    fn is_outage(&self, error: &(dyn Error + Send + Sync + 'static)) -> bool {
+        http::is_outage(error)
+    }
 }
 
 // =============================================================================
@@ -973,10 +1189,7 @@ mod tests {
 
     #[test]
     fn test_convert_message_user() {
-        let msg = AiMessage {
-            role: "user".to_string(),
-            content: "Hello!".to_string(),
-        };
+        let msg = AiMessage::user("Hello!");
 
         let content = GeminiClient::convert_message(&msg);
 
@@ -985,12 +1198,180 @@ mod tests {
         assert_eq!(content.parts[0].text, Some("Hello!".to_string()));
     }
 
+    fn response_parts(json: serde_json::Value) -> Vec<Part> {
+        serde_json::from_value(json).expect("parts deserialize")
+    }
+
+    #[test]
+    fn test_thought_flag_decides_what_is_thinking() {
+        // A plain answer split over several parts is all content
+        let parts = response_parts(serde_json::json!([
+            { "text": "The answer is " },
+            { "text": "42." },
+            { "functionCall": { "name": "lookup", "args": {} } },
+            { "text": "Plan: add the numbers.", "thought": true },
+        ]));
+        let (content, thinking) = GeminiClient::split_thinking(&parts);
+        assert_eq!(content, "The answer is 42.");
+        assert_eq!(thinking.as_deref(), Some("Plan: add the numbers."));
+
+        let parts = response_parts(serde_json::json!([
+            { "text": "Hello " },
+            { "text": "there!", "thought": false },
+        ]));
+        let (content, thinking) = GeminiClient::split_thinking(&parts);
+        assert_eq!(content, "Hello there!");
+        assert_eq!(thinking, None);
+    }
+
+    #[test]
+    fn test_unflagged_parts_fall_back_to_last_part_as_answer() {
+        let parts = response_parts(serde_json::json!([
+            { "text": "First I think." },
+            { "text": "Then I think more." },
+            { "text": "Answer." },
+        ]));
+        let (content, thinking) = GeminiClient::split_thinking(&parts);
+        assert_eq!(content, "Answer.");
+        assert_eq!(
+            thinking.as_deref(),
+            Some("First I think.\n\nThen I think more.")
+        );
+
+        let parts = response_parts(serde_json::json!([{ "text": "Just this." }]));
+        assert_eq!(
+            GeminiClient::split_thinking(&parts),
+            ("Just this.".to_string(), None)
+        );
+        assert_eq!(GeminiClient::split_thinking(&[]), (String::new(), None));
+    }
+
+    #[test]
+    fn test_grounding_supports_point_at_chunks() {
+        let metadata: GeminiGroundingMetadata = serde_json::from_value(serde_json::json!({
+            "webSearchQueries": ["rust 2024 edition release"],
+            "groundingChunks": [
+                { "web": { "uri": "https://blog.rust-lang.org/2025/02/20", "title": "rust-lang.org" } },
+                { "retrievedContext": { "title": "notes.md", "text": "Edition 2024 notes" } },
+                { "web": { "uri": "https://doc.rust-lang.org/edition-guide", "title": "Edition Guide" } }
+            ],
+            "groundingSupports": [
+                {
+                    "segment": { "endIndex": 38, "text": "Rust 2024 shipped with Rust 1.85." },
+                    "groundingChunkIndices": [0],
+                    "confidenceScores": [0.97]
+                },
+                {
+                    "segment": { "startIndex": 39, "endIndex": 80, "text": "It changes how RPIT captures." },
+                    "groundingChunkIndices": [2, 0],
+                    "confidenceScores": [0.88, 0.61]
+                },
+                { "groundingChunkIndices": [1] }
+            ]
+        }))
+        .expect("metadata deserializes");
+
+        let grounding = GeminiClient::convert_grounding_metadata(&metadata);
+
+        // Only linked web chunks are sources, but all chunks keep their index
+        assert_eq!(grounding.web_sources.len(), 2);
+        assert_eq!(grounding.grounding_chunks.len(), 3);
+        assert_eq!(
+            grounding.grounding_chunks[1],
+            GroundingChunk {
+                content: Some("Edition 2024 notes".to_string()),
+                source: None,
+            }
+        );
+        assert_eq!(
+            grounding.grounding_chunks[2].source.as_ref().unwrap().uri,
+            "https://doc.rust-lang.org/edition-guide"
+        );
+
+        // A support without a segment can't be placed and is dropped
+        assert_eq!(
+            grounding.supports,
+            vec![
+                GroundingSupport {
+                    start_index: 0,
+                    end_index: 38,
+                    text: Some("Rust 2024 shipped with Rust 1.85.".to_string()),
+                    chunk_indices: vec![0],
+                    confidence_scores: vec![0.97],
+                },
+                GroundingSupport {
+                    start_index: 39,
+                    end_index: 80,
+                    text: Some("It changes how RPIT captures.".to_string()),
+                    chunk_indices: vec![2, 0],
+                    confidence_scores: vec![0.88, 0.61],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_url_context_metadata_from_response() {
+        let response: GenerateContentResponse = serde_json::from_value(serde_json::json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{ "text": "The post is about Rust." }]
+                },
+                "finishReason": "STOP",
+                "urlContextMetadata": {
+                    "urlMetadata": [
+                        {
+                            "retrievedUrl": "https://example.com/post",
+                            "urlRetrievalStatus": "URL_RETRIEVAL_STATUS_SUCCESS"
+                        },
+                        {
+                            "retrievedUrl": "https://news.example.org/story",
+                            "urlRetrievalStatus": "URL_RETRIEVAL_STATUS_PAYWALL"
+                        },
+                        {
+                            "retrievedUrl": "https://example.net/missing",
+                            "urlRetrievalStatus": "URL_RETRIEVAL_STATUS_ERROR"
+                        },
+                        { "retrievedUrl": "https://example.net/odd" }
+                    ]
+                }
+            }],
+            "usageMetadata": { "totalTokenCount": 120 }
+        }))
+        .expect("response deserializes");
+
+        let candidate = &response.candidates.unwrap()[0];
+        let metadata = GeminiClient::convert_url_context_metadata(
+            candidate.url_context_metadata.as_ref().unwrap(),
+        );
+
+        let statuses: Vec<(&str, UrlRetrievalStatus)> = metadata
+            .urls
+            .iter()
+            .map(|entry| (entry.url.as_str(), entry.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("https://example.com/post", UrlRetrievalStatus::Success),
+                (
+                    "https://news.example.org/story",
+                    UrlRetrievalStatus::Paywall
+                ),
+                ("https://example.net/missing", UrlRetrievalStatus::Error),
+                ("https://example.net/odd", UrlRetrievalStatus::Unknown),
+            ]
+        );
+        assert_eq!(
+            metadata.urls_read().collect::<Vec<_>>(),
+            vec!["https://example.com/post"]
+        );
+    }
+
     #[test]
     fn test_convert_message_assistant_to_model() {
-        let msg = AiMessage {
-            role: "assistant".to_string(),
-            content: "Hi there!".to_string(),
-        };
+        let msg = AiMessage::assistant("Hi there!");
 
         let content = GeminiClient::convert_message(&msg);
 
@@ -999,6 +1380,32 @@ mod tests {
         assert_eq!(content.parts[0].text, Some("Hi there!".to_string()));
     }
 
+    #[test]
+    fn test_convert_message_with_inline_image() {
+        let mut msg = AiMessage::user("What's in this picture?");
+        msg.images.push(AiImage {
+            mime_type: "image/png".to_string(),
+            source: ImageSource::Inline("aGVsbG8=".to_string()),
+        });
+
+        let content = GeminiClient::convert_message(&msg);
+        assert_eq!(content.parts.len(), 2);
+
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(json["parts"][0]["text"], "What's in this picture?");
+        assert_eq!(json["parts"][1]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(json["parts"][1]["inlineData"]["data"], "aGVsbG8=");
+        assert!(json["parts"][1].get("text").is_none());
+    }
+
+    #[test]
+    fn test_gemini_roles_never_send_system() {
+        // Gemini rejects anything but "user"/"model" inside `contents`
+        assert_eq!(GeminiClient::gemini_role(Role::User), "user");
+        assert_eq!(GeminiClient::gemini_role(Role::Assistant), "model");
+        assert_eq!(GeminiClient::gemini_role(Role::System), "user");
+    }
+
     #[test]
     fn test_generation_config_serialization() {
         let config = GenerationConfig {
@@ -1024,7 +1431,7 @@ mod tests {
     #[test]
     fn test_google_search_tool_conversion() {
         let tools = vec![AiTool::GoogleSearch];
-        let gemini_tools = GeminiClient::convert_tools(&tools);
+        let gemini_tools = GeminiClient::convert_tools(&tools, false);
 
         assert_eq!(gemini_tools.len(), 1);
         assert!(gemini_tools[0].google_search.is_some());
@@ -1037,7 +1444,7 @@ mod tests {
         let tools = vec![AiTool::UrlContext {
             urls: vec!["https://example.com".to_string()],
         }];
-        let gemini_tools = GeminiClient::convert_tools(&tools);
+        let gemini_tools = GeminiClient::convert_tools(&tools, false);
 
         assert_eq!(gemini_tools.len(), 1);
         assert!(gemini_tools[0].google_search.is_none());
@@ -1058,7 +1465,7 @@ mod tests {
                 urls: vec!["https://example.com".to_string()],
             },
         ];
-        let gemini_tools = GeminiClient::convert_tools(&tools);
+        let gemini_tools = GeminiClient::convert_tools(&tools, false);
 
         // Each tool type gets its own entry
         assert_eq!(gemini_tools.len(), 2);
@@ -1083,7 +1490,7 @@ mod tests {
             parameters,
         };
         let tools = vec![AiTool::GoogleSearch, AiTool::FunctionDeclaration(func_def)];
-        let gemini_tools = GeminiClient::convert_tools(&tools);
+        let gemini_tools = GeminiClient::convert_tools(&tools, false);
 
         assert_eq!(gemini_tools.len(), 1);
         assert!(gemini_tools[0].function_declarations.is_some());
@@ -1092,12 +1499,9 @@ mod tests {
     }
 
     #[test]
-    fn test_allow_mixing_tools_and_functions_via_env() {
-        // If env var GEMINI_ALLOW_MIXED_TOOLS_AND_FUNCTIONS=true, allow mixing
-        // built-in tools and function_declarations. This is opt-in and not done
-        // by default because Gemini may reject mixed requests.
-        use std::env;
-        env::set_var("GEMINI_ALLOW_MIXED_TOOLS_AND_FUNCTIONS", "true");
+    fn test_allow_mixing_tools_and_functions() {
+        // Mixing built-in tools and function_declarations is opt-in and not
+        // done by default because Gemini may reject mixed requests.
         use crate::core::ai::models::{FunctionDef, FunctionParameters};
         let parameters = FunctionParameters {
             param_type: "object".to_string(),
@@ -1110,7 +1514,7 @@ mod tests {
             parameters,
         };
         let tools = vec![AiTool::GoogleSearch, AiTool::FunctionDeclaration(func_def)];
-        let gemini_tools = GeminiClient::convert_tools(&tools);
+        let gemini_tools = GeminiClient::convert_tools(&tools, true);
 
         // Both tools should be present when mixing is allowed
         assert_eq!(gemini_tools.len(), 2);
@@ -1118,8 +1522,5 @@ mod tests {
         assert!(gemini_tools
             .iter()
             .any(|t| t.function_declarations.is_some()));
-
-        // Clean up env var
-        env::remove_var("GEMINI_ALLOW_MIXED_TOOLS_AND_FUNCTIONS");
     }
 }
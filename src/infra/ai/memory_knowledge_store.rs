@@ -0,0 +1,78 @@
+use crate::core::ai::knowledge::{KnowledgeChunk, KnowledgeStore};
+use async_trait::async_trait;
+use std::error::Error;
+use tokio::sync::RwLock;
+
+/// In-memory knowledge store, used when `STORAGE_BACKEND=memory`.
+#[allow(dead_code)]
+#[derive(Default)]
+pub struct InMemoryKnowledgeStore {
+    chunks: RwLock<Vec<KnowledgeChunk>>,
+}
+
+impl InMemoryKnowledgeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl KnowledgeStore for InMemoryKnowledgeStore {
+    async fn add_chunk(
+        &self,
+        category: &str,
+        content: &str,
+        keywords: &[&str],
+    ) -> Result<i64, Box<dyn Error + Send + Sync>> {
+        let mut chunks = self.chunks.write().await;
+        let id = chunks.iter().map(|c| c.id).max().unwrap_or(0) + 1;
+        chunks.push(KnowledgeChunk {
+            id,
+            category: category.to_string(),
+            content: content.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            created_at: chrono::Utc::now().timestamp(),
+        });
+        Ok(id)
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<KnowledgeChunk>, Box<dyn Error + Send + Sync>> {
+        // Case-insensitive substring match, like SQLite's LIKE '%query%'
+        let query = query.to_lowercase();
+        let chunks = self.chunks.read().await;
+        Ok(chunks
+            .iter()
+            .rev()
+            .filter(|c| {
+                c.content.to_lowercase().contains(&query)
+                    || c.keywords.iter().any(|k| k.to_lowercase().contains(&query))
+            })
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_by_category(
+        &self,
+        category: &str,
+        limit: usize,
+    ) -> Result<Vec<KnowledgeChunk>, Box<dyn Error + Send + Sync>> {
+        let chunks = self.chunks.read().await;
+        Ok(chunks
+            .iter()
+            .rev()
+            .filter(|c| c.category == category)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_chunk(&self, id: i64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.chunks.write().await.retain(|c| c.id != id);
+        Ok(())
+    }
+}
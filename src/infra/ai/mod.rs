@@ -1,8 +1,16 @@
+pub mod ai_settings_loader;
 pub mod gemini_client;
 pub mod knowledge_store;
+pub mod memory_knowledge_store;
 pub mod openrouter_client;
+pub mod opt_out_store;
+pub mod response_limits_store;
 
+pub use ai_settings_loader::{load_ai_settings, SettingsSource};
 pub use gemini_client::GeminiClient;
 #[allow(unused_imports)]
 pub use knowledge_store::SqliteKnowledgeStore;
+pub use memory_knowledge_store::InMemoryKnowledgeStore;
 pub use openrouter_client::OpenRouterClient;
+pub use opt_out_store::{InMemoryOptOutStore, SqliteOptOutStore};
+pub use response_limits_store::{InMemoryResponseLimitsStore, SqliteResponseLimitsStore};
@@ -1,35 +1,56 @@
 use crate::core::ai::{
-    models::{AiConfig, AiMessage, AiProviderResponse},
+    models::{AiConfig, AiMessage, AiProviderResponse, EmptyResponse},
     AiProvider,
 };
+use crate::infra::http;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::json;
 use std::error::Error;
+use std::time::Duration;
 
 pub struct OpenRouterClient {
     client: Client,
     api_key: String,
+    timeout: Duration,
+    /// Model name prefixes that don't accept reasoning parameters.
+    no_reasoning_models: Vec<String>,
 }
 
 impl OpenRouterClient {
-    pub fn new(api_key: String) -> Self {
+    /// `client` is the bot's shared HTTP client (`http::shared_client`).
+    /// Requests give up after `timeout` with `http::RequestTimeout`.
+    pub fn new(client: Client, api_key: String, timeout: Duration) -> Self {
         Self {
-            client: Client::new(),
+            client,
             api_key,
+            timeout,
+            no_reasoning_models: Vec::new(),
         }
     }
-}
 
-#[async_trait]
-impl AiProvider for OpenRouterClient {
-    async fn chat_complete(
-        &self,
-        messages: &[AiMessage],
-        config: &AiConfig,
-    ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
-        let url = "https://openrouter.ai/api/v1/chat/completions";
+    /// Leave reasoning parameters out of requests to models whose name starts
+    /// with one of `prefixes` (`OPENROUTER_NO_REASONING_MODELS`). Some models
+    /// reject them, others silently spend tokens on them.
+    pub fn with_no_reasoning_models(mut self, prefixes: Vec<String>) -> Self {
+        self.no_reasoning_models = prefixes;
+        self
+    }
+
+    /// Whether `model` may be sent reasoning parameters.
+    fn supports_reasoning(&self, model: &str) -> bool {
+        !self
+            .no_reasoning_models
+            .iter()
+            .any(|prefix| model.starts_with(prefix.as_str()))
+    }
+
+    fn request_error(&self, error: reqwest::Error) -> Box<dyn Error + Send + Sync> {
+        http::request_error("OpenRouter", self.timeout, error)
+    }
 
+    /// The chat completion request body.
+    fn build_payload(&self, messages: &[AiMessage], config: &AiConfig) -> serde_json::Value {
         let mut payload = json!({
             "model": config.model,
             "messages": messages,
@@ -57,8 +78,8 @@ impl AiProvider for OpenRouterClient {
                 .insert("repetition_penalty".to_string(), json!(repetition_penalty));
         }
 
-        if let Some(enabled) = config.reasoning_enabled {
-            if enabled {
+        if config.reasoning_enabled == Some(true) {
+            if self.supports_reasoning(&config.model) {
                 let mut reasoning = serde_json::Map::new();
                 reasoning.insert("enabled".to_string(), json!(true));
 
@@ -70,9 +91,65 @@ impl AiProvider for OpenRouterClient {
                     "reasoning".to_string(),
                     serde_json::Value::Object(reasoning),
                 );
+            } else {
+                tracing::debug!(
+                    "Model {} doesn't support reasoning, leaving it out of the request",
+                    config.model
+                );
             }
         }
 
+        payload
+    }
+
+    /// Read the reply out of a successful response body. A body without
+    /// choices or without any text is an `EmptyResponse`, so nothing blank
+    /// gets posted.
+    fn parse_response(
+        response_json: &serde_json::Value,
+    ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+        let Some(choice) = response_json["choices"]
+            .as_array()
+            .and_then(|choices| choices.first())
+        else {
+            return Err(EmptyResponse {
+                provider: "OpenRouter",
+                reason: response_json["error"]["message"].as_str().map(String::from),
+            }
+            .into());
+        };
+
+        let content = choice["message"]["content"].as_str().unwrap_or_default();
+        if content.trim().is_empty() {
+            return Err(EmptyResponse {
+                provider: "OpenRouter",
+                reason: choice["finish_reason"].as_str().map(String::from),
+            }
+            .into());
+        }
+
+        // OpenRouter doesn't have separate thinking field in the same way,
+        // so we return None for thinking (the XML parsing in AiService handles it)
+        Ok(AiProviderResponse {
+            content: content.to_string(),
+            thinking: None,
+            grounding_metadata: None,
+            url_context_metadata: None,
+            function_calls: None,
+        })
+    }
+}
+
+#[async_trait]
+impl AiProvider for OpenRouterClient {
+    async fn chat_complete(
+        &self,
+        messages: &[AiMessage],
+        config: &AiConfig,
+    ) -> Result<AiProviderResponse, Box<dyn Error + Send + Sync>> {
+        let url = "https://openrouter.ai/api/v1/chat/completions";
+        let payload = self.build_payload(messages, config);
+
         let response = self
             .client
             .post(url)
@@ -84,31 +161,118 @@ impl AiProvider for OpenRouterClient {
             )
             .header("X-Title", "Rust Discord Bot")
             .json(&payload)
+            .timeout(self.timeout)
             .send()
-            .await?;
+            .await
+            .map_err(|e| self.request_error(e))?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let text = response.text().await?;
-            return Err(format!("OpenRouter API error: {} - {}", status, text).into());
+            let text = response.text().await.map_err(|e| self.request_error(e))?;
+            return Err(http::ApiError {
+                service: "OpenRouter",
+                status: status.as_u16(),
+                message: text,
+            }
+            .into());
         }
 
-        let response_json: serde_json::Value = response.json().await?;
+        let response_json: serde_json::Value =
+            response.json().await.map_err(|e| self.request_error(e))?;
 
-        // Extract content from the response
-        let content = response_json["choices"][0]["message"]["content"]
-            .as_str()
-            .ok_or("Failed to parse response content")?
-            .to_string();
+        Self::parse_response(&response_json)
+    }
 
-        // OpenRouter doesn't have separate thinking field in the same way,
-        // so we return None for thinking (the XML parsing in AiService handles it)
-        Ok(AiProviderResponse {
-            content,
-            thinking: None,
-            grounding_metadata: None,
-            url_context_metadata: None,
-            function_calls: None,
-        })
+    fn is_outage(&self, error: &(dyn Error + Send + Sync + 'static)) -> bool {
+        http::is_outage(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_serialize_with_openai_role_names() {
+        let messages = vec![
+            AiMessage::system("Be nice"),
+            AiMessage::user("Hi"),
+            AiMessage::assistant("Hello!"),
+        ];
+
+        let value = serde_json::to_value(&messages).unwrap();
+        let roles: Vec<&str> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, vec!["system", "user", "assistant"]);
+
+        let parsed: AiMessage =
+            serde_json::from_str(r#"{"role":"assistant","content":"ok"}"#).unwrap();
+        assert_eq!(parsed.role, crate::core::ai::Role::Assistant);
+        assert!(serde_json::from_str::<AiMessage>(r#"{"role":"model","content":"x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_reasoning_left_out_for_models_without_it() {
+        let client =
+            OpenRouterClient::new(Client::new(), "key".to_string(), Duration::from_secs(5))
+                .with_no_reasoning_models(vec![
+                    "openai/gpt-4o".to_string(),
+                    "meta-llama/".to_string(),
+                ]);
+        let config = |model: &str| AiConfig {
+            model: model.to_string(),
+            temperature: 0.7,
+            max_tokens: None,
+            top_p: None,
+            repetition_penalty: None,
+            reasoning_enabled: Some(true),
+            reasoning_effort: Some("high".to_string()),
+            tools: None,
+            tool_config: None,
+        };
+        let messages = [AiMessage::user("Hi")];
+
+        let payload = client.build_payload(&messages, &config("openai/gpt-4o-mini"));
+        assert!(payload.get("reasoning").is_none());
+        assert_eq!(payload["model"], "openai/gpt-4o-mini");
+        let payload = client.build_payload(&messages, &config("meta-llama/llama-3.3-70b-instruct"));
+        assert!(payload.get("reasoning").is_none());
+
+        let payload = client.build_payload(&messages, &config("x-ai/grok-4.1-fast"));
+        assert_eq!(payload["reasoning"]["enabled"], true);
+        assert_eq!(payload["reasoning"]["effort"], "high");
+    }
+
+    #[test]
+    fn test_empty_choices_are_an_empty_response_error() {
+        let empty_error = |body: serde_json::Value| {
+            let error = OpenRouterClient::parse_response(&body).unwrap_err();
+            error
+                .downcast_ref::<EmptyResponse>()
+                .expect("an EmptyResponse")
+                .clone()
+        };
+
+        let error = empty_error(json!({ "id": "gen-1", "choices": [] }));
+        assert_eq!(error.provider, "OpenRouter");
+        assert_eq!(error.reason, None);
+
+        let error = empty_error(json!({
+            "choices": [{ "message": { "role": "assistant", "content": "" }, "finish_reason": "content_filter" }]
+        }));
+        assert_eq!(error.reason.as_deref(), Some("content_filter"));
+
+        let error = empty_error(json!({ "error": { "message": "Provider returned error" } }));
+        assert_eq!(error.reason.as_deref(), Some("Provider returned error"));
+
+        let response = OpenRouterClient::parse_response(&json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Hello!" } }]
+        }))
+        .unwrap();
+        assert_eq!(response.content, "Hello!");
     }
 }
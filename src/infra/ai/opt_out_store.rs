@@ -0,0 +1,135 @@
+// Who has opted out of the AI (`/ai forgetme`): one `ai_opt_outs` row per
+// user with the time they opted out. The list is global rather than per
+// guild, and opting back in deletes the row. `AiPrivacyService` reads the
+// whole table once at startup and only writes here on changes.
+
+use crate::core::ai::AiOptOutStore;
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashSet;
+use std::error::Error;
+use tokio::sync::RwLock;
+
+pub struct SqliteOptOutStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteOptOutStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS ai_opt_outs (
+                user_id INTEGER PRIMARY KEY,
+                opted_out_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AiOptOutStore for SqliteOptOutStore {
+    async fn load_opted_out(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query("SELECT user_id FROM ai_opt_outs")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<i64, _>("user_id") as u64)
+            .collect())
+    }
+
+    async fn set_opted_out(
+        &self,
+        user_id: u64,
+        opted_out: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if opted_out {
+            sqlx::query(
+                "INSERT INTO ai_opt_outs (user_id, opted_out_at) VALUES (?, ?) ON CONFLICT(user_id) DO NOTHING",
+            )
+            .bind(user_id as i64)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM ai_opt_outs WHERE user_id = ?")
+                .bind(user_id as i64)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryOptOutStore {
+    users: RwLock<HashSet<u64>>,
+}
+
+impl InMemoryOptOutStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AiOptOutStore for InMemoryOptOutStore {
+    async fn load_opted_out(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        Ok(self.users.read().await.iter().copied().collect())
+    }
+
+    async fn set_opted_out(
+        &self,
+        user_id: u64,
+        opted_out: bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut users = self.users.write().await;
+        if opted_out {
+            users.insert(user_id);
+        } else {
+            users.remove(&user_id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_opt_out_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = SqliteOptOutStore::new(pool);
+        store.migrate().await.unwrap();
+        assert!(store.load_opted_out().await.unwrap().is_empty());
+
+        store.set_opted_out(1, true).await.unwrap();
+        store.set_opted_out(2, true).await.unwrap();
+        // Opting out twice keeps a single row
+        store.set_opted_out(1, true).await.unwrap();
+        let mut opted_out = store.load_opted_out().await.unwrap();
+        opted_out.sort_unstable();
+        assert_eq!(opted_out, vec![1, 2]);
+
+        store.set_opted_out(1, false).await.unwrap();
+        assert_eq!(store.load_opted_out().await.unwrap(), vec![2]);
+    }
+}
@@ -0,0 +1,108 @@
+// Stores for per-guild AI response limits (`/ai responses`).
+//
+// The SQLite table lives in the shared AI settings database next to the
+// opt-out list.
+
+use crate::core::ai::{LongResponseMode, ResponseLimits, ResponseLimitsStore};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use sqlx::{Pool, Row, Sqlite};
+use std::error::Error;
+
+pub struct SqliteResponseLimitsStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteResponseLimitsStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS ai_response_limits (
+                guild_id INTEGER PRIMARY KEY,
+                max_messages INTEGER NOT NULL,
+                mode TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ResponseLimitsStore for SqliteResponseLimitsStore {
+    async fn get_limits(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<ResponseLimits>, Box<dyn Error + Send + Sync>> {
+        let row =
+            sqlx::query("SELECT max_messages, mode FROM ai_response_limits WHERE guild_id = ?")
+                .bind(guild_id as i64)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.map(|row| {
+            let mode: String = row.get("mode");
+            ResponseLimits {
+                max_messages: row.get::<i64, _>("max_messages") as u8,
+                mode: LongResponseMode::parse(&mode).unwrap_or(LongResponseMode::Truncate),
+            }
+        }))
+    }
+
+    async fn save_limits(
+        &self,
+        guild_id: u64,
+        limits: ResponseLimits,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO ai_response_limits (guild_id, max_messages, mode) VALUES (?, ?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET max_messages = excluded.max_messages, mode = excluded.mode
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(limits.max_messages as i64)
+        .bind(limits.mode.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryResponseLimitsStore {
+    limits: DashMap<u64, ResponseLimits>,
+}
+
+impl InMemoryResponseLimitsStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ResponseLimitsStore for InMemoryResponseLimitsStore {
+    async fn get_limits(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<ResponseLimits>, Box<dyn Error + Send + Sync>> {
+        Ok(self.limits.get(&guild_id).map(|limits| *limits))
+    }
+
+    async fn save_limits(
+        &self,
+        guild_id: u64,
+        limits: ResponseLimits,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.limits.insert(guild_id, limits);
+        Ok(())
+    }
+}
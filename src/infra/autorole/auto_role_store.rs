@@ -0,0 +1,142 @@
+// Each guild's auto-role: one `auto_roles` row per guild with the role handed
+// to new members and whether that is switched on. Turning the auto-role off
+// keeps the row (and the role) so turning it back on needs no role; there is
+// no way to delete the setting.
+
+use crate::core::autorole::{AutoRoleConfig, AutoRoleStore};
+use async_trait::async_trait;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use std::error::Error;
+use tokio::sync::RwLock;
+
+pub struct SqliteAutoRoleStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteAutoRoleStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS auto_roles (
+                guild_id INTEGER PRIMARY KEY,
+                role_id INTEGER NOT NULL,
+                enabled INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AutoRoleStore for SqliteAutoRoleStore {
+    async fn get_auto_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<AutoRoleConfig>, Box<dyn Error + Send + Sync>> {
+        let row = sqlx::query("SELECT role_id, enabled FROM auto_roles WHERE guild_id = ?")
+            .bind(guild_id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| AutoRoleConfig {
+            role_id: row.get::<i64, _>("role_id") as u64,
+            enabled: row.get::<bool, _>("enabled"),
+        }))
+    }
+
+    async fn set_auto_role(
+        &self,
+        guild_id: u64,
+        config: AutoRoleConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO auto_roles (guild_id, role_id, enabled)
+            VALUES (?, ?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET
+                role_id = excluded.role_id,
+                enabled = excluded.enabled
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(config.role_id as i64)
+        .bind(config.enabled)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryAutoRoleStore {
+    configs: RwLock<HashMap<u64, AutoRoleConfig>>,
+}
+
+impl InMemoryAutoRoleStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AutoRoleStore for InMemoryAutoRoleStore {
+    async fn get_auto_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<AutoRoleConfig>, Box<dyn Error + Send + Sync>> {
+        Ok(self.configs.read().await.get(&guild_id).copied())
+    }
+
+    async fn set_auto_role(
+        &self,
+        guild_id: u64,
+        config: AutoRoleConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.configs.write().await.insert(guild_id, config);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_auto_role_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = SqliteAutoRoleStore::new(pool);
+        store.migrate().await.unwrap();
+        assert_eq!(store.get_auto_role(1).await.unwrap(), None);
+
+        let config = AutoRoleConfig {
+            role_id: 10,
+            enabled: true,
+        };
+        store.set_auto_role(1, config).await.unwrap();
+        assert_eq!(store.get_auto_role(1).await.unwrap(), Some(config));
+
+        // Switching it off keeps the role
+        let disabled = AutoRoleConfig {
+            enabled: false,
+            ..config
+        };
+        store.set_auto_role(1, disabled).await.unwrap();
+        assert_eq!(store.get_auto_role(1).await.unwrap(), Some(disabled));
+        assert_eq!(store.get_auto_role(2).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,309 @@
+// Stores for member birthdays (`/birthday`) and each guild's announcement
+// setting (`/config birthdays`).
+//
+// SQLite keeps `birthdays` and `birthday_channels` tables in the guild settings
+// database; the in-memory store is used when `STORAGE_BACKEND=memory` and in
+// tests.
+
+use crate::core::birthdays::{Birthday, BirthdayConfig, BirthdayStore};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use std::error::Error;
+use tokio::sync::RwLock;
+
+pub struct SqliteBirthdayStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteBirthdayStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS birthdays (
+                guild_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                month INTEGER NOT NULL,
+                day INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, user_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS birthday_channels (
+                guild_id INTEGER PRIMARY KEY,
+                channel_id INTEGER NOT NULL,
+                role_id INTEGER,
+                last_celebrated TEXT,
+                role_holders TEXT NOT NULL DEFAULT '[]'
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn birthday_from_row(month: i64, day: i64) -> Result<Birthday, Box<dyn Error + Send + Sync>> {
+    Birthday::new(month as u32, day as u32)
+        .ok_or_else(|| format!("Invalid stored birthday {}-{}", month, day).into())
+}
+
+#[async_trait]
+impl BirthdayStore for SqliteBirthdayStore {
+    async fn get_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<Option<Birthday>, Box<dyn Error + Send + Sync>> {
+        let row =
+            sqlx::query("SELECT month, day FROM birthdays WHERE guild_id = ? AND user_id = ?")
+                .bind(guild_id as i64)
+                .bind(user_id as i64)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        row.map(|row| birthday_from_row(row.get("month"), row.get("day")))
+            .transpose()
+    }
+
+    async fn set_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        birthday: Birthday,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO birthdays (guild_id, user_id, month, day)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(guild_id, user_id) DO UPDATE SET
+                month = excluded.month,
+                day = excluded.day
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(user_id as i64)
+        .bind(birthday.month() as i64)
+        .bind(birthday.day() as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query("DELETE FROM birthdays WHERE guild_id = ? AND user_id = ?")
+            .bind(guild_id as i64)
+            .bind(user_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn guild_birthdays(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<(u64, Birthday)>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query("SELECT user_id, month, day FROM birthdays WHERE guild_id = ?")
+            .bind(guild_id as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let birthday = birthday_from_row(row.get("month"), row.get("day"))?;
+                Ok((row.get::<i64, _>("user_id") as u64, birthday))
+            })
+            .collect()
+    }
+
+    async fn get_birthday_config(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<BirthdayConfig>, Box<dyn Error + Send + Sync>> {
+        let row = sqlx::query(
+            r#"
+            SELECT channel_id, role_id, last_celebrated, role_holders
+            FROM birthday_channels WHERE guild_id = ?
+            "#,
+        )
+        .bind(guild_id as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let last_celebrated = row
+            .get::<Option<String>, _>("last_celebrated")
+            .map(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d"))
+            .transpose()?;
+        let role_holders = serde_json::from_str(&row.get::<String, _>("role_holders"))?;
+        Ok(Some(BirthdayConfig {
+            channel_id: row.get::<i64, _>("channel_id") as u64,
+            role_id: row.get::<Option<i64>, _>("role_id").map(|id| id as u64),
+            last_celebrated,
+            role_holders,
+        }))
+    }
+
+    async fn set_birthday_config(
+        &self,
+        guild_id: u64,
+        config: &BirthdayConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO birthday_channels
+                (guild_id, channel_id, role_id, last_celebrated, role_holders)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET
+                channel_id = excluded.channel_id,
+                role_id = excluded.role_id,
+                last_celebrated = excluded.last_celebrated,
+                role_holders = excluded.role_holders
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(config.channel_id as i64)
+        .bind(config.role_id.map(|id| id as i64))
+        .bind(config.last_celebrated.map(|date| date.to_string()))
+        .bind(serde_json::to_string(&config.role_holders)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_birthday_config(
+        &self,
+        guild_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query("DELETE FROM birthday_channels WHERE guild_id = ?")
+            .bind(guild_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn birthday_guilds(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query("SELECT guild_id FROM birthday_channels")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get::<i64, _>("guild_id") as u64)
+            .collect())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryBirthdayStore {
+    birthdays: RwLock<HashMap<(u64, u64), Birthday>>,
+    configs: RwLock<HashMap<u64, BirthdayConfig>>,
+}
+
+impl InMemoryBirthdayStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BirthdayStore for InMemoryBirthdayStore {
+    async fn get_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<Option<Birthday>, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .birthdays
+            .read()
+            .await
+            .get(&(guild_id, user_id))
+            .copied())
+    }
+
+    async fn set_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        birthday: Birthday,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.birthdays
+            .write()
+            .await
+            .insert((guild_id, user_id), birthday);
+        Ok(())
+    }
+
+    async fn remove_birthday(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.birthdays.write().await.remove(&(guild_id, user_id));
+        Ok(())
+    }
+
+    async fn guild_birthdays(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<(u64, Birthday)>, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .birthdays
+            .read()
+            .await
+            .iter()
+            .filter(|((guild, _), _)| *guild == guild_id)
+            .map(|(&(_, user_id), &birthday)| (user_id, birthday))
+            .collect())
+    }
+
+    async fn get_birthday_config(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<BirthdayConfig>, Box<dyn Error + Send + Sync>> {
+        Ok(self.configs.read().await.get(&guild_id).cloned())
+    }
+
+    async fn set_birthday_config(
+        &self,
+        guild_id: u64,
+        config: &BirthdayConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.configs.write().await.insert(guild_id, config.clone());
+        Ok(())
+    }
+
+    async fn remove_birthday_config(
+        &self,
+        guild_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.configs.write().await.remove(&guild_id);
+        Ok(())
+    }
+
+    async fn birthday_guilds(&self) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+        Ok(self.configs.read().await.keys().copied().collect())
+    }
+}
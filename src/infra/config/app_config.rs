@@ -0,0 +1,1055 @@
+// Startup configuration: an optional `config.toml` with environment variables
+// on top.
+//
+// Every knob the composition root needs lives in `Config`, grouped the same
+// way as the file:
+//
+// ```toml
+// [discord]
+// token = "..."
+//
+// [ai]
+// provider = "gemini"
+// gemini_model = "gemini-3-pro"
+//
+// [github]
+// poll_interval_secs = 600
+// ```
+//
+// The file is read from `CONFIG_FILE` (default `config.toml`) and may be
+// missing entirely. Each field can also be set with the environment variable
+// documented in `.env.example`; the environment wins, so secrets can stay out
+// of the file. Problems are collected and reported together by `load_config`.
+//
+// The system prompt and reasoning options can also be reloaded at runtime:
+// `/reload` builds a fresh `Config` (see `infra::ai::load_ai_settings`) and
+// applies only `Config::ai_settings`.
+
+use crate::core::leveling::{DailyRewardConfig, LevelingError, VoiceFlags};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Default location of the config file.
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub discord: DiscordConfig,
+    pub storage: StorageConfig,
+    pub ai: AiProviderConfig,
+    pub leveling: LevelingSettings,
+    pub github: GithubSettings,
+    pub economy: EconomySettings,
+    pub cooldowns: CooldownSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiscordConfig {
+    /// Bot token (`DISCORD_TOKEN`). Required.
+    pub token: Option<String>,
+    /// Channel for detailed command error reports (`ERROR_LOG_CHANNEL_ID`).
+    pub error_log_channel_id: Option<u64>,
+    /// Channel that new `/feedback` entries are forwarded to
+    /// (`FEEDBACK_CHANNEL_ID`).
+    pub feedback_channel_id: Option<u64>,
+    /// Users allowed to run owner-only commands such as `/admin`, in addition
+    /// to the application's owner or team (`BOT_OWNER_IDS`, comma-separated).
+    pub owner_ids: Vec<u64>,
+    /// Prefix for text commands such as `!level` (`COMMAND_PREFIX`).
+    pub command_prefix: String,
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            error_log_channel_id: None,
+            feedback_channel_id: None,
+            owner_ids: Vec::new(),
+            command_prefix: "!".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// SQLite/JSON files under `data_dir` and `config/`.
+    #[default]
+    Sqlite,
+    /// Nothing is written to disk.
+    Memory,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// `STORAGE_BACKEND`
+    pub backend: StorageBackend,
+    /// Folder for the SQLite databases and JSON state (`DATA_DIR`).
+    pub data_dir: String,
+    /// Extra/override locale files (`LOCALES_DIR`).
+    pub locales_dir: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Sqlite,
+            data_dir: "data".to_string(),
+            locales_dir: "locales".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProviderKind {
+    OpenRouter,
+    Gemini,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AiProviderConfig {
+    /// `AI_PROVIDER`. When unset, whichever provider has an API key is used
+    /// (OpenRouter first), and AI is off if neither has one.
+    pub provider: Option<AiProviderKind>,
+    /// `OPENROUTER_API_KEY`, required for OpenRouter.
+    pub openrouter_api_key: Option<String>,
+    /// `OPENROUTER_MODEL`
+    pub openrouter_model: String,
+    /// Model name prefixes that get no reasoning parameters, because the
+    /// models don't support them (`OPENROUTER_NO_REASONING_MODELS`).
+    pub openrouter_no_reasoning_models: Vec<String>,
+    /// `GEMINI_API_KEY`, required for Gemini.
+    pub gemini_api_key: Option<String>,
+    /// `GEMINI_MODEL`; "auto" or "best" pick the top of the model hierarchy.
+    pub gemini_model: String,
+    /// `AI_TEMPERATURE`
+    pub temperature: f32,
+    /// `AI_MAX_TOKENS` (no limit when unset)
+    pub max_tokens: Option<u32>,
+    /// `AI_TOP_P`
+    pub top_p: f32,
+    /// Messages fetched for context on a mention (`AI_MAX_HISTORY`).
+    pub max_history: u8,
+    /// Token budget for context selection (`AI_CONTEXT_TOKEN_BUDGET`).
+    pub context_token_budget: usize,
+    /// Leave other bots' messages out of the context (`AI_HISTORY_SKIP_BOTS`).
+    pub history_skip_bots: bool,
+    /// Leave text commands out of the context (`AI_HISTORY_SKIP_COMMANDS`).
+    pub history_skip_commands: bool,
+    /// Leave out messages shorter than this many characters (`AI_HISTORY_MIN_CHARS`).
+    pub history_min_chars: usize,
+    /// Reuse replies to identical tool-less requests (`AI_RESPONSE_CACHE`).
+    pub response_cache: bool,
+    /// How long a cached reply is reused (`AI_RESPONSE_CACHE_TTL_SECS`).
+    pub response_cache_ttl_secs: u64,
+    /// Provider errors in a row before requests start failing fast; 0 turns
+    /// the circuit breaker off (`AI_CIRCUIT_BREAKER_FAILURES`).
+    pub circuit_breaker_failures: u32,
+    /// How long to fail fast before probing the provider again
+    /// (`AI_CIRCUIT_BREAKER_COOLDOWN_SECS`).
+    pub circuit_breaker_cooldown_secs: u64,
+    /// How long one provider request may take (`AI_REQUEST_TIMEOUT_SECS`).
+    pub request_timeout_secs: u64,
+    /// AI provider calls in flight at once across the bot; requests past the
+    /// limit are told to try again. 0 = unlimited (`AI_MAX_CONCURRENT_REQUESTS`).
+    pub max_concurrent_requests: usize,
+    /// Google Search grounding for Gemini (`AI_ENABLE_GOOGLE_SEARCH`).
+    pub enable_google_search: bool,
+    /// How long one Google Docs request may take (`GOOGLE_DOCS_TIMEOUT_SECS`).
+    pub google_docs_timeout_secs: u64,
+    /// Let Gemini requests mix built-in tools with function calling, which
+    /// Gemini may reject (`GEMINI_ALLOW_MIXED_TOOLS_AND_FUNCTIONS`).
+    pub gemini_allow_mixed_tools: bool,
+    /// `AI_SYSTEM_PROMPT`
+    pub system_prompt: Option<String>,
+    /// File to read the system prompt from, used over `system_prompt`
+    /// (`AI_SYSTEM_PROMPT_FILE`).
+    pub system_prompt_file: Option<String>,
+    /// `AI_REASONING_ENABLED`; unset leaves reasoning up to the provider.
+    pub reasoning_enabled: Option<bool>,
+    /// `AI_REASONING_EFFORT`, e.g. "low", "medium" or "high".
+    pub reasoning_effort: Option<String>,
+}
+
+impl Default for AiProviderConfig {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            openrouter_api_key: None,
+            openrouter_model: "deepseek/deepseek-chat-v3.1:free".to_string(),
+            openrouter_no_reasoning_models: [
+                "openai/gpt-4o",
+                "openai/gpt-4.1",
+                "openai/gpt-3.5",
+                "meta-llama/",
+                "mistralai/mistral-",
+                "google/gemma",
+            ]
+            .map(String::from)
+            .to_vec(),
+            gemini_api_key: None,
+            gemini_model: "auto".to_string(),
+            temperature: 0.7,
+            max_tokens: None,
+            top_p: 1.0,
+            max_history: 50,
+            context_token_budget: 8000,
+            history_skip_bots: true,
+            history_skip_commands: true,
+            history_min_chars: 0,
+            response_cache: false,
+            response_cache_ttl_secs: 300,
+            circuit_breaker_failures: 5,
+            circuit_breaker_cooldown_secs: 60,
+            request_timeout_secs: 60,
+            max_concurrent_requests: 4,
+            enable_google_search: true,
+            google_docs_timeout_secs: 30,
+            gemini_allow_mixed_tools: false,
+            system_prompt: None,
+            system_prompt_file: None,
+            reasoning_enabled: None,
+            reasoning_effort: None,
+        }
+    }
+}
+
+/// Leveling knobs. Unset values keep the defaults from
+/// `core::leveling::LevelingConfig` and `DailyRewardConfig`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LevelingSettings {
+    /// `LEVEL_UP_ANNOUNCE_COOLDOWN_SECS`
+    pub level_up_announce_cooldown_secs: Option<u64>,
+    /// `DAILY_STREAK_GRACE_DAYS`
+    pub daily_streak_grace_days: Option<u32>,
+    /// `DAILY_BASE_XP`
+    pub daily_base_xp: Option<u64>,
+    /// `DAILY_STREAK_BONUS_STEP`
+    pub daily_streak_bonus_step: Option<u64>,
+    /// `DAILY_STREAK_BONUS_CAP`
+    pub daily_streak_bonus_cap: Option<u64>,
+    /// `DAILY_GOAL_BONUS_XP`
+    pub daily_goal_bonus_xp: Option<u64>,
+    /// How often leaderboard ranks are recomputed (`RANK_REFRESH_INTERVAL_SECS`).
+    pub rank_refresh_interval_secs: u64,
+    /// Voice flags that pause voice time, comma-separated (`VOICE_PAUSE_FLAGS`).
+    pub voice_pause_flags: Option<String>,
+}
+
+impl LevelingSettings {
+    /// The `/daily` XP amounts with the defaults filled in for unset values.
+    pub fn daily_reward_config(&self) -> DailyRewardConfig {
+        let defaults = DailyRewardConfig::default();
+        DailyRewardConfig {
+            base_xp: self.daily_base_xp.unwrap_or(defaults.base_xp),
+            streak_bonus_step: self
+                .daily_streak_bonus_step
+                .unwrap_or(defaults.streak_bonus_step),
+            streak_bonus_cap: self
+                .daily_streak_bonus_cap
+                .unwrap_or(defaults.streak_bonus_cap),
+            goal_bonus_xp: self.daily_goal_bonus_xp.unwrap_or(defaults.goal_bonus_xp),
+        }
+    }
+}
+
+impl Default for LevelingSettings {
+    fn default() -> Self {
+        Self {
+            level_up_announce_cooldown_secs: None,
+            daily_streak_grace_days: None,
+            daily_base_xp: None,
+            daily_streak_bonus_step: None,
+            daily_streak_bonus_cap: None,
+            daily_goal_bonus_xp: None,
+            rank_refresh_interval_secs: 3600,
+            voice_pause_flags: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GithubSettings {
+    /// Personal access token for private repos and higher rate limits
+    /// (`GITHUB_TOKEN`).
+    pub token: Option<String>,
+    /// `GITHUB_POLL_INTERVAL_SECS`
+    pub poll_interval_secs: u64,
+    /// Diff stats on commit notifications (`GITHUB_COMMIT_STATS`).
+    pub commit_stats: bool,
+    /// How often tracked organizations are re-listed to find new repos
+    /// (`GITHUB_ORG_REFRESH_INTERVAL_SECS`).
+    pub org_refresh_interval_secs: u64,
+    /// How long one GitHub API request may take (`GITHUB_REQUEST_TIMEOUT_SECS`).
+    pub request_timeout_secs: u64,
+}
+
+impl Default for GithubSettings {
+    fn default() -> Self {
+        Self {
+            token: None,
+            poll_interval_secs: 300,
+            commit_stats: false,
+            org_refresh_interval_secs: 24 * 60 * 60,
+            request_timeout_secs: 30,
+        }
+    }
+}
+
+/// Coins from chatting. Unset values keep the defaults from
+/// `core::economy::EconomyConfig`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EconomySettings {
+    /// `MESSAGE_COIN_REWARDS`
+    pub message_rewards: bool,
+    /// Chance from 0 to 1 (`MESSAGE_COIN_CHANCE`).
+    pub message_reward_chance: Option<f64>,
+    /// `MESSAGE_COIN_MIN`
+    pub message_reward_min: Option<i64>,
+    /// `MESSAGE_COIN_MAX`
+    pub message_reward_max: Option<i64>,
+    /// Post a message when chatting earns coins (`ANNOUNCE_MESSAGE_COINS`).
+    pub announce_message_rewards: bool,
+}
+
+#[cfg(feature = "economy")]
+impl EconomySettings {
+    /// The economy settings with the defaults filled in for unset values.
+    pub fn economy_config(&self) -> crate::core::economy::EconomyConfig {
+        let defaults = crate::core::economy::EconomyConfig::default();
+        crate::core::economy::EconomyConfig {
+            message_reward_chance: self
+                .message_reward_chance
+                .unwrap_or(defaults.message_reward_chance),
+            message_reward_min: self
+                .message_reward_min
+                .unwrap_or(defaults.message_reward_min),
+            message_reward_max: self
+                .message_reward_max
+                .unwrap_or(defaults.message_reward_max),
+            message_rewards_enabled: self.message_rewards,
+            announce_message_rewards: self.announce_message_rewards,
+            ..defaults
+        }
+    }
+}
+
+impl Default for EconomySettings {
+    fn default() -> Self {
+        Self {
+            message_rewards: true,
+            message_reward_chance: None,
+            message_reward_min: None,
+            message_reward_max: None,
+            announce_message_rewards: false,
+        }
+    }
+}
+
+/// Per-command cooldowns in seconds, keyed by the command's full name
+/// (`leaderboard`, `ai responses`). Setting a map replaces the defaults; use 0
+/// to turn a default off.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CooldownSettings {
+    /// Between uses by the same user (`COMMAND_USER_COOLDOWNS`, e.g.
+    /// `leaderboard=10,summarize=30`).
+    pub user_secs: BTreeMap<String, u64>,
+    /// Between uses in the same channel (`COMMAND_CHANNEL_COOLDOWNS`).
+    pub channel_secs: BTreeMap<String, u64>,
+}
+
+impl Default for CooldownSettings {
+    fn default() -> Self {
+        let entries = |pairs: &[(&str, u64)]| {
+            pairs
+                .iter()
+                .map(|&(name, secs)| (name.to_string(), secs))
+                .collect()
+        };
+        Self {
+            user_secs: entries(&[
+                ("feedback", 300),
+                ("leaderboard", 10),
+                ("level", 5),
+                ("profile", 5),
+                ("summarize", 30),
+                ("tldr", 30),
+                ("translate", 10),
+            ]),
+            channel_secs: entries(&[("leaderboard", 3)]),
+        }
+    }
+}
+
+/// Everything wrong with the configuration, reported in one go.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration:")?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Parse a `config.toml`.
+    pub fn from_toml_str(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Override fields with environment variables. `lookup` returns a
+    /// variable's value (`std::env::var` outside of tests). Returns the
+    /// variables that were set but couldn't be parsed.
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let mut env = EnvOverrides {
+            lookup: &lookup,
+            problems: Vec::new(),
+        };
+
+        env.string("DISCORD_TOKEN", &mut self.discord.token);
+        env.parse_opt(
+            "ERROR_LOG_CHANNEL_ID",
+            &mut self.discord.error_log_channel_id,
+        );
+        env.parse_opt("FEEDBACK_CHANNEL_ID", &mut self.discord.feedback_channel_id);
+        env.list("BOT_OWNER_IDS", &mut self.discord.owner_ids);
+        env.parse("COMMAND_PREFIX", &mut self.discord.command_prefix);
+
+        if let Some(backend) = env.get("STORAGE_BACKEND") {
+            // Anything but "memory" means the default on-disk storage
+            self.storage.backend = if backend.eq_ignore_ascii_case("memory") {
+                StorageBackend::Memory
+            } else {
+                StorageBackend::Sqlite
+            };
+        }
+        env.parse("DATA_DIR", &mut self.storage.data_dir);
+        env.parse("LOCALES_DIR", &mut self.storage.locales_dir);
+
+        if let Some(provider) = env.get("AI_PROVIDER") {
+            // Anything but "gemini" means OpenRouter
+            self.ai.provider = Some(if provider.eq_ignore_ascii_case("gemini") {
+                AiProviderKind::Gemini
+            } else {
+                AiProviderKind::OpenRouter
+            });
+        }
+        env.string("OPENROUTER_API_KEY", &mut self.ai.openrouter_api_key);
+        env.parse("OPENROUTER_MODEL", &mut self.ai.openrouter_model);
+        env.list(
+            "OPENROUTER_NO_REASONING_MODELS",
+            &mut self.ai.openrouter_no_reasoning_models,
+        );
+        env.string("GEMINI_API_KEY", &mut self.ai.gemini_api_key);
+        env.parse("GEMINI_MODEL", &mut self.ai.gemini_model);
+        env.parse("AI_TEMPERATURE", &mut self.ai.temperature);
+        env.parse_opt("AI_MAX_TOKENS", &mut self.ai.max_tokens);
+        env.parse("AI_TOP_P", &mut self.ai.top_p);
+        env.parse("OPENROUTER_MAX_HISTORY", &mut self.ai.max_history); // Backwards compat
+        env.parse("AI_MAX_HISTORY", &mut self.ai.max_history);
+        env.parse("AI_CONTEXT_TOKEN_BUDGET", &mut self.ai.context_token_budget);
+        env.flag("AI_HISTORY_SKIP_BOTS", &mut self.ai.history_skip_bots);
+        env.flag(
+            "AI_HISTORY_SKIP_COMMANDS",
+            &mut self.ai.history_skip_commands,
+        );
+        env.parse("AI_HISTORY_MIN_CHARS", &mut self.ai.history_min_chars);
+        env.flag("AI_RESPONSE_CACHE", &mut self.ai.response_cache);
+        env.parse(
+            "AI_RESPONSE_CACHE_TTL_SECS",
+            &mut self.ai.response_cache_ttl_secs,
+        );
+        env.parse(
+            "AI_CIRCUIT_BREAKER_FAILURES",
+            &mut self.ai.circuit_breaker_failures,
+        );
+        env.parse(
+            "AI_CIRCUIT_BREAKER_COOLDOWN_SECS",
+            &mut self.ai.circuit_breaker_cooldown_secs,
+        );
+        env.parse("AI_REQUEST_TIMEOUT_SECS", &mut self.ai.request_timeout_secs);
+        env.parse(
+            "AI_MAX_CONCURRENT_REQUESTS",
+            &mut self.ai.max_concurrent_requests,
+        );
+        env.flag("AI_ENABLE_GOOGLE_SEARCH", &mut self.ai.enable_google_search);
+        env.parse(
+            "GOOGLE_DOCS_TIMEOUT_SECS",
+            &mut self.ai.google_docs_timeout_secs,
+        );
+        env.flag(
+            "GEMINI_ALLOW_MIXED_TOOLS_AND_FUNCTIONS",
+            &mut self.ai.gemini_allow_mixed_tools,
+        );
+        env.string("OPENROUTER_SYSTEM_PROMPT", &mut self.ai.system_prompt); // Backwards compat
+        env.string("AI_SYSTEM_PROMPT", &mut self.ai.system_prompt);
+        env.string("AI_SYSTEM_PROMPT_FILE", &mut self.ai.system_prompt_file);
+        env.parse_opt("AI_REASONING_ENABLED", &mut self.ai.reasoning_enabled);
+        env.string("AI_REASONING_EFFORT", &mut self.ai.reasoning_effort);
+        // The OpenRouter-specific names win when OpenRouter is the provider
+        if self.ai_provider() == Some(AiProviderKind::OpenRouter) {
+            env.parse_opt(
+                "OPENROUTER_REASONING_ENABLED",
+                &mut self.ai.reasoning_enabled,
+            );
+            env.string("OPENROUTER_REASONING_EFFORT", &mut self.ai.reasoning_effort);
+        }
+
+        let leveling = &mut self.leveling;
+        env.parse_opt(
+            "LEVEL_UP_ANNOUNCE_COOLDOWN_SECS",
+            &mut leveling.level_up_announce_cooldown_secs,
+        );
+        env.parse_opt(
+            "DAILY_STREAK_GRACE_DAYS",
+            &mut leveling.daily_streak_grace_days,
+        );
+        env.parse_opt("DAILY_BASE_XP", &mut leveling.daily_base_xp);
+        env.parse_opt(
+            "DAILY_STREAK_BONUS_STEP",
+            &mut leveling.daily_streak_bonus_step,
+        );
+        env.parse_opt(
+            "DAILY_STREAK_BONUS_CAP",
+            &mut leveling.daily_streak_bonus_cap,
+        );
+        env.parse_opt("DAILY_GOAL_BONUS_XP", &mut leveling.daily_goal_bonus_xp);
+        env.parse(
+            "RANK_REFRESH_INTERVAL_SECS",
+            &mut leveling.rank_refresh_interval_secs,
+        );
+        env.string("VOICE_PAUSE_FLAGS", &mut leveling.voice_pause_flags);
+
+        env.string("GITHUB_TOKEN", &mut self.github.token);
+        env.parse(
+            "GITHUB_POLL_INTERVAL_SECS",
+            &mut self.github.poll_interval_secs,
+        );
+        env.flag("GITHUB_COMMIT_STATS", &mut self.github.commit_stats);
+        env.parse(
+            "GITHUB_ORG_REFRESH_INTERVAL_SECS",
+            &mut self.github.org_refresh_interval_secs,
+        );
+        env.parse(
+            "GITHUB_REQUEST_TIMEOUT_SECS",
+            &mut self.github.request_timeout_secs,
+        );
+
+        let economy = &mut self.economy;
+        env.flag("MESSAGE_COIN_REWARDS", &mut economy.message_rewards);
+        env.parse_opt("MESSAGE_COIN_CHANCE", &mut economy.message_reward_chance);
+        env.parse_opt("MESSAGE_COIN_MIN", &mut economy.message_reward_min);
+        env.parse_opt("MESSAGE_COIN_MAX", &mut economy.message_reward_max);
+        env.flag(
+            "ANNOUNCE_MESSAGE_COINS",
+            &mut economy.announce_message_rewards,
+        );
+
+        env.map("COMMAND_USER_COOLDOWNS", &mut self.cooldowns.user_secs);
+        env.map(
+            "COMMAND_CHANNEL_COOLDOWNS",
+            &mut self.cooldowns.channel_secs,
+        );
+
+        env.problems
+    }
+
+    /// Required settings that are missing.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.discord.token.is_none() {
+            problems.push(
+                "DISCORD_TOKEN is not set (add it to .env or [discord] token in config.toml)"
+                    .to_string(),
+            );
+        }
+
+        // Discord IDs are never 0, and a 0 would panic when turned into a UserId
+        if self.discord.owner_ids.contains(&0) {
+            problems.push("BOT_OWNER_IDS must not contain 0".to_string());
+        }
+
+        if self.discord.command_prefix.trim().is_empty() {
+            problems.push("COMMAND_PREFIX must not be empty".to_string());
+        }
+
+        // An explicitly chosen provider needs its key; otherwise AI is optional
+        match self.ai.provider {
+            Some(AiProviderKind::OpenRouter) if self.ai.openrouter_api_key.is_none() => {
+                problems.push(
+                    "OPENROUTER_API_KEY is not set (required when AI_PROVIDER=openrouter)"
+                        .to_string(),
+                );
+            }
+            Some(AiProviderKind::Gemini) if self.ai.gemini_api_key.is_none() => {
+                problems.push(
+                    "GEMINI_API_KEY is not set (required when AI_PROVIDER=gemini)".to_string(),
+                );
+            }
+            _ => {}
+        }
+
+        // A zero timeout would fail every request before it is sent
+        if self.ai.google_docs_timeout_secs == 0 {
+            problems.push("GOOGLE_DOCS_TIMEOUT_SECS must be at least 1".to_string());
+        }
+
+        #[cfg(feature = "ai")]
+        if let Err(problem) = self.ai_settings() {
+            problems.push(problem);
+        }
+
+        if let Some(list) = &self.leveling.voice_pause_flags {
+            if let Err(LevelingError::InvalidConfig(problem)) = VoiceFlags::parse_list(list) {
+                problems.push(format!("VOICE_PAUSE_FLAGS is invalid: {}", problem));
+            }
+        }
+
+        if let Err(LevelingError::InvalidConfig(problem)) =
+            self.leveling.daily_reward_config().validate()
+        {
+            problems.push(format!(
+                "DAILY_STREAK_BONUS_* settings are invalid: {}",
+                problem
+            ));
+        }
+
+        #[cfg(feature = "economy")]
+        if let Err(crate::core::economy::EconomyError::InvalidConfig(problem)) =
+            self.economy.economy_config().validate()
+        {
+            problems.push(format!("MESSAGE_COIN_* settings are invalid: {}", problem));
+        }
+
+        problems
+    }
+
+    /// The system prompt and reasoning options. The prompt comes from
+    /// `system_prompt_file`, else `system_prompt`, else the default.
+    ///
+    /// Fails only if `system_prompt_file` is set but can't be read.
+    #[cfg(feature = "ai")]
+    pub fn ai_settings(&self) -> Result<crate::core::ai::AiSettings, String> {
+        let system_prompt = match &self.ai.system_prompt_file {
+            Some(path) => std::fs::read_to_string(path).map_err(|e| {
+                format!(
+                    "AI_SYSTEM_PROMPT_FILE: failed to read the system prompt file at {}: {}",
+                    path, e
+                )
+            })?,
+            None => self
+                .ai
+                .system_prompt
+                .clone()
+                .unwrap_or_else(|| crate::core::ai::DEFAULT_SYSTEM_PROMPT.to_string()),
+        };
+        Ok(crate::core::ai::AiSettings {
+            system_prompt,
+            reasoning_enabled: self.ai.reasoning_enabled,
+            reasoning_effort: self.ai.reasoning_effort.clone(),
+        })
+    }
+
+    /// The AI provider to use, or `None` to run without AI.
+    pub fn ai_provider(&self) -> Option<AiProviderKind> {
+        if let Some(provider) = self.ai.provider {
+            return Some(provider);
+        }
+        if self.ai.openrouter_api_key.is_some() {
+            Some(AiProviderKind::OpenRouter)
+        } else if self.ai.gemini_api_key.is_some() {
+            Some(AiProviderKind::Gemini)
+        } else {
+            None
+        }
+    }
+
+    pub fn in_memory(&self) -> bool {
+        self.storage.backend == StorageBackend::Memory
+    }
+}
+
+/// Variables `load_dotenv` filled in from `.env`, as opposed to ones set in
+/// the environment the bot was started with.
+static DOTENV_NAMES: OnceLock<HashSet<String>> = OnceLock::new();
+
+/// Load `.env` (if there is one) into the environment. Variables that are
+/// already set are left alone, and the ones it fills in are remembered.
+pub fn load_dotenv() {
+    #[allow(deprecated)]
+    let names = match dotenv::dotenv_iter() {
+        Ok(iter) => iter
+            .filter_map(Result::ok)
+            .map(|(name, _)| name)
+            .filter(|name| std::env::var_os(name).is_none())
+            .collect(),
+        Err(_) => HashSet::new(),
+    };
+    dotenv::dotenv().ok();
+    let _ = DOTENV_NAMES.set(names);
+}
+
+/// Whether `name` is set in the environment the bot was started with, not
+/// just from `.env`.
+pub fn set_outside_dotenv(name: &str) -> bool {
+    std::env::var_os(name).is_some()
+        && !DOTENV_NAMES.get().is_some_and(|names| names.contains(name))
+}
+
+/// Read `CONFIG_FILE` (or `config.toml`, if present), apply the environment
+/// and check the required settings.
+pub fn load_config() -> Result<Config, ConfigError> {
+    load_config_with(|name| std::env::var(name).ok())
+}
+
+/// `load_config` with environment variables looked up by `lookup` instead.
+pub fn load_config_with(lookup: impl Fn(&str) -> Option<String>) -> Result<Config, ConfigError> {
+    let (path, explicit) = match lookup("CONFIG_FILE") {
+        Some(path) => (path, true),
+        None => (DEFAULT_CONFIG_FILE.to_string(), false),
+    };
+    let mut problems = Vec::new();
+
+    let mut config = match std::fs::read_to_string(&path) {
+        Ok(contents) => Config::from_toml_str(&contents).unwrap_or_else(|e| {
+            problems.push(format!("{}: {}", path, e.message()));
+            Config::default()
+        }),
+        // The default file is optional; one named in CONFIG_FILE is not
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !explicit => Config::default(),
+        Err(e) => {
+            problems.push(format!("Could not read {}: {}", path, e));
+            Config::default()
+        }
+    };
+
+    problems.extend(config.apply_env(lookup));
+    problems.extend(config.validate());
+
+    if problems.is_empty() {
+        Ok(config)
+    } else {
+        Err(ConfigError { problems })
+    }
+}
+
+/// Applies environment variables to config fields, noting unparsable values.
+struct EnvOverrides<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    problems: Vec<String>,
+}
+
+impl EnvOverrides<'_> {
+    /// A variable's trimmed value; empty counts as unset.
+    fn get(&self, name: &str) -> Option<String> {
+        (self.lookup)(name)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn string(&mut self, name: &str, target: &mut Option<String>) {
+        if let Some(value) = self.get(name) {
+            *target = Some(value);
+        }
+    }
+
+    fn parse<T: FromStr>(&mut self, name: &str, target: &mut T) {
+        let Some(value) = self.get(name) else {
+            return;
+        };
+        match value.parse() {
+            Ok(parsed) => *target = parsed,
+            Err(_) => self
+                .problems
+                .push(format!("{} has an invalid value: {:?}", name, value)),
+        }
+    }
+
+    fn parse_opt<T: FromStr>(&mut self, name: &str, target: &mut Option<T>) {
+        let Some(value) = self.get(name) else {
+            return;
+        };
+        match value.parse() {
+            Ok(parsed) => *target = Some(parsed),
+            Err(_) => self
+                .problems
+                .push(format!("{} has an invalid value: {:?}", name, value)),
+        }
+    }
+
+    /// Comma-separated values; one bad entry rejects the whole variable.
+    fn list<T: FromStr>(&mut self, name: &str, target: &mut Vec<T>) {
+        let Some(value) = self.get(name) else {
+            return;
+        };
+        let parsed: Result<Vec<T>, _> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::parse)
+            .collect();
+        match parsed {
+            Ok(parsed) => *target = parsed,
+            Err(_) => self
+                .problems
+                .push(format!("{} has an invalid value: {:?}", name, value)),
+        }
+    }
+
+    /// Comma-separated `key=value` pairs; one bad entry rejects the whole
+    /// variable.
+    fn map<T: FromStr>(&mut self, name: &str, target: &mut BTreeMap<String, T>) {
+        let Some(value) = self.get(name) else {
+            return;
+        };
+        let parsed: Option<BTreeMap<String, T>> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                let (key, value) = item.split_once('=')?;
+                let key = key.trim();
+                if key.is_empty() {
+                    return None;
+                }
+                Some((key.to_string(), value.trim().parse().ok()?))
+            })
+            .collect();
+        match parsed {
+            Some(parsed) => *target = parsed,
+            None => self
+                .problems
+                .push(format!("{} has an invalid value: {:?}", name, value)),
+        }
+    }
+
+    /// `true` (any case) turns a flag on, anything else turns it off.
+    fn flag(&mut self, name: &str, target: &mut bool) {
+        if let Some(value) = self.get(name) {
+            *target = value.eq_ignore_ascii_case("true");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const SAMPLE: &str = r#"
+        [discord]
+        token = "file-token"
+
+        [storage]
+        backend = "memory"
+        data_dir = "/var/lib/bot"
+
+        [ai]
+        provider = "gemini"
+        gemini_api_key = "gemini-key"
+        gemini_model = "gemini-3-flash"
+        max_history = 30
+
+        [leveling]
+        daily_base_xp = 40
+        rank_refresh_interval_secs = 600
+
+        [github]
+        poll_interval_secs = 900
+        commit_stats = true
+
+        [economy]
+        message_reward_chance = 0.1
+        announce_message_rewards = true
+
+        [cooldowns.user_secs]
+        leaderboard = 20
+    "#;
+
+    #[test]
+    fn test_sample_config_deserializes() {
+        let config = Config::from_toml_str(SAMPLE).unwrap();
+
+        assert_eq!(config.discord.token.as_deref(), Some("file-token"));
+        assert!(config.in_memory());
+        assert_eq!(config.storage.data_dir, "/var/lib/bot");
+        // Unset fields keep their defaults
+        assert_eq!(config.storage.locales_dir, "locales");
+        assert_eq!(config.ai_provider(), Some(AiProviderKind::Gemini));
+        assert_eq!(config.ai.gemini_model, "gemini-3-flash");
+        assert_eq!(config.ai.max_history, 30);
+        assert_eq!(config.ai.context_token_budget, 8000);
+        assert!(config
+            .ai
+            .openrouter_no_reasoning_models
+            .contains(&"meta-llama/".to_string()));
+        assert!(config.ai.history_skip_bots && config.ai.history_skip_commands);
+        assert_eq!(config.ai.max_concurrent_requests, 4);
+        assert_eq!(config.leveling.daily_base_xp, Some(40));
+        assert_eq!(config.leveling.daily_streak_bonus_cap, None);
+        assert_eq!(config.github.poll_interval_secs, 900);
+        assert!(config.github.commit_stats);
+        assert_eq!(config.github.org_refresh_interval_secs, 86400);
+        assert!(config.economy.message_rewards);
+        assert_eq!(config.economy.message_reward_chance, Some(0.1));
+        assert!(config.economy.announce_message_rewards);
+        assert_eq!(
+            config.cooldowns.user_secs,
+            BTreeMap::from([("leaderboard".to_string(), 20)])
+        );
+        assert_eq!(config.cooldowns.channel_secs.get("leaderboard"), Some(&3));
+        assert!(config.validate().is_empty());
+
+        // The documented example stays loadable
+        assert!(Config::from_toml_str(include_str!("../../../config.example.toml")).is_ok());
+        // Typos are errors rather than silently ignored
+        assert!(Config::from_toml_str("[github]\npoll_interval = 5").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides_file_and_missing_values_are_collected() {
+        let mut config = Config::from_toml_str(SAMPLE).unwrap();
+        let env: HashMap<&str, &str> = HashMap::from([
+            ("DISCORD_TOKEN", "env-token"),
+            ("GITHUB_POLL_INTERVAL_SECS", "1200"),
+            ("AI_MAX_HISTORY", "lots"),
+            ("AI_PROVIDER", "openrouter"),
+            ("BOT_OWNER_IDS", "123456789012345678, 234567890123456789"),
+            ("COMMAND_USER_COOLDOWNS", "leaderboard=15, ai responses=5"),
+            ("COMMAND_CHANNEL_COOLDOWNS", "leaderboard=soon"),
+            ("OPENROUTER_NO_REASONING_MODELS", "openai/gpt-4o, qwen/"),
+            ("AI_MAX_CONCURRENT_REQUESTS", "8"),
+            ("FEEDBACK_CHANNEL_ID", "345678901234567890"),
+            ("GOOGLE_DOCS_TIMEOUT_SECS", "45"),
+            ("COMMAND_PREFIX", " ? "),
+            ("AI_SYSTEM_PROMPT", "Be brief."),
+            ("AI_REASONING_ENABLED", "false"),
+            ("AI_REASONING_EFFORT", "low"),
+            ("OPENROUTER_REASONING_EFFORT", "high"),
+        ]);
+
+        let problems = config.apply_env(|name| env.get(name).map(|v| v.to_string()));
+        assert_eq!(config.discord.token.as_deref(), Some("env-token"));
+        assert_eq!(
+            config.discord.owner_ids,
+            vec![123456789012345678, 234567890123456789]
+        );
+        assert_eq!(config.github.poll_interval_secs, 1200);
+        assert_eq!(config.discord.feedback_channel_id, Some(345678901234567890));
+        assert_eq!(config.ai.max_concurrent_requests, 8);
+        assert_eq!(config.ai.google_docs_timeout_secs, 45);
+        assert_eq!(config.discord.command_prefix, "?");
+        // OpenRouter is the provider, so its reasoning variables win
+        assert_eq!(config.ai.reasoning_enabled, Some(false));
+        assert_eq!(config.ai.reasoning_effort.as_deref(), Some("high"));
+        #[cfg(feature = "ai")]
+        assert_eq!(
+            config.ai_settings().unwrap().system_prompt,
+            "Be brief.".to_string()
+        );
+        assert_eq!(
+            config.ai.openrouter_no_reasoning_models,
+            vec!["openai/gpt-4o", "qwen/"]
+        );
+        assert_eq!(
+            config.cooldowns.user_secs,
+            BTreeMap::from([
+                ("ai responses".to_string(), 5),
+                ("leaderboard".to_string(), 15)
+            ])
+        );
+        // A bad value keeps the previous map
+        assert_eq!(config.cooldowns.channel_secs.get("leaderboard"), Some(&3));
+        assert_eq!(
+            problems,
+            vec![
+                "AI_MAX_HISTORY has an invalid value: \"lots\"".to_string(),
+                "COMMAND_CHANNEL_COOLDOWNS has an invalid value: \"leaderboard=soon\"".to_string()
+            ]
+        );
+
+        // Switching to OpenRouter without a key, and no token at all
+        config.discord.token = None;
+        let problems = config.validate();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("DISCORD_TOKEN"));
+        assert!(problems[1].starts_with("OPENROUTER_API_KEY"));
+
+        // Without AI_PROVIDER a missing key just means no AI
+        config.ai.provider = None;
+        assert_eq!(config.validate().len(), 1);
+        assert_eq!(config.ai_provider(), Some(AiProviderKind::Gemini));
+        config.ai.gemini_api_key = None;
+        assert_eq!(config.ai_provider(), None);
+
+        config.ai.google_docs_timeout_secs = 0;
+        assert_eq!(
+            config.validate()[1],
+            "GOOGLE_DOCS_TIMEOUT_SECS must be at least 1"
+        );
+        config.ai.google_docs_timeout_secs = 30;
+
+        #[cfg(feature = "economy")]
+        {
+            config.economy.message_reward_min = Some(10);
+            config.economy.message_reward_max = Some(5);
+            assert!(config.validate()[1].starts_with("MESSAGE_COIN_* settings are invalid"));
+            config.economy.message_reward_max = None;
+            config.economy.message_reward_min = None;
+        }
+
+        config.leveling.voice_pause_flags = Some("self_deaf, muted".to_string());
+        assert_eq!(
+            config.validate()[1],
+            "VOICE_PAUSE_FLAGS is invalid: unknown voice flag \"muted\" \
+             (expected self_mute, self_deaf, mute or deaf)"
+        );
+        config.leveling.voice_pause_flags = None;
+
+        config.leveling.daily_streak_bonus_step = Some(10);
+        config.leveling.daily_streak_bonus_cap = Some(5);
+        assert_eq!(
+            config.validate()[1],
+            "DAILY_STREAK_BONUS_* settings are invalid: \
+             daily streak bonus cap (5) is below the step (10)"
+        );
+        config.leveling.daily_streak_bonus_cap = None;
+        config.leveling.daily_streak_bonus_step = None;
+
+        config.discord.owner_ids.push(0);
+        assert_eq!(config.validate()[1], "BOT_OWNER_IDS must not contain 0");
+        config.discord.owner_ids.pop();
+
+        config.discord.command_prefix = " ".to_string();
+        assert_eq!(config.validate()[1], "COMMAND_PREFIX must not be empty");
+        config.discord.command_prefix = "!".to_string();
+
+        #[cfg(feature = "ai")]
+        {
+            config.ai.system_prompt_file = Some("missing/system_prompt.txt".to_string());
+            assert!(config.validate()[1].starts_with("AI_SYSTEM_PROMPT_FILE: failed to read"));
+        }
+    }
+}
@@ -0,0 +1,305 @@
+// In-memory implementations of CoinStore and InventoryStore.
+//
+// Used when `STORAGE_BACKEND=memory` (quick local testing, ephemeral deployments)
+// and in tests. Behaves like the SQLite stores but nothing survives a restart.
+
+use crate::core::economy::{
+    CoinStore, EconomyError, InventoryItem, InventoryStore, ItemId, Transaction, Wallet,
+    WealthRanking,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use std::sync::Mutex;
+
+/// Wallets keyed by (user_id, guild_id) plus a transaction log.
+pub struct InMemoryCoinStore {
+    wallets: DashMap<(u64, u64), Wallet>,
+    transactions: Mutex<Vec<Transaction>>,
+}
+
+impl InMemoryCoinStore {
+    pub fn new() -> Self {
+        Self {
+            wallets: DashMap::new(),
+            transactions: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn empty_wallet(user_id: u64, guild_id: u64) -> Wallet {
+        Wallet {
+            user_id,
+            guild_id,
+            balance: 0,
+            last_daily: None,
+            total_earned: 0,
+        }
+    }
+}
+
+impl Default for InMemoryCoinStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl CoinStore for InMemoryCoinStore {
+    async fn get_wallet(&self, user_id: u64, guild_id: u64) -> Result<Wallet, EconomyError> {
+        Ok(self
+            .wallets
+            .entry((user_id, guild_id))
+            .or_insert_with(|| Self::empty_wallet(user_id, guild_id))
+            .clone())
+    }
+
+    async fn update_balance(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        new_balance: i64,
+    ) -> Result<(), EconomyError> {
+        self.wallets
+            .entry((user_id, guild_id))
+            .or_insert_with(|| Self::empty_wallet(user_id, guild_id))
+            .balance = new_balance;
+        Ok(())
+    }
+
+    async fn update_last_daily(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), EconomyError> {
+        self.wallets
+            .entry((user_id, guild_id))
+            .or_insert_with(|| Self::empty_wallet(user_id, guild_id))
+            .last_daily = Some(timestamp);
+        Ok(())
+    }
+
+    async fn add_coins(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        amount: i64,
+    ) -> Result<(), EconomyError> {
+        let mut wallet = self
+            .wallets
+            .entry((user_id, guild_id))
+            .or_insert_with(|| Self::empty_wallet(user_id, guild_id));
+        wallet.balance += amount;
+        wallet.total_earned += amount;
+        Ok(())
+    }
+
+    async fn log_transaction(&self, transaction: Transaction) -> Result<(), EconomyError> {
+        self.transactions
+            .lock()
+            .map_err(|e| EconomyError::StoreError(e.to_string(), None))?
+            .push(transaction);
+        Ok(())
+    }
+
+    async fn get_transactions(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, EconomyError> {
+        let transactions = self
+            .transactions
+            .lock()
+            .map_err(|e| EconomyError::StoreError(e.to_string(), None))?;
+
+        // Newest first, like the SQLite store
+        Ok(transactions
+            .iter()
+            .rev()
+            .filter(|t| t.user_id == user_id && t.guild_id == guild_id)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_top_wallets(
+        &self,
+        guild_id: u64,
+        ranking: WealthRanking,
+        limit: usize,
+    ) -> Result<Vec<Wallet>, EconomyError> {
+        let mut wallets: Vec<Wallet> = self
+            .wallets
+            .iter()
+            .filter(|entry| entry.guild_id == guild_id && ranking.value(entry.value()) > 0)
+            .map(|entry| entry.value().clone())
+            .collect();
+        // Same order as the SQLite store: highest first, then by user ID
+        wallets.sort_by_key(|w| (std::cmp::Reverse(ranking.value(w)), w.user_id));
+        wallets.truncate(limit);
+        Ok(wallets)
+    }
+}
+
+/// Inventories keyed by (user_id, guild_id).
+pub struct InMemoryInventoryStore {
+    items: DashMap<(u64, u64), Vec<InventoryItem>>,
+}
+
+impl InMemoryInventoryStore {
+    pub fn new() -> Self {
+        Self {
+            items: DashMap::new(),
+        }
+    }
+}
+
+impl Default for InMemoryInventoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl InventoryStore for InMemoryInventoryStore {
+    async fn add_item(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: ItemId,
+    ) -> Result<(), EconomyError> {
+        self.items
+            .entry((user_id, guild_id))
+            .or_default()
+            .push(InventoryItem {
+                user_id,
+                guild_id,
+                item_id,
+                acquired_at: Utc::now(),
+            });
+        Ok(())
+    }
+
+    async fn remove_item(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+    ) -> Result<bool, EconomyError> {
+        let Some(mut items) = self.items.get_mut(&(user_id, guild_id)) else {
+            return Ok(false);
+        };
+
+        // Remove one instance of the item
+        match items.iter().position(|item| &item.item_id == item_id) {
+            Some(index) => {
+                items.remove(index);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn has_item(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+    ) -> Result<bool, EconomyError> {
+        Ok(self.get_item_count(user_id, guild_id, item_id).await? > 0)
+    }
+
+    async fn get_item_count(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+    ) -> Result<i64, EconomyError> {
+        Ok(self
+            .items
+            .get(&(user_id, guild_id))
+            .map(|items| items.iter().filter(|item| &item.item_id == item_id).count() as i64)
+            .unwrap_or(0))
+    }
+
+    async fn get_inventory(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Vec<InventoryItem>, EconomyError> {
+        Ok(self
+            .items
+            .get(&(user_id, guild_id))
+            .map(|items| items.clone())
+            .unwrap_or_default())
+    }
+
+    async fn transfer_items(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+        quantity: u32,
+    ) -> Result<(), EconomyError> {
+        // Take the items out first and release the sender's entry before
+        // touching the recipient's (both may live in the same DashMap shard)
+        let moved: Vec<InventoryItem> = {
+            let mut sender = self.items.entry((from_user_id, guild_id)).or_default();
+            let available = sender
+                .iter()
+                .filter(|item| &item.item_id == item_id)
+                .count();
+            if available < quantity as usize {
+                return Err(EconomyError::NotEnoughItems {
+                    requested: quantity,
+                    available: available as i64,
+                });
+            }
+
+            let mut moved = Vec::with_capacity(quantity as usize);
+            sender.retain(|item| {
+                if moved.len() < quantity as usize && &item.item_id == item_id {
+                    moved.push(item.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            moved
+        };
+
+        let now = Utc::now();
+        self.items
+            .entry((to_user_id, guild_id))
+            .or_default()
+            .extend(moved.into_iter().map(|item| InventoryItem {
+                user_id: to_user_id,
+                acquired_at: now,
+                ..item
+            }));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_inventory_add_and_consume() {
+        let store = InMemoryInventoryStore::new();
+        let item = ItemId::DailyStreakSaver;
+
+        assert!(!store.has_item(1, 2, &item).await.unwrap());
+        store.add_item(1, 2, item.clone()).await.unwrap();
+        store.add_item(1, 2, item.clone()).await.unwrap();
+        assert_eq!(store.get_item_count(1, 2, &item).await.unwrap(), 2);
+
+        assert!(store.remove_item(1, 2, &item).await.unwrap());
+        assert_eq!(store.get_item_count(1, 2, &item).await.unwrap(), 1);
+        // Other guilds are separate
+        assert!(!store.remove_item(1, 3, &item).await.unwrap());
+    }
+}
@@ -1,7 +1,9 @@
-// Economy infrastructure - SQLite storage implementation
+// Economy infrastructure - SQLite and in-memory storage implementations
 
+mod in_memory;
 mod sqlite_coin_store;
 mod sqlite_inventory_store;
 
+pub use in_memory::{InMemoryCoinStore, InMemoryInventoryStore};
 pub use sqlite_coin_store::SqliteCoinStore;
 pub use sqlite_inventory_store::SqliteInventoryStore;
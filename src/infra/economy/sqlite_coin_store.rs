@@ -1,6 +1,6 @@
 // SQLite implementation of the CoinStore trait
 
-use crate::core::economy::{CoinStore, EconomyError, Transaction, Wallet};
+use crate::core::economy::{CoinStore, EconomyError, Transaction, Wallet, WealthRanking};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
@@ -92,6 +92,25 @@ impl SqliteCoinStore {
         .execute(&self.pool)
         .await?;
 
+        // Indexes for the coins leaderboard (`/richest`)
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_wallets_guild_balance
+            ON wallets(guild_id, balance DESC)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_wallets_guild_total_earned
+            ON wallets(guild_id, total_earned DESC)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create inventory table
         sqlx::query(
             r#"
@@ -135,21 +154,10 @@ impl CoinStore for SqliteCoinStore {
         .bind(guild_id as i64)
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         if let Some(row) = row {
-            let last_daily: Option<String> = row.get("last_daily");
-            let last_daily = last_daily
-                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
-                .map(|dt| dt.with_timezone(&Utc));
-
-            Ok(Wallet {
-                user_id: row.get::<i64, _>("user_id") as u64,
-                guild_id: row.get::<i64, _>("guild_id") as u64,
-                balance: row.get::<i64, _>("balance"),
-                last_daily,
-                total_earned: row.get::<i64, _>("total_earned"),
-            })
+            Ok(wallet_from_row(&row))
         } else {
             // Create new wallet if it doesn't exist
             sqlx::query(
@@ -162,7 +170,7 @@ impl CoinStore for SqliteCoinStore {
             .bind(guild_id as i64)
             .execute(&self.pool)
             .await
-            .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+            .map_err(EconomyError::store)?;
 
             Ok(Wallet {
                 user_id,
@@ -192,7 +200,7 @@ impl CoinStore for SqliteCoinStore {
         .bind(guild_id as i64)
         .execute(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         Ok(())
     }
@@ -215,7 +223,7 @@ impl CoinStore for SqliteCoinStore {
         .bind(guild_id as i64)
         .execute(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         Ok(())
     }
@@ -244,7 +252,7 @@ impl CoinStore for SqliteCoinStore {
         .bind(guild_id as i64)
         .execute(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         Ok(())
     }
@@ -263,7 +271,7 @@ impl CoinStore for SqliteCoinStore {
         .bind(transaction.timestamp.to_rfc3339())
         .execute(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         Ok(())
     }
@@ -288,7 +296,7 @@ impl CoinStore for SqliteCoinStore {
         .bind(limit as i64)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         let transactions = rows
             .iter()
@@ -310,4 +318,56 @@ impl CoinStore for SqliteCoinStore {
 
         Ok(transactions)
     }
+
+    async fn get_top_wallets(
+        &self,
+        guild_id: u64,
+        ranking: WealthRanking,
+        limit: usize,
+    ) -> Result<Vec<Wallet>, EconomyError> {
+        let query = match ranking {
+            WealthRanking::Balance => {
+                r#"
+                SELECT user_id, guild_id, balance, last_daily, total_earned
+                FROM wallets
+                WHERE guild_id = ? AND balance > 0
+                ORDER BY balance DESC, user_id ASC
+                LIMIT ?
+                "#
+            }
+            WealthRanking::TotalEarned => {
+                r#"
+                SELECT user_id, guild_id, balance, last_daily, total_earned
+                FROM wallets
+                WHERE guild_id = ? AND total_earned > 0
+                ORDER BY total_earned DESC, user_id ASC
+                LIMIT ?
+                "#
+            }
+        };
+
+        let rows = sqlx::query(query)
+            .bind(guild_id as i64)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(EconomyError::store)?;
+
+        Ok(rows.iter().map(wallet_from_row).collect())
+    }
+}
+
+fn wallet_from_row(row: &sqlx::sqlite::SqliteRow) -> Wallet {
+    let last_daily: Option<String> = row.get("last_daily");
+    let last_daily = last_daily
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc));
+
+    Wallet {
+        user_id: row.get::<i64, _>("user_id") as u64,
+        guild_id: row.get::<i64, _>("guild_id") as u64,
+        balance: row.get::<i64, _>("balance"),
+        last_daily,
+        total_earned: row.get::<i64, _>("total_earned"),
+    }
 }
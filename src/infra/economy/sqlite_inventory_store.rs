@@ -37,7 +37,7 @@ impl InventoryStore for SqliteInventoryStore {
         .bind(Utc::now().to_rfc3339())
         .execute(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         Ok(())
     }
@@ -61,7 +61,7 @@ impl InventoryStore for SqliteInventoryStore {
         .bind(item_id.as_str())
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         if let Some(row) = row {
             let id: i64 = row.get("id");
@@ -71,7 +71,7 @@ impl InventoryStore for SqliteInventoryStore {
                 .bind(id)
                 .execute(&self.pool)
                 .await
-                .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+                .map_err(EconomyError::store)?;
 
             Ok(true)
         } else {
@@ -106,7 +106,7 @@ impl InventoryStore for SqliteInventoryStore {
         .bind(item_id.as_str())
         .fetch_one(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         Ok(row.get::<i64, _>("count"))
     }
@@ -128,7 +128,7 @@ impl InventoryStore for SqliteInventoryStore {
         .bind(guild_id as i64)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| EconomyError::StoreError(e.to_string()))?;
+        .map_err(EconomyError::store)?;
 
         let items = rows
             .iter()
@@ -152,4 +152,108 @@ impl InventoryStore for SqliteInventoryStore {
 
         Ok(items)
     }
+
+    async fn transfer_items(
+        &self,
+        from_user_id: u64,
+        to_user_id: u64,
+        guild_id: u64,
+        item_id: &ItemId,
+        quantity: u32,
+    ) -> Result<(), EconomyError> {
+        let store_error = EconomyError::store::<sqlx::Error>;
+
+        // Count and move inside one transaction: the rows change owner rather
+        // than being deleted and re-created, so a crash either moves all of
+        // them or none
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+
+        let available: i64 = sqlx::query(
+            r#"
+            SELECT COUNT(*) as count FROM inventory
+            WHERE user_id = ? AND guild_id = ? AND item_id = ?
+            "#,
+        )
+        .bind(from_user_id as i64)
+        .bind(guild_id as i64)
+        .bind(item_id.as_str())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(store_error)?
+        .get("count");
+
+        if available < i64::from(quantity) {
+            return Err(EconomyError::NotEnoughItems {
+                requested: quantity,
+                available,
+            });
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE inventory
+            SET user_id = ?, acquired_at = ?
+            WHERE id IN (
+                SELECT id FROM inventory
+                WHERE user_id = ? AND guild_id = ? AND item_id = ?
+                ORDER BY id
+                LIMIT ?
+            )
+            "#,
+        )
+        .bind(to_user_id as i64)
+        .bind(Utc::now().to_rfc3339())
+        .bind(from_user_id as i64)
+        .bind(guild_id as i64)
+        .bind(item_id.as_str())
+        .bind(i64::from(quantity))
+        .execute(&mut *tx)
+        .await
+        .map_err(store_error)?;
+
+        tx.commit().await.map_err(store_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::economy::SqliteCoinStore;
+
+    #[tokio::test]
+    async fn test_transfer_moves_all_or_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("economy.db");
+        // The coin store creates the shared schema, inventory table included
+        SqliteCoinStore::new(db_path.to_str().unwrap())
+            .await
+            .unwrap();
+        let pool = SqlitePool::connect(&format!("sqlite://{}", db_path.display()))
+            .await
+            .unwrap();
+        let store = SqliteInventoryStore::new(pool);
+        let potion = ItemId::XpBoostPotion;
+
+        for _ in 0..3 {
+            store.add_item(1, 1, potion.clone()).await.unwrap();
+        }
+        store.add_item(2, 1, potion.clone()).await.unwrap();
+
+        store.transfer_items(1, 2, 1, &potion, 2).await.unwrap();
+        let result = store.transfer_items(1, 2, 1, &potion, 5).await;
+        assert!(matches!(
+            result,
+            Err(EconomyError::NotEnoughItems {
+                requested: 5,
+                available: 1
+            })
+        ));
+
+        let count = |user_id| store.get_item_count(user_id, 1, &potion);
+        assert_eq!(count(1).await.unwrap(), 1);
+        assert_eq!(count(2).await.unwrap(), 3);
+        // Other guilds are separate
+        assert_eq!(store.get_item_count(2, 2, &potion).await.unwrap(), 0);
+    }
 }
@@ -0,0 +1,148 @@
+// Every `/feedback` entry ever sent, in an append-only `feedback` table.
+// The guild ID is NULL for feedback sent from a DM. Entries are never edited
+// or deleted; owners read the newest ones back with `recent_feedback`.
+
+use crate::core::feedback::{Feedback, FeedbackStore};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Row, Sqlite};
+use std::error::Error;
+use tokio::sync::RwLock;
+
+pub struct SqliteFeedbackStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteFeedbackStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS feedback (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id INTEGER,
+                user_id INTEGER NOT NULL,
+                message TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FeedbackStore for SqliteFeedbackStore {
+    async fn add_feedback(&self, feedback: &Feedback) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            "INSERT INTO feedback (guild_id, user_id, message, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(feedback.guild_id.map(|id| id as i64))
+        .bind(feedback.user_id as i64)
+        .bind(&feedback.message)
+        .bind(feedback.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn recent_feedback(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<Feedback>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query(
+            "SELECT guild_id, user_id, message, created_at FROM feedback ORDER BY id DESC LIMIT ?",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let created_at: String = row.get("created_at");
+            entries.push(Feedback {
+                guild_id: row.get::<Option<i64>, _>("guild_id").map(|id| id as u64),
+                user_id: row.get::<i64, _>("user_id") as u64,
+                message: row.get("message"),
+                created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+            });
+        }
+        Ok(entries)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryFeedbackStore {
+    entries: RwLock<Vec<Feedback>>,
+}
+
+impl InMemoryFeedbackStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl FeedbackStore for InMemoryFeedbackStore {
+    async fn add_feedback(&self, feedback: &Feedback) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.entries.write().await.push(feedback.clone());
+        Ok(())
+    }
+
+    async fn recent_feedback(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<Feedback>, Box<dyn Error + Send + Sync>> {
+        let entries = self.entries.read().await;
+        Ok(entries.iter().rev().take(limit).cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_feedback_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = SqliteFeedbackStore::new(pool);
+        store.migrate().await.unwrap();
+
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let first = Feedback {
+            guild_id: Some(1),
+            user_id: 10,
+            message: "more achievements please".to_string(),
+            created_at: now,
+        };
+        let second = Feedback {
+            guild_id: None,
+            user_id: 11,
+            message: "DM feedback".to_string(),
+            created_at: now + Duration::minutes(1),
+        };
+        store.add_feedback(&first).await.unwrap();
+        store.add_feedback(&second).await.unwrap();
+
+        assert_eq!(
+            store.recent_feedback(10).await.unwrap(),
+            vec![second.clone(), first]
+        );
+        assert_eq!(store.recent_feedback(1).await.unwrap(), vec![second]);
+    }
+}
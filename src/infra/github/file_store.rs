@@ -2,18 +2,23 @@ use std::path::{Path, PathBuf};
 
 use async_trait::async_trait;
 use tokio::fs;
+use tokio::sync::Mutex;
 
 use crate::core::github::{GithubConfig, GithubConfigStore, GithubError};
+use crate::infra::json_file::write_atomic;
 
 /// Simple JSON file store for GitHub tracking configuration.
 pub struct GithubFileStore {
     path: PathBuf,
+    /// Serializes writes so concurrent saves can't interleave on disk.
+    write_lock: Mutex<()>,
 }
 
 impl GithubFileStore {
     pub fn new(path: impl AsRef<Path>) -> Self {
         Self {
             path: path.as_ref().to_path_buf(),
+            write_lock: Mutex::new(()),
         }
     }
 }
@@ -27,24 +32,18 @@ impl GithubConfigStore for GithubFileStore {
 
         let text = fs::read_to_string(&self.path)
             .await
-            .map_err(|e| GithubError::Store(e.to_string()))?;
+            .map_err(GithubError::store)?;
 
-        let config: GithubConfig =
-            serde_json::from_str(&text).map_err(|e| GithubError::Store(e.to_string()))?;
+        let config: GithubConfig = serde_json::from_str(&text).map_err(GithubError::store)?;
         Ok(config)
     }
 
     async fn save(&self, config: &GithubConfig) -> Result<(), GithubError> {
-        if let Some(parent) = self.path.parent() {
-            fs::create_dir_all(parent)
-                .await
-                .map_err(|e| GithubError::Store(e.to_string()))?;
-        }
+        let text = serde_json::to_string_pretty(config).map_err(GithubError::store)?;
 
-        let text =
-            serde_json::to_string_pretty(config).map_err(|e| GithubError::Store(e.to_string()))?;
-        fs::write(&self.path, text)
+        let _guard = self.write_lock.lock().await;
+        write_atomic(&self.path, text.as_bytes())
             .await
-            .map_err(|e| GithubError::Store(e.to_string()))
+            .map_err(GithubError::store)
     }
 }
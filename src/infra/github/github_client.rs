@@ -1,27 +1,47 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::{Client, StatusCode};
+use reqwest::{Client, IntoUrl, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::time::Duration;
 
-use crate::core::github::{Commit, GithubClient, GithubError, Issue, IssueState};
+use crate::core::github::{Commit, CommitStats, GithubClient, GithubError, Issue, IssueState};
+use crate::infra::http;
+
+/// REST API version the response types are written against.
+const API_VERSION: &str = "2022-11-28";
+
+/// Most pages fetched for one listing while catching up after downtime.
+const MAX_PAGES: usize = 10;
 
 /// Minimal GitHub REST API client. It deliberately exposes only the calls the core layer needs.
 pub struct GithubApiClient {
     client: Client,
+    /// User-Agent, Accept, API version and Authorization headers sent with
+    /// every request. GitHub rejects requests without a User-Agent.
+    headers: HeaderMap,
     base_url: String,
+    timeout: Duration,
 }
 
 impl GithubApiClient {
-    pub fn new(token: Option<String>) -> Result<Self, GithubError> {
+    /// `client` is the bot's shared HTTP client (`http::shared_client`).
+    /// Requests give up after `timeout` with `GithubError::Timeout`.
+    pub fn new(
+        client: Client,
+        token: Option<String>,
+        timeout: Duration,
+    ) -> Result<Self, GithubError> {
         let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", HeaderValue::from_static(http::USER_AGENT));
         headers.insert(
             "Accept",
             HeaderValue::from_static("application/vnd.github+json"),
         );
         headers.insert(
-            "User-Agent",
-            HeaderValue::from_static("RustDiscordBot/1.0"),
+            "X-GitHub-Api-Version",
+            HeaderValue::from_static(API_VERSION),
         );
         if let Some(token) = token {
             headers.insert(
@@ -31,17 +51,111 @@ impl GithubApiClient {
             );
         }
 
-        let client = Client::builder()
-            .default_headers(headers)
-            .build()
-            .map_err(|e| GithubError::Api(e.to_string()))?;
-
         Ok(Self {
             client,
+            headers,
             base_url: "https://api.github.com".to_string(),
+            timeout,
         })
     }
 
+    /// Point the client at a mock server.
+    #[cfg(test)]
+    fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// A GET request with the GitHub headers and timeout.
+    fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.client
+            .get(url)
+            .headers(self.headers.clone())
+            .timeout(self.timeout)
+    }
+
+    /// Fetch `request` and follow its `Link: rel="next"` pages until `is_done`
+    /// returns true for a page, there are no more pages, or `MAX_PAGES` is
+    /// reached. A missing repository yields an empty list.
+    async fn get_pages<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+        what: &str,
+        mut is_done: impl FnMut(&[T]) -> bool,
+    ) -> Result<Vec<T>, GithubError> {
+        let mut items = Vec::new();
+        let mut request = request;
+        for page in 1..=MAX_PAGES {
+            let resp = request.send().await.map_err(|e| self.request_error(e))?;
+
+            if resp.status() == StatusCode::NOT_FOUND {
+                return Ok(Vec::new());
+            }
+            self.handle_rate_limit(resp.status()).await?;
+            if !resp.status().is_success() {
+                return Err(GithubError::Api(format!(
+                    "Failed to fetch {}: {}",
+                    what,
+                    resp.status()
+                )));
+            }
+
+            let next = next_page_url(resp.headers());
+            let batch: Vec<T> = resp.json().await.map_err(|e| self.request_error(e))?;
+            let done = is_done(&batch);
+            items.extend(batch);
+
+            match next {
+                Some(url) if !done => {
+                    if page == MAX_PAGES {
+                        tracing::warn!("Stopped after {} pages of GitHub {}", MAX_PAGES, what);
+                        break;
+                    }
+                    request = self.get(url);
+                }
+                _ => break,
+            }
+        }
+        Ok(items)
+    }
+
+    /// Issues by last update, newest first. With `since`, every page updated
+    /// after it is fetched so nothing is skipped after downtime; without it,
+    /// only the first page.
+    async fn list_issues(
+        &self,
+        owner: &str,
+        repo: &str,
+        label: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        what: &str,
+    ) -> Result<Vec<ApiIssue>, GithubError> {
+        let url = format!("{}/repos/{}/{}/issues", self.base_url, owner, repo);
+        let mut request = self.get(url).query(&[
+            ("state", "all"),
+            ("sort", "updated"),
+            ("direction", "desc"),
+            ("per_page", "30"),
+        ]);
+        if let Some(label) = label {
+            request = request.query(&[("labels", label)]);
+        }
+        if let Some(since) = since {
+            request = request.query(&[("since", &since.to_rfc3339())]);
+        }
+
+        self.get_pages(request, what, |_: &[ApiIssue]| since.is_none())
+            .await
+    }
+
+    fn request_error(&self, error: reqwest::Error) -> GithubError {
+        if error.is_timeout() {
+            GithubError::Timeout(self.timeout)
+        } else {
+            GithubError::Api(error.to_string())
+        }
+    }
+
     fn parse_datetime(value: Option<String>) -> Option<DateTime<Utc>> {
         value
             .as_deref()
@@ -94,12 +208,11 @@ impl GithubClient for GithubApiClient {
 
         for page in 1..=5 {
             let resp = self
-                .client
                 .get(&url)
                 .query(&[("per_page", "100"), ("page", &page.to_string())])
                 .send()
                 .await
-                .map_err(|e| GithubError::Api(e.to_string()))?;
+                .map_err(|e| self.request_error(e))?;
 
             if resp.status() == StatusCode::NOT_FOUND {
                 return Ok(Vec::new());
@@ -107,10 +220,7 @@ impl GithubClient for GithubApiClient {
             self.handle_rate_limit(resp.status()).await?;
 
             if resp.status().is_success() {
-                let repos: Vec<ApiRepo> = resp
-                    .json()
-                    .await
-                    .map_err(|e| GithubError::Api(e.to_string()))?;
+                let repos: Vec<ApiRepo> = resp.json().await.map_err(|e| self.request_error(e))?;
                 if repos.is_empty() {
                     break;
                 }
@@ -139,12 +249,11 @@ impl GithubClient for GithubApiClient {
     async fn list_branches(&self, owner: &str, repo: &str) -> Result<Vec<String>, GithubError> {
         let url = format!("{}/repos/{}/{}/branches", self.base_url, owner, repo);
         let resp = self
-            .client
             .get(url)
             .query(&[("per_page", "100")])
             .send()
             .await
-            .map_err(|e| GithubError::Api(e.to_string()))?;
+            .map_err(|e| self.request_error(e))?;
 
         if resp.status() == StatusCode::NOT_FOUND {
             return Ok(Vec::new());
@@ -152,10 +261,7 @@ impl GithubClient for GithubApiClient {
         self.handle_rate_limit(resp.status()).await?;
 
         if resp.status().is_success() {
-            let branches: Vec<ApiBranch> = resp
-                .json()
-                .await
-                .map_err(|e| GithubError::Api(e.to_string()))?;
+            let branches: Vec<ApiBranch> = resp.json().await.map_err(|e| self.request_error(e))?;
             Ok(branches
                 .into_iter()
                 .filter_map(|b| b.name)
@@ -174,60 +280,77 @@ impl GithubClient for GithubApiClient {
         repo: &str,
         branch: &str,
         per_page: usize,
+        until_sha: Option<&str>,
     ) -> Result<Vec<Commit>, GithubError> {
         let url = format!("{}/repos/{}/{}/commits", self.base_url, owner, repo);
+        let request = self
+            .get(url)
+            .query(&[("sha", branch), ("per_page", &per_page.to_string())]);
+        // Keep paging until the last commit we've already seen turns up
+        let commits: Vec<ApiCommit> = self
+            .get_pages(request, "commits", |page: &[ApiCommit]| {
+                until_sha.is_none_or(|sha| page.iter().any(|c| c.sha.as_deref() == Some(sha)))
+            })
+            .await?;
+
+        Ok(commits
+            .into_iter()
+            .filter_map(|c| {
+                c.sha.map(|sha| Commit {
+                    sha,
+                    message: c
+                        .commit
+                        .as_ref()
+                        .and_then(|c| c.message.clone())
+                        .unwrap_or_else(|| "No commit message".to_string()),
+                    author_name: c
+                        .commit
+                        .as_ref()
+                        .and_then(|c| c.author.as_ref())
+                        .and_then(|a| a.name.clone())
+                        .or_else(|| c.author.as_ref().and_then(|a| a.login.clone()))
+                        .unwrap_or_else(|| "Unknown author".to_string()),
+                    html_url: c
+                        .html_url
+                        .unwrap_or_else(|| "https://github.com".to_string()),
+                    avatar_url: c.author.and_then(|a| a.avatar_url),
+                    committed_at: c
+                        .commit
+                        .and_then(|c| Self::parse_datetime(c.author.and_then(|a| a.date))),
+                    stats: None,
+                })
+            })
+            .collect())
+    }
+
+    async fn commit_stats(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<Option<CommitStats>, GithubError> {
+        let url = format!("{}/repos/{}/{}/commits/{}", self.base_url, owner, repo, sha);
         let resp = self
-            .client
             .get(url)
-            .query(&[
-                ("sha", branch),
-                ("per_page", &per_page.to_string()),
-            ])
             .send()
             .await
-            .map_err(|e| GithubError::Api(e.to_string()))?;
+            .map_err(|e| self.request_error(e))?;
 
         if resp.status() == StatusCode::NOT_FOUND {
-            return Ok(Vec::new());
+            return Ok(None);
         }
         self.handle_rate_limit(resp.status()).await?;
 
         if resp.status().is_success() {
-            let commits: Vec<ApiCommit> = resp
-                .json()
-                .await
-                .map_err(|e| GithubError::Api(e.to_string()))?;
-
-            Ok(commits
-                .into_iter()
-                .filter_map(|c| {
-                    c.sha.map(|sha| Commit {
-                        sha,
-                        message: c
-                            .commit
-                            .as_ref()
-                            .and_then(|c| c.message.clone())
-                            .unwrap_or_else(|| "No commit message".to_string()),
-                        author_name: c
-                            .commit
-                            .as_ref()
-                            .and_then(|c| c.author.as_ref())
-                            .and_then(|a| a.name.clone())
-                            .or_else(|| c.author.as_ref().and_then(|a| a.login.clone()))
-                            .unwrap_or_else(|| "Unknown author".to_string()),
-                        html_url: c
-                            .html_url
-                            .unwrap_or_else(|| "https://github.com".to_string()),
-                        avatar_url: c.author.and_then(|a| a.avatar_url),
-                        committed_at: c
-                            .commit
-                            .and_then(|c| Self::parse_datetime(c.author.and_then(|a| a.date))),
-                    })
-                })
-                .collect())
+            let detail: ApiCommitDetail = resp.json().await.map_err(|e| self.request_error(e))?;
+            Ok(detail.stats.map(|stats| CommitStats {
+                files_changed: detail.files.map_or(0, |files| files.len() as u32),
+                additions: stats.additions.unwrap_or_default(),
+                deletions: stats.deletions.unwrap_or_default(),
+            }))
         } else {
             Err(GithubError::Api(format!(
-                "Failed to fetch commits: {}",
+                "Failed to fetch commit stats: {}",
                 resp.status()
             )))
         }
@@ -239,48 +362,14 @@ impl GithubClient for GithubApiClient {
         repo: &str,
         since: Option<DateTime<Utc>>,
     ) -> Result<Vec<Issue>, GithubError> {
-        let url = format!("{}/repos/{}/{}/issues", self.base_url, owner, repo);
-        let mut req = self
-            .client
-            .get(url)
-            .query(&[
-                ("state", "all"),
-                ("labels", "bug"),
-                ("sort", "updated"),
-                ("direction", "desc"),
-                ("per_page", "30"),
-            ]);
-
-        if let Some(since) = since {
-            req = req.query(&[("since", &since.to_rfc3339())]);
-        }
-
-        let resp = req
-            .send()
-            .await
-            .map_err(|e| GithubError::Api(e.to_string()))?;
-
-        if resp.status() == StatusCode::NOT_FOUND {
-            return Ok(Vec::new());
-        }
-        self.handle_rate_limit(resp.status()).await?;
-
-        if resp.status().is_success() {
-            let issues: Vec<ApiIssue> = resp
-                .json()
-                .await
-                .map_err(|e| GithubError::Api(e.to_string()))?;
-            Ok(issues
-                .into_iter()
-                .filter(|issue| issue.pull_request.is_none())
-                .map(|issue| Self::map_issue(issue, true))
-                .collect())
-        } else {
-            Err(GithubError::Api(format!(
-                "Failed to fetch bug issues: {}",
-                resp.status()
-            )))
-        }
+        let issues = self
+            .list_issues(owner, repo, Some("bug"), since, "bug issues")
+            .await?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .map(|issue| Self::map_issue(issue, true))
+            .collect())
     }
 
     async fn list_general_issues(
@@ -289,58 +378,41 @@ impl GithubClient for GithubApiClient {
         repo: &str,
         since: Option<DateTime<Utc>>,
     ) -> Result<Vec<Issue>, GithubError> {
-        let url = format!("{}/repos/{}/{}/issues", self.base_url, owner, repo);
-        let mut req = self
-            .client
-            .get(url)
-            .query(&[
-                ("state", "all"),
-                ("sort", "updated"),
-                ("direction", "desc"),
-                ("per_page", "30"),
-            ]);
-
-        if let Some(since) = since {
-            req = req.query(&[("since", &since.to_rfc3339())]);
-        }
-
-        let resp = req
-            .send()
-            .await
-            .map_err(|e| GithubError::Api(e.to_string()))?;
-
-        if resp.status() == StatusCode::NOT_FOUND {
-            return Ok(Vec::new());
-        }
-        self.handle_rate_limit(resp.status()).await?;
-
-        if resp.status().is_success() {
-            let issues: Vec<ApiIssue> = resp
-                .json()
-                .await
-                .map_err(|e| GithubError::Api(e.to_string()))?;
-            Ok(issues
-                .into_iter()
-                .filter(|issue| issue.pull_request.is_none())
-                .filter(|issue| {
-                    !issue
-                        .labels
-                        .as_ref()
-                        .unwrap_or(&Vec::new())
-                        .iter()
-                        .any(|l| l.name.as_deref().unwrap_or("").eq_ignore_ascii_case("bug"))
-                })
-                .map(|issue| Self::map_issue(issue, false))
-                .collect())
-        } else {
-            Err(GithubError::Api(format!(
-                "Failed to fetch issues: {}",
-                resp.status()
-            )))
-        }
+        let issues = self.list_issues(owner, repo, None, since, "issues").await?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .filter(|issue| {
+                !issue
+                    .labels
+                    .as_ref()
+                    .unwrap_or(&Vec::new())
+                    .iter()
+                    .any(|l| l.name.as_deref().unwrap_or("").eq_ignore_ascii_case("bug"))
+            })
+            .map(|issue| Self::map_issue(issue, false))
+            .collect())
     }
 }
 
+/// The `rel="next"` URL of a `Link` header, e.g.
+/// `<https://api.github.com/...&page=2>; rel="next", <...>; rel="last"`.
+fn next_page_url(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(reqwest::header::LINK)?.to_str().ok()?;
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiRepo {
     name: Option<String>,
@@ -359,6 +431,19 @@ struct ApiCommit {
     author: Option<ApiUser>,
 }
 
+/// Single-commit response; only the diff stats are read.
+#[derive(Debug, Deserialize)]
+struct ApiCommitDetail {
+    stats: Option<ApiCommitStats>,
+    files: Option<Vec<serde::de::IgnoredAny>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCommitStats {
+    additions: Option<u32>,
+    deletions: Option<u32>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiCommitInfo {
     message: Option<String>,
@@ -401,3 +486,141 @@ struct ApiUser {
     login: Option<String>,
     avatar_url: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Answers one request per `(body, next page path)` and returns the
+    /// request heads, lowercased.
+    async fn mock_server(
+        pages: Vec<(String, Option<&'static str>)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server_url = base_url.clone();
+        let requests = tokio::spawn(async move {
+            let mut heads = Vec::new();
+            for (body, next) in pages {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.ends_with(b"\r\n\r\n") {
+                    let read = socket.read(&mut buf).await.unwrap();
+                    assert!(read > 0, "connection closed mid-request");
+                    head.extend_from_slice(&buf[..read]);
+                }
+                let link = next
+                    .map(|path| {
+                        format!(
+                            "Link: <{}{}>; rel=\"next\", <{}/last>; rel=\"last\"\r\n",
+                            server_url, path, server_url
+                        )
+                    })
+                    .unwrap_or_default();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    link,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                heads.push(String::from_utf8(head).unwrap().to_ascii_lowercase());
+            }
+            heads
+        });
+        (base_url, requests)
+    }
+
+    fn client(base_url: String) -> GithubApiClient {
+        // A bare client, so the headers must come from `GithubApiClient` itself
+        GithubApiClient::new(Client::new(), None, Duration::from_secs(5))
+            .unwrap()
+            .with_base_url(base_url)
+    }
+
+    fn commits_json(shas: &[&str]) -> String {
+        let commits: Vec<String> = shas
+            .iter()
+            .map(|sha| format!(r#"{{"sha":"{}"}}"#, sha))
+            .collect();
+        format!("[{}]", commits.join(","))
+    }
+
+    #[tokio::test]
+    async fn test_requests_carry_github_headers() {
+        let (base_url, requests) = mock_server(vec![("[]".to_string(), None)]).await;
+
+        let branches = client(base_url)
+            .list_branches("owner", "repo")
+            .await
+            .unwrap();
+        assert!(branches.is_empty());
+
+        let head = requests.await.unwrap().remove(0);
+        assert!(head.starts_with("get /repos/owner/repo/branches?per_page=100 "));
+        let user_agent = format!("user-agent: {}\r\n", http::USER_AGENT.to_ascii_lowercase());
+        assert!(head.contains(&user_agent), "{}", head);
+        assert!(head.contains("accept: application/vnd.github+json\r\n"));
+        assert!(head.contains("x-github-api-version: 2022-11-28\r\n"));
+        assert!(!head.contains("authorization:"));
+    }
+
+    #[test]
+    fn test_next_page_url() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(next_page_url(&headers), None);
+
+        headers.insert(
+            reqwest::header::LINK,
+            HeaderValue::from_static(
+                r#"<https://api.github.com/x?page=1>; rel="prev", <https://api.github.com/x?page=3>; rel="next""#,
+            ),
+        );
+        assert_eq!(
+            next_page_url(&headers).as_deref(),
+            Some("https://api.github.com/x?page=3")
+        );
+
+        headers.insert(
+            reqwest::header::LINK,
+            HeaderValue::from_static(r#"<https://api.github.com/x?page=1>; rel="first""#),
+        );
+        assert_eq!(next_page_url(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_commits_follow_pages_until_last_seen() {
+        let first_page = commits_json(&["e", "d"]);
+        let (base_url, requests) = mock_server(vec![
+            (first_page.clone(), Some("/page2")),
+            (commits_json(&["c", "b"]), Some("/page3")),
+            (first_page, Some("/page2")),
+        ])
+        .await;
+        let client = client(base_url);
+
+        // "b" is on the second page, so the third is never requested
+        let commits = client
+            .list_commits("owner", "repo", "main", 2, Some("b"))
+            .await
+            .unwrap();
+        let shas: Vec<&str> = commits.iter().map(|c| c.sha.as_str()).collect();
+        assert_eq!(shas, vec!["e", "d", "c", "b"]);
+
+        // Without a last-seen commit only the first page is fetched
+        let commits = client
+            .list_commits("owner", "repo", "main", 2, None)
+            .await
+            .unwrap();
+        assert_eq!(commits.len(), 2);
+
+        let heads = requests.await.unwrap();
+        let first = "get /repos/owner/repo/commits?sha=main&per_page=2 ";
+        assert!(heads[0].starts_with(first));
+        assert!(heads[1].starts_with("get /page2 "));
+        assert!(heads[2].starts_with(first));
+    }
+}
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::core::github::{GithubConfig, GithubConfigStore, GithubError};
+
+/// In-memory store for GitHub tracking configuration.
+///
+/// Used when `STORAGE_BACKEND=memory`; tracked repos are forgotten on restart.
+#[derive(Default)]
+pub struct InMemoryGithubStore {
+    config: RwLock<GithubConfig>,
+}
+
+impl InMemoryGithubStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl GithubConfigStore for InMemoryGithubStore {
+    async fn load(&self) -> Result<GithubConfig, GithubError> {
+        Ok(self.config.read().await.clone())
+    }
+
+    async fn save(&self, config: &GithubConfig) -> Result<(), GithubError> {
+        *self.config.write().await = config.clone();
+        Ok(())
+    }
+}
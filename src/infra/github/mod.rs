@@ -1,9 +1,13 @@
 // GitHub infra layer.
 // - `github_client.rs` talks to the GitHub HTTP API.
 // - `file_store.rs` persists tracking config to disk.
+// - `memory_store.rs` keeps tracking config in memory (`STORAGE_BACKEND=memory`).
 
 #[path = "github_client.rs"]
 pub mod github_client;
 
 #[path = "file_store.rs"]
 pub mod file_store;
+
+#[path = "memory_store.rs"]
+pub mod memory_store;
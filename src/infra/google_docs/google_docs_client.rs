@@ -54,6 +54,8 @@
 // - `GOOGLE_SERVICE_ACCOUNT_KEY` - Path to service account JSON file
 // - `GOOGLE_SERVICE_ACCOUNT_JSON` - Service account JSON content (alternative)
 // - `GOOGLE_DOC_IDS` - Comma-separated list of document IDs to pre-fetch
+// - `GOOGLE_DOCS_TIMEOUT_SECS` - Per-request timeout (default 30), read by
+//   `infra::config` and passed in
 
 use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 use reqwest::Client;
@@ -66,6 +68,7 @@ use tokio::sync::RwLock;
 
 use crate::core::ai::models::{AiTool, FunctionDef, FunctionParameters, PropertyDef};
 use crate::core::ai::FunctionCallHandler;
+use crate::infra::http;
 use async_trait::async_trait;
 
 // =============================================================================
@@ -104,6 +107,10 @@ struct JwtClaims {
     exp: u64,
 }
 
+fn request_error(timeout: Duration, error: reqwest::Error) -> Box<dyn Error + Send + Sync> {
+    http::request_error("Google Docs", timeout, error)
+}
+
 /// Response from Google's token endpoint.
 #[derive(Debug, Deserialize)]
 struct TokenResponse {
@@ -124,34 +131,48 @@ struct CachedToken {
 pub struct ServiceAccountAuth {
     credentials: ServiceAccountCredentials,
     client: Client,
+    /// How long a token request may take (`GOOGLE_DOCS_TIMEOUT_SECS`).
+    timeout: Duration,
     cached_token: Arc<RwLock<Option<CachedToken>>>,
 }
 
 impl ServiceAccountAuth {
     /// Creates a new authenticator from a JSON key file path.
-    pub async fn from_file(path: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+    pub async fn from_file(
+        client: Client,
+        path: &str,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let content = tokio::fs::read_to_string(path).await?;
-        Self::from_json(&content)
+        Self::from_json(client, &content, timeout)
     }
 
     /// Creates a new authenticator from JSON content.
-    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+    pub fn from_json(
+        client: Client,
+        json: &str,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let credentials: ServiceAccountCredentials = serde_json::from_str(json)?;
         Ok(Self {
             credentials,
-            client: Client::new(),
+            client,
+            timeout,
             cached_token: Arc::new(RwLock::new(None)),
         })
     }
 
     /// Creates from environment variables.
-    pub async fn from_env() -> Result<Self, Box<dyn Error + Send + Sync>> {
+    pub async fn from_env(
+        client: Client,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         if let Ok(path) = std::env::var("GOOGLE_SERVICE_ACCOUNT_KEY") {
-            return Self::from_file(&path).await;
+            return Self::from_file(client, &path, timeout).await;
         }
 
         if let Ok(json) = std::env::var("GOOGLE_SERVICE_ACCOUNT_JSON") {
-            return Self::from_json(&json);
+            return Self::from_json(client, &json, timeout);
         }
 
         Err("Neither GOOGLE_SERVICE_ACCOUNT_KEY nor GOOGLE_SERVICE_ACCOUNT_JSON is set.".into())
@@ -207,16 +228,24 @@ impl ServiceAccountAuth {
                 ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
                 ("assertion", &jwt),
             ])
+            .timeout(self.timeout)
             .send()
-            .await?;
+            .await
+            .map_err(|e| request_error(self.timeout, e))?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let text = response.text().await?;
+            let text = response
+                .text()
+                .await
+                .map_err(|e| request_error(self.timeout, e))?;
             return Err(format!("Token exchange failed ({}): {}", status, text).into());
         }
 
-        let token_response: TokenResponse = response.json().await?;
+        let token_response: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| request_error(self.timeout, e))?;
         Ok(token_response.access_token)
     }
 }
@@ -322,29 +351,41 @@ struct Document {
 pub struct GoogleDocsClient {
     client: Client,
     auth: Option<ServiceAccountAuth>,
+    /// How long a request may take before it fails with `RequestTimeout`.
+    timeout: Duration,
 }
 
 impl GoogleDocsClient {
     /// Creates a new client for public documents only (no authentication).
-    pub fn new() -> Self {
+    /// `client` is the bot's shared HTTP client (`http::shared_client`).
+    pub fn new(client: Client, timeout: Duration) -> Self {
         Self {
-            client: Client::new(),
+            client,
             auth: None,
+            timeout,
         }
     }
 
     /// Creates a client with service account authentication.
-    pub fn with_service_account(auth: ServiceAccountAuth) -> Self {
+    pub fn with_service_account(
+        client: Client,
+        auth: ServiceAccountAuth,
+        timeout: Duration,
+    ) -> Self {
         Self {
-            client: Client::new(),
+            client,
             auth: Some(auth),
+            timeout,
         }
     }
 
     /// Creates a client with service account from environment variables.
-    pub async fn with_service_account_from_env() -> Result<Self, Box<dyn Error + Send + Sync>> {
-        let auth = ServiceAccountAuth::from_env().await?;
-        Ok(Self::with_service_account(auth))
+    pub async fn with_service_account_from_env(
+        client: Client,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let auth = ServiceAccountAuth::from_env(client.clone(), timeout).await?;
+        Ok(Self::with_service_account(client, auth, timeout))
     }
 
     /// Pre-fetches documents at startup and returns their combined content.
@@ -431,11 +472,20 @@ impl GoogleDocsClient {
 
         tracing::debug!("Fetching Google Doc via export: {}", doc_id);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .client
+            .get(&url)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|e| request_error(self.timeout, e))?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let text = response.text().await?;
+            let text = response
+                .text()
+                .await
+                .map_err(|e| request_error(self.timeout, e))?;
             return Err(format!(
                 "Failed to fetch document ({}): {}. \
                  Make sure the document is shared as 'Anyone with the link can view'.",
@@ -444,7 +494,10 @@ impl GoogleDocsClient {
             .into());
         }
 
-        Ok(response.text().await?)
+        response
+            .text()
+            .await
+            .map_err(|e| request_error(self.timeout, e))
     }
 
     /// Fetches ALL tabs from a document using the Google Docs API.
@@ -473,12 +526,17 @@ impl GoogleDocsClient {
             .client
             .get(&url)
             .header("Authorization", format!("Bearer {}", token))
+            .timeout(self.timeout)
             .send()
-            .await?;
+            .await
+            .map_err(|e| request_error(self.timeout, e))?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let text = response.text().await?;
+            let text = response
+                .text()
+                .await
+                .map_err(|e| request_error(self.timeout, e))?;
             return Err(format!(
                 "Google Docs API error ({}): {}. \
                  Make sure the document is shared with your service account email.",
@@ -487,7 +545,10 @@ impl GoogleDocsClient {
             .into());
         }
 
-        let document: Document = response.json().await?;
+        let document: Document = response
+            .json()
+            .await
+            .map_err(|e| request_error(self.timeout, e))?;
 
         let mut combined = format!("# {}\n\n", document.title);
 
@@ -570,12 +631,6 @@ impl GoogleDocsClient {
     }
 }
 
-impl Default for GoogleDocsClient {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 // =============================================================================
 // FUNCTION TOOL HELPERS
 // =============================================================================
@@ -694,8 +749,11 @@ impl GoogleDocsFunctionHandler {
         }
     }
 
-    pub async fn from_env_with_auth() -> Result<Self, Box<dyn Error + Send + Sync>> {
-        let client = GoogleDocsClient::with_service_account_from_env().await?;
+    pub async fn from_env_with_auth(
+        client: Client,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let client = GoogleDocsClient::with_service_account_from_env(client, timeout).await?;
 
         let project_docs = ProjectDocsConfig {
             story_bible_id: std::env::var("PROJECT_DOC_STORY_BIBLE").ok(),
@@ -707,7 +765,7 @@ impl GoogleDocsFunctionHandler {
         Ok(Self::new(client, project_docs))
     }
 
-    pub fn from_env() -> Self {
+    pub fn from_env(client: Client, timeout: Duration) -> Self {
         let project_docs = ProjectDocsConfig {
             story_bible_id: std::env::var("PROJECT_DOC_STORY_BIBLE").ok(),
             script_id: std::env::var("PROJECT_DOC_SCRIPT").ok(),
@@ -715,7 +773,7 @@ impl GoogleDocsFunctionHandler {
             additional_docs: vec![],
         };
 
-        Self::new(GoogleDocsClient::new(), project_docs)
+        Self::new(GoogleDocsClient::new(client, timeout), project_docs)
     }
 
     pub fn get_tools(&self, include_google_search: bool) -> Vec<AiTool> {
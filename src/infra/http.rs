@@ -0,0 +1,173 @@
+// Shared setup for the outbound HTTP clients (AI providers, GitHub, Google Docs).
+//
+// `main` builds one `reqwest::Client` with `shared_client` and hands clones of
+// it to every API client, so they share one connection pool and User-Agent.
+// It has no overall timeout: each API client sets its own on every request,
+// since a provider that accepts the connection and never answers would
+// otherwise hold the request (and the typing indicator) forever. A timeout is
+// reported as `RequestTimeout` rather than a generic error, and an error status
+// as `ApiError`, so callers can tell an outage from a rejected request
+// (`is_outage`).
+
+use reqwest::Client;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// Sent with every outbound request.
+pub const USER_AGENT: &str = concat!("RustDiscordBot/", env!("CARGO_PKG_VERSION"));
+
+/// How long to wait for a connection to open.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The client shared by all outbound calls. Cloning it is cheap and keeps the
+/// same connection pool. Set a timeout on each request
+/// (`RequestBuilder::timeout`); the client itself only limits connecting.
+///
+/// Fails if the TLS backend can't be initialised.
+pub fn shared_client() -> Result<Client, reqwest::Error> {
+    Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+}
+
+/// `service` did not answer within `after`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTimeout {
+    pub service: &'static str,
+    pub after: Duration,
+}
+
+impl fmt::Display for RequestTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} did not respond within {}s",
+            self.service,
+            self.after.as_secs()
+        )
+    }
+}
+
+impl Error for RequestTimeout {}
+
+/// `service` answered with an error status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub service: &'static str,
+    pub status: u16,
+    /// The error message from the response body.
+    pub message: String,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} API error ({}): {}",
+            self.service, self.status, self.message
+        )
+    }
+}
+
+impl Error for ApiError {}
+
+/// Whether a failed request says the service is down or unusable: timeouts,
+/// connection errors, rate limits, server errors and rejected credentials.
+/// Anything else is a problem with the request itself.
+pub fn is_outage(error: &(dyn Error + Send + Sync + 'static)) -> bool {
+    if error.downcast_ref::<RequestTimeout>().is_some() {
+        return true;
+    }
+    if let Some(api) = error.downcast_ref::<ApiError>() {
+        return is_outage_status(api.status);
+    }
+    error
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(|error| error.is_connect() || error.is_timeout())
+}
+
+fn is_outage_status(status: u16) -> bool {
+    matches!(status, 401 | 403 | 429) || status >= 500
+}
+
+/// Turns a timed-out request into `RequestTimeout`; other errors pass through.
+pub fn request_error(
+    service: &'static str,
+    timeout: Duration,
+    error: reqwest::Error,
+) -> Box<dyn Error + Send + Sync> {
+    if error.is_timeout() {
+        Box::new(RequestTimeout {
+            service,
+            after: timeout,
+        })
+    } else {
+        Box::new(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_is_outage() {
+        let api_error = |status| -> Box<dyn Error + Send + Sync> {
+            Box::new(ApiError {
+                service: "Test API",
+                status,
+                message: "nope".to_string(),
+            })
+        };
+        for status in [401, 403, 429, 500, 503] {
+            assert!(is_outage(api_error(status).as_ref()), "{status}");
+        }
+        for status in [400, 404, 413, 422] {
+            assert!(!is_outage(api_error(status).as_ref()), "{status}");
+        }
+
+        let timeout: Box<dyn Error + Send + Sync> = Box::new(RequestTimeout {
+            service: "Test API",
+            after: Duration::from_secs(1),
+        });
+        assert!(is_outage(timeout.as_ref()));
+        let other: Box<dyn Error + Send + Sync> = "unexpected reply".into();
+        assert!(!is_outage(other.as_ref()));
+    }
+
+    #[tokio::test]
+    async fn test_stalled_server_times_out() {
+        // Accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                open.push(socket);
+            }
+        });
+
+        let timeout = Duration::from_millis(200);
+        let started = Instant::now();
+        let error = shared_client()
+            .unwrap()
+            .get(format!("http://{}/", addr))
+            .timeout(timeout)
+            .send()
+            .await
+            .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let error = request_error("Test API", timeout, error);
+        assert_eq!(
+            error.downcast_ref::<RequestTimeout>(),
+            Some(&RequestTimeout {
+                service: "Test API",
+                after: timeout,
+            })
+        );
+    }
+}
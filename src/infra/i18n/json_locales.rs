@@ -0,0 +1,97 @@
+// Loads locale files (simple keyed JSON) into the core `Localizer`.
+//
+// **Format:** one file per locale, named after the locale code, containing a
+// flat object of `"key": "template"` pairs:
+//
+// ```json
+// { "daily.claimed_title": "✅ Daily Reward Claimed!" }
+// ```
+//
+// English and Spanish are compiled into the binary so the bot always has
+// translations, even when deployed without the `locales/` folder. Files found
+// in `LOCALES_DIR` (default `locales`) are layered on top, so operators can
+// tweak wording or add new languages (`fr.json`, `pt-BR.json`, ...) without
+// rebuilding.
+
+use crate::core::i18n::{LocaleStrings, Localizer};
+use std::path::Path;
+
+/// Locale used when the user's locale has no translation for a key.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales shipped with the bot.
+const BUILTIN_LOCALES: [(&str, &str); 2] = [
+    ("en", include_str!("../../../locales/en.json")),
+    ("es", include_str!("../../../locales/es.json")),
+];
+
+/// Build a `Localizer` from the built-in locales plus any JSON files in `dir`.
+pub fn load_localizer(dir: impl AsRef<Path>) -> Localizer {
+    let mut localizer = Localizer::new(DEFAULT_LOCALE);
+
+    for (locale, raw) in BUILTIN_LOCALES {
+        match serde_json::from_str::<LocaleStrings>(raw) {
+            Ok(strings) => localizer.add_locale(locale, strings),
+            Err(e) => tracing::error!(locale, error = %e, "Built-in locale file is invalid"),
+        }
+    }
+
+    let dir = dir.as_ref();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        // No override directory is fine - the built-in locales are enough.
+        Err(_) => return localizer,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(locale) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| serde_json::from_str::<LocaleStrings>(&raw).map_err(|e| e.to_string()));
+
+        match parsed {
+            Ok(strings) => localizer.add_locale(locale, strings),
+            Err(e) => tracing::warn!(
+                path = %path.display(),
+                error = %e,
+                "Skipping invalid locale file"
+            ),
+        }
+    }
+
+    localizer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_locales_have_the_same_keys() {
+        let en: LocaleStrings = serde_json::from_str(BUILTIN_LOCALES[0].1).unwrap();
+        let es: LocaleStrings = serde_json::from_str(BUILTIN_LOCALES[1].1).unwrap();
+        let mut missing: Vec<&String> = en.keys().filter(|k| !es.contains_key(*k)).collect();
+        missing.sort();
+        assert!(missing.is_empty(), "es.json is missing keys: {:?}", missing);
+    }
+
+    #[test]
+    fn test_spanish_daily_text() {
+        let localizer = load_localizer("does-not-exist");
+        assert_eq!(
+            localizer.t(Some("es-ES"), "daily.claimed_title", &[]),
+            "✅ ¡Recompensa diaria reclamada!"
+        );
+        assert_eq!(
+            localizer.t(Some("en-US"), "daily.claimed_title", &[]),
+            "✅ Daily Reward Claimed!"
+        );
+    }
+}
@@ -10,17 +10,53 @@ pub mod server_stats;
 #[path = "logging/mod.rs"]
 pub mod logging;
 
+#[cfg(feature = "github")]
 #[path = "github/mod.rs"]
 pub mod github;
 
+#[cfg(feature = "ai")]
 #[path = "ai/mod.rs"]
 pub mod ai;
 
+#[cfg(feature = "economy")]
 #[path = "economy/mod.rs"]
 pub mod economy;
 
+#[cfg(feature = "google-docs")]
 #[path = "google_docs/mod.rs"]
 pub mod google_docs;
 
+#[cfg(feature = "moderation")]
 #[path = "moderation/mod.rs"]
 pub mod moderation;
+
+#[path = "i18n/json_locales.rs"]
+pub mod i18n;
+
+#[cfg(any(feature = "ai", feature = "github"))]
+#[path = "http.rs"]
+pub mod http;
+
+#[path = "json_file.rs"]
+pub mod json_file;
+
+#[path = "access/manager_role_store.rs"]
+pub mod access;
+
+#[path = "autorole/auto_role_store.rs"]
+pub mod autorole;
+
+#[path = "reactionroles/reaction_role_store.rs"]
+pub mod reactionroles;
+
+#[path = "welcome/mod.rs"]
+pub mod welcome;
+
+#[path = "feedback/feedback_store.rs"]
+pub mod feedback;
+
+#[path = "birthdays/birthday_store.rs"]
+pub mod birthdays;
+
+#[path = "config/app_config.rs"]
+pub mod config;
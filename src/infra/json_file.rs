@@ -0,0 +1,37 @@
+// Atomic writes for the JSON-file stores (server stats, GitHub tracking).
+//
+// Writing straight to the target with `File::create` truncates it first, so a
+// crash or a concurrent writer can leave a half-written file behind. Instead we
+// write the full contents to a sibling temp file and `rename` it over the target;
+// rename is atomic on the same filesystem, so readers only ever see the old or
+// the new version.
+//
+// This does NOT serialize writers - each store holds its own mutex around
+// "snapshot + write" so the last write wins.
+
+use std::path::{Path, PathBuf};
+
+/// Temp file next to `path` (same directory, so `rename` stays on one filesystem).
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_else(|| "store.json".into());
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Atomically replace `path` with `contents`.
+pub async fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let tmp = temp_path(path);
+    tokio::fs::write(&tmp, contents).await?;
+    if let Err(e) = tokio::fs::rename(&tmp, path).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e);
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+// Loads custom achievements from a JSON file.
+//
+// **Format:** an array of achievement definitions. A definition whose `id`
+// matches a built-in achievement replaces it; any other ID adds a new one.
+//
+// ```json
+// [
+//   {
+//     "id": "night_owl",
+//     "name": "Night Owl",
+//     "description": "Send 250 messages",
+//     "emoji": "🦉",
+//     "category": "Activity",
+//     "reward_xp": 150,
+//     "requirement": { "messages": 250 }
+//   }
+// ]
+// ```
+//
+// The file is optional. If it is missing the built-in set is used as-is; if it
+// can't be parsed, or individual entries are invalid, the problems are logged
+// and the rest of the achievements still load. A file that exists but can't be
+// read (permissions, a directory in its place) is an error, so a broken setup
+// isn't mistaken for having no custom achievements.
+
+use crate::core::leveling::achievements::{builtin_achievements, merge_achievements, Achievement};
+use std::path::Path;
+
+/// Built-in achievements plus the valid entries from `path`.
+///
+/// Fails only if the file exists but can't be read.
+pub fn load_achievements(path: impl AsRef<Path>) -> std::io::Result<Vec<Achievement>> {
+    let path = path.as_ref();
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        // No custom file is fine - the built-in achievements are enough.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(builtin_achievements()),
+        Err(e) => return Err(e),
+    };
+
+    let custom: Vec<Achievement> = match serde_json::from_str(&raw) {
+        Ok(custom) => custom,
+        Err(e) => {
+            tracing::error!(
+                path = %path.display(),
+                error = %e,
+                "Invalid achievements file, using built-in achievements only"
+            );
+            return Ok(builtin_achievements());
+        }
+    };
+
+    let (achievements, errors) = merge_achievements(builtin_achievements(), custom);
+    for error in errors {
+        tracing::warn!(path = %path.display(), error = %error, "Skipping custom achievement");
+    }
+    Ok(achievements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::leveling::{LevelingConfig, LevelingService, XpSource};
+    use crate::infra::leveling::InMemoryXpStore;
+
+    #[tokio::test]
+    async fn test_custom_achievements_are_awarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("achievements.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {
+                    "id": "warming_up",
+                    "name": "Warming Up",
+                    "description": "Earn 200 total XP",
+                    "emoji": "🔥",
+                    "category": "Progression",
+                    "reward_xp": 20,
+                    "requirement": { "total_xp": 200 }
+                },
+                {
+                    "id": "impossible",
+                    "name": "Impossible",
+                    "description": "Send 0 messages",
+                    "emoji": "🚫",
+                    "category": "Activity",
+                    "reward_xp": 10,
+                    "requirement": { "messages": 0 }
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        // The invalid entry is skipped, the valid one is added
+        let achievements = load_achievements(&path).unwrap();
+        assert_eq!(achievements.len(), builtin_achievements().len() + 1);
+
+        let service = LevelingService::with_config(
+            InMemoryXpStore::new(),
+            LevelingConfig {
+                achievements,
+                ..LevelingConfig::default()
+            },
+        );
+        service
+            .award_xp(1, 1, 200, XpSource::Message)
+            .await
+            .unwrap();
+        let profile = service.get_user_profile(1, 1).await.unwrap();
+        assert!(profile.achievements.iter().any(|id| id == "warming_up"));
+    }
+
+    #[test]
+    fn test_missing_or_broken_file_uses_builtins() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("achievements.json");
+        assert_eq!(
+            load_achievements(&path).unwrap().len(),
+            builtin_achievements().len()
+        );
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(
+            load_achievements(&path).unwrap().len(),
+            builtin_achievements().len()
+        );
+    }
+
+    #[test]
+    fn test_unreadable_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory where the file should be can't be read as one
+        let path = dir.path().join("achievements.json");
+        std::fs::create_dir(&path).unwrap();
+        assert!(load_achievements(&path).is_err());
+    }
+}
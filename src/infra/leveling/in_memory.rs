@@ -11,10 +11,13 @@
 // Once the leveling system works, we'll create a SqlxXpStore that implements
 // the same XpStore trait but persists data to PostgreSQL.
 
-use crate::core::leveling::{LevelingError, UserProfile, UserStats, XpStore};
+use crate::core::leveling::{
+    LevelUpMention, LevelingError, UserProfile, UserStats, XpBoost, XpStore,
+};
 use async_trait::async_trait;
 use dashmap::DashMap;
-use std::collections::VecDeque;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::Instant;
 
 /// A composite key for looking up user XP.
@@ -28,7 +31,6 @@ struct UserGuildKey {
 /// Data we store for each user in each guild.
 #[derive(Clone, Debug)]
 struct StoredUserData {
-    xp: u64,
     last_xp_time: Option<Instant>,
     // Rich profile fields
     profile: UserProfile,
@@ -45,6 +47,15 @@ pub struct InMemoryXpStore {
     data: DashMap<UserGuildKey, StoredUserData>,
     /// Per-guild meta data (daily goals, etc.)
     meta: DashMap<u64, crate::core::leveling::DailyGoal>,
+    /// guild_id -> channel_id -> XP multiplier
+    channel_multipliers: DashMap<u64, HashMap<u64, f64>>,
+    /// guild_id -> channels excluded from XP
+    excluded_channels: DashMap<u64, HashSet<u64>>,
+    /// (user_id, guild_id) -> channel_id -> voice minutes
+    voice_minutes: DashMap<UserGuildKey, HashMap<u64, u64>>,
+    level_up_mentions: DashMap<u64, LevelUpMention>,
+    daily_timezones: DashMap<u64, String>,
+    xp_boosts: DashMap<UserGuildKey, Vec<XpBoost>>,
 }
 
 impl InMemoryXpStore {
@@ -53,6 +64,12 @@ impl InMemoryXpStore {
         Self {
             data: DashMap::new(),
             meta: DashMap::new(),
+            channel_multipliers: DashMap::new(),
+            excluded_channels: DashMap::new(),
+            voice_minutes: DashMap::new(),
+            level_up_mentions: DashMap::new(),
+            daily_timezones: DashMap::new(),
+            xp_boosts: DashMap::new(),
         }
     }
 }
@@ -62,107 +79,76 @@ impl InMemoryXpStore {
 // that this is in-memory vs a database.
 #[async_trait]
 impl XpStore for InMemoryXpStore {
-    async fn get_xp(&self, user_id: u64, guild_id: u64) -> Result<u64, LevelingError> {
-        let key = UserGuildKey { user_id, guild_id };
-
-        // get() returns Option<RefMulti<...>> which is a smart pointer to the value
-        // We extract the xp field and return it, or 0 if the user doesn't exist yet
-        Ok(self.data.get(&key).map(|entry| entry.xp).unwrap_or(0))
-    }
-
-    async fn add_xp(&self, user_id: u64, guild_id: u64, amount: u64) -> Result<(), LevelingError> {
-        let key = UserGuildKey { user_id, guild_id };
-
-        // entry() API lets us update or insert atomically
-        self.data
-            .entry(key)
-            .and_modify(|data| {
-                // User exists - add to their XP and update profile
-                data.xp = data.xp.saturating_add(amount);
-                data.profile.total_xp = data.profile.total_xp.saturating_add(amount);
-            })
-            .or_insert_with(|| {
-                // User doesn't exist - create new entry
-                let profile = UserProfile {
-                    user_id,
-                    guild_id,
-                    level: 1,
-                    total_xp: amount,
-                    xp_to_next_level: 0,
-                    total_commands_used: 0,
-                    total_messages: 0,
-                    last_daily: None,
-                    daily_streak: 0,
-                    last_message_timestamp: None,
-                    achievements: Vec::new(),
-                    best_rank: 999,
-                    previous_rank: 999,
-                    rank_improvement: 0,
-                    images_shared: 0,
-                    long_messages: 0,
-                    links_shared: 0,
-                    goals_completed: 0,
-                    boost_days: 0,
-                    first_boost_date: None,
-                    prestige_level: 0,
-                    xp_history: VecDeque::new(),
-                };
-                StoredUserData {
-                    xp: amount,
-                    last_xp_time: None,
-                    profile,
-                }
-            });
-
-        Ok(())
-    }
-
-    async fn get_leaderboard(
+    async fn get_leaderboard_page(
         &self,
         guild_id: u64,
-        limit: usize,
+        page: usize,
+        per_page: usize,
+        excluded: &[u64],
     ) -> Result<Vec<UserStats>, LevelingError> {
-        if limit == 0 {
-            return Err(LevelingError::StorageError(
-                "Leaderboard limit must be at least 1".to_string(),
-            ));
-        }
-        use crate::core::leveling::LevelingService;
-
-        // Create a temporary service just for level calculation
-        // This is a bit awkward - in a real impl, we'd store the level in the database
-        let temp_service = LevelingService::new(InMemoryXpStore::new());
-
         // Collect all users in this guild
-        let mut users: Vec<UserStats> = self
+        let mut profiles: Vec<UserProfile> = self
             .data
             .iter()
-            .filter(|entry| entry.key().guild_id == guild_id) // Only this guild
-            .map(|entry| {
-                let key = entry.key();
-                let data = entry.value();
-                UserStats {
-                    user_id: key.user_id,
-                    guild_id: key.guild_id,
-                    xp: data.xp,
-                    level: temp_service.calculate_level(data.xp),
-                    prestige_level: data.profile.prestige_level,
-                    last_xp_gain: data.last_xp_time,
-                }
-            })
+            .filter(|entry| entry.key().guild_id == guild_id)
+            .filter(|entry| !excluded.contains(&entry.key().user_id))
+            .map(|entry| entry.value().profile.clone())
             .collect();
 
-        // Sort by Prestige (highest first), then XP (highest first)
-        users.sort_by(|a, b| {
+        // Sort by Prestige (highest first), then XP (highest first), then user ID
+        profiles.sort_by(|a, b| {
             b.prestige_level
                 .cmp(&a.prestige_level)
-                .then(b.xp.cmp(&a.xp))
+                .then(b.total_xp.cmp(&a.total_xp))
+                .then(a.user_id.cmp(&b.user_id))
         });
 
-        // Take only the requested number
-        users.truncate(limit);
+        let offset = page.saturating_sub(1).saturating_mul(per_page);
+        Ok(profiles
+            .into_iter()
+            .skip(offset)
+            .take(per_page)
+            .map(|profile| UserStats {
+                user_id: profile.user_id,
+                guild_id: profile.guild_id,
+                xp: profile.total_xp,
+                level: profile.level,
+                prestige_level: profile.prestige_level,
+                last_xp_gain: None,
+            })
+            .collect())
+    }
+
+    async fn count_profiles(&self, guild_id: u64, excluded: &[u64]) -> Result<u64, LevelingError> {
+        Ok(self
+            .data
+            .iter()
+            .filter(|entry| entry.key().guild_id == guild_id)
+            .filter(|entry| !excluded.contains(&entry.key().user_id))
+            .count() as u64)
+    }
 
-        Ok(users)
+    async fn count_ranked_above(
+        &self,
+        guild_id: u64,
+        prestige_level: u32,
+        total_xp: u64,
+        user_id: u64,
+        excluded: &[u64],
+    ) -> Result<u64, LevelingError> {
+        // Lower user IDs win ties, as in `get_leaderboard_page`
+        let rank_key = |prestige: u32, xp: u64, user: u64| (prestige, xp, Reverse(user));
+        let own = rank_key(prestige_level, total_xp, user_id);
+        Ok(self
+            .data
+            .iter()
+            .filter(|entry| entry.key().guild_id == guild_id)
+            .filter(|entry| !excluded.contains(&entry.key().user_id))
+            .filter(|entry| {
+                let profile = &entry.value().profile;
+                rank_key(profile.prestige_level, profile.total_xp, profile.user_id) > own
+            })
+            .count() as u64)
     }
 
     async fn get_streak_leaderboard(
@@ -173,6 +159,7 @@ impl XpStore for InMemoryXpStore {
         if limit == 0 {
             return Err(LevelingError::StorageError(
                 "Leaderboard limit must be at least 1".to_string(),
+                None,
             ));
         }
 
@@ -213,36 +200,9 @@ impl XpStore for InMemoryXpStore {
             .and_modify(|data| {
                 data.last_xp_time = Some(time);
             })
-            .or_insert_with(|| {
-                let default_profile = UserProfile {
-                    user_id,
-                    guild_id,
-                    level: 1,
-                    total_xp: 0,
-                    xp_to_next_level: 0,
-                    total_commands_used: 0,
-                    total_messages: 0,
-                    last_daily: None,
-                    daily_streak: 0,
-                    last_message_timestamp: None,
-                    achievements: Vec::new(),
-                    best_rank: 999,
-                    previous_rank: 999,
-                    rank_improvement: 0,
-                    images_shared: 0,
-                    long_messages: 0,
-                    links_shared: 0,
-                    goals_completed: 0,
-                    boost_days: 0,
-                    first_boost_date: None,
-                    prestige_level: 0,
-                    xp_history: VecDeque::new(),
-                };
-                StoredUserData {
-                    xp: 0,
-                    last_xp_time: Some(time),
-                    profile: default_profile,
-                }
+            .or_insert_with(|| StoredUserData {
+                last_xp_time: Some(time),
+                profile: UserProfile::default_with_ids(user_id, guild_id),
             });
 
         Ok(())
@@ -274,6 +234,134 @@ impl XpStore for InMemoryXpStore {
         Ok(())
     }
 
+    async fn get_channel_multipliers(
+        &self,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, f64>, LevelingError> {
+        Ok(self
+            .channel_multipliers
+            .get(&guild_id)
+            .map(|entry| entry.clone())
+            .unwrap_or_default())
+    }
+
+    async fn set_channel_multiplier(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        multiplier: Option<f64>,
+    ) -> Result<(), LevelingError> {
+        let mut channels = self.channel_multipliers.entry(guild_id).or_default();
+        match multiplier {
+            Some(multiplier) => channels.insert(channel_id, multiplier),
+            None => channels.remove(&channel_id),
+        };
+        Ok(())
+    }
+
+    async fn get_excluded_channels(&self, guild_id: u64) -> Result<HashSet<u64>, LevelingError> {
+        Ok(self
+            .excluded_channels
+            .get(&guild_id)
+            .map(|entry| entry.clone())
+            .unwrap_or_default())
+    }
+
+    async fn set_channel_excluded(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        excluded: bool,
+    ) -> Result<(), LevelingError> {
+        let mut channels = self.excluded_channels.entry(guild_id).or_default();
+        if excluded {
+            channels.insert(channel_id);
+        } else {
+            channels.remove(&channel_id);
+        }
+        Ok(())
+    }
+
+    async fn get_voice_minutes(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, u64>, LevelingError> {
+        Ok(self
+            .voice_minutes
+            .get(&UserGuildKey { user_id, guild_id })
+            .map(|entry| entry.clone())
+            .unwrap_or_default())
+    }
+
+    async fn add_voice_minutes(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: u64,
+        minutes: u64,
+    ) -> Result<(), LevelingError> {
+        let mut channels = self
+            .voice_minutes
+            .entry(UserGuildKey { user_id, guild_id })
+            .or_default();
+        let total = channels.entry(channel_id).or_default();
+        *total = total.saturating_add(minutes);
+        Ok(())
+    }
+
+    async fn get_level_up_mention(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<LevelUpMention>, LevelingError> {
+        Ok(self.level_up_mentions.get(&guild_id).map(|entry| *entry))
+    }
+
+    async fn set_level_up_mention(
+        &self,
+        guild_id: u64,
+        mention: LevelUpMention,
+    ) -> Result<(), LevelingError> {
+        self.level_up_mentions.insert(guild_id, mention);
+        Ok(())
+    }
+
+    async fn get_daily_timezone(&self, guild_id: u64) -> Result<Option<String>, LevelingError> {
+        Ok(self
+            .daily_timezones
+            .get(&guild_id)
+            .map(|entry| entry.clone()))
+    }
+
+    async fn set_daily_timezone(&self, guild_id: u64, timezone: &str) -> Result<(), LevelingError> {
+        self.daily_timezones.insert(guild_id, timezone.to_string());
+        Ok(())
+    }
+
+    async fn get_xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Vec<XpBoost>, LevelingError> {
+        let key = UserGuildKey { user_id, guild_id };
+        Ok(self
+            .xp_boosts
+            .get(&key)
+            .map(|entry| entry.clone())
+            .unwrap_or_default())
+    }
+
+    async fn set_xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        boosts: &[XpBoost],
+    ) -> Result<(), LevelingError> {
+        self.xp_boosts
+            .insert(UserGuildKey { user_id, guild_id }, boosts.to_vec());
+        Ok(())
+    }
+
     async fn get_user_profile(
         &self,
         user_id: u64,
@@ -290,12 +378,8 @@ impl XpStore for InMemoryXpStore {
         };
         self.data
             .entry(key)
-            .and_modify(|data| {
-                data.profile = profile.clone();
-                data.xp = profile.total_xp;
-            })
+            .and_modify(|data| data.profile = profile.clone())
             .or_insert(StoredUserData {
-                xp: profile.total_xp,
                 last_xp_time: None,
                 profile,
             });
@@ -323,24 +407,22 @@ impl Default for InMemoryXpStore {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::leveling::LevelingService;
 
     #[tokio::test]
     async fn test_in_memory_store() {
         let store = InMemoryXpStore::new();
 
-        // Initially, user should have 0 XP
-        let xp = store.get_xp(123, 456).await.unwrap();
-        assert_eq!(xp, 0);
+        // Initially, the user has no profile
+        assert!(store.get_user_profile(123, 456).await.unwrap().is_none());
 
-        // Add some XP
-        store.add_xp(123, 456, 100).await.unwrap();
-        let xp = store.get_xp(123, 456).await.unwrap();
-        assert_eq!(xp, 100);
+        let mut profile = UserProfile::default_with_ids(123, 456);
+        profile.total_xp = 150;
+        store.save_user_profile(profile).await.unwrap();
 
-        // Add more XP
-        store.add_xp(123, 456, 50).await.unwrap();
-        let xp = store.get_xp(123, 456).await.unwrap();
-        assert_eq!(xp, 150);
+        let saved = store.get_user_profile(123, 456).await.unwrap().unwrap();
+        assert_eq!(saved.total_xp, 150);
+        assert!(store.get_user_profile(123, 999).await.unwrap().is_none());
     }
 
     #[tokio::test]
@@ -348,12 +430,16 @@ mod tests {
         let store = InMemoryXpStore::new();
 
         // Add XP for multiple users in the same guild
-        store.add_xp(1, 100, 500).await.unwrap();
-        store.add_xp(2, 100, 300).await.unwrap();
-        store.add_xp(3, 100, 700).await.unwrap();
-        store.add_xp(4, 200, 400).await.unwrap(); // Different guild
+        for (user_id, guild_id, xp) in [(1, 100, 500), (2, 100, 300), (3, 100, 700), (4, 200, 400)]
+        {
+            let mut profile = UserProfile::default_with_ids(user_id, guild_id);
+            profile.total_xp = xp;
+            store.save_user_profile(profile).await.unwrap();
+        }
+
+        let service = LevelingService::new(store);
 
-        let leaderboard = store.get_leaderboard(100, 10).await.unwrap();
+        let leaderboard = service.get_leaderboard(100, 10).await.unwrap();
 
         // Should have 3 users from guild 100
         assert_eq!(leaderboard.len(), 3);
@@ -362,5 +448,21 @@ mod tests {
         assert_eq!(leaderboard[0].user_id, 3); // 700 XP
         assert_eq!(leaderboard[1].user_id, 1); // 500 XP
         assert_eq!(leaderboard[2].user_id, 2); // 300 XP
+
+        // Paging and ranks
+        let page = service.get_leaderboard_page(100, 2, 2, &[]).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].user_id, 2);
+        assert_eq!(service.count_ranked_users(100, &[]).await.unwrap(), 3);
+        assert_eq!(service.get_user_rank(1, 100, &[]).await.unwrap(), Some(2));
+        assert_eq!(service.get_user_rank(9, 100, &[]).await.unwrap(), None);
+
+        // Leaving out a bot moves everyone below it up a place
+        let page = service.get_leaderboard_page(100, 1, 2, &[3]).await.unwrap();
+        let ids: Vec<u64> = page.iter().map(|stats| stats.user_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(service.count_ranked_users(100, &[3]).await.unwrap(), 2);
+        assert_eq!(service.get_user_rank(1, 100, &[3]).await.unwrap(), Some(1));
+        assert_eq!(service.get_user_rank(3, 100, &[3]).await.unwrap(), None);
     }
 }
@@ -1,9 +1,12 @@
 // Implementations for the leveling system.
 #![allow(unused_imports)]
 
+pub mod achievements_file;
 pub mod in_memory;
+pub mod migrations;
 pub mod sqlite_store;
 
 // Re-export for convenience
+pub use achievements_file::load_achievements;
 pub use in_memory::InMemoryXpStore;
 pub use sqlite_store::SqliteXpStore;
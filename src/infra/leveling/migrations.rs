@@ -0,0 +1,403 @@
+// Versioned schema migrations for the leveling database.
+//
+// **How it works:**
+// - `schema_version` records every migration that has been applied.
+// - On startup we run each migration with a version above the current one,
+//   in order, each inside its own transaction.
+// - Every step is idempotent (`CREATE ... IF NOT EXISTS`, "add column unless it
+//   exists"), so databases created before this table existed - which already
+//   have some of the columns - upgrade cleanly from version 0.
+//
+// **Adding a new profile field:** append a `Migration` with the next version
+// number and an `AddColumn` step with a sensible default. Never edit or reorder
+// migrations that have already shipped.
+
+use chrono::Utc;
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+
+/// A single schema change.
+enum Step {
+    /// Raw SQL (must be safe to re-run, e.g. `IF NOT EXISTS`).
+    Sql(&'static str),
+    /// `ALTER TABLE ... ADD COLUMN`, skipped if the column already exists.
+    /// SQLite has no `ADD COLUMN IF NOT EXISTS`, so we check `PRAGMA table_info`.
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    },
+}
+
+struct Migration {
+    version: i64,
+    description: &'static str,
+    steps: &'static [Step],
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create user_profiles",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS user_profiles (
+                user_id INTEGER NOT NULL,
+                guild_id INTEGER NOT NULL,
+                level INTEGER NOT NULL DEFAULT 1,
+                total_xp INTEGER NOT NULL DEFAULT 0,
+                xp_to_next_level INTEGER NOT NULL DEFAULT 100,
+                total_commands_used INTEGER NOT NULL DEFAULT 0,
+                total_messages INTEGER NOT NULL DEFAULT 0,
+                last_daily TEXT,
+                daily_streak INTEGER NOT NULL DEFAULT 0,
+                last_message_timestamp TEXT,
+                achievements TEXT NOT NULL DEFAULT '[]',
+                best_rank INTEGER NOT NULL DEFAULT 999,
+                previous_rank INTEGER NOT NULL DEFAULT 999,
+                rank_improvement INTEGER NOT NULL DEFAULT 0,
+                images_shared INTEGER NOT NULL DEFAULT 0,
+                long_messages INTEGER NOT NULL DEFAULT 0,
+                links_shared INTEGER NOT NULL DEFAULT 0,
+                goals_completed INTEGER NOT NULL DEFAULT 0,
+                boost_days INTEGER NOT NULL DEFAULT 0,
+                first_boost_date TEXT,
+                PRIMARY KEY (user_id, guild_id)
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 2,
+        description: "add prestige_level",
+        steps: &[Step::AddColumn {
+            table: "user_profiles",
+            column: "prestige_level",
+            definition: "INTEGER NOT NULL DEFAULT 0",
+        }],
+    },
+    Migration {
+        version: 3,
+        description: "add xp_history",
+        steps: &[Step::AddColumn {
+            table: "user_profiles",
+            column: "xp_history",
+            definition: "TEXT NOT NULL DEFAULT '[]'",
+        }],
+    },
+    Migration {
+        version: 4,
+        description: "create daily_goals",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS daily_goals (
+                guild_id INTEGER PRIMARY KEY,
+                date TEXT NOT NULL,
+                target INTEGER NOT NULL,
+                progress INTEGER NOT NULL,
+                claimers TEXT NOT NULL DEFAULT '[]',
+                completed BOOLEAN NOT NULL DEFAULT 0,
+                bonus_awarded_to TEXT NOT NULL DEFAULT '[]'
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 5,
+        description: "index leaderboard queries",
+        steps: &[Step::Sql(
+            "CREATE INDEX IF NOT EXISTS idx_user_profiles_guild_xp ON user_profiles(guild_id, total_xp DESC);",
+        )],
+    },
+    Migration {
+        version: 6,
+        description: "index paged leaderboard order",
+        steps: &[Step::Sql(
+            "CREATE INDEX IF NOT EXISTS idx_user_profiles_guild_rank ON user_profiles(guild_id, prestige_level DESC, total_xp DESC);",
+        )],
+    },
+    Migration {
+        version: 7,
+        description: "create channel_xp_multipliers",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS channel_xp_multipliers (
+                guild_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                multiplier REAL NOT NULL,
+                PRIMARY KEY (guild_id, channel_id)
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 8,
+        description: "add earned_achievements",
+        steps: &[Step::AddColumn {
+            table: "user_profiles",
+            column: "earned_achievements",
+            definition: "TEXT NOT NULL DEFAULT '[]'",
+        }],
+    },
+    Migration {
+        version: 9,
+        description: "create voice_minutes",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS voice_minutes (
+                user_id INTEGER NOT NULL,
+                guild_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                minutes INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (user_id, guild_id, channel_id)
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 10,
+        description: "create guild_leveling_settings",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS guild_leveling_settings (
+                guild_id INTEGER PRIMARY KEY,
+                level_up_mention TEXT NOT NULL
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 11,
+        description: "add daily_timezone",
+        steps: &[Step::AddColumn {
+            table: "guild_leveling_settings",
+            column: "daily_timezone",
+            definition: "TEXT",
+        }],
+    },
+    Migration {
+        version: 12,
+        description: "create xp_boosts",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS xp_boosts (
+                user_id INTEGER NOT NULL,
+                guild_id INTEGER NOT NULL,
+                multiplier REAL NOT NULL,
+                expires_at TEXT NOT NULL,
+                PRIMARY KEY (user_id, guild_id)
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 13,
+        description: "add emoji and sticker counters",
+        steps: &[
+            Step::AddColumn {
+                table: "user_profiles",
+                column: "emojis_used",
+                definition: "INTEGER NOT NULL DEFAULT 0",
+            },
+            Step::AddColumn {
+                table: "user_profiles",
+                column: "stickers_used",
+                definition: "INTEGER NOT NULL DEFAULT 0",
+            },
+        ],
+    },
+    Migration {
+        version: 14,
+        description: "create excluded_xp_channels",
+        steps: &[Step::Sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS excluded_xp_channels (
+                guild_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                PRIMARY KEY (guild_id, channel_id)
+            );
+            "#,
+        )],
+    },
+    Migration {
+        version: 15,
+        description: "break leaderboard ties by user ID",
+        steps: &[
+            Step::Sql("DROP INDEX IF EXISTS idx_user_profiles_guild_rank;"),
+            Step::Sql(
+                "CREATE INDEX IF NOT EXISTS idx_user_profiles_guild_rank_user ON user_profiles(guild_id, prestige_level DESC, total_xp DESC, user_id);",
+            ),
+        ],
+    },
+    Migration {
+        version: 16,
+        description: "queue xp_boosts by multiplier",
+        steps: &[
+            Step::Sql(
+                r#"
+                CREATE TABLE IF NOT EXISTS xp_boosts_queue (
+                    user_id INTEGER NOT NULL,
+                    guild_id INTEGER NOT NULL,
+                    multiplier REAL NOT NULL,
+                    expires_at TEXT NOT NULL,
+                    PRIMARY KEY (user_id, guild_id, multiplier)
+                );
+                "#,
+            ),
+            Step::Sql(
+                "INSERT INTO xp_boosts_queue (user_id, guild_id, multiplier, expires_at) SELECT user_id, guild_id, multiplier, expires_at FROM xp_boosts;",
+            ),
+            Step::Sql("DROP TABLE xp_boosts;"),
+            Step::Sql("ALTER TABLE xp_boosts_queue RENAME TO xp_boosts;"),
+        ],
+    },
+];
+
+/// Highest schema version known to this build.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Currently applied schema version (0 for a fresh or pre-versioning database).
+pub async fn current_version(pool: &Pool<Sqlite>) -> anyhow::Result<i64> {
+    ensure_version_table(pool).await?;
+    let row = sqlx::query("SELECT COALESCE(MAX(version), 0) AS version FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+    Ok(row.get("version"))
+}
+
+/// Apply all pending migrations.
+pub async fn run_migrations(pool: &Pool<Sqlite>) -> anyhow::Result<()> {
+    let current = current_version(pool).await?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let mut tx = pool.begin().await?;
+
+        for step in migration.steps {
+            apply_step(&mut tx, step).await?;
+        }
+
+        sqlx::query(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)",
+        )
+        .bind(migration.version)
+        .bind(migration.description)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(
+            version = migration.version,
+            description = migration.description,
+            "Applied leveling DB migration"
+        );
+    }
+
+    Ok(())
+}
+
+async fn ensure_version_table(pool: &Pool<Sqlite>) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        );
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn apply_step(conn: &mut SqliteConnection, step: &Step) -> anyhow::Result<()> {
+    match step {
+        Step::Sql(sql) => {
+            sqlx::query(sql).execute(&mut *conn).await?;
+        }
+        Step::AddColumn {
+            table,
+            column,
+            definition,
+        } => {
+            let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+                .fetch_all(&mut *conn)
+                .await?;
+            let exists = columns.iter().any(|row| {
+                let name: String = row.get("name");
+                name == *column
+            });
+
+            if !exists {
+                sqlx::query(&format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    table, column, definition
+                ))
+                .execute(&mut *conn)
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::leveling::XpStore;
+    use crate::infra::leveling::SqliteXpStore;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_migrating_old_schema_preserves_rows_and_backfills_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("leveling.db");
+        let db_url = format!("sqlite://{}", db_path.display());
+
+        // An "old" database: no schema_version, no prestige_level / xp_history
+        {
+            let options = SqliteConnectOptions::from_str(&db_url)
+                .unwrap()
+                .create_if_missing(true);
+            let pool = SqlitePoolOptions::new()
+                .connect_with(options)
+                .await
+                .unwrap();
+            for step in MIGRATIONS[0].steps {
+                let mut conn = pool.acquire().await.unwrap();
+                apply_step(&mut conn, step).await.unwrap();
+            }
+            sqlx::query(
+                "INSERT INTO user_profiles (user_id, guild_id, level, total_xp, daily_streak) VALUES (1, 2, 7, 1234, 3)",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            pool.close().await;
+        }
+
+        let store = SqliteXpStore::new(&db_url).await.unwrap();
+        let profile = store.get_user_profile(1, 2).await.unwrap().unwrap();
+        assert_eq!(profile.level, 7);
+        assert_eq!(profile.total_xp, 1234);
+        assert_eq!(profile.daily_streak, 3);
+        assert_eq!(profile.prestige_level, 0);
+        assert!(profile.xp_history.is_empty());
+        drop(store);
+
+        // Re-running is a no-op and the version is recorded
+        let store = SqliteXpStore::new(&db_url).await.unwrap();
+        assert!(store.get_user_profile(1, 2).await.unwrap().is_some());
+
+        let options = SqliteConnectOptions::from_str(&db_url).unwrap();
+        let pool = SqlitePoolOptions::new()
+            .connect_with(options)
+            .await
+            .unwrap();
+        assert_eq!(current_version(&pool).await.unwrap(), latest_version());
+    }
+}
@@ -1,13 +1,24 @@
-use crate::core::leveling::{DailyGoal, LevelingError, UserProfile, UserStats, XpEvent, XpStore};
+use crate::core::leveling::{
+    DailyGoal, LevelUpMention, LevelingError, UserProfile, UserStats, XpBoost, XpEvent, XpStore,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sqlx::sqlite::SqlitePoolOptions;
 use sqlx::{Pool, Row, Sqlite};
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
 use std::str::FromStr;
 use std::time::Instant;
 
+/// Columns read by `row_to_profile`. Listed explicitly rather than `SELECT *`:
+/// a pooled connection opened before a migration added a column can otherwise
+/// report a stale column count for `*`.
+const PROFILE_COLUMNS: &str = "user_id, guild_id, level, total_xp, xp_to_next_level,
+    total_commands_used, total_messages, last_daily, daily_streak, last_message_timestamp,
+    achievements, earned_achievements, best_rank, previous_rank, rank_improvement,
+    images_shared, long_messages, links_shared, emojis_used, stickers_used, goals_completed,
+    boost_days, first_boost_date, prestige_level, xp_history";
+
 pub struct SqliteXpStore {
     pool: Pool<Sqlite>,
 }
@@ -42,173 +53,96 @@ impl SqliteXpStore {
         Ok(store)
     }
 
+    /// Bring the schema up to date (see `migrations.rs`).
     async fn migrate(&self) -> anyhow::Result<()> {
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS user_profiles (
-                user_id INTEGER NOT NULL,
-                guild_id INTEGER NOT NULL,
-                level INTEGER NOT NULL DEFAULT 1,
-                total_xp INTEGER NOT NULL DEFAULT 0,
-                xp_to_next_level INTEGER NOT NULL DEFAULT 100,
-                total_commands_used INTEGER NOT NULL DEFAULT 0,
-                total_messages INTEGER NOT NULL DEFAULT 0,
-                last_daily TEXT,
-                daily_streak INTEGER NOT NULL DEFAULT 0,
-                last_message_timestamp TEXT,
-                achievements TEXT NOT NULL DEFAULT '[]',
-                best_rank INTEGER NOT NULL DEFAULT 999,
-                previous_rank INTEGER NOT NULL DEFAULT 999,
-                rank_improvement INTEGER NOT NULL DEFAULT 0,
-                images_shared INTEGER NOT NULL DEFAULT 0,
-                long_messages INTEGER NOT NULL DEFAULT 0,
-                links_shared INTEGER NOT NULL DEFAULT 0,
-                goals_completed INTEGER NOT NULL DEFAULT 0,
-                boost_days INTEGER NOT NULL DEFAULT 0,
-                first_boost_date TEXT,
-                prestige_level INTEGER NOT NULL DEFAULT 0,
-                xp_history TEXT NOT NULL DEFAULT '[]',
-                PRIMARY KEY (user_id, guild_id)
-            );
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Migration: Add prestige_level column if it doesn't exist (for existing databases)
-        // SQLite doesn't support IF NOT EXISTS for ALTER TABLE, so we need to check if the column exists
-        let column_check = sqlx::query("PRAGMA table_info(user_profiles)")
-            .fetch_all(&self.pool)
-            .await?;
-
-        let has_prestige_level = column_check.iter().any(|row| {
-            let name: String = row.get("name");
-            name == "prestige_level"
-        });
-
-        if !has_prestige_level {
-            sqlx::query(
-                "ALTER TABLE user_profiles ADD COLUMN prestige_level INTEGER NOT NULL DEFAULT 0",
-            )
-            .execute(&self.pool)
-            .await?;
-        }
-
-        // Migration: Add xp_history column if it doesn't exist (for existing databases)
-        let has_xp_history = column_check.iter().any(|row| {
-            let name: String = row.get("name");
-            name == "xp_history"
-        });
-
-        if !has_xp_history {
-            sqlx::query(
-                "ALTER TABLE user_profiles ADD COLUMN xp_history TEXT NOT NULL DEFAULT '[]'",
-            )
-            .execute(&self.pool)
-            .await?;
-        }
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS daily_goals (
-                guild_id INTEGER PRIMARY KEY,
-                date TEXT NOT NULL,
-                target INTEGER NOT NULL,
-                progress INTEGER NOT NULL,
-                claimers TEXT NOT NULL DEFAULT '[]',
-                completed BOOLEAN NOT NULL DEFAULT 0,
-                bonus_awarded_to TEXT NOT NULL DEFAULT '[]'
-            );
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
-
-        // Performance optimization for leaderboard queries
-        sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_user_profiles_guild_xp ON user_profiles(guild_id, total_xp DESC);"
-        )
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        super::migrations::run_migrations(&self.pool).await
     }
 }
 
 #[async_trait]
 impl XpStore for SqliteXpStore {
-    async fn get_xp(&self, user_id: u64, guild_id: u64) -> Result<u64, LevelingError> {
-        let result =
-            sqlx::query("SELECT total_xp FROM user_profiles WHERE user_id = ? AND guild_id = ?")
-                .bind(user_id as i64)
-                .bind(guild_id as i64)
-                .fetch_optional(&self.pool)
-                .await
-                .map_err(|e| LevelingError::StorageError(e.to_string()))?;
-
-        Ok(result.map(|row| row.get::<i64, _>(0) as u64).unwrap_or(0))
-    }
-
-    async fn add_xp(&self, user_id: u64, guild_id: u64, amount: u64) -> Result<(), LevelingError> {
-        // This is a bit complex because we need to initialize the row if it doesn't exist.
-        // However, usually we load the profile first.
-        // For a pure add_xp, we can do an UPSERT.
-        // But we also need to update level, etc. which this method doesn't do.
-        // The trait definition implies just adding to the counter.
-        // We'll assume the profile exists or create a default one.
-
-        // Note: This implementation is simplistic and doesn't handle level up logic.
-        // The service layer usually handles the logic.
-
-        sqlx::query(
-            r#"
-            INSERT INTO user_profiles (user_id, guild_id, total_xp)
-            VALUES (?, ?, ?)
-            ON CONFLICT(user_id, guild_id) DO UPDATE SET
-            total_xp = total_xp + excluded.total_xp
-            "#,
-        )
-        .bind(user_id as i64)
-        .bind(guild_id as i64)
-        .bind(amount as i64)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| LevelingError::StorageError(e.to_string()))?;
-
-        Ok(())
-    }
-
-    async fn get_leaderboard(
+    async fn get_leaderboard_page(
         &self,
         guild_id: u64,
-        limit: usize,
+        page: usize,
+        per_page: usize,
+        excluded: &[u64],
     ) -> Result<Vec<UserStats>, LevelingError> {
+        let offset = page.saturating_sub(1).saturating_mul(per_page);
         let rows = sqlx::query(
-            "SELECT user_id, guild_id, total_xp, level, prestige_level FROM user_profiles WHERE guild_id = ? ORDER BY prestige_level DESC, total_xp DESC LIMIT ?"
+            "SELECT user_id, guild_id, total_xp, level, prestige_level FROM user_profiles
+             WHERE guild_id = ? AND user_id NOT IN (SELECT value FROM json_each(?))
+             ORDER BY prestige_level DESC, total_xp DESC, user_id ASC
+             LIMIT ? OFFSET ?",
         )
         .bind(guild_id as i64)
-        .bind(limit as i64)
+        .bind(excluded_json(excluded)?)
+        .bind(per_page as i64)
+        .bind(offset as i64)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        .map_err(LevelingError::storage)?;
 
         let stats = rows
             .iter()
-            .map(|row| {
-                UserStats {
-                    user_id: row.get::<i64, _>("user_id") as u64,
-                    guild_id: row.get::<i64, _>("guild_id") as u64,
-                    xp: row.get::<i64, _>("total_xp") as u64,
-                    level: row.get::<i64, _>("level") as u32,
-                    prestige_level: row.get::<i64, _>("prestige_level") as u32,
-                    last_xp_gain: None, // Not stored in DB as Instant
-                }
+            .map(|row| UserStats {
+                user_id: row.get::<i64, _>("user_id") as u64,
+                guild_id: row.get::<i64, _>("guild_id") as u64,
+                xp: row.get::<i64, _>("total_xp") as u64,
+                level: row.get::<i64, _>("level") as u32,
+                prestige_level: row.get::<i64, _>("prestige_level") as u32,
+                last_xp_gain: None, // Not stored in DB as Instant
             })
             .collect();
 
         Ok(stats)
     }
 
+    async fn count_profiles(&self, guild_id: u64, excluded: &[u64]) -> Result<u64, LevelingError> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS count FROM user_profiles
+             WHERE guild_id = ? AND user_id NOT IN (SELECT value FROM json_each(?))",
+        )
+        .bind(guild_id as i64)
+        .bind(excluded_json(excluded)?)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
+
+        Ok(row.get::<i64, _>("count") as u64)
+    }
+
+    async fn count_ranked_above(
+        &self,
+        guild_id: u64,
+        prestige_level: u32,
+        total_xp: u64,
+        user_id: u64,
+        excluded: &[u64],
+    ) -> Result<u64, LevelingError> {
+        // Same order as `get_leaderboard_page`: lower user IDs win ties
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS count FROM user_profiles
+             WHERE guild_id = ? AND user_id NOT IN (SELECT value FROM json_each(?))
+               AND (prestige_level > ?
+                    OR (prestige_level = ? AND total_xp > ?)
+                    OR (prestige_level = ? AND total_xp = ? AND user_id < ?))",
+        )
+        .bind(guild_id as i64)
+        .bind(excluded_json(excluded)?)
+        .bind(prestige_level as i64)
+        .bind(prestige_level as i64)
+        .bind(total_xp as i64)
+        .bind(prestige_level as i64)
+        .bind(total_xp as i64)
+        .bind(user_id as i64)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
+
+        Ok(row.get::<i64, _>("count") as u64)
+    }
+
     async fn get_streak_leaderboard(
         &self,
         guild_id: u64,
@@ -217,9 +151,9 @@ impl XpStore for SqliteXpStore {
         let rows = sqlx::query(
             "SELECT user_id, guild_id, level, total_xp, xp_to_next_level, total_commands_used, 
                     total_messages, last_daily, daily_streak, last_message_timestamp, achievements,
-                    best_rank, previous_rank, rank_improvement, images_shared, long_messages,
-                    links_shared, goals_completed, boost_days, first_boost_date, prestige_level,
-                    xp_history
+                    earned_achievements, best_rank, previous_rank, rank_improvement, images_shared, long_messages,
+                    links_shared, emojis_used, stickers_used, goals_completed, boost_days,
+                    first_boost_date, prestige_level, xp_history
              FROM user_profiles 
              WHERE guild_id = ? AND daily_streak > 0
              ORDER BY daily_streak DESC, last_daily DESC 
@@ -229,7 +163,7 @@ impl XpStore for SqliteXpStore {
         .bind(limit as i64)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        .map_err(LevelingError::storage)?;
 
         let profiles = rows
             .iter()
@@ -238,6 +172,9 @@ impl XpStore for SqliteXpStore {
                 let achievements: Vec<String> =
                     serde_json::from_str(&achievements_json).unwrap_or_default();
 
+                let earned_json: String = row.get("earned_achievements");
+                let earned_achievements = serde_json::from_str(&earned_json).unwrap_or_default();
+
                 let xp_history_json: String = row.get("xp_history");
                 let xp_history: VecDeque<XpEvent> =
                     serde_json::from_str(&xp_history_json).unwrap_or_default();
@@ -254,12 +191,15 @@ impl XpStore for SqliteXpStore {
                     daily_streak: row.get::<i64, _>("daily_streak") as u32,
                     last_message_timestamp: row.get::<Option<DateTime<Utc>>, _>("last_message_timestamp"),
                     achievements,
+                    earned_achievements,
                     best_rank: row.get::<i64, _>("best_rank") as u32,
                     previous_rank: row.get::<i64, _>("previous_rank") as u32,
                     rank_improvement: row.get::<i64, _>("rank_improvement") as u32,
                     images_shared: row.get::<i64, _>("images_shared") as u64,
                     long_messages: row.get::<i64, _>("long_messages") as u64,
                     links_shared: row.get::<i64, _>("links_shared") as u64,
+                    emojis_used: row.get::<i64, _>("emojis_used") as u64,
+                    stickers_used: row.get::<i64, _>("stickers_used") as u64,
                     goals_completed: row.get::<i64, _>("goals_completed") as u64,
                     boost_days: row.get::<i64, _>("boost_days") as u64,
                     first_boost_date: row.get::<Option<DateTime<Utc>>, _>("first_boost_date"),
@@ -288,7 +228,7 @@ impl XpStore for SqliteXpStore {
         .bind(guild_id as i64)
         .execute(&self.pool)
         .await
-        .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        .map_err(LevelingError::storage)?;
         Ok(())
     }
 
@@ -311,12 +251,15 @@ impl XpStore for SqliteXpStore {
         user_id: u64,
         guild_id: u64,
     ) -> Result<Option<UserProfile>, LevelingError> {
-        let row = sqlx::query("SELECT * FROM user_profiles WHERE user_id = ? AND guild_id = ?")
-            .bind(user_id as i64)
-            .bind(guild_id as i64)
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM user_profiles WHERE user_id = ? AND guild_id = ?",
+            PROFILE_COLUMNS
+        ))
+        .bind(user_id as i64)
+        .bind(guild_id as i64)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
 
         if let Some(row) = row {
             Ok(Some(row_to_profile(&row)?))
@@ -326,10 +269,12 @@ impl XpStore for SqliteXpStore {
     }
 
     async fn save_user_profile(&self, profile: UserProfile) -> Result<(), LevelingError> {
-        let achievements_json = serde_json::to_string(&profile.achievements)
-            .map_err(|e| LevelingError::StorageError(e.to_string()))?;
-        let xp_history_json = serde_json::to_string(&profile.xp_history)
-            .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        let achievements_json =
+            serde_json::to_string(&profile.achievements).map_err(LevelingError::storage)?;
+        let earned_json =
+            serde_json::to_string(&profile.earned_achievements).map_err(LevelingError::storage)?;
+        let xp_history_json =
+            serde_json::to_string(&profile.xp_history).map_err(LevelingError::storage)?;
 
         sqlx::query(
             r#"
@@ -338,8 +283,9 @@ impl XpStore for SqliteXpStore {
                 total_commands_used, total_messages, last_daily, daily_streak,
                 last_message_timestamp, achievements, best_rank, previous_rank,
                 rank_improvement, images_shared, long_messages, links_shared,
-                goals_completed, boost_days, first_boost_date, prestige_level, xp_history
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                goals_completed, boost_days, first_boost_date, prestige_level, xp_history,
+                earned_achievements, emojis_used, stickers_used
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(user_id, guild_id) DO UPDATE SET
                 level = excluded.level,
                 total_xp = excluded.total_xp,
@@ -360,7 +306,10 @@ impl XpStore for SqliteXpStore {
                 boost_days = excluded.boost_days,
                 first_boost_date = excluded.first_boost_date,
                 prestige_level = excluded.prestige_level,
-                xp_history = excluded.xp_history
+                xp_history = excluded.xp_history,
+                earned_achievements = excluded.earned_achievements,
+                emojis_used = excluded.emojis_used,
+                stickers_used = excluded.stickers_used
             "#,
         )
         .bind(profile.user_id as i64)
@@ -385,19 +334,25 @@ impl XpStore for SqliteXpStore {
         .bind(profile.first_boost_date)
         .bind(profile.prestige_level as i64)
         .bind(xp_history_json)
+        .bind(earned_json)
+        .bind(profile.emojis_used as i64)
+        .bind(profile.stickers_used as i64)
         .execute(&self.pool)
         .await
-        .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        .map_err(LevelingError::storage)?;
 
         Ok(())
     }
 
     async fn get_all_profiles(&self, guild_id: u64) -> Result<Vec<UserProfile>, LevelingError> {
-        let rows = sqlx::query("SELECT * FROM user_profiles WHERE guild_id = ?")
-            .bind(guild_id as i64)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM user_profiles WHERE guild_id = ?",
+            PROFILE_COLUMNS
+        ))
+        .bind(guild_id as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
 
         let mut profiles = Vec::new();
         for row in rows {
@@ -411,7 +366,7 @@ impl XpStore for SqliteXpStore {
             .bind(guild_id as i64)
             .fetch_optional(&self.pool)
             .await
-            .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+            .map_err(LevelingError::storage)?;
 
         if let Some(row) = row {
             let claimers_json: String = row.get("claimers");
@@ -431,10 +386,10 @@ impl XpStore for SqliteXpStore {
     }
 
     async fn save_daily_goal(&self, guild_id: u64, goal: DailyGoal) -> Result<(), LevelingError> {
-        let claimers_json = serde_json::to_string(&goal.claimers)
-            .map_err(|e| LevelingError::StorageError(e.to_string()))?;
-        let bonus_json = serde_json::to_string(&goal.bonus_awarded_to)
-            .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        let claimers_json =
+            serde_json::to_string(&goal.claimers).map_err(LevelingError::storage)?;
+        let bonus_json =
+            serde_json::to_string(&goal.bonus_awarded_to).map_err(LevelingError::storage)?;
 
         sqlx::query(
             r#"
@@ -458,14 +413,290 @@ impl XpStore for SqliteXpStore {
         .bind(bonus_json)
         .execute(&self.pool)
         .await
-        .map_err(|e| LevelingError::StorageError(e.to_string()))?;
+        .map_err(LevelingError::storage)?;
+
+        Ok(())
+    }
+
+    async fn get_channel_multipliers(
+        &self,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, f64>, LevelingError> {
+        let rows = sqlx::query(
+            "SELECT channel_id, multiplier FROM channel_xp_multipliers WHERE guild_id = ?",
+        )
+        .bind(guild_id as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get::<i64, _>("channel_id") as u64,
+                    row.get::<f64, _>("multiplier"),
+                )
+            })
+            .collect())
+    }
+
+    async fn set_channel_multiplier(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        multiplier: Option<f64>,
+    ) -> Result<(), LevelingError> {
+        let query = match multiplier {
+            Some(multiplier) => sqlx::query(
+                r#"
+                INSERT INTO channel_xp_multipliers (guild_id, channel_id, multiplier)
+                VALUES (?, ?, ?)
+                ON CONFLICT(guild_id, channel_id) DO UPDATE SET multiplier = excluded.multiplier
+                "#,
+            )
+            .bind(guild_id as i64)
+            .bind(channel_id as i64)
+            .bind(multiplier),
+            None => sqlx::query(
+                "DELETE FROM channel_xp_multipliers WHERE guild_id = ? AND channel_id = ?",
+            )
+            .bind(guild_id as i64)
+            .bind(channel_id as i64),
+        };
+
+        query
+            .execute(&self.pool)
+            .await
+            .map_err(LevelingError::storage)?;
 
         Ok(())
     }
+
+    async fn get_excluded_channels(&self, guild_id: u64) -> Result<HashSet<u64>, LevelingError> {
+        let rows = sqlx::query("SELECT channel_id FROM excluded_xp_channels WHERE guild_id = ?")
+            .bind(guild_id as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(LevelingError::storage)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<i64, _>("channel_id") as u64)
+            .collect())
+    }
+
+    async fn set_channel_excluded(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        excluded: bool,
+    ) -> Result<(), LevelingError> {
+        let query = if excluded {
+            sqlx::query(
+                "INSERT OR IGNORE INTO excluded_xp_channels (guild_id, channel_id) VALUES (?, ?)",
+            )
+        } else {
+            sqlx::query("DELETE FROM excluded_xp_channels WHERE guild_id = ? AND channel_id = ?")
+        };
+
+        query
+            .bind(guild_id as i64)
+            .bind(channel_id as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(LevelingError::storage)?;
+
+        Ok(())
+    }
+
+    async fn get_voice_minutes(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<HashMap<u64, u64>, LevelingError> {
+        let rows = sqlx::query(
+            "SELECT channel_id, minutes FROM voice_minutes WHERE user_id = ? AND guild_id = ?",
+        )
+        .bind(user_id as i64)
+        .bind(guild_id as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get::<i64, _>("channel_id") as u64,
+                    row.get::<i64, _>("minutes") as u64,
+                )
+            })
+            .collect())
+    }
+
+    async fn add_voice_minutes(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        channel_id: u64,
+        minutes: u64,
+    ) -> Result<(), LevelingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO voice_minutes (user_id, guild_id, channel_id, minutes)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(user_id, guild_id, channel_id) DO UPDATE SET minutes = minutes + excluded.minutes
+            "#,
+        )
+        .bind(user_id as i64)
+        .bind(guild_id as i64)
+        .bind(channel_id as i64)
+        .bind(minutes as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
+
+        Ok(())
+    }
+
+    async fn get_level_up_mention(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<LevelUpMention>, LevelingError> {
+        let row =
+            sqlx::query("SELECT level_up_mention FROM guild_leveling_settings WHERE guild_id = ?")
+                .bind(guild_id as i64)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(LevelingError::storage)?;
+
+        Ok(row.and_then(|row| LevelUpMention::parse(&row.get::<String, _>("level_up_mention"))))
+    }
+
+    async fn set_level_up_mention(
+        &self,
+        guild_id: u64,
+        mention: LevelUpMention,
+    ) -> Result<(), LevelingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_leveling_settings (guild_id, level_up_mention)
+            VALUES (?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET level_up_mention = excluded.level_up_mention
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(mention.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
+
+        Ok(())
+    }
+
+    async fn get_daily_timezone(&self, guild_id: u64) -> Result<Option<String>, LevelingError> {
+        let row =
+            sqlx::query("SELECT daily_timezone FROM guild_leveling_settings WHERE guild_id = ?")
+                .bind(guild_id as i64)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(LevelingError::storage)?;
+
+        Ok(row.and_then(|row| row.get("daily_timezone")))
+    }
+
+    async fn set_daily_timezone(&self, guild_id: u64, timezone: &str) -> Result<(), LevelingError> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_leveling_settings (guild_id, level_up_mention, daily_timezone)
+            VALUES (?, ?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET daily_timezone = excluded.daily_timezone
+            "#,
+        )
+        .bind(guild_id as i64)
+        // A new row needs a mention setting; keep the default
+        .bind(LevelUpMention::default().as_str())
+        .bind(timezone)
+        .execute(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
+
+        Ok(())
+    }
+
+    async fn get_xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+    ) -> Result<Vec<XpBoost>, LevelingError> {
+        let rows = sqlx::query(
+            "SELECT multiplier, expires_at FROM xp_boosts WHERE user_id = ? AND guild_id = ?",
+        )
+        .bind(user_id as i64)
+        .bind(guild_id as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(LevelingError::storage)?;
+
+        let mut boosts: Vec<XpBoost> = rows
+            .iter()
+            .filter_map(|row| {
+                let expires_at: String = row.get("expires_at");
+                Some(XpBoost {
+                    multiplier: row.get("multiplier"),
+                    expires_at: DateTime::parse_from_rfc3339(&expires_at)
+                        .ok()?
+                        .with_timezone(&Utc),
+                })
+            })
+            .collect();
+        boosts.sort_by_key(|boost| boost.expires_at);
+        Ok(boosts)
+    }
+
+    async fn set_xp_boosts(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        boosts: &[XpBoost],
+    ) -> Result<(), LevelingError> {
+        let mut tx = self.pool.begin().await.map_err(LevelingError::storage)?;
+
+        sqlx::query("DELETE FROM xp_boosts WHERE user_id = ? AND guild_id = ?")
+            .bind(user_id as i64)
+            .bind(guild_id as i64)
+            .execute(&mut *tx)
+            .await
+            .map_err(LevelingError::storage)?;
+
+        for boost in boosts {
+            sqlx::query(
+                "INSERT INTO xp_boosts (user_id, guild_id, multiplier, expires_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(user_id as i64)
+            .bind(guild_id as i64)
+            .bind(boost.multiplier)
+            .bind(boost.expires_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .map_err(LevelingError::storage)?;
+        }
+
+        tx.commit().await.map_err(LevelingError::storage)?;
+        Ok(())
+    }
+}
+
+/// User IDs as a JSON array, for `NOT IN (SELECT value FROM json_each(?))`.
+fn excluded_json(user_ids: &[u64]) -> Result<String, LevelingError> {
+    let ids: Vec<i64> = user_ids.iter().map(|&id| id as i64).collect();
+    serde_json::to_string(&ids).map_err(LevelingError::storage)
 }
 
 fn row_to_profile(row: &sqlx::sqlite::SqliteRow) -> Result<UserProfile, LevelingError> {
     let achievements_json: String = row.get("achievements");
+    let earned_json: String = row.get("earned_achievements");
     let xp_history_json: String = row.get("xp_history");
 
     Ok(UserProfile {
@@ -480,12 +711,15 @@ fn row_to_profile(row: &sqlx::sqlite::SqliteRow) -> Result<UserProfile, Leveling
         daily_streak: row.get::<i64, _>("daily_streak") as u32,
         last_message_timestamp: row.get("last_message_timestamp"),
         achievements: serde_json::from_str(&achievements_json).unwrap_or_default(),
+        earned_achievements: serde_json::from_str(&earned_json).unwrap_or_default(),
         best_rank: row.get::<i64, _>("best_rank") as u32,
         previous_rank: row.get::<i64, _>("previous_rank") as u32,
         rank_improvement: row.get::<i64, _>("rank_improvement") as u32,
         images_shared: row.get::<i64, _>("images_shared") as u64,
         long_messages: row.get::<i64, _>("long_messages") as u64,
         links_shared: row.get::<i64, _>("links_shared") as u64,
+        emojis_used: row.get::<i64, _>("emojis_used") as u64,
+        stickers_used: row.get::<i64, _>("stickers_used") as u64,
         goals_completed: row.get::<i64, _>("goals_completed") as u64,
         boost_days: row.get::<i64, _>("boost_days") as u64,
         first_boost_date: row.get("first_boost_date"),
@@ -493,3 +727,194 @@ fn row_to_profile(row: &sqlx::sqlite::SqliteRow) -> Result<UserProfile, Leveling
         xp_history: serde_json::from_str(&xp_history_json).unwrap_or_default(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_excluded_channels_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_url = format!("sqlite://{}", dir.path().join("leveling.db").display());
+        let store = SqliteXpStore::new(&db_url).await.unwrap();
+
+        store
+            .set_channel_multiplier(1, 10, Some(0.0))
+            .await
+            .unwrap();
+        store
+            .set_channel_multiplier(1, 11, Some(2.0))
+            .await
+            .unwrap();
+        store
+            .set_channel_multiplier(2, 10, Some(3.0))
+            .await
+            .unwrap();
+        store.set_channel_multiplier(1, 11, None).await.unwrap();
+
+        let channels = store.get_channel_multipliers(1).await.unwrap();
+        assert_eq!(channels, HashMap::from([(10, 0.0)]));
+
+        store.set_channel_excluded(1, 11, true).await.unwrap();
+        store.set_channel_excluded(1, 11, true).await.unwrap();
+        store.set_channel_excluded(1, 12, true).await.unwrap();
+        store.set_channel_excluded(1, 12, false).await.unwrap();
+        let excluded = store.get_excluded_channels(1).await.unwrap();
+        assert_eq!(excluded, HashSet::from([11]));
+        assert!(store.get_excluded_channels(2).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_level_up_mention_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_url = format!("sqlite://{}", dir.path().join("leveling.db").display());
+        let store = SqliteXpStore::new(&db_url).await.unwrap();
+
+        assert_eq!(store.get_level_up_mention(1).await.unwrap(), None);
+        store
+            .set_level_up_mention(1, LevelUpMention::Ping)
+            .await
+            .unwrap();
+        store
+            .set_level_up_mention(1, LevelUpMention::Silent)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get_level_up_mention(1).await.unwrap(),
+            Some(LevelUpMention::Silent)
+        );
+        assert_eq!(store.get_level_up_mention(2).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_daily_timezone_shares_the_settings_row() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_url = format!("sqlite://{}", dir.path().join("leveling.db").display());
+        let store = SqliteXpStore::new(&db_url).await.unwrap();
+
+        assert_eq!(store.get_daily_timezone(1).await.unwrap(), None);
+        store.set_daily_timezone(1, "Europe/Berlin").await.unwrap();
+        assert_eq!(
+            store.get_level_up_mention(1).await.unwrap(),
+            Some(LevelUpMention::default())
+        );
+
+        // Neither setting overwrites the other
+        store
+            .set_level_up_mention(1, LevelUpMention::Ping)
+            .await
+            .unwrap();
+        store.set_daily_timezone(1, "Asia/Tokyo").await.unwrap();
+        assert_eq!(
+            store.get_level_up_mention(1).await.unwrap(),
+            Some(LevelUpMention::Ping)
+        );
+        assert_eq!(
+            store.get_daily_timezone(1).await.unwrap().as_deref(),
+            Some("Asia/Tokyo")
+        );
+
+        store
+            .set_level_up_mention(2, LevelUpMention::Silent)
+            .await
+            .unwrap();
+        assert_eq!(store.get_daily_timezone(2).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_page_and_rank_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_url = format!("sqlite://{}", dir.path().join("leveling.db").display());
+        let store = SqliteXpStore::new(&db_url).await.unwrap();
+
+        for (user_id, prestige, xp) in [
+            (1, 0, 500),
+            (2, 0, 300),
+            (3, 1, 10),
+            (4, 0, 700),
+            (6, 0, 300),
+        ] {
+            let mut profile = UserProfile::default_with_ids(user_id, 100);
+            profile.prestige_level = prestige;
+            profile.total_xp = xp;
+            store.save_user_profile(profile).await.unwrap();
+        }
+        store
+            .save_user_profile(UserProfile::default_with_ids(5, 200))
+            .await
+            .unwrap();
+
+        // Prestige outranks raw XP
+        let first = store.get_leaderboard_page(100, 1, 2, &[]).await.unwrap();
+        let ids: Vec<u64> = first.iter().map(|s| s.user_id).collect();
+        assert_eq!(ids, vec![3, 4]);
+
+        // Users 2 and 6 are tied; the lower ID comes first
+        let second = store.get_leaderboard_page(100, 2, 2, &[]).await.unwrap();
+        let ids: Vec<u64> = second.iter().map(|s| s.user_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        let third = store.get_leaderboard_page(100, 3, 2, &[]).await.unwrap();
+        let ids: Vec<u64> = third.iter().map(|s| s.user_id).collect();
+        assert_eq!(ids, vec![6]);
+
+        let past_end = store.get_leaderboard_page(100, 4, 2, &[]).await.unwrap();
+        assert!(past_end.is_empty());
+        assert_eq!(store.count_profiles(100, &[]).await.unwrap(), 5);
+        for (prestige, xp, user_id, above) in [
+            (0, 500, 1, 2),
+            (1, 10, 3, 0),
+            (0, 300, 2, 3),
+            (0, 300, 6, 4),
+        ] {
+            let count = store
+                .count_ranked_above(100, prestige, xp, user_id, &[])
+                .await
+                .unwrap();
+            assert_eq!(count, above);
+        }
+
+        // Excluded users (bots) don't take up places
+        let first = store.get_leaderboard_page(100, 1, 2, &[3]).await.unwrap();
+        let ids: Vec<u64> = first.iter().map(|s| s.user_id).collect();
+        assert_eq!(ids, vec![4, 1]);
+        assert_eq!(store.count_profiles(100, &[3, 4]).await.unwrap(), 3);
+        let count = store
+            .count_ranked_above(100, 0, 500, 1, &[3])
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_xp_boost_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_url = format!("sqlite://{}", dir.path().join("leveling.db").display());
+        let store = SqliteXpStore::new(&db_url).await.unwrap();
+
+        assert!(store.get_xp_boosts(1, 1).await.unwrap().is_empty());
+        let expires_at = DateTime::parse_from_rfc3339("2030-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let first = XpBoost {
+            multiplier: 2.0,
+            expires_at,
+        };
+        let second = XpBoost {
+            multiplier: 1.5,
+            expires_at: expires_at + chrono::Duration::minutes(30),
+        };
+        store.set_xp_boosts(1, 1, &[first]).await.unwrap();
+        store.set_xp_boosts(1, 1, &[first, second]).await.unwrap();
+
+        assert_eq!(
+            store.get_xp_boosts(1, 1).await.unwrap(),
+            vec![first, second]
+        );
+        assert!(store.get_xp_boosts(1, 2).await.unwrap().is_empty());
+
+        // Replacing the queue drops boosts that aren't in it any more
+        store.set_xp_boosts(1, 1, &[second]).await.unwrap();
+        assert_eq!(store.get_xp_boosts(1, 1).await.unwrap(), vec![second]);
+    }
+}
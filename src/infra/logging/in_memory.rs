@@ -0,0 +1,137 @@
+// In-memory implementation of LogConfigStore.
+//
+// Used when `STORAGE_BACKEND=memory` (quick local testing, ephemeral deployments)
+// and in tests. Everything is lost on restart.
+
+use crate::core::logging::{CommandAudit, LogConfig, LogConfigStore};
+use anyhow::Result;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::collections::VecDeque;
+
+pub struct InMemoryLogStore {
+    configs: DashMap<u64, LogConfig>,
+    /// Guild ID -> audit entries, oldest first
+    audits: DashMap<u64, VecDeque<CommandAudit>>,
+}
+
+impl InMemoryLogStore {
+    pub fn new() -> Self {
+        Self {
+            configs: DashMap::new(),
+            audits: DashMap::new(),
+        }
+    }
+}
+
+impl Default for InMemoryLogStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LogConfigStore for InMemoryLogStore {
+    async fn get_config(&self, guild_id: u64) -> Result<Option<LogConfig>> {
+        Ok(self.configs.get(&guild_id).map(|entry| entry.clone()))
+    }
+
+    async fn save_config(&self, config: LogConfig) -> Result<()> {
+        self.configs.insert(config.guild_id, config);
+        Ok(())
+    }
+
+    async fn record_command_audit(&self, entry: CommandAudit, keep: usize) -> Result<()> {
+        let mut entries = self.audits.entry(entry.guild_id).or_default();
+        entries.push_back(entry);
+        while entries.len() > keep {
+            entries.pop_front();
+        }
+        Ok(())
+    }
+
+    async fn get_recent_command_audits(
+        &self,
+        guild_id: u64,
+        limit: usize,
+    ) -> Result<Vec<CommandAudit>> {
+        Ok(self
+            .audits
+            .get(&guild_id)
+            .map(|entries| entries.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::logging::{LogEvent, LoggingService, OptionalLogEvent};
+    use chrono::Utc;
+
+    fn audit(guild_id: u64, command_name: &str) -> CommandAudit {
+        CommandAudit {
+            guild_id,
+            user_id: 1,
+            user_name: "tester".to_string(),
+            command_name: command_name.to_string(),
+            success: true,
+            error: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_retention_keeps_newest() {
+        let store = InMemoryLogStore::new();
+        for name in ["a", "b", "c"] {
+            store.record_command_audit(audit(1, name), 2).await.unwrap();
+        }
+        store
+            .record_command_audit(audit(2, "other"), 2)
+            .await
+            .unwrap();
+
+        let recent = store.get_recent_command_audits(1, 10).await.unwrap();
+        let names: Vec<&str> = recent.iter().map(|a| a.command_name.as_str()).collect();
+        assert_eq!(names, vec!["c", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_ban_toggles_survive_channel_changes() {
+        let service = LoggingService::new(InMemoryLogStore::new());
+
+        // Toggles can be set before logging is configured, but nothing is logged yet
+        service
+            .set_event_enabled(1, OptionalLogEvent::Unbans, false)
+            .await
+            .unwrap();
+        assert!(!service.should_log(1, OptionalLogEvent::Bans).await.unwrap());
+
+        service.set_log_channel(1, 42).await.unwrap();
+        assert!(service.should_log(1, OptionalLogEvent::Bans).await.unwrap());
+        assert!(!service
+            .should_log(1, OptionalLogEvent::Unbans)
+            .await
+            .unwrap());
+
+        let config = service.get_config(1).await.unwrap().unwrap();
+        assert!(!config.logs(&LogEvent::MemberUnbanned {
+            guild_id: 1,
+            user_id: 2,
+            user_name: "spammer".to_string(),
+            avatar_url: None,
+            attribution: None,
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_attribution_is_opt_in() {
+        let service = LoggingService::new(InMemoryLogStore::new());
+        service.set_log_channel(1, 42).await.unwrap();
+        assert!(!service.should_attribute(1).await.unwrap());
+
+        assert!(service.set_audit_attribution(1, true).await.unwrap());
+        assert!(service.should_attribute(1).await.unwrap());
+    }
+}
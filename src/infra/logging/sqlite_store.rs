@@ -1,6 +1,7 @@
-use crate::core::logging::{LogConfig, LogConfigStore};
+use crate::core::logging::{CommandAudit, LogConfig, LogConfigStore};
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::{Pool, Row, Sqlite};
 
 pub struct SqliteLogStore {
@@ -18,12 +19,69 @@ impl SqliteLogStore {
             CREATE TABLE IF NOT EXISTS logging_config (
                 guild_id INTEGER PRIMARY KEY,
                 enabled BOOLEAN NOT NULL DEFAULT 0,
-                channel_id INTEGER
+                channel_id INTEGER,
+                log_bans BOOLEAN NOT NULL DEFAULT 1,
+                log_unbans BOOLEAN NOT NULL DEFAULT 1,
+                audit_attribution BOOLEAN NOT NULL DEFAULT 0
             );
             "#,
         )
         .execute(&self.pool)
         .await?;
+
+        // Per-event toggles were added later; older databases lack the columns
+        for (column, definition) in [
+            ("log_bans", "BOOLEAN NOT NULL DEFAULT 1"),
+            ("log_unbans", "BOOLEAN NOT NULL DEFAULT 1"),
+            ("audit_attribution", "BOOLEAN NOT NULL DEFAULT 0"),
+        ] {
+            self.add_column_if_missing("logging_config", column, definition)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS command_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                guild_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                user_name TEXT NOT NULL,
+                command_name TEXT NOT NULL,
+                success BOOLEAN NOT NULL,
+                error TEXT,
+                timestamp TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_command_audit_guild_time
+                ON command_audit(guild_id, timestamp);
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// SQLite has no `ADD COLUMN IF NOT EXISTS`, so check `PRAGMA table_info`.
+    async fn add_column_if_missing(
+        &self,
+        table: &str,
+        column: &str,
+        definition: &str,
+    ) -> Result<()> {
+        let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(&self.pool)
+            .await?;
+        let exists = columns
+            .iter()
+            .any(|row| row.get::<String, _>("name") == column);
+
+        if !exists {
+            sqlx::query(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, definition
+            ))
+            .execute(&self.pool)
+            .await?;
+        }
         Ok(())
     }
 }
@@ -41,6 +99,9 @@ impl LogConfigStore for SqliteLogStore {
                 guild_id,
                 enabled: row.get("enabled"),
                 channel_id: row.get::<Option<i64>, _>("channel_id").map(|id| id as u64),
+                log_bans: row.get("log_bans"),
+                log_unbans: row.get("log_unbans"),
+                audit_attribution: row.get("audit_attribution"),
             }))
         } else {
             Ok(None)
@@ -50,18 +111,103 @@ impl LogConfigStore for SqliteLogStore {
     async fn save_config(&self, config: LogConfig) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO logging_config (guild_id, enabled, channel_id)
-            VALUES (?, ?, ?)
+            INSERT INTO logging_config
+                (guild_id, enabled, channel_id, log_bans, log_unbans, audit_attribution)
+            VALUES (?, ?, ?, ?, ?, ?)
             ON CONFLICT(guild_id) DO UPDATE SET
                 enabled = excluded.enabled,
-                channel_id = excluded.channel_id
+                channel_id = excluded.channel_id,
+                log_bans = excluded.log_bans,
+                log_unbans = excluded.log_unbans,
+                audit_attribution = excluded.audit_attribution
             "#,
         )
         .bind(config.guild_id as i64)
         .bind(config.enabled)
         .bind(config.channel_id.map(|id| id as i64))
+        .bind(config.log_bans)
+        .bind(config.log_unbans)
+        .bind(config.audit_attribution)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_command_audit(&self, entry: CommandAudit, keep: usize) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO command_audit
+                (guild_id, user_id, user_name, command_name, success, error, timestamp)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(entry.guild_id as i64)
+        .bind(entry.user_id as i64)
+        .bind(&entry.user_name)
+        .bind(&entry.command_name)
+        .bind(entry.success)
+        .bind(&entry.error)
+        .bind(entry.timestamp.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        // Retention: only keep the newest `keep` rows for this guild.
+        sqlx::query(
+            r#"
+            DELETE FROM command_audit
+            WHERE guild_id = ?
+              AND id NOT IN (
+                SELECT id FROM command_audit
+                WHERE guild_id = ?
+                ORDER BY timestamp DESC, id DESC
+                LIMIT ?
+              )
+            "#,
+        )
+        .bind(entry.guild_id as i64)
+        .bind(entry.guild_id as i64)
+        .bind(keep as i64)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
+
+    async fn get_recent_command_audits(
+        &self,
+        guild_id: u64,
+        limit: usize,
+    ) -> Result<Vec<CommandAudit>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, user_name, command_name, success, error, timestamp
+            FROM command_audit
+            WHERE guild_id = ?
+            ORDER BY timestamp DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let entries = rows
+            .into_iter()
+            .map(|row| {
+                let timestamp: String = row.get("timestamp");
+                CommandAudit {
+                    guild_id,
+                    user_id: row.get::<i64, _>("user_id") as u64,
+                    user_name: row.get("user_name"),
+                    command_name: row.get("command_name"),
+                    success: row.get("success"),
+                    error: row.get("error"),
+                    timestamp: DateTime::parse_from_rfc3339(&timestamp)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                }
+            })
+            .collect();
+        Ok(entries)
+    }
 }
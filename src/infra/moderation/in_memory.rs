@@ -0,0 +1,144 @@
+// In-memory implementation of SpamStore.
+//
+// Used when `STORAGE_BACKEND=memory` (quick local testing, ephemeral deployments).
+// Message history, warnings and configs are lost on restart.
+
+use crate::core::moderation::{MessageRecord, SpamConfig, SpamError, SpamStore, SpamType};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+
+pub struct InMemorySpamStore {
+    /// (user_id, guild_id) -> recent messages
+    messages: DashMap<(u64, u64), Vec<MessageRecord>>,
+    warnings: DashMap<(u64, u64), u32>,
+    rate_limits: DashMap<(u64, u64), DateTime<Utc>>,
+    configs: DashMap<u64, SpamConfig>,
+}
+
+impl InMemorySpamStore {
+    pub fn new() -> Self {
+        Self {
+            messages: DashMap::new(),
+            warnings: DashMap::new(),
+            rate_limits: DashMap::new(),
+            configs: DashMap::new(),
+        }
+    }
+}
+
+impl Default for InMemorySpamStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SpamStore for InMemorySpamStore {
+    async fn record_message(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        record: MessageRecord,
+    ) -> Result<(), SpamError> {
+        self.messages
+            .entry((user_id, guild_id))
+            .or_default()
+            .push(record);
+        Ok(())
+    }
+
+    async fn get_recent_messages(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<MessageRecord>, SpamError> {
+        // Newest first, like the SQLite store
+        Ok(self
+            .messages
+            .get(&(user_id, guild_id))
+            .map(|records| {
+                records
+                    .iter()
+                    .rev()
+                    .filter(|r| r.timestamp >= since)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn add_warning(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        _spam_type: SpamType,
+    ) -> Result<u32, SpamError> {
+        let mut count = self.warnings.entry((user_id, guild_id)).or_insert(0);
+        *count += 1;
+        Ok(*count)
+    }
+
+    async fn get_warnings(&self, user_id: u64, guild_id: u64) -> Result<u32, SpamError> {
+        Ok(self
+            .warnings
+            .get(&(user_id, guild_id))
+            .map(|count| *count)
+            .unwrap_or(0))
+    }
+
+    async fn clear_warnings(&self, user_id: u64, guild_id: u64) -> Result<(), SpamError> {
+        self.warnings.remove(&(user_id, guild_id));
+        Ok(())
+    }
+
+    async fn is_rate_limited(&self, user_id: u64, guild_id: u64) -> Result<bool, SpamError> {
+        let key = (user_id, guild_id);
+        let Some(until) = self.rate_limits.get(&key).map(|until| *until) else {
+            return Ok(false);
+        };
+
+        if Utc::now() < until {
+            Ok(true)
+        } else {
+            // Rate limit expired, clean it up
+            self.rate_limits.remove(&key);
+            Ok(false)
+        }
+    }
+
+    async fn set_rate_limited(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        until: DateTime<Utc>,
+    ) -> Result<(), SpamError> {
+        self.rate_limits.insert((user_id, guild_id), until);
+        Ok(())
+    }
+
+    async fn get_config(&self, guild_id: u64) -> Result<SpamConfig, SpamError> {
+        Ok(self
+            .configs
+            .get(&guild_id)
+            .map(|config| config.clone())
+            .unwrap_or_default())
+    }
+
+    async fn save_config(&self, guild_id: u64, config: SpamConfig) -> Result<(), SpamError> {
+        self.configs.insert(guild_id, config);
+        Ok(())
+    }
+
+    async fn cleanup_old_records(&self, older_than: DateTime<Utc>) -> Result<u64, SpamError> {
+        let mut removed = 0u64;
+        for mut records in self.messages.iter_mut() {
+            let before = records.len();
+            records.retain(|r| r.timestamp >= older_than);
+            removed += (before - records.len()) as u64;
+        }
+        self.messages.retain(|_, records| !records.is_empty());
+        Ok(removed)
+    }
+}
@@ -1,5 +1,10 @@
-// Infra layer for moderation - SQLite-backed spam store.
+// Infra layer for moderation - SQLite-backed and in-memory spam and
+// temporary-role stores.
 
+pub mod in_memory;
 pub mod sqlite_spam_store;
+pub mod temp_role_store;
 
+pub use in_memory::InMemorySpamStore;
 pub use sqlite_spam_store::*;
+pub use temp_role_store::*;
@@ -0,0 +1,207 @@
+// Stores for temporary roles (`/tempmute`) and each guild's mute role.
+//
+// SQLite keeps `temp_roles` and `mute_roles` tables in the moderation
+// database, so pending removals survive restarts. The in-memory store is used
+// when `STORAGE_BACKEND=memory` and in tests.
+
+use crate::core::moderation::{TempRole, TempRoleStore};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+pub struct SqliteTempRoleStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteTempRoleStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS temp_roles (
+                guild_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                role_id INTEGER NOT NULL,
+                expires_at TEXT NOT NULL,
+                reason TEXT,
+                PRIMARY KEY (guild_id, user_id, role_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS mute_roles (
+                guild_id INTEGER PRIMARY KEY,
+                role_id INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TempRoleStore for SqliteTempRoleStore {
+    async fn pending_temp_roles(&self) -> Result<Vec<TempRole>, Box<dyn Error + Send + Sync>> {
+        let rows =
+            sqlx::query("SELECT guild_id, user_id, role_id, expires_at, reason FROM temp_roles")
+                .fetch_all(&self.pool)
+                .await?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let expires_at: String = row.get("expires_at");
+            entries.push(TempRole {
+                guild_id: row.get::<i64, _>("guild_id") as u64,
+                user_id: row.get::<i64, _>("user_id") as u64,
+                role_id: row.get::<i64, _>("role_id") as u64,
+                expires_at: DateTime::parse_from_rfc3339(&expires_at)?.with_timezone(&Utc),
+                reason: row.get("reason"),
+            });
+        }
+        Ok(entries)
+    }
+
+    async fn save_temp_role(&self, entry: &TempRole) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO temp_roles (guild_id, user_id, role_id, expires_at, reason)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(guild_id, user_id, role_id) DO UPDATE SET
+                expires_at = excluded.expires_at,
+                reason = excluded.reason
+            "#,
+        )
+        .bind(entry.guild_id as i64)
+        .bind(entry.user_id as i64)
+        .bind(entry.role_id as i64)
+        .bind(entry.expires_at.to_rfc3339())
+        .bind(&entry.reason)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_temp_role(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query("DELETE FROM temp_roles WHERE guild_id = ? AND user_id = ? AND role_id = ?")
+            .bind(guild_id as i64)
+            .bind(user_id as i64)
+            .bind(role_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_mute_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        let row = sqlx::query("SELECT role_id FROM mute_roles WHERE guild_id = ?")
+            .bind(guild_id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get::<i64, _>("role_id") as u64))
+    }
+
+    async fn set_mute_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO mute_roles (guild_id, role_id)
+            VALUES (?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET role_id = excluded.role_id
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(role_id as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Clones share the same contents.
+#[derive(Clone, Default)]
+pub struct InMemoryTempRoleStore {
+    pending: Arc<RwLock<Vec<TempRole>>>,
+    mute_roles: Arc<RwLock<HashMap<u64, u64>>>,
+}
+
+impl InMemoryTempRoleStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TempRoleStore for InMemoryTempRoleStore {
+    async fn pending_temp_roles(&self) -> Result<Vec<TempRole>, Box<dyn Error + Send + Sync>> {
+        Ok(self.pending.read().await.clone())
+    }
+
+    async fn save_temp_role(&self, entry: &TempRole) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut pending = self.pending.write().await;
+        pending.retain(|existing| {
+            !(existing.guild_id == entry.guild_id
+                && existing.user_id == entry.user_id
+                && existing.role_id == entry.role_id)
+        });
+        pending.push(entry.clone());
+        Ok(())
+    }
+
+    async fn delete_temp_role(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.pending.write().await.retain(|existing| {
+            !(existing.guild_id == guild_id
+                && existing.user_id == user_id
+                && existing.role_id == role_id)
+        });
+        Ok(())
+    }
+
+    async fn get_mute_role(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        Ok(self.mute_roles.read().await.get(&guild_id).copied())
+    }
+
+    async fn set_mute_role(
+        &self,
+        guild_id: u64,
+        role_id: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.mute_roles.write().await.insert(guild_id, role_id);
+        Ok(())
+    }
+}
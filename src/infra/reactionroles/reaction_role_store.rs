@@ -0,0 +1,253 @@
+// Reaction-role bindings, one `reaction_roles` row per message and emoji key.
+// Message IDs are unique across Discord, so (message_id, emoji) is the key and
+// the guild ID is only an index for loading a guild's bindings in one query.
+// Bindings are listed by message, then in the order they were added.
+
+use crate::core::reactionroles::{ReactionRole, ReactionRoleStore};
+use async_trait::async_trait;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use std::error::Error;
+use tokio::sync::RwLock;
+
+pub struct SqliteReactionRoleStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteReactionRoleStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS reaction_roles (
+                guild_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                message_id INTEGER NOT NULL,
+                emoji TEXT NOT NULL,
+                role_id INTEGER NOT NULL,
+                PRIMARY KEY (message_id, emoji)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_reaction_roles_guild ON reaction_roles (guild_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReactionRoleStore for SqliteReactionRoleStore {
+    async fn list_reaction_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ReactionRole>, Box<dyn Error + Send + Sync>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT channel_id, message_id, emoji, role_id
+            FROM reaction_roles
+            WHERE guild_id = ?
+            ORDER BY message_id, rowid
+            "#,
+        )
+        .bind(guild_id as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ReactionRole {
+                channel_id: row.get::<i64, _>("channel_id") as u64,
+                message_id: row.get::<i64, _>("message_id") as u64,
+                emoji: row.get("emoji"),
+                role_id: row.get::<i64, _>("role_id") as u64,
+            })
+            .collect())
+    }
+
+    async fn set_reaction_role(
+        &self,
+        guild_id: u64,
+        binding: ReactionRole,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO reaction_roles (guild_id, channel_id, message_id, emoji, role_id)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(message_id, emoji) DO UPDATE SET
+                role_id = excluded.role_id
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(binding.channel_id as i64)
+        .bind(binding.message_id as i64)
+        .bind(&binding.emoji)
+        .bind(binding.role_id as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_reaction_roles(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        emoji: Option<&str>,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM reaction_roles
+            WHERE guild_id = ? AND message_id = ? AND (? IS NULL OR emoji = ?)
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(message_id as i64)
+        .bind(emoji)
+        .bind(emoji)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryReactionRoleStore {
+    bindings: RwLock<HashMap<u64, Vec<ReactionRole>>>,
+}
+
+impl InMemoryReactionRoleStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ReactionRoleStore for InMemoryReactionRoleStore {
+    async fn list_reaction_roles(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ReactionRole>, Box<dyn Error + Send + Sync>> {
+        let mut bindings = self
+            .bindings
+            .read()
+            .await
+            .get(&guild_id)
+            .cloned()
+            .unwrap_or_default();
+        // Same order as the SQLite store: by message, then insertion
+        bindings.sort_by_key(|binding| binding.message_id);
+        Ok(bindings)
+    }
+
+    async fn set_reaction_role(
+        &self,
+        guild_id: u64,
+        binding: ReactionRole,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut guilds = self.bindings.write().await;
+        let bindings = guilds.entry(guild_id).or_default();
+        match bindings
+            .iter_mut()
+            .find(|b| b.message_id == binding.message_id && b.emoji == binding.emoji)
+        {
+            Some(existing) => existing.role_id = binding.role_id,
+            None => bindings.push(binding),
+        }
+        Ok(())
+    }
+
+    async fn remove_reaction_roles(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        emoji: Option<&str>,
+    ) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        let mut guilds = self.bindings.write().await;
+        let Some(bindings) = guilds.get_mut(&guild_id) else {
+            return Ok(0);
+        };
+        let before = bindings.len();
+        bindings.retain(|b| {
+            !(b.message_id == message_id && emoji.is_none_or(|emoji| b.emoji == emoji))
+        });
+        Ok(before - bindings.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn binding(message_id: u64, emoji: &str, role_id: u64) -> ReactionRole {
+        ReactionRole {
+            channel_id: 5,
+            message_id,
+            emoji: emoji.to_string(),
+            role_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_reaction_roles_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = SqliteReactionRoleStore::new(pool);
+        store.migrate().await.unwrap();
+        assert!(store.list_reaction_roles(1).await.unwrap().is_empty());
+
+        store
+            .set_reaction_role(1, binding(200, "🎮", 10))
+            .await
+            .unwrap();
+        store
+            .set_reaction_role(1, binding(100, "🎨", 11))
+            .await
+            .unwrap();
+        store
+            .set_reaction_role(1, binding(200, "🎵", 12))
+            .await
+            .unwrap();
+        // Binding the same emoji again replaces its role
+        store
+            .set_reaction_role(1, binding(200, "🎮", 13))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.list_reaction_roles(1).await.unwrap(),
+            vec![
+                binding(100, "🎨", 11),
+                binding(200, "🎮", 13),
+                binding(200, "🎵", 12)
+            ]
+        );
+        assert!(store.list_reaction_roles(2).await.unwrap().is_empty());
+
+        assert_eq!(
+            store
+                .remove_reaction_roles(1, 200, Some("🎮"))
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(store.remove_reaction_roles(1, 100, None).await.unwrap(), 1);
+        assert_eq!(
+            store.list_reaction_roles(1).await.unwrap(),
+            vec![binding(200, "🎵", 12)]
+        );
+    }
+}
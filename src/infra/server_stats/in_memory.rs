@@ -0,0 +1,39 @@
+use crate::core::server_stats::{ServerStatsConfig, ServerStatsStore, StoreError};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// In-memory server stats store, used when `STORAGE_BACKEND=memory`.
+#[derive(Default)]
+pub struct InMemoryServerStatsStore {
+    configs: RwLock<HashMap<u64, ServerStatsConfig>>,
+}
+
+impl InMemoryServerStatsStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ServerStatsStore for InMemoryServerStatsStore {
+    async fn get_config(&self, guild_id: u64) -> Result<Option<ServerStatsConfig>, StoreError> {
+        Ok(self.configs.read().await.get(&guild_id).cloned())
+    }
+
+    async fn save_config(&self, config: ServerStatsConfig) -> Result<(), StoreError> {
+        self.configs.write().await.insert(config.guild_id, config);
+        Ok(())
+    }
+
+    async fn delete_config(&self, guild_id: u64) -> Result<(), StoreError> {
+        match self.configs.write().await.remove(&guild_id) {
+            Some(_) => Ok(()),
+            None => Err(StoreError::NotFound),
+        }
+    }
+
+    async fn get_all_configs(&self) -> Result<Vec<ServerStatsConfig>, StoreError> {
+        Ok(self.configs.read().await.values().cloned().collect())
+    }
+}
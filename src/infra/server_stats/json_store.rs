@@ -1,12 +1,16 @@
 use crate::core::server_stats::{ServerStatsConfig, ServerStatsStore, StoreError};
+use crate::infra::json_file::write_atomic;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 pub struct JsonServerStatsStore {
     path: PathBuf,
     cache: RwLock<HashMap<u64, ServerStatsConfig>>,
+    /// Serializes writes so concurrent saves (e.g. a member join and a guild
+    /// update at the same time) can't interleave on disk.
+    write_lock: Mutex<()>,
 }
 
 impl JsonServerStatsStore {
@@ -21,13 +25,21 @@ impl JsonServerStatsStore {
             RwLock::new(HashMap::new())
         };
 
-        Self { path, cache }
+        Self {
+            path,
+            cache,
+            write_lock: Mutex::new(()),
+        }
     }
 
     async fn persist(&self) -> Result<(), StoreError> {
-        let cache = self.cache.read().await;
-        let file = std::fs::File::create(&self.path)?;
-        serde_json::to_writer_pretty(file, &*cache)?;
+        let _guard = self.write_lock.lock().await;
+        // Snapshot while holding the write lock so the newest state is what lands on disk
+        let json = {
+            let cache = self.cache.read().await;
+            serde_json::to_vec_pretty(&*cache)?
+        };
+        write_atomic(&self.path, &json).await?;
         Ok(())
     }
 }
@@ -62,3 +74,50 @@ impl ServerStatsStore for JsonServerStatsStore {
         Ok(cache.values().cloned().collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn config(guild_id: u64, category_id: u64) -> ServerStatsConfig {
+        ServerStatsConfig {
+            guild_id,
+            category_id,
+            total_members_channel_id: 1,
+            members_channel_id: 2,
+            bots_channel_id: 3,
+            boost_channel_id: 4,
+            enabled: true,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_saves_keep_file_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server_stats.json");
+        let store = Arc::new(JsonServerStatsStore::new(&path));
+
+        let mut handles = Vec::new();
+        for i in 0..50u64 {
+            let store = Arc::clone(&store);
+            handles.push(tokio::spawn(async move {
+                // Half the tasks fight over the same guild, the rest use their own
+                let guild_id = if i % 2 == 0 { 1 } else { 100 + i };
+                store.save_config(config(guild_id, i)).await.unwrap();
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let on_disk: HashMap<u64, ServerStatsConfig> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(on_disk.len(), 26);
+
+        // The file matches the in-memory state, i.e. the last write won
+        let in_memory = store.get_config(1).await.unwrap().unwrap();
+        assert_eq!(on_disk[&1].category_id, in_memory.category_id);
+        assert!(!dir.path().join("server_stats.json.tmp").exists());
+    }
+}
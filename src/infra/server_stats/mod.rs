@@ -1,2 +1,4 @@
+pub mod in_memory;
 pub mod json_store;
+pub use in_memory::InMemoryServerStatsStore;
 pub use json_store::JsonServerStatsStore;
@@ -0,0 +1,166 @@
+// Where each guild's goodbye message lives: one `goodbye_messages` row per
+// guild with the channel, the template as saved by `/config goodbye set`, and
+// whether kicks and bans are skipped. `/config goodbye disable` deletes the
+// row, so a guild without one has goodbyes off.
+
+use crate::core::welcome::{GoodbyeConfig, GoodbyeStore};
+use async_trait::async_trait;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use std::error::Error;
+use tokio::sync::RwLock;
+
+pub struct SqliteGoodbyeStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteGoodbyeStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS goodbye_messages (
+                guild_id INTEGER PRIMARY KEY,
+                channel_id INTEGER NOT NULL,
+                template TEXT NOT NULL,
+                skip_kicks_and_bans INTEGER NOT NULL DEFAULT 0
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GoodbyeStore for SqliteGoodbyeStore {
+    async fn get_goodbye(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<GoodbyeConfig>, Box<dyn Error + Send + Sync>> {
+        let row = sqlx::query(
+            "SELECT channel_id, template, skip_kicks_and_bans FROM goodbye_messages WHERE guild_id = ?",
+        )
+        .bind(guild_id as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| GoodbyeConfig {
+            channel_id: row.get::<i64, _>("channel_id") as u64,
+            template: row.get("template"),
+            skip_kicks_and_bans: row.get::<bool, _>("skip_kicks_and_bans"),
+        }))
+    }
+
+    async fn set_goodbye(
+        &self,
+        guild_id: u64,
+        config: &GoodbyeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO goodbye_messages (guild_id, channel_id, template, skip_kicks_and_bans)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET
+                channel_id = excluded.channel_id,
+                template = excluded.template,
+                skip_kicks_and_bans = excluded.skip_kicks_and_bans
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(config.channel_id as i64)
+        .bind(&config.template)
+        .bind(config.skip_kicks_and_bans)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_goodbye(&self, guild_id: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query("DELETE FROM goodbye_messages WHERE guild_id = ?")
+            .bind(guild_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryGoodbyeStore {
+    configs: RwLock<HashMap<u64, GoodbyeConfig>>,
+}
+
+impl InMemoryGoodbyeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl GoodbyeStore for InMemoryGoodbyeStore {
+    async fn get_goodbye(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<GoodbyeConfig>, Box<dyn Error + Send + Sync>> {
+        Ok(self.configs.read().await.get(&guild_id).cloned())
+    }
+
+    async fn set_goodbye(
+        &self,
+        guild_id: u64,
+        config: &GoodbyeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.configs.write().await.insert(guild_id, config.clone());
+        Ok(())
+    }
+
+    async fn remove_goodbye(&self, guild_id: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.configs.write().await.remove(&guild_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_goodbye_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = SqliteGoodbyeStore::new(pool);
+        store.migrate().await.unwrap();
+        assert_eq!(store.get_goodbye(1).await.unwrap(), None);
+
+        let config = GoodbyeConfig {
+            channel_id: 10,
+            template: "Bye {user}!".to_string(),
+            skip_kicks_and_bans: false,
+        };
+        store.set_goodbye(1, &config).await.unwrap();
+        assert_eq!(store.get_goodbye(1).await.unwrap(), Some(config.clone()));
+
+        // Saving again replaces the setting
+        let updated = GoodbyeConfig {
+            skip_kicks_and_bans: true,
+            ..config
+        };
+        store.set_goodbye(1, &updated).await.unwrap();
+        assert_eq!(store.get_goodbye(1).await.unwrap(), Some(updated));
+        assert_eq!(store.get_goodbye(2).await.unwrap(), None);
+
+        store.remove_goodbye(1).await.unwrap();
+        assert_eq!(store.get_goodbye(1).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,7 @@
+// Stores for welcome and goodbye message settings.
+
+pub mod goodbye_message_store;
+pub mod welcome_message_store;
+
+pub use goodbye_message_store::*;
+pub use welcome_message_store::*;
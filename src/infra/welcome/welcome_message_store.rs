@@ -0,0 +1,159 @@
+// Welcome messages, keyed by guild in `welcome_messages`. The template is
+// stored with its placeholders (`{user}`, `{member_count}`, ...) unfilled;
+// they are substituted on every join. Disabling welcomes removes the row
+// rather than flagging it.
+
+use crate::core::welcome::{WelcomeConfig, WelcomeStore};
+use async_trait::async_trait;
+use sqlx::{Pool, Row, Sqlite};
+use std::collections::HashMap;
+use std::error::Error;
+use tokio::sync::RwLock;
+
+pub struct SqliteWelcomeStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteWelcomeStore {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS welcome_messages (
+                guild_id INTEGER PRIMARY KEY,
+                channel_id INTEGER NOT NULL,
+                template TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WelcomeStore for SqliteWelcomeStore {
+    async fn get_welcome(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<WelcomeConfig>, Box<dyn Error + Send + Sync>> {
+        let row =
+            sqlx::query("SELECT channel_id, template FROM welcome_messages WHERE guild_id = ?")
+                .bind(guild_id as i64)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.map(|row| WelcomeConfig {
+            channel_id: row.get::<i64, _>("channel_id") as u64,
+            template: row.get("template"),
+        }))
+    }
+
+    async fn set_welcome(
+        &self,
+        guild_id: u64,
+        config: &WelcomeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO welcome_messages (guild_id, channel_id, template)
+            VALUES (?, ?, ?)
+            ON CONFLICT(guild_id) DO UPDATE SET
+                channel_id = excluded.channel_id,
+                template = excluded.template
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(config.channel_id as i64)
+        .bind(&config.template)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_welcome(&self, guild_id: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        sqlx::query("DELETE FROM welcome_messages WHERE guild_id = ?")
+            .bind(guild_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryWelcomeStore {
+    configs: RwLock<HashMap<u64, WelcomeConfig>>,
+}
+
+impl InMemoryWelcomeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl WelcomeStore for InMemoryWelcomeStore {
+    async fn get_welcome(
+        &self,
+        guild_id: u64,
+    ) -> Result<Option<WelcomeConfig>, Box<dyn Error + Send + Sync>> {
+        Ok(self.configs.read().await.get(&guild_id).cloned())
+    }
+
+    async fn set_welcome(
+        &self,
+        guild_id: u64,
+        config: &WelcomeConfig,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.configs.write().await.insert(guild_id, config.clone());
+        Ok(())
+    }
+
+    async fn remove_welcome(&self, guild_id: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.configs.write().await.remove(&guild_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_sqlite_welcome_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = SqliteWelcomeStore::new(pool);
+        store.migrate().await.unwrap();
+        assert_eq!(store.get_welcome(1).await.unwrap(), None);
+
+        let config = WelcomeConfig {
+            channel_id: 10,
+            template: "Welcome {user}, member #{member_count}!".to_string(),
+        };
+        store.set_welcome(1, &config).await.unwrap();
+        assert_eq!(store.get_welcome(1).await.unwrap(), Some(config.clone()));
+
+        // A new channel replaces the old one
+        let moved = WelcomeConfig {
+            channel_id: 20,
+            ..config
+        };
+        store.set_welcome(1, &moved).await.unwrap();
+        assert_eq!(store.get_welcome(1).await.unwrap(), Some(moved));
+
+        store.remove_welcome(1).await.unwrap();
+        assert_eq!(store.get_welcome(1).await.unwrap(), None);
+    }
+}
@@ -11,6 +11,19 @@
 // 3. Set up the Discord framework
 // 4. Register commands and event handlers
 
+// Builds without some of the optional features (see Cargo.toml) leave a few
+// helpers shared with those subsystems unused.
+#![cfg_attr(
+    not(all(
+        feature = "ai",
+        feature = "github",
+        feature = "economy",
+        feature = "moderation",
+        feature = "google-docs"
+    )),
+    allow(dead_code)
+)]
+
 // These attrs point each module declaration at a more descriptive root file
 // so we don't end up with half a dozen mod.rs files that all look the same.
 #[path = "core/core_layer.rs"]
@@ -20,43 +33,65 @@ mod discord;
 #[path = "infra/infra_layer.rs"]
 mod infra;
 
+#[cfg(feature = "ai")]
 use crate::core::ai::models::AiTool;
-use crate::core::ai::{AiConfig, AiService, FunctionCallHandler};
+#[cfg(feature = "ai")]
+use crate::core::ai::{AiConfig, AiService, AiSettings, FunctionCallHandler};
+#[cfg(feature = "economy")]
 use crate::core::economy::EconomyService;
-use crate::core::github::GithubService;
-use crate::core::leveling::{LevelingService, MessageContentStats};
-use crate::core::logging::{LoggingService, TrackedMessage};
-use crate::core::server_stats::ServerStatsService;
+#[cfg(feature = "github")]
+use crate::core::github::{GithubConfigStore, GithubService};
+use crate::core::leveling::{
+    LevelingConfig, LevelingService, VoiceFlags, XpStore, DEFAULT_VOICE_PAUSE_FLAGS,
+};
+use crate::core::logging::{LogConfigStore, LoggingService, TrackedMessage};
+use crate::core::server_stats::{ServerStatsService, ServerStatsStore};
 use crate::core::timezones::TimezoneService;
 use crate::discord::commands::presence;
 use crate::discord::commands::server_stats::{update_guild_stats, StatsUpdateEvent};
+#[cfg(feature = "github")]
 use crate::discord::github::dispatcher as github_dispatcher;
 use crate::discord::leveling_announcements::send_level_up_embed;
 use crate::discord::logging::events as logging_events;
 use crate::discord::{Data, Error};
+#[cfg(feature = "ai")]
 use crate::infra::ai::{GeminiClient, OpenRouterClient};
-use crate::infra::economy::SqliteCoinStore;
+use crate::infra::config::{load_config, load_dotenv};
+#[cfg(feature = "ai")]
+use crate::infra::config::AiProviderKind;
+#[cfg(feature = "economy")]
+use crate::infra::economy::{InMemoryCoinStore, InMemoryInventoryStore, SqliteCoinStore};
+#[cfg(feature = "github")]
 use crate::infra::github::file_store::GithubFileStore;
+#[cfg(feature = "github")]
 use crate::infra::github::github_client::GithubApiClient;
+#[cfg(feature = "github")]
+use crate::infra::github::memory_store::InMemoryGithubStore;
+#[cfg(feature = "google-docs")]
 use crate::infra::google_docs::GoogleDocsFunctionHandler;
-use crate::infra::leveling::SqliteXpStore;
+use crate::infra::leveling::{load_achievements, InMemoryXpStore, SqliteXpStore};
+use crate::infra::logging::in_memory::InMemoryLogStore;
 use crate::infra::logging::sqlite_store::SqliteLogStore;
-use crate::infra::server_stats::JsonServerStatsStore;
+use crate::infra::server_stats::{InMemoryServerStatsStore, JsonServerStatsStore};
+use anyhow::Context as _;
 use poise::serenity_prelude as serenity;
+use std::process::ExitCode;
 use std::str::FromStr;
-
-const DEFAULT_SYSTEM_PROMPT: &str = "You are a helpful AI assistant.";
+use std::sync::Arc;
 
 /// Event handler for non-command Discord events.
 /// This is where we'll handle messages for XP gain.
+#[cfg_attr(not(feature = "ai"), allow(unused_variables))]
 async fn event_handler(
     ctx: &serenity::Context,
     event: &serenity::FullEvent,
-    _framework: poise::FrameworkContext<'_, Data, Error>,
+    framework: poise::FrameworkContext<'_, Data, Error>,
     data: &Data,
 ) -> Result<(), Error> {
     match event {
         serenity::FullEvent::Message { new_message } => {
+            data.metrics.record_message();
+
             // Ignore bot messages (including our own)
             if new_message.author.bot {
                 return Ok(());
@@ -64,6 +99,7 @@ async fn event_handler(
 
             // Anti-spam check (before any other processing)
             // If detected as spam, the handler will delete/warn/timeout as needed
+            #[cfg(feature = "moderation")]
             if let Ok(is_spam) = discord::moderation::spam_handler::handle_message_for_spam(
                 ctx,
                 new_message,
@@ -77,9 +113,72 @@ async fn event_handler(
                 }
             }
 
-            // Check for bot mention for AI response
+            // Check for bot mention for AI response.
+            // Prefix commands (`!level`, `@Bot level`) are dispatched by poise, so skip the AI for those.
+            #[cfg(feature = "ai")]
             let bot_id = ctx.cache.current_user().id;
-            if new_message.mentions.iter().any(|u| u.id == bot_id) {
+            #[cfg(feature = "ai")]
+            let prefix = framework
+                .options
+                .prefix_options
+                .prefix
+                .as_deref()
+                .unwrap_or_default();
+            #[cfg(feature = "ai")]
+            let is_prefix_command = discord::prefix_commands::is_prefix_command_invocation(
+                &new_message.content,
+                prefix,
+                bot_id.get(),
+                &framework.options.commands,
+            );
+            // Users who opted out with `/ai forgetme` don't get AI replies.
+            #[cfg(feature = "ai")]
+            let ai_opted_out = data.ai_privacy.is_opted_out(new_message.author.id.get());
+            #[cfg(feature = "ai")]
+            let mentions_bot = !is_prefix_command
+                && !ai_opted_out
+                && new_message.mentions.iter().any(|u| u.id == bot_id);
+            // Don't spend an AI call on a reply the bot isn't allowed to post
+            #[cfg(feature = "ai")]
+            let mentions_bot = mentions_bot && {
+                let can_reply = discord::util::permissions::can_send_messages(
+                    ctx,
+                    new_message.guild_id,
+                    new_message.channel_id,
+                );
+                if !can_reply {
+                    tracing::debug!(
+                        channel_id = new_message.channel_id.get(),
+                        "Ignoring mention in a channel the bot can't send messages in"
+                    );
+                }
+                can_reply
+            };
+
+            // Debounce: one AI call at a time per user and channel. The slot is
+            // released when the guard drops at the end of this block.
+            #[cfg(feature = "ai")]
+            let mention_guard = if mentions_bot {
+                match data
+                    .mention_throttle
+                    .try_begin(new_message.channel_id.get(), new_message.author.id.get())
+                {
+                    Ok(guard) => Some(guard),
+                    Err(crate::core::ai::MentionDecision::InFlight) => {
+                        let _ = new_message
+                            .reply(&ctx.http, "I'm still thinking about your last message.")
+                            .await;
+                        None
+                    }
+                    // Just answered this user here, ignore the repeat mention
+                    Err(_) => None,
+                }
+            } else {
+                None
+            };
+
+            #[cfg(feature = "ai")]
+            if let Some(_mention_guard) = mention_guard {
                 // Check if it's a question about the project
                 let content_lower = new_message.content.to_lowercase();
                 let is_project_question = content_lower.contains("project")
@@ -107,6 +206,14 @@ async fn event_handler(
                     return Ok(());
                 }
 
+                // Without an AI provider there's nothing more we can answer
+                let Some(ai) = data.ai.as_deref() else {
+                    let _ = new_message
+                        .reply(&ctx.http, crate::discord::commands::ai::AI_NOT_CONFIGURED)
+                        .await;
+                    return Ok(());
+                };
+
                 // It's a mention!
                 // Trigger typing
                 let _ = new_message.channel_id.broadcast_typing(&ctx.http).await;
@@ -115,17 +222,10 @@ async fn event_handler(
                 // We want the last N messages, excluding the current one if possible, but Serenity's `messages`
                 // usually returns the latest ones.
                 // We'll fetch slightly more to be safe and filter.
-                let max_history = std::env::var("AI_MAX_HISTORY")
-                    .or_else(|_| std::env::var("OPENROUTER_MAX_HISTORY")) // Legacy fallback
-                    .ok()
-                    .and_then(|v| v.parse::<u8>().ok())
-                    .unwrap_or(50);
+                let max_history = data.config.ai.max_history;
 
                 // Token budget for context selection (default 8000 tokens)
-                let token_budget = std::env::var("AI_CONTEXT_TOKEN_BUDGET")
-                    .ok()
-                    .and_then(|v| v.parse::<usize>().ok())
-                    .unwrap_or(8000);
+                let token_budget = data.config.ai.context_token_budget;
 
                 // First, fetch background context from announcement/sneak-peek channels.
                 // This gives the AI knowledge about the project even if the current
@@ -135,10 +235,7 @@ async fn event_handler(
 
                 // Add a separator between background context and current conversation
                 if !context_messages.is_empty() {
-                    context_messages.push(crate::core::ai::AiMessage {
-                        role: "system".to_string(),
-                        content: "--- Current conversation ---".to_string(),
-                    });
+                    context_messages.push(crate::core::ai::AiMessage::system("--- Current conversation ---".to_string()));
                 }
 
                 let messages = new_message
@@ -146,46 +243,59 @@ async fn event_handler(
                     .messages(&ctx.http, serenity::GetMessages::new().limit(max_history))
                     .await
                     .unwrap_or_default();
+                // Leave out messages from users who opted out of AI context
+                let messages = data
+                    .ai_privacy
+                    .retain_allowed(messages, |msg| msg.author.id.get());
+
+                // Multimodal models also get the images attached to the mention,
+                // on the mention itself; others just see the text.
+                let mut images = if ai.supports_images() && !new_message.attachments.is_empty() {
+                    crate::discord::ai::download_images(&new_message.attachments).await
+                } else {
+                    Vec::new()
+                };
 
-                // Convert to ContextMessage for smart selection, reversing order so it's oldest -> newest
-                let mut raw_context: Vec<crate::core::ai::context::ContextMessage> = Vec::new();
-                for msg in messages.iter().rev() {
-                    let role = if msg.author.id == bot_id {
-                        "assistant".to_string()
-                    } else {
-                        "user".to_string()
-                    };
-
-                    let timestamp = msg.timestamp.unix_timestamp() as u64;
-                    let author_name = if role == "user" {
-                        msg.author.name.clone()
-                    } else {
-                        String::new()
-                    };
-
-                    raw_context.push(crate::core::ai::context::ContextMessage::new(
-                        role,
-                        msg.content.clone(),
-                        timestamp,
-                        author_name,
-                    ));
-                }
+                // Oldest -> newest, filtered and trimmed to the token budget
+                let history: Vec<crate::core::ai::HistoryMessage> = messages
+                    .iter()
+                    .rev()
+                    .map(|msg| {
+                        let mut message = discord::util::messages::history_message(msg);
+                        if msg.id == new_message.id {
+                            message.images = std::mem::take(&mut images);
+                        }
+                        message
+                    })
+                    .collect();
+                let history_filter = crate::core::ai::HistoryFilter {
+                    skip_other_bots: data.config.ai.history_skip_bots,
+                    command_prefix: data
+                        .config
+                        .ai
+                        .history_skip_commands
+                        .then(|| data.config.discord.command_prefix.clone()),
+                    min_chars: data.config.ai.history_min_chars,
+                };
+                context_messages.extend(crate::core::ai::history_to_context(
+                    &history,
+                    bot_id.get(),
+                    &history_filter,
+                    token_budget,
+                ));
 
-                // Use smart context selection to stay within token budget
-                let selected_messages = crate::core::ai::context::select_context(raw_context, token_budget);
-                context_messages.extend(selected_messages);
+                // Links in the mention are handed to the URL Context tool so the
+                // model can read the pages.
+                let urls = crate::core::util::extract_urls(&new_message.content);
 
                 // Call AI with metadata to get citations
-                match data.ai.chat_with_metadata(&context_messages).await {
+                match ai.chat_with_urls(&context_messages, &urls).await {
                     Ok(response) => {
                         // Send reasoning if present
                         if let Some(reasoning) = response.reasoning {
                             // Truncate reasoning if too long for embed description (4096 chars)
-                            let mut reasoning_text = reasoning;
-                            if reasoning_text.len() > 4000 {
-                                reasoning_text.truncate(4000);
-                                reasoning_text.push_str("...");
-                            }
+                            let reasoning_text =
+                                crate::core::util::truncate_chars(&reasoning, 4000);
 
                             let embed = serenity::CreateEmbed::new()
                                 .title("🧠 Reasoning")
@@ -215,27 +325,55 @@ async fn event_handler(
                             full_answer.push_str("\n\n");
                             full_answer.push_str(&citations_text);
                         }
+                        // And which of the linked pages the model could read
+                        if let Some(pages_text) = response
+                            .url_context
+                            .as_ref()
+                            .and_then(crate::core::ai::format_url_context_for_discord)
+                        {
+                            full_answer.push_str("\n\n");
+                            full_answer.push_str(&pages_text);
+                        }
 
-                        // Split answer if too long (Discord limit 2000)
+                        // Split answer if too long (Discord limit 2000), capped by the
+                        // guild's `/ai responses` limit so one prompt can't flood the channel.
                         // Use CreateMessage with empty allowed_mentions to prevent pings
-                        for chunk in full_answer.chars().collect::<Vec<char>>().chunks(2000) {
-                            let chunk_str: String = chunk.iter().collect();
-                            let msg = serenity::CreateMessage::new()
-                                .content(chunk_str)
-                                .allowed_mentions(serenity::CreateAllowedMentions::new());
+                        let limits = match new_message.guild_id {
+                            Some(guild_id) => data.response_limits.get(guild_id.get()).await,
+                            None => crate::core::ai::ResponseLimits::default(),
+                        };
+                        let outgoing: Vec<serenity::CreateMessage> =
+                            match crate::core::ai::plan_response(&full_answer, limits) {
+                                crate::core::ai::ResponsePlan::Messages(chunks) => chunks
+                                    .into_iter()
+                                    .map(|chunk| serenity::CreateMessage::new().content(chunk))
+                                    .collect(),
+                                crate::core::ai::ResponsePlan::Attachment { preview, full_text } => {
+                                    vec![serenity::CreateMessage::new().content(preview).add_file(
+                                        serenity::CreateAttachment::bytes(
+                                            full_text.into_bytes(),
+                                            "response.txt",
+                                        ),
+                                    )]
+                                }
+                            };
+                        for msg in outgoing {
+                            let msg = msg.allowed_mentions(serenity::CreateAllowedMentions::new());
                             if let Err(e) = new_message.channel_id.send_message(&ctx.http, msg).await {
                                 tracing::error!("Failed to send AI response: {}", e);
                             }
                         }
                     }
                     Err(e) => {
-                        tracing::error!("AI error: {}", e);
-                        let _ = new_message
-                            .reply(
-                                &ctx.http,
-                                "Sorry, I encountered an error processing your request.",
-                            )
-                            .await;
+                        // A failing-fast provider was already logged when the circuit
+                        // opened, and being at the request limit isn't an error
+                        if e.downcast_ref::<crate::core::ai::CircuitOpen>().is_none()
+                            && e.downcast_ref::<crate::core::ai::AiBusy>().is_none()
+                        {
+                            tracing::error!("AI error: {}", e);
+                        }
+                        let reply = crate::discord::commands::ai::ai_error_reply(e.as_ref());
+                        let _ = new_message.reply(&ctx.http, reply).await;
                     }
                 }
             }
@@ -245,39 +383,41 @@ async fn event_handler(
                 let user_id = new_message.author.id.get();
                 let guild_id = guild_id.get();
 
+                // Feed the `/serverstats activity` chart
+                data.server_stats
+                    .record_message(guild_id, new_message.timestamp.to_utc());
+
                 // Try to award XP for this message
-                // Detect Nitro boosting (best-effort using cache). If unavailable, assume false.
-                let boosted = ctx
-                    .cache
-                    .guild(serenity::GuildId::from(guild_id))
-                    .and_then(|g| g.members.get(&serenity::UserId::from(user_id)).cloned())
-                    .and_then(|m| m.premium_since)
-                    .is_some();
+                // Detect Nitro boosting (cache first, HTTP fallback on a cache miss)
+                let boosted = discord::boosters::is_boosting(
+                    ctx,
+                    data,
+                    serenity::GuildId::from(guild_id),
+                    serenity::UserId::from(user_id),
+                )
+                .await;
 
                 // Analyze message content
-                let has_image = new_message.attachments.iter().any(|a| {
-                    let name = a.filename.to_lowercase();
-                    name.ends_with(".png")
-                        || name.ends_with(".jpg")
-                        || name.ends_with(".jpeg")
-                        || name.ends_with(".gif")
-                        || name.ends_with(".webp")
-                });
-                let is_long = new_message.content.len() >= 100;
-                let has_link = new_message.content.contains("http://")
-                    || new_message.content.contains("https://");
-
-                let content_stats = MessageContentStats {
-                    has_image,
-                    is_long,
-                    has_link,
-                };
+                let content_stats = discord::util::message_content_stats(new_message);
 
-                match data
+                let xp_result = data
                     .leveling
-                    .process_message(user_id, guild_id, boosted, Some(content_stats))
-                    .await
-                {
+                    .process_message(
+                        user_id,
+                        guild_id,
+                        new_message.channel_id.get(),
+                        boosted,
+                        Some(content_stats),
+                    )
+                    .await;
+                // Coins share the XP cooldown, so spamming doesn't earn more rolls
+                #[cfg(feature = "economy")]
+                let earned_xp = !matches!(
+                    xp_result,
+                    Err(crate::core::leveling::LevelingError::OnCooldown(_))
+                );
+
+                match xp_result {
                     Ok(Some(level_up)) => {
                         tracing::info!(
                             user_id = level_up.user_id,
@@ -288,11 +428,13 @@ async fn event_handler(
                             "User leveled up"
                         );
 
-                        // User leveled up! Announce it
-                        if let Err(err) =
-                            send_level_up_embed(ctx, new_message, data, &level_up).await
+                        // User leveled up! Announce it, unless they were just
+                        // announced - then it's batched into a later announcement
+                        if let Some(level_up) = data.leveling.queue_level_up_announcement(level_up)
                         {
-                            tracing::warn!("Failed to send level-up embed: {err}");
+                            if let Err(err) = send_level_up_embed(ctx, data, &level_up).await {
+                                tracing::warn!("Failed to send level-up embed: {err}");
+                            }
                         }
                     }
                     Ok(None) => {
@@ -303,17 +445,43 @@ async fn event_handler(
                     }
                     Err(e) => {
                         // Some other error - log it but don't crash
-                        eprintln!("Error processing XP for message: {}", e);
+                        tracing::error!(
+                            user_id,
+                            guild_id,
+                            error = %crate::core::util::ErrorChain(&e),
+                            "Error processing XP for message"
+                        );
                     }
                 }
 
-                // Try to award random coins (silent - no announcement)
-                if let Err(e) = data
-                    .economy
-                    .try_random_message_reward(user_id, guild_id)
-                    .await
-                {
-                    tracing::debug!("Failed to award random message coins: {}", e);
+                // Try to award random coins (announced only if configured)
+                #[cfg(feature = "economy")]
+                if earned_xp {
+                    match data
+                        .economy
+                        .try_random_message_reward(user_id, guild_id)
+                        .await
+                    {
+                        Ok(Some(amount)) if data.economy.config().announce_message_rewards => {
+                            let announcement = serenity::CreateMessage::new()
+                                .content(format!(
+                                    "🍀 Lucky drop! <@{}> found **{} GreyCoins** 🪙",
+                                    user_id, amount
+                                ))
+                                .allowed_mentions(serenity::CreateAllowedMentions::new());
+                            if let Err(e) = new_message
+                                .channel_id
+                                .send_message(&ctx.http, announcement)
+                                .await
+                            {
+                                tracing::debug!("Failed to announce message coins: {}", e);
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            tracing::debug!("Failed to award random message coins: {}", e);
+                        }
+                    }
                 }
             }
 
@@ -347,11 +515,18 @@ async fn event_handler(
             )
             .await
             {
-                eprintln!("Error updating stats on join: {}", e);
+                tracing::error!(
+                    guild_id = new_member.guild_id.get(),
+                    user_id = new_member.user.id.get(),
+                    error = %e,
+                    "Error updating stats on join"
+                );
             }
             if let Err(e) = logging_events::handle_member_join(ctx, data, new_member).await {
                 tracing::error!("Error handling member join log: {}", e);
             }
+            discord::auto_role::assign_on_join(ctx, data, new_member).await;
+            discord::welcome::send_on_join(ctx, data, new_member).await;
         }
         serenity::FullEvent::GuildMemberRemoval {
             guild_id,
@@ -361,19 +536,67 @@ async fn event_handler(
             if let Err(e) =
                 update_guild_stats(ctx, data, *guild_id, StatsUpdateEvent::MemberLeave(user)).await
             {
-                eprintln!("Error updating stats on leave: {}", e);
+                tracing::error!(
+                    guild_id = guild_id.get(),
+                    user_id = user.id.get(),
+                    error = %e,
+                    "Error updating stats on leave"
+                );
             }
+            data.boost_cache.forget_member(guild_id.get(), user.id.get());
+            // Shared so the audit log is read once for both
+            let removal = logging_events::RemovalAudit::new(*guild_id, user.id);
             if let Err(e) = logging_events::handle_member_remove(
                 ctx,
                 data,
                 *guild_id,
                 user,
                 member_data_if_available.as_ref(),
+                &removal,
             )
             .await
             {
                 tracing::error!("Error handling member remove log: {}", e);
             }
+            discord::welcome::send_on_leave(
+                ctx,
+                data,
+                *guild_id,
+                user,
+                member_data_if_available.as_ref(),
+                &removal,
+            )
+            .await;
+        }
+        // The bot left (or was removed from) a guild
+        serenity::FullEvent::GuildDelete { incomplete, .. } if !incomplete.unavailable => {
+            data.boost_cache.forget_guild(incomplete.id.get());
+        }
+        serenity::FullEvent::ReactionAdd { add_reaction } => {
+            discord::reaction_roles::handle_reaction(ctx, data, add_reaction, true).await;
+        }
+        serenity::FullEvent::ReactionRemove { removed_reaction } => {
+            discord::reaction_roles::handle_reaction(ctx, data, removed_reaction, false).await;
+        }
+        serenity::FullEvent::GuildBanAddition {
+            guild_id,
+            banned_user,
+        } => {
+            if let Err(e) =
+                logging_events::handle_member_ban(ctx, data, *guild_id, banned_user).await
+            {
+                tracing::error!("Error handling member ban log: {}", e);
+            }
+        }
+        serenity::FullEvent::GuildBanRemoval {
+            guild_id,
+            unbanned_user,
+        } => {
+            if let Err(e) =
+                logging_events::handle_member_unban(ctx, data, *guild_id, unbanned_user).await
+            {
+                tracing::error!("Error handling member unban log: {}", e);
+            }
         }
         serenity::FullEvent::GuildUpdate {
             old_data_if_available: _,
@@ -387,7 +610,11 @@ async fn event_handler(
             )
             .await
             {
-                eprintln!("Error updating stats on guild update: {}", e);
+                tracing::error!(
+                    guild_id = new_data.id.get(),
+                    error = %e,
+                    "Error updating stats on guild update"
+                );
             }
         }
         serenity::FullEvent::MessageDelete {
@@ -407,6 +634,23 @@ async fn event_handler(
                 tracing::error!("Error handling message delete: {}", e);
             }
         }
+        serenity::FullEvent::MessageDeleteBulk {
+            channel_id,
+            multiple_deleted_messages_ids,
+            guild_id,
+        } => {
+            if let Err(e) = logging_events::handle_message_delete_bulk(
+                ctx,
+                data,
+                *channel_id,
+                multiple_deleted_messages_ids,
+                *guild_id,
+            )
+            .await
+            {
+                tracing::error!("Error handling bulk message delete: {}", e);
+            }
+        }
         serenity::FullEvent::MessageUpdate {
             old_if_available,
             new,
@@ -425,6 +669,28 @@ async fn event_handler(
             }
         }
         serenity::FullEvent::VoiceStateUpdate { old, new } => {
+            // Accumulate voice time for /voicestats
+            let is_bot = new.member.as_ref().is_some_and(|member| member.user.bot);
+            if let (Some(guild_id), false) = (new.guild_id, is_bot) {
+                if let Err(e) = data
+                    .leveling
+                    .record_voice_state(
+                        new.user_id.get(),
+                        guild_id.get(),
+                        new.channel_id.map(|id| id.get()),
+                        VoiceFlags {
+                            self_mute: new.self_mute,
+                            self_deaf: new.self_deaf,
+                            mute: new.mute,
+                            deaf: new.deaf,
+                        },
+                    )
+                    .await
+                {
+                    tracing::warn!("Failed to record voice time: {}", e);
+                }
+            }
+
             if let Err(e) =
                 logging_events::handle_voice_state_update(ctx, data, old.as_ref(), new).await
             {
@@ -439,21 +705,50 @@ async fn event_handler(
 }
 
 #[tokio::main]
-async fn main() {
-    // Initialize logging so we can see what's happening
-    tracing_subscriber::fmt::init();
-
+async fn main() -> ExitCode {
     // Load environment variables from .env file (if it exists)
-    dotenv::dotenv().ok();
+    load_dotenv();
+
+    // Initialize logging so we can see what's happening.
+    // Filter with RUST_LOG (e.g. `RUST_LOG=rust_discord_bot=debug`); defaults to `info`.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    // Startup problems (missing tokens, unreachable databases, ...) end up
+    // here as one readable message instead of a panic with a backtrace.
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::error!("{:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    // Get Discord bot token from environment
-    let token = std::env::var("DISCORD_TOKEN").expect(
-        "Missing DISCORD_TOKEN environment variable! Create a .env file with your bot token.",
-    );
+/// Build every service, start the Discord client and run until it stops.
+async fn run() -> anyhow::Result<()> {
+    // Load config.toml (optional) and environment overrides, and make sure the
+    // required settings are there before anything starts
+    let config = Arc::new(load_config()?);
+    let token = config.discord.token.clone().unwrap_or_default();
+
+    // STORAGE_BACKEND=memory runs every store in memory (nothing is written to disk).
+    // Anything else (default: sqlite) uses the SQLite/JSON stores under data/ and config/.
+    let in_memory = config.in_memory();
+    if in_memory {
+        tracing::warn!("STORAGE_BACKEND=memory: all data will be lost when the bot stops");
+    }
 
     // Keep runtime databases in a dedicated folder so the repo root stays tidy.
-    let data_dir = "data";
-    std::fs::create_dir_all(data_dir).expect("Failed to create data directory for SQLite files");
+    let data_dir = config.storage.data_dir.as_str();
+    if !in_memory {
+        std::fs::create_dir_all(data_dir)
+            .context("Failed to create data directory for SQLite files")?;
+    }
     let leveling_db_path = format!("{}/leveling.db", data_dir);
     let logging_db_path = format!("{}/logging.db", data_dir);
 
@@ -463,298 +758,277 @@ async fn main() {
     // Create our services with their dependencies.
     // This is the "composition root" where we wire everything together.
 
-    use std::sync::Arc;
+    // Create the XP store (SQLite-backed unless running in memory)
+    let xp_store: Box<dyn XpStore> = if in_memory {
+        Box::new(InMemoryXpStore::new())
+    } else {
+        Box::new(
+            SqliteXpStore::new(&leveling_db_path)
+                .await
+                .context("Failed to initialize SQLite store")?,
+        )
+    };
 
-    // Create the SQLite-backed XP store
-    let xp_store = SqliteXpStore::new(&leveling_db_path)
-        .await
-        .expect("Failed to initialize SQLite store");
+    // Built-in achievements plus any custom ones from data/achievements.json
+    let achievements_path = format!("{}/achievements.json", data_dir);
+    let achievements = load_achievements(&achievements_path)
+        .with_context(|| format!("Failed to read {}", achievements_path))?;
+    tracing::info!(count = achievements.len(), "Loaded achievements");
+
+    // Minimum seconds between level-up announcements for one member; level-ups
+    // inside the window are batched into one announcement
+    let level_up_announce_cooldown = config
+        .leveling
+        .level_up_announce_cooldown_secs
+        .map(std::time::Duration::from_secs)
+        .unwrap_or_else(|| LevelingConfig::default().level_up_announce_cooldown);
+
+    // Days a daily streak may be missed without resetting (0 = strict)
+    let daily_streak_grace_days = config
+        .leveling
+        .daily_streak_grace_days
+        .unwrap_or_else(|| LevelingConfig::default().daily_streak_grace_days);
+
+    // `/daily` XP amounts; `Config::validate` has already checked the curve
+    let daily = config.leveling.daily_reward_config();
+
+    // Voice flags that pause voice time; `Config::validate` has already checked the list
+    let voice_pause_flags = config
+        .leveling
+        .voice_pause_flags
+        .as_deref()
+        .and_then(|list| VoiceFlags::parse_list(list).ok())
+        .unwrap_or(DEFAULT_VOICE_PAUSE_FLAGS);
 
     // Create the leveling service with the store injected and wrap in Arc
-    let leveling_service = Arc::new(LevelingService::new(xp_store));
+    let leveling_service = Arc::new(LevelingService::with_config(
+        xp_store,
+        LevelingConfig {
+            achievements,
+            level_up_announce_cooldown,
+            daily_streak_grace_days,
+            daily,
+            voice_pause_flags,
+            ..LevelingConfig::default()
+        },
+    ));
 
     // Create server stats store
-    let config_dir = "config";
-    std::fs::create_dir_all(config_dir).expect("Failed to create config directory");
-    let stats_store = JsonServerStatsStore::new(format!("{}/server_stats.json", config_dir));
+    let stats_store: Box<dyn ServerStatsStore> = if in_memory {
+        Box::new(InMemoryServerStatsStore::new())
+    } else {
+        let config_dir = "config";
+        std::fs::create_dir_all(config_dir).context("Failed to create config directory")?;
+        Box::new(JsonServerStatsStore::new(format!(
+            "{}/server_stats.json",
+            config_dir
+        )))
+    };
     let stats_service = Arc::new(ServerStatsService::new(stats_store));
 
     let timezone_service = Arc::new(TimezoneService::new());
 
-    let log_conn_str = format!("sqlite://{}", logging_db_path);
-    let log_options = sqlx::sqlite::SqliteConnectOptions::from_str(&log_conn_str)
-        .expect("Invalid connection string")
-        .create_if_missing(true)
-        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
-        .busy_timeout(std::time::Duration::from_secs(5));
-
-    let log_pool = sqlx::sqlite::SqlitePoolOptions::new()
-        .connect_with(log_options)
-        .await
-        .expect("Failed to connect to logging DB");
-    let log_store = SqliteLogStore::new(log_pool);
-    log_store
-        .migrate()
-        .await
-        .expect("Failed to migrate logging DB");
-    let logging_service = Arc::new(LoggingService::new(log_store));
-
-    // GitHub tracking service (polls commits/issues across repos)
-    let github_token = std::env::var("GITHUB_TOKEN").ok();
-    let github_client =
-        GithubApiClient::new(github_token).expect("Failed to create GitHub API client");
-    let github_store = GithubFileStore::new(format!("{}/github_config.json", data_dir));
-    let github_service = Arc::new(
-        GithubService::new(github_client, github_store)
+    let log_store: Box<dyn LogConfigStore> = if in_memory {
+        Box::new(InMemoryLogStore::new())
+    } else {
+        let log_conn_str = format!("sqlite://{}", logging_db_path);
+        let log_options = sqlx::sqlite::SqliteConnectOptions::from_str(&log_conn_str)
+            .context("Invalid connection string")?
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+
+        let log_pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .connect_with(log_options)
             .await
-            .expect("Failed to initialize GitHub tracking service"),
-    );
+            .context("Failed to connect to logging DB")?;
+        let log_store = SqliteLogStore::new(log_pool);
+        log_store
+            .migrate()
+            .await
+            .context("Failed to migrate logging DB")?;
+        Box::new(log_store)
+    };
+    let logging_service = Arc::new(LoggingService::new(log_store));
 
-    // AI Service
-    // -------------------------------------------------------------------------
-    // The bot supports two AI providers:
-    // 1. OpenRouter (default) - Access to many models via openrouter.ai
-    // 2. Gemini - Google's Gemini API via ai.google.dev
-    //
-    // Set AI_PROVIDER=gemini to use Gemini, otherwise OpenRouter is used.
-    // -------------------------------------------------------------------------
-    let ai_provider = std::env::var("AI_PROVIDER").unwrap_or_else(|_| "openrouter".to_string());
+    // One HTTP client (connection pool and User-Agent) for all outbound API calls
+    #[cfg(any(feature = "ai", feature = "github"))]
+    let http_client =
+        crate::infra::http::shared_client().context("Failed to initialise the HTTP client")?;
 
-    // Load system prompt (shared between providers)
-    let system_prompt = if let Ok(path) = std::env::var("AI_SYSTEM_PROMPT_FILE") {
-        std::fs::read_to_string(&path).unwrap_or_else(|e| {
-            tracing::warn!("Failed to read system prompt file at {}: {}", path, e);
-            DEFAULT_SYSTEM_PROMPT.to_string()
-        })
+    // GitHub tracking service (polls commits/issues across repos)
+    #[cfg(feature = "github")]
+    let github_service = init_github(&config, http_client.clone(), data_dir, in_memory).await?;
+
+    // AI service (optional: without an API key the bot runs without it)
+    #[cfg(feature = "ai")]
+    let ai_service = init_ai(&config, http_client.clone()).await;
+
+    // Economy and inventory services
+    #[cfg(feature = "economy")]
+    let (economy_service, inventory_service) = init_economy(&config, data_dir, in_memory).await?;
+
+    // Anti-Spam Moderation Service and pending temporary roles
+    #[cfg(feature = "moderation")]
+    let (anti_spam_service, temp_role_service) = init_moderation(data_dir, in_memory).await?;
+
+    // Knowledge store, AI opt-outs and per-guild response limits
+    #[cfg(feature = "ai")]
+    let (knowledge_service, response_limits, ai_privacy) =
+        init_ai_stores(data_dir, in_memory).await?;
+
+    // Per-guild settings database shared by manager roles (`/config managers`),
+    // auto-roles (`/config autorole`), welcome/goodbye messages
+    // (`/config welcome`, `/config goodbye`), birthdays (`/birthday`) and
+    // `/feedback`
+    let guild_settings_pool = if in_memory {
+        None
     } else {
-        std::env::var("AI_SYSTEM_PROMPT")
-            .or_else(|_| std::env::var("OPENROUTER_SYSTEM_PROMPT")) // Backwards compat
-            .unwrap_or_else(|_| DEFAULT_SYSTEM_PROMPT.to_string())
+        let guild_settings_db_path = format!("{}/guild_settings.db", data_dir);
+        let guild_settings_conn_str = format!("sqlite://{}", guild_settings_db_path);
+        let guild_settings_options =
+            sqlx::sqlite::SqliteConnectOptions::from_str(&guild_settings_conn_str)
+                .context("Invalid guild settings DB connection string")?
+                .create_if_missing(true)
+                .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+                .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+                .busy_timeout(std::time::Duration::from_secs(5));
+
+        Some(
+            sqlx::sqlite::SqlitePoolOptions::new()
+                .max_connections(2)
+                .connect_with(guild_settings_options)
+                .await
+                .context("Failed to connect to guild settings DB")?,
+        )
     };
 
-    // Build AI service based on provider
-    let ai_service: Arc<AiService<Box<dyn crate::core::ai::AiProvider>>> = if ai_provider
-        .to_lowercase()
-        == "gemini"
-    {
-        // Gemini configuration
-        let gemini_api_key = std::env::var("GEMINI_API_KEY")
-            .expect("Missing GEMINI_API_KEY environment variable when AI_PROVIDER=gemini");
-        let mut gemini_model = std::env::var("GEMINI_MODEL").unwrap_or_else(|_| "auto".to_string());
-
-        // Handle "auto" or "best" to always use the top of our hierarchy
-        if gemini_model == "auto" || gemini_model == "best" {
-            gemini_model = crate::core::ai::models::MODEL_HIERARCHY[0].to_string();
-        }
-
-        tracing::info!("Using Gemini AI provider with model: {}", gemini_model);
-
-        // Set up Google Docs function handler
-        // Try to use service account auth for multi-tab support, fall back to public export
-        let handler = match GoogleDocsFunctionHandler::from_env_with_auth().await {
-            Ok(h) => {
-                tracing::info!(
-                    "Google Docs: Using service account authentication (multi-tab support enabled)"
-                );
-                h
-            }
-            Err(e) => {
-                tracing::info!("Google Docs: Service account not configured ({}), using public export (first tab only)", e);
-                GoogleDocsFunctionHandler::from_env()
+    // Manager roles: roles allowed to run admin commands (`/config managers`)
+    let manager_role_store: Box<dyn crate::core::access::ManagerRoleStore> =
+        match &guild_settings_pool {
+            None => Box::new(crate::infra::access::InMemoryManagerRoleStore::new()),
+            Some(pool) => {
+                let manager_role_store =
+                    crate::infra::access::SqliteManagerRoleStore::new(pool.clone());
+                manager_role_store
+                    .migrate()
+                    .await
+                    .map_err(anyhow::Error::from_boxed)
+                    .context("Failed to migrate guild settings DB")?;
+                Box::new(manager_role_store)
             }
         };
+    let manager_roles = Arc::new(crate::core::access::ManagerRoleService::new(
+        manager_role_store,
+    ));
 
-        // Check if any project docs are configured
-        let has_project_docs = handler.supported_functions().len() > 1; // More than just read_google_doc
-
-        // Enable Google Search + Google Docs reading
-        let enable_search = std::env::var("AI_ENABLE_GOOGLE_SEARCH")
-            .map(|v| v.to_lowercase() == "true")
-            .unwrap_or(true); // Default to enabled
-
-        let (tools, function_handler): (
-            Option<Vec<AiTool>>,
-            Option<Box<dyn crate::core::ai::FunctionCallHandler>>,
-        ) = {
-            let tools = handler.get_tools(enable_search);
-            tracing::info!(
-                "Gemini tools enabled: Google Search={}, Google Docs functions={}",
-                enable_search,
-                handler.supported_functions().join(", ")
-            );
-
-            if has_project_docs {
-                tracing::info!("Project documents configured for AI access");
+    // Auto-role: role given to members when they join (`/config autorole`)
+    let auto_role_store: Box<dyn crate::core::autorole::AutoRoleStore> = match &guild_settings_pool
+    {
+        None => Box::new(crate::infra::autorole::InMemoryAutoRoleStore::new()),
+        Some(pool) => {
+            let auto_role_store = crate::infra::autorole::SqliteAutoRoleStore::new(pool.clone());
+            auto_role_store
+                .migrate()
+                .await
+                .map_err(anyhow::Error::from_boxed)
+                .context("Failed to migrate auto-role table")?;
+            Box::new(auto_role_store)
+        }
+    };
+    let auto_roles = Arc::new(crate::core::autorole::AutoRoleService::new(auto_role_store));
+
+    // Reaction roles: roles members get by reacting to a message (`/reactionrole`)
+    let reaction_role_store: Box<dyn crate::core::reactionroles::ReactionRoleStore> =
+        match &guild_settings_pool {
+            None => Box::new(crate::infra::reactionroles::InMemoryReactionRoleStore::new()),
+            Some(pool) => {
+                let reaction_role_store =
+                    crate::infra::reactionroles::SqliteReactionRoleStore::new(pool.clone());
+                reaction_role_store
+                    .migrate()
+                    .await
+                    .map_err(anyhow::Error::from_boxed)
+                    .context("Failed to migrate reaction role table")?;
+                Box::new(reaction_role_store)
             }
-
-            (
-                Some(tools),
-                Some(Box::new(handler) as Box<dyn crate::core::ai::FunctionCallHandler>),
-            )
-        };
-
-        let gemini_client = GeminiClient::new(gemini_api_key);
-        let ai_config = AiConfig {
-            model: gemini_model,
-            temperature: std::env::var("AI_TEMPERATURE")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(0.7),
-            max_tokens: std::env::var("AI_MAX_TOKENS")
-                .ok()
-                .and_then(|v| v.parse().ok()),
-            top_p: std::env::var("AI_TOP_P")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .or(Some(1.0)),
-            repetition_penalty: None, // Not supported by Gemini
-            reasoning_enabled: std::env::var("AI_REASONING_ENABLED")
-                .ok()
-                .and_then(|v| v.parse().ok()),
-            reasoning_effort: std::env::var("AI_REASONING_EFFORT").ok(),
-            tools,
-            tool_config: None, // Default tool behavior (AUTO)
         };
-
-        // Create AI service with or without function handler
-        match function_handler {
-            Some(handler) => Arc::new(AiService::with_function_handler(
-                Box::new(gemini_client) as Box<dyn crate::core::ai::AiProvider>,
-                system_prompt,
-                ai_config,
-                handler,
-            )),
-            None => Arc::new(AiService::new(
-                Box::new(gemini_client) as Box<dyn crate::core::ai::AiProvider>,
-                system_prompt,
-                ai_config,
-            )),
+    let reaction_roles = Arc::new(crate::core::reactionroles::ReactionRoleService::new(
+        reaction_role_store,
+    ));
+
+    // Welcome messages posted when members join (`/config welcome`)
+    let welcome_store: Box<dyn crate::core::welcome::WelcomeStore> = match &guild_settings_pool {
+        None => Box::new(crate::infra::welcome::InMemoryWelcomeStore::new()),
+        Some(pool) => {
+            let welcome_store = crate::infra::welcome::SqliteWelcomeStore::new(pool.clone());
+            welcome_store
+                .migrate()
+                .await
+                .map_err(anyhow::Error::from_boxed)
+                .context("Failed to migrate welcome message table")?;
+            Box::new(welcome_store)
         }
-    } else {
-        // OpenRouter configuration (default)
-        let openrouter_api_key = std::env::var("OPENROUTER_API_KEY")
-            .expect("Missing OPENROUTER_API_KEY environment variable!");
-        let openrouter_model = std::env::var("OPENROUTER_MODEL")
-            .unwrap_or_else(|_| "deepseek/deepseek-chat-v3.1:free".to_string());
-
-        tracing::info!(
-            "Using OpenRouter AI provider with model: {}",
-            openrouter_model
-        );
-
-        let reasoning_enabled = std::env::var("OPENROUTER_REASONING_ENABLED")
-            .or_else(|_| std::env::var("AI_REASONING_ENABLED"))
-            .ok()
-            .and_then(|v| v.parse::<bool>().ok());
-        let reasoning_effort = std::env::var("OPENROUTER_REASONING_EFFORT")
-            .or_else(|_| std::env::var("AI_REASONING_EFFORT"))
-            .ok();
-
-        let ai_client = OpenRouterClient::new(openrouter_api_key);
-        let ai_config = AiConfig {
-            model: openrouter_model,
-            temperature: std::env::var("AI_TEMPERATURE")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(0.7),
-            max_tokens: std::env::var("AI_MAX_TOKENS")
-                .ok()
-                .and_then(|v| v.parse().ok()),
-            top_p: std::env::var("AI_TOP_P")
-                .ok()
-                .and_then(|v| v.parse().ok())
-                .or(Some(1.0)),
-            repetition_penalty: Some(1.0),
-            reasoning_enabled,
-            reasoning_effort,
-            tools: None, // OpenRouter: limited tool support depends on model
-            tool_config: None,
-        };
-
-        Arc::new(AiService::new(
-            Box::new(ai_client) as Box<dyn crate::core::ai::AiProvider>,
-            system_prompt,
-            ai_config,
-        ))
     };
+    let welcome = Arc::new(crate::core::welcome::WelcomeService::new(welcome_store));
+
+    // Goodbye messages posted when members leave (`/config goodbye`)
+    let goodbye_store: Box<dyn crate::core::welcome::GoodbyeStore> = match &guild_settings_pool {
+        None => Box::new(crate::infra::welcome::InMemoryGoodbyeStore::new()),
+        Some(pool) => {
+            let goodbye_store = crate::infra::welcome::SqliteGoodbyeStore::new(pool.clone());
+            goodbye_store
+                .migrate()
+                .await
+                .map_err(anyhow::Error::from_boxed)
+                .context("Failed to migrate goodbye message table")?;
+            Box::new(goodbye_store)
+        }
+    };
+    let goodbye = Arc::new(crate::core::welcome::GoodbyeService::new(goodbye_store));
+
+    // Member birthdays and where they're announced (`/birthday`, `/config birthdays`)
+    let birthday_store: Box<dyn crate::core::birthdays::BirthdayStore> =
+        match &guild_settings_pool {
+            None => Box::new(crate::infra::birthdays::InMemoryBirthdayStore::new()),
+            Some(pool) => {
+                let birthday_store =
+                    crate::infra::birthdays::SqliteBirthdayStore::new(pool.clone());
+                birthday_store
+                    .migrate()
+                    .await
+                    .map_err(anyhow::Error::from_boxed)
+                    .context("Failed to migrate birthday tables")?;
+                Box::new(birthday_store)
+            }
+        };
+    let birthdays = Arc::new(crate::core::birthdays::BirthdayService::new(birthday_store));
+
+    // Feedback from `/feedback`, read back with `/admin feedback recent`
+    let feedback_store: Box<dyn crate::core::feedback::FeedbackStore> =
+        match &guild_settings_pool {
+            None => Box::new(crate::infra::feedback::InMemoryFeedbackStore::new()),
+            Some(pool) => {
+                let feedback_store =
+                    crate::infra::feedback::SqliteFeedbackStore::new(pool.clone());
+                feedback_store
+                    .migrate()
+                    .await
+                    .map_err(anyhow::Error::from_boxed)
+                    .context("Failed to migrate feedback table")?;
+                Box::new(feedback_store)
+            }
+        };
+    let feedback = Arc::new(crate::core::feedback::FeedbackService::new(feedback_store));
 
-    let _max_history = std::env::var("AI_MAX_HISTORY")
-        .or_else(|_| std::env::var("OPENROUTER_MAX_HISTORY")) // Backwards compat
-        .ok()
-        .and_then(|v| v.parse::<usize>().ok())
-        .unwrap_or(50);
-
-    // Economy Service
-    let economy_db_path = format!("{}/economy.db", data_dir);
-    let coin_store = SqliteCoinStore::new(&economy_db_path)
-        .await
-        .expect("Failed to initialize economy store");
-    let economy_service = Arc::new(EconomyService::new(coin_store));
-
-    // Inventory Service (uses same SQLite pool as economy for shared schema)
-    let inventory_conn_str = format!("sqlite://{}", economy_db_path);
-    let inventory_options = sqlx::sqlite::SqliteConnectOptions::from_str(&inventory_conn_str)
-        .expect("Invalid connection string")
-        .create_if_missing(true)
-        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
-        .busy_timeout(std::time::Duration::from_secs(5));
-
-    let inventory_pool = sqlx::sqlite::SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect_with(inventory_options)
-        .await
-        .expect("Failed to connect to inventory DB");
-
-    let inventory_store = crate::infra::economy::SqliteInventoryStore::new(inventory_pool);
-    let inventory_service = Arc::new(crate::core::economy::InventoryService::new(inventory_store));
-
-    // Anti-Spam Moderation Service
-    let moderation_db_path = format!("{}/moderation.db", data_dir);
-    let moderation_conn_str = format!("sqlite://{}", moderation_db_path);
-    let moderation_options = sqlx::sqlite::SqliteConnectOptions::from_str(&moderation_conn_str)
-        .expect("Invalid moderation DB connection string")
-        .create_if_missing(true)
-        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
-        .busy_timeout(std::time::Duration::from_secs(5));
-
-    let moderation_pool = sqlx::sqlite::SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect_with(moderation_options)
-        .await
-        .expect("Failed to connect to moderation DB");
+    // Optional channel for detailed command error reports
+    let error_log_channel_id = config.discord.error_log_channel_id;
 
-    let spam_store = crate::infra::moderation::SqliteSpamStore::new(moderation_pool);
-    spam_store
-        .migrate()
-        .await
-        .expect("Failed to migrate moderation DB");
-    let anti_spam_service = Arc::new(crate::core::moderation::AntiSpamService::new(spam_store));
-
-    // Knowledge Store for RAG-lite retrieval
-    let knowledge_db_path = format!("{}/knowledge.db", data_dir);
-    let knowledge_conn_str = format!("sqlite://{}", knowledge_db_path);
-    let knowledge_options = sqlx::sqlite::SqliteConnectOptions::from_str(&knowledge_conn_str)
-        .expect("Invalid knowledge DB connection string")
-        .create_if_missing(true)
-        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
-        .busy_timeout(std::time::Duration::from_secs(5));
-
-    let knowledge_pool = sqlx::sqlite::SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect_with(knowledge_options)
-        .await
-        .expect("Failed to connect to knowledge DB");
-
-    let knowledge_store = crate::infra::ai::SqliteKnowledgeStore::new(knowledge_pool);
-    knowledge_store
-        .migrate()
-        .await
-        .expect("Failed to migrate knowledge DB");
-    let knowledge_service = Arc::new(knowledge_store);
+    // Localized reply strings: built-in en/es plus overrides from LOCALES_DIR
+    let localizer = crate::infra::i18n::load_localizer(&config.storage.locales_dir);
+    tracing::info!(locales = ?localizer.available_locales(), "Loaded locales");
+    let i18n = Arc::new(localizer);
 
     // Create the data structure that will be shared across all commands
     let data = Data {
@@ -762,12 +1036,39 @@ async fn main() {
         server_stats: Arc::clone(&stats_service),
         timezones: Arc::clone(&timezone_service),
         logging: Arc::clone(&logging_service),
+        #[cfg(feature = "github")]
         github: Arc::clone(&github_service),
-        ai: Arc::clone(&ai_service),
+        #[cfg(feature = "ai")]
+        ai: ai_service,
+        config: Arc::clone(&config),
+        #[cfg(feature = "economy")]
         economy: Arc::clone(&economy_service),
+        #[cfg(feature = "economy")]
         inventory: Arc::clone(&inventory_service),
+        #[cfg(feature = "moderation")]
         anti_spam: Arc::clone(&anti_spam_service),
+        #[cfg(feature = "moderation")]
+        temp_roles: Arc::clone(&temp_role_service),
+        #[cfg(feature = "ai")]
         knowledge: Arc::clone(&knowledge_service),
+        error_log_channel_id,
+        i18n,
+        boost_cache: Arc::new(discord::boosters::BoostStatusCache::new()),
+        unknown_users: Arc::new(discord::user_identity::UnknownUsers::new()),
+        #[cfg(feature = "ai")]
+        ai_privacy,
+        #[cfg(feature = "ai")]
+        response_limits,
+        #[cfg(feature = "ai")]
+        mention_throttle: Arc::new(crate::core::ai::MentionThrottle::default()),
+        manager_roles,
+        auto_roles,
+        reaction_roles,
+        welcome,
+        goodbye,
+        birthdays,
+        feedback,
+        metrics: Arc::new(crate::core::metrics::AppMetrics::new()),
     };
 
     // ========================================================================
@@ -778,37 +1079,102 @@ async fn main() {
     let intents = serenity::GatewayIntents::GUILD_MESSAGES
         | serenity::GatewayIntents::MESSAGE_CONTENT // Required to read message content
         | serenity::GatewayIntents::GUILDS
-        | serenity::GatewayIntents::GUILD_MEMBERS;
+        | serenity::GatewayIntents::GUILD_MEMBERS
+        | serenity::GatewayIntents::GUILD_MODERATION // Ban/unban events for logging
+        | serenity::GatewayIntents::GUILD_MESSAGE_REACTIONS; // Reaction roles
+
+    // Register all our commands here. Optional subsystems add theirs when
+    // their Cargo feature is enabled.
+    #[allow(unused_mut)]
+    let mut commands = vec![
+        discord::commands::leveling::level(),
+        discord::commands::leveling::profile(),
+        discord::commands::leveling::xpstats(),
+        discord::commands::leveling::voicestats(),
+        discord::commands::leveling::next_achievement(),
+        discord::commands::leveling::leaderboard(),
+        discord::commands::leveling::dailyleaderboard(),
+        discord::commands::leveling::give_xp(),
+        discord::commands::leveling::achievements(),
+        discord::commands::leveling::prestige(),
+        discord::commands::leveling::sync_prestige(),
+        discord::commands::leveling::leveling(),
+        discord::commands::server_stats::serverstats(),
+        discord::commands::timezones::timezones(),
+        crate::discord::logging::commands::logging(),
+        discord::commands::info::info(),
+        discord::commands::bot_stats::stats(),
+        discord::commands::help::help(),
+        discord::commands::feedback::feedback(),
+        discord::commands::birthday::birthday(),
+        // Owner-only bot administration
+        discord::commands::admin::admin(),
+        // Reminders
+        discord::commands::remind::remind(),
+        // Server configuration (manager roles)
+        discord::commands::config::config(),
+        discord::commands::reaction_roles::reactionrole(),
+    ];
+    #[cfg(feature = "economy")]
+    commands.extend([
+        discord::commands::economy::balance(),
+        discord::commands::economy::daily(),
+        discord::commands::economy::transactions(),
+        discord::commands::economy::richest(),
+        discord::commands::economy::coinflip(),
+        discord::commands::shop::shop(),
+        discord::commands::shop::inventory(),
+        discord::commands::shop::use_item(),
+        discord::commands::shop::gift(),
+    ]);
+    #[cfg(feature = "github")]
+    commands.push(discord::commands::github::github());
+    #[cfg(feature = "ai")]
+    commands.extend([
+        discord::commands::reload::reload(),
+        discord::commands::ai::ai(),
+        discord::commands::ai::summarize(),
+        discord::commands::ai::tldr(),
+        discord::commands::ai::translate(),
+    ]);
+    // Anti-spam moderation
+    #[cfg(feature = "moderation")]
+    commands.extend([
+        discord::moderation::commands::antispam(),
+        discord::moderation::commands::purge(),
+        discord::moderation::commands::slowmode(),
+        discord::moderation::commands::tempmute(),
+        discord::moderation::commands::muterole(),
+    ]);
+
+    // Cooldowns on expensive commands (`[cooldowns]` in the config). The
+    // defaults name AI commands, which are missing without the `ai` feature.
+    let default_cooldowns = infra::config::CooldownSettings::default();
+    for name in discord::cooldowns::apply_cooldowns(&mut commands, &config.cooldowns) {
+        if !default_cooldowns.user_secs.contains_key(&name) {
+            tracing::warn!("Cooldown configured for unknown command `{}`", name);
+        }
+    }
+
+    // Owner-only commands accept these users plus the application's owner or
+    // team, which poise looks up at startup
+    let owners = config
+        .discord
+        .owner_ids
+        .iter()
+        .map(|&id| serenity::UserId::new(id))
+        .collect();
 
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            // Register all our commands here
-            commands: vec![
-                discord::commands::leveling::level(),
-                discord::commands::leveling::profile(),
-                discord::commands::leveling::xpstats(),
-                discord::commands::leveling::next_achievement(),
-                discord::commands::leveling::leaderboard(),
-                discord::commands::leveling::dailyleaderboard(),
-                discord::commands::leveling::give_xp(),
-                discord::commands::leveling::achievements(),
-                discord::commands::leveling::prestige(),
-                discord::commands::leveling::sync_prestige(),
-                discord::commands::economy::balance(),
-                discord::commands::economy::daily(),
-                discord::commands::shop::shop(),
-                discord::commands::shop::inventory(),
-                discord::commands::server_stats::serverstats(),
-                discord::commands::timezones::timezones(),
-                crate::discord::logging::commands::logging(),
-                discord::commands::github::github(),
-                discord::commands::info::info(),
-                discord::commands::help::help(),
-// Anti-spam moderation
-                discord::moderation::commands::antispam(),
-                // Reminders
-                discord::commands::remind::remind(),
-            ],
+            commands,
+            owners,
+            // Text commands: `!level` (prefix from COMMAND_PREFIX) or `@Bot level`
+            prefix_options: poise::PrefixFrameworkOptions {
+                prefix: Some(config.discord.command_prefix.clone()),
+                mention_as_prefix: true,
+                ..Default::default()
+            },
             // Event handler for messages and other events
             event_handler: |ctx, event, framework, data| {
                 Box::pin(event_handler(ctx, event, framework, data))
@@ -830,13 +1196,17 @@ async fn main() {
                             tracing::error!("Failed to increment command count: {}", e);
                         }
                     }
+
+                    logging_events::record_command_audit(ctx, None).await;
                 })
             },
+            // Central error handler: friendly replies, audit entries and error reports
+            on_error: |error| Box::pin(discord::error_handler::on_error(error)),
             ..Default::default()
         })
         .setup(|ctx, _ready, framework| {
             Box::pin(async move {
-                println!("🤖 Bot is starting up...");
+                tracing::info!("Bot is starting up...");
 
                 // Register slash commands globally (can take up to an hour to propagate)
                 // For faster development, use register_in_guild instead:
@@ -856,42 +1226,61 @@ async fn main() {
                 // )
                 // .await?;
 
-                println!("✅ Commands registered!");
-                println!("🚀 Bot is ready!");
+                tracing::info!(
+                    commands = framework.options().commands.len(),
+                    "Commands registered"
+                );
                 presence::on_ready(ctx, &data).await;
 
                 // Background GitHub poller (commits, issues). Default: every 5 minutes.
-                let github_service = Arc::clone(&data.github);
-                let github_http = ctx.http.clone();
-                let poll_interval_secs = std::env::var("GITHUB_POLL_INTERVAL_SECS")
-                    .ok()
-                    .and_then(|v| v.parse::<u64>().ok())
-                    .unwrap_or(300)
-                    .max(300);
+                #[cfg(feature = "github")]
+                spawn_github_poller(ctx.http.clone(), &data);
+
+                // Take temporary roles (`/tempmute`) away again once they expire
+                #[cfg(feature = "moderation")]
+                spawn_temp_role_expiry(ctx.http.clone(), &data);
+
+                // Birthday announcements at each server's local midnight
+                spawn_birthday_announcer(ctx.clone(), &data);
+
+                // Background leaderboard rank refresh. Recomputes best/previous rank
+                // (used by the podium/top ten/climber achievements) for guilds where
+                // XP changed since the last run, so ranks are at most one interval stale.
+                let rank_leveling = Arc::clone(&data.leveling);
+                let rank_refresh_secs = data.config.leveling.rank_refresh_interval_secs.max(60);
                 tracing::info!(
-                    "GitHub poll interval set to {} seconds",
-                    poll_interval_secs
+                    "Leaderboard rank refresh interval set to {} seconds",
+                    rank_refresh_secs
                 );
                 tokio::spawn(async move {
                     use std::time::Duration as StdDuration;
                     use tokio::time::sleep;
 
-                    let poll_interval = StdDuration::from_secs(poll_interval_secs);
                     loop {
-                        tracing::debug!("Starting background GitHub poll...");
-                        match github_service.poll_updates().await {
-                            Ok(updates) => {
-                                if !updates.is_empty() {
-                                    tracing::info!("Found {} GitHub updates", updates.len());
-                                    github_dispatcher::send_updates(&github_http, updates).await;
-                                } else {
-                                    tracing::debug!("No GitHub updates found");
-                                }
-                            }
-                            Err(err) => tracing::warn!("GitHub poll failed: {}", err),
+                        sleep(StdDuration::from_secs(rank_refresh_secs)).await;
+                        let refreshed = rank_leveling.refresh_stale_ranks().await;
+                        if refreshed > 0 {
+                            tracing::info!(guilds = refreshed, "Refreshed leaderboard ranks");
                         }
+                    }
+                });
+
+                // Announce level-ups that were held back by the announcement cooldown
+                let announce_ctx = ctx.clone();
+                let announce_data = data.clone();
+                tokio::spawn(async move {
+                    use std::time::Duration as StdDuration;
+                    use tokio::time::sleep;
 
-                        sleep(poll_interval).await;
+                    loop {
+                        sleep(StdDuration::from_secs(15)).await;
+                        for level_up in announce_data.leveling.take_due_level_ups() {
+                            if let Err(err) =
+                                send_level_up_embed(&announce_ctx, &announce_data, &level_up).await
+                            {
+                                tracing::warn!("Failed to send level-up embed: {err}");
+                            }
+                        }
                     }
                 });
 
@@ -952,7 +1341,564 @@ async fn main() {
         .framework(framework)
         .cache_settings(settings)
         .await
-        .expect("Error creating client");
+        .context("Error creating client")?;
+
+    client.start().await.context("Error running bot")?;
+    Ok(())
+}
+
+/// GitHub tracking service (polls commits/issues across repos)
+#[cfg(feature = "github")]
+async fn init_github(
+    config: &crate::infra::config::Config,
+    http_client: reqwest::Client,
+    data_dir: &str,
+    in_memory: bool,
+) -> anyhow::Result<Arc<GithubService<GithubApiClient, Box<dyn GithubConfigStore>>>> {
+    let github_client = GithubApiClient::new(
+        http_client,
+        config.github.token.clone(),
+        std::time::Duration::from_secs(config.github.request_timeout_secs),
+    )
+    .context("Failed to create GitHub API client")?;
+    let github_store: Box<dyn GithubConfigStore> = if in_memory {
+        Box::new(InMemoryGithubStore::new())
+    } else {
+        Box::new(GithubFileStore::new(format!(
+            "{}/github_config.json",
+            data_dir
+        )))
+    };
+    // Diff stats cost one extra API call per reported commit, so they're opt-in
+    let github_commit_stats = config.github.commit_stats;
+    let github_service = Arc::new(
+        GithubService::new(github_client, github_store)
+            .await
+            .context("Failed to initialize GitHub tracking service")?
+            .with_commit_stats(github_commit_stats)
+            .with_org_refresh_interval(chrono::Duration::seconds(
+                config.github.org_refresh_interval_secs as i64,
+            )),
+    );
+    Ok(github_service)
+}
+
+/// Build the AI service for the configured provider, or `None` when there
+/// is no provider.
+#[cfg(feature = "ai")]
+async fn init_ai(
+    config: &crate::infra::config::Config,
+    http_client: reqwest::Client,
+) -> Option<Arc<crate::core::ai::DynAiService>> {
+    // -------------------------------------------------------------------------
+    // The bot supports two AI providers:
+    // 1. OpenRouter (default) - Access to many models via openrouter.ai
+    // 2. Gemini - Google's Gemini API via ai.google.dev
+    //
+    // Set AI_PROVIDER=gemini to use Gemini, otherwise OpenRouter is used.
+    // AI is optional: without an API key the bot runs without it.
+    // -------------------------------------------------------------------------
+    let ai_provider = config.ai_provider();
+
+    // System prompt and reasoning options (shared between providers,
+    // reloadable at runtime with `/reload`)
+    let AiSettings {
+        system_prompt,
+        reasoning_enabled,
+        reasoning_effort,
+    } = config.ai_settings().unwrap_or_else(|e| {
+        // Checked by `load_config`, so the prompt file went away since then
+        tracing::warn!("{}", e);
+        AiSettings {
+            system_prompt: crate::core::ai::DEFAULT_SYSTEM_PROMPT.to_string(),
+            reasoning_enabled: config.ai.reasoning_enabled,
+            reasoning_effort: config.ai.reasoning_effort.clone(),
+        }
+    });
+    // Replies to identical tool-less requests can be reused for a while
+    let response_cache_ttl = config
+        .ai
+        .response_cache
+        .then(|| std::time::Duration::from_secs(config.ai.response_cache_ttl_secs));
+    let request_timeout = std::time::Duration::from_secs(config.ai.request_timeout_secs);
+    let circuit_breaker_failures = config.ai.circuit_breaker_failures;
+    let circuit_breaker_cooldown =
+        std::time::Duration::from_secs(config.ai.circuit_breaker_cooldown_secs);
+    let max_concurrent_requests = config.ai.max_concurrent_requests;
+
+    // Build AI service based on provider
+    match ai_provider {
+        Some(AiProviderKind::Gemini) => {
+            // Gemini configuration
+            let gemini_api_key = config.ai.gemini_api_key.clone().unwrap_or_default();
+            let mut gemini_model = config.ai.gemini_model.clone();
+
+            // Handle "auto" or "best" to always use the top of our hierarchy
+            if gemini_model == "auto" || gemini_model == "best" {
+                gemini_model = crate::core::ai::models::MODEL_HIERARCHY[0].to_string();
+            }
+
+            tracing::info!("Using Gemini AI provider with model: {}", gemini_model);
+
+            // Google Search and, with the `google-docs` feature, Google Docs reading
+            let docs_timeout =
+                std::time::Duration::from_secs(config.ai.google_docs_timeout_secs);
+            let (tools, function_handler) = gemini_tools(
+                config.ai.enable_google_search,
+                http_client.clone(),
+                docs_timeout,
+            )
+            .await;
+
+            let gemini_client = GeminiClient::new(http_client, gemini_api_key, request_timeout)
+                .with_mixed_tools(config.ai.gemini_allow_mixed_tools);
+            let ai_config = AiConfig {
+                model: gemini_model,
+                temperature: config.ai.temperature,
+                max_tokens: config.ai.max_tokens,
+                top_p: Some(config.ai.top_p),
+                repetition_penalty: None, // Not supported by Gemini
+                reasoning_enabled,
+                reasoning_effort,
+                tools,
+                tool_config: None, // Default tool behavior (AUTO)
+            };
+
+            // Create AI service with or without function handler
+            Some(match function_handler {
+                Some(handler) => Arc::new(
+                    AiService::with_function_handler(
+                        Box::new(gemini_client) as Box<dyn crate::core::ai::AiProvider>,
+                        system_prompt,
+                        ai_config,
+                        handler,
+                    )
+                    .with_response_cache(response_cache_ttl)
+                    .with_circuit_breaker(circuit_breaker_failures, circuit_breaker_cooldown)
+                    .with_max_concurrent_requests(max_concurrent_requests),
+                ),
+                None => Arc::new(
+                    AiService::new(
+                        Box::new(gemini_client) as Box<dyn crate::core::ai::AiProvider>,
+                        system_prompt,
+                        ai_config,
+                    )
+                    .with_response_cache(response_cache_ttl)
+                    .with_circuit_breaker(circuit_breaker_failures, circuit_breaker_cooldown)
+                    .with_max_concurrent_requests(max_concurrent_requests),
+                ),
+            })
+        }
+        Some(AiProviderKind::OpenRouter) => {
+            // OpenRouter configuration (default)
+            let openrouter_api_key = config.ai.openrouter_api_key.clone().unwrap_or_default();
+            let openrouter_model = config.ai.openrouter_model.clone();
+
+            tracing::info!(
+                "Using OpenRouter AI provider with model: {}",
+                openrouter_model
+            );
+
+            let ai_client = OpenRouterClient::new(http_client, openrouter_api_key, request_timeout)
+                .with_no_reasoning_models(config.ai.openrouter_no_reasoning_models.clone());
+            let ai_config = AiConfig {
+                model: openrouter_model,
+                temperature: config.ai.temperature,
+                max_tokens: config.ai.max_tokens,
+                top_p: Some(config.ai.top_p),
+                repetition_penalty: Some(1.0),
+                reasoning_enabled,
+                reasoning_effort,
+                tools: None, // OpenRouter: limited tool support depends on model
+                tool_config: None,
+            };
+
+            Some(Arc::new(
+                AiService::new(
+                    Box::new(ai_client) as Box<dyn crate::core::ai::AiProvider>,
+                    system_prompt,
+                    ai_config,
+                )
+                .with_response_cache(response_cache_ttl)
+                .with_circuit_breaker(circuit_breaker_failures, circuit_breaker_cooldown)
+                .with_max_concurrent_requests(max_concurrent_requests),
+            ))
+        }
+        None => {
+            tracing::warn!(
+                "No AI provider configured (set OPENROUTER_API_KEY or GEMINI_API_KEY), AI features are disabled"
+            );
+            None
+        }
+    }
+}
+
+/// GreyCoin wallets and the shop inventory, stored in the same database.
+#[cfg(feature = "economy")]
+async fn init_economy(
+    config: &crate::infra::config::Config,
+    data_dir: &str,
+    in_memory: bool,
+) -> anyhow::Result<(
+    Arc<EconomyService<Box<dyn crate::core::economy::CoinStore>>>,
+    Arc<crate::core::economy::InventoryService<Box<dyn crate::core::economy::InventoryStore>>>,
+)> {
+    let economy_db_path = format!("{}/economy.db", data_dir);
+    let coin_store: Box<dyn crate::core::economy::CoinStore> = if in_memory {
+        Box::new(InMemoryCoinStore::new())
+    } else {
+        Box::new(
+            SqliteCoinStore::new(&economy_db_path)
+                .await
+                .context("Failed to initialize economy store")?,
+        )
+    };
+    // Coins from chatting; `Config::validate` has already checked the amounts
+    let economy_config = config.economy.economy_config();
+    let economy_service = Arc::new(EconomyService::new_with_config(coin_store, economy_config));
+
+    // Inventory Service (uses same SQLite pool as economy for shared schema)
+    let inventory_store: Box<dyn crate::core::economy::InventoryStore> = if in_memory {
+        Box::new(InMemoryInventoryStore::new())
+    } else {
+        let inventory_conn_str = format!("sqlite://{}", economy_db_path);
+        let inventory_options = sqlx::sqlite::SqliteConnectOptions::from_str(&inventory_conn_str)
+            .context("Invalid connection string")?
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+
+        let inventory_pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(inventory_options)
+            .await
+            .context("Failed to connect to inventory DB")?;
+
+        let inventory_store = crate::infra::economy::SqliteInventoryStore::new(inventory_pool);
+        Box::new(inventory_store)
+    };
+    let inventory_service = Arc::new(crate::core::economy::InventoryService::new(inventory_store));
+    Ok((economy_service, inventory_service))
+}
+
+/// Anti-spam moderation service and the temporary roles waiting to expire,
+/// both kept in the moderation database.
+#[cfg(feature = "moderation")]
+async fn init_moderation(
+    data_dir: &str,
+    in_memory: bool,
+) -> anyhow::Result<(
+    Arc<crate::core::moderation::AntiSpamService<Box<dyn crate::core::moderation::SpamStore>>>,
+    Arc<crate::core::moderation::TempRoleService<Box<dyn crate::core::moderation::TempRoleStore>>>,
+)> {
+    let (spam_store, temp_role_store): (
+        Box<dyn crate::core::moderation::SpamStore>,
+        Box<dyn crate::core::moderation::TempRoleStore>,
+    ) = if in_memory {
+        (
+            Box::new(crate::infra::moderation::InMemorySpamStore::new()),
+            Box::new(crate::infra::moderation::InMemoryTempRoleStore::new()),
+        )
+    } else {
+        let moderation_db_path = format!("{}/moderation.db", data_dir);
+        let moderation_conn_str = format!("sqlite://{}", moderation_db_path);
+        let moderation_options = sqlx::sqlite::SqliteConnectOptions::from_str(&moderation_conn_str)
+            .context("Invalid moderation DB connection string")?
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+
+        let moderation_pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(moderation_options)
+            .await
+            .context("Failed to connect to moderation DB")?;
+
+        let spam_store = crate::infra::moderation::SqliteSpamStore::new(moderation_pool.clone());
+        spam_store
+            .migrate()
+            .await
+            .context("Failed to migrate moderation DB")?;
+        let temp_role_store = crate::infra::moderation::SqliteTempRoleStore::new(moderation_pool);
+        temp_role_store
+            .migrate()
+            .await
+            .map_err(anyhow::Error::from_boxed)
+            .context("Failed to migrate temporary roles table")?;
+        (Box::new(spam_store), Box::new(temp_role_store))
+    };
+    let anti_spam_service = Arc::new(crate::core::moderation::AntiSpamService::new(spam_store));
+
+    // Removals that came due while the bot was offline run on the first tick
+    let temp_role_service = crate::core::moderation::TempRoleService::load(temp_role_store)
+        .await
+        .map_err(anyhow::Error::from_boxed)
+        .context("Failed to load pending temporary roles")?;
+    tracing::info!(
+        pending = temp_role_service.pending_count(),
+        "Loaded pending temporary roles"
+    );
+    Ok((anti_spam_service, Arc::new(temp_role_service)))
+}
+
+/// Knowledge store for RAG-lite retrieval, AI opt-outs and per-guild
+/// response limits.
+#[cfg(feature = "ai")]
+async fn init_ai_stores(
+    data_dir: &str,
+    in_memory: bool,
+) -> anyhow::Result<(
+    Arc<dyn crate::core::ai::KnowledgeStore>,
+    Arc<crate::core::ai::ResponseLimitsService<Box<dyn crate::core::ai::ResponseLimitsStore>>>,
+    Arc<crate::core::ai::AiPrivacyService<Box<dyn crate::core::ai::AiOptOutStore>>>,
+)> {
+    // Knowledge Store for RAG-lite retrieval
+    let knowledge_service: Arc<dyn crate::core::ai::KnowledgeStore> = if in_memory {
+        Arc::new(crate::infra::ai::InMemoryKnowledgeStore::new())
+    } else {
+        let knowledge_db_path = format!("{}/knowledge.db", data_dir);
+        let knowledge_conn_str = format!("sqlite://{}", knowledge_db_path);
+        let knowledge_options = sqlx::sqlite::SqliteConnectOptions::from_str(&knowledge_conn_str)
+            .context("Invalid knowledge DB connection string")?
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+
+        let knowledge_pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(knowledge_options)
+            .await
+            .context("Failed to connect to knowledge DB")?;
+
+        let knowledge_store = crate::infra::ai::SqliteKnowledgeStore::new(knowledge_pool);
+        knowledge_store
+            .migrate()
+            .await
+            .map_err(anyhow::Error::from_boxed)
+            .context("Failed to migrate knowledge DB")?;
+        Arc::new(knowledge_store)
+    };
+
+    // AI settings: users who opted out of AI context (`/ai forgetme`) and
+    // per-guild response limits (`/ai responses`)
+    let (ai_opt_out_store, response_limits_store): (
+        Box<dyn crate::core::ai::AiOptOutStore>,
+        Box<dyn crate::core::ai::ResponseLimitsStore>,
+    ) = if in_memory {
+        (
+            Box::new(crate::infra::ai::InMemoryOptOutStore::new()),
+            Box::new(crate::infra::ai::InMemoryResponseLimitsStore::new()),
+        )
+    } else {
+        let ai_settings_db_path = format!("{}/ai_settings.db", data_dir);
+        let ai_settings_conn_str = format!("sqlite://{}", ai_settings_db_path);
+        let ai_settings_options =
+            sqlx::sqlite::SqliteConnectOptions::from_str(&ai_settings_conn_str)
+                .context("Invalid AI settings DB connection string")?
+                .create_if_missing(true)
+                .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+                .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+                .busy_timeout(std::time::Duration::from_secs(5));
+
+        let ai_settings_pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(2)
+            .connect_with(ai_settings_options)
+            .await
+            .context("Failed to connect to AI settings DB")?;
+
+        let opt_out_store = crate::infra::ai::SqliteOptOutStore::new(ai_settings_pool.clone());
+        opt_out_store
+            .migrate()
+            .await
+            .map_err(anyhow::Error::from_boxed)
+            .context("Failed to migrate AI settings DB")?;
+        let limits_store = crate::infra::ai::SqliteResponseLimitsStore::new(ai_settings_pool);
+        limits_store
+            .migrate()
+            .await
+            .map_err(anyhow::Error::from_boxed)
+            .context("Failed to migrate AI settings DB")?;
+        (Box::new(opt_out_store), Box::new(limits_store))
+    };
+    let response_limits = Arc::new(crate::core::ai::ResponseLimitsService::new(
+        response_limits_store,
+    ));
+    let ai_privacy = Arc::new(
+        crate::core::ai::AiPrivacyService::load(ai_opt_out_store)
+            .await
+            .map_err(anyhow::Error::from_boxed)
+            .context("Failed to load AI opt-outs")?,
+    );
+    Ok((knowledge_service, response_limits, ai_privacy))
+}
 
-    client.start().await.expect("Error running bot");
+/// Gemini tools plus the handler that runs the Google Docs functions.
+#[cfg(feature = "google-docs")]
+async fn gemini_tools(
+    enable_search: bool,
+    http_client: reqwest::Client,
+    docs_timeout: std::time::Duration,
+) -> (Option<Vec<AiTool>>, Option<Box<dyn FunctionCallHandler>>) {
+    // Set up Google Docs function handler
+    // Try to use service account auth for multi-tab support, fall back to public export
+    let handler = match GoogleDocsFunctionHandler::from_env_with_auth(
+        http_client.clone(),
+        docs_timeout,
+    )
+    .await
+    {
+        Ok(h) => {
+            tracing::info!(
+                "Google Docs: Using service account authentication (multi-tab support enabled)"
+            );
+            h
+        }
+        Err(e) => {
+            tracing::info!("Google Docs: Service account not configured ({}), using public export (first tab only)", e);
+            GoogleDocsFunctionHandler::from_env(http_client, docs_timeout)
+        }
+    };
+
+    // Check if any project docs are configured
+    let has_project_docs = handler.supported_functions().len() > 1; // More than just read_google_doc
+
+    let tools = handler.get_tools(enable_search);
+    tracing::info!(
+        "Gemini tools enabled: Google Search={}, Google Docs functions={}",
+        enable_search,
+        handler.supported_functions().join(", ")
+    );
+
+    if has_project_docs {
+        tracing::info!("Project documents configured for AI access");
+    }
+
+    (
+        Some(tools),
+        Some(Box::new(handler) as Box<dyn FunctionCallHandler>),
+    )
+}
+
+/// Without the `google-docs` feature Gemini only gets Google Search.
+#[cfg(all(feature = "ai", not(feature = "google-docs")))]
+async fn gemini_tools(
+    enable_search: bool,
+    _http_client: reqwest::Client,
+    _docs_timeout: std::time::Duration,
+) -> (Option<Vec<AiTool>>, Option<Box<dyn FunctionCallHandler>>) {
+    tracing::info!("Gemini tools enabled: Google Search={}", enable_search);
+    (enable_search.then(|| vec![AiTool::GoogleSearch]), None)
+}
+
+/// Check every minute whether a server's day has started and announce its
+/// birthdays (`/config birthdays`).
+fn spawn_birthday_announcer(ctx: serenity::Context, data: &Data) {
+    let data = data.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            let guilds = match data.birthdays.guilds().await {
+                Ok(guilds) => guilds,
+                Err(e) => {
+                    tracing::warn!("Failed to load birthday guilds: {}", e);
+                    continue;
+                }
+            };
+            for guild_id in guilds {
+                if let Err(e) = discord::birthdays::celebrate_due(&ctx, &data, guild_id).await {
+                    tracing::warn!(guild_id, error = %e, "Failed to announce birthdays");
+                }
+            }
+        }
+    });
+}
+
+/// Remove temporary roles (`/tempmute`) once they expire. A removal that fails
+/// for a transient reason stays pending and is retried on the next tick.
+#[cfg(feature = "moderation")]
+fn spawn_temp_role_expiry(http: Arc<serenity::Http>, data: &Data) {
+    let temp_roles = Arc::clone(&data.temp_roles);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+        loop {
+            interval.tick().await;
+            for entry in temp_roles.take_due(chrono::Utc::now()) {
+                let result = http
+                    .remove_member_role(
+                        serenity::GuildId::new(entry.guild_id),
+                        serenity::UserId::new(entry.user_id),
+                        serenity::RoleId::new(entry.role_id),
+                        Some("Temporary role expired"),
+                    )
+                    .await;
+                let done = match &result {
+                    Ok(()) => true,
+                    // The member left, the role is gone or the bot lost access:
+                    // retrying won't help. A rate limit (429) is only temporary.
+                    Err(serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(
+                        response,
+                    ))) => {
+                        response.status_code.is_client_error()
+                            && response.status_code.as_u16() != 429
+                    }
+                    Err(_) => false,
+                };
+                if let Err(e) = &result {
+                    tracing::warn!(
+                        guild_id = entry.guild_id,
+                        user_id = entry.user_id,
+                        role_id = entry.role_id,
+                        retrying = !done,
+                        error = %e,
+                        "Failed to remove expired temporary role"
+                    );
+                }
+                if done {
+                    if let Err(e) = temp_roles.complete(&entry).await {
+                        tracing::warn!("Failed to clear expired temporary role: {}", e);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Poll the tracked repositories in the background and post new commits and
+/// issues.
+#[cfg(feature = "github")]
+fn spawn_github_poller(http: Arc<serenity::Http>, data: &Data) {
+    let github_service = Arc::clone(&data.github);
+    let poll_interval_secs = data.config.github.poll_interval_secs.max(300);
+    tracing::info!("GitHub poll interval set to {} seconds", poll_interval_secs);
+    tokio::spawn(async move {
+        use std::time::Duration as StdDuration;
+        use tokio::time::sleep;
+
+        let poll_interval = StdDuration::from_secs(poll_interval_secs);
+        loop {
+            tracing::debug!("Starting background GitHub poll...");
+            match github_service.poll_updates().await {
+                Ok(poll) => {
+                    if !poll.updates.is_empty() {
+                        tracing::info!("Found {} GitHub updates", poll.updates.len());
+                    } else {
+                        tracing::debug!("No GitHub updates found");
+                    }
+                    // Watermarks are only committed for updates that were posted
+                    if let Err(err) =
+                        github_dispatcher::dispatch_poll(&http, &github_service, poll).await
+                    {
+                        tracing::warn!("Failed to save GitHub poll state: {}", err);
+                    }
+                }
+                Err(err) => tracing::warn!("GitHub poll failed: {}", err),
+            }
+
+            sleep(poll_interval).await;
+        }
+    });
 }